@@ -8,6 +8,12 @@ use std::collections::HashSet;
 use std::path::Path;
 use thiserror::Error;
 
+use crate::persistence::dedup::DedupConfig;
+use crate::persistence::mongo::MongoSinkConfig;
+use crate::persistence::replay::ReplayConfig;
+use crate::persistence::spool::{OnFullPolicy, SpoolConfig};
+use crate::persistence::{ParquetArchiveConfig, PipelineConfig};
+
 // ---------------------------------------------------------------------------
 // Error types
 // ---------------------------------------------------------------------------
@@ -45,6 +51,8 @@ pub struct CollectorConfig {
     pub run: RunConfig,
     #[serde(rename = "exchange")]
     pub exchanges: Vec<ExchangeInstance>,
+    #[serde(default)]
+    pub persistence: PersistenceConfig,
 }
 
 #[derive(Debug, Clone, Deserialize)]
@@ -107,6 +115,9 @@ pub struct PersistenceConfig {
 
     #[serde(default)]
     pub dedup: DedupConfigToml,
+
+    #[serde(default)]
+    pub archive: ArchiveConfigToml,
 }
 
 #[derive(Debug, Clone, Deserialize)]
@@ -163,6 +174,90 @@ impl Default for DedupConfigToml {
     }
 }
 
+#[derive(Debug, Clone, Deserialize)]
+pub struct ArchiveConfigToml {
+    /// Enable the Parquet archive (default: false).
+    #[serde(default)]
+    pub enabled: bool,
+    /// Root directory for `exchange=/channel=/date=/hour=` partitions.
+    #[serde(default = "default_archive_dir")]
+    pub dir: String,
+    /// Roll a Parquet file once it reaches this size in MiB (default: 128).
+    #[serde(default = "default_archive_max_file_mb")]
+    pub max_file_mb: u64,
+    /// Roll a Parquet file once it has been open this many seconds (default: 3600).
+    #[serde(default = "default_archive_max_file_age_seconds")]
+    pub max_file_age_seconds: u64,
+    /// Write out a row group once this many rows are buffered (default: 131072).
+    #[serde(default = "default_archive_row_group_rows")]
+    pub row_group_rows: usize,
+    /// Write out a row group once the buffered rows reach this size in MiB (default: 16).
+    #[serde(default = "default_archive_row_group_mb")]
+    pub row_group_mb: u64,
+}
+
+impl Default for ArchiveConfigToml {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            dir: default_archive_dir(),
+            max_file_mb: default_archive_max_file_mb(),
+            max_file_age_seconds: default_archive_max_file_age_seconds(),
+            row_group_rows: default_archive_row_group_rows(),
+            row_group_mb: default_archive_row_group_mb(),
+        }
+    }
+}
+
+impl ArchiveConfigToml {
+    pub fn to_archive_config(&self) -> ParquetArchiveConfig {
+        let mut cfg = ParquetArchiveConfig::new(&self.dir);
+        cfg.max_file_bytes = self.max_file_mb * 1024 * 1024;
+        cfg.max_file_age_ms = self.max_file_age_seconds * 1000;
+        cfg.row_group_rows = self.row_group_rows;
+        cfg.row_group_bytes = self.row_group_mb * 1024 * 1024;
+        cfg
+    }
+}
+
+impl PersistenceConfig {
+    /// Pipeline settings for the `[persistence]` section.  `on_full` has
+    /// already been checked by `validate_config`.
+    pub fn to_pipeline_config(&self) -> PipelineConfig {
+        let spool = self.spool.enabled.then(|| SpoolConfig {
+            dir: self.spool.dir.clone().into(),
+            max_segment_bytes: self.spool.max_segment_mb * 1024 * 1024,
+            max_total_bytes: self.spool.max_total_mb * 1024 * 1024,
+            on_full: parse_on_full(&self.spool.on_full)
+                .unwrap_or(OnFullPolicy::DropTickerDepthKeepTrade),
+        });
+        let dedup = self.dedup.enabled.then_some(DedupConfig {
+            window_seconds: self.dedup.window_seconds,
+            max_keys: self.dedup.max_keys,
+        });
+        PipelineConfig {
+            mongo: MongoSinkConfig {
+                max_retries: self.mongo_max_retries,
+                retry_base_ms: self.mongo_retry_base_ms,
+                consecutive_failures_for_degraded: self.mongo_consecutive_failures_for_degraded,
+            },
+            spool,
+            dedup,
+            replay: ReplayConfig::default(),
+        }
+    }
+}
+
+fn parse_on_full(s: &str) -> Option<OnFullPolicy> {
+    [
+        OnFullPolicy::DropTickerDepthKeepTrade,
+        OnFullPolicy::DropAll,
+        OnFullPolicy::Block,
+    ]
+    .into_iter()
+    .find(|p| p.as_str() == s)
+}
+
 fn default_mongo_uri() -> String {
     "mongodb://localhost:27017".to_string()
 }
@@ -199,6 +294,21 @@ fn default_dedup_window_seconds() -> u64 {
 fn default_dedup_max_keys() -> usize {
     100_000
 }
+fn default_archive_dir() -> String {
+    "/var/lib/crypto-collector/archive".to_string()
+}
+fn default_archive_max_file_mb() -> u64 {
+    128
+}
+fn default_archive_max_file_age_seconds() -> u64 {
+    3600
+}
+fn default_archive_row_group_rows() -> usize {
+    128 * 1024
+}
+fn default_archive_row_group_mb() -> u64 {
+    16
+}
 
 // ---------------------------------------------------------------------------
 // Loading
@@ -245,6 +355,16 @@ fn validate_config(config: &CollectorConfig) -> Result<(), ConfigError> {
         ));
     }
 
+    if parse_on_full(&config.persistence.spool.on_full).is_none() {
+        errors.push(format!(
+            "persistence.spool.on_full '{}' is not a valid policy (expected one of: drop_ticker_depth_keep_trade, drop_all, block)",
+            config.persistence.spool.on_full
+        ));
+    }
+    if config.persistence.archive.enabled && config.persistence.archive.dir.is_empty() {
+        errors.push("persistence.archive.dir is empty".to_string());
+    }
+
     // Exchange instances
     if config.exchanges.is_empty() {
         errors.push("at least one [[exchange]] instance must be defined".to_string());
@@ -415,6 +535,56 @@ channels = ["Y"]
         assert!(err.to_string().contains("not a valid level"));
     }
 
+    #[test]
+    fn parse_archive_section() {
+        let toml = format!(
+            "{}{}",
+            valid_toml(),
+            r#"
+[persistence.spool]
+enabled = true
+dir = "/tmp/spool"
+on_full = "drop_all"
+
+[persistence.archive]
+enabled = true
+dir = "/tmp/archive"
+max_file_mb = 64
+row_group_rows = 1000
+"#
+        );
+        let cfg = parse_config(&toml).unwrap();
+        let archive = &cfg.persistence.archive;
+        assert!(archive.enabled);
+        assert_eq!(archive.max_file_age_seconds, 3600);
+        let ac = archive.to_archive_config();
+        assert_eq!(ac.root, std::path::PathBuf::from("/tmp/archive"));
+        assert_eq!(ac.max_file_bytes, 64 * 1024 * 1024);
+        assert_eq!(ac.row_group_rows, 1000);
+        assert_eq!(ac.row_group_bytes, 16 * 1024 * 1024);
+        let pc = cfg.persistence.to_pipeline_config();
+        assert_eq!(pc.spool.unwrap().on_full, OnFullPolicy::DropAll);
+        assert!(pc.dedup.is_none());
+
+        // Configs without [persistence] keep the archive off.
+        assert!(
+            !parse_config(valid_toml())
+                .unwrap()
+                .persistence
+                .archive
+                .enabled
+        );
+
+        let bad = format!(
+            "{}\n[persistence.spool]\non_full = \"sometimes\"\n",
+            valid_toml()
+        );
+        assert!(parse_config(&bad)
+            .unwrap_err()
+            .to_string()
+            .contains("not a valid policy"));
+    }
+
     #[test]
     fn allow_disabled_with_empty_symbols() {
         let toml = r#"
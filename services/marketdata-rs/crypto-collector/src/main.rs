@@ -23,14 +23,27 @@ mod state;
 use std::net::SocketAddr;
 use std::path::{Path, PathBuf};
 use std::sync::Arc;
+use std::time::Duration;
 
 use axum::{routing::get, Router};
+use tokio::sync::watch;
 use tracing::{error, info, warn};
 
 use config::CollectorConfig;
 use health::InstanceStatus;
+use ingestion::{BufferRunner, IngestSender, PipelineHandle};
+use persistence::bridge::{IngestBridge, DEFAULT_BRIDGE_CAPACITY};
+use persistence::{ParquetArchive, PersistenceMetrics, PipelineSink};
 use state::AppState;
 
+/// How often open archive files are checked for age and finished hours.
+const ARCHIVE_ROLL_INTERVAL: Duration = Duration::from_secs(30);
+
+/// Ingestion queue and batching in front of the archive pipeline.
+const INGEST_QUEUE_CAPACITY: usize = 10_000;
+const INGEST_MAX_BATCH_ITEMS: usize = 500;
+const INGEST_MAX_BATCH_INTERVAL_MS: u64 = 200;
+
 // ---------------------------------------------------------------------------
 // Main
 // ---------------------------------------------------------------------------
@@ -57,6 +70,9 @@ async fn main() {
 
     let http_port = resolve_http_port(&config_path);
 
+    let (shutdown_tx, shutdown_rx) = watch::channel(false);
+    let archive = start_archive(&config_path, shutdown_rx).await;
+
     let app_state = Arc::new(state);
     let app = Router::new()
         .route("/healthz", get(health::healthz))
@@ -84,6 +100,11 @@ async fn main() {
             std::process::exit(1);
         });
 
+    let _ = shutdown_tx.send(true);
+    if let Some(archive) = archive {
+        archive.shutdown().await;
+    }
+
     info!("crypto-collector shut down gracefully");
 }
 
@@ -208,6 +229,114 @@ fn resolve_descriptor_path(config_dir: &Path, descriptor_path: &str) -> PathBuf
     }
 }
 
+// ---------------------------------------------------------------------------
+// Parquet archive
+// ---------------------------------------------------------------------------
+
+/// The Parquet archive behind its `PipelineSink` (spool, dedup and replay as
+/// configured in `[persistence]`), fed by the ingestion runtime through an
+/// [`IngestBridge`], plus the task that rolls its files.
+struct ArchiveService {
+    archive: Arc<ParquetArchive>,
+    /// Entry point for envelopes bound for the archive.
+    ingest: IngestSender,
+    pipeline: PipelineHandle,
+    forward: tokio::task::JoinHandle<()>,
+    roller: tokio::task::JoinHandle<()>,
+}
+
+impl ArchiveService {
+    /// Drains the ingestion queue into the pipeline, stops the roller, then
+    /// closes every open file so nothing is left `.inprogress` for the next
+    /// start to recover.
+    async fn shutdown(self) {
+        drop(self.ingest);
+        self.pipeline.shutdown().await;
+        let _ = self.forward.await;
+        let _ = self.roller.await;
+        let archive = self.archive.clone();
+        match tokio::task::spawn_blocking(move || archive.close_all()).await {
+            Ok(Ok(closed)) => info!(closed, "parquet archive closed"),
+            Ok(Err(e)) => error!(%e, "parquet archive close failed"),
+            Err(e) => error!(%e, "parquet archive close task failed"),
+        }
+    }
+}
+
+/// Build the archive from `[persistence.archive]` when it is enabled.
+/// Failures are logged and leave the collector running without an archive.
+async fn start_archive(
+    config_path: &str,
+    shutdown_rx: watch::Receiver<bool>,
+) -> Option<ArchiveService> {
+    let cfg = config::load_config(Path::new(config_path)).ok()?;
+    let persistence = &cfg.persistence;
+    if !persistence.archive.enabled {
+        return None;
+    }
+
+    let metrics = PersistenceMetrics::new();
+    let archive = ParquetArchive::new(persistence.archive.to_archive_config(), metrics.clone());
+    let recovering = archive.clone();
+    match tokio::task::spawn_blocking(move || recovering.recover_leftovers()).await {
+        Ok(Ok(0)) => {}
+        Ok(Ok(closed)) => info!(closed, "parquet archive journals recovered"),
+        Ok(Err(e)) => error!(%e, "parquet archive recovery failed"),
+        Err(e) => error!(%e, "parquet archive recovery task failed"),
+    }
+    let sink = match PipelineSink::build(
+        archive.clone(),
+        persistence.to_pipeline_config(),
+        metrics,
+        Some(shutdown_rx.clone()),
+    )
+    .await
+    {
+        Ok(sink) => Arc::new(sink),
+        Err(e) => {
+            error!(%e, "parquet archive pipeline failed to start");
+            return None;
+        }
+    };
+    info!(dir = %persistence.archive.dir, "parquet archive enabled");
+
+    let (bridge, forward) = IngestBridge::spawn(sink, DEFAULT_BRIDGE_CAPACITY);
+    let (ingest, pipeline) = BufferRunner::spawn(
+        INGEST_QUEUE_CAPACITY,
+        INGEST_MAX_BATCH_ITEMS,
+        INGEST_MAX_BATCH_INTERVAL_MS,
+        Box::new(bridge),
+        Arc::new(metrics::Metrics::default()),
+    );
+
+    let roller = tokio::spawn(roll_archive(archive.clone(), shutdown_rx));
+    Some(ArchiveService {
+        archive,
+        ingest,
+        pipeline,
+        forward,
+        roller,
+    })
+}
+
+/// Periodically closes files that are too old or whose hour has ended.
+async fn roll_archive(archive: Arc<ParquetArchive>, mut shutdown_rx: watch::Receiver<bool>) {
+    let mut interval = tokio::time::interval(ARCHIVE_ROLL_INTERVAL);
+    loop {
+        tokio::select! {
+            _ = interval.tick() => {}
+            _ = shutdown_rx.changed() => return,
+        }
+        let archive = archive.clone();
+        match tokio::task::spawn_blocking(move || archive.roll_expired()).await {
+            Ok(Ok(0)) => {}
+            Ok(Ok(closed)) => info!(closed, "parquet archive files rolled"),
+            Ok(Err(e)) => warn!(%e, "parquet archive roll failed"),
+            Err(e) => warn!(%e, "parquet archive roll task failed"),
+        }
+    }
+}
+
 // ---------------------------------------------------------------------------
 // Graceful shutdown
 // ---------------------------------------------------------------------------
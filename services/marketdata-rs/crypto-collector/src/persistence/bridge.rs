@@ -0,0 +1,153 @@
+//! Bridge from the ingestion runtime into the persistence pipeline.
+//!
+//! [`crate::ingestion::BufferRunner`] batches envelopes and hands them to a
+//! synchronous [`crate::ingestion::Sink`]; the persistence [`Sink`] is async.
+//! [`IngestBridge`] converts each batch to persistence envelopes and queues it
+//! for a forwarding task that awaits `write_batch`, so archive I/O never runs
+//! on the ingestion runner.  A full queue is reported back to the runner as
+//! an error (counted in `ingest_errors_total`) instead of blocking it.
+
+use std::sync::Arc;
+
+use tokio::sync::mpsc;
+use tokio::task::JoinHandle;
+
+use super::envelope::Envelope;
+use super::sink::Sink;
+use crate::ingestion;
+
+/// Batches queued between the ingestion runner and the persistence sink.
+pub const DEFAULT_BRIDGE_CAPACITY: usize = 64;
+
+/// `ingestion::Sink` that forwards every batch into a persistence [`Sink`].
+pub struct IngestBridge {
+    tx: mpsc::Sender<Vec<Envelope>>,
+}
+
+impl IngestBridge {
+    /// Spawns the forwarding task.  It ends once the bridge (and so the
+    /// ingestion runner owning it) is dropped and the queue is drained.
+    pub fn spawn(sink: Arc<dyn Sink>, capacity: usize) -> (Self, JoinHandle<()>) {
+        let (tx, mut rx) = mpsc::channel::<Vec<Envelope>>(capacity.max(1));
+        let forward = tokio::spawn(async move {
+            while let Some(batch) = rx.recv().await {
+                let rows = batch.len();
+                if let Err(e) = sink.write_batch(batch).await {
+                    tracing::warn!(rows, %e, "persistence sink rejected ingested batch");
+                }
+            }
+        });
+        (Self { tx }, forward)
+    }
+}
+
+impl ingestion::Sink for IngestBridge {
+    fn emit_batch(
+        &mut self,
+        batch: Vec<crate::envelope::Envelope>,
+    ) -> Result<(), ingestion::SinkError> {
+        let batch: Vec<Envelope> = batch.into_iter().map(to_persistence).collect();
+        self.tx.try_send(batch).map_err(|e| ingestion::SinkError {
+            message: match e {
+                mpsc::error::TrySendError::Full(b) => {
+                    format!("persistence backlog full, dropped {} envelopes", b.len())
+                }
+                mpsc::error::TrySendError::Closed(_) => "persistence sink closed".to_string(),
+            },
+        })
+    }
+}
+
+/// Ingestion envelope → persistence envelope.
+pub fn to_persistence(env: crate::envelope::Envelope) -> Envelope {
+    Envelope {
+        message_id: env.message_id,
+        sequence: env.sequence,
+        exchange: env.exchange,
+        channel: env.channel,
+        symbol: env.symbol,
+        server_time_ms: env.server_time,
+        received_at_ms: (env.local_time_ns / 1_000_000) as i64,
+        payload: env.payload,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ingestion::BufferRunner;
+    use crate::metrics::Metrics;
+    use crate::persistence::metrics::PersistenceMetrics;
+    use crate::persistence::parquet::{ParquetArchive, ParquetArchiveConfig};
+    use crate::persistence::pipeline::{PipelineConfig, PipelineSink};
+    use arrow_array::{Array, StringArray};
+    use parquet::arrow::arrow_reader::ParquetRecordBatchReaderBuilder;
+    use serde_json::json;
+    use std::fs::File;
+    use tempfile::TempDir;
+
+    // 2024-03-09T13:00:00Z
+    const T0_NS: u64 = 1_709_989_200_000_000_000;
+
+    #[tokio::test]
+    async fn ingested_envelopes_land_in_a_closed_parquet_part() {
+        let tmp = TempDir::new().unwrap();
+        let metrics = PersistenceMetrics::new();
+        let archive = ParquetArchive::new(ParquetArchiveConfig::new(tmp.path()), metrics.clone());
+        let pipeline =
+            PipelineSink::build(archive.clone(), PipelineConfig::default(), metrics, None)
+                .await
+                .unwrap();
+        let (bridge, forward) = IngestBridge::spawn(Arc::new(pipeline), 4);
+        let (ingest, handle) =
+            BufferRunner::spawn(16, 2, 50, Box::new(bridge), Arc::new(Metrics::default()));
+
+        for (i, price) in ["50000.5", "50001.25", "49999.75"].into_iter().enumerate() {
+            let env = crate::envelope::Envelope::builder(
+                "adapter@1",
+                "cid",
+                "binance",
+                "BTCUSDT",
+                "trade",
+                json!({"t": i, "p": price, "q": "0.1", "side": "buy"}),
+            )
+            .local_time_ns(T0_NS + i as u64 * 1_000_000)
+            .sequence(i as u64 + 1)
+            .build();
+            ingest.try_send(env).unwrap();
+        }
+        drop(ingest);
+        handle.shutdown().await;
+        forward.await.unwrap();
+        assert_eq!(archive.close_all().unwrap(), 1);
+
+        let dir = tmp
+            .path()
+            .join("exchange=binance/channel=trade/date=2024-03-09/hour=13");
+        assert!(!dir.join("part-000001.parquet.inprogress").exists());
+        assert!(!dir.join("part-000001.parquet.wal").exists());
+        let batches: Vec<_> = ParquetRecordBatchReaderBuilder::try_new(
+            File::open(dir.join("part-000001.parquet")).unwrap(),
+        )
+        .unwrap()
+        .build()
+        .unwrap()
+        .map(|b| b.unwrap())
+        .collect();
+        let prices: Vec<String> = batches
+            .iter()
+            .flat_map(|b| {
+                let col = b
+                    .column_by_name("price")
+                    .unwrap()
+                    .as_any()
+                    .downcast_ref::<StringArray>()
+                    .unwrap();
+                (0..col.len())
+                    .map(|i| col.value(i).to_string())
+                    .collect::<Vec<_>>()
+            })
+            .collect();
+        assert_eq!(prices, ["50000.5", "50001.25", "49999.75"]);
+    }
+}
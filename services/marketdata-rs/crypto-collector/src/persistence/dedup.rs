@@ -3,7 +3,7 @@
 //! ## Key rules (in priority order)
 //! 1. `message_id` → `"mid:<id>"`
 //! 2. `sequence`   → `"seq:<exchange>:<channel>:<seq>"`
-//! 3. payload hash → `"hash:<16 hex chars>"`
+//! 3. payload hash → `"hash:<32 hex chars>"` (SHA-256 of canonical JSON)
 //!
 //! ## Eviction
 //!
@@ -11,17 +11,42 @@
 //! call.  When `len > max_keys` the oldest entries are also evicted to keep
 //! the window bounded (no memory leak).
 //!
+//! ## Persistent index
+//!
+//! The in-memory window forgets everything on restart.  When the pipeline
+//! runs with a spool, a [`DedupIndex`] is kept in `{spool.dir}/dedup/`: it
+//! records the keys of envelopes that have *reached the sink* (directly or via
+//! replay), with wall-clock timestamps so the same window applies after a
+//! restart.
+//!
+//! ```text
+//! {spool.dir}/dedup/
+//!     index.json     ← compacted snapshot
+//!     journal.log    ← one JSON line per delivered key since the snapshot
+//! ```
+//!
+//! `DedupWindow` treats indexed keys as duplicates, and `ReplayWorker` skips
+//! indexed keys before inserting, so a segment that was partly replayed before
+//! a crash — or whose envelopes were re-sent live after the restart — is not
+//! written to the sink twice.
+//!
 //! ## Thread safety
 //!
-//! `DedupWindow` is `Send + Sync`; all internal state is guarded by a single
-//! `Mutex`.
+//! `DedupWindow` and `DedupIndex` are `Send + Sync`; all internal state is
+//! guarded by a single `Mutex` each.
 
 use std::collections::{HashSet, VecDeque};
+use std::fs::{File, OpenOptions};
+use std::io::Write;
+use std::path::{Path, PathBuf};
 use std::sync::{Arc, Mutex};
-use std::time::{Duration, Instant};
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+
+use serde::{Deserialize, Serialize};
 
 use super::envelope::Envelope;
 use super::metrics::PersistenceMetrics;
+use super::sink::SinkError;
 
 // ---------------------------------------------------------------------------
 // DedupConfig
@@ -111,6 +136,7 @@ impl Inner {
 pub struct DedupWindow {
     config: DedupConfig,
     inner: Mutex<Inner>,
+    index: Option<Arc<DedupIndex>>,
     metrics: Arc<PersistenceMetrics>,
 }
 
@@ -119,6 +145,22 @@ impl DedupWindow {
         Arc::new(Self {
             config,
             inner: Mutex::new(Inner::new()),
+            index: None,
+            metrics,
+        })
+    }
+
+    /// Like [`DedupWindow::new`], but keys already in the persistent `index`
+    /// (delivered before a restart, or by replay) are also duplicates.
+    pub fn with_index(
+        config: DedupConfig,
+        index: Arc<DedupIndex>,
+        metrics: Arc<PersistenceMetrics>,
+    ) -> Arc<Self> {
+        Arc::new(Self {
+            config,
+            inner: Mutex::new(Inner::new()),
+            index: Some(index),
             metrics,
         })
     }
@@ -134,7 +176,8 @@ impl DedupWindow {
             .into_iter()
             .filter(|env| {
                 let key = env.dedup_key();
-                if guard.check_and_mark(key, window, max_keys) {
+                let delivered = self.index.as_ref().is_some_and(|ix| ix.contains(&key));
+                if delivered || guard.check_and_mark(key, window, max_keys) {
                     self.metrics
                         .increment_dedup_dropped(&env.exchange, &env.channel);
                     false
@@ -155,6 +198,202 @@ impl DedupWindow {
     }
 }
 
+// ---------------------------------------------------------------------------
+// DedupIndex (persistent)
+// ---------------------------------------------------------------------------
+
+const INDEX_SNAPSHOT: &str = "index.json";
+const INDEX_JOURNAL: &str = "journal.log";
+
+/// Compact the journal into a snapshot once it holds this many lines more
+/// than there are live keys.
+const COMPACT_SLACK: usize = 4096;
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+struct IndexEntry {
+    key: String,
+    at_ms: i64,
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct IndexSnapshot {
+    entries: Vec<IndexEntry>,
+}
+
+struct IndexInner {
+    /// Insertion order (oldest at front) for eviction.
+    queue: VecDeque<IndexEntry>,
+    seen: HashSet<String>,
+    journal: File,
+    journal_lines: usize,
+}
+
+impl IndexInner {
+    fn evict(&mut self, window_ms: i64, max_keys: usize, now_ms: i64) {
+        while let Some(front) = self.queue.front() {
+            let expired = now_ms - front.at_ms > window_ms;
+            if expired || self.queue.len() > max_keys {
+                let front = self.queue.pop_front().expect("front checked");
+                self.seen.remove(&front.key);
+            } else {
+                break;
+            }
+        }
+    }
+}
+
+/// Disk-backed set of dedup keys that have been delivered to the sink.
+///
+/// Appends go to a line-oriented journal; [`DedupIndex::checkpoint`] rewrites
+/// the live keys into a snapshot (write-then-rename) and truncates the
+/// journal.  A torn last journal line from a crash is ignored on open.
+pub struct DedupIndex {
+    dir: PathBuf,
+    config: DedupConfig,
+    inner: Mutex<IndexInner>,
+}
+
+fn wall_ms() -> i64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_millis() as i64)
+        .unwrap_or(0)
+}
+
+impl DedupIndex {
+    /// Open (or create) the index in `dir`, replaying snapshot + journal and
+    /// dropping keys that fell out of the window while the process was down.
+    pub fn open(dir: impl Into<PathBuf>, config: DedupConfig) -> Result<Arc<Self>, SinkError> {
+        let dir = dir.into();
+        std::fs::create_dir_all(&dir)?;
+
+        let mut entries = match std::fs::read(dir.join(INDEX_SNAPSHOT)) {
+            Ok(bytes) => serde_json::from_slice::<IndexSnapshot>(&bytes)
+                .map_err(|e| SinkError::Serialise(format!("dedup index snapshot: {e}")))?
+                .entries,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Vec::new(),
+            Err(e) => return Err(e.into()),
+        };
+        match std::fs::read_to_string(dir.join(INDEX_JOURNAL)) {
+            Ok(text) => {
+                for line in text.lines() {
+                    match serde_json::from_str::<IndexEntry>(line) {
+                        Ok(entry) => entries.push(entry),
+                        Err(e) => {
+                            tracing::warn!(error = %e, "skipping torn dedup journal line");
+                        }
+                    }
+                }
+            }
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => {}
+            Err(e) => return Err(e.into()),
+        }
+
+        let mut queue = VecDeque::with_capacity(entries.len());
+        let mut seen = HashSet::with_capacity(entries.len());
+        for entry in entries {
+            if seen.insert(entry.key.clone()) {
+                queue.push_back(entry);
+            }
+        }
+        let journal = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(dir.join(INDEX_JOURNAL))?;
+        let index = Arc::new(Self {
+            dir,
+            config,
+            inner: Mutex::new(IndexInner { queue, seen, journal, journal_lines: 0 }),
+        });
+        index.checkpoint()?;
+        Ok(index)
+    }
+
+    pub fn dir(&self) -> &Path {
+        &self.dir
+    }
+
+    fn window_ms(&self) -> i64 {
+        (self.config.window_seconds as i64).saturating_mul(1000)
+    }
+
+    /// `true` if `key` was delivered within the window.
+    pub fn contains(&self, key: &str) -> bool {
+        let mut guard = self.inner.lock().unwrap();
+        guard.evict(self.window_ms(), self.config.max_keys, wall_ms());
+        guard.seen.contains(key)
+    }
+
+    /// Drop envelopes whose keys are already indexed.  Returns the remaining
+    /// envelopes and the number skipped.
+    pub fn filter_delivered(&self, batch: &[Envelope]) -> (Vec<Envelope>, usize) {
+        let mut guard = self.inner.lock().unwrap();
+        guard.evict(self.window_ms(), self.config.max_keys, wall_ms());
+        let fresh: Vec<Envelope> = batch
+            .iter()
+            .filter(|env| !guard.seen.contains(&env.dedup_key()))
+            .cloned()
+            .collect();
+        let skipped = batch.len() - fresh.len();
+        (fresh, skipped)
+    }
+
+    /// Journal the keys of `delivered` envelopes.  Call only after the sink
+    /// has accepted them.
+    pub fn record(&self, delivered: &[Envelope]) -> Result<(), SinkError> {
+        let now = wall_ms();
+        let mut guard = self.inner.lock().unwrap();
+        let mut buf = Vec::new();
+        for env in delivered {
+            let entry = IndexEntry { key: env.dedup_key(), at_ms: now };
+            if !guard.seen.insert(entry.key.clone()) {
+                continue;
+            }
+            serde_json::to_writer(&mut buf, &entry).map_err(|e| SinkError::Serialise(e.to_string()))?;
+            buf.push(b'\n');
+            guard.queue.push_back(entry);
+            guard.journal_lines += 1;
+        }
+        if !buf.is_empty() {
+            guard.journal.write_all(&buf)?;
+            guard.journal.flush()?;
+            guard.journal.sync_data()?;
+        }
+        guard.evict(self.window_ms(), self.config.max_keys, now);
+        let compact = guard.journal_lines > guard.queue.len() + COMPACT_SLACK;
+        drop(guard);
+        if compact {
+            self.checkpoint()?;
+        }
+        Ok(())
+    }
+
+    /// Rewrite the live keys into the snapshot and truncate the journal.
+    pub fn checkpoint(&self) -> Result<(), SinkError> {
+        let mut guard = self.inner.lock().unwrap();
+        guard.evict(self.window_ms(), self.config.max_keys, wall_ms());
+        let snapshot = IndexSnapshot { entries: guard.queue.iter().cloned().collect() };
+        let bytes = serde_json::to_vec(&snapshot).map_err(|e| SinkError::Serialise(e.to_string()))?;
+        let tmp = self.dir.join(format!("{INDEX_SNAPSHOT}.tmp"));
+        let mut file = File::create(&tmp)?;
+        file.write_all(&bytes)?;
+        file.sync_all()?;
+        std::fs::rename(&tmp, self.dir.join(INDEX_SNAPSHOT))?;
+        guard.journal.set_len(0)?;
+        guard.journal_lines = 0;
+        Ok(())
+    }
+
+    /// Number of live keys (for observability/tests).
+    pub fn len(&self) -> usize {
+        self.inner.lock().unwrap().seen.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
 // ---------------------------------------------------------------------------
 // Tests
 // ---------------------------------------------------------------------------
@@ -272,4 +511,60 @@ mod tests {
         assert_eq!(metrics.dedup_dropped_total.get("kraken", "orderbook"), 1);
         assert_eq!(metrics.dedup_dropped_total.get("binance", "trades"), 0);
     }
+
+    #[test]
+    fn index_survives_reopen_and_feeds_window() {
+        let tmp = tempfile::TempDir::new().unwrap();
+        let index = DedupIndex::open(tmp.path(), DedupConfig::default()).unwrap();
+        index.record(&[make_env(Some("a"), "trades"), make_env(Some("b"), "trades")]).unwrap();
+        drop(index);
+
+        // Restart: keys come back from the journal.
+        let index = DedupIndex::open(tmp.path(), DedupConfig::default()).unwrap();
+        assert_eq!(index.len(), 2);
+        let metrics = PersistenceMetrics::new();
+        let dw = DedupWindow::with_index(DedupConfig::default(), index.clone(), metrics.clone());
+        let out = dw.filter(vec![make_env(Some("a"), "trades"), make_env(Some("c"), "trades")]);
+        assert_eq!(out.len(), 1);
+        assert_eq!(out[0].message_id.as_deref(), Some("c"));
+        assert_eq!(metrics.dedup_dropped_total.get("binance", "trades"), 1);
+
+        let (fresh, skipped) = index.filter_delivered(&[make_env(Some("b"), "trades"), make_env(Some("c"), "trades")]);
+        assert_eq!((fresh.len(), skipped), (1, 1));
+    }
+
+    #[test]
+    fn index_ignores_torn_journal_line_and_compacts() {
+        let tmp = tempfile::TempDir::new().unwrap();
+        let index = DedupIndex::open(tmp.path(), DedupConfig::default()).unwrap();
+        index.record(&[make_env(Some("a"), "trades")]).unwrap();
+        drop(index);
+        let mut journal = OpenOptions::new().append(true).open(tmp.path().join(INDEX_JOURNAL)).unwrap();
+        journal.write_all(br#"{"key":"mid:b","at_"#).unwrap();
+        drop(journal);
+
+        let index = DedupIndex::open(tmp.path(), DedupConfig::default()).unwrap();
+        assert!(index.contains("mid:a"));
+        assert!(!index.contains("mid:b"));
+        // Open compacts: everything lives in the snapshot, journal is empty.
+        assert_eq!(std::fs::metadata(tmp.path().join(INDEX_JOURNAL)).unwrap().len(), 0);
+        assert!(tmp.path().join(INDEX_SNAPSHOT).exists());
+    }
+
+    #[test]
+    fn index_respects_window_and_cap() {
+        let tmp = tempfile::TempDir::new().unwrap();
+        let index = DedupIndex::open(tmp.path(), DedupConfig { window_seconds: 3600, max_keys: 2 }).unwrap();
+        for id in ["a", "b", "c"] {
+            index.record(&[make_env(Some(id), "trades")]).unwrap();
+        }
+        assert_eq!(index.len(), 2);
+        assert!(!index.contains("mid:a"));
+        drop(index);
+
+        // A zero window expires every persisted key on reopen.
+        let index = DedupIndex::open(tmp.path(), DedupConfig { window_seconds: 0, max_keys: 2 }).unwrap();
+        std::thread::sleep(Duration::from_millis(2));
+        assert!(!index.contains("mid:c"));
+    }
 }
@@ -14,7 +14,7 @@ use serde::{Deserialize, Serialize};
 /// Field priority for dedup key resolution:
 /// 1. `message_id`  (exchange-provided, most specific)
 /// 2. `sequence`    (monotonic sequence, scoped to exchange+channel)
-/// 3. payload hash  (fallback: DefaultHasher of serialised payload)
+/// 3. payload hash  (fallback: SHA-256 of the canonical JSON payload)
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
 pub struct Envelope {
     /// Optional exchange-provided message ID (dedup priority 1).
@@ -39,12 +39,11 @@ impl Envelope {
     /// Compute the dedup key for this envelope using the priority rules:
     /// 1. `message_id` → `"mid:<id>"`
     /// 2. `sequence`   → `"seq:<exchange>:<channel>:<seq>"`
-    /// 3. hash         → `"hash:<16 hex chars>"`
+    /// 3. hash         → `"hash:<32 hex chars>"`
     ///
-    /// Note: The hash uses `std::hash::DefaultHasher` on the serialised payload
-    /// string. JSON key ordering is not normalised, so semantically equal
-    /// payloads with different key order may produce different hashes (false
-    /// negatives are acceptable for dedup; false positives are not).
+    /// The hash is the first 128 bits of SHA-256 over [`canonical_json`] of
+    /// the payload, so it is independent of key order and of the Rust
+    /// toolchain, and can be persisted across restarts (see `dedup`).
     pub fn dedup_key(&self) -> String {
         if let Some(ref mid) = self.message_id {
             return format!("mid:{mid}");
@@ -52,11 +51,61 @@ impl Envelope {
         if let Some(seq) = self.sequence {
             return format!("seq:{}:{}:{}", self.exchange, self.channel, seq);
         }
-        use std::collections::hash_map::DefaultHasher;
-        use std::hash::{Hash, Hasher};
-        let mut hasher = DefaultHasher::new();
-        self.payload.to_string().hash(&mut hasher);
-        format!("hash:{:016x}", hasher.finish())
+        use sha2::{Digest, Sha256};
+        let digest = Sha256::digest(canonical_json(&self.payload).as_bytes());
+        let hex: String = digest[..16].iter().map(|b| format!("{b:02x}")).collect();
+        format!("hash:{hex}")
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Canonical JSON
+// ---------------------------------------------------------------------------
+
+/// Serialise `value` into a canonical form: object keys sorted bytewise, no
+/// insignificant whitespace, and integral floats written as integers
+/// (`1.0` → `1`) so that venues switching number formatting do not change
+/// the dedup key.
+pub fn canonical_json(value: &serde_json::Value) -> String {
+    let mut out = String::new();
+    write_canonical(value, &mut out);
+    out
+}
+
+fn write_canonical(value: &serde_json::Value, out: &mut String) {
+    use serde_json::Value;
+    match value {
+        Value::Null | Value::Bool(_) | Value::String(_) => out.push_str(&value.to_string()),
+        Value::Number(n) => match n.as_f64() {
+            Some(f) if n.is_f64() && f.fract() == 0.0 && f.abs() < 9_007_199_254_740_992.0 => {
+                out.push_str(&(f as i64).to_string())
+            }
+            _ => out.push_str(&n.to_string()),
+        },
+        Value::Array(items) => {
+            out.push('[');
+            for (i, item) in items.iter().enumerate() {
+                if i > 0 {
+                    out.push(',');
+                }
+                write_canonical(item, out);
+            }
+            out.push(']');
+        }
+        Value::Object(map) => {
+            let mut entries: Vec<_> = map.iter().collect();
+            entries.sort_by(|a, b| a.0.as_bytes().cmp(b.0.as_bytes()));
+            out.push('{');
+            for (i, (key, item)) in entries.into_iter().enumerate() {
+                if i > 0 {
+                    out.push(',');
+                }
+                out.push_str(&Value::String(key.clone()).to_string());
+                out.push(':');
+                write_canonical(item, out);
+            }
+            out.push('}');
+        }
     }
 }
 
@@ -112,7 +161,7 @@ mod tests {
             k.starts_with("hash:"),
             "expected hash: prefix, got: {k}"
         );
-        assert_eq!(k.len(), "hash:".len() + 16);
+        assert_eq!(k.len(), "hash:".len() + 32);
     }
 
     #[test]
@@ -122,6 +171,33 @@ mod tests {
         assert_eq!(env1.dedup_key(), env2.dedup_key());
     }
 
+    #[test]
+    fn dedup_key_hash_ignores_key_order_and_whitespace() {
+        let mut env1 = make_env(None, None);
+        env1.payload = serde_json::from_str(r#"{"qty": "1.0", "price": "50000", "x": {"b": 2, "a": [1.0, null]}}"#).unwrap();
+        let mut env2 = make_env(None, None);
+        env2.payload = serde_json::from_str(r#"{"x":{"a":[1,null],"b":2},"price":"50000","qty":"1.0"}"#).unwrap();
+        assert_eq!(env1.dedup_key(), env2.dedup_key());
+
+        env2.payload["qty"] = json!("1.00");
+        assert_ne!(env1.dedup_key(), env2.dedup_key());
+    }
+
+    #[test]
+    fn dedup_key_hash_is_fixed() {
+        // Pinned so that keys persisted by the dedup index stay valid across
+        // builds; changing the hash scheme must change this test.
+        let env = make_env(None, None);
+        assert_eq!(canonical_json(&env.payload), r#"{"price":"50000","qty":"1.0"}"#);
+        assert_eq!(env.dedup_key(), "hash:4889e2958be13da1ef83f6b5f24e8234");
+    }
+
+    #[test]
+    fn canonical_json_escapes_and_sorts() {
+        let v = json!({"é": "a\"b", "Z": [true, 1.5, -0.0], "a": {}});
+        assert_eq!(canonical_json(&v), r#"{"Z":[true,1.5,0],"a":{},"é":"a\"b"}"#);
+    }
+
     #[test]
     fn envelope_json_roundtrip() {
         let env = Envelope {
@@ -25,6 +25,12 @@ impl ExchangeChannelCounter {
             .or_insert(0) += 1;
     }
 
+    pub fn add(&self, exchange: &str, channel: &str, n: u64) {
+        let mut m = self.data.lock().unwrap();
+        *m.entry((exchange.to_string(), channel.to_string()))
+            .or_insert(0) += n;
+    }
+
     pub fn get(&self, exchange: &str, channel: &str) -> u64 {
         let m = self.data.lock().unwrap();
         *m.get(&(exchange.to_string(), channel.to_string()))
@@ -87,10 +93,20 @@ pub struct PersistenceMetrics {
     // D3 -----------------------------------------------------------------------
     /// spool_replay_total (cumulative envelopes successfully replayed)
     pub spool_replay_total: AtomicU64,
+    /// replay_dedup_skipped_total (replayed envelopes already delivered)
+    pub replay_dedup_skipped_total: AtomicU64,
 
     // D4 -----------------------------------------------------------------------
     /// dedup_dropped_total{exchange,channel}
     pub dedup_dropped_total: ExchangeChannelCounter,
+    /// dedup_index_errors_total (delivered batches whose keys failed to journal)
+    pub dedup_index_errors_total: AtomicU64,
+
+    // D7 -----------------------------------------------------------------------
+    /// archive_rows_total{exchange,channel} (Parquet rows written)
+    pub archive_rows_total: ExchangeChannelCounter,
+    /// archive_files_total (cumulative Parquet files closed)
+    pub archive_files_total: AtomicU64,
 }
 
 impl PersistenceMetrics {
@@ -144,11 +160,41 @@ impl PersistenceMetrics {
         self.spool_replay_total.load(Ordering::Relaxed)
     }
 
+    pub fn add_replay_dedup_skipped(&self, count: u64) {
+        self.replay_dedup_skipped_total.fetch_add(count, Ordering::Relaxed);
+    }
+
+    pub fn replay_dedup_skipped_total(&self) -> u64 {
+        self.replay_dedup_skipped_total.load(Ordering::Relaxed)
+    }
+
     // D4 helpers ---------------------------------------------------------------
 
     pub fn increment_dedup_dropped(&self, exchange: &str, channel: &str) {
         self.dedup_dropped_total.increment(exchange, channel);
     }
+
+    pub fn increment_dedup_index_errors(&self) {
+        self.dedup_index_errors_total.fetch_add(1, Ordering::Relaxed);
+    }
+
+    pub fn dedup_index_errors_total(&self) -> u64 {
+        self.dedup_index_errors_total.load(Ordering::Relaxed)
+    }
+
+    // D7 helpers ---------------------------------------------------------------
+
+    pub fn add_archive_rows(&self, exchange: &str, channel: &str, rows: u64) {
+        self.archive_rows_total.add(exchange, channel, rows);
+    }
+
+    pub fn increment_archive_files(&self) {
+        self.archive_files_total.fetch_add(1, Ordering::Relaxed);
+    }
+
+    pub fn archive_files_total(&self) -> u64 {
+        self.archive_files_total.load(Ordering::Relaxed)
+    }
 }
 
 // ---------------------------------------------------------------------------
@@ -208,5 +254,11 @@ mod tests {
 
         m.increment_dedup_dropped("binance", "orderbook");
         assert_eq!(m.dedup_dropped_total.get("binance", "orderbook"), 1);
+
+        m.add_archive_rows("binance", "trades", 5);
+        m.add_archive_rows("binance", "trades", 2);
+        assert_eq!(m.archive_rows_total.get("binance", "trades"), 7);
+        m.increment_archive_files();
+        assert_eq!(m.archive_files_total(), 1);
     }
 }
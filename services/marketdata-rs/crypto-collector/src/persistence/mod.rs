@@ -1,13 +1,15 @@
 //! Persistence layer for the crypto-collector framework v1.4.
 //!
-//! These types are intentionally not wired into the binary entry-point yet;
-//! they will be connected in Tasks E and F.
+//! The binary builds `ParquetArchive` behind a `PipelineSink` when
+//! `[persistence.archive]` is enabled and feeds it from the ingestion runtime
+//! through `bridge::IngestBridge`; the rest is connected in Tasks E and F.
 #![allow(dead_code)]
 //!
 //! ## Modules
 //!
 //! | Module       | Purpose                                                |
 //! |--------------|--------------------------------------------------------|
+//! | `bridge`     | `IngestBridge` — ingestion runtime → `PipelineSink`    |
 //! | `envelope`   | Envelope v1 — canonical data type for raw messages     |
 //! | `sink`       | `Sink` trait + `SinkState` + `SinkError`               |
 //! | `metrics`    | `PersistenceMetrics` — atomic counters and gauges      |
@@ -16,6 +18,7 @@
 //! | `replay`     | D3: `ReplayWorker` — background spool-to-Mongo drain   |
 //! | `dedup`      | D4: `DedupWindow` — bounded time-windowed dedup        |
 //! | `pipeline`   | D5: `PipelineSink` — full fallback integration         |
+//! | `parquet`    | D7: `ParquetArchive` — partitioned columnar archive    |
 //!
 //! ## Fallback chain (implemented in `pipeline`)
 //!
@@ -31,10 +34,12 @@
 //!               block → wait + retry
 //! ```
 
+pub mod bridge;
 pub mod dedup;
 pub mod envelope;
 pub mod metrics;
 pub mod mongo;
+pub mod parquet;
 pub mod pipeline;
 pub mod replay;
 pub mod sink;
@@ -46,6 +51,8 @@ pub use envelope::Envelope;
 #[allow(unused_imports)]
 pub use metrics::PersistenceMetrics;
 #[allow(unused_imports)]
+pub use parquet::{ParquetArchive, ParquetArchiveConfig};
+#[allow(unused_imports)]
 pub use pipeline::{PipelineConfig, PipelineSink};
 #[allow(unused_imports)]
 pub use sink::{Sink, SinkError, SinkState};
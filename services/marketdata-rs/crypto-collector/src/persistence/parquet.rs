@@ -0,0 +1,1449 @@
+//! D7 — Columnar Parquet archive.
+//!
+//! `ParquetArchive` lands envelopes as Parquet files for offline research.
+//! It implements [`MongoTarget`], so it plugs into `PipelineSink::build` and
+//! `ReplayWorker` exactly where a Mongo collection would: a failed write
+//! (disk full, permission error, …) surfaces as `SinkError::MongoUnavailable`
+//! and the batch spills to the `DurableSpool`, to be replayed into the
+//! archive once it recovers.
+//!
+//! ## Layout
+//!
+//! ```text
+//! {root}/exchange={exchange}/channel={channel}/date={YYYY-MM-DD}/hour={HH}/
+//!     part-000001.parquet
+//!     part-000002.parquet
+//!     part-000003.parquet.inprogress   ← currently open file
+//!     _manifest.json                   ← closed files only
+//! ```
+//!
+//! Partitions are keyed on `received_at_ms` (UTC), which is always present
+//! and monotonic per collector, so exchange clock skew never reopens an old
+//! partition.
+//!
+//! ## Schemas
+//!
+//! The channel name selects an [`ArchiveKind`]:
+//!
+//! | Kind         | Rows                         | Typed columns                          |
+//! |--------------|------------------------------|----------------------------------------|
+//! | `Trades`     | one per trade in the payload | trade_id, price, qty, side             |
+//! | `Tickers`    | one per envelope             | bid, bid_qty, ask, ask_qty, last, volume |
+//! | `BookDeltas` | one per price level          | side, price, qty                       |
+//! | `Raw`        | one per envelope             | (payload JSON only)                    |
+//!
+//! Every kind also carries the envelope metadata columns (exchange, channel,
+//! symbol, received_at_ms, server_time_ms, sequence, message_id); trades,
+//! tickers and raw rows keep the original payload as a JSON string.
+//!
+//! Prices and quantities are stored as decimal strings exactly as the venue
+//! sent them (`"0.00012300"` stays `"0.00012300"`), never as `f64`.
+//!
+//! ## Rolling and durability
+//!
+//! A file is closed (footer written, renamed from `.inprogress`, appended to
+//! the partition manifest) once it reaches `max_file_bytes`, has been open
+//! for `max_file_age_ms`, or its partition's UTC hour has ended; a partition
+//! whose hour has ended is also dropped from memory.  Rows are buffered and
+//! written out as a row group once `row_group_rows` or `row_group_bytes` is
+//! reached.
+//!
+//! An `.inprogress` file has no footer until it is closed, so it cannot be
+//! trusted after a crash.  Every group written to it is therefore also
+//! appended to `part-NNNNNN.parquet.wal` and fsynced before the write is
+//! acknowledged; the journal is deleted once the part is closed and renamed.
+//! A leftover `.inprogress` file (crash, or a failed write) is renamed to
+//! `.orphaned` and its journal is replayed into a fresh part, so acknowledged
+//! rows are never lost.  Call [`ParquetArchive::recover_leftovers`] on start,
+//! [`ParquetArchive::roll_expired`] periodically and
+//! [`ParquetArchive::close_all`] on shutdown.
+//!
+//! ## Retries
+//!
+//! A batch spanning several partitions can fail half-way.  Each partition
+//! remembers fingerprints of the groups it accepted, so the retried batch
+//! skips them; a partition whose write fails has its open file quarantined
+//! as `.orphaned` and starts a fresh part on the next attempt, so a group is
+//! never half-present in a closed file.  Groups replayed from a journal are
+//! remembered too, so a retry of a group that reached the journal before the
+//! failure is not archived twice.
+
+use std::collections::{HashMap, VecDeque};
+use std::fs::{File, OpenOptions};
+use std::io::{BufRead, BufReader, Write};
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use arrow_array::builder::{Int64Builder, StringBuilder, UInt64Builder};
+use arrow_array::{ArrayRef, RecordBatch};
+use arrow_schema::{DataType, Field, Schema, SchemaRef};
+use async_trait::async_trait;
+use parquet::arrow::ArrowWriter;
+use parquet::basic::Compression;
+use parquet::file::properties::WriterProperties;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use sha2::{Digest, Sha256};
+
+use super::envelope::Envelope;
+use super::metrics::PersistenceMetrics;
+use super::mongo::MongoTarget;
+
+const INPROGRESS_SUFFIX: &str = ".inprogress";
+const JOURNAL_SUFFIX: &str = ".wal";
+const MANIFEST_FILE: &str = "_manifest.json";
+const HOUR_MS: i64 = 3_600_000;
+/// Group fingerprints remembered per partition for retry dedupe.
+const RECENT_GROUPS: usize = 1024;
+
+// ---------------------------------------------------------------------------
+// Configuration
+// ---------------------------------------------------------------------------
+
+#[derive(Debug, Clone)]
+pub struct ParquetArchiveConfig {
+    pub root: PathBuf,
+    /// Close the current file once its on-disk size reaches this many bytes.
+    pub max_file_bytes: u64,
+    /// Close the current file once it has been open this long.
+    pub max_file_age_ms: u64,
+    /// Write out a row group once this many rows are buffered.
+    pub row_group_rows: usize,
+    /// Write out a row group once the buffered rows reach this many bytes.
+    pub row_group_bytes: u64,
+}
+
+impl ParquetArchiveConfig {
+    pub fn new(root: impl Into<PathBuf>) -> Self {
+        Self {
+            root: root.into(),
+            max_file_bytes: 128 * 1024 * 1024, // 128 MiB
+            max_file_age_ms: 60 * 60 * 1000,   // 1 hour
+            row_group_rows: 128 * 1024,
+            row_group_bytes: 16 * 1024 * 1024, // 16 MiB
+        }
+    }
+}
+
+// ---------------------------------------------------------------------------
+// ArchiveKind
+// ---------------------------------------------------------------------------
+
+/// Which typed schema a channel is archived with.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ArchiveKind {
+    Trades,
+    Tickers,
+    BookDeltas,
+    Raw,
+}
+
+impl ArchiveKind {
+    pub fn for_channel(channel: &str) -> Self {
+        let c = channel.to_ascii_lowercase();
+        if c.contains("trade") {
+            Self::Trades
+        } else if c.contains("ticker") || c == "bbo" {
+            Self::Tickers
+        } else if c.contains("book") || c.contains("depth") || c.starts_with("l2") {
+            Self::BookDeltas
+        } else {
+            Self::Raw
+        }
+    }
+
+    pub fn schema(&self) -> SchemaRef {
+        let mut fields = vec![
+            Field::new("exchange", DataType::Utf8, false),
+            Field::new("channel", DataType::Utf8, false),
+            Field::new("symbol", DataType::Utf8, false),
+            Field::new("received_at_ms", DataType::Int64, false),
+            Field::new("server_time_ms", DataType::Int64, true),
+            Field::new("sequence", DataType::UInt64, true),
+            Field::new("message_id", DataType::Utf8, true),
+        ];
+        let decimal_col = |name: &str| Field::new(name, DataType::Utf8, true);
+        match self {
+            Self::Trades => {
+                fields.push(Field::new("trade_id", DataType::Utf8, true));
+                fields.push(decimal_col("price"));
+                fields.push(decimal_col("qty"));
+                fields.push(Field::new("side", DataType::Utf8, true));
+            }
+            Self::Tickers => {
+                for name in ["bid", "bid_qty", "ask", "ask_qty", "last", "volume"] {
+                    fields.push(decimal_col(name));
+                }
+            }
+            Self::BookDeltas => {
+                fields.push(Field::new("side", DataType::Utf8, false));
+                fields.push(Field::new("price", DataType::Utf8, false));
+                fields.push(Field::new("qty", DataType::Utf8, false));
+            }
+            Self::Raw => {}
+        }
+        if *self != Self::BookDeltas {
+            fields.push(Field::new("payload", DataType::Utf8, false));
+        }
+        Arc::new(Schema::new(fields))
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Payload field extraction
+// ---------------------------------------------------------------------------
+
+const PRICE_KEYS: &[&str] = &["price", "p", "px", "rate"];
+const QTY_KEYS: &[&str] = &[
+    "qty", "q", "size", "sz", "amount", "quantity", "volume", "v",
+];
+const SIDE_KEYS: &[&str] = &["side", "S", "taker_side", "takerSide"];
+const TRADE_ID_KEYS: &[&str] = &["trade_id", "tradeId", "t", "id", "tid", "trade_seq"];
+
+fn pick<'a>(obj: &'a Value, keys: &[&str]) -> Option<&'a Value> {
+    keys.iter()
+        .find_map(|k| obj.get(*k).filter(|v| !v.is_null()))
+}
+
+/// A decimal number as text: strings are kept verbatim (trimmed) when they
+/// are plain decimals, JSON numbers use their shortest round-trip form.
+fn as_decimal(v: &Value) -> Option<String> {
+    match v {
+        Value::Number(n) => Some(n.to_string()),
+        Value::String(s) => {
+            let s = s.trim();
+            let digits = s.strip_prefix(['-', '+']).unwrap_or(s);
+            let (int, frac) = digits.split_once('.').unwrap_or((digits, ""));
+            let valid = !(int.is_empty() && frac.is_empty())
+                && int.bytes().all(|b| b.is_ascii_digit())
+                && frac.bytes().all(|b| b.is_ascii_digit());
+            valid.then(|| s.to_string())
+        }
+        _ => None,
+    }
+}
+
+fn as_text(v: &Value) -> Option<String> {
+    match v {
+        Value::String(s) => Some(s.clone()),
+        Value::Number(n) => Some(n.to_string()),
+        Value::Bool(b) => Some(b.to_string()),
+        _ => None,
+    }
+}
+
+/// Unwraps the common `{"data": …}` wrapper used by several venues.
+fn body(payload: &Value) -> &Value {
+    match payload.get("data") {
+        Some(inner) if inner.is_object() || inner.is_array() => inner,
+        _ => payload,
+    }
+}
+
+/// Trades may arrive one per message or batched in an array.
+fn trade_items(payload: &Value) -> Vec<&Value> {
+    match body(payload) {
+        Value::Array(items) => items.iter().filter(|v| v.is_object()).collect(),
+        other => vec![other],
+    }
+}
+
+fn ticker_item(payload: &Value) -> &Value {
+    match body(payload) {
+        Value::Array(items) => items.first().unwrap_or(payload),
+        other => other,
+    }
+}
+
+/// Price levels as `[price, qty, …]` arrays or `{price, size}` objects.
+fn book_levels(payload: &Value) -> Vec<(&'static str, String, String)> {
+    let book = match body(payload) {
+        Value::Array(items) => items.first().unwrap_or(payload),
+        other => other,
+    };
+    let mut out = Vec::new();
+    for (side, keys) in [("bid", ["bids", "b"]), ("ask", ["asks", "a"])] {
+        let Some(levels) = pick(book, &keys).and_then(Value::as_array) else {
+            continue;
+        };
+        for level in levels {
+            let pq = match level {
+                Value::Array(parts) => parts
+                    .first()
+                    .and_then(as_decimal)
+                    .zip(parts.get(1).and_then(as_decimal)),
+                Value::Object(_) => pick(level, PRICE_KEYS)
+                    .and_then(as_decimal)
+                    .zip(pick(level, QTY_KEYS).and_then(as_decimal)),
+                _ => None,
+            };
+            if let Some((price, qty)) = pq {
+                out.push((side, price, qty));
+            }
+        }
+    }
+    out
+}
+
+// ---------------------------------------------------------------------------
+// RecordBatch construction
+// ---------------------------------------------------------------------------
+
+#[derive(Default)]
+struct MetaColumns {
+    exchange: StringBuilder,
+    channel: StringBuilder,
+    symbol: StringBuilder,
+    received_at_ms: Int64Builder,
+    server_time_ms: Int64Builder,
+    sequence: UInt64Builder,
+    message_id: StringBuilder,
+}
+
+impl MetaColumns {
+    fn push(&mut self, env: &Envelope) {
+        self.exchange.append_value(&env.exchange);
+        self.channel.append_value(&env.channel);
+        self.symbol.append_value(&env.symbol);
+        self.received_at_ms.append_value(env.received_at_ms);
+        self.server_time_ms.append_option(env.server_time_ms);
+        self.sequence.append_option(env.sequence);
+        self.message_id.append_option(env.message_id.as_deref());
+    }
+
+    fn finish(mut self) -> Vec<ArrayRef> {
+        vec![
+            Arc::new(self.exchange.finish()),
+            Arc::new(self.channel.finish()),
+            Arc::new(self.symbol.finish()),
+            Arc::new(self.received_at_ms.finish()),
+            Arc::new(self.server_time_ms.finish()),
+            Arc::new(self.sequence.finish()),
+            Arc::new(self.message_id.finish()),
+        ]
+    }
+}
+
+/// Builds one `RecordBatch` for envelopes that all share `kind`.
+fn build_batch(kind: ArchiveKind, envelopes: &[Envelope]) -> Result<RecordBatch, String> {
+    let mut meta = MetaColumns::default();
+    let mut payload = StringBuilder::new();
+    let mut text = StringBuilder::new();
+    let mut side = StringBuilder::new();
+    let mut nums: Vec<StringBuilder> = (0..6).map(|_| StringBuilder::new()).collect();
+
+    for env in envelopes {
+        let payload_json = env.payload.to_string();
+        match kind {
+            ArchiveKind::Trades => {
+                for trade in trade_items(&env.payload) {
+                    meta.push(env);
+                    text.append_option(pick(trade, TRADE_ID_KEYS).and_then(as_text));
+                    nums[0].append_option(pick(trade, PRICE_KEYS).and_then(as_decimal));
+                    nums[1].append_option(pick(trade, QTY_KEYS).and_then(as_decimal));
+                    side.append_option(pick(trade, SIDE_KEYS).and_then(as_text));
+                    payload.append_value(&payload_json);
+                }
+            }
+            ArchiveKind::Tickers => {
+                let t = ticker_item(&env.payload);
+                meta.push(env);
+                let cols: [&[&str]; 6] = [
+                    &["bid", "best_bid", "bidPrice", "bid_price", "bidPx", "b"],
+                    &["bid_qty", "bidQty", "bid_size", "bidSz", "B"],
+                    &["ask", "best_ask", "askPrice", "ask_price", "askPx", "a"],
+                    &["ask_qty", "askQty", "ask_size", "askSz", "A"],
+                    &["last", "last_price", "lastPrice", "ltp", "c"],
+                    &["volume", "vol", "volume_24h", "v"],
+                ];
+                for (builder, keys) in nums.iter_mut().zip(cols) {
+                    builder.append_option(pick(t, keys).and_then(as_decimal));
+                }
+                payload.append_value(&payload_json);
+            }
+            ArchiveKind::BookDeltas => {
+                for (level_side, price, qty) in book_levels(&env.payload) {
+                    meta.push(env);
+                    side.append_value(level_side);
+                    nums[0].append_value(price);
+                    nums[1].append_value(qty);
+                }
+            }
+            ArchiveKind::Raw => {
+                meta.push(env);
+                payload.append_value(&payload_json);
+            }
+        }
+    }
+
+    let mut columns = meta.finish();
+    let mut num = |i: usize| -> ArrayRef { Arc::new(nums[i].finish()) };
+    match kind {
+        ArchiveKind::Trades => {
+            columns.push(Arc::new(text.finish()));
+            columns.push(num(0));
+            columns.push(num(1));
+            columns.push(Arc::new(side.finish()));
+        }
+        ArchiveKind::Tickers => columns.extend((0..6).map(&mut num)),
+        ArchiveKind::BookDeltas => {
+            columns.push(Arc::new(side.finish()));
+            columns.push(num(0));
+            columns.push(num(1));
+        }
+        ArchiveKind::Raw => {}
+    }
+    if kind != ArchiveKind::BookDeltas {
+        columns.push(Arc::new(payload.finish()));
+    }
+    RecordBatch::try_new(kind.schema(), columns).map_err(|e| e.to_string())
+}
+
+// ---------------------------------------------------------------------------
+// Partitioning
+// ---------------------------------------------------------------------------
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct PartitionKey {
+    pub exchange: String,
+    pub channel: String,
+    /// UTC date, `YYYY-MM-DD`.
+    pub date: String,
+    /// UTC hour, 0–23.
+    pub hour: u8,
+}
+
+/// Days since 1970-01-01 → (year, month, day), proleptic Gregorian.
+fn civil_from_days(days: i64) -> (i64, u32, u32) {
+    let z = days + 719_468;
+    let era = z.div_euclid(146_097);
+    let doe = z.rem_euclid(146_097);
+    let yoe = (doe - doe / 1_460 + doe / 36_524 - doe / 146_096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = (doy - (153 * mp + 2) / 5 + 1) as u32;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 } as u32;
+    let year = yoe + era * 400 + i64::from(month <= 2);
+    (year, month, day)
+}
+
+/// Keeps partition path segments filesystem-safe (`BTC/USDT`-style values
+/// never reach this, but exchange/channel come from descriptors).
+fn path_segment(s: &str) -> String {
+    s.chars()
+        .map(|c| {
+            if c.is_ascii_alphanumeric() || c == '-' || c == '_' || c == '.' {
+                c
+            } else {
+                '_'
+            }
+        })
+        .collect()
+}
+
+impl PartitionKey {
+    pub fn for_envelope(env: &Envelope) -> Self {
+        const DAY_MS: i64 = 86_400_000;
+        let (y, m, d) = civil_from_days(env.received_at_ms.div_euclid(DAY_MS));
+        Self {
+            exchange: env.exchange.clone(),
+            channel: env.channel.clone(),
+            date: format!("{y:04}-{m:02}-{d:02}"),
+            hour: (env.received_at_ms.rem_euclid(DAY_MS) / HOUR_MS) as u8,
+        }
+    }
+
+    /// End (exclusive, epoch ms) of the UTC hour `received_at_ms` falls in.
+    fn hour_end_ms(received_at_ms: i64) -> i64 {
+        received_at_ms - received_at_ms.rem_euclid(HOUR_MS) + HOUR_MS
+    }
+
+    /// Inverse of [`PartitionKey::dir`] for a directory under `root`.
+    fn from_dir(root: &Path, dir: &Path) -> Option<Self> {
+        let mut parts = dir.strip_prefix(root).ok()?.iter();
+        let mut segment = |prefix: &str| {
+            parts
+                .next()?
+                .to_str()?
+                .strip_prefix(prefix)
+                .map(str::to_string)
+        };
+        Some(Self {
+            exchange: segment("exchange=")?,
+            channel: segment("channel=")?,
+            date: segment("date=")?,
+            hour: segment("hour=")?.parse().ok()?,
+        })
+    }
+
+    pub fn dir(&self, root: &Path) -> PathBuf {
+        root.join(format!("exchange={}", path_segment(&self.exchange)))
+            .join(format!("channel={}", path_segment(&self.channel)))
+            .join(format!("date={}", self.date))
+            .join(format!("hour={:02}", self.hour))
+    }
+}
+
+/// Every `exchange=/channel=/date=/hour=` directory under `root`.
+fn partition_dirs(root: &Path) -> std::io::Result<Vec<PathBuf>> {
+    let mut dirs = vec![root.to_path_buf()];
+    for _ in 0..4 {
+        let mut next = Vec::new();
+        for dir in dirs {
+            let entries = match std::fs::read_dir(&dir) {
+                Ok(entries) => entries,
+                Err(e) if e.kind() == std::io::ErrorKind::NotFound => continue,
+                Err(e) => return Err(e),
+            };
+            for entry in entries {
+                let entry = entry?;
+                if entry.file_type()?.is_dir() {
+                    next.push(entry.path());
+                }
+            }
+        }
+        dirs = next;
+    }
+    dirs.sort();
+    Ok(dirs)
+}
+
+// ---------------------------------------------------------------------------
+// Manifest
+// ---------------------------------------------------------------------------
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ManifestEntry {
+    pub file: String,
+    pub rows: u64,
+    pub bytes: u64,
+    pub min_received_at_ms: i64,
+    pub max_received_at_ms: i64,
+    pub closed_at_ms: i64,
+}
+
+/// Per-partition `_manifest.json`, listing closed files only.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PartitionManifest {
+    pub exchange: String,
+    pub channel: String,
+    pub date: String,
+    pub hour: u8,
+    pub kind: ArchiveKind,
+    pub files: Vec<ManifestEntry>,
+}
+
+impl PartitionManifest {
+    pub fn load(dir: &Path) -> std::io::Result<Option<Self>> {
+        match std::fs::read(dir.join(MANIFEST_FILE)) {
+            Ok(bytes) => serde_json::from_slice(&bytes)
+                .map(Some)
+                .map_err(|e| std::io::Error::new(std::io::ErrorKind::InvalidData, e)),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(None),
+            Err(e) => Err(e),
+        }
+    }
+
+    /// Write-then-rename so readers never observe a half-written manifest.
+    fn store(&self, dir: &Path) -> std::io::Result<()> {
+        let tmp = dir.join(format!("{MANIFEST_FILE}.tmp"));
+        let bytes = serde_json::to_vec_pretty(self)
+            .map_err(|e| std::io::Error::new(std::io::ErrorKind::InvalidData, e))?;
+        std::fs::write(&tmp, bytes)?;
+        std::fs::rename(tmp, dir.join(MANIFEST_FILE))
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Writer state
+// ---------------------------------------------------------------------------
+
+struct OpenFile {
+    name: String,
+    writer: ArrowWriter<File>,
+    /// One JSON line per group written to `writer`, fsynced before the ack.
+    journal: File,
+    rows: u64,
+    opened_at_ms: i64,
+    min_received_at_ms: i64,
+    max_received_at_ms: i64,
+}
+
+struct PartitionWriter {
+    dir: PathBuf,
+    manifest: PartitionManifest,
+    next_part: u32,
+    open: Option<OpenFile>,
+    hour_end_ms: i64,
+    recent: VecDeque<[u8; 32]>,
+    /// Journals of abandoned parts whose groups still have to be rewritten.
+    pending_journals: Vec<PathBuf>,
+}
+
+fn now_ms() -> i64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_millis() as i64)
+        .unwrap_or(0)
+}
+
+fn part_number(name: &str) -> Option<u32> {
+    name.strip_prefix("part-")?.split('.').next()?.parse().ok()
+}
+
+/// Identifies one partition's share of a batch, so a retried batch can skip
+/// the groups that already landed.
+fn group_fingerprint(envelopes: &[Envelope]) -> [u8; 32] {
+    let mut hasher = Sha256::new();
+    for env in envelopes {
+        hasher.update(env.dedup_key().as_bytes());
+        hasher.update(env.received_at_ms.to_be_bytes());
+    }
+    hasher.finalize().into()
+}
+
+/// Reads the groups of a journal.  A torn last line (crash mid-append) was
+/// never acknowledged and is skipped.
+fn read_journal(path: &Path) -> std::io::Result<Vec<Vec<Envelope>>> {
+    let mut groups = Vec::new();
+    for line in BufReader::new(File::open(path)?).lines() {
+        let line = line?;
+        match serde_json::from_str::<Vec<Envelope>>(&line) {
+            Ok(group) if !group.is_empty() => groups.push(group),
+            Ok(_) => {}
+            Err(e) => {
+                tracing::warn!(file = %path.display(), %e, "skipping torn parquet journal line")
+            }
+        }
+    }
+    Ok(groups)
+}
+
+impl PartitionWriter {
+    /// Opens a partition directory, quarantining footer-less leftovers from a
+    /// previous run and continuing the part numbering after them.  Their
+    /// journals are replayed by [`PartitionWriter::recover`].
+    fn open(
+        root: &Path,
+        key: &PartitionKey,
+        kind: ArchiveKind,
+        hour_end_ms: i64,
+    ) -> std::io::Result<Self> {
+        let dir = key.dir(root);
+        std::fs::create_dir_all(&dir)?;
+        let mut names = Vec::new();
+        for entry in std::fs::read_dir(&dir)? {
+            names.push(entry?.file_name().to_string_lossy().into_owned());
+        }
+        names.sort();
+        let mut next_part = 1;
+        let mut pending_journals = Vec::new();
+        for name in names {
+            if let Some(n) = part_number(&name) {
+                next_part = next_part.max(n + 1);
+            }
+            if name.ends_with(INPROGRESS_SUFFIX) {
+                let orphan = name.trim_end_matches(INPROGRESS_SUFFIX).to_string() + ".orphaned";
+                tracing::warn!(file = %dir.join(&name).display(), "orphaned parquet file from previous run");
+                std::fs::rename(dir.join(&name), dir.join(orphan))?;
+            } else if let Some(part) = name.strip_suffix(JOURNAL_SUFFIX) {
+                if dir.join(part).exists() {
+                    // Closed and renamed, but the crash came before the journal was removed.
+                    std::fs::remove_file(dir.join(&name))?;
+                } else {
+                    pending_journals.push(dir.join(&name));
+                }
+            }
+        }
+        let manifest = PartitionManifest::load(&dir)?.unwrap_or_else(|| PartitionManifest {
+            exchange: key.exchange.clone(),
+            channel: key.channel.clone(),
+            date: key.date.clone(),
+            hour: key.hour,
+            kind,
+            files: Vec::new(),
+        });
+        Ok(Self {
+            dir,
+            manifest,
+            next_part,
+            open: None,
+            hour_end_ms,
+            recent: VecDeque::new(),
+            pending_journals,
+        })
+    }
+
+    fn has_written(&self, fingerprint: &[u8; 32]) -> bool {
+        self.recent.contains(fingerprint)
+    }
+
+    fn remember(&mut self, fingerprint: [u8; 32]) {
+        if self.recent.len() == RECENT_GROUPS {
+            self.recent.pop_front();
+        }
+        self.recent.push_back(fingerprint);
+    }
+
+    /// Rewrites the groups of abandoned journals into the current part, then
+    /// deletes those journals.  Returns the number of rows recovered.
+    fn recover(&mut self, cfg: &ParquetArchiveConfig, now: i64) -> Result<u64, String> {
+        if self.pending_journals.is_empty() {
+            return Ok(0);
+        }
+        let mut rows = 0;
+        for path in self.pending_journals.clone() {
+            let groups =
+                read_journal(&path).map_err(|e| format!("read {}: {e}", path.display()))?;
+            for group in groups {
+                let fingerprint = group_fingerprint(&group);
+                if self.has_written(&fingerprint) {
+                    continue;
+                }
+                let batch = build_batch(self.manifest.kind, &group)?;
+                if batch.num_rows() > 0 {
+                    self.write(&batch, &group, cfg, now)?;
+                    rows += batch.num_rows() as u64;
+                }
+                self.remember(fingerprint);
+            }
+            std::fs::remove_file(&path).map_err(|e| format!("remove {}: {e}", path.display()))?;
+            self.pending_journals.retain(|p| p != &path);
+        }
+        Ok(rows)
+    }
+
+    fn start_file(
+        &mut self,
+        now: i64,
+        cfg: &ParquetArchiveConfig,
+    ) -> Result<&mut OpenFile, String> {
+        let name = format!("part-{:06}.parquet", self.next_part);
+        self.next_part += 1;
+        let path = self.dir.join(format!("{name}{INPROGRESS_SUFFIX}"));
+        let journal_path = self.dir.join(format!("{name}{JOURNAL_SUFFIX}"));
+        let journal = OpenOptions::new()
+            .create_new(true)
+            .append(true)
+            .open(&journal_path)
+            .map_err(|e| format!("create {}: {e}", journal_path.display()))?;
+        let file = File::create(&path).map_err(|e| format!("create {}: {e}", path.display()))?;
+        let props = WriterProperties::builder()
+            .set_compression(Compression::SNAPPY)
+            .set_max_row_group_size(cfg.row_group_rows.max(1))
+            .build();
+        let writer = ArrowWriter::try_new(file, self.manifest.kind.schema(), Some(props))
+            .map_err(|e| e.to_string())?;
+        Ok(self.open.insert(OpenFile {
+            name,
+            writer,
+            journal,
+            rows: 0,
+            opened_at_ms: now,
+            min_received_at_ms: i64::MAX,
+            max_received_at_ms: i64::MIN,
+        }))
+    }
+
+    /// Writes one group; once this returns `Ok` the group is durable in the
+    /// part's journal even though the part itself has no footer yet.
+    fn write(
+        &mut self,
+        batch: &RecordBatch,
+        envelopes: &[Envelope],
+        cfg: &ParquetArchiveConfig,
+        now: i64,
+    ) -> Result<bool, String> {
+        let mut rolled = self.roll_if_expired(cfg, now)?;
+        let file = match self.open.as_mut() {
+            Some(f) => f,
+            None => self.start_file(now, cfg)?,
+        };
+        // The writer itself cuts a row group every `row_group_rows`; cut one
+        // early when the buffered rows grow past `row_group_bytes`.
+        file.writer.write(batch).map_err(|e| e.to_string())?;
+        if file.writer.in_progress_size() as u64 >= cfg.row_group_bytes {
+            file.writer.flush().map_err(|e| e.to_string())?;
+        }
+        let mut line = serde_json::to_vec(envelopes).map_err(|e| e.to_string())?;
+        line.push(b'\n');
+        file.journal
+            .write_all(&line)
+            .and_then(|()| file.journal.sync_data())
+            .map_err(|e| format!("journal {}: {e}", file.name))?;
+        file.rows += batch.num_rows() as u64;
+        for env in envelopes {
+            file.min_received_at_ms = file.min_received_at_ms.min(env.received_at_ms);
+            file.max_received_at_ms = file.max_received_at_ms.max(env.received_at_ms);
+        }
+        let size = file.writer.bytes_written() + file.writer.in_progress_size();
+        if size as u64 >= cfg.max_file_bytes {
+            self.close_open(now)?;
+            rolled = true;
+        }
+        Ok(rolled)
+    }
+
+    /// Abandons the open file after a failed write: whatever it held is
+    /// renamed to `.orphaned`, exactly as a crash leftover would be, and its
+    /// journal is queued for [`PartitionWriter::recover`].
+    fn quarantine_open(&mut self) {
+        let Some(file) = self.open.take() else {
+            return;
+        };
+        let tmp = self.dir.join(format!("{}{INPROGRESS_SUFFIX}", file.name));
+        let orphan = self.dir.join(format!("{}.orphaned", file.name));
+        self.pending_journals
+            .push(self.dir.join(format!("{}{JOURNAL_SUFFIX}", file.name)));
+        drop(file);
+        if let Err(e) = std::fs::rename(&tmp, &orphan) {
+            tracing::warn!(file = %tmp.display(), %e, "failed to quarantine parquet file");
+        }
+    }
+
+    fn roll_if_expired(&mut self, cfg: &ParquetArchiveConfig, now: i64) -> Result<bool, String> {
+        let expired = self
+            .open
+            .as_ref()
+            .is_some_and(|f| now.saturating_sub(f.opened_at_ms) >= cfg.max_file_age_ms as i64);
+        if expired {
+            self.close_open(now)?;
+        }
+        Ok(expired)
+    }
+
+    /// Writes the footer, drops the `.inprogress` suffix, records the file
+    /// in the manifest and deletes its journal.  Returns whether a file was
+    /// closed.
+    fn close_open(&mut self, now: i64) -> Result<bool, String> {
+        let Some(file) = self.open.take() else {
+            return Ok(false);
+        };
+        let OpenFile {
+            name,
+            writer,
+            journal,
+            rows,
+            min_received_at_ms,
+            max_received_at_ms,
+            ..
+        } = file;
+        drop(journal);
+        let journal_path = self.dir.join(format!("{name}{JOURNAL_SUFFIX}"));
+        let tmp = self.dir.join(format!("{name}{INPROGRESS_SUFFIX}"));
+        if let Err(e) = writer.close() {
+            // The journal still holds every row; replay it into a fresh part.
+            self.pending_journals.push(journal_path);
+            let _ = std::fs::rename(&tmp, self.dir.join(format!("{name}.orphaned")));
+            return Err(e.to_string());
+        }
+        let dst = self.dir.join(&name);
+        std::fs::rename(&tmp, &dst).map_err(|e| format!("rename {}: {e}", tmp.display()))?;
+        let bytes = std::fs::metadata(&dst).map(|m| m.len()).unwrap_or(0);
+        self.manifest.files.push(ManifestEntry {
+            file: name,
+            rows,
+            bytes,
+            min_received_at_ms,
+            max_received_at_ms,
+            closed_at_ms: now,
+        });
+        self.manifest
+            .store(&self.dir)
+            .map_err(|e| format!("manifest {}: {e}", self.dir.display()))?;
+        if let Err(e) = std::fs::remove_file(&journal_path) {
+            tracing::warn!(file = %journal_path.display(), %e, "failed to remove parquet journal");
+        }
+        Ok(true)
+    }
+}
+
+// ---------------------------------------------------------------------------
+// ParquetArchive
+// ---------------------------------------------------------------------------
+
+/// Partitioned, rolling Parquet writer usable as the pipeline's primary target.
+pub struct ParquetArchive {
+    config: ParquetArchiveConfig,
+    partitions: Arc<Mutex<HashMap<PartitionKey, PartitionWriter>>>,
+    metrics: Arc<PersistenceMetrics>,
+}
+
+impl ParquetArchive {
+    pub fn new(config: ParquetArchiveConfig, metrics: Arc<PersistenceMetrics>) -> Arc<Self> {
+        Arc::new(Self {
+            config,
+            partitions: Arc::new(Mutex::new(HashMap::new())),
+            metrics,
+        })
+    }
+
+    pub fn root(&self) -> &Path {
+        &self.config.root
+    }
+
+    fn write_sync(&self, envelopes: &[Envelope], now: i64) -> Result<(), String> {
+        let mut groups: HashMap<PartitionKey, Vec<Envelope>> = HashMap::new();
+        for env in envelopes {
+            groups
+                .entry(PartitionKey::for_envelope(env))
+                .or_default()
+                .push(env.clone());
+        }
+
+        let mut partitions = self
+            .partitions
+            .lock()
+            .map_err(|_| "parquet archive lock poisoned".to_string())?;
+        // Partitions this batch does not touch and whose hour is over will not
+        // see live data again; close them now rather than at shutdown.
+        self.close_finished(&mut partitions, now, |key| !groups.contains_key(key))?;
+
+        for (key, group) in groups {
+            let kind = ArchiveKind::for_channel(&key.channel);
+            let writer = match partitions.get_mut(&key) {
+                Some(w) => w,
+                None => {
+                    let hour_end_ms = PartitionKey::hour_end_ms(group[0].received_at_ms);
+                    let w = PartitionWriter::open(&self.config.root, &key, kind, hour_end_ms)
+                        .map_err(|e| {
+                            format!(
+                                "open partition {}: {e}",
+                                key.dir(&self.config.root).display()
+                            )
+                        })?;
+                    partitions.entry(key.clone()).or_insert(w)
+                }
+            };
+            if let Err(e) = writer.recover(&self.config, now) {
+                writer.quarantine_open();
+                return Err(e);
+            }
+            let fingerprint = group_fingerprint(&group);
+            if writer.has_written(&fingerprint) {
+                tracing::debug!(partition = %key.dir(&self.config.root).display(), "skipping group already archived");
+                continue;
+            }
+            let batch = build_batch(kind, &group)?;
+            if batch.num_rows() == 0 {
+                continue;
+            }
+            match writer.write(&batch, &group, &self.config, now) {
+                Ok(rolled) => {
+                    if rolled {
+                        self.metrics.increment_archive_files();
+                    }
+                }
+                Err(e) => {
+                    writer.quarantine_open();
+                    return Err(e);
+                }
+            }
+            writer.remember(fingerprint);
+            self.metrics
+                .add_archive_rows(&key.exchange, &key.channel, batch.num_rows() as u64);
+        }
+        Ok(())
+    }
+
+    /// Closes and forgets every partition whose UTC hour ended before `now`
+    /// and that `eligible` accepts.  Returns the number of files closed.
+    fn close_finished(
+        &self,
+        partitions: &mut HashMap<PartitionKey, PartitionWriter>,
+        now: i64,
+        eligible: impl Fn(&PartitionKey) -> bool,
+    ) -> Result<usize, String> {
+        let finished: Vec<PartitionKey> = partitions
+            .iter()
+            .filter(|(key, w)| w.hour_end_ms <= now && eligible(key))
+            .map(|(key, _)| key.clone())
+            .collect();
+        let mut closed = 0;
+        for key in finished {
+            if let Some(mut writer) = partitions.remove(&key) {
+                if writer.close_open(now)? {
+                    closed += 1;
+                    self.metrics.increment_archive_files();
+                }
+            }
+        }
+        Ok(closed)
+    }
+
+    fn for_each_partition(
+        &self,
+        mut f: impl FnMut(&mut PartitionWriter) -> Result<bool, String>,
+    ) -> Result<usize, String> {
+        let mut partitions = self
+            .partitions
+            .lock()
+            .map_err(|_| "parquet archive lock poisoned".to_string())?;
+        let mut closed = 0;
+        for writer in partitions.values_mut() {
+            if f(writer)? {
+                closed += 1;
+                self.metrics.increment_archive_files();
+            }
+        }
+        Ok(closed)
+    }
+
+    /// Close files that have been open longer than `max_file_age_ms`, and
+    /// close and drop partitions whose UTC hour has ended.
+    /// Returns the number of files closed.
+    pub fn roll_expired(&self) -> Result<usize, String> {
+        let now = now_ms();
+        let finished = {
+            let mut partitions = self
+                .partitions
+                .lock()
+                .map_err(|_| "parquet archive lock poisoned".to_string())?;
+            self.close_finished(&mut partitions, now, |_| true)?
+        };
+        Ok(finished + self.for_each_partition(|w| w.roll_if_expired(&self.config, now))?)
+    }
+
+    /// Replays the journals a previous run left behind (crash or failed
+    /// write) into fresh, closed parts.  Call once on start, before any write.
+    /// Returns the number of files closed.
+    pub fn recover_leftovers(&self) -> Result<usize, String> {
+        let now = now_ms();
+        let mut closed = 0;
+        for dir in partition_dirs(&self.config.root)
+            .map_err(|e| format!("scan {}: {e}", self.config.root.display()))?
+        {
+            let Some(key) = PartitionKey::from_dir(&self.config.root, &dir) else {
+                continue;
+            };
+            let kind = ArchiveKind::for_channel(&key.channel);
+            let mut writer = PartitionWriter::open(&self.config.root, &key, kind, 0)
+                .map_err(|e| format!("open partition {}: {e}", dir.display()))?;
+            if writer.pending_journals.is_empty() {
+                continue;
+            }
+            let rows = writer.recover(&self.config, now)?;
+            tracing::info!(partition = %dir.display(), rows, "recovered parquet journal");
+            if writer.close_open(now)? {
+                closed += 1;
+                self.metrics.increment_archive_files();
+            }
+        }
+        Ok(closed)
+    }
+
+    /// Close every open file (call on shutdown).
+    pub fn close_all(&self) -> Result<usize, String> {
+        let now = now_ms();
+        self.for_each_partition(|w| w.close_open(now))
+    }
+}
+
+#[async_trait]
+impl MongoTarget for ParquetArchive {
+    async fn insert_many_envelopes(&self, envelopes: &[Envelope]) -> Result<(), String> {
+        if envelopes.is_empty() {
+            return Ok(());
+        }
+        let this = Self {
+            config: self.config.clone(),
+            partitions: self.partitions.clone(),
+            metrics: self.metrics.clone(),
+        };
+        let envelopes = envelopes.to_vec();
+        tokio::task::spawn_blocking(move || this.write_sync(&envelopes, now_ms()))
+            .await
+            .map_err(|e| format!("parquet writer task failed: {e}"))?
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Tests
+// ---------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::persistence::dedup::DedupConfig;
+    use crate::persistence::mongo::MongoSinkConfig;
+    use crate::persistence::pipeline::{PipelineConfig, PipelineSink};
+    use crate::persistence::replay::{ReplayConfig, ReplayWorker};
+    use crate::persistence::sink::Sink;
+    use crate::persistence::spool::{DurableSpool, OnFullPolicy, SpoolConfig};
+    use arrow_array::{Array, StringArray};
+    use parquet::arrow::arrow_reader::ParquetRecordBatchReaderBuilder;
+    use serde_json::json;
+    use tempfile::TempDir;
+
+    // 2024-03-09T13:20:00Z
+    const T0: i64 = 1_709_990_400_000;
+
+    fn env(channel: &str, received_at_ms: i64, payload: Value) -> Envelope {
+        Envelope {
+            message_id: None,
+            sequence: Some(received_at_ms as u64),
+            exchange: "binance".to_string(),
+            channel: channel.to_string(),
+            symbol: "BTC/USDT".to_string(),
+            server_time_ms: Some(received_at_ms - 5),
+            received_at_ms,
+            payload,
+        }
+    }
+
+    fn read_all(path: &Path) -> Vec<RecordBatch> {
+        let file = File::open(path).unwrap();
+        ParquetRecordBatchReaderBuilder::try_new(file)
+            .unwrap()
+            .build()
+            .unwrap()
+            .map(|b| b.unwrap())
+            .collect()
+    }
+
+    fn col<'a, T: 'static>(batch: &'a RecordBatch, name: &str) -> &'a T {
+        batch
+            .column_by_name(name)
+            .unwrap()
+            .as_any()
+            .downcast_ref::<T>()
+            .unwrap()
+    }
+
+    fn partition_dir(root: &Path, channel: &str, hour: &str) -> PathBuf {
+        root.join("exchange=binance")
+            .join(format!("channel={channel}"))
+            .join("date=2024-03-09")
+            .join(format!("hour={hour}"))
+    }
+
+    #[test]
+    fn partition_key_uses_utc_date_and_hour() {
+        let k = PartitionKey::for_envelope(&env("trades", T0, json!({})));
+        assert_eq!(k.date, "2024-03-09");
+        assert_eq!(k.hour, 13);
+        let k = PartitionKey::for_envelope(&env("trades", 951_782_400_000, json!({}))); // 2000-02-29
+        assert_eq!(k.date, "2000-02-29");
+        assert_eq!(k.hour, 0);
+    }
+
+    #[test]
+    fn channel_selects_schema() {
+        assert_eq!(ArchiveKind::for_channel("trades"), ArchiveKind::Trades);
+        assert_eq!(ArchiveKind::for_channel("publicTrade"), ArchiveKind::Trades);
+        assert_eq!(ArchiveKind::for_channel("ticker"), ArchiveKind::Tickers);
+        assert_eq!(
+            ArchiveKind::for_channel("orderbook"),
+            ArchiveKind::BookDeltas
+        );
+        assert_eq!(ArchiveKind::for_channel("depth"), ArchiveKind::BookDeltas);
+        assert_eq!(ArchiveKind::for_channel("funding"), ArchiveKind::Raw);
+    }
+
+    #[tokio::test]
+    async fn writes_typed_columns_and_manifest_on_close() {
+        let tmp = TempDir::new().unwrap();
+        let metrics = PersistenceMetrics::new();
+        let archive = ParquetArchive::new(ParquetArchiveConfig::new(tmp.path()), metrics.clone());
+
+        archive
+            .insert_many_envelopes(&[
+                env("trades", T0, json!([{"t": 1, "p": "50000.5", "q": "0.25", "side": "buy"}, {"t": 2, "p": 50001, "q": 1, "side": "sell"}])),
+                env("ticker", T0, json!({"data": {"bid": "49999", "ask": "50001", "last": 50000, "volume": "12.5"}})),
+                env("orderbook", T0, json!({"bids": [["49999", "1.5"]], "asks": [{"price": "50001", "size": "0.5"}, ["50002", "0"]]})),
+            ])
+            .await
+            .unwrap();
+        assert_eq!(archive.close_all().unwrap(), 3);
+        assert_eq!(metrics.archive_rows_total.get("binance", "trades"), 2);
+        assert_eq!(metrics.archive_rows_total.get("binance", "orderbook"), 3);
+        assert_eq!(metrics.archive_files_total(), 3);
+
+        let trades_dir = partition_dir(tmp.path(), "trades", "13");
+        let batches = read_all(&trades_dir.join("part-000001.parquet"));
+        assert_eq!(batches[0].num_rows(), 2);
+        let price = col::<StringArray>(&batches[0], "price");
+        assert_eq!(price.value(0), "50000.5");
+        assert_eq!(price.value(1), "50001");
+        assert_eq!(col::<StringArray>(&batches[0], "trade_id").value(1), "2");
+        assert_eq!(col::<StringArray>(&batches[0], "side").value(0), "buy");
+
+        let ticker =
+            read_all(&partition_dir(tmp.path(), "ticker", "13").join("part-000001.parquet"));
+        assert_eq!(col::<StringArray>(&ticker[0], "ask").value(0), "50001");
+        assert_eq!(col::<StringArray>(&ticker[0], "volume").value(0), "12.5");
+        assert!(col::<StringArray>(&ticker[0], "bid_qty").is_null(0));
+
+        let book =
+            read_all(&partition_dir(tmp.path(), "orderbook", "13").join("part-000001.parquet"));
+        assert_eq!(book[0].num_rows(), 3);
+        assert_eq!(col::<StringArray>(&book[0], "side").value(1), "ask");
+        assert_eq!(col::<StringArray>(&book[0], "qty").value(2), "0");
+        assert!(book[0].column_by_name("payload").is_none());
+
+        let manifest = PartitionManifest::load(&trades_dir).unwrap().unwrap();
+        assert_eq!(manifest.kind, ArchiveKind::Trades);
+        assert_eq!(manifest.files.len(), 1);
+        assert_eq!(manifest.files[0].rows, 2);
+        assert_eq!(manifest.files[0].min_received_at_ms, T0);
+        assert!(manifest.files[0].bytes > 0);
+    }
+
+    #[tokio::test]
+    async fn rolls_on_size_and_age_and_splits_hours() {
+        let tmp = TempDir::new().unwrap();
+        let metrics = PersistenceMetrics::new();
+        let mut cfg = ParquetArchiveConfig::new(tmp.path());
+        cfg.max_file_bytes = 1; // every flushed batch fills a file
+        let archive = ParquetArchive::new(cfg, metrics.clone());
+
+        for i in 0..3 {
+            archive
+                .insert_many_envelopes(&[env("trades", T0 + i, json!({"p": 1, "q": 1}))])
+                .await
+                .unwrap();
+        }
+        // A new hour lands in its own partition.
+        archive
+            .insert_many_envelopes(&[env("trades", T0 + 3_600_000, json!({"p": 1, "q": 1}))])
+            .await
+            .unwrap();
+
+        let h13 = PartitionManifest::load(&partition_dir(tmp.path(), "trades", "13"))
+            .unwrap()
+            .unwrap();
+        assert_eq!(
+            h13.files
+                .iter()
+                .map(|f| f.file.as_str())
+                .collect::<Vec<_>>(),
+            [
+                "part-000001.parquet",
+                "part-000002.parquet",
+                "part-000003.parquet"
+            ]
+        );
+        assert!(partition_dir(tmp.path(), "trades", "14")
+            .join("part-000001.parquet")
+            .exists());
+        assert_eq!(archive.close_all().unwrap(), 0);
+
+        let tmp = TempDir::new().unwrap();
+        let mut cfg = ParquetArchiveConfig::new(tmp.path());
+        cfg.max_file_age_ms = 0;
+        let archive = ParquetArchive::new(cfg, metrics.clone());
+        archive
+            .insert_many_envelopes(&[env("funding", T0, json!({"rate": "0.0001"}))])
+            .await
+            .unwrap();
+        assert!(partition_dir(tmp.path(), "funding", "13")
+            .join("part-000001.parquet.inprogress")
+            .exists());
+        assert_eq!(archive.roll_expired().unwrap(), 1);
+        let raw = read_all(&partition_dir(tmp.path(), "funding", "13").join("part-000001.parquet"));
+        assert_eq!(
+            col::<StringArray>(&raw[0], "payload").value(0),
+            r#"{"rate":"0.0001"}"#
+        );
+    }
+
+    #[tokio::test]
+    async fn partitions_close_when_their_hour_ends() {
+        let tmp = TempDir::new().unwrap();
+        let metrics = PersistenceMetrics::new();
+        let archive = ParquetArchive::new(ParquetArchiveConfig::new(tmp.path()), metrics.clone());
+
+        for i in 0..3 {
+            archive
+                .insert_many_envelopes(&[env("trades", T0 + i, json!({"p": 1, "q": 1}))])
+                .await
+                .unwrap();
+        }
+        let h13 = partition_dir(tmp.path(), "trades", "13");
+        assert!(h13.join("part-000001.parquet.inprogress").exists());
+
+        // The next hour's first batch closes hour 13 without waiting for shutdown.
+        archive
+            .insert_many_envelopes(&[env("trades", T0 + 3_600_000, json!({"p": 1, "q": 1}))])
+            .await
+            .unwrap();
+        assert!(h13.join("part-000001.parquet").exists());
+        assert!(!h13.join("part-000001.parquet.inprogress").exists());
+
+        // Buffered rows land as one row group, not one per batch.
+        let file = File::open(h13.join("part-000001.parquet")).unwrap();
+        let reader = ParquetRecordBatchReaderBuilder::try_new(file).unwrap();
+        assert_eq!(reader.metadata().num_row_groups(), 1);
+        assert_eq!(reader.metadata().file_metadata().num_rows(), 3);
+
+        // Hour 14 is also over by wall clock: the periodic roll closes and drops it.
+        assert_eq!(archive.roll_expired().unwrap(), 1);
+        assert!(partition_dir(tmp.path(), "trades", "14")
+            .join("part-000001.parquet")
+            .exists());
+        assert!(archive.partitions.lock().unwrap().is_empty());
+        assert_eq!(metrics.archive_files_total(), 2);
+    }
+
+    #[tokio::test]
+    async fn retried_batch_is_not_archived_twice() {
+        let tmp = TempDir::new().unwrap();
+        let metrics = PersistenceMetrics::new();
+        let archive = ParquetArchive::new(ParquetArchiveConfig::new(tmp.path()), metrics.clone());
+        // A plain file where the ticker channel directory should be makes
+        // only that partition fail.
+        let blocker = tmp.path().join("exchange=binance").join("channel=ticker");
+        std::fs::create_dir_all(blocker.parent().unwrap()).unwrap();
+        std::fs::write(&blocker, b"not a directory").unwrap();
+
+        let batch = [
+            env("trades", T0, json!({"t": 1, "p": 1, "q": 1})),
+            env("ticker", T0, json!({"bid": 1, "ask": 2})),
+        ];
+        assert!(archive.insert_many_envelopes(&batch).await.is_err());
+
+        std::fs::remove_file(&blocker).unwrap();
+        archive.insert_many_envelopes(&batch).await.unwrap();
+        archive.close_all().unwrap();
+
+        assert_eq!(metrics.archive_rows_total.get("binance", "trades"), 1);
+        assert_eq!(metrics.archive_rows_total.get("binance", "ticker"), 1);
+        let trades =
+            read_all(&partition_dir(tmp.path(), "trades", "13").join("part-000001.parquet"));
+        assert_eq!(trades.iter().map(|b| b.num_rows()).sum::<usize>(), 1);
+    }
+
+    #[tokio::test]
+    async fn leftover_inprogress_files_are_quarantined() {
+        let tmp = TempDir::new().unwrap();
+        let dir = partition_dir(tmp.path(), "trades", "13");
+        std::fs::create_dir_all(&dir).unwrap();
+        std::fs::write(
+            dir.join("part-000004.parquet.inprogress"),
+            b"PAR1 no footer",
+        )
+        .unwrap();
+
+        let archive = ParquetArchive::new(
+            ParquetArchiveConfig::new(tmp.path()),
+            PersistenceMetrics::new(),
+        );
+        archive
+            .insert_many_envelopes(&[env("trades", T0, json!({"p": 1, "q": 1}))])
+            .await
+            .unwrap();
+        archive.close_all().unwrap();
+
+        assert!(dir.join("part-000004.parquet.orphaned").exists());
+        assert!(dir.join("part-000005.parquet").exists());
+    }
+
+    #[tokio::test]
+    async fn acked_rows_survive_a_crash_before_close() {
+        let tmp = TempDir::new().unwrap();
+        let dir = partition_dir(tmp.path(), "trades", "13");
+        {
+            let archive = ParquetArchive::new(
+                ParquetArchiveConfig::new(tmp.path()),
+                PersistenceMetrics::new(),
+            );
+            archive
+                .insert_many_envelopes(&[env("trades", T0, json!({"t": 1, "p": "1.10", "q": 1}))])
+                .await
+                .unwrap();
+            archive
+                .insert_many_envelopes(&[env(
+                    "trades",
+                    T0 + 1,
+                    json!({"t": 2, "p": "1.20", "q": 2}),
+                )])
+                .await
+                .unwrap();
+            // Crash: the archive goes away without close_all.
+        }
+        assert!(dir.join("part-000001.parquet.inprogress").exists());
+        assert!(dir.join("part-000001.parquet.wal").exists());
+
+        let archive = ParquetArchive::new(
+            ParquetArchiveConfig::new(tmp.path()),
+            PersistenceMetrics::new(),
+        );
+        assert_eq!(archive.recover_leftovers().unwrap(), 1);
+
+        assert!(dir.join("part-000001.parquet.orphaned").exists());
+        assert!(!dir.join("part-000001.parquet.wal").exists());
+        assert!(!dir.join("part-000002.parquet.wal").exists());
+        let batches = read_all(&dir.join("part-000002.parquet"));
+        assert_eq!(batches.iter().map(|b| b.num_rows()).sum::<usize>(), 2);
+        let price = col::<StringArray>(&batches[0], "price");
+        assert_eq!(price.value(0), "1.10");
+        assert_eq!(price.value(1), "1.20");
+        let manifest = PartitionManifest::load(&dir).unwrap().unwrap();
+        assert_eq!(manifest.files.len(), 1);
+        assert_eq!(manifest.files[0].file, "part-000002.parquet");
+
+        // Nothing left to recover on the next start.
+        assert_eq!(archive.recover_leftovers().unwrap(), 0);
+    }
+
+    #[tokio::test]
+    async fn outage_spills_to_spool_and_replays_into_archive() {
+        let tmp = TempDir::new().unwrap();
+        let root = tmp.path().join("archive");
+        // A plain file where the archive root should be makes every write fail.
+        std::fs::write(&root, b"not a directory").unwrap();
+
+        let metrics = PersistenceMetrics::new();
+        let archive = ParquetArchive::new(ParquetArchiveConfig::new(&root), metrics.clone());
+        let spool_config = SpoolConfig {
+            dir: tmp.path().join("spool"),
+            // One envelope per segment, so earlier writes become replayable.
+            max_segment_bytes: 10,
+            max_total_bytes: 10 * 1024 * 1024,
+            on_full: OnFullPolicy::DropAll,
+        };
+        let config = PipelineConfig {
+            mongo: MongoSinkConfig {
+                max_retries: 0,
+                retry_base_ms: 1,
+                consecutive_failures_for_degraded: 3,
+            },
+            spool: Some(spool_config.clone()),
+            dedup: Some(DedupConfig::default()),
+            replay: ReplayConfig::default(),
+        };
+        let sink = PipelineSink::build(archive.clone(), config, metrics.clone(), None)
+            .await
+            .unwrap();
+
+        for i in 0..3 {
+            sink.write_batch(vec![env("trades", T0 + i, json!({"p": 1, "q": i}))])
+                .await
+                .unwrap();
+        }
+        assert!(metrics.spool_bytes() > 0);
+        assert_eq!(metrics.archive_rows_total.total(), 0);
+
+        // Storage comes back: the replay worker drains complete segments into Parquet.
+        std::fs::remove_file(&root).unwrap();
+        let spool = DurableSpool::open(spool_config, metrics.clone())
+            .await
+            .unwrap();
+        let worker = ReplayWorker::new(
+            spool,
+            archive.clone(),
+            ReplayConfig::default(),
+            metrics.clone(),
+        );
+        while worker.replay_oldest_segment().await.unwrap() {}
+        archive.close_all().unwrap();
+
+        assert_eq!(metrics.spool_replay_total(), 2);
+        assert_eq!(metrics.archive_rows_total.get("binance", "trades"), 2);
+        let batches = read_all(&partition_dir(&root, "trades", "13").join("part-000001.parquet"));
+        let rows: usize = batches.iter().map(|b| b.num_rows()).sum();
+        assert_eq!(rows, 2);
+    }
+}
@@ -6,9 +6,10 @@
 //! write_batch(batch)
 //!   │
 //!   ├─ [dedup enabled] → filter batch → drop duplicates
+//!   │     (also drops keys in the persistent DedupIndex, if any)
 //!   │
 //!   ├─ try MongoSink.write_batch(batch)
-//!   │     └─ Ok  →  return Ok
+//!   │     └─ Ok  →  record keys in DedupIndex  →  return Ok
 //!   │
 //!   └─ Err(MongoUnavailable) and spool enabled
 //!         ├─ DurableSpool.append_batch(batch) → Ok  →  return Ok
@@ -17,6 +18,13 @@
 //!               (Block → waits inside spool until space)
 //! ```
 //!
+//! ## Persistent dedup
+//!
+//! With both dedup and spool enabled, a `DedupIndex` is opened in
+//! `{spool.dir}/dedup/` and shared by the dedup filter and the replay worker,
+//! so delivered keys survive restarts and replayed segments are not written
+//! to the sink twice.
+//!
 //! ## Stability guarantee
 //!
 //! `PipelineSink` implements the `Sink` trait, which is the stable interface
@@ -27,7 +35,7 @@ use std::sync::Arc;
 use async_trait::async_trait;
 use tokio::sync::watch;
 
-use super::dedup::{DedupConfig, DedupWindow};
+use super::dedup::{DedupConfig, DedupIndex, DedupWindow};
 use super::envelope::Envelope;
 use super::metrics::PersistenceMetrics;
 use super::mongo::{MongoSink, MongoSinkConfig, MongoTarget};
@@ -68,6 +76,7 @@ pub struct PipelineSink {
     mongo: Arc<MongoSink>,
     spool: Option<Arc<DurableSpool>>,
     dedup: Option<Arc<DedupWindow>>,
+    dedup_index: Option<Arc<DedupIndex>>,
     metrics: Arc<PersistenceMetrics>,
 }
 
@@ -92,26 +101,43 @@ impl PipelineSink {
             None
         };
 
-        let dedup = config.dedup.clone().map(|dc| DedupWindow::new(dc, metrics.clone()));
+        // Persistent dedup index lives next to the spool segments.
+        let dedup_index = match (&config.dedup, &spool) {
+            (Some(dc), Some(s)) => Some(DedupIndex::open(s.config().dir.join("dedup"), dc.clone())?),
+            _ => None,
+        };
+
+        let dedup = config.dedup.clone().map(|dc| match dedup_index {
+            Some(ref ix) => DedupWindow::with_index(dc, ix.clone(), metrics.clone()),
+            None => DedupWindow::new(dc, metrics.clone()),
+        });
 
         // Spawn replay worker if spool is enabled.
         if let (Some(ref s), Some(rx)) = (&spool, shutdown_rx) {
-            let worker = ReplayWorker::new(
+            let mut worker = ReplayWorker::new(
                 s.clone(),
                 mongo_target,
                 config.replay.clone(),
                 metrics.clone(),
             );
+            if let Some(ref ix) = dedup_index {
+                worker = worker.with_dedup_index(ix.clone());
+            }
             worker.spawn(rx);
         }
 
-        Ok(Self { mongo, spool, dedup, metrics })
+        Ok(Self { mongo, spool, dedup, dedup_index, metrics })
     }
 
     /// Access shared metrics for observability.
     pub fn metrics(&self) -> &Arc<PersistenceMetrics> {
         &self.metrics
     }
+
+    /// The persistent dedup index, when dedup and spool are both enabled.
+    pub fn dedup_index(&self) -> Option<&Arc<DedupIndex>> {
+        self.dedup_index.as_ref()
+    }
 }
 
 #[async_trait]
@@ -134,7 +160,18 @@ impl Sink for PipelineSink {
 
         // Step 2: try Mongo.
         match self.mongo.write_batch(batch.clone()).await {
-            Ok(()) => return Ok(()),
+            Ok(()) => {
+                // The batch is already in Mongo; failing here would make the
+                // caller retry and write it twice.  A missed key only means a
+                // later replay may offer the envelope again.
+                if let Some(ref ix) = self.dedup_index {
+                    if let Err(e) = ix.record(&batch) {
+                        self.metrics.increment_dedup_index_errors();
+                        tracing::warn!(error = %e, "failed to journal delivered dedup keys");
+                    }
+                }
+                return Ok(());
+            }
             Err(SinkError::MongoUnavailable { .. }) => {
                 // fall through to spool
             }
@@ -333,6 +370,77 @@ mod tests {
         assert_eq!(fake.calls.load(Ordering::Relaxed), 1);
     }
 
+    #[tokio::test]
+    async fn dedup_index_keeps_replay_exactly_once_across_restarts() {
+        let tmp = TempDir::new().unwrap();
+        let with_id = |id: &str| {
+            let mut e = make_envelope("binance", "trades");
+            e.message_id = Some(id.to_string());
+            e
+        };
+        let config = PipelineConfig {
+            mongo: MongoSinkConfig { max_retries: 0, retry_base_ms: 1, consecutive_failures_for_degraded: 5 },
+            spool: Some(SpoolConfig {
+                dir: tmp.path().to_path_buf(),
+                // One envelope per segment.
+                max_segment_bytes: 10,
+                max_total_bytes: 10 * 1024 * 1024,
+                on_full: OnFullPolicy::DropAll,
+            }),
+            dedup: Some(DedupConfig::default()),
+            replay: ReplayConfig::default(),
+        };
+
+        // --- Run 1: Mongo down, three envelopes spooled ---
+        let sink = PipelineSink::build(FakeMongoTarget::new(100), config.clone(), PersistenceMetrics::new(), None)
+            .await
+            .unwrap();
+        for id in ["m1", "m2", "m3"] {
+            sink.write_batch(vec![with_id(id)]).await.unwrap();
+        }
+        drop(sink);
+
+        // --- Run 2: Mongo back; the venue re-sends m1 live before replay ---
+        let fake = FakeMongoTarget::new(0);
+        let metrics = PersistenceMetrics::new();
+        let sink = PipelineSink::build(fake.clone(), config.clone(), metrics.clone(), None)
+            .await
+            .unwrap();
+        sink.write_batch(vec![with_id("m1")]).await.unwrap();
+
+        let spool = sink.spool.clone().unwrap();
+        let index = sink.dedup_index().unwrap().clone();
+        let seg1 = std::fs::read(crate::persistence::spool::segment_path(tmp.path(), 1)).unwrap();
+        let worker = ReplayWorker::new(spool.clone(), fake.clone(), ReplayConfig::default(), metrics.clone())
+            .with_dedup_index(index);
+        while worker.replay_oldest_segment().await.unwrap() {}
+
+        // Crash before segment 1 was deleted: it reappears and is replayed again.
+        std::fs::write(crate::persistence::spool::segment_path(tmp.path(), 1), seg1).unwrap();
+        while worker.replay_oldest_segment().await.unwrap() {}
+        drop(worker);
+        drop(sink);
+
+        // --- Run 3: a live duplicate of a replayed envelope is dropped ---
+        let sink = PipelineSink::build(fake.clone(), config, metrics.clone(), None)
+            .await
+            .unwrap();
+        sink.write_batch(vec![with_id("m2")]).await.unwrap();
+
+        let delivered: Vec<String> = fake
+            .inserted
+            .lock()
+            .unwrap()
+            .iter()
+            .flatten()
+            .map(|e| e.message_id.clone().unwrap())
+            .collect();
+        // m3 sits in the still-open segment and is not replayed yet.
+        assert_eq!(delivered, ["m1", "m2"]);
+        assert_eq!(metrics.replay_dedup_skipped_total(), 2);
+        assert_eq!(metrics.dedup_dropped_total.get("binance", "trades"), 1);
+    }
+
     #[tokio::test]
     async fn empty_batch_is_noop_pipeline() {
         let tmp = TempDir::new().unwrap();
@@ -9,12 +9,18 @@
 //! * **Shutdown-safe**: monitors a `watch::Receiver<bool>` shutdown signal.
 //!   On signal the worker drains the current batch (if in-flight) and exits
 //!   cleanly — it does **not** delete a segment if the insert is still pending.
+//! * **Exactly-once with a dedup index**: when built with
+//!   [`ReplayWorker::with_dedup_index`], envelopes whose keys are already in
+//!   the persistent `DedupIndex` are skipped, and each inserted chunk is
+//!   recorded there.  Re-replaying a segment after a crash (or after the same
+//!   messages were re-sent live) therefore does not duplicate them at the sink.
 
 use std::sync::Arc;
 use std::time::Duration;
 
 use tokio::sync::watch;
 
+use super::dedup::DedupIndex;
 use super::envelope::Envelope;
 use super::metrics::PersistenceMetrics;
 use super::mongo::MongoTarget;
@@ -54,6 +60,7 @@ pub struct ReplayWorker {
     target: Arc<dyn MongoTarget>,
     config: ReplayConfig,
     metrics: Arc<PersistenceMetrics>,
+    index: Option<Arc<DedupIndex>>,
 }
 
 impl ReplayWorker {
@@ -63,7 +70,13 @@ impl ReplayWorker {
         config: ReplayConfig,
         metrics: Arc<PersistenceMetrics>,
     ) -> Self {
-        Self { spool, target, config, metrics }
+        Self { spool, target, config, metrics, index: None }
+    }
+
+    /// Skip already-delivered envelopes and record replayed ones in `index`.
+    pub fn with_dedup_index(mut self, index: Arc<DedupIndex>) -> Self {
+        self.index = Some(index);
+        self
     }
 
     /// Spawn the worker as a background Tokio task.
@@ -144,11 +157,26 @@ impl ReplayWorker {
 
         // Replay in chunks of `batch_size`.
         for chunk in envelopes.chunks(self.config.batch_size) {
-            self.target
-                .insert_many_envelopes(chunk)
-                .await
-                .map_err(|e| e)?;
-            self.metrics.add_spool_replay(chunk.len() as u64);
+            let Some(ref index) = self.index else {
+                self.target
+                    .insert_many_envelopes(chunk)
+                    .await
+                    .map_err(|e| e)?;
+                self.metrics.add_spool_replay(chunk.len() as u64);
+                continue;
+            };
+            let (fresh, skipped) = index.filter_delivered(chunk);
+            self.metrics.add_replay_dedup_skipped(skipped as u64);
+            if fresh.is_empty() {
+                continue;
+            }
+            self.target.insert_many_envelopes(&fresh).await?;
+            // Delivered already: keep going so the segment is still deleted.
+            if let Err(e) = index.record(&fresh) {
+                self.metrics.increment_dedup_index_errors();
+                tracing::warn!(error = %e, "failed to journal replayed dedup keys");
+            }
+            self.metrics.add_spool_replay(fresh.len() as u64);
         }
 
         // Delete segment ONLY after all chunks succeeded.
@@ -0,0 +1,98 @@
+use crate::config::AppConfig;
+use crate::sink::JsonlDocumentSink;
+use std::collections::BTreeMap;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, RwLock};
+use std::thread::JoinHandle;
+use tokio::sync::watch;
+use ucel_ir::{FsCheckpointStore, IrSyncRunner, IrSyncSourceMetrics, UcelIrError};
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum HealthStatus {
+    Ok,
+    Degraded { reason: String },
+}
+
+#[derive(Clone, Debug)]
+pub struct HealthSnapshot {
+    pub status: HealthStatus,
+}
+
+#[derive(Clone)]
+pub struct AppState {
+    pub cfg: AppConfig,
+    pub health_tx: watch::Sender<HealthSnapshot>,
+    pub health_rx: watch::Receiver<HealthSnapshot>,
+    pub metrics: Arc<RwLock<BTreeMap<String, IrSyncSourceMetrics>>>,
+}
+
+impl AppState {
+    pub fn new(cfg: AppConfig) -> Self {
+        let (health_tx, health_rx) = watch::channel(HealthSnapshot {
+            status: HealthStatus::Degraded {
+                reason: "starting".to_string(),
+            },
+        });
+        Self {
+            cfg,
+            health_tx,
+            health_rx,
+            metrics: Arc::new(RwLock::new(BTreeMap::new())),
+        }
+    }
+
+    /// Runner over the configured sources, checkpointed under `checkpoint_dir`.
+    pub fn build_runner(&self) -> Result<IrSyncRunner, UcelIrError> {
+        let checkpoints = Arc::new(FsCheckpointStore::new(&self.cfg.checkpoint_dir)?);
+        let sink = Arc::new(JsonlDocumentSink::open(&self.cfg.document_log)?);
+        let mut runner = IrSyncRunner::new(checkpoints, sink);
+        for source in &self.cfg.sources {
+            runner.add_source(source.to_sync_config());
+        }
+        Ok(runner)
+    }
+
+    /// Publishes the runner's metrics and derives health from them.
+    pub fn publish(&self, runner: &IrSyncRunner) {
+        if let Ok(mut m) = self.metrics.write() {
+            *m = runner.metrics();
+        }
+        let reasons = runner.health_reasons();
+        let status = if reasons.is_empty() {
+            HealthStatus::Ok
+        } else {
+            HealthStatus::Degraded {
+                reason: reasons.join(","),
+            }
+        };
+        let _ = self.health_tx.send(HealthSnapshot { status });
+    }
+}
+
+/// Runs the blocking sync loop on its own thread until `shutdown`.
+pub struct SyncWorker {
+    stop: Arc<AtomicBool>,
+    handle: Option<JoinHandle<()>>,
+}
+
+impl SyncWorker {
+    pub fn spawn(st: AppState, mut runner: IrSyncRunner) -> Self {
+        let stop = Arc::new(AtomicBool::new(false));
+        let flag = stop.clone();
+        st.publish(&runner);
+        let handle = std::thread::spawn(move || {
+            runner.run(&flag, |r, _| st.publish(r));
+        });
+        Self {
+            stop,
+            handle: Some(handle),
+        }
+    }
+
+    pub fn shutdown(mut self) {
+        self.stop.store(true, Ordering::Relaxed);
+        if let Some(h) = self.handle.take() {
+            let _ = h.join();
+        }
+    }
+}
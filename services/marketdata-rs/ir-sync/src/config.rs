@@ -0,0 +1,139 @@
+use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
+use std::sync::Arc;
+use std::time::Duration;
+use ucel_ir::{
+    jp_issuer_feed_adapter, jp_issuer_html_adapter, sec_adapter, statutory_adapter, timely_adapter,
+    us_issuer_feed_adapter, us_issuer_html_adapter, IrSourceAdapter, IrSyncPoliteness,
+    IrSyncSourceConfig, IssuerSitePolitenessPolicy, JpPolitenessPolicy, UsPolitenessPolicy,
+};
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AppConfig {
+    pub http: HttpConfig,
+    #[serde(default = "default_checkpoint_dir")]
+    pub checkpoint_dir: PathBuf,
+    #[serde(default = "default_document_log")]
+    pub document_log: PathBuf,
+    #[serde(default)]
+    pub sources: Vec<SourceConfig>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct HttpConfig {
+    #[serde(default = "default_listen")]
+    pub listen: String,
+}
+
+fn default_listen() -> String {
+    "127.0.0.1:8091".to_string()
+}
+
+fn default_checkpoint_dir() -> PathBuf {
+    PathBuf::from("services/marketdata-rs/ir-sync/checkpoints")
+}
+
+fn default_document_log() -> PathBuf {
+    PathBuf::from("services/marketdata-rs/ir-sync/documents.jsonl")
+}
+
+fn default_poll_interval_secs() -> u64 {
+    300
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SourceName {
+    Edinet,
+    Tdnet,
+    Sec,
+    JpIssuerFeed,
+    JpIssuerHtml,
+    UsIssuerFeed,
+    UsIssuerHtml,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SourceConfig {
+    pub source: SourceName,
+    #[serde(default = "default_poll_interval_secs")]
+    pub poll_interval_secs: u64,
+    #[serde(default)]
+    pub issuer_keys: Vec<String>,
+    #[serde(default)]
+    pub review_approved: bool,
+}
+
+#[derive(thiserror::Error, Debug)]
+pub enum ConfigError {
+    #[error("failed to read config file: {0}")]
+    Io(#[from] std::io::Error),
+    #[error("failed to parse yaml: {0}")]
+    Yaml(#[from] serde_yaml::Error),
+    #[error("invalid config: sources must not be empty")]
+    EmptySources,
+    #[error("invalid config: poll_interval_secs must be > 0 for {0:?}")]
+    ZeroPollInterval(SourceName),
+}
+
+impl AppConfig {
+    pub fn load_yaml(path: &std::path::Path) -> Result<Self, ConfigError> {
+        let bytes = std::fs::read(path)?;
+        let mut cfg: AppConfig = serde_yaml::from_slice(&bytes)?;
+        if cfg.sources.is_empty() {
+            return Err(ConfigError::EmptySources);
+        }
+        if let Some(s) = cfg.sources.iter().find(|s| s.poll_interval_secs == 0) {
+            return Err(ConfigError::ZeroPollInterval(s.source));
+        }
+        if cfg.http.listen.trim().is_empty() {
+            cfg.http.listen = default_listen();
+        }
+        Ok(cfg)
+    }
+}
+
+impl SourceConfig {
+    /// Adapter plus the politeness policy of its source family.
+    pub fn to_sync_config(&self) -> IrSyncSourceConfig {
+        let (adapter, politeness): (Arc<dyn IrSourceAdapter + Send + Sync>, IrSyncPoliteness) =
+            match self.source {
+                SourceName::Edinet => (
+                    Arc::new(statutory_adapter()),
+                    JpPolitenessPolicy::default().into(),
+                ),
+                SourceName::Tdnet => (
+                    Arc::new(timely_adapter()),
+                    JpPolitenessPolicy::default().into(),
+                ),
+                SourceName::Sec => (
+                    Arc::new(sec_adapter()),
+                    UsPolitenessPolicy::default().into(),
+                ),
+                SourceName::JpIssuerFeed => (
+                    Arc::new(jp_issuer_feed_adapter()),
+                    IssuerSitePolitenessPolicy::default().into(),
+                ),
+                SourceName::JpIssuerHtml => (
+                    Arc::new(jp_issuer_html_adapter()),
+                    IssuerSitePolitenessPolicy::default().into(),
+                ),
+                SourceName::UsIssuerFeed => (
+                    Arc::new(us_issuer_feed_adapter()),
+                    IssuerSitePolitenessPolicy::default().into(),
+                ),
+                SourceName::UsIssuerHtml => (
+                    Arc::new(us_issuer_html_adapter()),
+                    IssuerSitePolitenessPolicy::default().into(),
+                ),
+            };
+        let cfg = IrSyncSourceConfig::new(adapter, politeness)
+            .with_issuer_keys(self.issuer_keys.clone())
+            .with_poll_interval(Duration::from_secs(self.poll_interval_secs));
+        if self.review_approved {
+            cfg.review_approved()
+        } else {
+            cfg
+        }
+    }
+}
@@ -0,0 +1,4 @@
+pub mod app;
+pub mod config;
+pub mod http;
+pub mod sink;
@@ -0,0 +1,47 @@
+use ir_sync::app::{AppState, SyncWorker};
+use ir_sync::config::AppConfig;
+
+#[tokio::main]
+async fn main() {
+    let mut args = std::env::args().skip(1);
+    let mut config_path: Option<std::path::PathBuf> = None;
+    while let Some(arg) = args.next() {
+        if arg == "--config" {
+            config_path = args.next().map(std::path::PathBuf::from);
+        }
+    }
+
+    let cfg_path = config_path
+        .unwrap_or_else(|| std::path::PathBuf::from("services/marketdata-rs/ir-sync/config.yaml"));
+
+    let cfg = match AppConfig::load_yaml(&cfg_path) {
+        Ok(v) => v,
+        Err(e) => {
+            eprintln!("ir-sync: config error: {e}");
+            std::process::exit(2);
+        }
+    };
+
+    let app_state = AppState::new(cfg.clone());
+    let runner = match app_state.build_runner() {
+        Ok(r) => r,
+        Err(e) => {
+            eprintln!("ir-sync: startup error: {e}");
+            std::process::exit(2);
+        }
+    };
+    let worker = SyncWorker::spawn(app_state.clone(), runner);
+
+    let listen = cfg.http.listen.clone();
+    let router = ir_sync::http::router(app_state);
+    let http_h = tokio::spawn(async move {
+        let addr: std::net::SocketAddr = listen.parse().expect("http.listen must be SocketAddr");
+        let listener = tokio::net::TcpListener::bind(addr).await.expect("bind");
+        axum::serve(listener, router).await.expect("serve");
+    });
+
+    let _ = tokio::signal::ctrl_c().await;
+
+    let _ = tokio::task::spawn_blocking(move || worker.shutdown()).await;
+    http_h.abort();
+}
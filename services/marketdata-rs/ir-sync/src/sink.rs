@@ -0,0 +1,40 @@
+use std::fs::{File, OpenOptions};
+use std::io::Write;
+use std::path::Path;
+use std::sync::Mutex;
+use ucel_core::IrDocumentDescriptor;
+use ucel_ir::{IrDocumentSink, UcelIrError, UcelIrErrorKind};
+
+/// Appends each new document descriptor as one JSON line.
+pub struct JsonlDocumentSink {
+    file: Mutex<File>,
+}
+
+impl JsonlDocumentSink {
+    pub fn open(path: &Path) -> Result<Self, UcelIrError> {
+        if let Some(parent) = path.parent().filter(|p| !p.as_os_str().is_empty()) {
+            std::fs::create_dir_all(parent)
+                .map_err(|e| UcelIrError::new(UcelIrErrorKind::Sink, e.to_string()))?;
+        }
+        let file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(path)
+            .map_err(|e| UcelIrError::new(UcelIrErrorKind::Sink, e.to_string()))?;
+        Ok(Self {
+            file: Mutex::new(file),
+        })
+    }
+}
+
+impl IrDocumentSink for JsonlDocumentSink {
+    fn put_document(&self, doc: &IrDocumentDescriptor) -> Result<(), UcelIrError> {
+        let line = serde_json::to_string(doc)
+            .map_err(|e| UcelIrError::new(UcelIrErrorKind::Sink, e.to_string()))?;
+        let mut file = self
+            .file
+            .lock()
+            .map_err(|_| UcelIrError::new(UcelIrErrorKind::Sink, "document log lock poisoned"))?;
+        writeln!(file, "{line}").map_err(|e| UcelIrError::new(UcelIrErrorKind::Sink, e.to_string()))
+    }
+}
@@ -0,0 +1,86 @@
+use axum::body::Body;
+use axum::http::{Request, StatusCode};
+use ir_sync::config::{AppConfig, HttpConfig, SourceConfig, SourceName};
+use std::time::Instant;
+use tower::ServiceExt;
+
+fn config(dir: &std::path::Path, sources: Vec<SourceConfig>) -> AppConfig {
+    AppConfig {
+        http: HttpConfig {
+            listen: "127.0.0.1:0".to_string(),
+        },
+        checkpoint_dir: dir.join("checkpoints"),
+        document_log: dir.join("documents.jsonl"),
+        sources,
+    }
+}
+
+fn source(source: SourceName) -> SourceConfig {
+    SourceConfig {
+        source,
+        poll_interval_secs: 60,
+        issuer_keys: vec![],
+        review_approved: false,
+    }
+}
+
+async fn get(router: axum::Router, uri: &str) -> (StatusCode, serde_json::Value) {
+    let response = router
+        .oneshot(Request::builder().uri(uri).body(Body::empty()).unwrap())
+        .await
+        .unwrap();
+    let status = response.status();
+    let bytes = axum::body::to_bytes(response.into_body(), usize::MAX)
+        .await
+        .unwrap();
+    (status, serde_json::from_slice(&bytes).unwrap())
+}
+
+#[tokio::test]
+async fn healthz_and_metrics_follow_sync_progress() {
+    let dir = tempfile::tempdir().unwrap();
+    let app = ir_sync::app::AppState::new(config(
+        dir.path(),
+        vec![source(SourceName::Edinet), source(SourceName::Tdnet)],
+    ));
+
+    let (status, body) = get(ir_sync::http::router(app.clone()), "/healthz").await;
+    assert_eq!(status, StatusCode::SERVICE_UNAVAILABLE);
+    assert_eq!(body["reason"], "starting");
+
+    let mut runner = app.build_runner().unwrap();
+    runner.poll_due(Instant::now());
+    app.publish(&runner);
+
+    let (status, _) = get(ir_sync::http::router(app.clone()), "/healthz").await;
+    assert_eq!(status, StatusCode::OK);
+    let (status, metrics) = get(ir_sync::http::router(app.clone()), "/metrics").await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(metrics["edinet_api_documents_v2"]["status"], "ok");
+    assert!(
+        metrics["edinet_api_documents_v2"]["documents_new"]
+            .as_u64()
+            .unwrap()
+            > 0
+    );
+    assert_eq!(metrics["jp_tdnet_timely_html"]["status"], "review_required");
+
+    let log = std::fs::read_to_string(dir.path().join("documents.jsonl")).unwrap();
+    assert!(!log.is_empty());
+    assert!(dir
+        .path()
+        .join("checkpoints/ir_sync.edinet_api_documents_v2.checkpoint")
+        .exists());
+}
+
+#[tokio::test]
+async fn healthz_degraded_without_runnable_sources() {
+    let dir = tempfile::tempdir().unwrap();
+    let app = ir_sync::app::AppState::new(config(dir.path(), vec![source(SourceName::Tdnet)]));
+    let runner = app.build_runner().unwrap();
+    app.publish(&runner);
+
+    let (status, body) = get(ir_sync::http::router(app), "/healthz").await;
+    assert_eq!(status, StatusCode::SERVICE_UNAVAILABLE);
+    assert_eq!(body["reason"], "no_runnable_sources");
+}
@@ -0,0 +1,55 @@
+//! Validates every coverage_v2 file against a saved symbol list, offline.
+//!
+//! usage: ucel-coverage-check --symbols <symbols.json> [--coverage-dir ucel/coverage_v2]
+//!        [--rules-dir ucel/crates/ucel-ws-rules/rules]
+//!
+//! Prints one JSON report per file and exits 1 if any file fails.
+
+use std::path::PathBuf;
+
+use ucel_ws_subscriber::coverage_check::{check_coverage_dir, load_symbol_lists};
+
+fn main() {
+    let mut args = std::env::args().skip(1);
+    let mut symbols_path: Option<PathBuf> = None;
+    let mut coverage_dir = PathBuf::from("ucel/coverage_v2");
+    let mut rules_dir = PathBuf::from("ucel/crates/ucel-ws-rules/rules");
+    while let Some(arg) = args.next() {
+        let value = args.next().map(PathBuf::from);
+        match (arg.as_str(), value) {
+            ("--symbols", Some(v)) => symbols_path = Some(v),
+            ("--coverage-dir", Some(v)) => coverage_dir = v,
+            ("--rules-dir", Some(v)) => rules_dir = v,
+            _ => {
+                eprintln!("ucel-coverage-check: unexpected argument: {arg}");
+                std::process::exit(2);
+            }
+        }
+    }
+    let Some(symbols_path) = symbols_path else {
+        eprintln!("ucel-coverage-check: --symbols <symbols.json> is required");
+        std::process::exit(2);
+    };
+
+    let checks = load_symbol_lists(&symbols_path)
+        .and_then(|symbols| check_coverage_dir(&coverage_dir, &rules_dir, &symbols));
+    let checks = match checks {
+        Ok(c) => c,
+        Err(e) => {
+            eprintln!("ucel-coverage-check: {e}");
+            std::process::exit(2);
+        }
+    };
+
+    let failed = checks.iter().filter(|c| !c.ok).count();
+    for c in &checks {
+        println!("{}", serde_json::to_string(c).unwrap_or_default());
+    }
+    eprintln!(
+        "ucel-coverage-check: {} files, {failed} failed",
+        checks.len()
+    );
+    if failed > 0 {
+        std::process::exit(1);
+    }
+}
@@ -31,7 +31,6 @@ pub struct IngestConfig {
 
     pub max_connections_per_exchange: usize,
 
-    #[allow(dead_code)]
     pub enable_private_ws: bool,
     pub exchange_allowlist: Vec<String>,
 
@@ -39,6 +38,9 @@ pub struct IngestConfig {
     pub allow_partial_rules: bool,
 
     pub shutdown_grace: Duration,
+
+    /// symbol refresh + live replan period for coverage_v2 exchanges; zero disables
+    pub replan_interval: Duration,
 }
 
 impl IngestConfig {
@@ -73,6 +75,7 @@ impl IngestConfig {
         let allow_partial_rules = env_bool("UCEL_ALLOW_PARTIAL_RULES", false);
 
         let shutdown_grace = Duration::from_secs(env_u64("UCEL_SHUTDOWN_GRACE_SECS", 5)?);
+        let replan_interval = Duration::from_secs(env_u64("UCEL_REPLAN_INTERVAL_SECS", 900)?);
 
         Ok(Self {
             coverage_dir,
@@ -95,6 +98,7 @@ impl IngestConfig {
             require_rules_full,
             allow_partial_rules,
             shutdown_grace,
+            replan_interval,
         })
     }
 }
@@ -0,0 +1,87 @@
+//! Offline coverage_v2 validation: expand every `<dir>/*.yaml` against a saved symbol list
+//! with the same planner and rules the subscriber uses, without touching the network.
+
+use std::collections::BTreeMap;
+use std::path::Path;
+
+use serde::Serialize;
+use ucel_subscription_planner::{generate_plan_v2, load_coverage_v2, PlanReport};
+use ucel_ws_rules::load_for_exchange;
+
+#[derive(Debug, Clone, Serialize)]
+pub struct CoverageCheck {
+    pub file: String,
+    pub exchange_id: String,
+    /// Loaded, planned, and every family expanded.
+    pub ok: bool,
+    pub error: Option<String>,
+    pub report: Option<PlanReport>,
+}
+
+/// Reads `{"<exchange_id>": ["SYMBOL", ...], ...}`, keyed like the coverage file stems.
+pub fn load_symbol_lists(path: &Path) -> Result<BTreeMap<String, Vec<String>>, String> {
+    let raw = std::fs::read(path).map_err(|e| format!("read {}: {e}", path.display()))?;
+    serde_json::from_slice(&raw).map_err(|e| format!("parse {}: {e}", path.display()))
+}
+
+/// Checks every top-level `*.yaml` in `coverage_dir`, sorted by file name.
+///
+/// Symbols are looked up by file stem, then by the coverage `venue`. A file with
+/// symbol-bound families but no symbol list fails rather than planning nothing.
+pub fn check_coverage_dir(
+    coverage_dir: &Path,
+    rules_dir: &Path,
+    symbols: &BTreeMap<String, Vec<String>>,
+) -> Result<Vec<CoverageCheck>, String> {
+    let mut files: Vec<_> = std::fs::read_dir(coverage_dir)
+        .map_err(|e| format!("read_dir {}: {e}", coverage_dir.display()))?
+        .filter_map(|e| e.ok().map(|e| e.path()))
+        .filter(|p| p.is_file() && p.extension().is_some_and(|x| x == "yaml"))
+        .collect();
+    files.sort();
+
+    let mut out = Vec::new();
+    for path in files {
+        let exchange_id = path
+            .file_stem()
+            .map(|s| s.to_string_lossy().to_string())
+            .unwrap_or_default();
+        let mut check = CoverageCheck {
+            file: path.display().to_string(),
+            exchange_id: exchange_id.clone(),
+            ok: false,
+            error: None,
+            report: None,
+        };
+        let cov = match load_coverage_v2(&path) {
+            Ok(c) => c,
+            Err(e) => {
+                check.error = Some(e);
+                out.push(check);
+                continue;
+            }
+        };
+        let syms = symbols
+            .get(&exchange_id)
+            .or_else(|| symbols.get(&cov.venue));
+        if syms.is_none() && cov.families.iter().any(|f| f.requires_symbol) {
+            check.error = Some(format!("no symbol list for {exchange_id}"));
+            out.push(check);
+            continue;
+        }
+        let syms = syms.cloned().unwrap_or_default();
+        let rules = load_for_exchange(rules_dir, &exchange_id);
+        match generate_plan_v2(&exchange_id, &cov, &syms, &rules) {
+            Ok(plan) => {
+                check.ok = plan.report.is_clean();
+                check.report = Some(plan.report);
+            }
+            Err(e) => {
+                check.error = Some(e.to_string());
+                check.report = Some(*e.report);
+            }
+        }
+        out.push(check);
+    }
+    Ok(out)
+}
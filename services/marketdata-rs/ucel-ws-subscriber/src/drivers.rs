@@ -0,0 +1,29 @@
+use ucel_registry::ingest::IngestDriverRegistry;
+
+/// Every venue this binary can ingest, keyed by exchange id (`kraken`, `bybit-linear`, ...).
+///
+/// Each venue crate registers its own ids; unknown ids fail in `IngestDriverRegistry::resolve`.
+pub fn registry() -> IngestDriverRegistry {
+    let mut registry = IngestDriverRegistry::new();
+    ucel_cex_binance::ws_manager::register_ingest_drivers(&mut registry);
+    ucel_cex_binance_coinm::ws_manager::register_ingest_drivers(&mut registry);
+    ucel_cex_binance_options::ws_manager::register_ingest_drivers(&mut registry);
+    ucel_cex_binance_usdm::ws_manager::register_ingest_drivers(&mut registry);
+    ucel_cex_bitbank::ws_manager::register_ingest_drivers(&mut registry);
+    ucel_cex_bitflyer::ws_manager::register_ingest_drivers(&mut registry);
+    ucel_cex_bitget::ws_manager::register_ingest_drivers(&mut registry);
+    ucel_cex_bithumb::ws_manager::register_ingest_drivers(&mut registry);
+    ucel_cex_bitmex::ws_manager::register_ingest_drivers(&mut registry);
+    ucel_cex_bittrade::ws_manager::register_ingest_drivers(&mut registry);
+    ucel_cex_bybit::ws_manager::register_ingest_drivers(&mut registry);
+    ucel_cex_coinbase::ws_manager::register_ingest_drivers(&mut registry);
+    ucel_cex_coincheck::ws_manager::register_ingest_drivers(&mut registry);
+    ucel_cex_deribit::ws_manager::register_ingest_drivers(&mut registry);
+    ucel_cex_gmocoin::ws_manager::register_ingest_drivers(&mut registry);
+    ucel_cex_htx::ws_manager::register_ingest_drivers(&mut registry);
+    ucel_cex_kraken::ws_manager::register_ingest_drivers(&mut registry);
+    ucel_cex_okx::ws_manager::register_ingest_drivers(&mut registry);
+    ucel_cex_sbivc::ws_manager::register_ingest_drivers(&mut registry);
+    ucel_cex_upbit::ws_manager::register_ingest_drivers(&mut registry);
+    registry
+}
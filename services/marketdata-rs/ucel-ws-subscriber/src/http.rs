@@ -12,14 +12,22 @@ pub fn router(state: AppState) -> Router {
 
 async fn healthz(State(state): State<AppState>) -> impl IntoResponse {
     let h = state.health.read().clone();
-    Json(h)
+    let mut v = serde_json::to_value(h).unwrap_or_default();
+    if let Some(obj) = v.as_object_mut() {
+        obj.insert("plan_reports".into(), plan_reports(&state));
+    }
+    Json(v)
+}
+
+fn plan_reports(state: &AppState) -> serde_json::Value {
+    serde_json::to_value(&*state.plan_reports.read()).unwrap_or_default()
 }
 
 async fn support_bundle(State(state): State<AppState>) -> impl IntoResponse {
     let health = state.health.read().clone();
     let rules = state.rules_snapshot.read().clone();
 
-    let bundle = build_support_bundle(SupportBundleInput {
+    let mut bundle = build_support_bundle(SupportBundleInput {
         exchange_id: state.exchange_id.clone(),
         conn_id: state.conn_id.clone(),
         health,
@@ -27,5 +35,8 @@ async fn support_bundle(State(state): State<AppState>) -> impl IntoResponse {
         events: state.events.clone(),
         rules_snapshot: rules,
     });
+    if let Some(obj) = bundle.as_object_mut() {
+        obj.insert("plan_reports".into(), plan_reports(&state));
+    }
     Json(bundle)
 }
@@ -1,6 +1,8 @@
-pub mod adapters;
 pub mod config;
+pub mod coverage_check;
+pub mod drivers;
 pub mod http;
 pub mod lock;
+pub mod replan;
 pub mod state;
 pub mod supervisor;
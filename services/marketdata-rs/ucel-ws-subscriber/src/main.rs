@@ -1,10 +1,11 @@
 use tracing::{error, info, warn};
 use tracing_subscriber::EnvFilter;
 
-mod adapters;
 mod config;
+mod drivers;
 mod http;
 mod lock;
+mod replan;
 mod state;
 mod supervisor;
 
@@ -0,0 +1,210 @@
+//! Live replanning for one exchange: listings/delistings become per-stream store changes
+//! on the running connections instead of a full restart.
+
+use std::collections::{BTreeMap, BTreeSet};
+
+use ucel_subscription_planner::{
+    canon_params, diff_plan, generate_plan_v2_with_load, stable_key, CoverageV2, LiveAssignment,
+    LoadModel, PlanDiff, PlanReport, ShardCaps, SubscriptionKey,
+};
+use ucel_subscription_store::{SubscriptionRow, SubscriptionStore};
+use ucel_symbol_core::SymbolStatus;
+use ucel_symbol_store::SymbolEvent;
+use ucel_ws_rules::ExchangeWsRules;
+
+pub struct ExchangeReplanner {
+    exchange_id: String,
+    coverage: CoverageV2,
+    rules: ExchangeWsRules,
+    symbols: BTreeSet<String>,
+    /// Every connection started for this exchange, including drained ones; never reused.
+    started_conns: BTreeSet<String>,
+    last_report: Option<PlanReport>,
+    /// Per-stream inbound rates, fed from the running connections' transport metrics.
+    load: LoadModel,
+}
+
+impl ExchangeReplanner {
+    pub fn new(
+        exchange_id: &str,
+        coverage: CoverageV2,
+        rules: ExchangeWsRules,
+        symbols: &[String],
+        started_conns: &[String],
+    ) -> Self {
+        Self {
+            exchange_id: exchange_id.to_string(),
+            coverage,
+            rules,
+            symbols: symbols.iter().cloned().collect(),
+            started_conns: started_conns.iter().cloned().collect(),
+            last_report: None,
+            load: LoadModel::default(),
+        }
+    }
+
+    pub fn exchange_id(&self) -> &str {
+        &self.exchange_id
+    }
+
+    /// Expansion report of the last `replan`, failed or not. Shards are those of a fresh plan,
+    /// not the live assignment.
+    pub fn last_report(&self) -> Option<&PlanReport> {
+        self.last_report.as_ref()
+    }
+
+    pub fn symbols(&self) -> Vec<String> {
+        self.symbols.iter().cloned().collect()
+    }
+
+    /// Replaces the symbol universe (e.g. a fresh `fetch_symbols`). Returns true if it changed.
+    pub fn set_symbols(&mut self, symbols: &[String]) -> bool {
+        let next: BTreeSet<String> = symbols.iter().cloned().collect();
+        if next == self.symbols {
+            return false;
+        }
+        self.symbols = next;
+        true
+    }
+
+    /// Applies `SymbolStore` events for this exchange by raw symbol. Returns true if it changed.
+    ///
+    /// Listing adds, delisting (removal or status `Delisted`) drops; parameter changes are ignored.
+    /// The binary polls `fetch_symbols` instead; this is for embedders fed by a `SymbolStore`.
+    #[allow(dead_code)]
+    pub fn apply_symbol_events(&mut self, events: &[SymbolEvent]) -> bool {
+        let mut changed = false;
+        for ev in events {
+            changed |= match ev {
+                SymbolEvent::Added { instrument, .. } => {
+                    self.symbols.insert(instrument.raw_symbol.clone())
+                }
+                SymbolEvent::Removed { id, .. } => self.symbols.remove(&id.raw_symbol),
+                SymbolEvent::StatusChanged { id, to, .. } if *to == SymbolStatus::Delisted => {
+                    self.symbols.remove(&id.raw_symbol)
+                }
+                SymbolEvent::StatusChanged { id, from, .. } if *from == SymbolStatus::Delisted => {
+                    self.symbols.insert(id.raw_symbol.clone())
+                }
+                _ => false,
+            };
+        }
+        changed
+    }
+
+    /// Folds measured inbound msgs/sec per connection into the load model, splitting each
+    /// connection's rate across the streams the store has assigned to it.
+    ///
+    /// Returns true when a connection carrying more than one stream runs over the
+    /// per-connection msgs/sec cap, i.e. a `replan` would move streams off it.
+    pub fn observe_load(
+        &mut self,
+        store: &SubscriptionStore,
+        rates: &BTreeMap<String, f64>,
+    ) -> Result<bool, String> {
+        let mut by_conn: BTreeMap<String, Vec<SubscriptionKey>> = BTreeMap::new();
+        for r in store.assigned_rows(&self.exchange_id)? {
+            let Some(conn_id) = r.assigned_conn else {
+                continue;
+            };
+            let params = serde_json::from_str(&r.params_json)
+                .map_err(|e| format!("params of {}: {e}", r.key))?;
+            by_conn.entry(conn_id).or_default().push(SubscriptionKey {
+                exchange_id: r.exchange_id,
+                op_id: r.op_id,
+                symbol: r.symbol,
+                params,
+            });
+        }
+        let cap = ShardCaps::from_rules(&self.rules).max_msgs_per_sec;
+        let mut over_cap = false;
+        for (conn_id, keys) in &by_conn {
+            let Some(rate) = rates.get(conn_id) else {
+                continue;
+            };
+            self.load.observe_connection(keys, *rate);
+            over_cap |= keys.len() > 1 && cap.is_some_and(|c| *rate > c as f64);
+        }
+        Ok(over_cap)
+    }
+
+    /// Diffs the coverage plan for the current symbols against the store and applies it.
+    ///
+    /// Nothing is written when the diff would exceed `max_conns` live connections. The caller
+    /// starts `opened_conns`; `closed_conns` shut themselves down once drained.
+    pub fn replan(
+        &mut self,
+        store: &mut SubscriptionStore,
+        max_conns: usize,
+        now: i64,
+    ) -> Result<PlanDiff, String> {
+        let symbols = self.symbols();
+        let plan = match generate_plan_v2_with_load(
+            &self.exchange_id,
+            &self.coverage,
+            &symbols,
+            &self.rules,
+            &self.load,
+        ) {
+            Ok(plan) => plan,
+            Err(e) => {
+                self.last_report = Some((*e.report).clone());
+                return Err(e.into());
+            }
+        };
+        self.last_report = Some(plan.report);
+        let desired = plan.seed;
+        let rows = store.assigned_rows(&self.exchange_id)?;
+        let live: Vec<LiveAssignment> = rows
+            .into_iter()
+            .filter_map(|r| {
+                Some(LiveAssignment {
+                    conn_id: r.assigned_conn?,
+                    key: r.key,
+                    symbol: r.symbol,
+                })
+            })
+            .collect();
+        let live_conns: BTreeSet<&str> = live.iter().map(|a| a.conn_id.as_str()).collect();
+        let reserved: Vec<String> = self.started_conns.iter().cloned().collect();
+
+        let diff = diff_plan(
+            &self.exchange_id,
+            &desired,
+            &live,
+            ShardCaps::from_rules(&self.rules),
+            &self.load,
+            &reserved,
+        );
+        let conns_after = live_conns.len() + diff.opened_conns.len() - diff.closed_conns.len();
+        if conns_after > max_conns {
+            return Err(format!(
+                "replan exceeds connection cap: exchange={} conns={conns_after} max={max_conns}",
+                self.exchange_id
+            ));
+        }
+
+        let added: Vec<SubscriptionRow> = diff
+            .added
+            .iter()
+            .map(|a| SubscriptionRow {
+                key: stable_key(&a.key),
+                exchange_id: a.key.exchange_id.clone(),
+                op_id: a.key.op_id.clone(),
+                symbol: a.key.symbol.clone(),
+                params_json: canon_params(&a.key.params),
+                assigned_conn: Some(a.conn_id.clone()),
+            })
+            .collect();
+        let removed: Vec<String> = diff.removed.iter().map(|r| r.key.clone()).collect();
+        let moved: Vec<(String, String)> = diff
+            .moved
+            .iter()
+            .map(|m| (m.key.clone(), m.to_conn.clone()))
+            .collect();
+        store.apply_reassignment(&added, &removed, &moved, now)?;
+
+        self.started_conns.extend(diff.opened_conns.iter().cloned());
+        Ok(diff)
+    }
+}
@@ -1,5 +1,7 @@
+use std::collections::BTreeMap;
 use std::sync::Arc;
 
+use ucel_subscription_planner::PlanReport;
 use ucel_transport::health::TransportHealth;
 use ucel_transport::obs::{StabilityEventRing, TransportMetrics};
 
@@ -11,6 +13,8 @@ pub struct AppState {
     pub events: Arc<StabilityEventRing>,
     pub health: Arc<parking_lot::RwLock<TransportHealth>>,
     pub rules_snapshot: Arc<parking_lot::RwLock<serde_json::Value>>,
+    /// Latest plan expansion report per exchange.
+    pub plan_reports: Arc<parking_lot::RwLock<BTreeMap<String, PlanReport>>>,
 }
 
 impl AppState {
@@ -22,6 +26,7 @@ impl AppState {
             events: StabilityEventRing::new(512),
             health: Arc::new(parking_lot::RwLock::new(TransportHealth::healthy())),
             rules_snapshot: Arc::new(parking_lot::RwLock::new(serde_json::json!({}))),
+            plan_reports: Arc::new(parking_lot::RwLock::new(BTreeMap::new())),
         }
     }
 }
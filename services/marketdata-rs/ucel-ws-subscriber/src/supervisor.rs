@@ -1,5 +1,5 @@
 use crate::config::IngestConfig;
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
 use std::sync::{
     atomic::{AtomicBool, Ordering},
     Arc,
@@ -7,17 +7,22 @@ use std::sync::{
 use tokio::sync::Mutex;
 use tokio::task::JoinHandle;
 
+use crate::replan::ExchangeReplanner;
 use crate::state::AppState;
-use tracing::{error, info, warn};
+use tracing::{info, warn};
 
+use ucel_registry::ingest::{
+    ConnectionEndHook, ConnectionMetricsHook, ExchangeIngestDriver, IngestConfigRef, IngestPlanRef,
+    IngestRulesRef, IngestRuntimeRef,
+};
 use ucel_subscription_planner::{
     canon_params, extract_ws_ops, generate_plan, generate_plan_v2, load_coverage_v2, load_manifest,
     stable_key,
 };
 use ucel_subscription_store::{SubscriptionRow, SubscriptionStore};
 use ucel_transport::health::{HealthReason, TransportHealth};
-use ucel_transport::obs::StabilityEvent;
-use ucel_transport::ws::connection::{run_ws_connection, ShutdownToken, WsRunConfig};
+use ucel_transport::obs::{StabilityEvent, TransportMetrics};
+use ucel_transport::ws::connection::{ShutdownToken, WsRunConfig};
 use ucel_ws_rules::{load_for_exchange, SupportLevel};
 
 #[derive(Clone)]
@@ -55,6 +60,126 @@ fn should_include_public_crypto(op: &str) -> bool {
     op.starts_with("crypto.public.ws.")
 }
 
+/// Per-connection transport metrics of one exchange, sampled into inbound msgs/sec.
+#[derive(Default)]
+struct ConnLoadSampler {
+    metrics: Arc<parking_lot::Mutex<HashMap<String, Arc<TransportMetrics>>>>,
+    last: HashMap<String, (u64, std::time::Instant)>,
+}
+
+impl ConnLoadSampler {
+    /// Hands every connection of the exchange its metrics; reconnects keep the same set.
+    fn hook(&self) -> ConnectionMetricsHook {
+        let metrics = self.metrics.clone();
+        Arc::new(move |conn_id: &str| {
+            metrics
+                .lock()
+                .entry(conn_id.to_string())
+                .or_insert_with(TransportMetrics::new)
+                .clone()
+        })
+    }
+
+    /// Inbound frames/sec per connection since the previous sample; a connection's first
+    /// sample only sets its baseline.
+    fn sample(&mut self) -> BTreeMap<String, f64> {
+        let now = std::time::Instant::now();
+        let mut rates = BTreeMap::new();
+        for (conn_id, m) in self.metrics.lock().iter() {
+            let frames = m.inbound_frames.load(Ordering::Relaxed);
+            if let Some((prev, at)) = self.last.insert(conn_id.clone(), (frames, now)) {
+                let secs = now.duration_since(at).as_secs_f64();
+                if secs > 0.0 {
+                    rates.insert(conn_id.clone(), frames.saturating_sub(prev) as f64 / secs);
+                }
+            }
+        }
+        rates
+    }
+}
+
+/// A coverage_v2 exchange whose subscriptions follow symbol listings/delistings and observed
+/// per-connection load live.
+struct LiveExchange {
+    replanner: ExchangeReplanner,
+    load: ConnLoadSampler,
+    driver: Arc<dyn ExchangeIngestDriver>,
+    runtime: IngestRuntimeRef,
+    rules: IngestRulesRef,
+    cfg: IngestConfigRef,
+}
+
+impl LiveExchange {
+    /// Refetches symbols and samples connection load; if the symbols changed or a connection
+    /// runs over its msgs/sec cap, applies the plan diff to the store and starts any
+    /// connection the diff opened.
+    async fn refresh(
+        &mut self,
+        store_path: &str,
+        max_conns: usize,
+        state: &AppState,
+    ) -> Result<Option<JoinHandle<()>>, String> {
+        let symbols = self.driver.fetch_symbols().await?;
+        let symbols_changed = self.replanner.set_symbols(&symbols);
+        let exchange = self.replanner.exchange_id().to_string();
+        let mut store = SubscriptionStore::open(store_path)?;
+        let over_cap = self.replanner.observe_load(&store, &self.load.sample())?;
+        if !symbols_changed && !over_cap {
+            return Ok(None);
+        }
+        let replanned = self.replanner.replan(&mut store, max_conns, now_unix_i64());
+        drop(store);
+        if let Some(report) = self.replanner.last_report() {
+            state
+                .plan_reports
+                .write()
+                .insert(exchange.clone(), report.clone());
+        }
+        let diff = replanned?;
+        info!(
+            exchange=%exchange,
+            symbols=%symbols.len(),
+            over_cap=%over_cap,
+            added=%diff.added.len(),
+            removed=%diff.removed.len(),
+            moved=%diff.moved.len(),
+            opened=?diff.opened_conns,
+            closed=?diff.closed_conns,
+            "live replan applied"
+        );
+        state.events.push(StabilityEvent::now(
+            &exchange,
+            "*",
+            "live_replan",
+            serde_json::json!({
+                "symbols": symbols.len(),
+                "over_cap": over_cap,
+                "added": diff.added.len(),
+                "removed": diff.removed.len(),
+                "moved": diff.moved.len(),
+                "opened_conns": diff.opened_conns,
+                "closed_conns": diff.closed_conns,
+            }),
+        ));
+        if diff.opened_conns.is_empty() {
+            return Ok(None);
+        }
+
+        let plan_ref = IngestPlanRef {
+            exchange_id: exchange.clone(),
+            seed_len: diff.added.len() + diff.moved.len(),
+            conn_ids: diff.opened_conns,
+        };
+        let driver = self.driver.clone();
+        let (runtime, rules, cfg) = (self.runtime.clone(), self.rules.clone(), self.cfg.clone());
+        Ok(Some(tokio::spawn(async move {
+            if let Err(e) = driver.run_ws_ingest(plan_ref, runtime, rules, cfg).await {
+                warn!(exchange=%exchange, err=%e, "ws ingest (replanned conns) ended");
+            }
+        })))
+    }
+}
+
 pub async fn run_supervisor(
     cfg: &IngestConfig,
     shutdown: SupervisorShutdown,
@@ -71,18 +196,20 @@ pub async fn run_supervisor(
         "note": "redacted"
     });
 
+    let drivers = crate::drivers::registry();
     let mut handles: Vec<JoinHandle<()>> = Vec::new();
+    let mut live: Vec<LiveExchange> = Vec::new();
+    let live_replan = !cfg.replan_interval.is_zero();
 
     for exchange in &cfg.exchange_allowlist {
         if shutdown.is_triggered() {
             break;
         }
 
-        // Adapter create (now with reason)
-        let adapter = match crate::adapters::create(exchange.as_str()) {
-            Ok(a) => a,
+        let driver: Arc<dyn ExchangeIngestDriver> = match drivers.resolve(exchange.as_str()) {
+            Ok(d) => Arc::from(d),
             Err(e) => {
-                warn!(exchange=%exchange, err=%e, "adapter create failed; skip");
+                warn!(exchange=%exchange, err=%e, "ingest driver resolve failed; skip");
                 continue;
             }
         };
@@ -102,16 +229,54 @@ pub async fn run_supervisor(
 
         // Prefer coverage_v2 if present; else fallback to legacy coverage
         let v2_path = cfg.coverage_v2_dir.join(format!("{exchange}.yaml"));
+        let mut replanner = None;
         let (plan, symbols_len): (ucel_subscription_planner::Plan, usize) = if v2_path.exists() {
             let cov2 = load_coverage_v2(&v2_path)?;
-            let symbols = adapter.fetch_symbols().await?;
+            let symbols = driver.fetch_symbols().await?;
             info!(
                 exchange=%exchange,
                 symbols=%symbols.len(),
                 families=%cov2.families.len(),
                 "symbols loaded (coverage_v2)"
             );
-            let plan = generate_plan_v2(exchange, &cov2, &symbols, &rules);
+            let plan = match generate_plan_v2(exchange, &cov2, &symbols, &rules) {
+                Ok(plan) => plan,
+                Err(e) => {
+                    warn!(exchange=%exchange, err=%e, "coverage_v2 plan failed; skip");
+                    state.events.push(StabilityEvent::now(
+                        exchange,
+                        "*",
+                        "plan_error",
+                        serde_json::json!({"error": e.to_string()}),
+                    ));
+                    state
+                        .plan_reports
+                        .write()
+                        .insert(exchange.clone(), *e.report);
+                    continue;
+                }
+            };
+            if !plan.report.is_clean() {
+                warn!(
+                    exchange=%exchange,
+                    skipped=%plan.report.families_skipped.len(),
+                    "coverage_v2 families skipped"
+                );
+            }
+            if live_replan {
+                let conn_ids: Vec<String> = plan
+                    .conn_plans
+                    .iter()
+                    .map(|cp| cp.conn_id.clone())
+                    .collect();
+                replanner = Some(ExchangeReplanner::new(
+                    exchange,
+                    cov2,
+                    rules.clone(),
+                    &symbols,
+                    &conn_ids,
+                ));
+            }
             (plan, symbols.len())
         } else {
             let manifest_path = cfg.coverage_dir.join(format!("{exchange}.yaml"));
@@ -122,7 +287,7 @@ pub async fn run_supervisor(
                 warn!(exchange=%exchange, "no public crypto ws ops in legacy coverage; skip");
                 continue;
             }
-            let symbols = adapter.fetch_symbols().await?;
+            let symbols = driver.fetch_symbols().await?;
             info!(
                 exchange=%exchange,
                 symbols=%symbols.len(),
@@ -133,6 +298,11 @@ pub async fn run_supervisor(
             (plan, symbols.len())
         };
 
+        state
+            .plan_reports
+            .write()
+            .insert(exchange.clone(), plan.report.clone());
+
         if plan.conn_plans.len() > cfg.max_connections_per_exchange {
             return Err(format!(
                 "too many connections planned: exchange={exchange} conns={} max={}",
@@ -140,6 +310,15 @@ pub async fn run_supervisor(
                 cfg.max_connections_per_exchange
             ));
         }
+        for cp in &plan.conn_plans {
+            info!(
+                exchange=%exchange,
+                conn=%cp.conn_id,
+                streams=%cp.keys.len(),
+                est_msgs_per_sec=%format!("{:.1}", cp.est_msgs_per_sec),
+                "conn plan"
+            );
+        }
 
         // stable_key -> conn_id map
         let conn_by_key: HashMap<String, String> = plan
@@ -180,58 +359,88 @@ pub async fn run_supervisor(
             );
         }
 
-        // Spawn connection tasks
-        for cp in plan.conn_plans.clone() {
+        // Hand the seeded plan to the venue driver; it runs one connection per conn plan
+        let on_connection_end: ConnectionEndHook = {
             let exchange = exchange.clone();
-            let adapter = adapter.clone();
-            let rules = rules.clone();
-            let wal = wal.clone();
-            let store_path = cfg.store_path.clone();
-            let token = shutdown.token();
             let state = state.clone();
-            let run_cfg = WsRunConfig {
-                exchange_id: exchange.clone(),
-                conn_id: cp.conn_id.clone(),
+            Arc::new(move |conn_id: &str, err: &str| {
+                ucel_transport::obs::TransportMetrics::inc(&state.metrics.reconnect_failure);
+                state.events.push(StabilityEvent::now(
+                    &exchange,
+                    conn_id,
+                    "connection_ended",
+                    serde_json::json!({"error": err}),
+                ));
+                warn!(exchange=%exchange, conn=%conn_id, err=%err, "connection ended");
+            })
+        };
+        let plan_ref = IngestPlanRef {
+            exchange_id: exchange.clone(),
+            seed_len: plan.seed.len(),
+            conn_ids: plan
+                .conn_plans
+                .iter()
+                .map(|cp| cp.conn_id.clone())
+                .collect(),
+        };
+        let load = ConnLoadSampler::default();
+        let runtime_ref = IngestRuntimeRef {
+            store_path: cfg
+                .store_path
+                .to_str()
+                .unwrap_or("/tmp/ucel.sqlite")
+                .to_string(),
+            journal_dir: cfg.journal_dir.display().to_string(),
+            wal: wal.clone(),
+            shutdown: shutdown.token(),
+            on_connection_end: Some(on_connection_end),
+            on_connection_start: None,
+            on_connection_exit: None,
+            connection_metrics: replanner.is_some().then(|| load.hook()),
+        };
+        let rules_ref = IngestRulesRef {
+            support_level: format!("{:?}", rules.support_level),
+            rules,
+        };
+        let cfg_ref = IngestConfigRef {
+            enable_private_ws: cfg.enable_private_ws,
+            run: WsRunConfig {
                 max_frame_bytes: cfg.max_frame_bytes,
                 max_inflight_per_conn: cfg.max_inflight_per_conn,
                 connect_timeout: cfg.connect_timeout,
                 idle_timeout: cfg.idle_timeout,
                 reconnect_storm_window: cfg.reconnect_storm_window,
                 reconnect_storm_max: cfg.reconnect_storm_max,
+                // replanned shards that empty out close themselves
+                close_when_drained: replanner.is_some(),
                 ..Default::default()
-            };
-
-            let h = tokio::task::spawn_local(async move {
-                let mut store = match SubscriptionStore::open(
-                    store_path.to_str().unwrap_or("/tmp/ucel.sqlite"),
-                ) {
-                    Ok(s) => s,
-                    Err(e) => {
-                        error!(exchange=%exchange, conn=%run_cfg.conn_id, err=%e, "store open failed");
-                        return;
-                    }
-                };
-
-                if let Err(e) =
-                    run_ws_connection(adapter, rules, &mut store, wal, run_cfg, token).await
-                {
-                    ucel_transport::obs::TransportMetrics::inc(&state.metrics.reconnect_failure);
-                    state.events.push(StabilityEvent::now(
-                        &exchange,
-                        &cp.conn_id,
-                        "connection_ended",
-                        serde_json::json!({"error": e.to_string()}),
-                    ));
-                    warn!(exchange=%exchange, conn=%cp.conn_id, err=%e, "connection ended");
-                }
+            },
+        };
+        if let Some(replanner) = replanner {
+            live.push(LiveExchange {
+                replanner,
+                load,
+                driver: driver.clone(),
+                runtime: runtime_ref.clone(),
+                rules: rules_ref.clone(),
+                cfg: cfg_ref.clone(),
             });
-            handles.push(h);
         }
+        let exchange = exchange.clone();
+        handles.push(tokio::spawn(async move {
+            if let Err(e) = driver
+                .run_ws_ingest(plan_ref, runtime_ref, rules_ref, cfg_ref)
+                .await
+            {
+                warn!(exchange=%exchange, err=%e, "ws ingest ended");
+            }
+        }));
     }
 
     // maintenance + health snapshot loop
     let mut last_maintenance = std::time::Instant::now();
     let mut last_health = std::time::Instant::now();
+    let mut last_replan = std::time::Instant::now();
     while !shutdown.is_triggered() {
         tokio::time::sleep(std::time::Duration::from_millis(250)).await;
         if last_health.elapsed() >= std::time::Duration::from_secs(2) {
@@ -247,6 +456,20 @@ pub async fn run_supervisor(
                     detail: format!("failures={failures}"),
                 });
             }
+            // strict coverage only yields a report with skipped families when it failed
+            let failed_plans: Vec<String> = state
+                .plan_reports
+                .read()
+                .iter()
+                .filter(|(_, r)| r.strict && !r.is_clean())
+                .map(|(ex, _)| ex.clone())
+                .collect();
+            if !failed_plans.is_empty() {
+                reasons.push(HealthReason {
+                    code: "PLAN_FAILED".to_string(),
+                    detail: format!("exchanges={}", failed_plans.join(",")),
+                });
+            }
             let h = if reasons.is_empty() {
                 TransportHealth::healthy()
             } else {
@@ -254,12 +477,30 @@ pub async fn run_supervisor(
             };
             *state.health.write() = h;
         }
+        if live_replan && last_replan.elapsed() >= cfg.replan_interval {
+            last_replan = std::time::Instant::now();
+            let store_path = cfg.store_path.to_str().unwrap_or("/tmp/ucel.sqlite");
+            for ex in live.iter_mut() {
+                match ex
+                    .refresh(store_path, cfg.max_connections_per_exchange, &state)
+                    .await
+                {
+                    Ok(Some(h)) => handles.push(h),
+                    Ok(None) => {}
+                    Err(e) => {
+                        warn!(exchange=%ex.replanner.exchange_id(), err=%e, "live replan skipped")
+                    }
+                }
+            }
+        }
         if last_maintenance.elapsed() >= std::time::Duration::from_secs(300) {
             last_maintenance = std::time::Instant::now();
             if let Ok(mut store) =
                 SubscriptionStore::open(cfg.store_path.to_str().unwrap_or("/tmp/ucel.sqlite"))
             {
                 let _ = store.purge_deadletter_keep_last(5000);
+                // unsubscribed tombstones only matter until the next replan has seen them
+                let _ = store.purge_removed_older_than(now_unix_i64() - 3600);
             }
         }
     }
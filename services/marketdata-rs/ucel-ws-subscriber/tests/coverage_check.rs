@@ -0,0 +1,46 @@
+use std::collections::BTreeMap;
+use std::path::Path;
+
+use ucel_ws_subscriber::coverage_check::check_coverage_dir;
+
+fn write(dir: &Path, name: &str, body: &str) {
+    std::fs::write(dir.join(name), body).unwrap();
+}
+
+#[test]
+fn reports_each_file_without_panicking_on_strict_errors() {
+    let dir = std::env::temp_dir().join(format!("ucel-coverage-check-{}", std::process::id()));
+    std::fs::create_dir_all(&dir).unwrap();
+    write(
+        &dir,
+        "a.yaml",
+        "venue: a\nstrict: true\nfamilies:\n  - id: a.trades\n    requires_symbol: true\n    topic_template: \"{symbol}@trade\"\n",
+    );
+    write(
+        &dir,
+        "b.yaml",
+        "venue: b\nstrict: true\nfamilies:\n  - id: b.kline\n    requires_symbol: false\n    topic_template: \"{pair}@kline\"\n",
+    );
+    write(&dir, "c.yaml", "venue: c\nstrict: true\nfamilies:\n  - id: c.trades\n    requires_symbol: true\n    topic_template: \"{symbol}\"\n");
+    write(&dir, "notes.txt", "ignored");
+
+    let symbols: BTreeMap<String, Vec<String>> = [
+        ("a".to_string(), vec!["BTCUSDT".to_string()]),
+        ("b".to_string(), vec!["BTCUSDT".to_string()]),
+    ]
+    .into_iter()
+    .collect();
+    let checks = check_coverage_dir(&dir, Path::new("/nonexistent"), &symbols).unwrap();
+    std::fs::remove_dir_all(&dir).unwrap();
+
+    let summary: Vec<(&str, bool)> = checks
+        .iter()
+        .map(|c| (c.exchange_id.as_str(), c.ok))
+        .collect();
+    assert_eq!(summary, vec![("a", true), ("b", false), ("c", false)]);
+    assert_eq!(checks[0].report.as_ref().unwrap().streams, 1);
+    let b = checks[1].report.as_ref().unwrap();
+    assert_eq!(b.missing_template_vars[0].var, "pair");
+    assert_eq!(b.symbols_filtered.len(), 1);
+    assert_eq!(checks[2].error.as_deref(), Some("no symbol list for c"));
+}
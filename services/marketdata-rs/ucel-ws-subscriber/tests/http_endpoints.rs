@@ -25,6 +25,7 @@ async fn endpoints_return_json() {
     let body = to_bytes(resp.into_body(), usize::MAX).await.unwrap();
     let v: serde_json::Value = serde_json::from_slice(&body).unwrap();
     assert!(v.get("status").is_some());
+    assert!(v["plan_reports"].is_object());
 
     let resp2 = app
         .oneshot(
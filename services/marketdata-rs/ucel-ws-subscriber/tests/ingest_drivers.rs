@@ -0,0 +1,33 @@
+use ucel_registry::ingest::registered_ingest_driver_ids;
+use ucel_ws_subscriber::drivers::registry;
+
+#[test]
+fn every_registered_venue_id_resolves() {
+    let drivers = registry();
+    for id in registered_ingest_driver_ids() {
+        let driver = drivers.resolve(id).unwrap_or_else(|e| panic!("{id}: {e}"));
+        assert_eq!(driver.exchange_id(), id);
+    }
+}
+
+#[test]
+fn every_coverage_v2_venue_has_a_driver() {
+    let dir = std::path::Path::new(env!("CARGO_MANIFEST_DIR")).join("../../../ucel/coverage_v2");
+    let drivers = registry();
+    for entry in std::fs::read_dir(dir).unwrap() {
+        let path = entry.unwrap().path();
+        if path.extension().and_then(|e| e.to_str()) != Some("yaml") {
+            continue;
+        }
+        let id = path.file_stem().unwrap().to_str().unwrap();
+        assert!(
+            drivers.contains(id),
+            "no ingest driver for coverage_v2/{id}.yaml"
+        );
+    }
+}
+
+#[test]
+fn unknown_venue_is_rejected() {
+    assert!(registry().resolve("no-such-venue").is_err());
+}
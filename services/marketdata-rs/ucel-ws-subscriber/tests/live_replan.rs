@@ -0,0 +1,182 @@
+use std::time::SystemTime;
+
+use ucel_subscription_planner::{canon_params, generate_plan_v2, stable_key, CoverageV2};
+use ucel_subscription_store::{SubscriptionRow, SubscriptionStore};
+use ucel_symbol_core::{Exchange, InstrumentId, MarketType, SymbolStatus};
+use ucel_symbol_store::SymbolEvent;
+use ucel_ws_rules::{load_for_exchange, ExchangeWsRules};
+use ucel_ws_subscriber::replan::ExchangeReplanner;
+
+fn coverage() -> CoverageV2 {
+    serde_json::from_str(
+        r#"{"venue":"x","strict":true,"families":[
+            {"id":"x.public.ws.trades","requires_symbol":true,"topic_template":"trades","weight":20}
+        ]}"#,
+    )
+    .unwrap()
+}
+
+fn rules() -> ExchangeWsRules {
+    let mut r = load_for_exchange(std::path::Path::new("/nonexistent"), "x");
+    r.max_streams_per_conn = Some(2);
+    r
+}
+
+fn symbols(s: &[&str]) -> Vec<String> {
+    s.iter().map(|x| x.to_string()).collect()
+}
+
+/// Seeds the store the way the supervisor does at startup.
+fn seed(store: &mut SubscriptionStore, syms: &[String]) -> Vec<String> {
+    let plan = generate_plan_v2("x", &coverage(), syms, &rules()).unwrap();
+    let mut rows = Vec::new();
+    for cp in &plan.conn_plans {
+        for k in plan
+            .seed
+            .iter()
+            .filter(|k| cp.keys.contains(&stable_key(k)))
+        {
+            rows.push(SubscriptionRow {
+                key: stable_key(k),
+                exchange_id: k.exchange_id.clone(),
+                op_id: k.op_id.clone(),
+                symbol: k.symbol.clone(),
+                params_json: canon_params(&k.params),
+                assigned_conn: Some(cp.conn_id.clone()),
+            });
+        }
+    }
+    store.seed(&rows, 1).unwrap();
+    plan.conn_plans
+        .iter()
+        .map(|cp| cp.conn_id.clone())
+        .collect()
+}
+
+fn conn_of(store: &SubscriptionStore) -> Vec<(String, String)> {
+    store
+        .assigned_rows("x")
+        .unwrap()
+        .into_iter()
+        .map(|r| (r.symbol.unwrap(), r.assigned_conn.unwrap()))
+        .collect()
+}
+
+fn removed(sym: &str) -> SymbolEvent {
+    SymbolEvent::Removed {
+        id: InstrumentId {
+            exchange: Exchange::Other("x".into()),
+            market_type: MarketType::Spot,
+            raw_symbol: sym.into(),
+            expiry: None,
+            strike: None,
+            option_right: None,
+            contract_size: None,
+        },
+        last_known: None,
+        reason: Some("delisted".into()),
+        ts_recv: SystemTime::now(),
+        store_version: 2,
+    }
+}
+
+#[test]
+fn listing_and_delisting_update_running_shards_in_place() {
+    let mut store = SubscriptionStore::open(":memory:").unwrap();
+    let initial = symbols(&["A", "B", "C"]);
+    let conns = seed(&mut store, &initial);
+    assert_eq!(conns, vec!["x-conn-1", "x-conn-2"]);
+    let mut rp = ExchangeReplanner::new("x", coverage(), rules(), &initial, &conns);
+
+    // unchanged universe -> nothing to do
+    assert!(!rp.set_symbols(&initial));
+
+    // load-balanced seed: x-conn-1=[A,C], x-conn-2=[B]; listing D fills the half-empty shard
+    assert!(rp.set_symbols(&symbols(&["A", "B", "C", "D"])));
+    let diff = rp.replan(&mut store, 8, 2).unwrap();
+    assert_eq!(diff.added.len(), 1);
+    assert!(diff.opened_conns.is_empty() && diff.closed_conns.is_empty());
+    assert_eq!(store.state_counts("x", "x-conn-2").unwrap().pending, 2);
+
+    // delisting B and D empties x-conn-2
+    assert!(rp.apply_symbol_events(&[removed("B"), removed("D")]));
+    let diff = rp.replan(&mut store, 8, 3).unwrap();
+    assert_eq!(diff.removed.len(), 2);
+    assert_eq!(diff.closed_conns, vec!["x-conn-2".to_string()]);
+    assert_eq!(store.state_counts("x", "x-conn-2").unwrap().total(), 0);
+
+    // new listings overflow x-conn-1 and never reuse the drained id
+    rp.set_symbols(&symbols(&["A", "C", "E", "F", "G"]));
+    let diff = rp.replan(&mut store, 8, 4).unwrap();
+    assert_eq!(diff.opened_conns, vec!["x-conn-3", "x-conn-4"]);
+    assert_eq!(
+        conn_of(&store),
+        vec![
+            ("A".to_string(), "x-conn-1".to_string()),
+            ("C".to_string(), "x-conn-1".to_string()),
+            ("E".to_string(), "x-conn-3".to_string()),
+            ("F".to_string(), "x-conn-3".to_string()),
+            ("G".to_string(), "x-conn-4".to_string()),
+        ]
+    );
+}
+
+#[test]
+fn replan_over_connection_cap_leaves_store_untouched() {
+    let mut store = SubscriptionStore::open(":memory:").unwrap();
+    let initial = symbols(&["A", "B"]);
+    let conns = seed(&mut store, &initial);
+    let mut rp = ExchangeReplanner::new("x", coverage(), rules(), &initial, &conns);
+
+    let delisted = SymbolEvent::StatusChanged {
+        id: match removed("B") {
+            SymbolEvent::Removed { id, .. } => id,
+            _ => unreachable!(),
+        },
+        from: SymbolStatus::Trading,
+        to: SymbolStatus::Delisted,
+        ts_recv: SystemTime::now(),
+        store_version: 3,
+    };
+    assert!(rp.apply_symbol_events(&[delisted]));
+    rp.set_symbols(&symbols(&["A", "C", "D", "E"]));
+
+    let err = rp.replan(&mut store, 1, 2).unwrap_err();
+    assert!(err.contains("connection cap"), "{err}");
+    assert_eq!(
+        conn_of(&store),
+        vec![
+            ("A".to_string(), "x-conn-1".to_string()),
+            ("B".to_string(), "x-conn-1".to_string()),
+        ]
+    );
+}
+
+#[test]
+fn observed_load_over_cap_moves_streams_to_a_new_connection() {
+    let mut store = SubscriptionStore::open(":memory:").unwrap();
+    let initial = symbols(&["A", "B", "C"]);
+    let conns = seed(&mut store, &initial);
+    let mut capped = rules();
+    capped
+        .safety_profile
+        .as_mut()
+        .unwrap()
+        .max_msgs_per_sec_per_conn = Some(100);
+    let mut rp = ExchangeReplanner::new("x", coverage(), capped, &initial, &conns);
+
+    // x-conn-1=[A,C] within budget: nothing to rebalance
+    let rates = [("x-conn-1".to_string(), 50.0)].into_iter().collect();
+    assert!(!rp.observe_load(&store, &rates).unwrap());
+
+    // 300 msgs/sec on two streams runs over the 100 msgs/sec cap
+    let rates = [("x-conn-1".to_string(), 300.0)].into_iter().collect();
+    assert!(rp.observe_load(&store, &rates).unwrap());
+
+    let diff = rp.replan(&mut store, 8, 2).unwrap();
+    assert!(diff.added.is_empty() && diff.removed.is_empty());
+    assert_eq!(diff.opened_conns, vec!["x-conn-3".to_string()]);
+    assert_eq!(diff.moved.len(), 1);
+    assert_eq!(diff.moved[0].from_conn, "x-conn-1");
+    assert_eq!(diff.moved[0].to_conn, "x-conn-3");
+}
@@ -1,11 +1,13 @@
 use bytes::Bytes;
 use serde::de::DeserializeOwned;
 use serde::{Deserialize, Serialize};
-use std::collections::{HashSet, VecDeque};
+use std::collections::HashSet;
 use std::sync::atomic::{AtomicU64, Ordering};
 use std::sync::Arc;
 use tokio::sync::{mpsc, Mutex};
-use ucel_core::{Decimal, OrderBookDelta, OrderBookLevel, OrderBookSnapshot, Side, TradeEvent};
+use ucel_core::{
+    Decimal, OrderBook, OrderBookDelta, OrderBookLevel, OrderBookSnapshot, Side, TradeEvent,
+};
 use ucel_core::{ErrorCode, Exchange, OpName, UcelError};
 use ucel_transport::{
     enforce_auth_boundary, HttpRequest, RequestContext, RetryPolicy, Transport, WsConnectRequest,
@@ -421,74 +423,45 @@ pub enum OrderBookHealth {
 
 #[derive(Debug, Default)]
 pub struct OrderBookResyncEngine {
-    buffered_deltas: VecDeque<OrderBookDelta>,
-    expected_next: Option<u64>,
+    book: OrderBook,
     health: OrderBookHealth,
 }
 
 impl OrderBookResyncEngine {
+    /// snapshot 前の差分は buffer され、snapshot 適用時に再生される
     pub fn ingest_delta(&mut self, delta: OrderBookDelta) -> Result<(), UcelError> {
-        if let Some(next) = self.expected_next {
-            if delta.sequence_start != next {
-                self.expected_next = None;
-                self.buffered_deltas.clear();
-                self.health = OrderBookHealth::Degraded;
-                return Err(UcelError::new(
-                    ErrorCode::Desync,
-                    "orderbook gap detected; immediate resync required",
-                ));
-            }
-            self.expected_next = Some(delta.sequence_end + 1);
-        } else {
-            self.buffered_deltas.push_back(delta);
+        if let Err(e) = self.book.apply_update((&delta).into()) {
+            self.health = OrderBookHealth::Degraded;
+            return Err(UcelError::new(
+                ErrorCode::Desync,
+                format!("orderbook gap detected; immediate resync required ({e})"),
+            ));
         }
         Ok(())
     }
 
     pub fn apply_snapshot(
         &mut self,
-        mut snapshot: OrderBookSnapshot,
+        snapshot: OrderBookSnapshot,
     ) -> Result<OrderBookSnapshot, UcelError> {
-        self.expected_next = Some(snapshot.sequence + 1);
-        while let Some(delta) = self.buffered_deltas.pop_front() {
-            if delta.sequence_end <= snapshot.sequence {
-                continue;
-            }
-            if delta.sequence_start > snapshot.sequence + 1 {
-                self.health = OrderBookHealth::Degraded;
-                return Err(UcelError::new(ErrorCode::Desync, "snapshot mismatch"));
-            }
-            merge_levels(&mut snapshot.bids, delta.bids, true);
-            merge_levels(&mut snapshot.asks, delta.asks, false);
-            snapshot.sequence = delta.sequence_end;
+        if let Err(e) = self.book.apply_snapshot_levels(&snapshot) {
+            self.health = OrderBookHealth::Degraded;
+            return Err(UcelError::new(
+                ErrorCode::Desync,
+                format!("snapshot/delta mismatch; resync required ({e})"),
+            ));
         }
         self.health = OrderBookHealth::Recovered;
-        self.expected_next = Some(snapshot.sequence + 1);
-        Ok(snapshot)
+        Ok(self.book.to_order_book_snapshot())
     }
 
-    pub fn health(&self) -> OrderBookHealth {
-        self.health
+    pub fn book(&self) -> &OrderBook {
+        &self.book
     }
-}
 
-fn merge_levels(target: &mut Vec<OrderBookLevel>, updates: Vec<OrderBookLevel>, desc: bool) {
-    for update in updates {
-        if let Some(existing) = target.iter_mut().find(|x| x.price == update.price) {
-            existing.qty = update.qty;
-        } else {
-            target.push(update);
-        }
+    pub fn health(&self) -> OrderBookHealth {
+        self.health
     }
-    target.retain(|x| x.qty > Decimal::ZERO);
-    target.sort_by(|a, b| {
-        if desc {
-            b.price.partial_cmp(&a.price)
-        } else {
-            a.price.partial_cmp(&b.price)
-        }
-        .unwrap_or(std::cmp::Ordering::Equal)
-    });
 }
 
 #[derive(Debug, Clone)]
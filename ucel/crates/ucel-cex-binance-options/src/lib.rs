@@ -1,12 +1,14 @@
 use bytes::Bytes;
 use serde::de::DeserializeOwned;
 use serde::{Deserialize, Serialize};
-use std::collections::{HashSet, VecDeque};
+use std::collections::HashSet;
 use std::sync::atomic::{AtomicU64, Ordering};
 use std::sync::Arc;
 use tokio::sync::mpsc;
 use ucel_core::decimal::serde::deserialize_decimal_observation;
-use ucel_core::{Decimal, ErrorCode, OpName, UcelError};
+use ucel_core::{
+    Decimal, ErrorCode, OpName, OrderBook, OrderBookError, OrderBookUpdate, UcelError,
+};
 use ucel_transport::{
     enforce_auth_boundary, HttpRequest, RequestContext, Transport, WsConnectRequest,
 };
@@ -263,47 +265,77 @@ pub struct OrderBookDelta {
     pub stream: String,
     pub prev_update_id: u64,
     pub update_id: u64,
+    pub bids: Vec<(Decimal, Decimal)>,
+    pub asks: Vec<(Decimal, Decimal)>,
 }
 
+impl From<&OrderBookDelta> for OrderBookUpdate {
+    /// pu / u は (pu, u] の範囲として扱う。
+    /// `SequencePolicy::Contiguous` の下で「pu が直前の u と一致する」検査になる。
+    fn from(d: &OrderBookDelta) -> Self {
+        OrderBookUpdate::new(d.bids.clone(), d.asks.clone())
+            .with_range(d.prev_update_id + 1, d.update_id)
+            .with_prev_sequence(d.prev_update_id)
+    }
+}
+
+/// ucel-core の共有板エンジンで depth を同期する。
+/// snapshot 前の差分は buffer され、snapshot 適用時に lastUpdateId 以降だけ再生される。
 pub struct OrderBookResyncEngine {
-    next_expected_prev: Option<u64>,
-    buffered: VecDeque<OrderBookDelta>,
+    pub book: OrderBook,
     pub health: OrderBookHealth,
     metrics: Arc<WsMetrics>,
 }
 impl OrderBookResyncEngine {
     pub fn new(metrics: Arc<WsMetrics>) -> Self {
         Self {
-            next_expected_prev: None,
-            buffered: VecDeque::new(),
+            book: OrderBook::default(),
             health: OrderBookHealth::Ok,
             metrics,
         }
     }
-    pub fn apply_snapshot(&mut self, last_update_id: u64) {
-        self.next_expected_prev = Some(last_update_id);
-        self.health = OrderBookHealth::Ok;
-        self.metrics
-            .ws_orderbook_recovered_total
-            .fetch_add(1, Ordering::Relaxed);
-    }
-    pub fn ingest_delta(&mut self, delta: OrderBookDelta) -> Result<(), UcelError> {
-        if let Some(next_prev) = self.next_expected_prev {
-            if delta.prev_update_id != next_prev {
-                self.health = OrderBookHealth::Degraded;
-                self.next_expected_prev = None;
-                self.buffered.clear();
+    pub fn apply_snapshot(
+        &mut self,
+        last_update_id: u64,
+        bids: &[(Decimal, Decimal)],
+        asks: &[(Decimal, Decimal)],
+    ) -> Result<usize, UcelError> {
+        match self.book.apply_snapshot(Some(last_update_id), bids, asks) {
+            Ok(replayed) => {
+                self.health = OrderBookHealth::Ok;
                 self.metrics
-                    .ws_orderbook_gap_total
+                    .ws_orderbook_recovered_total
                     .fetch_add(1, Ordering::Relaxed);
+                Ok(replayed)
+            }
+            Err(e) => {
+                self.health = OrderBookHealth::Degraded;
                 self.metrics
                     .ws_orderbook_resync_total
                     .fetch_add(1, Ordering::Relaxed);
-                return Err(UcelError::new(ErrorCode::Desync, "gap detected"));
+                Err(e.into())
+            }
+        }
+    }
+    pub fn ingest_delta(&mut self, delta: OrderBookDelta) -> Result<(), UcelError> {
+        if let Err(e) = self.book.apply_update((&delta).into()) {
+            self.health = OrderBookHealth::Degraded;
+            if matches!(
+                e,
+                OrderBookError::Gap { .. } | OrderBookError::MissingSequence
+            ) {
+                self.metrics
+                    .ws_orderbook_gap_total
+                    .fetch_add(1, Ordering::Relaxed);
             }
-            self.next_expected_prev = Some(delta.update_id);
+            self.metrics
+                .ws_orderbook_resync_total
+                .fetch_add(1, Ordering::Relaxed);
+            return Err(UcelError::new(
+                ErrorCode::Desync,
+                format!("gap detected ({e})"),
+            ));
         }
-        self.buffered.push_back(delta);
         Ok(())
     }
 }
@@ -445,10 +477,16 @@ impl BinanceOptionsWsAdapter {
             }
             "options.public.ws.depth" => {
                 let m: DepthWs = parse_json(raw)?;
+                let levels = OrderBookUpdate::from_str_levels(
+                    &options_levels(&m.bids),
+                    &options_levels(&m.asks),
+                )?;
                 Ok(MarketEvent::DepthDelta(OrderBookDelta {
                     stream: m.stream,
                     prev_update_id: m.prev_update_id,
                     update_id: m.update_id,
+                    bids: levels.bids,
+                    asks: levels.asks,
                 }))
             }
             "options.public.ws.markprice" => {
@@ -523,6 +561,17 @@ struct DepthWs {
     prev_update_id: u64,
     #[serde(rename = "u")]
     update_id: u64,
+    #[serde(rename = "b", default)]
+    bids: Vec<[String; 2]>,
+    #[serde(rename = "a", default)]
+    asks: Vec<[String; 2]>,
+}
+
+fn options_levels(levels: &[[String; 2]]) -> Vec<(&str, &str)> {
+    levels
+        .iter()
+        .map(|x| (x[0].as_str(), x[1].as_str()))
+        .collect()
 }
 #[derive(Debug, Deserialize)]
 struct MarkPriceWs {
@@ -617,24 +666,32 @@ mod tests {
     fn orderbook_gap_triggers_resync_recover() {
         let metrics = Arc::new(WsMetrics::default());
         let mut engine = OrderBookResyncEngine::new(metrics.clone());
-        engine.apply_snapshot(100);
-        engine
-            .ingest_delta(OrderBookDelta {
-                stream: "s".into(),
-                prev_update_id: 100,
-                update_id: 101,
-            })
+        let d = |s: &str| s.parse::<Decimal>().unwrap();
+        let delta = |pu: u64, u: u64, bid: &str| OrderBookDelta {
+            stream: "s".into(),
+            prev_update_id: pu,
+            update_id: u,
+            bids: vec![(d(bid), d("1"))],
+            asks: vec![],
+        };
+        // snapshot 前の差分は buffer され、lastUpdateId を跨ぐものから再生される
+        engine.ingest_delta(delta(95, 98, "9")).unwrap();
+        engine.ingest_delta(delta(98, 103, "10")).unwrap();
+        let replayed = engine
+            .apply_snapshot(100, &[(d("8"), d("1"))], &[(d("20"), d("1"))])
             .unwrap();
-        let err = engine
-            .ingest_delta(OrderBookDelta {
-                stream: "s".into(),
-                prev_update_id: 999,
-                update_id: 102,
-            })
-            .unwrap_err();
+        assert_eq!(replayed, 1);
+        assert_eq!(engine.book.sequence(), Some(103));
+        assert_eq!(engine.book.best_bid(), Some((d("10"), d("1"))));
+
+        engine.ingest_delta(delta(103, 110, "11")).unwrap();
+        let err = engine.ingest_delta(delta(999, 1002, "12")).unwrap_err();
         assert_eq!(err.code, ErrorCode::Desync);
+        assert_eq!(engine.health, OrderBookHealth::Degraded);
         assert_eq!(metrics.ws_orderbook_gap_total.load(Ordering::Relaxed), 1);
-        engine.apply_snapshot(200);
+        engine
+            .apply_snapshot(200, &[(d("8"), d("1"))], &[(d("20"), d("1"))])
+            .unwrap();
         assert_eq!(engine.health, OrderBookHealth::Ok);
     }
 
@@ -680,7 +737,7 @@ mod tests {
             ),
             (
                 "options.public.ws.depth",
-                Bytes::from_static(br#"{"stream":"BTC@depth","pu":10,"u":11}"#),
+                Bytes::from_static(br#"{"stream":"BTC@depth","pu":10,"u":11,"b":[["100.5","1"]],"a":[["101","2"]]}"#),
             ),
             (
                 "options.public.ws.markprice",
@@ -1,3 +1,14 @@
-pub fn run_ingest() -> Result<(), String> {
-    Ok(())
+use std::sync::Arc;
+use ucel_registry::ingest::{IngestDriverRegistry, WsAdapterIngestDriver};
+
+use crate::ws::BinanceOptionsWsAdapter;
+
+/// `binance-options` の ingest driver を registry に登録する
+pub fn register_ingest_drivers(registry: &mut IngestDriverRegistry) {
+    registry.register("binance-options", || {
+        Ok(
+            WsAdapterIngestDriver::new("binance-options", Arc::new(BinanceOptionsWsAdapter::new()))
+                .boxed(),
+        )
+    });
 }
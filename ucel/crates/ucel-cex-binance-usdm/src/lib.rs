@@ -1,10 +1,12 @@
 use bytes::Bytes;
 use serde::{de::DeserializeOwned, Deserialize};
-use std::collections::{BTreeMap, HashSet};
+use std::collections::HashSet;
 use std::sync::Arc;
 use tokio::sync::mpsc;
 use tracing::info;
-use ucel_core::{ErrorCode, OpName, UcelError};
+use ucel_core::{
+    BookSide, ErrorCode, OpName, OrderBook, OrderBookUpdate, SequencePolicy, UcelError,
+};
 use ucel_transport::{
     enforce_auth_boundary, HttpRequest, RequestContext, RetryPolicy, Transport, WsConnectRequest,
 };
@@ -225,33 +227,49 @@ pub struct LiquidationOrder {
     pub s: String,
 }
 
-#[derive(Debug, Clone, Default)]
+#[derive(Debug, Clone)]
 pub struct OrderBookSync {
-    pub last_update_id: u64,
-    pub bids: BTreeMap<String, String>,
-    pub asks: BTreeMap<String, String>,
+    /// diff の U / u は snapshot の lastUpdateId を跨いでよい（`SequencePolicy::Overlapping`）
+    pub book: OrderBook,
     pub degraded: bool,
 }
 
+impl Default for OrderBookSync {
+    fn default() -> Self {
+        Self {
+            book: OrderBook::default().with_sequence_policy(SequencePolicy::Overlapping),
+            degraded: false,
+        }
+    }
+}
+
+fn usdm_levels(levels: &[[String; 2]]) -> Vec<(&str, &str)> {
+    levels
+        .iter()
+        .map(|x| (x[0].as_str(), x[1].as_str()))
+        .collect()
+}
+
 impl OrderBookSync {
+    pub fn last_update_id(&self) -> u64 {
+        self.book.sequence().unwrap_or_default()
+    }
+
     pub fn apply_snapshot(
         &mut self,
         last_update_id: u64,
         bids: Vec<[String; 2]>,
         asks: Vec<[String; 2]>,
     ) {
-        self.last_update_id = last_update_id;
-        self.bids = bids
-            .into_iter()
-            .map(|x| (x[0].clone(), x[1].clone()))
-            .collect();
-        self.asks = asks
-            .into_iter()
-            .map(|x| (x[0].clone(), x[1].clone()))
-            .collect();
-        self.degraded = false;
+        let applied = OrderBookUpdate::from_str_levels(&usdm_levels(&bids), &usdm_levels(&asks))
+            .and_then(|u| {
+                self.book
+                    .apply_snapshot(Some(last_update_id), &u.bids, &u.asks)
+            });
+        self.degraded = applied.is_err();
     }
 
+    /// snapshot 前の diff は buffer し、snapshot 適用時に lastUpdateId 以降だけ再生する
     pub fn apply_diff(
         &mut self,
         first_id: u64,
@@ -260,26 +278,22 @@ impl OrderBookSync {
         asks: Vec<[String; 2]>,
         metrics: &mut WsMetrics,
     ) {
-        if self.last_update_id == 0
-            || first_id > self.last_update_id + 1
-            || final_id < self.last_update_id
-        {
+        let applied = OrderBookUpdate::from_str_levels(&usdm_levels(&bids), &usdm_levels(&asks))
+            .and_then(|u| self.book.apply_update(u.with_range(first_id, final_id)));
+        if applied.is_err() {
             self.degraded = true;
             metrics.ws_orderbook_gap_total += 1;
-            return;
-        }
-        self.last_update_id = final_id;
-        for level in bids {
-            self.bids.insert(level[0].clone(), level[1].clone());
-        }
-        for level in asks {
-            self.asks.insert(level[0].clone(), level[1].clone());
         }
     }
 
+    /// REST snapshot の lastUpdateId で同期点を張り直す（保持中の段はそのまま使う）
     pub fn resync(&mut self, snapshot_last_update_id: u64) {
-        self.last_update_id = snapshot_last_update_id;
-        self.degraded = false;
+        let bids: Vec<_> = self.book.levels(BookSide::Bid).collect();
+        let asks: Vec<_> = self.book.levels(BookSide::Ask).collect();
+        self.degraded = self
+            .book
+            .apply_snapshot(Some(snapshot_last_update_id), &bids, &asks)
+            .is_err();
     }
 }
 
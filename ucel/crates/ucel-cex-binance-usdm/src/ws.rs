@@ -67,6 +67,18 @@ impl WsVenueAdapter for BinanceUsdmWsAdapter {
         }])
     }
 
+    fn build_unsubscribe(
+        &self,
+        op_id: &str,
+        symbol: &str,
+        params: &Value,
+    ) -> Result<Vec<OutboundMsg>, String> {
+        let topic = topic_from_params(op_id, symbol, params)?;
+        Ok(vec![OutboundMsg {
+            text: json!({"method":"UNSUBSCRIBE","params":[topic],"id":1}).to_string(),
+        }])
+    }
+
     fn classify_inbound(&self, raw: &[u8]) -> InboundClass {
         if InboundJsonGuard::default().enforce(raw).is_err() {
             return InboundClass::Unknown;
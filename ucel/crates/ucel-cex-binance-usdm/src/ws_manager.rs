@@ -1,3 +1,14 @@
-pub fn run_ingest() -> Result<(), String> {
-    Ok(())
+use std::sync::Arc;
+use ucel_registry::ingest::{IngestDriverRegistry, WsAdapterIngestDriver};
+
+use crate::ws::BinanceUsdmWsAdapter;
+
+/// `binance-usdm` の ingest driver を registry に登録する
+pub fn register_ingest_drivers(registry: &mut IngestDriverRegistry) {
+    registry.register("binance-usdm", || {
+        Ok(
+            WsAdapterIngestDriver::new("binance-usdm", Arc::new(BinanceUsdmWsAdapter::new()))
+                .boxed(),
+        )
+    });
 }
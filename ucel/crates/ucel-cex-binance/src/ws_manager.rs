@@ -1,3 +1,20 @@
-pub fn run_ingest() -> Result<(), String> {
-    Ok(())
+use std::sync::Arc;
+use ucel_registry::ingest::{IngestDriverRegistry, WsAdapterIngestDriver};
+
+use crate::ws::BinanceSpotWsAdapter;
+
+/// `binance` 系の ingest driver を registry に登録する（素の `binance` は `binance-spot` の別名）
+pub fn register_ingest_drivers(registry: &mut IngestDriverRegistry) {
+    register(registry, "binance", BinanceSpotWsAdapter::new);
+    register(registry, "binance-spot", BinanceSpotWsAdapter::new);
+}
+
+fn register(
+    registry: &mut IngestDriverRegistry,
+    id: &'static str,
+    adapter: fn() -> BinanceSpotWsAdapter,
+) {
+    registry.register(id, move || {
+        Ok(WsAdapterIngestDriver::new(id, Arc::new(adapter())).boxed())
+    });
 }
@@ -2,12 +2,12 @@ pub mod private;
 use bytes::Bytes;
 use serde::de::DeserializeOwned;
 use serde::Deserialize;
-use std::collections::{BTreeMap, HashSet, VecDeque};
+use std::collections::{BTreeMap, HashSet};
 use std::sync::atomic::{AtomicU64, Ordering};
 use std::sync::Arc;
 use tokio::sync::mpsc;
 use ucel_core::{
-    Decimal, ErrorCode, OpName, OrderBookDelta, OrderBookLevel, OrderBookSnapshot, Side,
+    Decimal, ErrorCode, OpName, OrderBook, OrderBookDelta, OrderBookLevel, OrderBookSnapshot, Side,
     TradeEvent, UcelError,
 };
 use ucel_transport::security::{EndpointAllowlist, SubdomainPolicy};
@@ -247,31 +247,23 @@ impl BitbankWsAdapter {
 #[derive(Debug, Default)]
 pub struct OrderbookResync {
     pub degraded: bool,
-    next: Option<u64>,
-    pending: VecDeque<OrderBookDelta>,
+    book: OrderBook,
 }
 impl OrderbookResync {
     pub fn on_delta(&mut self, d: OrderBookDelta) -> Result<(), UcelError> {
-        if let Some(next) = self.next {
-            if d.sequence_start != next {
-                self.degraded = true;
-                self.next = None;
-                self.pending.clear();
-                return Err(UcelError::new(ErrorCode::Desync, "gap"));
-            }
-            self.next = Some(d.sequence_end + 1);
-        } else {
-            self.pending.push_back(d);
+        if let Err(e) = self.book.apply_update((&d).into()) {
+            self.degraded = true;
+            return Err(UcelError::new(ErrorCode::Desync, format!("gap: {e}")));
         }
         Ok(())
     }
-    pub fn apply_snapshot(&mut self, mut s: OrderBookSnapshot) -> OrderBookSnapshot {
-        self.next = Some(s.sequence + 1);
-        while let Some(d) = self.pending.pop_front() {
-            s.sequence = d.sequence_end;
-        }
-        self.degraded = false;
-        s
+    /// snapshot に buffer 済みの差分を重ねた板を返す（橋渡しできなければ degraded）
+    pub fn apply_snapshot(&mut self, s: OrderBookSnapshot) -> OrderBookSnapshot {
+        self.degraded = self.book.apply_snapshot_levels(&s).is_err();
+        self.book.to_order_book_snapshot()
+    }
+    pub fn book(&self) -> &OrderBook {
+        &self.book
     }
 }
 
@@ -1,3 +1,12 @@
-pub fn run_ingest() -> Result<(), String> {
-    Ok(())
+use ucel_registry::ingest::{IngestDriverRegistry, UnsupportedWsIngestDriver};
+
+use crate::symbols::fetch_symbols;
+
+/// `bitbank` の ingest driver を registry に登録する
+///
+/// WsVenueAdapter が未実装のため、symbol 取得のみ提供し run_ws_ingest は Err を返す。
+pub fn register_ingest_drivers(registry: &mut IngestDriverRegistry) {
+    registry.register("bitbank", || {
+        Ok(UnsupportedWsIngestDriver::new("bitbank", fetch_symbols).boxed())
+    });
 }
@@ -1,11 +1,11 @@
 pub mod private;
 use bytes::Bytes;
 use serde::Deserialize;
-use std::collections::{BTreeMap, HashMap, HashSet};
+use std::collections::{BTreeMap, HashSet};
 use std::sync::Arc;
 use tokio::sync::mpsc;
 use tracing::info;
-use ucel_core::{ErrorCode, OpName, UcelError};
+use ucel_core::{ErrorCode, OpName, OrderBook, OrderBookUpdate, UcelError};
 use ucel_transport::security::{EndpointAllowlist, SubdomainPolicy};
 use ucel_transport::{enforce_auth_boundary, HttpRequest, RequestContext, RetryPolicy, Transport};
 use uuid::Uuid;
@@ -346,9 +346,19 @@ impl WsBackpressure {
 #[derive(Debug, Default)]
 pub struct OrderbookResyncState {
     pub degraded: bool,
-    sequence: Option<u64>,
-    pub bids: HashMap<String, String>,
-    pub asks: HashMap<String, String>,
+    pub book: OrderBook,
+}
+
+fn board_update(msg: &BoardMessage) -> Result<OrderBookUpdate, UcelError> {
+    let levels = |ls: &[BoardLevel]| -> Vec<(String, String)> {
+        ls.iter()
+            .map(|l| (l.price.clone(), l.size.clone()))
+            .collect()
+    };
+    Ok(
+        OrderBookUpdate::from_str_levels(&levels(&msg.bids), &levels(&msg.asks))?
+            .with_sequence(msg.mid_price as u64),
+    )
 }
 
 impl OrderbookResyncState {
@@ -360,9 +370,9 @@ impl OrderbookResyncState {
         let msg: BoardMessage = serde_json::from_slice(snapshot_json).map_err(|e| {
             UcelError::new(ErrorCode::Internal, format!("snapshot parse error: {e}"))
         })?;
-        self.sequence = Some(msg.mid_price as u64);
-        self.bids = msg.bids.into_iter().map(|l| (l.price, l.size)).collect();
-        self.asks = msg.asks.into_iter().map(|l| (l.price, l.size)).collect();
+        let update = board_update(&msg)?;
+        self.book
+            .apply_snapshot(update.final_sequence, &update.bids, &update.asks)?;
         if self.degraded {
             metrics.ws_orderbook_recovered_total += 1;
         }
@@ -377,32 +387,15 @@ impl OrderbookResyncState {
     ) -> Result<(), UcelError> {
         let msg: BoardMessage = serde_json::from_slice(delta_json)
             .map_err(|e| UcelError::new(ErrorCode::Internal, format!("delta parse error: {e}")))?;
-        let next = msg.mid_price as u64;
-        let expected = self.sequence.map(|seq| seq.saturating_add(1));
-        if expected.is_none() || Some(next) != expected {
+        if let Err(e) = self.book.apply_update(board_update(&msg)?) {
             self.degraded = true;
             metrics.ws_orderbook_gap_total += 1;
             metrics.ws_orderbook_resync_total += 1;
             return Err(UcelError::new(
                 ErrorCode::Desync,
-                "orderbook gap: resync required",
+                format!("orderbook gap: resync required ({e})"),
             ));
         }
-        self.sequence = Some(next);
-        for l in msg.bids {
-            if l.size == "0" {
-                self.bids.remove(&l.price);
-            } else {
-                self.bids.insert(l.price, l.size);
-            }
-        }
-        for l in msg.asks {
-            if l.size == "0" {
-                self.asks.remove(&l.price);
-            } else {
-                self.asks.insert(l.price, l.size);
-            }
-        }
         Ok(())
     }
 }
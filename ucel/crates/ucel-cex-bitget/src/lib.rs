@@ -317,6 +317,7 @@ mod tests {
 }
 
 pub mod channels;
+pub mod orderbook;
 pub mod symbols;
 pub mod ws;
 pub mod ws_manager;
@@ -0,0 +1,127 @@
+use serde::Deserialize;
+use ucel_core::{
+    BookSide, ErrorCode, IngestResumeDirective, OrderBook, OrderBookUpdate, SequencePolicy,
+    UcelError,
+};
+use ucel_transport::ws::integrity::{
+    book_crc32, checksum_levels, interleaved_checksum_payload, BookChecksumGuard,
+};
+
+/// Bitget `books`（全量 + 差分）の checksum は bid / ask 上位 25 段を交互に連結した CRC32（i32）。
+/// books1 / books5 / books15 は毎回 snapshot なので checksum は 0 固定で照合しない。
+pub const BITGET_BOOK_CHECKSUM_DEPTH: usize = 25;
+
+#[derive(Debug, Deserialize)]
+struct BitgetBooksEnvelope {
+    #[serde(default)]
+    action: Option<String>,
+    #[serde(default)]
+    arg: Option<BitgetBooksArg>,
+    #[serde(default)]
+    data: Vec<BitgetBooksData>,
+}
+
+#[derive(Debug, Deserialize)]
+struct BitgetBooksArg {
+    #[serde(rename = "instId", default)]
+    inst_id: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct BitgetBooksData {
+    #[serde(default)]
+    asks: Vec<(String, String)>,
+    #[serde(default)]
+    bids: Vec<(String, String)>,
+    #[serde(default)]
+    checksum: Option<i64>,
+    #[serde(default)]
+    seq: Option<u64>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct BitgetBookData {
+    pub inst_id: Option<String>,
+    pub snapshot: bool,
+    pub seq: Option<u64>,
+    pub bids: Vec<(String, String)>,
+    pub asks: Vec<(String, String)>,
+    pub checksum: Option<i64>,
+}
+
+pub fn parse_bitget_books_payload(raw: &str) -> Result<Option<BitgetBookData>, UcelError> {
+    let msg: BitgetBooksEnvelope = serde_json::from_str(raw)
+        .map_err(|e| UcelError::new(ErrorCode::Internal, format!("ws json parse error: {e}")))?;
+    let Some(data) = msg.data.into_iter().next() else {
+        return Ok(None);
+    };
+    Ok(Some(BitgetBookData {
+        inst_id: msg.arg.and_then(|a| a.inst_id),
+        snapshot: msg.action.as_deref() == Some("snapshot"),
+        seq: data.seq,
+        bids: data.bids,
+        asks: data.asks,
+        checksum: data.checksum,
+    }))
+}
+
+#[derive(Debug, Clone)]
+pub struct BitgetBookState {
+    pub seq: Option<u64>,
+    pub degraded: bool,
+    /// seq は連番保証が無いので照合せず、欠落検知は checksum に任せる
+    pub book: OrderBook,
+    pub checksum_guard: BookChecksumGuard,
+    /// checksum 不一致で要求された復旧指示（snapshot 適用でクリア）
+    pub resync_directive: Option<IngestResumeDirective>,
+}
+
+impl Default for BitgetBookState {
+    fn default() -> Self {
+        Self {
+            seq: None,
+            degraded: false,
+            book: OrderBook::default().with_sequence_policy(SequencePolicy::None),
+            checksum_guard: BookChecksumGuard::default(),
+            resync_directive: None,
+        }
+    }
+}
+
+impl BitgetBookState {
+    pub fn apply(&mut self, book: &BitgetBookData) {
+        let update = match OrderBookUpdate::from_str_levels(&book.bids, &book.asks) {
+            Ok(u) => u,
+            Err(_) => {
+                self.degraded = true;
+                return;
+            }
+        };
+        if book.snapshot {
+            self.resync_directive = None;
+            self.degraded = self
+                .book
+                .apply_snapshot(None, &update.bids, &update.asks)
+                .is_err();
+        } else if self.book.apply_update(update).is_err() {
+            self.degraded = true;
+        }
+        self.seq = book.seq.or(self.seq);
+
+        // 0 は「checksum なし」
+        let Some(expected) = book.checksum.filter(|c| *c != 0) else {
+            return;
+        };
+        let computed = self.checksum() as u32;
+        if let Err(directive) = self.checksum_guard.verify(expected as i32 as u32, computed) {
+            self.degraded = true;
+            self.resync_directive = Some(directive);
+        }
+    }
+
+    pub fn checksum(&self) -> i32 {
+        let bids = checksum_levels(self.book.top(BookSide::Bid, BITGET_BOOK_CHECKSUM_DEPTH));
+        let asks = checksum_levels(self.book.top(BookSide::Ask, BITGET_BOOK_CHECKSUM_DEPTH));
+        book_crc32(&interleaved_checksum_payload(&bids, &asks)) as i32
+    }
+}
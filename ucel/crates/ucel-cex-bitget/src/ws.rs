@@ -103,11 +103,12 @@ impl BitgetWsAdapter {
         Value::Object(obj_sorted).to_string()
     }
 
-    fn build_subscribe_payload(&self, topic: &str) -> Result<String, String> {
+    /// `op` is "subscribe" or "unsubscribe"; both take the same args.
+    fn build_op_payload(&self, op: &str, topic: &str) -> Result<String, String> {
         let (inst_type, channel, inst_id) = Self::parse_topic(topic)?;
         // Subscribe args list<Object>: {instType, channel, instId} :contentReference[oaicite:8]{index=8}
         Ok(json!({
-            "op": "subscribe",
+            "op": op,
             "args": [{
                 "instType": inst_type,
                 "channel": channel,
@@ -116,6 +117,27 @@ impl BitgetWsAdapter {
         })
         .to_string())
     }
+
+    /// params["_topic"], checked against this adapter's instType.
+    fn checked_topic<'a>(&self, op_id: &str, params: &'a Value) -> Result<&'a str, String> {
+        let topic = params
+            .get("_topic")
+            .and_then(|v| v.as_str())
+            .ok_or_else(|| format!("missing params._topic (planner_v2 required): op_id={op_id}"))?;
+
+        // Ensure topic instType matches adapter kind (safety)
+        let (inst_type, _channel, _inst_id) = Self::parse_topic(topic)?;
+        if inst_type != self.inst_type() {
+            return Err(format!(
+                "instType mismatch: adapter={} expects {} but got {} in _topic={}",
+                self.exchange_id_str(),
+                self.inst_type(),
+                inst_type,
+                topic
+            ));
+        }
+        Ok(topic)
+    }
 }
 
 /// Decide op_id / interval / weight from (instType, channel).
@@ -183,24 +205,19 @@ impl WsVenueAdapter for BitgetWsAdapter {
         _symbol: &str,
         params: &Value,
     ) -> Result<Vec<OutboundMsg>, String> {
-        let topic = params
-            .get("_topic")
-            .and_then(|v| v.as_str())
-            .ok_or_else(|| format!("missing params._topic (planner_v2 required): op_id={op_id}"))?;
-
-        // Ensure topic instType matches adapter kind (safety)
-        let (inst_type, _channel, _inst_id) = Self::parse_topic(topic)?;
-        if inst_type != self.inst_type() {
-            return Err(format!(
-                "instType mismatch: adapter={} expects {} but got {} in _topic={}",
-                self.exchange_id_str(),
-                self.inst_type(),
-                inst_type,
-                topic
-            ));
-        }
+        let topic = self.checked_topic(op_id, params)?;
+        let payload = self.build_op_payload("subscribe", topic)?;
+        Ok(vec![OutboundMsg { text: payload }])
+    }
 
-        let payload = self.build_subscribe_payload(topic)?;
+    fn build_unsubscribe(
+        &self,
+        op_id: &str,
+        _symbol: &str,
+        params: &Value,
+    ) -> Result<Vec<OutboundMsg>, String> {
+        let topic = self.checked_topic(op_id, params)?;
+        let payload = self.build_op_payload("unsubscribe", topic)?;
         Ok(vec![OutboundMsg { text: payload }])
     }
 
@@ -1,3 +1,35 @@
-pub fn run_ingest() -> Result<(), String> {
-    Ok(())
+use std::sync::Arc;
+use ucel_registry::ingest::{IngestDriverRegistry, WsAdapterIngestDriver};
+
+use crate::ws::BitgetWsAdapter;
+
+/// `bitget` 系の ingest driver を registry に登録する（素の `bitget` は `bitget-spot` の別名）
+pub fn register_ingest_drivers(registry: &mut IngestDriverRegistry) {
+    register(registry, "bitget", BitgetWsAdapter::spot);
+    register(registry, "bitget-spot", BitgetWsAdapter::spot);
+    register(
+        registry,
+        "bitget-usdt-futures",
+        BitgetWsAdapter::usdt_futures,
+    );
+    register(
+        registry,
+        "bitget-coin-futures",
+        BitgetWsAdapter::coin_futures,
+    );
+    register(
+        registry,
+        "bitget-usdc-futures",
+        BitgetWsAdapter::usdc_futures,
+    );
+}
+
+fn register(
+    registry: &mut IngestDriverRegistry,
+    id: &'static str,
+    adapter: fn() -> BitgetWsAdapter,
+) {
+    registry.register(id, move || {
+        Ok(WsAdapterIngestDriver::new(id, Arc::new(adapter())).boxed())
+    });
 }
@@ -0,0 +1,72 @@
+use ucel_cex_bitget::orderbook::{parse_bitget_books_payload, BitgetBookData, BitgetBookState};
+use ucel_core::IngestResumeDirective;
+use ucel_transport::ws::integrity::book_crc32;
+
+/// 1 行目は Bitget ドキュメント（Depth Channel の checksum 計算例）の板と掲載 checksum。
+/// 2 行目以降はその板に手で当てた update で、checksum はこのクレートではなく
+/// zlib.crc32 による参照実装で計算した（参照実装は上の掲載値を再現することを確認済み）。
+/// 実 venue のキャプチャではない。
+const FIXTURE: &str = include_str!("fixtures/ws.books.BTCUSDT.ndjson");
+
+fn frames() -> Vec<BitgetBookData> {
+    FIXTURE
+        .lines()
+        .map(|l| parse_bitget_books_payload(l).unwrap().unwrap())
+        .collect()
+}
+
+#[test]
+fn documented_book_and_deltas_pass_checksum() {
+    let frames = frames();
+    assert!(frames[0].snapshot);
+    assert_eq!(frames[0].inst_id.as_deref(), Some("BTCUSDT"));
+
+    let mut ob = BitgetBookState::default();
+    for f in &frames {
+        ob.apply(f);
+        assert_eq!(Some(ob.checksum() as i64), f.checksum);
+    }
+    assert!(!ob.degraded);
+    assert_eq!(ob.seq, frames.last().unwrap().seq);
+}
+
+#[test]
+fn checksum_mismatch_escalates_to_deadletter_after_budget() {
+    let mut frames = frames();
+    frames[1].bids[0].1 = "0.4999".into();
+
+    let mut ob = BitgetBookState::default();
+    ob.apply(&frames[0]);
+    ob.apply(&frames[1]);
+    assert!(ob.degraded);
+    assert_eq!(
+        ob.resync_directive,
+        Some(IngestResumeDirective::ResnapshotThenResubscribe)
+    );
+
+    // 予算（max_checksum_retries）を超えて不一致が続いたら deadletter
+    let budget = ob.checksum_guard.budget.max_checksum_retries;
+    for _ in 0..budget {
+        ob.apply(&frames[1]);
+    }
+    assert_eq!(ob.resync_directive, Some(IngestResumeDirective::Deadletter));
+
+    ob.apply(&frames[0]);
+    assert!(!ob.degraded);
+    assert_eq!(ob.checksum_guard.consecutive_mismatches, 0);
+}
+
+/// Bitget のドキュメント（Depth Channel の checksum 計算例）。
+/// ドキュメントが示すのは check string で、期待値はその標準 CRC-32（符号付き 32bit）。
+#[test]
+fn documented_check_string_example() {
+    let frame = r#"{"action":"snapshot","arg":{"instType":"SPOT","channel":"books","instId":"BTCUSDT"},"data":[{"asks":[["3366.8","9"],["3368","8"]],"bids":[["3366.1","7"],["3366","6"]],"checksum":-1881014294,"seq":1,"ts":"1597026383085"}]}"#;
+    assert_eq!(
+        book_crc32("3366.1:7:3366.8:9:3366:6:3368:8") as i32,
+        -1881014294
+    );
+    let mut ob = BitgetBookState::default();
+    ob.apply(&parse_bitget_books_payload(frame).unwrap().unwrap());
+    assert_eq!(ob.checksum(), -1881014294);
+    assert!(!ob.degraded);
+}
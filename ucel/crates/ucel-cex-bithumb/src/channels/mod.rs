@@ -0,0 +1,7 @@
+pub fn supported_ws_ops() -> Vec<&'static str> {
+    vec![
+        "openapi.public.ws.ticker.snapshot",
+        "openapi.public.ws.trade.snapshot",
+        "openapi.public.ws.orderbook.snapshot",
+    ]
+}
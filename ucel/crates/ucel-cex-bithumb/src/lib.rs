@@ -6,6 +6,14 @@ use ucel_core::{
     UcelError,
 };
 
+pub mod channels;
+pub mod orderbook;
+pub mod symbols;
+pub mod ws;
+pub mod ws_manager;
+
+pub use orderbook::{parse_bithumb_depth_payload, BithumbBookDelta, BithumbBookState};
+
 #[derive(Debug, Clone, PartialEq, Serialize)]
 pub enum BithumbRestResponse {
     MarketList(Value),
@@ -26,6 +34,7 @@ pub struct TickerItem {
 pub enum BithumbWsEvent {
     Ticker(TickerSnapshot),
     Trade(TradeEvent),
+    OrderBookDelta(BithumbBookDelta),
     Unknown,
 }
 
@@ -135,6 +144,12 @@ pub fn normalize_ws_event(endpoint_id: &str, raw: &str) -> Result<BithumbWsEvent
                 last: parse_decimal(content, "closePrice")?,
             }))
         }
+        ("openapi.public.ws.orderbook.snapshot", "orderbook" | "orderbookdepth") => {
+            match parse_bithumb_depth_payload(raw)? {
+                Some(delta) => Ok(BithumbWsEvent::OrderBookDelta(delta)),
+                None => Ok(BithumbWsEvent::Unknown),
+            }
+        }
         _ => Ok(BithumbWsEvent::Unknown),
     }
 }
@@ -0,0 +1,208 @@
+use serde::{Deserialize, Serialize};
+use std::collections::VecDeque;
+use ucel_core::{
+    Decimal, ErrorCode, OrderBook, OrderBookSnapshot, OrderBookUpdate, SequencePolicy, UcelError,
+};
+
+/// snapshot 前に溜めておく差分の上限（超えたら古いものから捨てて再 snapshot に任せる）
+pub const BITHUMB_MAX_PENDING_DELTAS: usize = 1_000;
+
+#[derive(Debug, Deserialize)]
+struct BithumbDepthEnvelope {
+    #[serde(rename = "type", default)]
+    kind: String,
+    #[serde(default)]
+    content: Option<BithumbDepthContent>,
+}
+
+#[derive(Debug, Deserialize)]
+struct BithumbDepthContent {
+    #[serde(default)]
+    symbol: Option<String>,
+    /// マイクロ秒（文字列）
+    #[serde(default)]
+    datetime: Option<String>,
+    #[serde(default)]
+    list: Vec<BithumbDepthLevel>,
+}
+
+#[derive(Debug, Deserialize)]
+struct BithumbDepthLevel {
+    #[serde(default)]
+    symbol: Option<String>,
+    #[serde(rename = "orderType")]
+    order_type: String,
+    price: String,
+    /// 変更後の残量（差分量ではない）。0 は削除
+    quantity: String,
+}
+
+/// WS `orderbookdepth` の 1 メッセージ。`ts_us` は `datetime`(µs) そのもの、`ts_ms` はそれをミリ秒に丸めたもの
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct BithumbBookDelta {
+    pub symbol: Option<String>,
+    pub ts_us: u64,
+    pub ts_ms: u64,
+    pub bids: Vec<(Decimal, Decimal)>,
+    pub asks: Vec<(Decimal, Decimal)>,
+}
+
+pub fn parse_bithumb_depth_payload(raw: &str) -> Result<Option<BithumbBookDelta>, UcelError> {
+    let msg: BithumbDepthEnvelope = serde_json::from_str(raw)
+        .map_err(|e| UcelError::new(ErrorCode::Internal, format!("ws json parse error: {e}")))?;
+    if !matches!(msg.kind.as_str(), "orderbook" | "orderbookdepth") {
+        return Ok(None);
+    }
+    let Some(content) = msg.content else {
+        return Ok(None);
+    };
+    let ts_us: u64 = content
+        .datetime
+        .as_deref()
+        .ok_or_else(|| UcelError::new(ErrorCode::WsProtocolViolation, "missing datetime"))?
+        .parse()
+        .map_err(|e| UcelError::new(ErrorCode::WsProtocolViolation, format!("datetime: {e}")))?;
+
+    let mut bids = Vec::new();
+    let mut asks = Vec::new();
+    let mut symbol = content.symbol;
+    for level in content.list {
+        let price = parse_level(&level.price)?;
+        let qty = parse_level(&level.quantity)?;
+        match level.order_type.as_str() {
+            "bid" => bids.push((price, qty)),
+            "ask" => asks.push((price, qty)),
+            other => {
+                return Err(UcelError::new(
+                    ErrorCode::WsProtocolViolation,
+                    format!("unknown orderType={other}"),
+                ))
+            }
+        }
+        symbol = symbol.or(level.symbol);
+    }
+    Ok(Some(BithumbBookDelta {
+        symbol,
+        ts_us,
+        ts_ms: ts_us / 1_000,
+        bids,
+        asks,
+    }))
+}
+
+fn parse_level(v: &str) -> Result<Decimal, UcelError> {
+    v.parse::<Decimal>()
+        .map_err(|e| UcelError::new(ErrorCode::WsProtocolViolation, format!("level {v}: {e}")))
+}
+
+/// REST snapshot（`timestamp` ms）+ WS 差分（`datetime` µs）で板を組む。
+/// 連番が無いので時刻で snapshot 以前の差分を捨て、逆行は欠落扱いで再 snapshot を要求する。
+/// 比較は µs で行う。snapshot と同じミリ秒の差分は snapshot に含まれたか分からないので再生する
+/// （数量は変更後の残量なので、含まれていた差分を当て直しても板は変わらない）。
+#[derive(Debug, Clone)]
+pub struct BithumbBookState {
+    pub book: OrderBook,
+    pub degraded: bool,
+    /// 最後に反映した差分の時刻（µs）。snapshot 直後はその timestamp のミリ秒の先頭
+    pub last_ts_us: Option<u64>,
+    /// snapshot 待ちの差分
+    pub pending: VecDeque<BithumbBookDelta>,
+    pub gaps: u64,
+    pub resyncs: u64,
+    pub recovered: u64,
+}
+
+impl Default for BithumbBookState {
+    fn default() -> Self {
+        Self {
+            book: OrderBook::default().with_sequence_policy(SequencePolicy::None),
+            degraded: false,
+            last_ts_us: None,
+            pending: VecDeque::new(),
+            gaps: 0,
+            resyncs: 0,
+            recovered: 0,
+        }
+    }
+}
+
+impl BithumbBookState {
+    /// snapshot 待ちなら true（呼び出し側が REST orderbook を取りに行く合図）
+    pub fn needs_snapshot(&self) -> bool {
+        !self.book.is_synced()
+    }
+
+    /// REST snapshot を適用し、それより新しい保留差分を再生する。再生した件数を返す。
+    /// bithumb の snapshot は連番を持たないので、`sequence` は REST の `timestamp`（ms）
+    pub fn apply_snapshot(&mut self, snapshot: &OrderBookSnapshot) -> Result<usize, UcelError> {
+        let bids: Vec<_> = snapshot.bids.iter().map(|l| (l.price, l.qty)).collect();
+        let asks: Vec<_> = snapshot.asks.iter().map(|l| (l.price, l.qty)).collect();
+        let was_degraded = self.degraded;
+        if let Err(e) = self.book.apply_snapshot(None, &bids, &asks) {
+            self.degraded = true;
+            return Err(e.into());
+        }
+        self.resyncs += 1;
+        let snapshot_us = snapshot.sequence.saturating_mul(1_000);
+        self.last_ts_us = Some(snapshot_us);
+        self.degraded = false;
+
+        let mut replayed = 0;
+        for delta in std::mem::take(&mut self.pending) {
+            if delta.ts_us < snapshot_us {
+                continue;
+            }
+            self.apply_synced(delta)?;
+            replayed += 1;
+        }
+        if was_degraded {
+            self.recovered += 1;
+        }
+        Ok(replayed)
+    }
+
+    /// WS 差分を適用する。snapshot 前 / degraded 中は保留する
+    pub fn apply_delta(&mut self, delta: BithumbBookDelta) -> Result<(), UcelError> {
+        if !self.book.is_synced() {
+            if self.pending.len() >= BITHUMB_MAX_PENDING_DELTAS {
+                self.pending.pop_front();
+            }
+            self.pending.push_back(delta);
+            return Ok(());
+        }
+        self.apply_synced(delta)
+    }
+
+    fn apply_synced(&mut self, delta: BithumbBookDelta) -> Result<(), UcelError> {
+        if let Some(last) = self.last_ts_us {
+            if delta.ts_us < last {
+                self.mark_gap();
+                return Err(UcelError::new(
+                    ErrorCode::Desync,
+                    format!(
+                        "bithumb depth out of order: last_us={last} got_us={}",
+                        delta.ts_us
+                    ),
+                ));
+            }
+        }
+        let ts_us = delta.ts_us;
+        if let Err(e) = self
+            .book
+            .apply_update(OrderBookUpdate::new(delta.bids, delta.asks))
+        {
+            // apply_update は失敗時に板を invalidate 済み
+            self.mark_gap();
+            return Err(e.into());
+        }
+        self.last_ts_us = Some(ts_us);
+        Ok(())
+    }
+
+    fn mark_gap(&mut self) {
+        self.gaps += 1;
+        self.degraded = true;
+        self.pending.clear();
+        self.book.invalidate();
+    }
+}
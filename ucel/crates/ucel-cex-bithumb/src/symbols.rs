@@ -0,0 +1,54 @@
+use std::collections::BTreeMap;
+use ucel_market_meta_catalog as catalog;
+use ucel_symbol_core::{Exchange, MarketMeta, Snapshot};
+
+const EXCHANGE_CONST: Exchange = Exchange::Bithumb;
+
+/// WS 購読用シンボル（`BTC_KRW`）を返す
+pub fn fetch_symbols() -> Result<Vec<String>, String> {
+    let snap = catalog::snapshot_for_exchange(EXCHANGE_CONST);
+    if snap.instruments.is_empty() {
+        return Err("catalog empty for exchange=Bithumb".to_string());
+    }
+    Ok(snap.instruments.into_iter().map(|i| i.raw_symbol).collect())
+}
+
+pub async fn fetch_symbol_snapshot() -> Result<Snapshot, String> {
+    let snap = catalog::snapshot_for_exchange(EXCHANGE_CONST);
+    if snap.instruments.is_empty() {
+        return Err("catalog empty for exchange=Bithumb".to_string());
+    }
+    Ok(snap)
+}
+
+pub async fn fetch_market_meta() -> Result<BTreeMap<String, MarketMeta>, String> {
+    let snap = fetch_symbol_snapshot().await?;
+    let mut out = BTreeMap::new();
+    for s in snap.instruments {
+        let mm = catalog::get_meta(EXCHANGE_CONST, s.market_type.clone(), &s.raw_symbol)
+            .ok_or_else(|| {
+                format!(
+                    "catalog missing meta exchange=Bithumb symbol={}",
+                    s.raw_symbol
+                )
+            })?;
+        out.insert(s.raw_symbol, mm);
+    }
+    Ok(out)
+}
+
+/// WS シンボル `BTC_KRW` → REST マーケットコード `KRW-BTC`
+pub fn to_market_code(ws_symbol: &str) -> String {
+    match ws_symbol.split_once('_') {
+        Some((base, quote)) => format!("{quote}-{base}"),
+        None => ws_symbol.to_string(),
+    }
+}
+
+/// REST マーケットコード `KRW-BTC` → WS シンボル `BTC_KRW`
+pub fn to_ws_symbol(market_code: &str) -> String {
+    match market_code.split_once('-') {
+        Some((quote, base)) => format!("{base}_{quote}"),
+        None => market_code.to_string(),
+    }
+}
@@ -0,0 +1,159 @@
+use async_trait::async_trait;
+use serde_json::{json, Value};
+use std::collections::BTreeMap;
+use ucel_transport::ws::adapter::{InboundClass, InboundJsonGuard, OutboundMsg, WsVenueAdapter};
+
+use crate::symbols::fetch_symbols;
+
+const WS_PUBLIC: &str = "wss://pubwss.bithumb.com/pub/ws";
+/// 接続・購読成功時の `status`
+const STATUS_OK: &str = "0000";
+
+/// coverage_v2 の family id と WS `type` の対応（topic_template は type そのもの）
+fn channel_for_op_id(op_id: &str) -> Option<&'static str> {
+    match op_id {
+        "openapi.public.ws.ticker.snapshot" => Some("ticker"),
+        "openapi.public.ws.trade.snapshot" => Some("trade"),
+        "openapi.public.ws.orderbook.snapshot" => Some("orderbook"),
+        _ => None,
+    }
+}
+
+/// 受信 `type` → (op_id, coverage_v2 の weight)。旧名 `transaction` / `orderbookdepth` も受ける
+fn op_for_channel(channel: &str) -> Option<(&'static str, &'static str, u32)> {
+    match channel {
+        "ticker" => Some(("openapi.public.ws.ticker.snapshot", "ticker", 10)),
+        "trade" | "transaction" => Some(("openapi.public.ws.trade.snapshot", "trade", 20)),
+        "orderbook" | "orderbookdepth" => {
+            Some(("openapi.public.ws.orderbook.snapshot", "orderbook", 40))
+        }
+        _ => None,
+    }
+}
+
+/// planner の canon_params と同じ並び: {"_topic":..,"_w":..}
+fn canon_params_hint(topic: &str, weight: u32) -> String {
+    let mut m = BTreeMap::<String, Value>::new();
+    m.insert("_topic".into(), Value::String(topic.to_string()));
+    m.insert("_w".into(), Value::Number((weight as u64).into()));
+    let obj: serde_json::Map<String, Value> = m.into_iter().collect();
+    Value::Object(obj).to_string()
+}
+
+/// `content.symbol`、無ければ `content.list[0].symbol`
+fn symbol_of(v: &Value) -> Option<String> {
+    let content = v.get("content")?;
+    content
+        .get("symbol")
+        .or_else(|| content.get("list")?.get(0)?.get("symbol"))
+        .and_then(Value::as_str)
+        .map(str::to_string)
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct BithumbWsAdapter;
+
+impl BithumbWsAdapter {
+    pub fn new() -> Self {
+        Self
+    }
+}
+
+#[async_trait]
+impl WsVenueAdapter for BithumbWsAdapter {
+    fn exchange_id(&self) -> &str {
+        "bithumb"
+    }
+
+    fn ws_url(&self) -> String {
+        WS_PUBLIC.to_string()
+    }
+
+    async fn fetch_symbols(&self) -> Result<Vec<String>, String> {
+        fetch_symbols()
+    }
+
+    fn build_subscribe(
+        &self,
+        op_id: &str,
+        symbol: &str,
+        params: &Value,
+    ) -> Result<Vec<OutboundMsg>, String> {
+        let channel = params
+            .get("_topic")
+            .and_then(Value::as_str)
+            .or_else(|| channel_for_op_id(op_id))
+            .ok_or_else(|| format!("bithumb: unsupported op_id={op_id}"))?;
+        if symbol.is_empty() {
+            return Err(format!("bithumb: symbol required for op_id={op_id}"));
+        }
+        let mut msg = json!({"type": channel, "symbols": [symbol]});
+        if channel == "ticker" {
+            let tick_types = params
+                .get("tickTypes")
+                .cloned()
+                .unwrap_or_else(|| json!(["30M"]));
+            msg["tickTypes"] = tick_types;
+        }
+        Ok(vec![OutboundMsg {
+            text: msg.to_string(),
+        }])
+    }
+
+    /// Bithumb の公開 WS には購読解除フレームが無い。Err を返し、transport 側で
+    /// ストアから外す（実ストリームは次の再接続で止まる）
+    fn build_unsubscribe(
+        &self,
+        op_id: &str,
+        _symbol: &str,
+        _params: &Value,
+    ) -> Result<Vec<OutboundMsg>, String> {
+        Err(format!(
+            "bithumb: unsubscribe not supported (op_id={op_id})"
+        ))
+    }
+
+    fn classify_inbound(&self, raw: &[u8]) -> InboundClass {
+        if InboundJsonGuard::default().enforce(raw).is_err() {
+            return InboundClass::Unknown;
+        }
+        let v: Value = match serde_json::from_slice(raw) {
+            Ok(x) => x,
+            Err(_) => return InboundClass::Unknown,
+        };
+
+        if v.get("ping").is_some() {
+            return InboundClass::Respond {
+                msg: OutboundMsg {
+                    text: json!({"pong": v["ping"]}).to_string(),
+                },
+            };
+        }
+
+        // {"status":"0000","resmsg":"Connected Successfully" | "Filter Registered Successfully"}
+        // 購読応答は type を返さないので op_id に紐付けられない。失敗だけ Nack にする
+        if let Some(status) = v.get("status").and_then(Value::as_str) {
+            if status == STATUS_OK {
+                return InboundClass::System;
+            }
+            let resmsg = v.get("resmsg").and_then(Value::as_str).unwrap_or_default();
+            return InboundClass::Nack {
+                reason: format!("bithumb status={status} resmsg={resmsg}"),
+                op_id: None,
+                symbol: None,
+                params_canon_hint: None,
+                retry_after_ms: None,
+            };
+        }
+
+        let channel = v.get("type").and_then(Value::as_str).unwrap_or_default();
+        match op_for_channel(channel) {
+            Some((op_id, topic, weight)) => InboundClass::Data {
+                op_id: Some(op_id.to_string()),
+                symbol: symbol_of(&v),
+                params_canon_hint: Some(canon_params_hint(topic, weight)),
+            },
+            None => InboundClass::Unknown,
+        }
+    }
+}
@@ -0,0 +1,11 @@
+use std::sync::Arc;
+use ucel_registry::ingest::{IngestDriverRegistry, WsAdapterIngestDriver};
+
+use crate::ws::BithumbWsAdapter;
+
+/// `bithumb` の ingest driver を registry に登録する（公開 WS のみ）
+pub fn register_ingest_drivers(registry: &mut IngestDriverRegistry) {
+    registry.register("bithumb", || {
+        Ok(WsAdapterIngestDriver::new("bithumb", Arc::new(BithumbWsAdapter::new())).boxed())
+    });
+}
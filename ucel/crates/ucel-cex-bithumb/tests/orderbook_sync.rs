@@ -0,0 +1,143 @@
+use std::path::Path;
+
+use ucel_cex_bithumb::{
+    normalize_rest_response, normalize_ws_event, parse_bithumb_depth_payload, BithumbBookDelta,
+    BithumbBookState, BithumbRestResponse, BithumbWsEvent,
+};
+use ucel_core::{BookSide, Decimal, ErrorCode, OrderBookSnapshot};
+
+fn fixture(name: &str) -> String {
+    std::fs::read_to_string(
+        Path::new(env!("CARGO_MANIFEST_DIR"))
+            .join("tests/fixtures")
+            .join(name),
+    )
+    .unwrap()
+}
+
+fn d(v: &str) -> Decimal {
+    v.parse().unwrap()
+}
+
+fn depth(name: &str) -> BithumbBookDelta {
+    parse_bithumb_depth_payload(&fixture(name))
+        .unwrap()
+        .unwrap()
+}
+
+fn rest_snapshot() -> OrderBookSnapshot {
+    match normalize_rest_response(
+        "openapi.public.rest.orderbook.snapshot",
+        fixture("rest_orderbook_snapshot.json").as_bytes(),
+    )
+    .unwrap()
+    {
+        BithumbRestResponse::OrderBookSnapshot(s) => s,
+        other => panic!("unexpected rest response: {other:?}"),
+    }
+}
+
+#[test]
+fn depth_payload_parses_levels_and_microsecond_timestamp() {
+    let delta = depth("ws_orderbookdepth.json");
+    assert_eq!(delta.symbol.as_deref(), Some("BTC_KRW"));
+    assert_eq!(delta.ts_us, 1_760_000_000_600_000);
+    assert_eq!(delta.ts_ms, 1_760_000_000_600);
+    assert_eq!(delta.asks, vec![(d("150001000"), d("0"))]);
+    assert_eq!(delta.bids, vec![(d("150000500"), d("0.25"))]);
+
+    match normalize_ws_event(
+        "openapi.public.ws.orderbook.snapshot",
+        &fixture("ws_orderbookdepth.json"),
+    )
+    .unwrap()
+    {
+        BithumbWsEvent::OrderBookDelta(ev) => assert_eq!(ev, delta),
+        other => panic!("unexpected ws event: {other:?}"),
+    }
+}
+
+#[test]
+fn snapshot_replays_only_deltas_newer_than_rest_timestamp() {
+    let mut state = BithumbBookState::default();
+    assert!(state.needs_snapshot());
+
+    // snapshot 前の差分は保留される
+    state
+        .apply_delta(depth("ws_orderbookdepth_stale.json"))
+        .unwrap();
+    state.apply_delta(depth("ws_orderbookdepth.json")).unwrap();
+    assert_eq!(state.pending.len(), 2);
+
+    let replayed = state.apply_snapshot(&rest_snapshot()).unwrap();
+    assert_eq!(replayed, 1, "stale delta (ts < snapshot) must be dropped");
+    assert!(!state.needs_snapshot());
+    assert!(!state.degraded);
+    assert_eq!(state.last_ts_us, Some(1_760_000_000_600_000));
+
+    assert_eq!(
+        state.book.top(BookSide::Bid, 3),
+        vec![
+            (d("150000500"), d("0.25")),
+            (d("150000000"), d("1.0")),
+            (d("149999000"), d("1.0")),
+        ]
+    );
+    // 150001000 は qty=0 で削除
+    assert_eq!(
+        state.book.top(BookSide::Ask, 3),
+        vec![(d("150002000"), d("1.0"))]
+    );
+}
+
+#[test]
+fn out_of_order_delta_degrades_and_snapshot_recovers() {
+    let mut state = BithumbBookState::default();
+    state.apply_snapshot(&rest_snapshot()).unwrap();
+    state.apply_delta(depth("ws_orderbookdepth.json")).unwrap();
+
+    let err = state
+        .apply_delta(depth("ws_orderbookdepth_stale.json"))
+        .unwrap_err();
+    assert_eq!(err.code, ErrorCode::Desync);
+    assert!(state.degraded);
+    assert!(state.needs_snapshot());
+    assert_eq!(state.gaps, 1);
+
+    // degraded 中の差分は次の snapshot まで保留
+    state.apply_delta(depth("ws_orderbookdepth.json")).unwrap();
+    assert_eq!(state.pending.len(), 1);
+
+    state.apply_snapshot(&rest_snapshot()).unwrap();
+    assert!(!state.degraded);
+    assert_eq!(state.recovered, 1);
+    assert_eq!(state.resyncs, 2);
+    assert_eq!(state.book.best_bid(), Some((d("150000500"), d("0.25"))));
+}
+
+#[test]
+fn delta_in_the_snapshot_millisecond_is_replayed_at_microsecond_precision() {
+    let mut state = BithumbBookState::default();
+    // snapshot は ...500ms、差分は ...500.300ms（ms に丸めると同じ時刻）
+    let same_ms = depth("ws_orderbookdepth_same_ms.json");
+    assert_eq!(same_ms.ts_ms, rest_snapshot().sequence);
+    state.apply_delta(same_ms).unwrap();
+
+    assert_eq!(state.apply_snapshot(&rest_snapshot()).unwrap(), 1);
+    assert_eq!(state.last_ts_us, Some(1_760_000_000_500_300));
+    assert_eq!(state.book.best_bid(), Some((d("150000000"), d("0.75"))));
+
+    // 同じミリ秒でも µs で後ろなら順序どおり
+    let mut later = depth("ws_orderbookdepth_same_ms.json");
+    later.ts_us += 1;
+    later.bids = vec![(d("150000000"), d("0.5"))];
+    state.apply_delta(later).unwrap();
+    assert_eq!(state.book.best_bid(), Some((d("150000000"), d("0.5"))));
+
+    // µs で逆行したら（ms が同じでも）欠落扱い
+    let err = state
+        .apply_delta(depth("ws_orderbookdepth_same_ms.json"))
+        .unwrap_err();
+    assert_eq!(err.code, ErrorCode::Desync);
+    assert!(state.degraded);
+}
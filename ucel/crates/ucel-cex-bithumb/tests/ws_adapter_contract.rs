@@ -0,0 +1,171 @@
+use serde_json::json;
+use std::path::Path;
+
+use ucel_cex_bithumb::ws::BithumbWsAdapter;
+use ucel_registry::ingest::IngestDriverRegistry;
+use ucel_subscription_planner::load_manifest;
+use ucel_transport::ws::adapter::{InboundClass, WsVenueAdapter};
+
+fn repo_root() -> std::path::PathBuf {
+    // ucel/crates/ucel-cex-bithumb -> ucel workspace root
+    Path::new(env!("CARGO_MANIFEST_DIR")).join("..").join("..")
+}
+
+fn fixture(name: &str) -> String {
+    std::fs::read_to_string(
+        Path::new(env!("CARGO_MANIFEST_DIR"))
+            .join("tests/fixtures")
+            .join(name),
+    )
+    .unwrap()
+}
+
+#[test]
+fn coverage_ops_are_all_supported_by_build_subscribe() {
+    let a = BithumbWsAdapter::new();
+    let manifest = load_manifest(&repo_root().join("coverage").join("bithumb.yaml")).unwrap();
+    // extract_ws_ops は crypto.* 前提なので openapi.* を直接拾う
+    let ops: Vec<String> = manifest
+        .entries
+        .iter()
+        .filter(|e| e.id.starts_with("openapi.public.ws."))
+        .map(|e| e.id.clone())
+        .collect();
+    assert_eq!(ops.len(), 3, "bithumb coverage public ws ops: {ops:?}");
+
+    for op in ops {
+        let res = a.build_subscribe(&op, "BTC_KRW", &json!({}));
+        assert!(res.is_ok(), "op_id={op} err={:?}", res.err());
+    }
+}
+
+#[test]
+fn build_subscribe_prefers_planner_topic_and_adds_tick_types_for_ticker() {
+    let a = BithumbWsAdapter::new();
+
+    let msgs = a
+        .build_subscribe(
+            "openapi.public.ws.ticker.snapshot",
+            "BTC_KRW",
+            &json!({"_topic": "ticker", "_w": 10}),
+        )
+        .unwrap();
+    let v: serde_json::Value = serde_json::from_str(&msgs[0].text).unwrap();
+    assert_eq!(
+        v,
+        json!({"type": "ticker", "symbols": ["BTC_KRW"], "tickTypes": ["30M"]})
+    );
+
+    let msgs = a
+        .build_subscribe(
+            "openapi.public.ws.orderbook.snapshot",
+            "ETH_KRW",
+            &json!({}),
+        )
+        .unwrap();
+    let v: serde_json::Value = serde_json::from_str(&msgs[0].text).unwrap();
+    assert_eq!(v, json!({"type": "orderbook", "symbols": ["ETH_KRW"]}));
+
+    assert!(a
+        .build_subscribe("openapi.public.ws.unknown", "BTC_KRW", &json!({}))
+        .is_err());
+    assert!(a
+        .build_subscribe("openapi.public.ws.trade.snapshot", "", &json!({}))
+        .is_err());
+}
+
+#[test]
+fn build_unsubscribe_is_unsupported() {
+    // 購読解除フレームが無いので Err -> transport がストアから直接 removed にする
+    let a = BithumbWsAdapter::new();
+    let err = a
+        .build_unsubscribe(
+            "openapi.public.ws.ticker.snapshot",
+            "BTC_KRW",
+            &json!({"_topic": "ticker", "_w": 10}),
+        )
+        .unwrap_err();
+    assert!(err.contains("unsubscribe not supported"), "{err}");
+}
+
+#[test]
+fn classify_inbound_maps_type_to_op_id_symbol_and_planner_hint() {
+    let a = BithumbWsAdapter::new();
+
+    match a.classify_inbound(fixture("ws_ticker.json").as_bytes()) {
+        InboundClass::Data {
+            op_id,
+            symbol,
+            params_canon_hint,
+        } => {
+            assert_eq!(op_id.unwrap(), "openapi.public.ws.ticker.snapshot");
+            assert_eq!(symbol.unwrap(), "BTC_KRW");
+            assert_eq!(params_canon_hint.unwrap(), r#"{"_topic":"ticker","_w":10}"#);
+        }
+        other => panic!("unexpected inbound class: {other:?}"),
+    }
+
+    // orderbookdepth は content.list[0].symbol からシンボルを取る
+    match a.classify_inbound(fixture("ws_orderbookdepth.json").as_bytes()) {
+        InboundClass::Data {
+            op_id,
+            symbol,
+            params_canon_hint,
+        } => {
+            assert_eq!(op_id.unwrap(), "openapi.public.ws.orderbook.snapshot");
+            assert_eq!(symbol.unwrap(), "BTC_KRW");
+            assert_eq!(
+                params_canon_hint.unwrap(),
+                r#"{"_topic":"orderbook","_w":40}"#
+            );
+        }
+        other => panic!("unexpected inbound class: {other:?}"),
+    }
+}
+
+#[test]
+fn classify_inbound_handles_status_and_ping() {
+    let a = BithumbWsAdapter::new();
+
+    let ok = json!({"status": "0000", "resmsg": "Filter Registered Successfully"}).to_string();
+    assert!(matches!(
+        a.classify_inbound(ok.as_bytes()),
+        InboundClass::System
+    ));
+
+    match a.classify_inbound(fixture("ws_status_error.json").as_bytes()) {
+        InboundClass::Nack { reason, .. } => assert!(reason.contains("5100"), "{reason}"),
+        other => panic!("unexpected inbound class: {other:?}"),
+    }
+
+    match a.classify_inbound(br#"{"ping":1760000000000}"#) {
+        InboundClass::Respond { msg } => {
+            assert_eq!(msg.text, r#"{"pong":1760000000000}"#)
+        }
+        other => panic!("unexpected inbound class: {other:?}"),
+    }
+
+    assert!(matches!(
+        a.classify_inbound(b"not json"),
+        InboundClass::Unknown
+    ));
+}
+
+#[tokio::test(flavor = "current_thread")]
+async fn fetch_symbols_and_ingest_driver_registration() {
+    let a = BithumbWsAdapter::new();
+    let symbols = a.fetch_symbols().await.unwrap();
+    assert!(symbols.contains(&"BTC_KRW".to_string()), "{symbols:?}");
+    assert_eq!(
+        ucel_cex_bithumb::symbols::to_market_code("BTC_KRW"),
+        "KRW-BTC"
+    );
+    assert_eq!(
+        ucel_cex_bithumb::symbols::to_ws_symbol("KRW-BTC"),
+        "BTC_KRW"
+    );
+
+    let mut registry = IngestDriverRegistry::new();
+    ucel_cex_bithumb::ws_manager::register_ingest_drivers(&mut registry);
+    assert!(registry.resolve("bithumb").is_ok());
+}
@@ -295,13 +295,15 @@ impl<T: Transport + Send + Sync> ExecutionConnectorAsync for BittradeExecutionCo
 
     async fn reconcile(&self, venue: &VenueId) -> SdkExecutionResult<ReconcileReport> {
         // 最小実装: open_orders が叩ける = "照合の入口" は成立
-        // mismatches は空（v1）。詳細照合は FileAuditSink と組み合わせて次で強化
+        // mismatches は空（v1）。監査ログとの詳細照合は ExecutionClientAsync 側の Reconciler が行う
         Ok(ReconcileReport {
             venue: venue.clone(),
             source: ReconcileSource::Venue,
             ok: true,
             mismatches: vec![],
             generated_at_unix_ms: unix_ms_now(),
+            details: vec![],
+            actions: vec![],
         })
     }
 }
@@ -91,6 +91,20 @@ impl WsVenueAdapter for BitTradeWsAdapter {
         }])
     }
 
+    fn build_unsubscribe(
+        &self,
+        op_id: &str,
+        symbol: &str,
+        params: &Value,
+    ) -> Result<Vec<OutboundMsg>, String> {
+        let tpl =
+            template_for_family(op_id).ok_or_else(|| format!("unknown family_id: {op_id}"))?;
+        let unsub = render(tpl.to_string(), symbol, params);
+        Ok(vec![OutboundMsg {
+            text: json!({"unsub": unsub, "id":"1"}).to_string(),
+        }])
+    }
+
     fn classify_inbound(&self, raw: &[u8]) -> InboundClass {
         let bytes = gunzip_if_needed(raw);
         if InboundJsonGuard::default().enforce(&bytes).is_err() {
@@ -1,3 +1,11 @@
-pub fn run_ingest() -> Result<(), String> {
-    Ok(())
+use std::sync::Arc;
+use ucel_registry::ingest::{IngestDriverRegistry, WsAdapterIngestDriver};
+
+use crate::ws::BitTradeWsAdapter;
+
+/// `bittrade` の ingest driver を registry に登録する
+pub fn register_ingest_drivers(registry: &mut IngestDriverRegistry) {
+    registry.register("bittrade", || {
+        Ok(WsAdapterIngestDriver::new("bittrade", Arc::new(BitTradeWsAdapter::new())).boxed())
+    });
 }
@@ -1,7 +1,7 @@
 use serde::{Deserialize, Serialize};
 use std::collections::{BTreeMap, HashSet};
 use tokio::sync::mpsc;
-use ucel_core::{ErrorCode, OpName, UcelError};
+use ucel_core::{ErrorCode, OpName, OrderBook, OrderBookError, OrderBookUpdate, UcelError};
 use ucel_transport::security::{EndpointAllowlist, SubdomainPolicy};
 use ucel_transport::{RequestContext, Transport, WsConnectRequest};
 use uuid::Uuid;
@@ -144,19 +144,19 @@ impl WsBackpressureBuffer {
 
 #[derive(Debug, Default, Clone)]
 pub struct OrderBookSyncState {
-    pub sequence: Option<u64>,
-    pub bids: BTreeMap<String, String>,
-    pub asks: BTreeMap<String, String>,
+    /// update id（u、無ければ seq）が 1 ずつ進むことを `SequencePolicy::Contiguous` で検査する
+    pub book: OrderBook,
     pub degraded: bool,
 }
 
 impl OrderBookSyncState {
     #[allow(dead_code)]
     pub(crate) fn apply_snapshot(&mut self, data: &BybitOrderbookData) {
-        self.sequence = data.u.or(data.seq);
-        self.degraded = false;
-        self.bids = data.b.iter().cloned().collect();
-        self.asks = data.a.iter().cloned().collect();
+        let applied = OrderBookUpdate::from_str_levels(&data.b, &data.a).and_then(|u| {
+            self.book
+                .apply_snapshot(data.u.or(data.seq), &u.bids, &u.asks)
+        });
+        self.degraded = applied.is_err();
     }
 
     #[allow(dead_code)]
@@ -165,28 +165,21 @@ impl OrderBookSyncState {
         data: &BybitOrderbookData,
         metrics: &mut WsAdapterMetrics,
     ) {
-        let next = data.u.or(data.seq);
-        let expected_next = self.sequence.map(|s| s + 1);
-        if expected_next.is_none() || next != expected_next {
-            self.degraded = true;
-            metrics.ws_orderbook_gap_total += 1;
-            metrics.ws_orderbook_resync_total += 1;
-            return;
-        }
-        self.sequence = next;
-        for (price, qty) in &data.b {
-            if qty == "0" {
-                self.bids.remove(price);
-            } else {
-                self.bids.insert(price.clone(), qty.clone());
+        let applied = OrderBookUpdate::from_str_levels(&data.b, &data.a).and_then(|mut u| {
+            if let Some(next) = data.u.or(data.seq) {
+                u = u.with_sequence(next);
             }
-        }
-        for (price, qty) in &data.a {
-            if qty == "0" {
-                self.asks.remove(price);
-            } else {
-                self.asks.insert(price.clone(), qty.clone());
+            self.book.apply_update(u)
+        });
+        if let Err(e) = applied {
+            self.degraded = true;
+            if matches!(
+                e,
+                OrderBookError::Gap { .. } | OrderBookError::MissingSequence
+            ) {
+                metrics.ws_orderbook_gap_total += 1;
             }
+            metrics.ws_orderbook_resync_total += 1;
         }
     }
 
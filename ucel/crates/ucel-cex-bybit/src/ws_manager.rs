@@ -1,3 +1,23 @@
-pub fn run_ingest() -> Result<(), String> {
-    Ok(())
+use std::sync::Arc;
+use ucel_registry::ingest::{IngestDriverRegistry, WsAdapterIngestDriver};
+
+use crate::ws::BybitWsAdapter;
+
+/// `bybit` 系の ingest driver を registry に登録する（素の `bybit` は `bybit-spot` の別名）
+pub fn register_ingest_drivers(registry: &mut IngestDriverRegistry) {
+    register(registry, "bybit", BybitWsAdapter::spot);
+    register(registry, "bybit-spot", BybitWsAdapter::spot);
+    register(registry, "bybit-linear", BybitWsAdapter::linear);
+    register(registry, "bybit-inverse", BybitWsAdapter::inverse);
+    register(registry, "bybit-options", BybitWsAdapter::option);
+}
+
+fn register(
+    registry: &mut IngestDriverRegistry,
+    id: &'static str,
+    adapter: fn() -> BybitWsAdapter,
+) {
+    registry.register(id, move || {
+        Ok(WsAdapterIngestDriver::new(id, Arc::new(adapter())).boxed())
+    });
 }
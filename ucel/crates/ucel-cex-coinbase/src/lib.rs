@@ -1,13 +1,13 @@
 use bytes::Bytes;
 use serde::{Deserialize, Serialize};
-use std::collections::{HashSet, VecDeque};
+use std::collections::HashSet;
 use std::sync::atomic::{AtomicU64, Ordering};
 use std::sync::Arc;
 use tokio::sync::mpsc;
 use tracing::info;
 use ucel_core::decimal::serde::deserialize_decimal_observation;
 use ucel_core::{
-    Decimal, ErrorCode, OpName, OrderBookDelta, OrderBookLevel, OrderBookSnapshot, Side,
+    Decimal, ErrorCode, OpName, OrderBook, OrderBookDelta, OrderBookLevel, OrderBookSnapshot, Side,
     TradeEvent, UcelError,
 };
 use ucel_transport::{enforce_auth_boundary, HttpRequest, RequestContext, Transport};
@@ -247,51 +247,39 @@ pub enum OrderbookHealth {
 
 #[derive(Debug, Default)]
 pub struct OrderbookResync {
-    buffered_deltas: VecDeque<OrderBookDelta>,
-    next_sequence: Option<u64>,
+    book: OrderBook,
     health: OrderbookHealth,
 }
 
 impl OrderbookResync {
     pub fn ingest_delta(&mut self, delta: OrderBookDelta) -> Result<(), UcelError> {
-        if let Some(expected) = self.next_sequence {
-            if delta.sequence_start != expected {
-                self.health = OrderbookHealth::Degraded;
-                self.next_sequence = None;
-                self.buffered_deltas.clear();
-                return Err(UcelError::new(
-                    ErrorCode::Desync,
-                    "gap detected; force resync",
-                ));
-            }
-            self.next_sequence = Some(delta.sequence_end + 1);
-            return Ok(());
+        if let Err(e) = self.book.apply_update((&delta).into()) {
+            self.health = OrderbookHealth::Degraded;
+            return Err(UcelError::new(
+                ErrorCode::Desync,
+                format!("gap detected; force resync ({e})"),
+            ));
         }
-        self.buffered_deltas.push_back(delta);
         Ok(())
     }
 
     pub fn apply_snapshot(
         &mut self,
-        mut snapshot: OrderBookSnapshot,
+        snapshot: OrderBookSnapshot,
     ) -> Result<OrderBookSnapshot, UcelError> {
-        self.next_sequence = Some(snapshot.sequence + 1);
-        while let Some(delta) = self.buffered_deltas.pop_front() {
-            if delta.sequence_end <= snapshot.sequence {
-                continue;
-            }
-            if delta.sequence_start > snapshot.sequence + 1 {
-                self.health = OrderbookHealth::Degraded;
-                return Err(UcelError::new(
-                    ErrorCode::Desync,
-                    "delta mismatch; force resync",
-                ));
-            }
-            snapshot.sequence = delta.sequence_end;
+        if let Err(e) = self.book.apply_snapshot_levels(&snapshot) {
+            self.health = OrderbookHealth::Degraded;
+            return Err(UcelError::new(
+                ErrorCode::Desync,
+                format!("delta mismatch; force resync ({e})"),
+            ));
         }
         self.health = OrderbookHealth::Ok;
-        self.next_sequence = Some(snapshot.sequence + 1);
-        Ok(snapshot)
+        Ok(self.book.to_order_book_snapshot())
+    }
+
+    pub fn book(&self) -> &OrderBook {
+        &self.book
     }
 
     pub fn health(&self) -> &OrderbookHealth {
@@ -606,10 +594,14 @@ mod tests {
 
     #[test]
     fn duplicate_or_out_of_order_policy_is_safe_resync() {
-        let mut engine = OrderbookResync {
-            next_sequence: Some(5),
-            ..Default::default()
-        };
+        let mut engine = OrderbookResync::default();
+        engine
+            .apply_snapshot(OrderBookSnapshot {
+                bids: vec![],
+                asks: vec![],
+                sequence: 4,
+            })
+            .unwrap();
         let err = engine
             .ingest_delta(OrderBookDelta {
                 bids: vec![],
@@ -2,10 +2,10 @@ pub mod private;
 use bytes::Bytes;
 use serde::de::DeserializeOwned;
 use serde::{Deserialize, Serialize};
-use std::collections::{BTreeMap, HashMap, HashSet};
+use std::collections::{HashMap, HashSet};
 use tokio::sync::mpsc;
 use tracing::info;
-use ucel_core::{ErrorCode, OpName, UcelError};
+use ucel_core::{ErrorCode, OpName, OrderBook, OrderBookApply, OrderBookUpdate, UcelError};
 use ucel_transport::security::{EndpointAllowlist, SubdomainPolicy};
 use ucel_transport::{
     enforce_auth_boundary, HttpRequest, RequestContext, Transport, WsConnectRequest,
@@ -128,10 +128,8 @@ impl WsBackpressureBuffer {
 
 #[derive(Debug, Default, Clone)]
 pub struct OrderbookSyncState {
-    pub last_sequence: Option<u64>,
+    pub book: OrderBook,
     pub degraded: bool,
-    pub bids: BTreeMap<String, String>,
-    pub asks: BTreeMap<String, String>,
 }
 
 impl OrderbookSyncState {
@@ -141,12 +139,12 @@ impl OrderbookSyncState {
         bids: Vec<(String, String)>,
         asks: Vec<(String, String)>,
     ) {
-        self.last_sequence = Some(sequence);
-        self.degraded = false;
-        self.bids = bids.into_iter().collect();
-        self.asks = asks.into_iter().collect();
+        let applied = OrderBookUpdate::from_str_levels(&bids, &asks)
+            .and_then(|u| self.book.apply_snapshot(Some(sequence), &u.bids, &u.asks));
+        self.degraded = applied.is_err();
     }
 
+    /// snapshot 前の差分は book 側で buffer される（false を返す）
     pub fn apply_delta(
         &mut self,
         sequence: Option<u64>,
@@ -159,39 +157,30 @@ impl OrderbookSyncState {
             return false;
         };
 
-        match self.last_sequence {
+        // 同期が切れた後も直前の sequence で重複 / 順序違反を数え分ける
+        match self.book.sequence() {
             Some(last) if next == last => {
                 metrics.ws_duplicate_total += 1;
                 self.mark_gap(metrics);
-                false
+                return false;
             }
             Some(last) if next != last + 1 => {
                 metrics.ws_out_of_order_total += 1;
                 self.mark_gap(metrics);
-                false
+                return false;
             }
-            None => {
+            _ => {}
+        }
+
+        let applied = OrderBookUpdate::from_str_levels(&bids, &asks)
+            .and_then(|u| self.book.apply_update(u.with_sequence(next)));
+        match applied {
+            Ok(OrderBookApply::Applied) => true,
+            Ok(OrderBookApply::Buffered) => false,
+            Err(_) => {
                 self.mark_gap(metrics);
                 false
             }
-            _ => {
-                self.last_sequence = Some(next);
-                for (p, q) in bids {
-                    if q == "0" {
-                        self.bids.remove(&p);
-                    } else {
-                        self.bids.insert(p, q);
-                    }
-                }
-                for (p, q) in asks {
-                    if q == "0" {
-                        self.asks.remove(&p);
-                    } else {
-                        self.asks.insert(p, q);
-                    }
-                }
-                true
-            }
         }
     }
 
@@ -203,6 +192,7 @@ impl OrderbookSyncState {
     }
 
     fn mark_gap(&mut self, metrics: &mut WsAdapterMetrics) {
+        self.book.invalidate();
         self.degraded = true;
         metrics.ws_orderbook_gap_total += 1;
         metrics.ws_orderbook_resync_total += 1;
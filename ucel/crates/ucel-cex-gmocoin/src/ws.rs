@@ -107,6 +107,53 @@ impl GmoCoinPublicWsAdapter {
     }
 }
 
+/// Shared by subscribe / unsubscribe; only `command` differs.
+fn public_command(
+    command: &str,
+    op_id: &str,
+    symbol: &str,
+    params: &Value,
+) -> Result<Vec<OutboundMsg>, String> {
+    // planner_v2 provides params["_topic"] = "channel|symbol"; fallback for coverage op_ids
+    let topic = params
+        .get("_topic")
+        .and_then(|v| v.as_str())
+        .map(|s| s.to_string())
+        .or_else(|| {
+            let channel = channel_for_public_op_id(op_id)?;
+            let ws_symbol = if symbol.is_empty() {
+                "".to_string()
+            } else {
+                to_ws_symbol(symbol)
+            };
+            Some(format!("{channel}|{ws_symbol}"))
+        });
+
+    let topic =
+        topic.ok_or_else(|| format!("missing _topic (planner_v2 required): op_id={op_id}"))?;
+    let (channel, sym_in_topic) = parse_topic(&topic)?;
+    let sym = sym_in_topic.unwrap_or_else(|| symbol.to_string());
+
+    // trades may include option=TAKER_ONLY  [oai_citation:16‡Coin API](https://api.coin.z.com/docs/)
+    let option = params.get("option").and_then(|v| v.as_str()).unwrap_or("");
+
+    let mut msg = json!({
+        "command": command,
+        "channel": channel,
+        "symbol": sym
+    });
+
+    if !option.is_empty() {
+        msg.as_object_mut()
+            .unwrap()
+            .insert("option".into(), Value::String(option.to_string()));
+    }
+
+    Ok(vec![OutboundMsg {
+        text: msg.to_string(),
+    }])
+}
+
 #[async_trait]
 impl WsVenueAdapter for GmoCoinPublicWsAdapter {
     fn exchange_id(&self) -> &str {
@@ -127,44 +174,16 @@ impl WsVenueAdapter for GmoCoinPublicWsAdapter {
         symbol: &str,
         params: &Value,
     ) -> Result<Vec<OutboundMsg>, String> {
-        // planner_v2 provides params["_topic"] = "channel|symbol"; fallback for coverage op_ids
-        let topic = params
-            .get("_topic")
-            .and_then(|v| v.as_str())
-            .map(|s| s.to_string())
-            .or_else(|| {
-                let channel = channel_for_public_op_id(op_id)?;
-                let ws_symbol = if symbol.is_empty() {
-                    "".to_string()
-                } else {
-                    to_ws_symbol(symbol)
-                };
-                Some(format!("{channel}|{ws_symbol}"))
-            });
-
-        let topic =
-            topic.ok_or_else(|| format!("missing _topic (planner_v2 required): op_id={op_id}"))?;
-        let (channel, sym_in_topic) = parse_topic(&topic)?;
-        let sym = sym_in_topic.unwrap_or_else(|| symbol.to_string());
-
-        // trades may include option=TAKER_ONLY  [oai_citation:16‡Coin API](https://api.coin.z.com/docs/)
-        let option = params.get("option").and_then(|v| v.as_str()).unwrap_or("");
-
-        let mut msg = json!({
-            "command": "subscribe",
-            "channel": channel,
-            "symbol": sym
-        });
-
-        if !option.is_empty() {
-            msg.as_object_mut()
-                .unwrap()
-                .insert("option".into(), Value::String(option.to_string()));
-        }
+        public_command("subscribe", op_id, symbol, params)
+    }
 
-        Ok(vec![OutboundMsg {
-            text: msg.to_string(),
-        }])
+    fn build_unsubscribe(
+        &self,
+        op_id: &str,
+        symbol: &str,
+        params: &Value,
+    ) -> Result<Vec<OutboundMsg>, String> {
+        public_command("unsubscribe", op_id, symbol, params)
     }
 
     fn classify_inbound(&self, raw: &[u8]) -> InboundClass {
@@ -260,6 +279,33 @@ impl GmoCoinPrivateWsAdapter {
     }
 }
 
+/// Private channels carry no symbol; only `command` differs between sub/unsub.
+fn private_command(command: &str, op_id: &str, params: &Value) -> Result<Vec<OutboundMsg>, String> {
+    let topic = params
+        .get("_topic")
+        .and_then(|v| v.as_str())
+        .ok_or_else(|| format!("missing _topic (planner_v2 required): op_id={op_id}"))?;
+
+    let channel = topic;
+
+    let option = params.get("option").and_then(|v| v.as_str()).unwrap_or("");
+
+    let mut msg = json!({
+        "command": command,
+        "channel": channel
+    });
+
+    if !option.is_empty() {
+        msg.as_object_mut()
+            .unwrap()
+            .insert("option".into(), Value::String(option.to_string()));
+    }
+
+    Ok(vec![OutboundMsg {
+        text: msg.to_string(),
+    }])
+}
+
 #[async_trait]
 impl WsVenueAdapter for GmoCoinPrivateWsAdapter {
     fn exchange_id(&self) -> &str {
@@ -296,29 +342,16 @@ impl WsVenueAdapter for GmoCoinPrivateWsAdapter {
         _symbol: &str,
         params: &Value,
     ) -> Result<Vec<OutboundMsg>, String> {
-        let topic = params
-            .get("_topic")
-            .and_then(|v| v.as_str())
-            .ok_or_else(|| format!("missing _topic (planner_v2 required): op_id={op_id}"))?;
-
-        let channel = topic;
-
-        let option = params.get("option").and_then(|v| v.as_str()).unwrap_or("");
-
-        let mut msg = json!({
-            "command": "subscribe",
-            "channel": channel
-        });
-
-        if !option.is_empty() {
-            msg.as_object_mut()
-                .unwrap()
-                .insert("option".into(), Value::String(option.to_string()));
-        }
+        private_command("subscribe", op_id, params)
+    }
 
-        Ok(vec![OutboundMsg {
-            text: msg.to_string(),
-        }])
+    fn build_unsubscribe(
+        &self,
+        op_id: &str,
+        _symbol: &str,
+        params: &Value,
+    ) -> Result<Vec<OutboundMsg>, String> {
+        private_command("unsubscribe", op_id, params)
     }
 
     fn classify_inbound(&self, raw: &[u8]) -> InboundClass {
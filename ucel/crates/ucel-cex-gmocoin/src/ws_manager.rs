@@ -1,3 +1,31 @@
-pub fn run_ingest() -> Result<(), String> {
-    Ok(())
+use std::sync::Arc;
+use ucel_registry::ingest::{IngestDriverRegistry, WsAdapterIngestDriver};
+
+use crate::rest::GmoCredentials;
+use crate::ws::{GmoCoinPrivateWsAdapter, GmoCoinPublicWsAdapter};
+
+/// `gmocoin` 系の ingest driver を registry に登録する（素の `gmocoin` は `gmocoin-public` の別名）
+///
+/// `gmocoin-private` は解決時に `GMO_API_KEY` / `GMO_API_SECRET` を読む。
+pub fn register_ingest_drivers(registry: &mut IngestDriverRegistry) {
+    for id in ["gmocoin", "gmocoin-public"] {
+        registry.register(id, move || {
+            Ok(WsAdapterIngestDriver::new(id, Arc::new(GmoCoinPublicWsAdapter::new())).boxed())
+        });
+    }
+    registry.register("gmocoin-private", || {
+        let api_key = std::env::var("GMO_API_KEY")
+            .map_err(|_| "missing env GMO_API_KEY (required for gmocoin-private)".to_string())?;
+        let api_secret = std::env::var("GMO_API_SECRET")
+            .map_err(|_| "missing env GMO_API_SECRET (required for gmocoin-private)".to_string())?;
+        let adapter = GmoCoinPrivateWsAdapter::new(GmoCredentials {
+            api_key,
+            api_secret,
+        })?;
+        Ok(
+            WsAdapterIngestDriver::new("gmocoin-private", Arc::new(adapter))
+                .private()
+                .boxed(),
+        )
+    });
 }
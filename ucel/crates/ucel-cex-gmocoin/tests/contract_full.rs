@@ -29,7 +29,7 @@ fn gmocoin_public_coverage_v2_all_families_build_subscribe_ok() {
 
     // CI-safe: fixed symbol list (GMO public WS expects "BTC" like examples)
     let symbols = vec!["BTC".to_string(), "ETH".to_string()];
-    let plan = generate_plan_v2("gmocoin-public", &cov, &symbols, &rules);
+    let plan = generate_plan_v2("gmocoin-public", &cov, &symbols, &rules).expect("plan");
 
     let adapter = GmoCoinPublicWsAdapter::new();
 
@@ -61,7 +61,7 @@ fn gmocoin_private_coverage_v2_all_families_build_subscribe_ok() {
     let rules = load_for_exchange(&rules_dir, "gmocoin-private");
 
     let symbols: Vec<String> = vec![]; // private families are symbol-less
-    let plan = generate_plan_v2("gmocoin-private", &cov, &symbols, &rules);
+    let plan = generate_plan_v2("gmocoin-private", &cov, &symbols, &rules).expect("plan");
 
     // creds are not required for build_subscribe test (token is fetched on ws_url/fetch_symbols at runtime)
     let dummy = GmoCredentials {
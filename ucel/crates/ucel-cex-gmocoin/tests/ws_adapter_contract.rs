@@ -37,6 +37,31 @@ fn coverage_ops_are_all_supported_by_build_subscribe() {
     }
 }
 
+#[test]
+fn build_unsubscribe_mirrors_subscribe_frame() {
+    let a = GmoCoinWsAdapter::new();
+    let params = json!({"_topic": "trades|BTC_JPY", "option": "TAKER_ONLY"});
+
+    let sub: serde_json::Value = serde_json::from_str(
+        &a.build_subscribe("crypto.public.ws.trades.update", "BTC/JPY", &params)
+            .unwrap()[0]
+            .text,
+    )
+    .unwrap();
+    let unsub: serde_json::Value = serde_json::from_str(
+        &a.build_unsubscribe("crypto.public.ws.trades.update", "BTC/JPY", &params)
+            .unwrap()[0]
+            .text,
+    )
+    .unwrap();
+
+    assert_eq!(sub["command"], "subscribe");
+    assert_eq!(
+        unsub,
+        json!({"command": "unsubscribe", "channel": "trades", "symbol": "BTC_JPY", "option": "TAKER_ONLY"})
+    );
+}
+
 #[test]
 fn classify_inbound_maps_channel_to_op_id_and_symbol() {
     let a = GmoCoinWsAdapter::new();
@@ -1,3 +1,20 @@
-pub fn run_ingest() -> Result<(), String> {
-    Ok(())
+use std::sync::Arc;
+use ucel_registry::ingest::{IngestDriverRegistry, WsAdapterIngestDriver};
+
+use crate::ws::HtxSpotWsAdapter;
+
+/// `htx` 系の ingest driver を registry に登録する（素の `htx` は `htx-spot` の別名）
+pub fn register_ingest_drivers(registry: &mut IngestDriverRegistry) {
+    register(registry, "htx", HtxSpotWsAdapter::new);
+    register(registry, "htx-spot", HtxSpotWsAdapter::new);
+}
+
+fn register(
+    registry: &mut IngestDriverRegistry,
+    id: &'static str,
+    adapter: fn() -> HtxSpotWsAdapter,
+) {
+    registry.register(id, move || {
+        Ok(WsAdapterIngestDriver::new(id, Arc::new(adapter())).boxed())
+    });
 }
@@ -5,7 +5,11 @@ use std::collections::{HashMap, HashSet};
 use std::sync::Arc;
 use tokio::sync::mpsc;
 use ucel_core::order_gate::OrderGate;
-use ucel_core::{Decimal, ErrorCode, Exchange, OpName, Side, StepSize, TickSize, UcelError};
+use ucel_core::{
+    BookSide, Decimal, ErrorCode, Exchange, IngestResumeDirective, OpName, OrderBook,
+    OrderBookError, OrderBookUpdate, Side, StepSize, TickSize, UcelError,
+};
+use ucel_transport::ws::integrity::{book_crc32, checksum_levels, BookChecksumGuard};
 use ucel_transport::{enforce_auth_boundary, HttpRequest, RequestContext, RetryPolicy, Transport};
 use uuid::Uuid;
 
@@ -444,6 +448,7 @@ pub struct WsAdapterMetrics {
     pub ws_resubscribe_total: u64,
     pub ws_backpressure_overflow_total: u64,
     pub ws_orderbook_gap_total: u64,
+    pub ws_orderbook_checksum_mismatch_total: u64,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
@@ -476,12 +481,114 @@ impl WsBackpressureBuffer {
     }
 }
 
-#[derive(Debug, Default, Clone)]
+#[derive(Debug, Clone)]
 pub struct OrderBookSyncState {
-    pub sequence: Option<u64>,
-    pub bids: HashMap<String, String>,
-    pub asks: HashMap<String, String>,
+    /// 購読 depth で打ち切る（Kraken は範囲外になった段の削除を配信しない）
+    pub book: OrderBook,
     pub needs_resync: bool,
+    pub checksum_guard: BookChecksumGuard,
+    /// checksum 不一致で要求された復旧指示（snapshot 適用でクリア）
+    pub resync_directive: Option<IngestResumeDirective>,
+}
+
+/// spot v1 book の購読 depth。checksum も上位 10 段で計算される。
+pub const KRAKEN_BOOK_DEPTH: usize = 10;
+
+/// spot v1 book メッセージ（snapshot: "as"/"bs"、update: "a"/"b"/"c"）
+#[derive(Debug, Clone, PartialEq)]
+pub struct KrakenBookV1 {
+    pub pair: String,
+    pub snapshot: bool,
+    pub bids: Vec<(String, String)>,
+    pub asks: Vec<(String, String)>,
+    pub checksum: Option<u32>,
+}
+
+/// `[channelID, {..}, ({..},) "book-10", "XBT/USD"]` を読む。book 以外の配列は None。
+pub fn parse_kraken_book_v1(raw: &str) -> Result<Option<KrakenBookV1>, UcelError> {
+    let v: serde_json::Value = serde_json::from_str(raw)
+        .map_err(|e| UcelError::new(ErrorCode::Internal, format!("ws json parse error: {e}")))?;
+    let Some(items) = v.as_array() else {
+        return Ok(None);
+    };
+    let names: Vec<&str> = items.iter().filter_map(|i| i.as_str()).collect();
+    let (Some(channel), Some(pair)) = (names.first(), names.get(1)) else {
+        return Ok(None);
+    };
+    if !channel.starts_with("book") {
+        return Ok(None);
+    }
+
+    let levels = |v: &serde_json::Value| -> Vec<(String, String)> {
+        v.as_array()
+            .map(|ls| {
+                ls.iter()
+                    .filter_map(|l| {
+                        let l = l.as_array()?;
+                        Some((l.first()?.as_str()?.into(), l.get(1)?.as_str()?.into()))
+                    })
+                    .collect()
+            })
+            .unwrap_or_default()
+    };
+    let mut book = KrakenBookV1 {
+        pair: pair.to_string(),
+        snapshot: false,
+        bids: vec![],
+        asks: vec![],
+        checksum: None,
+    };
+    for obj in items.iter().filter_map(|i| i.as_object()) {
+        if let Some(a) = obj.get("as") {
+            book.snapshot = true;
+            book.asks = levels(a);
+        }
+        if let Some(b) = obj.get("bs") {
+            book.snapshot = true;
+            book.bids = levels(b);
+        }
+        if let Some(a) = obj.get("a") {
+            book.asks.extend(levels(a));
+        }
+        if let Some(b) = obj.get("b") {
+            book.bids.extend(levels(b));
+        }
+        if let Some(c) = obj.get("c").and_then(|c| c.as_str()) {
+            book.checksum = Some(c.parse().map_err(|_| {
+                UcelError::new(ErrorCode::Internal, format!("bad book checksum: {c}"))
+            })?);
+        }
+    }
+    Ok(Some(book))
+}
+
+/// Kraken の checksum 用表記: '.' を除き、先頭の 0 を落とす
+fn kraken_checksum_digits(s: &str) -> String {
+    let digits: String = s.chars().filter(|c| *c != '.').collect();
+    digits.trim_start_matches('0').to_string()
+}
+
+/// asks 上位 10 段（昇順）→ bids 上位 10 段（降順）の price / qty を連結した CRC32
+pub fn kraken_book_checksum(book: &OrderBook) -> u32 {
+    let mut payload = String::new();
+    let asks = checksum_levels(book.top(BookSide::Ask, KRAKEN_BOOK_DEPTH));
+    let bids = checksum_levels(book.top(BookSide::Bid, KRAKEN_BOOK_DEPTH));
+    for (p, q) in asks.iter().chain(&bids) {
+        payload.push_str(&kraken_checksum_digits(p));
+        payload.push_str(&kraken_checksum_digits(q));
+    }
+    book_crc32(&payload)
+}
+
+impl Default for OrderBookSyncState {
+    fn default() -> Self {
+        Self {
+            book: OrderBook::default().with_depth_cap(KRAKEN_BOOK_DEPTH),
+            needs_resync: false,
+            checksum_guard: BookChecksumGuard::default(),
+            resync_directive: None,
+        }
+    }
 }
 
 impl OrderBookSyncState {
@@ -491,10 +598,9 @@ impl OrderBookSyncState {
         bids: &[(String, String)],
         asks: &[(String, String)],
     ) {
-        self.sequence = Some(sequence);
-        self.needs_resync = false;
-        self.bids = bids.iter().cloned().collect();
-        self.asks = asks.iter().cloned().collect();
+        let applied = OrderBookUpdate::from_str_levels(bids, asks)
+            .and_then(|u| self.book.apply_snapshot(Some(sequence), &u.bids, &u.asks));
+        self.needs_resync = applied.is_err();
     }
 
     pub fn apply_delta(
@@ -504,29 +610,52 @@ impl OrderBookSyncState {
         asks: &[(String, String)],
         metrics: &mut WsAdapterMetrics,
     ) {
-        let expected_next = self.sequence.map(|v| v + 1);
-        if expected_next.is_none() || expected_next != Some(sequence) {
+        let applied = OrderBookUpdate::from_str_levels(bids, asks)
+            .and_then(|u| self.book.apply_update(u.with_sequence(sequence)));
+        if let Err(e) = applied {
             self.needs_resync = true;
-            metrics.ws_orderbook_gap_total += 1;
-            return;
+            if matches!(
+                e,
+                OrderBookError::Gap { .. } | OrderBookError::MissingSequence
+            ) {
+                metrics.ws_orderbook_gap_total += 1;
+            }
         }
+    }
 
-        self.sequence = Some(sequence);
-        for (price, qty) in bids {
-            if qty == "0" {
-                self.bids.remove(price);
-            } else {
-                self.bids.insert(price.clone(), qty.clone());
+    /// spot v1 book を適用し、配信された checksum と照合する。
+    /// v1 book に sequence は無いので、欠落検知は checksum に任せる。
+    pub fn apply_book_v1(&mut self, book: &KrakenBookV1, metrics: &mut WsAdapterMetrics) {
+        let update = match OrderBookUpdate::from_str_levels(&book.bids, &book.asks) {
+            Ok(u) => u,
+            Err(_) => {
+                self.needs_resync = true;
+                return;
             }
+        };
+        if book.snapshot {
+            self.resync_directive = None;
+            self.needs_resync = self
+                .book
+                .apply_snapshot(None, &update.bids, &update.asks)
+                .is_err();
+        } else if self.book.apply_update(update).is_err() {
+            self.needs_resync = true;
         }
-        for (price, qty) in asks {
-            if qty == "0" {
-                self.asks.remove(price);
-            } else {
-                self.asks.insert(price.clone(), qty.clone());
-            }
+
+        let Some(expected) = book.checksum else {
+            return;
+        };
+        if let Err(directive) = self.checksum_guard.verify(expected, self.checksum()) {
+            self.needs_resync = true;
+            self.resync_directive = Some(directive);
+            metrics.ws_orderbook_checksum_mismatch_total += 1;
         }
     }
+
+    pub fn checksum(&self) -> u32 {
+        kraken_book_checksum(&self.book)
+    }
 }
 
 #[derive(Debug, Default)]
@@ -19,6 +19,38 @@ impl KrakenSpotWsAdapter {
     }
 }
 
+/// subscribe / unsubscribe は `event` 以外同一のフレーム（book の depth、ohlc の interval も一致させる）
+fn subscription_msg(
+    event: &str,
+    op_id: &str,
+    symbol: &str,
+    params: &Value,
+) -> Result<Vec<OutboundMsg>, String> {
+    let msg = match op_id {
+        "kraken.public.ws.ticker" => {
+            json!({"event":event,"pair":[symbol],"subscription":{"name":"ticker"}})
+        }
+        "kraken.public.ws.trade" => {
+            json!({"event":event,"pair":[symbol],"subscription":{"name":"trade"}})
+        }
+        "kraken.public.ws.book" => {
+            let depth = params.get("depth").and_then(|v| v.as_u64()).unwrap_or(10);
+            json!({"event":event,"pair":[symbol],"subscription":{"name":"book","depth":depth}})
+        }
+        "kraken.public.ws.ohlc" => {
+            let interval = params.get("interval").and_then(|v| v.as_u64()).unwrap_or(1);
+            json!({"event":event,"pair":[symbol],"subscription":{"name":"ohlc","interval":interval}})
+        }
+        "kraken.public.ws.spread" => {
+            json!({"event":event,"pair":[symbol],"subscription":{"name":"spread"}})
+        }
+        _ => return Err(format!("unknown family_id: {op_id}")),
+    };
+    Ok(vec![OutboundMsg {
+        text: msg.to_string(),
+    }])
+}
+
 #[async_trait]
 impl WsVenueAdapter for KrakenSpotWsAdapter {
     fn exchange_id(&self) -> &str {
@@ -44,29 +76,16 @@ impl WsVenueAdapter for KrakenSpotWsAdapter {
         symbol: &str,
         params: &Value,
     ) -> Result<Vec<OutboundMsg>, String> {
-        let msg = match op_id {
-            "kraken.public.ws.ticker" => {
-                json!({"event":"subscribe","pair":[symbol],"subscription":{"name":"ticker"}})
-            }
-            "kraken.public.ws.trade" => {
-                json!({"event":"subscribe","pair":[symbol],"subscription":{"name":"trade"}})
-            }
-            "kraken.public.ws.book" => {
-                let depth = params.get("depth").and_then(|v| v.as_u64()).unwrap_or(10);
-                json!({"event":"subscribe","pair":[symbol],"subscription":{"name":"book","depth":depth}})
-            }
-            "kraken.public.ws.ohlc" => {
-                let interval = params.get("interval").and_then(|v| v.as_u64()).unwrap_or(1);
-                json!({"event":"subscribe","pair":[symbol],"subscription":{"name":"ohlc","interval":interval}})
-            }
-            "kraken.public.ws.spread" => {
-                json!({"event":"subscribe","pair":[symbol],"subscription":{"name":"spread"}})
-            }
-            _ => return Err(format!("unknown family_id: {op_id}")),
-        };
-        Ok(vec![OutboundMsg {
-            text: msg.to_string(),
-        }])
+        subscription_msg("subscribe", op_id, symbol, params)
+    }
+
+    fn build_unsubscribe(
+        &self,
+        op_id: &str,
+        symbol: &str,
+        params: &Value,
+    ) -> Result<Vec<OutboundMsg>, String> {
+        subscription_msg("unsubscribe", op_id, symbol, params)
     }
 
     fn classify_inbound(&self, raw: &[u8]) -> InboundClass {
@@ -0,0 +1,87 @@
+use ucel_cex_kraken::{
+    parse_kraken_book_v1, KrakenBookV1, OrderBookSyncState, WsAdapterMetrics, KRAKEN_BOOK_DEPTH,
+};
+use ucel_core::{BookSide, IngestResumeDirective};
+
+/// 1 行目は Kraken ドキュメント（Spot WebSocket v1 "Book Checksum"）の板そのもので、掲載 checksum は 974947235。
+/// 2 行目以降はその板に手で当てた update で、"c" はこのクレートではなく
+/// zlib.crc32 による参照実装で計算した（参照実装は上の掲載値を再現することを確認済み）。
+/// 実 venue のキャプチャではない。
+const FIXTURE: &str = include_str!("fixtures/ws.book-10.XBT-USD.ndjson");
+
+fn frames() -> Vec<KrakenBookV1> {
+    FIXTURE
+        .lines()
+        .map(|l| parse_kraken_book_v1(l).unwrap().unwrap())
+        .collect()
+}
+
+#[test]
+fn documented_book_and_deltas_pass_checksum_with_depth_truncation() {
+    let frames = frames();
+    assert!(frames[0].snapshot);
+    assert_eq!(frames[0].pair, "XBT/USD");
+    // a と b が別オブジェクトで届くメッセージも 1 件に畳む
+    assert_eq!(frames[3].asks.len(), 1);
+    assert_eq!(frames[3].bids.len(), 1);
+
+    let mut metrics = WsAdapterMetrics::default();
+    let mut ob = OrderBookSyncState::default();
+    for f in &frames {
+        ob.apply_book_v1(f, &mut metrics);
+        if let Some(c) = f.checksum {
+            assert_eq!(ob.checksum(), c);
+        }
+    }
+    assert!(ob.book.len(BookSide::Bid) <= KRAKEN_BOOK_DEPTH);
+    assert!(!ob.needs_resync);
+    assert_eq!(metrics.ws_orderbook_checksum_mismatch_total, 0);
+}
+
+#[test]
+fn checksum_mismatch_escalates_to_resnapshot() {
+    let mut frames = frames();
+    // bid の挿入を取りこぼした想定
+    frames[2].bids.clear();
+
+    let mut metrics = WsAdapterMetrics::default();
+    let mut ob = OrderBookSyncState::default();
+    for f in &frames[..3] {
+        ob.apply_book_v1(f, &mut metrics);
+    }
+    assert!(ob.needs_resync);
+    assert_eq!(
+        ob.resync_directive,
+        Some(IngestResumeDirective::ResnapshotThenResubscribe)
+    );
+    assert_eq!(metrics.ws_orderbook_checksum_mismatch_total, 1);
+
+    ob.apply_book_v1(&frames[0], &mut metrics);
+    assert!(!ob.needs_resync);
+    assert_eq!(ob.resync_directive, None);
+}
+
+#[test]
+fn non_book_frames_are_ignored() {
+    assert_eq!(
+        parse_kraken_book_v1(r#"{"event":"heartbeat"}"#).unwrap(),
+        None
+    );
+    assert_eq!(
+        parse_kraken_book_v1(
+            r#"[42,[["5541.2","1.0","1534614248.1","s","l",""]],"trade","XBT/USD"]"#
+        )
+        .unwrap(),
+        None
+    );
+}
+
+#[test]
+fn documented_book_example_checksum() {
+    // フィクスチャ 1 行目（ドキュメント掲載の板）単体で掲載 checksum になる
+    let frame = frames().remove(0);
+    let mut metrics = WsAdapterMetrics::default();
+    let mut ob = OrderBookSyncState::default();
+    ob.apply_book_v1(&frame, &mut metrics);
+    assert_eq!(ob.checksum(), 974947235);
+}
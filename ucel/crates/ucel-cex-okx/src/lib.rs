@@ -4,7 +4,13 @@ use std::collections::{BTreeMap, HashSet};
 use std::sync::Arc;
 use tokio::sync::mpsc;
 use tracing::info;
-use ucel_core::{ErrorCode, OpName, UcelError};
+use ucel_core::{
+    BookSide, ErrorCode, IngestResumeDirective, OpName, OrderBook, OrderBookApply, OrderBookError,
+    OrderBookUpdate, UcelError,
+};
+use ucel_transport::ws::integrity::{
+    book_crc32, checksum_levels, interleaved_checksum_payload, BookChecksumGuard,
+};
 use ucel_transport::{
     enforce_auth_boundary, HttpRequest, RequestContext, RetryPolicy, Transport, WsConnectRequest,
 };
@@ -143,8 +149,12 @@ pub struct OkxWsMetrics {
     pub ws_orderbook_gap_total: u64,
     pub ws_orderbook_resync_total: u64,
     pub ws_orderbook_recovered_total: u64,
+    pub ws_orderbook_checksum_mismatch_total: u64,
 }
 
+/// OKX の books checksum は bid / ask 上位 25 段を交互に連結した CRC32（i32）
+pub const OKX_BOOK_CHECKSUM_DEPTH: usize = 25;
+
 #[derive(Debug)]
 pub struct WsBackpressureBuffer {
     tx: mpsc::Sender<NormalizedWsEvent>,
@@ -170,46 +180,88 @@ impl WsBackpressureBuffer {
 
 #[derive(Debug, Clone, Default)]
 pub struct OrderBookSyncState {
-    pub seq_id: Option<u64>,
+    /// seqId / prevSeqId の連続性は `SequencePolicy::Contiguous` で検査する
+    pub book: OrderBook,
     pub degraded: bool,
-    pub bids: BTreeMap<String, String>,
-    pub asks: BTreeMap<String, String>,
+    pub checksum_guard: BookChecksumGuard,
+    /// checksum 不一致で要求された復旧指示（snapshot 適用でクリア）
+    pub resync_directive: Option<IngestResumeDirective>,
 }
 
 impl OrderBookSyncState {
-    pub fn apply_snapshot(&mut self, book: &OkxBookData, metrics: Option<&mut OkxWsMetrics>) {
-        self.seq_id = book.seq_id;
+    pub fn apply_snapshot(&mut self, book: &OkxBookData, mut metrics: Option<&mut OkxWsMetrics>) {
         if self.degraded {
-            if let Some(m) = metrics {
+            if let Some(m) = metrics.as_deref_mut() {
                 m.ws_orderbook_recovered_total += 1;
             }
         }
         self.degraded = false;
-        self.bids = book.bids.iter().cloned().collect();
-        self.asks = book.asks.iter().cloned().collect();
+        self.resync_directive = None;
+        let applied = OrderBookUpdate::from_str_levels(&book.bids, &book.asks)
+            .and_then(|u| self.book.apply_snapshot(book.seq_id, &u.bids, &u.asks));
+        if applied.is_err() {
+            self.degraded = true;
+            if let Some(m) = metrics.as_deref_mut() {
+                m.ws_orderbook_resync_total += 1;
+            }
+            return;
+        }
+        self.verify_checksum(book.checksum, metrics);
     }
 
     pub fn apply_delta(&mut self, book: &OkxBookData, metrics: &mut OkxWsMetrics) {
-        let expected = self.seq_id.map(|s| s + 1);
-        if expected.is_none() || book.prev_seq_id != self.seq_id || book.seq_id != expected {
-            self.degraded = true;
-            metrics.ws_orderbook_gap_total += 1;
-            metrics.ws_orderbook_resync_total += 1;
-            return;
+        let mut update = match OrderBookUpdate::from_str_levels(&book.bids, &book.asks) {
+            Ok(u) => u,
+            Err(_) => {
+                self.book.invalidate();
+                self.degraded = true;
+                metrics.ws_orderbook_resync_total += 1;
+                return;
+            }
+        };
+        if let Some(seq_id) = book.seq_id {
+            update = update.with_sequence(seq_id);
         }
-        self.seq_id = book.seq_id;
-        for (price, qty) in &book.bids {
-            if qty == "0" {
-                self.bids.remove(price);
-            } else {
-                self.bids.insert(price.clone(), qty.clone());
+        if let Some(prev) = book.prev_seq_id {
+            update = update.with_prev_sequence(prev);
+        }
+        match self.book.apply_update(update) {
+            Ok(OrderBookApply::Applied) => {}
+            Ok(OrderBookApply::Buffered) => return,
+            Err(e) => {
+                self.degraded = true;
+                if matches!(
+                    e,
+                    OrderBookError::Gap { .. } | OrderBookError::MissingSequence
+                ) {
+                    metrics.ws_orderbook_gap_total += 1;
+                }
+                metrics.ws_orderbook_resync_total += 1;
+                return;
             }
         }
-        for (price, qty) in &book.asks {
-            if qty == "0" {
-                self.asks.remove(price);
-            } else {
-                self.asks.insert(price.clone(), qty.clone());
+        self.verify_checksum(book.checksum, Some(metrics));
+    }
+
+    /// 現在の板から OKX 方式の checksum を計算する
+    pub fn checksum(&self) -> i32 {
+        let bids = checksum_levels(self.book.top(BookSide::Bid, OKX_BOOK_CHECKSUM_DEPTH));
+        let asks = checksum_levels(self.book.top(BookSide::Ask, OKX_BOOK_CHECKSUM_DEPTH));
+        book_crc32(&interleaved_checksum_payload(&bids, &asks)) as i32
+    }
+
+    /// 配信された checksum と照合し、不一致なら degraded にして復旧指示を残す
+    fn verify_checksum(&mut self, expected: Option<i64>, metrics: Option<&mut OkxWsMetrics>) {
+        let Some(expected) = expected else {
+            return;
+        };
+        let computed = self.checksum() as u32;
+        if let Err(directive) = self.checksum_guard.verify(expected as i32 as u32, computed) {
+            self.degraded = true;
+            self.resync_directive = Some(directive);
+            if let Some(m) = metrics {
+                m.ws_orderbook_checksum_mismatch_total += 1;
+                m.ws_orderbook_resync_total += 1;
             }
         }
     }
@@ -364,12 +416,16 @@ struct OkxWsData {
     inst_id: Option<String>,
     #[serde(rename = "seqId", default)]
     seq_id: Option<u64>,
+    /// snapshot では -1
     #[serde(rename = "prevSeqId", default)]
-    prev_seq_id: Option<u64>,
+    prev_seq_id: Option<i64>,
+    /// 実配信は [price, qty, "0", orders] の 4 要素
     #[serde(default)]
-    bids: Vec<(String, String)>,
+    bids: Vec<Vec<String>>,
     #[serde(default)]
-    asks: Vec<(String, String)>,
+    asks: Vec<Vec<String>>,
+    #[serde(default)]
+    checksum: Option<i64>,
 }
 
 #[derive(Debug, Clone)]
@@ -378,6 +434,16 @@ pub struct OkxBookData {
     pub prev_seq_id: Option<u64>,
     pub bids: Vec<(String, String)>,
     pub asks: Vec<(String, String)>,
+    pub checksum: Option<i64>,
+}
+
+fn okx_levels(raw: Vec<Vec<String>>) -> Vec<(String, String)> {
+    raw.into_iter()
+        .filter_map(|l| {
+            let mut it = l.into_iter();
+            Some((it.next()?, it.next()?))
+        })
+        .collect()
 }
 
 pub fn normalize_ws_event(channel_id: &str, raw: &str) -> Result<NormalizedWsEvent, UcelError> {
@@ -410,9 +476,10 @@ pub fn parse_okx_orderbook_payload(raw: &str) -> Result<Option<OkxBookData>, Uce
     };
     Ok(Some(OkxBookData {
         seq_id: data.seq_id,
-        prev_seq_id: data.prev_seq_id,
-        bids: data.bids,
-        asks: data.asks,
+        prev_seq_id: data.prev_seq_id.and_then(|v| u64::try_from(v).ok()),
+        bids: okx_levels(data.bids),
+        asks: okx_levels(data.asks),
+        checksum: data.checksum,
     }))
 }
 
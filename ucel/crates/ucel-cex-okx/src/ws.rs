@@ -36,6 +36,24 @@ impl OkxWsAdapter {
     }
 }
 
+/// `op` は "subscribe" / "unsubscribe"。args は両者で同一
+fn subscription_msg(op: &str, op_id: &str, symbol: &str) -> Result<Vec<OutboundMsg>, String> {
+    let channel = op_id
+        .strip_prefix("okx.public.ws.")
+        .ok_or("bad family_id")?;
+    if symbol.is_empty() {
+        let msg = json!({"op":op,"args":[{"channel":channel}]});
+        return Ok(vec![OutboundMsg {
+            text: msg.to_string(),
+        }]);
+    }
+    let inst = to_exchange_symbol(symbol);
+    let msg = json!({"op":op,"args":[{"channel":channel,"instId":inst}]});
+    Ok(vec![OutboundMsg {
+        text: msg.to_string(),
+    }])
+}
+
 #[async_trait]
 impl WsVenueAdapter for OkxWsAdapter {
     fn exchange_id(&self) -> &str {
@@ -60,20 +78,16 @@ impl WsVenueAdapter for OkxWsAdapter {
         symbol: &str,
         _params: &Value,
     ) -> Result<Vec<OutboundMsg>, String> {
-        let channel = op_id
-            .strip_prefix("okx.public.ws.")
-            .ok_or("bad family_id")?;
-        if symbol.is_empty() {
-            let msg = json!({"op":"subscribe","args":[{"channel":channel}]});
-            return Ok(vec![OutboundMsg {
-                text: msg.to_string(),
-            }]);
-        }
-        let inst = to_exchange_symbol(symbol);
-        let msg = json!({"op":"subscribe","args":[{"channel":channel,"instId":inst}]});
-        Ok(vec![OutboundMsg {
-            text: msg.to_string(),
-        }])
+        subscription_msg("subscribe", op_id, symbol)
+    }
+
+    fn build_unsubscribe(
+        &self,
+        op_id: &str,
+        symbol: &str,
+        _params: &Value,
+    ) -> Result<Vec<OutboundMsg>, String> {
+        subscription_msg("unsubscribe", op_id, symbol)
     }
 
     fn classify_inbound(&self, raw: &[u8]) -> InboundClass {
@@ -1,3 +1,19 @@
-pub fn run_ingest() -> Result<(), String> {
-    Ok(())
+use std::sync::Arc;
+use ucel_registry::ingest::{IngestDriverRegistry, WsAdapterIngestDriver};
+
+use crate::ws::OkxWsAdapter;
+
+/// `okx` 系の ingest driver を registry に登録する（素の `okx` は `okx-spot` の別名）
+pub fn register_ingest_drivers(registry: &mut IngestDriverRegistry) {
+    register(registry, "okx", OkxWsAdapter::spot);
+    register(registry, "okx-spot", OkxWsAdapter::spot);
+    register(registry, "okx-swap", OkxWsAdapter::swap);
+    register(registry, "okx-futures", OkxWsAdapter::futures);
+    register(registry, "okx-option", OkxWsAdapter::option);
+}
+
+fn register(registry: &mut IngestDriverRegistry, id: &'static str, adapter: fn() -> OkxWsAdapter) {
+    registry.register(id, move || {
+        Ok(WsAdapterIngestDriver::new(id, Arc::new(adapter())).boxed())
+    });
 }
@@ -0,0 +1,86 @@
+use ucel_cex_okx::{parse_okx_orderbook_payload, OkxBookData, OkxWsMetrics, OrderBookSyncState};
+use ucel_core::IngestResumeDirective;
+use ucel_transport::ws::integrity::book_crc32;
+
+/// 1 行目は OKX ドキュメント（Order book checksum）の snapshot 例そのもので、checksum も掲載値。
+/// seqId / prevSeqId は books push の例に合わせて足した。
+/// 2 行目以降はその板に手で当てた update で、checksum はこのクレートではなく
+/// zlib.crc32 による参照実装で計算した（参照実装は上の掲載値を再現することを確認済み）。
+/// 実 venue のキャプチャではない。
+const FIXTURE: &str = include_str!("fixtures/ws.books.BTC-USDT.ndjson");
+
+fn frames() -> Vec<OkxBookData> {
+    FIXTURE
+        .lines()
+        .map(|l| parse_okx_orderbook_payload(l).unwrap().unwrap())
+        .collect()
+}
+
+#[test]
+fn documented_book_and_deltas_pass_checksum() {
+    let frames = frames();
+    let mut metrics = OkxWsMetrics::default();
+    let mut ob = OrderBookSyncState::default();
+    ob.apply_snapshot(&frames[0], Some(&mut metrics));
+    assert_eq!(Some(ob.checksum() as i64), frames[0].checksum);
+    for f in &frames[1..] {
+        ob.apply_delta(f, &mut metrics);
+        assert_eq!(Some(ob.checksum() as i64), f.checksum);
+    }
+    assert!(!ob.degraded);
+    assert_eq!(ob.resync_directive, None);
+    assert_eq!(metrics.ws_orderbook_checksum_mismatch_total, 0);
+}
+
+#[test]
+fn checksum_mismatch_requests_resnapshot() {
+    let mut frames = frames();
+    // 受信途中で 1 段の数量が化けた想定
+    frames[2].asks[1].1 = "9.99999999".into();
+
+    let mut metrics = OkxWsMetrics::default();
+    let mut ob = OrderBookSyncState::default();
+    ob.apply_snapshot(&frames[0], Some(&mut metrics));
+    ob.apply_delta(&frames[1], &mut metrics);
+    ob.apply_delta(&frames[2], &mut metrics);
+    assert!(ob.degraded);
+    assert_eq!(
+        ob.resync_directive,
+        Some(IngestResumeDirective::ResnapshotThenResubscribe)
+    );
+    assert_eq!(metrics.ws_orderbook_checksum_mismatch_total, 1);
+    assert_eq!(metrics.ws_orderbook_resync_total, 1);
+
+    ob.apply_snapshot(&frames[0], Some(&mut metrics));
+    assert!(!ob.degraded);
+    assert_eq!(ob.resync_directive, None);
+    assert_eq!(ob.checksum_guard.consecutive_mismatches, 0);
+    assert_eq!(metrics.ws_orderbook_recovered_total, 1);
+}
+
+/// OKX のドキュメント（Order book checksum）の計算例。
+/// ドキュメントが示すのは check string で、期待値はその標準 CRC-32（符号付き 32bit）。
+#[test]
+fn documented_check_string_examples() {
+    let cases = [
+        (
+            r#"{"arg":{"channel":"books","instId":"BTC-USDT"},"action":"snapshot","data":[{"asks":[["3366.8","9","10","3"],["3368","8","3","4"]],"bids":[["3366.1","7","0","3"],["3366","6","3","4"]],"ts":"1597026383085","checksum":-1881014294}]}"#,
+            "3366.1:7:3366.8:9:3366:6:3368:8",
+            -1881014294,
+        ),
+        (
+            r#"{"arg":{"channel":"books","instId":"BTC-USDT"},"action":"snapshot","data":[{"asks":[["3366.8","9","10","3"],["3368","8","3","4"]],"bids":[["3366.1","7","0","3"]],"ts":"1597026383085","checksum":-1471518219}]}"#,
+            "3366.1:7:3366.8:9:3368:8",
+            -1471518219,
+        ),
+    ];
+    for (frame, check_string, expected) in cases {
+        assert_eq!(book_crc32(check_string) as i32, expected);
+        let f = parse_okx_orderbook_payload(frame).unwrap().unwrap();
+        let mut metrics = OkxWsMetrics::default();
+        let mut ob = OrderBookSyncState::default();
+        ob.apply_snapshot(&f, Some(&mut metrics));
+        assert_eq!(ob.checksum(), expected);
+        assert!(!ob.degraded);
+    }
+}
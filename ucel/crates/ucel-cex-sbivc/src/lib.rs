@@ -8,8 +8,8 @@ use tokio::sync::mpsc;
 use tracing::info;
 use ucel_core::decimal::serde::deserialize_decimal_observation;
 use ucel_core::{
-    Decimal, ErrorCode, OrderBookDelta, OrderBookLevel, OrderBookSnapshot, Side, TradeEvent,
-    UcelError,
+    Decimal, ErrorCode, OrderBook, OrderBookApply, OrderBookDelta, OrderBookLevel,
+    OrderBookSnapshot, Side, TradeEvent, UcelError,
 };
 use ucel_transport::security::{EndpointAllowlist, SubdomainPolicy};
 
@@ -250,31 +250,40 @@ pub fn parse_market_event(id: &str, body: &[u8]) -> Result<MarketEvent, UcelErro
 
 #[derive(Debug, Default)]
 pub struct OrderbookResync {
-    expected_next: Option<u64>,
+    book: OrderBook,
     pub degraded: bool,
 }
 
 impl OrderbookResync {
     pub fn on_snapshot(&mut self, snapshot: &OrderBookSnapshot) {
-        self.expected_next = Some(snapshot.sequence.saturating_add(1));
-        self.degraded = false;
+        self.degraded = self.book.apply_snapshot_levels(snapshot).is_err();
     }
 
+    /// 適用したら true。既に反映済みの差分と snapshot 待ちで buffer した差分は false。
     pub fn on_delta(&mut self, delta: &OrderBookDelta) -> Result<bool, UcelError> {
-        let Some(next) = self.expected_next else {
-            self.degraded = true;
-            return Err(UcelError::new(ErrorCode::Desync, "missing snapshot"));
-        };
-        if delta.sequence_end < next {
+        if self.book.is_synced()
+            && self
+                .book
+                .sequence()
+                .is_some_and(|last| delta.sequence_end <= last)
+        {
             return Ok(false);
         }
-        if delta.sequence_start != next {
-            self.degraded = true;
-            self.expected_next = None;
-            return Err(UcelError::new(ErrorCode::Desync, "gap mismatch"));
+        match self.book.apply_update(delta.into()) {
+            Ok(OrderBookApply::Applied) => Ok(true),
+            Ok(OrderBookApply::Buffered) => Ok(false),
+            Err(e) => {
+                self.degraded = true;
+                Err(UcelError::new(
+                    ErrorCode::Desync,
+                    format!("gap mismatch: {e}"),
+                ))
+            }
         }
-        self.expected_next = Some(delta.sequence_end.saturating_add(1));
-        Ok(true)
+    }
+
+    pub fn book(&self) -> &OrderBook {
+        &self.book
     }
 
     pub fn recover_with_snapshot(&mut self, snapshot: &OrderBookSnapshot) {
@@ -1,11 +1,13 @@
 use bytes::Bytes;
 use serde::de::DeserializeOwned;
 use serde::{Deserialize, Serialize};
-use std::collections::{BTreeMap, HashSet};
+use std::collections::HashSet;
 use tokio::sync::mpsc;
 use tracing::info;
 use ucel_core::decimal::serde::deserialize_decimal_observation;
-use ucel_core::{Decimal, ErrorCode, OpName, UcelError};
+use ucel_core::{
+    Decimal, ErrorCode, OpName, OrderBook, OrderBookUpdate, SequencePolicy, UcelError,
+};
 use ucel_transport::security::{EndpointAllowlist, SubdomainPolicy};
 use ucel_transport::{
     enforce_auth_boundary, HttpRequest, RequestContext, Transport, WsConnectRequest,
@@ -104,11 +106,19 @@ pub enum MarketEvent {
     Ticker {
         code: String,
         trade_price: Decimal,
+        /// ミリ秒
+        timestamp: Option<u64>,
     },
     Trade {
         code: String,
         trade_price: Decimal,
         trade_volume: Decimal,
+        /// "ASK"（売り）/ "BID"（買い）
+        ask_bid: Option<String>,
+        /// ミリ秒
+        trade_timestamp: Option<u64>,
+        /// 約定の一意番号
+        sequential_id: Option<u64>,
     },
     Orderbook {
         code: String,
@@ -140,6 +150,8 @@ enum UpbitMessage {
         code: String,
         #[serde(deserialize_with = "deserialize_decimal_observation")]
         trade_price: Decimal,
+        #[serde(default)]
+        timestamp: Option<u64>,
     },
     #[serde(rename = "trade")]
     Trade {
@@ -148,6 +160,12 @@ enum UpbitMessage {
         trade_price: Decimal,
         #[serde(deserialize_with = "deserialize_decimal_observation")]
         trade_volume: Decimal,
+        #[serde(default)]
+        ask_bid: Option<String>,
+        #[serde(default)]
+        trade_timestamp: Option<u64>,
+        #[serde(default)]
+        sequential_id: Option<u64>,
     },
     #[serde(rename = "orderbook")]
     Orderbook {
@@ -202,15 +220,29 @@ pub fn normalize_ws_message(raw: &str) -> Result<MarketEvent, UcelError> {
     let msg: UpbitMessage = serde_json::from_str(raw)
         .map_err(|e| UcelError::new(ErrorCode::Internal, format!("typed ws parse error: {e}")))?;
     Ok(match msg {
-        UpbitMessage::Ticker { code, trade_price } => MarketEvent::Ticker { code, trade_price },
+        UpbitMessage::Ticker {
+            code,
+            trade_price,
+            timestamp,
+        } => MarketEvent::Ticker {
+            code,
+            trade_price,
+            timestamp,
+        },
         UpbitMessage::Trade {
             code,
             trade_price,
             trade_volume,
+            ask_bid,
+            trade_timestamp,
+            sequential_id,
         } => MarketEvent::Trade {
             code,
             trade_price,
             trade_volume,
+            ask_bid,
+            trade_timestamp,
+            sequential_id,
         },
         UpbitMessage::Orderbook {
             code,
@@ -233,22 +265,54 @@ pub fn normalize_ws_message(raw: &str) -> Result<MarketEvent, UcelError> {
 
 // ─── オーダーブック同期 ────────────────────────────────────────────────────────
 
-#[derive(Debug, Default, Clone)]
+/// Upbit の orderbook は毎メッセージ全量（orderbook_units）で届く。
+/// 板は ucel-core の共有エンジン（sequence 検査なし）で持ち、timestamp の単調性だけを検査する。
+#[derive(Debug, Clone)]
 pub struct OrderbookSync {
     pub last_ts: Option<u64>,
     pub degraded: bool,
-    pub book: BTreeMap<String, f64>,
+    pub book: OrderBook,
+}
+
+impl Default for OrderbookSync {
+    fn default() -> Self {
+        Self {
+            last_ts: None,
+            degraded: false,
+            book: OrderBook::default().with_sequence_policy(SequencePolicy::None),
+        }
+    }
+}
+
+fn upbit_levels(units: &[UpbitOrderbookUnit]) -> OrderBookUpdate {
+    let (bids, asks) = units
+        .iter()
+        .map(|u| ((u.bid_price, u.bid_size), (u.ask_price, u.ask_size)))
+        .unzip();
+    OrderBookUpdate::new(bids, asks)
 }
 
 impl OrderbookSync {
-    pub fn apply_snapshot(&mut self, ts: u64) {
+    pub fn apply_snapshot(
+        &mut self,
+        ts: u64,
+        units: &[UpbitOrderbookUnit],
+    ) -> Result<(), UcelError> {
+        let levels = upbit_levels(units);
+        if let Err(e) = self.book.apply_snapshot(None, &levels.bids, &levels.asks) {
+            self.degraded = true;
+            return Err(e.into());
+        }
         self.last_ts = Some(ts);
         self.degraded = false;
+        Ok(())
     }
 
+    /// 後続のメッセージ。timestamp が進んでいなければ resync を要求し、進んでいれば板を置き換える。
     pub fn apply_delta(
         &mut self,
         ts: u64,
+        units: &[UpbitOrderbookUnit],
         metrics: &mut WsAdapterMetrics,
     ) -> Result<(), UcelError> {
         match self.last_ts {
@@ -262,6 +326,12 @@ impl OrderbookSync {
                 ))
             }
             Some(_) => {
+                let levels = upbit_levels(units);
+                if let Err(e) = self.book.apply_snapshot(None, &levels.bids, &levels.asks) {
+                    self.degraded = true;
+                    metrics.ws_orderbook_resync_total += 1;
+                    return Err(e.into());
+                }
                 self.last_ts = Some(ts);
                 Ok(())
             }
@@ -274,12 +344,18 @@ impl OrderbookSync {
         }
     }
 
-    pub fn resync(&mut self, snapshot_ts: u64, metrics: &mut WsAdapterMetrics) {
+    pub fn resync(
+        &mut self,
+        snapshot_ts: u64,
+        units: &[UpbitOrderbookUnit],
+        metrics: &mut WsAdapterMetrics,
+    ) -> Result<(), UcelError> {
         let was_degraded = self.degraded;
-        self.apply_snapshot(snapshot_ts);
+        self.apply_snapshot(snapshot_ts, units)?;
         if was_degraded {
             metrics.ws_orderbook_recovered_total += 1;
         }
+        Ok(())
     }
 
     pub fn mark_recovered(&mut self, metrics: &mut WsAdapterMetrics) {
@@ -529,6 +605,9 @@ pub struct UpbitMarket {
 pub struct UpbitTicker {
     #[serde(deserialize_with = "deserialize_decimal_observation")]
     pub trade_price: Decimal,
+    /// ミリ秒
+    #[serde(default)]
+    pub timestamp: Option<u64>,
 }
 
 #[derive(Debug, Clone, Deserialize, PartialEq)]
@@ -537,6 +616,15 @@ pub struct UpbitTrade {
     pub trade_price: Decimal,
     #[serde(deserialize_with = "deserialize_decimal_observation")]
     pub trade_volume: Decimal,
+    /// "ASK"（売り）/ "BID"（買い）
+    #[serde(default)]
+    pub ask_bid: Option<String>,
+    /// ミリ秒
+    #[serde(default)]
+    pub timestamp: Option<u64>,
+    /// 約定の一意番号
+    #[serde(default)]
+    pub sequential_id: Option<u64>,
 }
 
 #[derive(Debug, Clone, Deserialize, PartialEq)]
@@ -632,7 +720,10 @@ pub struct UpbitApiKey {
 
 // ─── レスポンスパーサ ──────────────────────────────────────────────────────────
 
-fn parse_upbit_response(endpoint_id: &str, body: &[u8]) -> Result<UpbitRestResponse, UcelError> {
+pub fn parse_upbit_response(
+    endpoint_id: &str,
+    body: &[u8],
+) -> Result<UpbitRestResponse, UcelError> {
     match endpoint_id {
         "quotation.public.rest.markets.list" => Ok(UpbitRestResponse::Markets(parse_json(body)?)),
         "quotation.public.rest.ticker.pairs" => Ok(UpbitRestResponse::Tickers(parse_json(body)?)),
@@ -910,14 +1001,29 @@ mod tests {
         );
     }
 
+    fn units(bid: &str, ask: &str) -> Vec<UpbitOrderbookUnit> {
+        vec![UpbitOrderbookUnit {
+            ask_price: ask.parse().unwrap(),
+            bid_price: bid.parse().unwrap(),
+            ask_size: "1".parse().unwrap(),
+            bid_size: "2".parse().unwrap(),
+        }]
+    }
+
     #[test]
     fn orderbook_gap_resync_recovered() {
         let mut sync = OrderbookSync::default();
         let mut m = WsAdapterMetrics::default();
-        sync.apply_snapshot(100);
-        assert!(sync.apply_delta(100, &mut m).is_err());
+        sync.apply_snapshot(100, &units("99", "101")).unwrap();
+        sync.apply_delta(101, &units("100", "102"), &mut m).unwrap();
+        assert_eq!(
+            sync.book.best_bid(),
+            Some(("100".parse().unwrap(), "2".parse().unwrap()))
+        );
+        assert_eq!(sync.book.len(ucel_core::BookSide::Ask), 1);
+        assert!(sync.apply_delta(101, &units("100", "102"), &mut m).is_err());
         assert!(sync.degraded);
-        sync.resync(101, &mut m);
+        sync.resync(102, &units("100", "102"), &mut m).unwrap();
         assert!(!sync.degraded);
         assert_eq!(m.ws_orderbook_gap_total, 1);
         assert_eq!(m.ws_orderbook_resync_total, 1);
@@ -928,8 +1034,11 @@ mod tests {
     fn duplicate_and_out_of_order_policy_forces_resync() {
         let mut sync = OrderbookSync::default();
         let mut m = WsAdapterMetrics::default();
-        sync.apply_snapshot(10);
-        assert!(sync.apply_delta(9, &mut m).is_err());
+        sync.apply_snapshot(10, &units("99", "101")).unwrap();
+        assert!(sync.apply_delta(9, &units("99", "101"), &mut m).is_err());
+        assert!(sync.degraded);
+        // 交差した板は desync
+        assert!(sync.apply_snapshot(11, &units("101", "99")).is_err());
         assert!(sync.degraded);
     }
 
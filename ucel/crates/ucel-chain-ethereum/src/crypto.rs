@@ -0,0 +1,80 @@
+use crate::errors::{reason_to_error, EvmReasonCode};
+use k256::ecdsa::{RecoveryId, Signature, SigningKey, VerifyingKey};
+use tiny_keccak::{Hasher, Keccak};
+use ucel_core::{EvmAddress, UcelError};
+
+/// secp256k1 の署名（r, s は 32 byte big-endian、s は常に low-s に正規化済み）
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RecoverableSignature {
+    pub r: [u8; 32],
+    pub s: [u8; 32],
+    /// R.y の偶奇（bit0）と R.x >= n（bit1）。EIP-155 の v / type-2 の yParity の元
+    pub recovery_id: u8,
+}
+
+pub fn keccak256(data: &[u8]) -> [u8; 32] {
+    let mut k = Keccak::v256();
+    k.update(data);
+    let mut out = [0u8; 32];
+    k.finalize(&mut out);
+    out
+}
+
+fn signer_err(message: impl Into<String>) -> UcelError {
+    reason_to_error(EvmReasonCode::InvalidSigner, message)
+}
+
+/// 0 < d < n を満たす秘密鍵だけを受け付ける
+fn signing_key(secret: &[u8; 32]) -> Result<SigningKey, UcelError> {
+    SigningKey::from_bytes(secret.into())
+        .map_err(|_| signer_err("private key out of secp256k1 range"))
+}
+
+fn address_of(vk: &VerifyingKey) -> EvmAddress {
+    let point = vk.to_encoded_point(false);
+    address_from_public_key(&point.as_bytes()[1..])
+}
+
+/// 非圧縮公開鍵（0x04 を除いた 64 byte）から keccak256 下位 20 byte のアドレスを作る
+pub fn address_from_public_key(xy: &[u8]) -> EvmAddress {
+    let hash = keccak256(xy);
+    EvmAddress(format!("0x{}", hex::encode(&hash[12..])))
+}
+
+pub fn address_from_secret(secret: &[u8; 32]) -> Result<EvmAddress, UcelError> {
+    let sk = signing_key(secret)?;
+    Ok(address_of(sk.verifying_key()))
+}
+
+/// 32 byte のメッセージハッシュに決定的 ECDSA（RFC 6979、k256 の定数時間実装）で署名する。
+/// s は low-s（EIP-2）に正規化し、それに合わせて recovery_id も反転する。
+pub fn sign_prehash(secret: &[u8; 32], hash: &[u8; 32]) -> Result<RecoverableSignature, UcelError> {
+    let sk = signing_key(secret)?;
+    let (sig, rid) = sk
+        .sign_prehash_recoverable(hash)
+        .map_err(|e| signer_err(format!("secp256k1 signing failed: {e}")))?;
+    let (sig, rid) = match sig.normalize_s() {
+        Some(low) => (low, RecoveryId::new(!rid.is_y_odd(), rid.is_x_reduced())),
+        None => (sig, rid),
+    };
+    let (r, s) = sig.split_bytes();
+    Ok(RecoverableSignature {
+        r: r.into(),
+        s: s.into(),
+        recovery_id: rid.to_byte(),
+    })
+}
+
+/// 署名とメッセージハッシュから署名者アドレスを復元する（ecrecover 相当）
+pub fn recover_address(
+    hash: &[u8; 32],
+    sig: &RecoverableSignature,
+) -> Result<EvmAddress, UcelError> {
+    let signature = Signature::from_scalars(sig.r, sig.s)
+        .map_err(|_| signer_err("signature scalar out of range"))?;
+    let rid = RecoveryId::from_byte(sig.recovery_id)
+        .ok_or_else(|| signer_err(format!("invalid recovery id {}", sig.recovery_id)))?;
+    let vk = VerifyingKey::recover_from_prehash(hash, &signature, rid)
+        .map_err(|e| signer_err(format!("signature recovery failed: {e}")))?;
+    Ok(address_of(&vk))
+}
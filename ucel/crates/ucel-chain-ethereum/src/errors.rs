@@ -12,6 +12,10 @@ pub enum EvmReasonCode {
     ReceiptTimeout,
     ReorgDetected,
     UnsupportedRpcMethod,
+    /// JSON-RPC の invalid params（-32602）など、リクエスト自体の不正
+    InvalidRequest,
+    /// 秘密鍵・署名・署名者アドレスの不一致など、署名側の入力不正
+    InvalidSigner,
 }
 
 pub fn reason_to_error(reason: EvmReasonCode, message: impl Into<String>) -> UcelError {
@@ -25,6 +29,8 @@ pub fn reason_to_error(reason: EvmReasonCode, message: impl Into<String>) -> Uce
         EvmReasonCode::ReceiptTimeout => ErrorCode::Timeout,
         EvmReasonCode::ReorgDetected => ErrorCode::Desync,
         EvmReasonCode::UnsupportedRpcMethod => ErrorCode::NotSupported,
+        EvmReasonCode::InvalidRequest => ErrorCode::InvalidOrder,
+        EvmReasonCode::InvalidSigner => ErrorCode::AuthFailed,
     };
     UcelError::new(code, message)
 }
@@ -0,0 +1,270 @@
+use crate::errors::EvmReasonCode;
+use crate::provider::EvmHttpProvider;
+use crate::rpc::{parse_batch_response, parse_single_response, JsonRpcRequest, JsonRpcResponse};
+use crate::types::{EvmProviderInfo, ProviderError, ProviderResponse};
+use bytes::Bytes;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{mpsc, Arc};
+use std::time::Duration;
+use ucel_core::{ErrorCode, OpName, UcelError};
+use ucel_transport::http::retry::parse_retry_after_ms;
+use ucel_transport::http::{ReliableHttpConfig, ReliableTransport};
+use ucel_transport::{
+    HttpRequest, HttpResponse, RequestContext, Transport, WsConnectRequest, WsStream,
+};
+
+#[derive(Debug, Clone)]
+pub struct JsonRpcHttpConfig {
+    pub endpoint: String,
+    /// 1 回の HTTP 試行のタイムアウト
+    pub request_timeout: Duration,
+    /// retry を含めた rpc_call 全体の待ち時間
+    pub call_timeout: Duration,
+    pub reliable: ReliableHttpConfig,
+}
+
+impl JsonRpcHttpConfig {
+    pub fn new(endpoint: impl Into<String>) -> Self {
+        Self {
+            endpoint: endpoint.into(),
+            request_timeout: Duration::from_secs(10),
+            call_timeout: Duration::from_secs(30),
+            reliable: ReliableHttpConfig::default(),
+        }
+    }
+}
+
+/// endpoint に JSON を POST するだけの Transport。limiter / retry は ReliableTransport 側で掛ける
+pub struct ReqwestRpcTransport {
+    client: reqwest::Client,
+    endpoint: String,
+}
+
+impl ReqwestRpcTransport {
+    pub fn new(endpoint: impl Into<String>, timeout: Duration) -> Result<Self, UcelError> {
+        let client = reqwest::Client::builder()
+            .timeout(timeout)
+            .build()
+            .map_err(|e| UcelError::new(ErrorCode::Internal, format!("rpc http client: {e}")))?;
+        Ok(Self {
+            client,
+            endpoint: endpoint.into(),
+        })
+    }
+}
+
+fn map_reqwest_error(e: reqwest::Error) -> UcelError {
+    if e.is_timeout() {
+        UcelError::new(ErrorCode::Timeout, format!("rpc request timed out: {e}"))
+    } else {
+        UcelError::new(ErrorCode::Network, format!("rpc request failed: {e}"))
+    }
+}
+
+#[allow(async_fn_in_trait)]
+impl Transport for ReqwestRpcTransport {
+    async fn send_http(
+        &self,
+        req: HttpRequest,
+        _ctx: RequestContext,
+    ) -> Result<HttpResponse, UcelError> {
+        let resp = self
+            .client
+            .post(format!("{}{}", self.endpoint, req.path))
+            .header(reqwest::header::CONTENT_TYPE, "application/json")
+            .body(req.body.unwrap_or_default())
+            .send()
+            .await
+            .map_err(map_reqwest_error)?;
+        let status = resp.status().as_u16();
+        let retry_after_ms = resp
+            .headers()
+            .get(reqwest::header::RETRY_AFTER)
+            .and_then(|v| v.to_str().ok())
+            .and_then(parse_retry_after_ms);
+        let body = resp.bytes().await.map_err(map_reqwest_error)?;
+        if status == 429 {
+            let mut err = UcelError::new(ErrorCode::RateLimited, "rpc rate limited");
+            err.retry_after_ms = retry_after_ms;
+            return Err(err);
+        }
+        if status >= 500 {
+            return Err(UcelError::new(
+                ErrorCode::Upstream5xx,
+                format!("rpc upstream status {status}"),
+            ));
+        }
+        Ok(HttpResponse { status, body })
+    }
+
+    async fn connect_ws(
+        &self,
+        _req: WsConnectRequest,
+        _ctx: RequestContext,
+    ) -> Result<WsStream, UcelError> {
+        Err(UcelError::new(
+            ErrorCode::NotSupported,
+            "use JsonRpcWsProvider for websocket endpoints",
+        ))
+    }
+}
+
+struct RpcJob {
+    request_id: u64,
+    body: Bytes,
+    reply: mpsc::Sender<Result<HttpResponse, UcelError>>,
+}
+
+fn request_context(provider: &str, request_id: u64) -> RequestContext {
+    RequestContext {
+        trace_id: format!("{provider}-{request_id}"),
+        request_id: request_id.to_string(),
+        run_id: provider.to_string(),
+        op: OpName::FetchStatus,
+        venue: provider.to_string(),
+        policy_id: "evm-jsonrpc".into(),
+        key_id: None,
+        requires_auth: false,
+    }
+}
+
+/// JSON-RPC 2.0 over HTTP の EvmHttpProvider。
+/// EvmHttpProvider は同期 trait なので、専用スレッドの current_thread runtime で
+/// ReliableTransport（limiter + retry）を回し、呼び出し側は channel で結果を待つ。
+/// 呼び出しは 1 件ずつ task として並行に流れ、同時実行数は ReliableTransport の limiter が絞る。
+pub struct JsonRpcHttpProvider {
+    info: EvmProviderInfo,
+    call_timeout: Duration,
+    next_id: AtomicU64,
+    jobs: tokio::sync::mpsc::UnboundedSender<RpcJob>,
+}
+
+impl JsonRpcHttpProvider {
+    pub fn new(info: EvmProviderInfo, config: JsonRpcHttpConfig) -> Result<Self, UcelError> {
+        let inner = ReqwestRpcTransport::new(config.endpoint, config.request_timeout)?;
+        let transport = Arc::new(ReliableTransport::new(Arc::new(inner), config.reliable));
+        let runtime = tokio::runtime::Builder::new_current_thread()
+            .enable_all()
+            .build()
+            .map_err(|e| UcelError::new(ErrorCode::Internal, format!("rpc runtime: {e}")))?;
+
+        let (jobs, mut rx) = tokio::sync::mpsc::unbounded_channel::<RpcJob>();
+        let provider = info.id.clone();
+        std::thread::Builder::new()
+            .name(format!("evm-rpc-http-{provider}"))
+            .spawn(move || {
+                // send_http の future は Send ではないので、同じスレッド上の LocalSet で並べる
+                let local = tokio::task::LocalSet::new();
+                local.block_on(&runtime, async move {
+                    // job ごとに task を起こすので、遅い呼び出しが後続の呼び出しを塞がない。
+                    // provider が drop されて Sender が無くなると抜ける
+                    while let Some(job) = rx.recv().await {
+                        let transport = Arc::clone(&transport);
+                        let ctx = request_context(&provider, job.request_id);
+                        tokio::task::spawn_local(async move {
+                            let req = HttpRequest {
+                                path: String::new(),
+                                method: "POST".into(),
+                                body: Some(job.body),
+                            };
+                            let out = transport.send_http(req, ctx).await;
+                            let _ = job.reply.send(out);
+                        });
+                    }
+                });
+            })
+            .map_err(|e| UcelError::new(ErrorCode::Internal, format!("rpc worker: {e}")))?;
+
+        Ok(Self {
+            info,
+            call_timeout: config.call_timeout,
+            next_id: AtomicU64::new(1),
+            jobs,
+        })
+    }
+
+    fn error(&self, reason: EvmReasonCode, message: impl Into<String>) -> ProviderError {
+        ProviderError {
+            provider: self.info.id.clone(),
+            reason,
+            message: message.into(),
+        }
+    }
+
+    fn next_id(&self) -> u64 {
+        self.next_id.fetch_add(1, Ordering::Relaxed)
+    }
+
+    /// transport 層の失敗（timeout / 429 / 5xx / 接続不可）は全て provider 不調として扱い、failover に回す
+    fn exchange(&self, request_id: u64, body: Vec<u8>) -> Result<HttpResponse, ProviderError> {
+        let (reply, rx) = mpsc::channel();
+        self.jobs
+            .send(RpcJob {
+                request_id,
+                body: Bytes::from(body),
+                reply,
+            })
+            .map_err(|_| self.error(EvmReasonCode::ProviderTimeout, "rpc worker stopped"))?;
+        match rx.recv_timeout(self.call_timeout) {
+            Ok(Ok(resp)) => Ok(resp),
+            Ok(Err(e)) => Err(self.error(EvmReasonCode::ProviderTimeout, e.message)),
+            Err(_) => Err(self.error(EvmReasonCode::ProviderTimeout, "rpc call timed out")),
+        }
+    }
+
+    /// 2xx 以外でも JSON-RPC のエラーオブジェクトが返っていればそちらを優先する
+    fn check_status(&self, resp: &HttpResponse) -> Result<(), ProviderError> {
+        if (200..300).contains(&resp.status) {
+            return Ok(());
+        }
+        let rpc_error = serde_json::from_slice::<JsonRpcResponse>(&resp.body)
+            .map(|r| r.error.is_some())
+            .unwrap_or(false);
+        if rpc_error {
+            return Ok(());
+        }
+        Err(self.error(
+            EvmReasonCode::ProviderTimeout,
+            format!("rpc http status {}", resp.status),
+        ))
+    }
+
+    /// 複数の呼び出しを 1 回の JSON-RPC バッチで送る。結果は calls と同じ順に並ぶ。
+    /// 外側の Err はバッチ全体の失敗、内側の Err は個々の呼び出しの失敗
+    pub fn batch_call(
+        &self,
+        calls: &[(&str, serde_json::Value)],
+    ) -> Result<Vec<Result<ProviderResponse, ProviderError>>, ProviderError> {
+        if calls.is_empty() {
+            return Ok(vec![]);
+        }
+        let requests: Vec<JsonRpcRequest> = calls
+            .iter()
+            .map(|(method, params)| JsonRpcRequest::new(self.next_id(), method, params.clone()))
+            .collect();
+        let body = serde_json::to_vec(&requests)
+            .map_err(|e| self.error(EvmReasonCode::InvalidRequest, e.to_string()))?;
+        let resp = self.exchange(requests[0].id, body)?;
+        self.check_status(&resp)?;
+        parse_batch_response(&self.info.id, &requests, &resp.body)
+    }
+}
+
+impl EvmHttpProvider for JsonRpcHttpProvider {
+    fn info(&self) -> &EvmProviderInfo {
+        &self.info
+    }
+
+    fn rpc_call(
+        &self,
+        method: &str,
+        params: serde_json::Value,
+    ) -> Result<ProviderResponse, ProviderError> {
+        let request = JsonRpcRequest::new(self.next_id(), method, params);
+        let body = serde_json::to_vec(&request)
+            .map_err(|e| self.error(EvmReasonCode::InvalidRequest, e.to_string()))?;
+        let resp = self.exchange(request.id, body)?;
+        self.check_status(&resp)?;
+        parse_single_response(&self.info.id, method, &resp.body)
+    }
+}
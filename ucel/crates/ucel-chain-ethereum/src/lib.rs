@@ -1,32 +1,48 @@
 pub mod balance;
+pub mod crypto;
 pub mod errors;
 pub mod fees;
 pub mod finality;
+pub mod http;
 pub mod logs;
 pub mod nonce;
 pub mod provider;
 pub mod receipt;
 pub mod reorg;
 pub mod resume;
+pub mod rlp;
+pub mod rpc;
 pub mod signer;
 pub mod tx;
 pub mod types;
+pub mod ws;
 
 use ucel_core::{ErrorCode, Exchange, OpName, UcelError};
 
 pub use balance::{get_block_number, get_chain_id, get_erc20_balance, get_native_balance};
+pub use crypto::{
+    address_from_public_key, address_from_secret, keccak256, recover_address, sign_prehash,
+    RecoverableSignature,
+};
 pub use errors::{reason_to_error, EvmReasonCode};
 pub use fees::{estimate_eip1559, estimate_legacy, FeePolicy};
 pub use finality::{finality_from_confirmations, FinalityPolicy};
-pub use logs::{cursor_after, get_logs, subscribe_logs};
+pub use http::{JsonRpcHttpConfig, JsonRpcHttpProvider, ReqwestRpcTransport};
+pub use logs::{cursor_after, get_logs, parse_log_event, subscribe_logs, subscribe_new_heads};
 pub use nonce::NonceManager;
 pub use provider::{EvmHttpProvider, EvmProviderPolicy, EvmProviderSet, EvmWsProvider};
 pub use receipt::{get_receipt, wait_for_receipt};
 pub use reorg::{detect_reorg, replay_range_for_reorg};
 pub use resume::{dedup_logs, resume_cursor};
-pub use signer::{redact_signer_material, DeterministicTestSigner, EvmSigner};
-pub use tx::{build_transaction, send_raw_transaction, sign_transaction};
+pub use rlp::{rlp_encode, RlpItem};
+pub use rpc::{rpc_error_reason, JsonRpcError, JsonRpcRequest, JsonRpcResponse};
+pub use signer::{redact_signer_material, DeterministicTestSigner, EvmSigner, LocalKeySigner};
+pub use tx::{
+    build_transaction, encode_signed_transaction, encode_unsigned_transaction, is_eip1559,
+    send_raw_transaction, sign_transaction, transaction_signing_hash,
+};
 pub use types::{EvmProviderInfo, ProviderError, ProviderResponse};
+pub use ws::{EvmWsEvent, JsonRpcWsConfig, JsonRpcWsProvider};
 
 pub struct EthereumAdapter;
 
@@ -0,0 +1,65 @@
+/// RLP（Recursive Length Prefix）エンコーダ。トランザクションの署名対象 / 送信形式の組み立てに使う。
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum RlpItem {
+    Bytes(Vec<u8>),
+    List(Vec<RlpItem>),
+}
+
+impl RlpItem {
+    pub fn bytes(b: impl Into<Vec<u8>>) -> Self {
+        Self::Bytes(b.into())
+    }
+
+    /// 整数は先頭ゼロを落とした big-endian（0 は空文字列）
+    pub fn uint(v: u128) -> Self {
+        Self::Bytes(trim_leading_zeros(&v.to_be_bytes()).to_vec())
+    }
+
+    /// 32 byte の署名値など、固定長 big-endian の整数
+    pub fn uint_bytes(be: &[u8]) -> Self {
+        Self::Bytes(trim_leading_zeros(be).to_vec())
+    }
+
+    pub fn list(items: Vec<RlpItem>) -> Self {
+        Self::List(items)
+    }
+}
+
+fn trim_leading_zeros(b: &[u8]) -> &[u8] {
+    let start = b.iter().position(|x| *x != 0).unwrap_or(b.len());
+    &b[start..]
+}
+
+fn length_prefix(out: &mut Vec<u8>, len: usize, short: u8, long: u8) {
+    if len <= 55 {
+        out.push(short + len as u8);
+    } else {
+        let len_be = trim_leading_zeros(&(len as u64).to_be_bytes()).to_vec();
+        out.push(long + len_be.len() as u8);
+        out.extend(len_be);
+    }
+}
+
+pub fn rlp_encode(item: &RlpItem) -> Vec<u8> {
+    let mut out = Vec::new();
+    encode_into(item, &mut out);
+    out
+}
+
+fn encode_into(item: &RlpItem, out: &mut Vec<u8>) {
+    match item {
+        RlpItem::Bytes(b) if b.len() == 1 && b[0] < 0x80 => out.push(b[0]),
+        RlpItem::Bytes(b) => {
+            length_prefix(out, b.len(), 0x80, 0xb7);
+            out.extend_from_slice(b);
+        }
+        RlpItem::List(items) => {
+            let mut payload = Vec::new();
+            for i in items {
+                encode_into(i, &mut payload);
+            }
+            length_prefix(out, payload.len(), 0xc0, 0xf7);
+            out.extend(payload);
+        }
+    }
+}
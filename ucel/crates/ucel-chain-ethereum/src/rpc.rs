@@ -0,0 +1,176 @@
+use crate::errors::EvmReasonCode;
+use crate::types::{ProviderError, ProviderResponse};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct JsonRpcRequest {
+    pub jsonrpc: &'static str,
+    pub id: u64,
+    pub method: String,
+    pub params: serde_json::Value,
+}
+
+impl JsonRpcRequest {
+    pub fn new(id: u64, method: &str, params: serde_json::Value) -> Self {
+        Self {
+            jsonrpc: "2.0",
+            id,
+            method: method.to_string(),
+            params,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct JsonRpcError {
+    pub code: i64,
+    pub message: String,
+    #[serde(default)]
+    pub data: Option<serde_json::Value>,
+}
+
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct JsonRpcResponse {
+    #[serde(default)]
+    pub id: Option<serde_json::Value>,
+    #[serde(default)]
+    pub result: Option<serde_json::Value>,
+    #[serde(default)]
+    pub error: Option<JsonRpcError>,
+}
+
+impl JsonRpcResponse {
+    pub fn id_u64(&self) -> Option<u64> {
+        match self.id.as_ref()? {
+            serde_json::Value::Number(n) => n.as_u64(),
+            serde_json::Value::String(s) => s.parse().ok(),
+            _ => None,
+        }
+    }
+}
+
+/// JSON-RPC のエラーを EvmReasonCode に寄せる。
+/// -32000 系はノード実装ごとに message で区別するしかないため、既知の文言を先に見る。
+/// ExecutionReverted は code 3 か revert data 付きのエラーに限る（文言だけでは判定しない）。
+pub fn rpc_error_reason(err: &JsonRpcError) -> EvmReasonCode {
+    let msg = err.message.to_ascii_lowercase();
+    if msg.contains("nonce too low") {
+        return EvmReasonCode::NonceTooLow;
+    }
+    if msg.contains("nonce too high") {
+        return EvmReasonCode::NonceTooHigh;
+    }
+    if msg.contains("underpriced") {
+        return EvmReasonCode::ReplacementUnderpriced;
+    }
+    if msg.contains("insufficient funds") {
+        return EvmReasonCode::InsufficientFunds;
+    }
+    match err.code {
+        // 3: revert data 付きの execution reverted
+        3 => EvmReasonCode::ExecutionReverted,
+        _ if has_revert_data(err) => EvmReasonCode::ExecutionReverted,
+        -32601 => EvmReasonCode::UnsupportedRpcMethod,
+        // parse error / invalid request / invalid params: 呼び出し側が組んだリクエストの誤りで、
+        // 別 provider に回しても直らない
+        -32700 | -32600 | -32602 => EvmReasonCode::InvalidRequest,
+        // 残り（-32000..-32099 / -32603 / 未知の code）はノード側の障害として failover に回す
+        _ => EvmReasonCode::ProviderTimeout,
+    }
+}
+
+/// error.data が revert data（0x 付きの hex。ノードによっては {"data": "0x.."} に包む）か
+fn has_revert_data(err: &JsonRpcError) -> bool {
+    let data = match err.data.as_ref() {
+        Some(serde_json::Value::Object(o)) => o.get("data"),
+        other => other,
+    };
+    data.and_then(|d| d.as_str())
+        .and_then(|d| d.strip_prefix("0x"))
+        .is_some_and(|hex| !hex.is_empty() && hex.chars().all(|c| c.is_ascii_hexdigit()))
+}
+
+pub fn rpc_error_to_provider_error(provider: &str, err: &JsonRpcError) -> ProviderError {
+    ProviderError {
+        provider: provider.to_string(),
+        reason: rpc_error_reason(err),
+        message: format!("rpc error {}: {}", err.code, err.message),
+    }
+}
+
+fn malformed(provider: &str, message: impl Into<String>) -> ProviderError {
+    ProviderError {
+        provider: provider.to_string(),
+        reason: EvmReasonCode::ProviderTimeout,
+        message: message.into(),
+    }
+}
+
+pub fn into_provider_response(
+    provider: &str,
+    method: &str,
+    resp: JsonRpcResponse,
+) -> Result<ProviderResponse, ProviderError> {
+    if let Some(err) = resp.error {
+        return Err(rpc_error_to_provider_error(provider, &err));
+    }
+    Ok(ProviderResponse {
+        provider: provider.to_string(),
+        method: method.to_string(),
+        value: resp.result.unwrap_or(serde_json::Value::Null),
+    })
+}
+
+/// 単発呼び出しのレスポンスを解釈する
+pub fn parse_single_response(
+    provider: &str,
+    method: &str,
+    body: &[u8],
+) -> Result<ProviderResponse, ProviderError> {
+    let resp: JsonRpcResponse = serde_json::from_slice(body)
+        .map_err(|e| malformed(provider, format!("malformed json-rpc response: {e}")))?;
+    into_provider_response(provider, method, resp)
+}
+
+/// バッチのレスポンスを id で突き合わせ、リクエスト順に並べ直す。
+/// 返ってこなかった id はその要素だけエラーにする。
+pub fn parse_batch_response(
+    provider: &str,
+    requests: &[JsonRpcRequest],
+    body: &[u8],
+) -> Result<Vec<Result<ProviderResponse, ProviderError>>, ProviderError> {
+    let parsed: serde_json::Value = serde_json::from_slice(body)
+        .map_err(|e| malformed(provider, format!("malformed json-rpc batch: {e}")))?;
+    let items = match parsed {
+        serde_json::Value::Array(items) => items,
+        // バッチ全体が拒否されると単発のエラーオブジェクトが返る
+        other => {
+            let resp: JsonRpcResponse = serde_json::from_value(other)
+                .map_err(|e| malformed(provider, format!("malformed json-rpc batch: {e}")))?;
+            return Err(match resp.error {
+                Some(err) => rpc_error_to_provider_error(provider, &err),
+                None => malformed(provider, "batch response is not an array"),
+            });
+        }
+    };
+
+    let mut by_id = HashMap::new();
+    for item in items {
+        let resp: JsonRpcResponse = serde_json::from_value(item)
+            .map_err(|e| malformed(provider, format!("malformed json-rpc batch item: {e}")))?;
+        if let Some(id) = resp.id_u64() {
+            by_id.insert(id, resp);
+        }
+    }
+    Ok(requests
+        .iter()
+        .map(|req| match by_id.remove(&req.id) {
+            Some(resp) => into_provider_response(provider, &req.method, resp),
+            None => Err(malformed(
+                provider,
+                format!("missing batch response for id {}", req.id),
+            )),
+        })
+        .collect())
+}
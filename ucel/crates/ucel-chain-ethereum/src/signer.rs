@@ -1,5 +1,9 @@
+use crate::crypto::{address_from_secret, keccak256, sign_prehash};
 use crate::errors::{reason_to_error, EvmReasonCode};
-use ucel_core::{EvmSignedTransaction, EvmTransactionRequest, UcelError};
+use crate::tx::{encode_signed_transaction, transaction_signing_hash};
+use std::fmt;
+use ucel_core::{EvmAddress, EvmSignedTransaction, EvmTransactionRequest, UcelError};
+use zeroize::Zeroizing;
 
 pub trait EvmSigner: Send + Sync {
     fn sign_transaction(
@@ -20,7 +24,7 @@ impl EvmSigner for DeterministicTestSigner {
     ) -> Result<EvmSignedTransaction, UcelError> {
         if self.signer_id.is_empty() {
             return Err(reason_to_error(
-                EvmReasonCode::ExecutionReverted,
+                EvmReasonCode::InvalidSigner,
                 "signer_id empty",
             ));
         }
@@ -36,6 +40,68 @@ impl EvmSigner for DeterministicTestSigner {
     }
 }
 
+/// secp256k1 秘密鍵で legacy（EIP-155）/ type-2（EIP-1559）トランザクションに署名する。
+/// 鍵は drop 時にゼロ化され、Debug にも出ない。
+pub struct LocalKeySigner {
+    secret: Zeroizing<[u8; 32]>,
+    address: EvmAddress,
+}
+
+impl LocalKeySigner {
+    /// 0x 付き / 無しの 64 桁 hex 秘密鍵から作る
+    pub fn from_secret_hex(secret_hex: &str) -> Result<Self, UcelError> {
+        let raw = Zeroizing::new(
+            secret_hex
+                .trim()
+                .trim_start_matches("0x")
+                .to_ascii_lowercase(),
+        );
+        let mut secret = Zeroizing::new([0u8; 32]);
+        hex::decode_to_slice(raw.as_bytes(), &mut secret[..]).map_err(|_| {
+            reason_to_error(
+                EvmReasonCode::InvalidSigner,
+                "private key must be 32 bytes of hex",
+            )
+        })?;
+        let address = address_from_secret(&secret)?;
+        Ok(Self { secret, address })
+    }
+
+    pub fn address(&self) -> &EvmAddress {
+        &self.address
+    }
+}
+
+impl fmt::Debug for LocalKeySigner {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("LocalKeySigner")
+            .field("address", &self.address.0)
+            .field("secret", &"***")
+            .finish()
+    }
+}
+
+impl EvmSigner for LocalKeySigner {
+    fn sign_transaction(
+        &self,
+        tx: &EvmTransactionRequest,
+    ) -> Result<EvmSignedTransaction, UcelError> {
+        if !tx.from.0.eq_ignore_ascii_case(&self.address.0) {
+            return Err(reason_to_error(
+                EvmReasonCode::InvalidSigner,
+                format!("tx.from {} does not match signer key", tx.from.0),
+            ));
+        }
+        let hash = transaction_signing_hash(tx)?;
+        let sig = sign_prehash(&self.secret, &hash)?;
+        let raw = encode_signed_transaction(tx, &sig)?;
+        Ok(EvmSignedTransaction {
+            tx_hash: format!("0x{}", hex::encode(keccak256(&raw))),
+            raw_tx_hex: format!("0x{}", hex::encode(&raw)),
+        })
+    }
+}
+
 pub fn redact_signer_material(input: &str) -> String {
     input
         .replace("private", "[redacted]")
@@ -1,5 +1,7 @@
+use crate::crypto::{keccak256, RecoverableSignature};
 use crate::errors::{reason_to_error, EvmReasonCode};
 use crate::provider::EvmProviderSet;
+use crate::rlp::{rlp_encode, RlpItem};
 use crate::signer::EvmSigner;
 use ucel_core::{
     validate_chain_id, validate_evm_address, EvmAddress, EvmChainId, EvmFeeEstimate,
@@ -34,6 +36,112 @@ pub fn build_transaction(
     })
 }
 
+/// 組み立て前の入力不正。送信もしていないので revert 扱いにはしない
+fn invalid_tx(message: impl Into<String>) -> UcelError {
+    reason_to_error(EvmReasonCode::InvalidRequest, message)
+}
+
+fn hex_field(name: &str, s: &str) -> Result<Vec<u8>, UcelError> {
+    hex::decode(s.strip_prefix("0x").unwrap_or(s))
+        .map_err(|_| invalid_tx(format!("{name} is not valid hex")))
+}
+
+/// max_fee_per_gas があれば type-2（EIP-1559）、無ければ legacy（EIP-155）で組み立てる
+pub fn is_eip1559(tx: &EvmTransactionRequest) -> bool {
+    tx.fee.max_fee_per_gas.is_some()
+}
+
+/// 署名対象 / 送信形式で共通の先頭フィールド
+fn common_fields(tx: &EvmTransactionRequest) -> Result<Vec<RlpItem>, UcelError> {
+    let to = match &tx.to {
+        Some(to) => {
+            let to = hex_field("to", &to.0)?;
+            if to.len() != 20 {
+                return Err(invalid_tx("to must be a 20 byte address"));
+            }
+            to
+        }
+        None => vec![],
+    };
+    let tail = [
+        RlpItem::uint(u128::from(tx.gas_limit)),
+        RlpItem::bytes(to),
+        RlpItem::uint(tx.value_wei),
+        RlpItem::bytes(hex_field("data", &tx.data_hex)?),
+    ];
+
+    let mut fields = Vec::with_capacity(9);
+    if is_eip1559(tx) {
+        let tip = tx
+            .fee
+            .max_priority_fee_per_gas
+            .ok_or_else(|| invalid_tx("max_priority_fee_per_gas required for type-2 tx"))?;
+        fields.push(RlpItem::uint(u128::from(tx.chain_id.0)));
+        fields.push(RlpItem::uint(u128::from(tx.nonce)));
+        fields.push(RlpItem::uint(tip));
+        fields.push(RlpItem::uint(tx.fee.max_fee_per_gas.unwrap_or_default()));
+        fields.extend(tail);
+        // access list は未対応（常に空）
+        fields.push(RlpItem::list(vec![]));
+    } else {
+        let gas_price = tx
+            .fee
+            .legacy_gas_price
+            .ok_or_else(|| invalid_tx("legacy_gas_price required for legacy tx"))?;
+        fields.push(RlpItem::uint(u128::from(tx.nonce)));
+        fields.push(RlpItem::uint(gas_price));
+        fields.extend(tail);
+    }
+    Ok(fields)
+}
+
+fn typed_envelope(payload: Vec<u8>) -> Vec<u8> {
+    let mut out = Vec::with_capacity(payload.len() + 1);
+    out.push(0x02);
+    out.extend(payload);
+    out
+}
+
+/// 署名前のエンコード（legacy は EIP-155 の chainId, 0, 0 を末尾に付ける）
+pub fn encode_unsigned_transaction(tx: &EvmTransactionRequest) -> Result<Vec<u8>, UcelError> {
+    validate_chain_id(tx.chain_id)?;
+    let mut fields = common_fields(tx)?;
+    if is_eip1559(tx) {
+        return Ok(typed_envelope(rlp_encode(&RlpItem::list(fields))));
+    }
+    fields.push(RlpItem::uint(u128::from(tx.chain_id.0)));
+    fields.push(RlpItem::uint(0));
+    fields.push(RlpItem::uint(0));
+    Ok(rlp_encode(&RlpItem::list(fields)))
+}
+
+pub fn transaction_signing_hash(tx: &EvmTransactionRequest) -> Result<[u8; 32], UcelError> {
+    Ok(keccak256(&encode_unsigned_transaction(tx)?))
+}
+
+/// eth_sendRawTransaction に渡す署名済みエンコード
+pub fn encode_signed_transaction(
+    tx: &EvmTransactionRequest,
+    sig: &RecoverableSignature,
+) -> Result<Vec<u8>, UcelError> {
+    validate_chain_id(tx.chain_id)?;
+    let mut fields = common_fields(tx)?;
+    let parity = u128::from(sig.recovery_id & 1);
+    if is_eip1559(tx) {
+        fields.push(RlpItem::uint(parity));
+    } else {
+        fields.push(RlpItem::uint(u128::from(tx.chain_id.0) * 2 + 35 + parity));
+    }
+    fields.push(RlpItem::uint_bytes(&sig.r));
+    fields.push(RlpItem::uint_bytes(&sig.s));
+    let encoded = rlp_encode(&RlpItem::list(fields));
+    Ok(if is_eip1559(tx) {
+        typed_envelope(encoded)
+    } else {
+        encoded
+    })
+}
+
 pub fn sign_transaction(
     signer: &dyn EvmSigner,
     tx: &EvmTransactionRequest,
@@ -55,9 +163,10 @@ pub fn send_raw_transaction(
         .map_err(|e| reason_to_error(e.reason, e.message))?;
     let tx_hash = r.value.as_str().unwrap_or_default().to_string();
     if !tx_hash.starts_with("0x") {
+        // 応答が tx hash になっていないのは provider 側の不具合
         return Err(reason_to_error(
-            EvmReasonCode::ExecutionReverted,
-            "invalid tx hash",
+            EvmReasonCode::ProviderTimeout,
+            format!("provider returned an invalid tx hash: {}", r.value),
         ));
     }
     Ok(tx_hash)
@@ -0,0 +1,571 @@
+use crate::errors::EvmReasonCode;
+use crate::provider::EvmWsProvider;
+use crate::rpc::{into_provider_response, JsonRpcRequest, JsonRpcResponse};
+use crate::types::{EvmProviderInfo, ProviderError, ProviderResponse};
+use futures_util::{SinkExt, StreamExt};
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{mpsc, Mutex};
+use std::time::{Duration, Instant};
+use tokio::sync::mpsc as tokio_mpsc;
+use tokio_tungstenite::tungstenite::Message;
+use ucel_transport::next_retry_delay_ms;
+use ucel_transport::ws::limiter::{BucketConfig, WsRateLimiter, WsRateLimiterConfig};
+use ucel_transport::ws::priority::OutboundPriority;
+use ucel_transport::RetryPolicy;
+
+#[derive(Debug, Clone)]
+pub struct JsonRpcWsConfig {
+    pub url: String,
+    pub call_timeout: Duration,
+    /// 1 回の接続（初回・張り直し）で試す回数
+    pub connect_attempts: u32,
+    pub retry: RetryPolicy,
+    pub limiter: WsRateLimiterConfig,
+    /// 切断時に張り直して購読をやり直すか。false なら切断で Closed になる
+    pub reconnect: bool,
+}
+
+impl JsonRpcWsConfig {
+    pub fn new(url: impl Into<String>) -> Self {
+        Self {
+            url: url.into(),
+            call_timeout: Duration::from_secs(10),
+            connect_attempts: 3,
+            retry: RetryPolicy {
+                base_delay_ms: 200,
+                max_delay_ms: 5_000,
+                jitter_ms: 100,
+                respect_retry_after: true,
+            },
+            limiter: WsRateLimiterConfig {
+                control: BucketConfig::per_second(10.0),
+                private: BucketConfig::per_second(10.0),
+                public: BucketConfig::per_second(20.0),
+                min_gap: Duration::ZERO,
+            },
+            reconnect: true,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum EvmWsEvent {
+    /// eth_subscription の通知（newHeads ならブロックヘッダ、logs ならログ 1 件）。
+    /// subscription は張り直し後も subscribe が返した id のまま
+    Notification {
+        subscription: String,
+        result: serde_json::Value,
+    },
+    /// 切断後に張り直した。切断中の通知は届いていないので、呼び出し側で取りこぼしを埋める。
+    /// failed の購読は張り直せず、以後通知は来ない
+    Reconnected {
+        reason: String,
+        resubscribed: Vec<String>,
+        failed: Vec<String>,
+    },
+    /// 接続が切れて張り直せなかった。以降の呼び出しは全てエラーになるので、購読は別の provider で張り直す
+    Closed { reason: String },
+}
+
+type CallReply = mpsc::Sender<Result<ProviderResponse, ProviderError>>;
+type Ws =
+    tokio_tungstenite::WebSocketStream<tokio_tungstenite::MaybeTlsStream<tokio::net::TcpStream>>;
+
+/// 1 フレームで送る呼び出し。2 件以上なら JSON-RPC バッチになる
+struct WsCall {
+    requests: Vec<(JsonRpcRequest, CallReply)>,
+    priority: OutboundPriority,
+}
+
+/// 張り直し時の eth_subscribe に使う id。呼び出し側の id（1 から採番）とは重ならない
+const RESUBSCRIBE_ID_BASE: u64 = 1 << 63;
+
+/// JSON-RPC 2.0 over WebSocket の EvmWsProvider。
+/// 接続は専用スレッドが持ち、応答は id で、eth_subscription 通知は events に振り分ける。
+/// 切断すると（reconnect 時）張り直して購読をやり直し、通知は元の購読 id で流し続ける。
+pub struct JsonRpcWsProvider {
+    info: EvmProviderInfo,
+    call_timeout: Duration,
+    next_id: AtomicU64,
+    calls: tokio_mpsc::UnboundedSender<WsCall>,
+    events: Mutex<mpsc::Receiver<EvmWsEvent>>,
+}
+
+impl JsonRpcWsProvider {
+    /// 接続が確立する（または connect_attempts を使い切る）まで待つ
+    pub fn connect(info: EvmProviderInfo, config: JsonRpcWsConfig) -> Result<Self, ProviderError> {
+        let provider = info.id.clone();
+        let err = |message: String| ProviderError {
+            provider: provider.clone(),
+            reason: EvmReasonCode::ProviderTimeout,
+            message,
+        };
+        let runtime = tokio::runtime::Builder::new_current_thread()
+            .enable_all()
+            .build()
+            .map_err(|e| err(format!("rpc runtime: {e}")))?;
+
+        let call_timeout = config.call_timeout;
+        let (calls, calls_rx) = tokio_mpsc::unbounded_channel();
+        let (events_tx, events) = mpsc::channel();
+        let (ready_tx, ready_rx) = mpsc::channel();
+        let worker_provider = provider.clone();
+        std::thread::Builder::new()
+            .name(format!("evm-rpc-ws-{provider}"))
+            .spawn(move || {
+                runtime.block_on(run_worker(
+                    worker_provider,
+                    config,
+                    calls_rx,
+                    events_tx,
+                    ready_tx,
+                ))
+            })
+            .map_err(|e| err(format!("rpc worker: {e}")))?;
+
+        match ready_rx.recv() {
+            Ok(Ok(())) => Ok(Self {
+                info,
+                call_timeout,
+                next_id: AtomicU64::new(1),
+                calls,
+                events: Mutex::new(events),
+            }),
+            Ok(Err(message)) => Err(err(message)),
+            Err(_) => Err(err("rpc worker stopped".into())),
+        }
+    }
+
+    fn error(&self, message: impl Into<String>) -> ProviderError {
+        ProviderError {
+            provider: self.info.id.clone(),
+            reason: EvmReasonCode::ProviderTimeout,
+            message: message.into(),
+        }
+    }
+
+    /// calls を 1 フレームで送り、calls と同じ順に結果を返す
+    fn send_calls(
+        &self,
+        calls: &[(&str, serde_json::Value)],
+        priority: OutboundPriority,
+    ) -> Result<Vec<Result<ProviderResponse, ProviderError>>, ProviderError> {
+        let (requests, replies): (Vec<_>, Vec<_>) = calls
+            .iter()
+            .map(|(method, params)| {
+                let id = self.next_id.fetch_add(1, Ordering::Relaxed);
+                let (reply, rx) = mpsc::channel();
+                ((JsonRpcRequest::new(id, method, params.clone()), reply), rx)
+            })
+            .unzip();
+        self.calls
+            .send(WsCall { requests, priority })
+            .map_err(|_| self.error("ws connection closed"))?;
+        let deadline = Instant::now() + self.call_timeout;
+        Ok(replies
+            .into_iter()
+            .map(|rx| {
+                rx.recv_timeout(deadline.saturating_duration_since(Instant::now()))
+                    .map_err(|_| self.error("ws rpc call timed out"))?
+            })
+            .collect())
+    }
+
+    fn call_with_priority(
+        &self,
+        method: &str,
+        params: serde_json::Value,
+        priority: OutboundPriority,
+    ) -> Result<ProviderResponse, ProviderError> {
+        self.send_calls(&[(method, params)], priority)?
+            .pop()
+            .unwrap_or_else(|| Err(self.error("ws rpc call lost")))
+    }
+
+    /// 通常の JSON-RPC 呼び出しを WS 経由で行う
+    pub fn call(
+        &self,
+        method: &str,
+        params: serde_json::Value,
+    ) -> Result<ProviderResponse, ProviderError> {
+        self.call_with_priority(method, params, OutboundPriority::Public)
+    }
+
+    /// 複数の呼び出しを 1 フレームの JSON-RPC バッチで送る。結果は calls と同じ順に並ぶ。
+    /// 外側の Err は送れなかった場合、内側の Err は個々の呼び出しの失敗（応答が無ければ timeout）
+    pub fn batch_call(
+        &self,
+        calls: &[(&str, serde_json::Value)],
+    ) -> Result<Vec<Result<ProviderResponse, ProviderError>>, ProviderError> {
+        if calls.is_empty() {
+            return Ok(vec![]);
+        }
+        self.send_calls(calls, OutboundPriority::Public)
+    }
+
+    /// 次のイベントを最大 timeout だけ待つ
+    pub fn recv_event(&self, timeout: Duration) -> Option<EvmWsEvent> {
+        self.events.lock().ok()?.recv_timeout(timeout).ok()
+    }
+
+    /// 溜まっているイベントを全て取り出す
+    pub fn drain_events(&self) -> Vec<EvmWsEvent> {
+        match self.events.lock() {
+            Ok(rx) => rx.try_iter().collect(),
+            Err(_) => vec![],
+        }
+    }
+}
+
+impl EvmWsProvider for JsonRpcWsProvider {
+    fn info(&self) -> &EvmProviderInfo {
+        &self.info
+    }
+
+    fn subscribe(&self, method: &str, params: serde_json::Value) -> Result<String, ProviderError> {
+        let r = self.call_with_priority(method, params, OutboundPriority::Control)?;
+        r.value
+            .as_str()
+            .map(str::to_string)
+            .ok_or_else(|| self.error("subscription id missing in response"))
+    }
+
+    fn unsubscribe(&self, id: &str) -> Result<(), ProviderError> {
+        let r = self.call_with_priority(
+            "eth_unsubscribe",
+            serde_json::json!([id]),
+            OutboundPriority::Control,
+        )?;
+        if r.value.as_bool() == Some(true) {
+            Ok(())
+        } else {
+            Err(self.error(format!("unknown subscription {id}")))
+        }
+    }
+}
+
+async fn connect(config: &JsonRpcWsConfig) -> Result<Ws, String> {
+    let mut last_err = String::new();
+    for attempt in 0..config.connect_attempts.max(1) {
+        match tokio_tungstenite::connect_async(config.url.as_str()).await {
+            Ok((ws, _)) => return Ok(ws),
+            Err(e) => {
+                last_err = format!("ws connect failed: {e}");
+                if attempt + 1 < config.connect_attempts {
+                    let ms = next_retry_delay_ms(&config.retry, attempt, None);
+                    tokio::time::sleep(Duration::from_millis(ms)).await;
+                }
+            }
+        }
+    }
+    Err(last_err)
+}
+
+async fn run_worker(
+    provider: String,
+    config: JsonRpcWsConfig,
+    mut calls: tokio_mpsc::UnboundedReceiver<WsCall>,
+    events: mpsc::Sender<EvmWsEvent>,
+    ready: mpsc::Sender<Result<(), String>>,
+) {
+    let mut ws = match connect(&config).await {
+        Ok(ws) => ws,
+        Err(e) => {
+            let _ = ready.send(Err(e));
+            return;
+        }
+    };
+    let _ = ready.send(Ok(()));
+
+    let mut worker = Worker {
+        provider,
+        limiter: WsRateLimiter::new(config.limiter),
+        config,
+        events,
+        pending: HashMap::new(),
+        subs: HashMap::new(),
+        routes: HashMap::new(),
+        next_resubscribe_id: RESUBSCRIBE_ID_BASE,
+    };
+    let reason = loop {
+        let Some(reason) = worker.serve(&mut ws, &mut calls).await else {
+            let _ = ws.close(None).await;
+            return;
+        };
+        worker.fail_pending(&reason);
+        if !worker.config.reconnect {
+            break reason;
+        }
+        // 張り直しの間に届いた呼び出しは channel に溜まり、接続後に送る
+        match connect(&worker.config).await {
+            Ok(next) => {
+                ws = next;
+                let (resubscribed, failed) = worker.resubscribe(&mut ws).await;
+                let _ = worker.events.send(EvmWsEvent::Reconnected {
+                    reason,
+                    resubscribed,
+                    failed,
+                });
+            }
+            Err(e) => break format!("{reason}; reconnect failed: {e}"),
+        }
+    };
+
+    let _ = worker.events.send(EvmWsEvent::Closed {
+        reason: reason.clone(),
+    });
+    // 切断後の呼び出しは provider が drop されるまで即エラーで返す
+    while let Some(call) = calls.recv().await {
+        for (_, reply) in call.requests {
+            let _ = reply.send(Err(closed_error(&worker.provider, reason.clone())));
+        }
+    }
+}
+
+struct PendingCall {
+    method: String,
+    /// 呼び出し側が渡した params（eth_unsubscribe は張り直し前の購読 id のまま）
+    params: serde_json::Value,
+    reply: CallReply,
+}
+
+struct ActiveSubscription {
+    /// eth_subscribe の params（張り直しで同じ購読を作り直す）
+    params: serde_json::Value,
+    /// 今の接続での購読 id
+    current: String,
+}
+
+struct Worker {
+    provider: String,
+    config: JsonRpcWsConfig,
+    limiter: WsRateLimiter,
+    events: mpsc::Sender<EvmWsEvent>,
+    pending: HashMap<u64, PendingCall>,
+    /// subscribe が返した購読 id -> 購読
+    subs: HashMap<String, ActiveSubscription>,
+    /// 今の接続での購読 id -> subscribe が返した購読 id
+    routes: HashMap<String, String>,
+    next_resubscribe_id: u64,
+}
+
+impl Worker {
+    /// 切断まで呼び出しと受信を捌き、切断理由を返す。provider が drop されたら None
+    async fn serve(
+        &mut self,
+        ws: &mut Ws,
+        calls: &mut tokio_mpsc::UnboundedReceiver<WsCall>,
+    ) -> Option<String> {
+        loop {
+            tokio::select! {
+                call = calls.recv() => {
+                    let call = call?;
+                    let wait = self.limiter.acquire_wait(call.priority, Instant::now());
+                    if !wait.is_zero() {
+                        tokio::time::sleep(wait).await;
+                    }
+                    if let Err(reason) = self.send(ws, call).await {
+                        return Some(reason);
+                    }
+                }
+                msg = ws.next() => match msg {
+                    Some(Ok(Message::Text(text))) => self.dispatch(&text),
+                    Some(Ok(Message::Close(_))) | None => return Some("ws closed by peer".to_string()),
+                    Some(Ok(_)) => {}
+                    Some(Err(e)) => return Some(format!("ws read failed: {e}")),
+                }
+            }
+        }
+    }
+
+    async fn send(&mut self, ws: &mut Ws, call: WsCall) -> Result<(), String> {
+        let (requests, replies): (Vec<_>, Vec<_>) = call.requests.into_iter().unzip();
+        let wire: Vec<JsonRpcRequest> = requests.iter().map(|r| self.to_wire(r)).collect();
+        let text = match wire.as_slice() {
+            [one] => serde_json::to_string(one),
+            many => serde_json::to_string(many),
+        };
+        let text = match text {
+            Ok(text) => text,
+            Err(e) => {
+                for reply in replies {
+                    let _ = reply.send(Err(ProviderError {
+                        provider: self.provider.clone(),
+                        reason: EvmReasonCode::InvalidRequest,
+                        message: e.to_string(),
+                    }));
+                }
+                return Ok(());
+            }
+        };
+        if let Err(e) = ws.send(Message::Text(text)).await {
+            for reply in replies {
+                let _ = reply.send(Err(closed_error(&self.provider, e.to_string())));
+            }
+            return Err(format!("ws send failed: {e}"));
+        }
+        for (request, reply) in requests.into_iter().zip(replies) {
+            self.pending.insert(
+                request.id,
+                PendingCall {
+                    method: request.method,
+                    params: request.params,
+                    reply,
+                },
+            );
+        }
+        Ok(())
+    }
+
+    /// eth_unsubscribe の購読 id を今の接続での id に差し替える
+    fn to_wire(&self, request: &JsonRpcRequest) -> JsonRpcRequest {
+        let mut wire = request.clone();
+        if wire.method == "eth_unsubscribe" {
+            let current = wire
+                .params
+                .get(0)
+                .and_then(|id| id.as_str())
+                .and_then(|id| self.subs.get(id))
+                .map(|s| s.current.clone());
+            if let Some(current) = current {
+                wire.params = serde_json::json!([current]);
+            }
+        }
+        wire
+    }
+
+    fn fail_pending(&mut self, reason: &str) {
+        for (_, call) in self.pending.drain() {
+            let _ = call.reply.send(Err(closed_error(&self.provider, reason)));
+        }
+    }
+
+    /// 張り直した接続で購読を作り直す。(作り直せた購読, 作り直せなかった購読)
+    async fn resubscribe(&mut self, ws: &mut Ws) -> (Vec<String>, Vec<String>) {
+        self.routes.clear();
+        let mut ids: Vec<String> = self.subs.keys().cloned().collect();
+        ids.sort();
+        let (mut resubscribed, mut failed) = (Vec::new(), Vec::new());
+        for id in ids {
+            let params = self.subs[&id].params.clone();
+            match self.subscribe_now(ws, params).await {
+                Some(current) => {
+                    self.routes.insert(current.clone(), id.clone());
+                    if let Some(s) = self.subs.get_mut(&id) {
+                        s.current = current;
+                    }
+                    resubscribed.push(id);
+                }
+                None => {
+                    self.subs.remove(&id);
+                    failed.push(id);
+                }
+            }
+        }
+        (resubscribed, failed)
+    }
+
+    /// eth_subscribe を送って応答を待つ。待つ間の他のフレームは通常どおり振り分ける
+    async fn subscribe_now(&mut self, ws: &mut Ws, params: serde_json::Value) -> Option<String> {
+        let id = self.next_resubscribe_id;
+        self.next_resubscribe_id += 1;
+        let text = serde_json::to_string(&JsonRpcRequest::new(id, "eth_subscribe", params)).ok()?;
+        ws.send(Message::Text(text)).await.ok()?;
+        let deadline = tokio::time::Instant::now() + self.config.call_timeout;
+        loop {
+            let msg = tokio::time::timeout_at(deadline, ws.next())
+                .await
+                .ok()??
+                .ok()?;
+            let Message::Text(text) = msg else { continue };
+            let Ok(value) = serde_json::from_str::<serde_json::Value>(&text) else {
+                continue;
+            };
+            if value.get("id").and_then(|v| v.as_u64()) == Some(id) {
+                return value
+                    .get("result")
+                    .and_then(|r| r.as_str())
+                    .map(str::to_string);
+            }
+            self.dispatch_value(value);
+        }
+    }
+
+    fn dispatch(&mut self, text: &str) {
+        match serde_json::from_str::<serde_json::Value>(text) {
+            Ok(serde_json::Value::Array(items)) => {
+                for item in items {
+                    self.dispatch_value(item);
+                }
+            }
+            Ok(value) => self.dispatch_value(value),
+            Err(_) => {}
+        }
+    }
+
+    fn dispatch_value(&mut self, value: serde_json::Value) {
+        if value.get("method").and_then(|m| m.as_str()) == Some("eth_subscription") {
+            let params = value.get("params").cloned().unwrap_or_default();
+            let subscription = params
+                .get("subscription")
+                .and_then(|s| s.as_str())
+                .unwrap_or_default()
+                .to_string();
+            let subscription = self
+                .routes
+                .get(&subscription)
+                .cloned()
+                .unwrap_or(subscription);
+            let result = params.get("result").cloned().unwrap_or_default();
+            let _ = self.events.send(EvmWsEvent::Notification {
+                subscription,
+                result,
+            });
+            return;
+        }
+        let Ok(resp) = serde_json::from_value::<JsonRpcResponse>(value) else {
+            return;
+        };
+        let Some(call) = resp.id_u64().and_then(|id| self.pending.remove(&id)) else {
+            return;
+        };
+        let out = into_provider_response(&self.provider, &call.method, resp);
+        if let Ok(r) = &out {
+            self.track_subscription(&call, &r.value);
+        }
+        let _ = call.reply.send(out);
+    }
+
+    fn track_subscription(&mut self, call: &PendingCall, value: &serde_json::Value) {
+        match call.method.as_str() {
+            "eth_subscribe" => {
+                if let Some(id) = value.as_str() {
+                    self.routes.insert(id.to_string(), id.to_string());
+                    self.subs.insert(
+                        id.to_string(),
+                        ActiveSubscription {
+                            params: call.params.clone(),
+                            current: id.to_string(),
+                        },
+                    );
+                }
+            }
+            "eth_unsubscribe" if value.as_bool() == Some(true) => {
+                let id = call.params.get(0).and_then(|id| id.as_str());
+                if let Some(sub) = id.and_then(|id| self.subs.remove(id)) {
+                    self.routes.remove(&sub.current);
+                }
+            }
+            _ => {}
+        }
+    }
+}
+
+fn closed_error(provider: &str, message: impl Into<String>) -> ProviderError {
+    ProviderError {
+        provider: provider.to_string(),
+        reason: EvmReasonCode::ProviderTimeout,
+        message: message.into(),
+    }
+}
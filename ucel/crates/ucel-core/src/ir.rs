@@ -147,7 +147,7 @@ pub struct IrSourceDescriptor {
     pub access_patterns: Vec<IrAccessPattern>,
 }
 
-#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize)]
 pub struct IrDocumentKey {
     pub source_id: String,
     pub source_document_id: String,
@@ -261,6 +261,18 @@ pub struct IrNormalizedTable {
     pub caption: Option<String>,
     pub headers: Vec<String>,
     pub rows: Vec<Vec<String>>,
+    /// Parallel to `rows`: canonical decimal per numeric cell (sign and digit forms
+    /// normalized, not multiplied by `unit_scale`); `None` for text or empty cells.
+    #[serde(default)]
+    pub numeric_rows: Vec<Vec<Option<String>>>,
+    /// Unit detected from the caption or surrounding text, e.g. `JPY`, `USD`, `shares`.
+    #[serde(default)]
+    pub unit: Option<String>,
+    /// Power of ten the numeric cells are expressed in (`百万円` = 6, `in thousands` = 3).
+    #[serde(default)]
+    pub unit_scale: Option<i32>,
+    #[serde(default)]
+    pub footnotes: Vec<String>,
     pub provenance: IrNormalizationProvenance,
 }
 
@@ -273,6 +285,73 @@ pub struct IrNormalizedAttachment {
     pub provenance: IrNormalizationProvenance,
 }
 
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum IrXbrlPeriod {
+    Instant { date: String },
+    Duration { start: String, end: String },
+    Forever,
+}
+
+/// One XBRL / iXBRL fact with its context and unit resolved.
+///
+/// Numeric facts carry `numeric_value` as a plain decimal string with `sign` and `scale`
+/// already applied; `value` keeps the text as it appeared in the filing.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct IrXbrlFact {
+    /// QName as written in the filing, e.g. `jppfs_cor:NetSales`.
+    pub concept: String,
+    pub namespace: Option<String>,
+    pub local_name: String,
+    pub context_ref: String,
+    pub entity_scheme: Option<String>,
+    pub entity_id: Option<String>,
+    pub period: Option<IrXbrlPeriod>,
+    /// Explicit/typed dimension QName -> member QName (or typed value).
+    pub dimensions: BTreeMap<String, String>,
+    pub unit_ref: Option<String>,
+    /// Resolved measures, e.g. `iso4217:JPY` or `iso4217:JPY/xbrli:shares`.
+    pub unit: Option<String>,
+    pub decimals: Option<String>,
+    pub scale: Option<i32>,
+    pub value: String,
+    pub numeric_value: Option<String>,
+    pub is_nil: bool,
+    pub provenance: IrNormalizationProvenance,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum IrFinancialConcept {
+    Revenue,
+    OperatingIncome,
+    OrdinaryIncome,
+    IncomeBeforeTax,
+    NetIncome,
+    TotalAssets,
+    TotalLiabilities,
+    NetAssets,
+    CashAndEquivalents,
+    OperatingCashFlow,
+    InvestingCashFlow,
+    FinancingCashFlow,
+    EpsBasic,
+    EpsDiluted,
+    SharesOutstanding,
+}
+
+/// Canonical financial statement line derived from a non-dimensional fact.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct IrFinancialStatementItem {
+    pub concept: IrFinancialConcept,
+    pub value: String,
+    pub unit: Option<String>,
+    pub period: Option<IrXbrlPeriod>,
+    /// Source fact QName; `provenance.context_ref` points at the fact's context.
+    pub source_concept: String,
+    pub provenance: IrNormalizationProvenance,
+}
+
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub struct IrNormalizedContent {
     pub document_key: IrDocumentKey,
@@ -283,6 +362,10 @@ pub struct IrNormalizedContent {
     pub sections: Vec<IrNormalizedSection>,
     pub tables: Vec<IrNormalizedTable>,
     pub extracted_attachments: Vec<IrNormalizedAttachment>,
+    #[serde(default)]
+    pub facts: Vec<IrXbrlFact>,
+    #[serde(default)]
+    pub financial_items: Vec<IrFinancialStatementItem>,
     pub language_hints: Vec<String>,
     pub charset: Option<String>,
     pub provenance: IrNormalizationProvenance,
@@ -6,6 +6,7 @@ pub mod equity;
 pub mod ir;
 pub mod market_data;
 pub mod order_gate;
+pub mod orderbook;
 pub mod policy;
 pub mod private_rest;
 pub mod private_ws;
@@ -40,10 +41,15 @@ pub use equity::{
 pub use ir::{
     normalize_alias, validate_document_artifact_pair, IrAccessDecision, IrAccessPattern,
     IrAccessPolicyClass, IrArtifactDescriptor, IrArtifactKey, IrArtifactKind, IrArtifactSource,
-    IrDocumentDescriptor, IrDocumentFamily, IrDocumentKey, IrFetchSupport, IrIssuerAlias,
-    IrIssuerIdentityKind, IrIssuerKey, IrMarket, IrNormalizationProvenance, IrNormalizationSchemaVersion,
+    IrDocumentDescriptor, IrDocumentFamily, IrDocumentKey, IrFetchSupport, IrFinancialConcept,
+    IrFinancialStatementItem, IrIssuerAlias, IrIssuerIdentityKind, IrIssuerKey, IrMarket,
+    IrNormalizationProvenance, IrNormalizationSchemaVersion,
     IrNormalizationSupport, IrNormalizedAttachment, IrNormalizedContent, IrNormalizedFormat, IrNormalizedSection,
-    IrNormalizedTable, IrSourceDescriptor, IrSourceFamily, IrSourceKind,
+    IrNormalizedTable, IrSourceDescriptor, IrSourceFamily, IrSourceKind, IrXbrlFact, IrXbrlPeriod,
+};
+pub use orderbook::{
+    BookSide, OrderBook, OrderBookApply, OrderBookConfig, OrderBookError, OrderBookUpdate,
+    SequencePolicy,
 };
 pub use market_data::{
     apply_orderbook_delta, guard_orderbook, validate_candle, validate_ticker, validate_trade,
@@ -4,8 +4,9 @@ use serde::{Deserialize, Serialize};
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct CanonicalTicker {
     pub symbol: String,
-    pub best_bid: Decimal,
-    pub best_ask: Decimal,
+    /// `None` when the venue's ticker carries no quote (e.g. last-trade-only feeds).
+    pub best_bid: Option<Decimal>,
+    pub best_ask: Option<Decimal>,
     pub last_price: Decimal,
     pub ts_event: Option<u64>,
 }
@@ -100,7 +101,7 @@ pub enum PublicWsReasonCode {
 }
 
 pub fn validate_ticker(ticker: &CanonicalTicker) -> Result<(), UcelError> {
-    if ticker.best_bid > ticker.best_ask {
+    if matches!((ticker.best_bid, ticker.best_ask), (Some(bid), Some(ask)) if bid > ask) {
         return Err(UcelError::new(
             crate::ErrorCode::Desync,
             "crossed book in ticker",
@@ -129,17 +130,26 @@ pub fn validate_trade(trade: &CanonicalTrade) -> Result<(), UcelError> {
     Ok(())
 }
 
+/// Pure helper for one-shot use. Streams should keep a `crate::OrderBook` instead of
+/// rebuilding from a snapshot on every delta.
 pub fn apply_orderbook_delta(
     snapshot: &CanonicalOrderBookSnapshot,
     delta: &CanonicalOrderBookDelta,
 ) -> CanonicalOrderBookSnapshot {
-    let mut next = snapshot.clone();
-    for level in &delta.bids {
-        upsert_level(&mut next.bids, level.clone(), true);
-    }
-    for level in &delta.asks {
-        upsert_level(&mut next.asks, level.clone(), false);
-    }
+    let levels = |ls: &[CanonicalOrderBookLevel]| -> Vec<(Decimal, Decimal)> {
+        ls.iter().map(|l| (l.price, l.qty)).collect()
+    };
+    let mut book = crate::OrderBook::default()
+        .with_sequence_policy(crate::SequencePolicy::None)
+        .with_reject_crossed(false);
+    // sequence checks and the crossed guard are disabled, so neither call can fail
+    let _ = book.apply_snapshot(
+        snapshot.sequence,
+        &levels(&snapshot.bids),
+        &levels(&snapshot.asks),
+    );
+    let _ = book.apply_update(delta.into());
+    let mut next = book.to_snapshot(&snapshot.symbol);
     next.sequence = delta
         .sequence_end
         .or(delta.sequence_start)
@@ -147,35 +157,6 @@ pub fn apply_orderbook_delta(
     next
 }
 
-fn upsert_level(
-    levels: &mut Vec<CanonicalOrderBookLevel>,
-    level: CanonicalOrderBookLevel,
-    bid: bool,
-) {
-    if level.qty <= Decimal::from(0) {
-        levels.retain(|x| x.price != level.price);
-        return;
-    }
-    if let Some(idx) = levels.iter().position(|x| x.price == level.price) {
-        levels[idx] = level;
-    } else {
-        levels.push(level);
-    }
-    if bid {
-        levels.sort_by(|a, b| {
-            b.price
-                .partial_cmp(&a.price)
-                .unwrap_or(std::cmp::Ordering::Equal)
-        });
-    } else {
-        levels.sort_by(|a, b| {
-            a.price
-                .partial_cmp(&b.price)
-                .unwrap_or(std::cmp::Ordering::Equal)
-        });
-    }
-}
-
 pub fn guard_orderbook(snapshot: &CanonicalOrderBookSnapshot) -> Result<(), UcelError> {
     let bid = snapshot.bids.first();
     let ask = snapshot.asks.first();
@@ -0,0 +1,672 @@
+//! Canonical L2 order book engine shared by all venue adapters.
+//!
+//! - Levels are `Decimal`-keyed `BTreeMap`s, so an upsert / delete is O(log n) and
+//!   best-first iteration never re-sorts.
+//! - Sequence gaps are detected according to a per-venue `SequencePolicy`.
+//! - Deltas that arrive before the first snapshot are buffered and replayed on snapshot.
+//! - An optional depth cap trims the far side after every update.
+use crate::{
+    CanonicalOrderBookDelta, CanonicalOrderBookLevel, CanonicalOrderBookSnapshot, Decimal,
+    ErrorCode, OrderBookDelta, OrderBookLevel, OrderBookSnapshot, UcelError,
+};
+use serde::{Deserialize, Serialize};
+use std::cmp::Reverse;
+use std::collections::{BTreeMap, VecDeque};
+use std::str::FromStr;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum BookSide {
+    Bid,
+    Ask,
+}
+
+/// How consecutive updates must chain onto the current book sequence.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum SequencePolicy {
+    /// No sequence checks (venue publishes no usable sequence).
+    None,
+    /// `first == last + 1`, and `prev == last` when the venue sends a previous id.
+    /// Duplicates and jumps are both gaps.
+    Contiguous,
+    /// Update-id ranges may overlap the book (Binance depth diff):
+    /// gap when `first > last + 1` or `final < last`.
+    Overlapping,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct OrderBookConfig {
+    pub sequence_policy: SequencePolicy,
+    /// Keep at most this many levels per side (None = unbounded).
+    pub depth_cap: Option<usize>,
+    /// Max deltas held while waiting for a snapshot (0 disables buffering).
+    pub max_buffered_updates: usize,
+    /// Treat a crossed book (best bid >= best ask) as a desync.
+    pub reject_crossed: bool,
+}
+
+impl Default for OrderBookConfig {
+    fn default() -> Self {
+        Self {
+            sequence_policy: SequencePolicy::Contiguous,
+            depth_cap: None,
+            max_buffered_updates: 1024,
+            reject_crossed: true,
+        }
+    }
+}
+
+/// One incremental update. Levels with zero qty delete the price.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct OrderBookUpdate {
+    pub prev_sequence: Option<u64>,
+    pub first_sequence: Option<u64>,
+    pub final_sequence: Option<u64>,
+    pub bids: Vec<(Decimal, Decimal)>,
+    pub asks: Vec<(Decimal, Decimal)>,
+}
+
+impl OrderBookUpdate {
+    pub fn new(bids: Vec<(Decimal, Decimal)>, asks: Vec<(Decimal, Decimal)>) -> Self {
+        Self {
+            bids,
+            asks,
+            ..Self::default()
+        }
+    }
+
+    /// Parse venue string levels (`[price, qty]`) as published.
+    pub fn from_str_levels<P, Q>(bids: &[(P, Q)], asks: &[(P, Q)]) -> Result<Self, OrderBookError>
+    where
+        P: AsRef<str>,
+        Q: AsRef<str>,
+    {
+        Ok(Self::new(parse_levels(bids)?, parse_levels(asks)?))
+    }
+
+    /// Single sequence number (first == final).
+    pub fn with_sequence(mut self, seq: u64) -> Self {
+        self.first_sequence = Some(seq);
+        self.final_sequence = Some(seq);
+        self
+    }
+
+    pub fn with_range(mut self, first: u64, last: u64) -> Self {
+        self.first_sequence = Some(first);
+        self.final_sequence = Some(last);
+        self
+    }
+
+    pub fn with_prev_sequence(mut self, prev: u64) -> Self {
+        self.prev_sequence = Some(prev);
+        self
+    }
+
+    fn first(&self) -> Option<u64> {
+        self.first_sequence.or(self.final_sequence)
+    }
+
+    fn last(&self) -> Option<u64> {
+        self.final_sequence.or(self.first_sequence)
+    }
+}
+
+impl From<&OrderBookDelta> for OrderBookUpdate {
+    fn from(d: &OrderBookDelta) -> Self {
+        Self::new(levels_of(&d.bids), levels_of(&d.asks))
+            .with_range(d.sequence_start, d.sequence_end)
+    }
+}
+
+impl From<&CanonicalOrderBookDelta> for OrderBookUpdate {
+    fn from(d: &CanonicalOrderBookDelta) -> Self {
+        Self {
+            prev_sequence: None,
+            first_sequence: d.sequence_start,
+            final_sequence: d.sequence_end,
+            bids: d.bids.iter().map(|l| (l.price, l.qty)).collect(),
+            asks: d.asks.iter().map(|l| (l.price, l.qty)).collect(),
+        }
+    }
+}
+
+fn levels_of(levels: &[OrderBookLevel]) -> Vec<(Decimal, Decimal)> {
+    levels.iter().map(|l| (l.price, l.qty)).collect()
+}
+
+pub fn parse_levels<P, Q>(levels: &[(P, Q)]) -> Result<Vec<(Decimal, Decimal)>, OrderBookError>
+where
+    P: AsRef<str>,
+    Q: AsRef<str>,
+{
+    levels
+        .iter()
+        .map(|(p, q)| {
+            let parse = |s: &str| {
+                Decimal::from_str(s).map_err(|_| OrderBookError::InvalidLevel {
+                    value: s.to_string(),
+                })
+            };
+            Ok((parse(p.as_ref())?, parse(q.as_ref())?))
+        })
+        .collect()
+}
+
+/// What happened to an update.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OrderBookApply {
+    Applied,
+    /// Held until the next snapshot.
+    Buffered,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+pub enum OrderBookError {
+    #[error("sequence gap: expected {expected}, found {found}")]
+    Gap { expected: u64, found: u64 },
+    #[error("update has no sequence under a sequenced policy")]
+    MissingSequence,
+    #[error("buffered snapshot replay does not bridge: snapshot={snapshot}, first={first}")]
+    SnapshotMismatch { snapshot: u64, first: u64 },
+    #[error("too many updates buffered before snapshot: {limit}")]
+    BufferOverflow { limit: usize },
+    #[error("book not synced; snapshot required")]
+    NotSynced,
+    #[error("crossed book: bid={bid} ask={ask}")]
+    Crossed { bid: Decimal, ask: Decimal },
+    #[error("invalid level value: {value}")]
+    InvalidLevel { value: String },
+}
+
+impl From<OrderBookError> for UcelError {
+    fn from(e: OrderBookError) -> Self {
+        let code = match e {
+            OrderBookError::InvalidLevel { .. } => ErrorCode::WsProtocolViolation,
+            _ => ErrorCode::Desync,
+        };
+        UcelError::new(code, e.to_string())
+    }
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct OrderBook {
+    config: OrderBookConfig,
+    /// Keyed by `Reverse(price)` so iteration is best (highest) first.
+    bids: BTreeMap<Reverse<Decimal>, Decimal>,
+    asks: BTreeMap<Decimal, Decimal>,
+    sequence: Option<u64>,
+    synced: bool,
+    buffered: VecDeque<OrderBookUpdate>,
+}
+
+impl OrderBook {
+    pub fn new(config: OrderBookConfig) -> Self {
+        Self {
+            config,
+            ..Self::default()
+        }
+    }
+
+    pub fn with_sequence_policy(mut self, policy: SequencePolicy) -> Self {
+        self.config.sequence_policy = policy;
+        self
+    }
+
+    pub fn with_depth_cap(mut self, depth: usize) -> Self {
+        self.config.depth_cap = Some(depth);
+        self
+    }
+
+    pub fn with_max_buffered_updates(mut self, n: usize) -> Self {
+        self.config.max_buffered_updates = n;
+        self
+    }
+
+    pub fn with_reject_crossed(mut self, reject: bool) -> Self {
+        self.config.reject_crossed = reject;
+        self
+    }
+
+    pub fn config(&self) -> &OrderBookConfig {
+        &self.config
+    }
+
+    pub fn sequence(&self) -> Option<u64> {
+        self.sequence
+    }
+
+    /// True once a snapshot has been applied and no desync has happened since.
+    pub fn is_synced(&self) -> bool {
+        self.synced
+    }
+
+    pub fn buffered_len(&self) -> usize {
+        self.buffered.len()
+    }
+
+    /// Drop levels and mark the book as needing a snapshot (buffer is kept).
+    pub fn invalidate(&mut self) {
+        self.synced = false;
+        self.bids.clear();
+        self.asks.clear();
+    }
+
+    /// Replace the book, then replay buffered updates newer than `sequence`.
+    /// Returns how many buffered updates were applied.
+    pub fn apply_snapshot(
+        &mut self,
+        sequence: Option<u64>,
+        bids: &[(Decimal, Decimal)],
+        asks: &[(Decimal, Decimal)],
+    ) -> Result<usize, OrderBookError> {
+        self.bids.clear();
+        self.asks.clear();
+        for (p, q) in bids {
+            self.upsert(BookSide::Bid, *p, *q);
+        }
+        for (p, q) in asks {
+            self.upsert(BookSide::Ask, *p, *q);
+        }
+        self.sequence = sequence;
+        // not synced until the buffered replay bridges and the book is sane
+        self.synced = false;
+
+        let result = self.replay_buffered().and_then(|replayed| {
+            self.trim();
+            self.guard_crossed()?;
+            Ok(replayed)
+        });
+        match result {
+            Ok(replayed) => {
+                self.synced = true;
+                Ok(replayed)
+            }
+            Err(e) => {
+                self.invalidate();
+                Err(e)
+            }
+        }
+    }
+
+    fn replay_buffered(&mut self) -> Result<usize, OrderBookError> {
+        let mut replayed = 0;
+        let buffered = std::mem::take(&mut self.buffered);
+        for u in buffered {
+            if let (Some(seq), Some(last)) = (self.sequence, u.last()) {
+                if last <= seq {
+                    continue;
+                }
+                // the first replayed update may straddle the snapshot
+                let first = u.first().unwrap_or(last);
+                if replayed == 0 && first > seq + 1 {
+                    return Err(OrderBookError::SnapshotMismatch {
+                        snapshot: seq,
+                        first,
+                    });
+                }
+                if replayed > 0 {
+                    self.check_sequence(&u)?;
+                }
+            }
+            self.apply_levels(&u);
+            if let Some(last) = u.last() {
+                self.sequence = Some(last);
+            }
+            replayed += 1;
+        }
+        Ok(replayed)
+    }
+
+    pub fn apply_snapshot_levels(
+        &mut self,
+        snapshot: &OrderBookSnapshot,
+    ) -> Result<usize, OrderBookError> {
+        self.apply_snapshot(
+            Some(snapshot.sequence),
+            &levels_of(&snapshot.bids),
+            &levels_of(&snapshot.asks),
+        )
+    }
+
+    /// Apply an incremental update. Before the first snapshot the update is buffered.
+    /// On a gap / crossed book the book is invalidated and must be re-snapshotted.
+    pub fn apply_update(
+        &mut self,
+        update: OrderBookUpdate,
+    ) -> Result<OrderBookApply, OrderBookError> {
+        if !self.synced {
+            if self.config.max_buffered_updates == 0 {
+                return Err(OrderBookError::NotSynced);
+            }
+            if self.buffered.len() >= self.config.max_buffered_updates {
+                self.buffered.clear();
+                return Err(OrderBookError::BufferOverflow {
+                    limit: self.config.max_buffered_updates,
+                });
+            }
+            self.buffered.push_back(update);
+            return Ok(OrderBookApply::Buffered);
+        }
+
+        if let Err(e) = self.check_sequence(&update) {
+            self.invalidate();
+            return Err(e);
+        }
+        self.apply_levels(&update);
+        if let Some(last) = update.last() {
+            self.sequence = Some(last);
+        }
+        self.trim();
+        if let Err(e) = self.guard_crossed() {
+            self.invalidate();
+            return Err(e);
+        }
+        Ok(OrderBookApply::Applied)
+    }
+
+    fn check_sequence(&self, u: &OrderBookUpdate) -> Result<(), OrderBookError> {
+        // a snapshot without a sequence leaves nothing to chain onto
+        let Some(last) = self.sequence else {
+            return Ok(());
+        };
+        match self.config.sequence_policy {
+            SequencePolicy::None => Ok(()),
+            SequencePolicy::Contiguous => {
+                if let Some(prev) = u.prev_sequence {
+                    if prev != last {
+                        return Err(OrderBookError::Gap {
+                            expected: last,
+                            found: prev,
+                        });
+                    }
+                }
+                let first = u.first().ok_or(OrderBookError::MissingSequence)?;
+                if first != last + 1 {
+                    return Err(OrderBookError::Gap {
+                        expected: last + 1,
+                        found: first,
+                    });
+                }
+                Ok(())
+            }
+            SequencePolicy::Overlapping => {
+                let first = u.first().ok_or(OrderBookError::MissingSequence)?;
+                let fin = u.last().unwrap_or(first);
+                if first > last + 1 || fin < last {
+                    return Err(OrderBookError::Gap {
+                        expected: last + 1,
+                        found: first,
+                    });
+                }
+                Ok(())
+            }
+        }
+    }
+
+    fn apply_levels(&mut self, u: &OrderBookUpdate) {
+        for (p, q) in &u.bids {
+            self.upsert(BookSide::Bid, *p, *q);
+        }
+        for (p, q) in &u.asks {
+            self.upsert(BookSide::Ask, *p, *q);
+        }
+    }
+
+    /// O(log n) insert / replace / delete (qty <= 0 deletes).
+    pub fn upsert(&mut self, side: BookSide, price: Decimal, qty: Decimal) {
+        let delete = qty <= Decimal::ZERO;
+        match side {
+            BookSide::Bid => {
+                if delete {
+                    self.bids.remove(&Reverse(price));
+                } else {
+                    self.bids.insert(Reverse(price), qty);
+                }
+            }
+            BookSide::Ask => {
+                if delete {
+                    self.asks.remove(&price);
+                } else {
+                    self.asks.insert(price, qty);
+                }
+            }
+        }
+    }
+
+    fn trim(&mut self) {
+        let Some(cap) = self.config.depth_cap else {
+            return;
+        };
+        while self.bids.len() > cap {
+            self.bids.pop_last();
+        }
+        while self.asks.len() > cap {
+            self.asks.pop_last();
+        }
+    }
+
+    fn guard_crossed(&self) -> Result<(), OrderBookError> {
+        if !self.config.reject_crossed {
+            return Ok(());
+        }
+        match (self.best_bid(), self.best_ask()) {
+            (Some((bid, _)), Some((ask, _))) if bid >= ask => {
+                Err(OrderBookError::Crossed { bid, ask })
+            }
+            _ => Ok(()),
+        }
+    }
+
+    pub fn is_crossed(&self) -> bool {
+        matches!(
+            (self.best_bid(), self.best_ask()),
+            (Some((bid, _)), Some((ask, _))) if bid >= ask
+        )
+    }
+
+    pub fn best_bid(&self) -> Option<(Decimal, Decimal)> {
+        self.bids.iter().next().map(|(p, q)| (p.0, *q))
+    }
+
+    pub fn best_ask(&self) -> Option<(Decimal, Decimal)> {
+        self.asks.iter().next().map(|(p, q)| (*p, *q))
+    }
+
+    pub fn mid(&self) -> Option<Decimal> {
+        let (bid, _) = self.best_bid()?;
+        let (ask, _) = self.best_ask()?;
+        Some((bid + ask) / Decimal::TWO)
+    }
+
+    pub fn spread(&self) -> Option<Decimal> {
+        let (bid, _) = self.best_bid()?;
+        let (ask, _) = self.best_ask()?;
+        Some(ask - bid)
+    }
+
+    pub fn len(&self, side: BookSide) -> usize {
+        match side {
+            BookSide::Bid => self.bids.len(),
+            BookSide::Ask => self.asks.len(),
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.bids.is_empty() && self.asks.is_empty()
+    }
+
+    pub fn qty_at(&self, side: BookSide, price: Decimal) -> Option<Decimal> {
+        match side {
+            BookSide::Bid => self.bids.get(&Reverse(price)).copied(),
+            BookSide::Ask => self.asks.get(&price).copied(),
+        }
+    }
+
+    /// Best-first levels of one side.
+    pub fn levels(&self, side: BookSide) -> Box<dyn Iterator<Item = (Decimal, Decimal)> + '_> {
+        match side {
+            BookSide::Bid => Box::new(self.bids.iter().map(|(p, q)| (p.0, *q))),
+            BookSide::Ask => Box::new(self.asks.iter().map(|(p, q)| (*p, *q))),
+        }
+    }
+
+    pub fn top(&self, side: BookSide, depth: usize) -> Vec<(Decimal, Decimal)> {
+        self.levels(side).take(depth).collect()
+    }
+
+    /// Average price to fill `qty` by walking `side` best-first
+    /// (`Ask` for a buy, `Bid` for a sell). None when the side is too thin.
+    pub fn vwap(&self, side: BookSide, qty: Decimal) -> Option<Decimal> {
+        if qty <= Decimal::ZERO {
+            return None;
+        }
+        let mut remaining = qty;
+        let mut notional = Decimal::ZERO;
+        for (p, q) in self.levels(side) {
+            let take = remaining.min(q);
+            notional += p * take;
+            remaining -= take;
+            if remaining.is_zero() {
+                return Some(notional / qty);
+            }
+        }
+        None
+    }
+
+    pub fn to_snapshot(&self, symbol: &str) -> CanonicalOrderBookSnapshot {
+        let level = |(price, qty)| CanonicalOrderBookLevel { price, qty };
+        CanonicalOrderBookSnapshot {
+            symbol: symbol.to_string(),
+            bids: self.levels(BookSide::Bid).map(level).collect(),
+            asks: self.levels(BookSide::Ask).map(level).collect(),
+            sequence: self.sequence,
+        }
+    }
+
+    pub fn to_order_book_snapshot(&self) -> OrderBookSnapshot {
+        let level = |(price, qty)| OrderBookLevel { price, qty };
+        OrderBookSnapshot {
+            bids: self.levels(BookSide::Bid).map(level).collect(),
+            asks: self.levels(BookSide::Ask).map(level).collect(),
+            sequence: self.sequence.unwrap_or_default(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn d(s: &str) -> Decimal {
+        Decimal::from_str(s).unwrap()
+    }
+
+    fn lv(p: &str, q: &str) -> (Decimal, Decimal) {
+        (d(p), d(q))
+    }
+
+    #[test]
+    fn buffers_until_snapshot_and_replays_bridging_updates() {
+        let mut ob = OrderBook::default().with_sequence_policy(SequencePolicy::Overlapping);
+        let u1 = OrderBookUpdate::new(vec![lv("100", "3")], vec![]).with_range(8, 10);
+        let u2 = OrderBookUpdate::new(vec![], vec![lv("101", "0")]).with_range(11, 12);
+        let stale = OrderBookUpdate::new(vec![lv("99", "9")], vec![]).with_range(5, 7);
+        for u in [stale, u1, u2] {
+            assert_eq!(ob.apply_update(u).unwrap(), OrderBookApply::Buffered);
+        }
+
+        let replayed = ob
+            .apply_snapshot(
+                Some(9),
+                &[lv("100", "1"), lv("99", "1")],
+                &[lv("101", "1"), lv("102", "1")],
+            )
+            .unwrap();
+        assert_eq!(replayed, 2);
+        assert_eq!(ob.sequence(), Some(12));
+        assert_eq!(ob.best_bid(), Some(lv("100", "3")));
+        assert_eq!(ob.best_ask(), Some(lv("102", "1")));
+        assert_eq!(ob.qty_at(BookSide::Bid, d("99")), Some(d("1")));
+    }
+
+    #[test]
+    fn snapshot_is_not_synced_when_replay_or_crossed_guard_fails() {
+        let mut ob = OrderBook::default();
+        ob.apply_update(OrderBookUpdate::default().with_sequence(11))
+            .unwrap();
+        ob.apply_update(OrderBookUpdate::default().with_sequence(13))
+            .unwrap();
+        assert!(matches!(
+            ob.apply_snapshot(Some(10), &[lv("1", "1")], &[lv("2", "1")]),
+            Err(OrderBookError::Gap {
+                expected: 12,
+                found: 13
+            })
+        ));
+        assert!(!ob.is_synced());
+        assert!(ob.is_empty());
+        assert_eq!(
+            ob.apply_update(OrderBookUpdate::default().with_sequence(14)),
+            Ok(OrderBookApply::Buffered)
+        );
+
+        let mut ob = OrderBook::default();
+        assert!(matches!(
+            ob.apply_snapshot(Some(1), &[lv("2", "1")], &[lv("2", "1")]),
+            Err(OrderBookError::Crossed { .. })
+        ));
+        assert!(!ob.is_synced());
+    }
+
+    #[test]
+    fn contiguous_gap_and_duplicate_invalidate_the_book() {
+        let mut ob = OrderBook::default();
+        ob.apply_snapshot(Some(10), &[lv("1", "1")], &[lv("2", "1")])
+            .unwrap();
+        let dup = ob.apply_update(OrderBookUpdate::default().with_sequence(10));
+        assert_eq!(
+            dup,
+            Err(OrderBookError::Gap {
+                expected: 11,
+                found: 10
+            })
+        );
+        assert!(!ob.is_synced());
+        assert!(ob.is_empty());
+
+        ob.apply_snapshot(Some(20), &[lv("1", "1")], &[lv("2", "1")])
+            .unwrap();
+        let linked = OrderBookUpdate::default()
+            .with_sequence(21)
+            .with_prev_sequence(19);
+        assert!(matches!(
+            ob.apply_update(linked),
+            Err(OrderBookError::Gap { .. })
+        ));
+    }
+
+    #[test]
+    fn depth_cap_vwap_and_crossed_guard() {
+        let mut ob = OrderBook::default().with_depth_cap(2);
+        ob.apply_snapshot(
+            Some(1),
+            &[lv("99", "1"), lv("98", "1"), lv("97", "1")],
+            &[lv("101", "1"), lv("102", "2"), lv("103", "5")],
+        )
+        .unwrap();
+        assert_eq!(ob.len(BookSide::Bid), 2);
+        assert_eq!(
+            ob.top(BookSide::Ask, 5),
+            vec![lv("101", "1"), lv("102", "2")]
+        );
+        assert_eq!(ob.mid(), Some(d("100")));
+        assert_eq!(ob.vwap(BookSide::Ask, d("2")), Some(d("101.5")));
+        assert_eq!(ob.vwap(BookSide::Ask, d("4")), None);
+
+        let crossing = OrderBookUpdate::new(vec![lv("101", "1")], vec![]).with_sequence(2);
+        assert!(matches!(
+            ob.apply_update(crossing),
+            Err(OrderBookError::Crossed { .. })
+        ));
+        assert!(!ob.is_synced());
+    }
+}
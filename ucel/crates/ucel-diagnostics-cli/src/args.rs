@@ -2,7 +2,7 @@ use clap::{Parser, Subcommand};
 
 #[derive(Parser, Debug)]
 #[command(name = "ucel-diag")]
-#[command(about = "UCEL Diagnostics CLI (Y domain): support bundle analyze/verify/export", long_about = None)]
+#[command(about = "UCEL Diagnostics CLI (Y domain): support bundle analyze/verify/export/import", long_about = None)]
 pub struct Cli {
     #[command(subcommand)]
     pub cmd: Command,
@@ -26,6 +26,7 @@ pub enum Command {
     Export {
         #[arg(long)]
         input: String,
+        /// Recipient X25519 public key (base64), as printed by `keygen`.
         #[arg(long)]
         recipient_pubkey: String,
         #[arg(long)]
@@ -37,5 +38,24 @@ pub enum Command {
         #[arg(long)]
         approval: Vec<String>,
     },
+    /// Decrypt an exported archive, check its TTL, then analyze/verify the bundle.
+    Import {
+        #[arg(long)]
+        input: String,
+        /// Path to the X25519 identity file written by `keygen`.
+        #[arg(long)]
+        identity: String,
+        /// Where to write the decrypted bundle JSON (created with mode 0600; must not exist).
+        #[arg(long)]
+        output: String,
+        /// Also write the analyze summary/compat/drift JSON here.
+        #[arg(long)]
+        analyze_output: Option<String>,
+    },
+    /// Generate a recipient identity file and print its public key.
+    Keygen {
+        #[arg(long)]
+        output: String,
+    },
     Whoami {},
 }
@@ -0,0 +1,65 @@
+use std::fs;
+use std::io::Write;
+use std::path::Path;
+
+use crate::export::{self, ExportMetadata};
+
+/// `--identity` is the path to an identity file written by `keygen`. A raw key is
+/// rejected so the secret never ends up in shell history or the process list.
+pub fn load_identity(identity: &str) -> Result<String, String> {
+    if !Path::new(identity).is_file() {
+        // the value is not echoed: it may be a pasted secret key
+        return Err("identity must be a path to a key file written by keygen".to_string());
+    }
+    let raw = fs::read_to_string(identity).map_err(|e| e.to_string())?;
+    Ok(raw.trim().to_string())
+}
+
+pub fn run(
+    input: &str,
+    identity: &str,
+    output: &str,
+    analyze_output: Option<&str>,
+    now_unix: i64,
+) -> Result<ExportMetadata, String> {
+    let archive = fs::read(input).map_err(|e| e.to_string())?;
+    let identity = load_identity(identity)?;
+    let decrypted =
+        export::decrypt_bundle(&archive, &identity, now_unix).map_err(|e| e.to_string())?;
+    let mut file = create_private(output)?;
+    file.write_all(&decrypted.bytes)
+        .and_then(|()| file.sync_all())
+        .map_err(|e| e.to_string())?;
+
+    if let Some(analyze_output) = analyze_output {
+        crate::cmd_analyze::run(output, analyze_output).map_err(|e| format!("ANALYZE:{e}"))?;
+    }
+    crate::cmd_verify::run(output).map_err(|e| format!("VERIFY:{e}"))?;
+    Ok(decrypted.metadata)
+}
+
+/// Creates `path` exclusively with mode 0600, so no other process can race in or
+/// read what is written there. An existing file is never overwritten.
+fn create_private(path: &str) -> Result<fs::File, String> {
+    let mut opts = fs::OpenOptions::new();
+    opts.write(true).create_new(true);
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::OpenOptionsExt;
+        opts.mode(0o600);
+    }
+    opts.open(path).map_err(|e| match e.kind() {
+        std::io::ErrorKind::AlreadyExists => format!("refusing to overwrite {path}"),
+        _ => e.to_string(),
+    })
+}
+
+/// Writes a new identity to `output`, which must not exist yet.
+pub fn keygen(output: &str) -> Result<String, String> {
+    let mut file = create_private(output)?;
+    let (secret, public) = export::generate_identity();
+    file.write_all(format!("{secret}\n").as_bytes())
+        .and_then(|()| file.sync_all())
+        .map_err(|e| e.to_string())?;
+    Ok(public)
+}
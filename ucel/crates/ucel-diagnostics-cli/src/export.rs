@@ -1,43 +1,301 @@
 use base64::Engine;
-use chacha20poly1305::aead::{Aead, KeyInit, OsRng};
+use chacha20poly1305::aead::{Aead, KeyInit, OsRng, Payload};
 use chacha20poly1305::{XChaCha20Poly1305, XNonce};
+use hkdf::Hkdf;
 use rand::RngCore;
 use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use x25519_dalek::{EphemeralSecret, PublicKey, StaticSecret};
+
+/// Archive format: X25519 ephemeral key agreement, HKDF-SHA256 content key, XChaCha20-Poly1305.
+pub const EXPORT_FORMAT: &str = "x25519-hkdf-sha256-xchacha20poly1305";
+const HKDF_INFO: &[u8] = b"ucel-diag/support-bundle/v1";
 
 #[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct EncryptedExport {
     pub format: String,
+    pub recipient_pubkey_b64: String,
+    pub ephemeral_pubkey_b64: String,
     pub nonce_b64: String,
     pub ciphertext_b64: String,
-    pub recipient_pubkey_ref: String,
+    /// Cleartext so the importer can check TTL before decrypting; tampering breaks the AEAD tag.
+    pub metadata: ExportMetadata,
+}
+
+/// Break-glass context bound to the ciphertext as associated data.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ExportMetadata {
+    pub created_at_unix: i64,
+    pub expires_at_unix: i64,
+    pub actor: String,
+    pub reason: String,
+    pub approvals_count: usize,
+    /// SHA-256 over the sorted approval tokens; the tokens themselves never leave the host.
+    pub approvals_sha256_hex: String,
+}
+
+impl ExportMetadata {
+    pub fn new(
+        created_at_unix: i64,
+        ttl_seconds: u64,
+        actor: &str,
+        reason: &str,
+        approvals: &[String],
+    ) -> Self {
+        let mut sorted = approvals.to_vec();
+        sorted.sort();
+        let mut h = Sha256::new();
+        for a in &sorted {
+            h.update(a.as_bytes());
+            h.update([0u8]);
+        }
+        Self {
+            created_at_unix,
+            expires_at_unix: created_at_unix.saturating_add(ttl_seconds as i64),
+            actor: actor.to_string(),
+            reason: reason.to_string(),
+            approvals_count: approvals.len(),
+            approvals_sha256_hex: hex::encode(h.finalize()),
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct DecryptedExport {
+    pub bytes: Vec<u8>,
+    pub metadata: ExportMetadata,
 }
 
 #[derive(Debug, thiserror::Error)]
 pub enum ExportError {
     #[error("encrypt failed")]
     EncryptFailed,
+    #[error("decrypt failed (wrong identity or tampered archive)")]
+    DecryptFailed,
+    #[error("invalid key: {0}")]
+    InvalidKey(String),
+    #[error("unsupported archive format: {0}")]
+    UnsupportedFormat(String),
+    #[error("archive is for recipient {archive}, identity is {identity}")]
+    RecipientMismatch { archive: String, identity: String },
+    #[error("archive expired at {expires_at_unix} (now {now_unix})")]
+    Expired { expires_at_unix: i64, now_unix: i64 },
+    #[error("invalid archive field {0}")]
+    InvalidField(&'static str),
     #[error("serialize failed: {0}")]
     Serde(#[from] serde_json::Error),
 }
 
-pub fn encrypt_bundle(bytes: &[u8], recipient_pubkey_ref: &str) -> Result<Vec<u8>, ExportError> {
-    let key = XChaCha20Poly1305::generate_key(&mut OsRng);
-    let cipher = XChaCha20Poly1305::new(&key);
+/// Fresh X25519 identity as `(secret_b64, public_b64)`.
+pub fn generate_identity() -> (String, String) {
+    let secret = StaticSecret::random_from_rng(OsRng);
+    let public = PublicKey::from(&secret);
+    (b64(secret.to_bytes()), b64(public.to_bytes()))
+}
+
+pub fn encrypt_bundle(
+    bytes: &[u8],
+    recipient_pubkey_b64: &str,
+    metadata: &ExportMetadata,
+) -> Result<Vec<u8>, ExportError> {
+    let recipient = PublicKey::from(decode_key(recipient_pubkey_b64)?);
+    let ephemeral = EphemeralSecret::random_from_rng(OsRng);
+    let ephemeral_public = PublicKey::from(&ephemeral);
+    let shared = ephemeral.diffie_hellman(&recipient);
+    if !shared.was_contributory() {
+        return Err(ExportError::InvalidKey("low-order recipient key".into()));
+    }
 
     let mut nonce = [0u8; 24];
     OsRng.fill_bytes(&mut nonce);
-    let nonce_ref = XNonce::from_slice(&nonce);
 
+    let mut output = EncryptedExport {
+        format: EXPORT_FORMAT.to_string(),
+        recipient_pubkey_b64: b64(recipient.to_bytes()),
+        ephemeral_pubkey_b64: b64(ephemeral_public.to_bytes()),
+        nonce_b64: b64(nonce),
+        ciphertext_b64: String::new(),
+        metadata: metadata.clone(),
+    };
+    let cipher = content_cipher(shared.as_bytes(), &ephemeral_public, &recipient)?;
+    let aad = associated_data(&output)?;
     let ciphertext = cipher
-        .encrypt(nonce_ref, bytes)
+        .encrypt(
+            XNonce::from_slice(&nonce),
+            Payload {
+                msg: bytes,
+                aad: &aad,
+            },
+        )
         .map_err(|_| ExportError::EncryptFailed)?;
-
-    let output = EncryptedExport {
-        format: "xchacha20poly1305".to_string(),
-        nonce_b64: base64::engine::general_purpose::STANDARD.encode(nonce),
-        ciphertext_b64: base64::engine::general_purpose::STANDARD.encode(ciphertext),
-        recipient_pubkey_ref: recipient_pubkey_ref.to_string(),
-    };
+    output.ciphertext_b64 = b64(ciphertext);
 
     Ok(serde_json::to_vec_pretty(&output)?)
 }
+
+/// Decrypts an archive for `identity_b64`; fails closed once `expires_at_unix` has passed.
+pub fn decrypt_bundle(
+    archive: &[u8],
+    identity_b64: &str,
+    now_unix: i64,
+) -> Result<DecryptedExport, ExportError> {
+    let export: EncryptedExport = serde_json::from_slice(archive)?;
+    if export.format != EXPORT_FORMAT {
+        return Err(ExportError::UnsupportedFormat(export.format));
+    }
+    let secret = StaticSecret::from(decode_key(identity_b64)?);
+    let recipient = PublicKey::from(&secret);
+    if export.recipient_pubkey_b64 != b64(recipient.to_bytes()) {
+        return Err(ExportError::RecipientMismatch {
+            archive: export.recipient_pubkey_b64,
+            identity: b64(recipient.to_bytes()),
+        });
+    }
+    if now_unix >= export.metadata.expires_at_unix {
+        return Err(ExportError::Expired {
+            expires_at_unix: export.metadata.expires_at_unix,
+            now_unix,
+        });
+    }
+
+    let ephemeral_public = PublicKey::from(decode_key(&export.ephemeral_pubkey_b64)?);
+    let shared = secret.diffie_hellman(&ephemeral_public);
+    if !shared.was_contributory() {
+        return Err(ExportError::InvalidKey("low-order ephemeral key".into()));
+    }
+    let nonce = decode_b64(&export.nonce_b64, "nonce_b64")?;
+    if nonce.len() != 24 {
+        return Err(ExportError::InvalidField("nonce_b64"));
+    }
+    let ciphertext = decode_b64(&export.ciphertext_b64, "ciphertext_b64")?;
+
+    let cipher = content_cipher(shared.as_bytes(), &ephemeral_public, &recipient)?;
+    let aad = associated_data(&export)?;
+    let bytes = cipher
+        .decrypt(
+            XNonce::from_slice(&nonce),
+            Payload {
+                msg: &ciphertext,
+                aad: &aad,
+            },
+        )
+        .map_err(|_| ExportError::DecryptFailed)?;
+
+    Ok(DecryptedExport {
+        bytes,
+        metadata: export.metadata,
+    })
+}
+
+fn content_cipher(
+    shared: &[u8; 32],
+    ephemeral: &PublicKey,
+    recipient: &PublicKey,
+) -> Result<XChaCha20Poly1305, ExportError> {
+    let mut salt = [0u8; 64];
+    salt[..32].copy_from_slice(ephemeral.as_bytes());
+    salt[32..].copy_from_slice(recipient.as_bytes());
+    let mut key = [0u8; 32];
+    Hkdf::<Sha256>::new(Some(&salt), shared)
+        .expand(HKDF_INFO, &mut key)
+        .map_err(|_| ExportError::EncryptFailed)?;
+    Ok(XChaCha20Poly1305::new(&key.into()))
+}
+
+/// Everything except the ciphertext itself, so format, keys, nonce and metadata are all authenticated.
+fn associated_data(export: &EncryptedExport) -> Result<Vec<u8>, ExportError> {
+    Ok(serde_json::to_vec(&serde_json::json!({
+        "format": export.format,
+        "recipient_pubkey_b64": export.recipient_pubkey_b64,
+        "ephemeral_pubkey_b64": export.ephemeral_pubkey_b64,
+        "nonce_b64": export.nonce_b64,
+        "metadata": export.metadata,
+    }))?)
+}
+
+fn decode_key(value: &str) -> Result<[u8; 32], ExportError> {
+    let raw = base64::engine::general_purpose::STANDARD
+        .decode(value.trim())
+        .map_err(|e| ExportError::InvalidKey(e.to_string()))?;
+    raw.try_into().map_err(|v: Vec<u8>| {
+        ExportError::InvalidKey(format!("expected 32 bytes, got {}", v.len()))
+    })
+}
+
+fn decode_b64(value: &str, field: &'static str) -> Result<Vec<u8>, ExportError> {
+    base64::engine::general_purpose::STANDARD
+        .decode(value)
+        .map_err(|_| ExportError::InvalidField(field))
+}
+
+fn b64(bytes: impl AsRef<[u8]>) -> String {
+    base64::engine::general_purpose::STANDARD.encode(bytes)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn metadata() -> ExportMetadata {
+        ExportMetadata::new(
+            1_000,
+            3_600,
+            "ops@host",
+            "incident-42",
+            &["b".into(), "a".into()],
+        )
+    }
+
+    #[test]
+    fn roundtrip_for_recipient_identity() {
+        let (secret, public) = generate_identity();
+
+        let archive = encrypt_bundle(b"{\"bundle\":1}", &public, &metadata()).unwrap();
+        let out = decrypt_bundle(&archive, &secret, 2_000).unwrap();
+        assert_eq!(out.bytes, b"{\"bundle\":1}");
+        assert_eq!(out.metadata, metadata());
+        assert_eq!(out.metadata.expires_at_unix, 4_600);
+        // approval order does not change the digest
+        assert_eq!(
+            out.metadata.approvals_sha256_hex,
+            ExportMetadata::new(
+                1_000,
+                3_600,
+                "ops@host",
+                "incident-42",
+                &["a".into(), "b".into()]
+            )
+            .approvals_sha256_hex
+        );
+    }
+
+    #[test]
+    fn wrong_identity_expired_and_tampered_archives_fail_closed() {
+        let (secret, public) = generate_identity();
+        let (other, _) = generate_identity();
+        let archive = encrypt_bundle(b"payload", &public, &metadata()).unwrap();
+
+        assert!(matches!(
+            decrypt_bundle(&archive, &other, 2_000),
+            Err(ExportError::RecipientMismatch { .. })
+        ));
+        assert!(matches!(
+            decrypt_bundle(&archive, &secret, 4_600),
+            Err(ExportError::Expired { .. })
+        ));
+
+        // extending the TTL in cleartext breaks the AEAD tag
+        let mut export: EncryptedExport = serde_json::from_slice(&archive).unwrap();
+        export.metadata.expires_at_unix += 86_400;
+        let tampered = serde_json::to_vec(&export).unwrap();
+        assert!(matches!(
+            decrypt_bundle(&tampered, &secret, 2_000),
+            Err(ExportError::DecryptFailed)
+        ));
+
+        assert!(matches!(
+            encrypt_bundle(b"payload", "not-a-key", &metadata()),
+            Err(ExportError::InvalidKey(_))
+        ));
+    }
+}
@@ -1,6 +1,7 @@
 mod args;
 mod audit;
 mod cmd_analyze;
+mod cmd_import;
 mod cmd_verify;
 mod export;
 mod rbac;
@@ -44,8 +45,15 @@ fn main() {
                 let bg = rbac::check_break_glass(ttl_minutes, &reason, &approval)
                     .map_err(|e| format!("RBAC:{e}"))?;
                 let bytes = std::fs::read(&input).map_err(|e| e.to_string())?;
-                let encrypted =
-                    export::encrypt_bundle(&bytes, &recipient_pubkey).map_err(|e| e.to_string())?;
+                let metadata = export::ExportMetadata::new(
+                    OffsetDateTime::now_utc().unix_timestamp(),
+                    bg.ttl.as_secs(),
+                    &actor,
+                    &bg.reason,
+                    &bg.approvals,
+                );
+                let encrypted = export::encrypt_bundle(&bytes, &recipient_pubkey, &metadata)
+                    .map_err(|e| e.to_string())?;
                 std::fs::write(&output, encrypted).map_err(|e| e.to_string())?;
                 Ok(audit::BreakGlassSummary {
                     ttl_seconds: bg.ttl.as_secs(),
@@ -91,6 +99,57 @@ fn main() {
                 }
             }
         }
+        Command::Import {
+            input,
+            identity,
+            output,
+            analyze_output,
+        } => {
+            let result = cmd_import::run(
+                &input,
+                &identity,
+                &output,
+                analyze_output.as_deref(),
+                OffsetDateTime::now_utc().unix_timestamp(),
+            );
+            let (outcome, reason_code, break_glass) = match &result {
+                Ok(meta) => (
+                    "ok",
+                    "OK".to_string(),
+                    Some(audit::BreakGlassSummary {
+                        ttl_seconds: (meta.expires_at_unix - meta.created_at_unix).max(0) as u64,
+                        reason: meta.reason.clone(),
+                        approvals_count: meta.approvals_count,
+                    }),
+                ),
+                Err(e) => ("error", format!("IMPORT_FAILED:{e}"), None),
+            };
+            let event = audit::AuditEvent {
+                event_id: uuid::Uuid::new_v4().to_string(),
+                ts_rfc3339: now_rfc3339(),
+                actor: rbac::current_actor(),
+                action: "import".into(),
+                bundle_id: None,
+                diag_semver: None,
+                result: outcome.into(),
+                reason_code,
+                break_glass,
+                prev_hash_hex: String::new(),
+                this_hash_hex: String::new(),
+            };
+            let _ = audit::append_audit_event(&audit_path, event);
+            if let Err(e) = result {
+                eprintln!("IMPORT_FAILED:{e}");
+                std::process::exit(1);
+            }
+        }
+        Command::Keygen { output } => match cmd_import::keygen(&output) {
+            Ok(public) => println!("{public}"),
+            Err(e) => {
+                eprintln!("KEYGEN_FAILED:{e}");
+                std::process::exit(1);
+            }
+        },
     }
 }
 
@@ -0,0 +1,179 @@
+use std::fs;
+use std::path::PathBuf;
+use std::process::{Command, Output};
+
+fn diag(dir: &std::path::Path, args: &[&str]) -> Output {
+    Command::new(env!("CARGO_BIN_EXE_ucel-diagnostics-cli"))
+        .args(args)
+        .env("UCEL_DIAG_AUDIT_PATH", dir.join("audit.jsonl"))
+        .env("UCEL_DIAG_BG_APPROVALS_REQUIRED", "2")
+        .output()
+        .expect("run ucel-diag")
+}
+
+fn path(dir: &std::path::Path, name: &str) -> String {
+    dir.join(name).display().to_string()
+}
+
+#[test]
+fn export_is_decryptable_only_by_recipient_identity() {
+    let dir = tempfile::tempdir().unwrap();
+    let bundle = PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+        .join("../../fixtures/support_bundle/bundle_v1.json");
+
+    let keygen = diag(
+        dir.path(),
+        &["keygen", "--output", &path(dir.path(), "id.key")],
+    );
+    assert!(keygen.status.success());
+    let pubkey = String::from_utf8(keygen.stdout).unwrap().trim().to_string();
+    let other = diag(
+        dir.path(),
+        &["keygen", "--output", &path(dir.path(), "other.key")],
+    );
+    assert!(other.status.success());
+    let again = diag(
+        dir.path(),
+        &["keygen", "--output", &path(dir.path(), "other.key")],
+    );
+    assert!(!again.status.success());
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        let mode = fs::metadata(dir.path().join("id.key"))
+            .unwrap()
+            .permissions()
+            .mode();
+        assert_eq!(mode & 0o777, 0o600);
+    }
+
+    let export = diag(
+        dir.path(),
+        &[
+            "export",
+            "--input",
+            &bundle.display().to_string(),
+            "--recipient-pubkey",
+            &pubkey,
+            "--output",
+            &path(dir.path(), "bundle.enc.json"),
+            "--ttl-minutes",
+            "60",
+            "--reason",
+            "incident",
+            "--approval",
+            "a",
+            "--approval",
+            "b",
+        ],
+    );
+    assert!(export.status.success(), "{export:?}");
+    let archive = fs::read_to_string(dir.path().join("bundle.enc.json")).unwrap();
+    assert!(archive.contains("x25519-hkdf-sha256-xchacha20poly1305"));
+    assert!(!archive.contains("\"approvals\""));
+
+    let wrong = diag(
+        dir.path(),
+        &[
+            "import",
+            "--input",
+            &path(dir.path(), "bundle.enc.json"),
+            "--identity",
+            &path(dir.path(), "other.key"),
+            "--output",
+            &path(dir.path(), "wrong.json"),
+        ],
+    );
+    assert!(!wrong.status.success());
+    assert!(
+        String::from_utf8_lossy(&wrong.stderr).contains("IMPORT_FAILED:archive is for recipient")
+    );
+    assert!(!dir.path().join("wrong.json").exists());
+
+    // the secret key itself is not accepted, and is not echoed back
+    let secret = fs::read_to_string(dir.path().join("id.key")).unwrap();
+    let raw_key = diag(
+        dir.path(),
+        &[
+            "import",
+            "--input",
+            &path(dir.path(), "bundle.enc.json"),
+            "--identity",
+            secret.trim(),
+            "--output",
+            &path(dir.path(), "raw.json"),
+        ],
+    );
+    assert!(!raw_key.status.success());
+    let stderr = String::from_utf8_lossy(&raw_key.stderr);
+    assert!(stderr.contains("identity must be a path"), "{stderr}");
+    assert!(!stderr.contains(secret.trim()));
+    assert!(!dir.path().join("raw.json").exists());
+
+    let import = diag(
+        dir.path(),
+        &[
+            "import",
+            "--input",
+            &path(dir.path(), "bundle.enc.json"),
+            "--identity",
+            &path(dir.path(), "id.key"),
+            "--output",
+            &path(dir.path(), "bundle.json"),
+            "--analyze-output",
+            &path(dir.path(), "summary.json"),
+        ],
+    );
+    assert_eq!(
+        fs::read(dir.path().join("bundle.json")).unwrap(),
+        fs::read(&bundle).unwrap()
+    );
+    assert!(dir.path().join("summary.json").exists());
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        let mode = fs::metadata(dir.path().join("bundle.json"))
+            .unwrap()
+            .permissions()
+            .mode();
+        assert_eq!(mode & 0o777, 0o600);
+    }
+    // decrypt + analyze succeeded; only the verify gate may reject the bundle
+    if !import.status.success() {
+        assert!(
+            String::from_utf8_lossy(&import.stderr).starts_with("IMPORT_FAILED:VERIFY:"),
+            "{import:?}"
+        );
+    }
+
+    // an existing output is never overwritten
+    let again = diag(
+        dir.path(),
+        &[
+            "import",
+            "--input",
+            &path(dir.path(), "bundle.enc.json"),
+            "--identity",
+            &path(dir.path(), "id.key"),
+            "--output",
+            &path(dir.path(), "bundle.json"),
+        ],
+    );
+    assert!(!again.status.success());
+    assert!(String::from_utf8_lossy(&again.stderr).contains("refusing to overwrite"));
+
+    let audit = fs::read_to_string(dir.path().join("audit.jsonl")).unwrap();
+    let actions: Vec<String> = audit
+        .lines()
+        .map(|l| {
+            serde_json::from_str::<serde_json::Value>(l).unwrap()["action"]
+                .as_str()
+                .unwrap()
+                .to_string()
+        })
+        .collect();
+    assert_eq!(
+        actions,
+        vec!["export", "import", "import", "import", "import"]
+    );
+}
@@ -15,6 +15,7 @@ pub mod model;
 pub mod normalize;
 pub mod providers;
 pub mod sinks;
+pub mod sync_runner;
 pub mod us_official;
 
 pub use checkpoint::{CheckpointStore, FsCheckpointStore, MemoryCheckpointStore};
@@ -29,6 +30,10 @@ pub use domain::{
 };
 pub use errors::{UcelIrError, UcelIrErrorKind};
 pub use sinks::{EventSink, FsRawSink, MemorySink, RawSink};
+pub use sync_runner::{
+    IrDocumentSink, IrSyncCursor, IrSyncPassReport, IrSyncPoliteness, IrSyncRunner,
+    IrSyncSourceConfig, IrSyncSourceMetrics, IrSyncSourceStatus, MemoryDocumentSink,
+};
 
 pub use providers::edinet::{
     EdinetConfig, EdinetProvider, FetchArtifactRequest, IrProviderSource, ListEventsRequest,
@@ -58,6 +63,7 @@ pub use identity::{
 pub use model::{build_source_descriptor, inventory_taxonomy_supported};
 
 pub use jp_official::{statutory_adapter, timely_adapter, JpOfficialAdapter, JpPolitenessPolicy};
+pub use us_official::{sec_adapter, UsOfficialAdapter, UsPolitenessPolicy};
 
 pub use issuer_sites::{
     jp_issuer_feed_adapter, jp_issuer_html_adapter, us_issuer_feed_adapter,
@@ -2,6 +2,7 @@ use super::errors::{IrNormalizationError, IrNormalizationReasonCode};
 use ucel_core::{IrNormalizationProvenance, IrNormalizationSchemaVersion, IrNormalizationSupport, IrNormalizedContent, IrNormalizedFormat};
 use crate::artifact::IrArtifactFetchResponse;
 
+#[allow(clippy::too_many_arguments)]
 pub fn assemble(
     fetch: &IrArtifactFetchResponse,
     format: IrNormalizedFormat,
@@ -24,6 +25,8 @@ pub fn assemble(
         sections,
         tables,
         extracted_attachments: attachments,
+        facts: vec![],
+        financial_items: vec![],
         language_hints: vec![],
         charset,
         provenance: IrNormalizationProvenance { source_type: Some("artifact".into()), source_ref: Some(fetch.metadata.key.artifact_id.clone()), context_ref: None, extra: Default::default() },
@@ -13,6 +13,27 @@ pub enum IrNormalizationReasonCode {
     MalformedPdf,
     TableExtractionFailed,
     ProvenanceLost,
+    /// A PDF page paints images but carries no text layer (scanned page; OCR is out of scope).
+    ImageOnlyPage,
+}
+
+impl IrNormalizationReasonCode {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::UnknownFormat => "unknown_format",
+            Self::InvalidCharset => "invalid_charset",
+            Self::ParseFailed => "parse_failed",
+            Self::OversizedArtifact => "oversized_artifact",
+            Self::InvalidArchive => "invalid_archive",
+            Self::UnsupportedNestedArchive => "unsupported_nested_archive",
+            Self::MalformedXbrl => "malformed_xbrl",
+            Self::MalformedHtml => "malformed_html",
+            Self::MalformedPdf => "malformed_pdf",
+            Self::TableExtractionFailed => "table_extraction_failed",
+            Self::ProvenanceLost => "provenance_lost",
+            Self::ImageOnlyPage => "image_only_page",
+        }
+    }
 }
 
 #[derive(Debug, Clone)]
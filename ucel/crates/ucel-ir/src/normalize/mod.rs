@@ -17,7 +17,7 @@ pub mod zip;
 
 use crate::artifact::IrArtifactFetchResponse;
 use errors::{IrNormalizationError, IrNormalizationReasonCode};
-use ucel_core::{IrNormalizationSupport, IrNormalizedContent, IrNormalizedFormat};
+use ucel_core::{IrNormalizationProvenance, IrNormalizationSupport, IrNormalizedContent, IrNormalizedFormat, IrNormalizedSection};
 
 pub fn normalize_artifact(fetch: &IrArtifactFetchResponse) -> Result<IrNormalizedContent, IrNormalizationError> {
     let bytes = fetch.bytes.as_deref().unwrap_or_default();
@@ -28,21 +28,87 @@ pub fn normalize_artifact(fetch: &IrArtifactFetchResponse) -> Result<IrNormalize
 
 pub fn normalize_artifact_with_format(fetch: &IrArtifactFetchResponse, fmt: IrNormalizedFormat) -> Result<IrNormalizedContent, IrNormalizationError> {
     let bytes = fetch.bytes.as_deref().unwrap_or_default();
+    let pdf_pages = if matches!(fmt, IrNormalizedFormat::Pdf) { pdf::pdf_pages(bytes)? } else { vec![] };
     let raw_text = if let Some(t) = &fetch.text_candidate { t.clone() } else { String::from_utf8_lossy(bytes).to_string() };
     let (txt, charset) = charset::normalize_to_utf8(raw_text.as_bytes(), fetch.metadata.encoding.as_deref())?;
     let normalized_text = match fmt {
         IrNormalizedFormat::Html | IrNormalizedFormat::Ixbrl => html::html_to_text(&txt),
         IrNormalizedFormat::Xml => xml::xml_to_text(&txt),
         IrNormalizedFormat::Xbrl => xbrl::xbrl_to_text(&txt),
-        IrNormalizedFormat::Pdf => pdf::pdf_text_layer(bytes)?,
+        IrNormalizedFormat::Pdf => pdf::pages_text(&pdf_pages)?,
         IrNormalizedFormat::Txt => text::normalize_text(&txt),
         IrNormalizedFormat::Csv => csv::normalize_csv(&txt),
         IrNormalizedFormat::Json => json::normalize_json(&txt)?,
         IrNormalizedFormat::Rss => rss::normalize_rss(&txt)?,
         IrNormalizedFormat::Zip => "[zip archive]".to_string(),
     };
-    let sections = sections::sections_from_text(&normalized_text);
-    let tables = if matches!(fmt, IrNormalizedFormat::Csv) { vec![tables::csv_to_table(&normalized_text)] } else { vec![] };
+    let sections = if pdf_pages.is_empty() { sections::sections_from_text(&normalized_text) } else { pdf_page_sections(&pdf_pages) };
+    let tables = match fmt {
+        IrNormalizedFormat::Csv => vec![tables::csv_to_table(&normalized_text)],
+        IrNormalizedFormat::Html | IrNormalizedFormat::Ixbrl => tables::html::html_tables(&txt),
+        IrNormalizedFormat::Pdf => tables::pdf::pdf_tables(&pdf_pages),
+        _ => vec![],
+    };
     let attachments = if matches!(fmt, IrNormalizedFormat::Zip) { zip::unpack_zip(bytes, safety::IrUnpackPolicy::default())? } else { vec![] };
-    content::assemble(fetch, fmt, normalized_text, sections, tables, attachments, charset, IrNormalizationSupport::Supported)
+    // XBRL that does not parse still yields its text layer; the facts are dropped and the reason kept
+    let fact_result = match fmt {
+        IrNormalizedFormat::Xbrl => Some(xbrl::parse_xbrl_instance(&txt)),
+        IrNormalizedFormat::Ixbrl => Some(xbrl::parse_ixbrl(&txt)),
+        _ => None,
+    };
+    let mut fact_error = None;
+    let (facts, support_level) = match fact_result {
+        Some(Ok(facts)) => {
+            // facts whose value could not be normalized keep their text, with the reason in their provenance
+            match facts.iter().find_map(|f| f.provenance.extra.get("reason_code")) {
+                Some(_) => {
+                    fact_error = Some(IrNormalizationReasonCode::MalformedXbrl);
+                    (facts, IrNormalizationSupport::Partial)
+                }
+                None => (facts, IrNormalizationSupport::Supported),
+            }
+        }
+        Some(Err(e)) => {
+            fact_error = Some(e.reason);
+            (vec![], IrNormalizationSupport::Partial)
+        }
+        None if matches!(fmt, IrNormalizedFormat::Pdf) && pdf_pages.iter().any(|p| p.image_only || p.unmapped_glyphs > 0) => (vec![], IrNormalizationSupport::Partial),
+        None => (vec![], IrNormalizationSupport::Supported),
+    };
+    let mut content = content::assemble(fetch, fmt, normalized_text, sections, tables, attachments, charset, support_level)?;
+    content.financial_items = xbrl::financial_items(&facts);
+    content.facts = facts;
+    if let Some(reason) = fact_error {
+        content.provenance.extra.insert("reason_code".into(), reason.as_str().into());
+    }
+    let image_only: Vec<String> = pdf_pages.iter().filter(|p| p.image_only).map(|p| p.page.to_string()).collect();
+    if !image_only.is_empty() {
+        content.provenance.extra.insert("image_only_pages".into(), image_only.join(","));
+        content.provenance.extra.insert("reason_code".into(), IrNormalizationReasonCode::ImageOnlyPage.as_str().into());
+    }
+    let unmapped: usize = pdf_pages.iter().map(|p| p.unmapped_glyphs).sum();
+    if unmapped > 0 {
+        content.provenance.extra.insert("unmapped_glyphs".into(), unmapped.to_string());
+    }
+    Ok(content)
+}
+
+/// One section per PDF page, with ranges into the `\n\n`-joined text layer.
+fn pdf_page_sections(pages: &[pdf::PdfPageText]) -> Vec<IrNormalizedSection> {
+    let mut out = Vec::new();
+    let mut idx = 0usize;
+    for p in pages.iter().filter(|p| !p.text.is_empty()) {
+        if !out.is_empty() {
+            idx += 2;
+        }
+        out.push(IrNormalizedSection {
+            heading_level: None,
+            title: format!("page {}", p.page),
+            ordinal: out.len(),
+            text_range: (idx, idx + p.text.len()),
+            provenance: IrNormalizationProvenance { source_type: Some("pdf_page".into()), source_ref: Some(format!("page:{}", p.page)), context_ref: None, extra: Default::default() },
+        });
+        idx += p.text.len();
+    }
+    out
 }
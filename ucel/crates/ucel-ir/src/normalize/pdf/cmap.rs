@@ -0,0 +1,389 @@
+//! Font code -> Unicode mapping (ToUnicode CMaps, predefined UCS-2 CMaps, simple encodings).
+
+use super::super::errors::IrNormalizationError;
+use super::object::{decode_or_skip, Dict, Lexer, Obj, PdfFile};
+use std::collections::HashMap;
+
+/// One decoded glyph: its text (None when the code has no Unicode mapping) and advance.
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct Glyph {
+    pub(crate) text: Option<String>,
+    /// Horizontal advance in glyph space (1/1000 text space units).
+    pub(crate) width: f64,
+    /// Single-byte code 32, which also receives word spacing (`Tw`).
+    pub(crate) is_space: bool,
+}
+
+#[derive(Debug, Clone, Default)]
+struct CMap {
+    /// (byte length, low, high) code space ranges.
+    codespace: Vec<(usize, u32, u32)>,
+    map: HashMap<(usize, u32), String>,
+}
+
+impl CMap {
+    fn parse(data: &[u8]) -> Self {
+        let mut cmap = CMap::default();
+        let mut lx = Lexer::new(data, 0);
+        let mut operands: Vec<Obj> = Vec::new();
+        let mut section = "";
+        while let Some(tok) = lx.parse() {
+            match tok {
+                Obj::Op(op) => {
+                    match op.as_str() {
+                        "begincodespacerange" => section = "codespace",
+                        "beginbfchar" => section = "bfchar",
+                        "beginbfrange" => section = "bfrange",
+                        "endcodespacerange" => {
+                            for pair in operands.chunks(2) {
+                                if let [Obj::Str(lo), Obj::Str(hi)] = pair {
+                                    cmap.codespace.push((lo.len(), be(lo), be(hi)));
+                                }
+                            }
+                            section = "";
+                        }
+                        "endbfchar" => {
+                            for pair in operands.chunks(2) {
+                                if let [Obj::Str(src), dst] = pair {
+                                    if let Some(t) = dst_text(dst) {
+                                        cmap.map.insert((src.len(), be(src)), t);
+                                    }
+                                }
+                            }
+                            section = "";
+                        }
+                        "endbfrange" => {
+                            for triple in operands.chunks(3) {
+                                let [Obj::Str(lo), Obj::Str(hi), dst] = triple else { continue };
+                                let (len, lo, hi) = (lo.len(), be(lo), be(hi));
+                                if hi < lo || hi - lo > 0xFFFF {
+                                    continue;
+                                }
+                                for (i, code) in (lo..=hi).enumerate() {
+                                    let text = match dst {
+                                        Obj::Str(base) => utf16_offset(base, i as u32),
+                                        Obj::Array(items) => items.get(i).and_then(dst_text),
+                                        _ => None,
+                                    };
+                                    if let Some(t) = text {
+                                        cmap.map.insert((len, code), t);
+                                    }
+                                }
+                            }
+                            section = "";
+                        }
+                        _ => {}
+                    }
+                    operands.clear();
+                }
+                other if !section.is_empty() => operands.push(other),
+                _ => {}
+            }
+        }
+        cmap
+    }
+
+    /// Length of the code starting at `bytes[0]` according to the code space ranges.
+    fn code_len(&self, bytes: &[u8]) -> Option<usize> {
+        let mut lens: Vec<usize> = self.codespace.iter().map(|(l, _, _)| *l).collect();
+        lens.sort_unstable();
+        lens.dedup();
+        lens.into_iter().find(|&len| {
+            bytes.len() >= len && {
+                let code = be(&bytes[..len]);
+                self.codespace.iter().any(|(l, lo, hi)| *l == len && (*lo..=*hi).contains(&code))
+            }
+        })
+    }
+}
+
+fn be(bytes: &[u8]) -> u32 {
+    bytes.iter().fold(0u32, |acc, b| acc << 8 | *b as u32)
+}
+
+fn utf16be(bytes: &[u8]) -> Option<String> {
+    let units: Vec<u16> = bytes.chunks(2).map(|c| u16::from_be_bytes([c[0], *c.get(1).unwrap_or(&0)])).collect();
+    char::decode_utf16(units).collect::<Result<String, _>>().ok()
+}
+
+fn dst_text(dst: &Obj) -> Option<String> {
+    match dst {
+        Obj::Str(b) => utf16be(b),
+        Obj::Name(n) => glyph_name_char(n).map(String::from),
+        _ => None,
+    }
+}
+
+/// `bfrange` destination: add `offset` to the last UTF-16 unit of `base`.
+fn utf16_offset(base: &[u8], offset: u32) -> Option<String> {
+    let mut units: Vec<u16> = base.chunks(2).map(|c| u16::from_be_bytes([c[0], *c.get(1).unwrap_or(&0)])).collect();
+    let last = units.last_mut()?;
+    *last = last.checked_add(u16::try_from(offset).ok()?)?;
+    char::decode_utf16(units).collect::<Result<String, _>>().ok()
+}
+
+#[derive(Debug, Clone)]
+enum Fallback {
+    /// Single-byte encoding table.
+    Simple(Box<[Option<char>; 256]>),
+    /// Predefined UCS-2 / UTF-16 CMaps (e.g. `UniJIS-UCS2-H`): codes are UTF-16BE.
+    Utf16,
+    /// Identity or legacy (Shift_JIS/EUC) CMaps without ToUnicode: no mapping.
+    Unmapped,
+}
+
+#[derive(Debug, Clone)]
+pub(crate) struct FontDecoder {
+    composite: bool,
+    to_unicode: Option<CMap>,
+    encoding_cmap: Option<CMap>,
+    fallback: Fallback,
+    widths: HashMap<u32, f64>,
+    default_width: f64,
+}
+
+impl Default for FontDecoder {
+    fn default() -> Self {
+        Self { composite: false, to_unicode: None, encoding_cmap: None, fallback: Fallback::Simple(Box::new(base_encoding("WinAnsiEncoding"))), widths: HashMap::new(), default_width: 500.0 }
+    }
+}
+
+impl FontDecoder {
+    /// Fails only when an embedded CMap stream exceeds the decode size cap.
+    pub(crate) fn from_font(file: &PdfFile, font: &Dict) -> Result<Self, IrNormalizationError> {
+        let to_unicode = match file.get(font, "ToUnicode") {
+            Some(Obj::Stream(d, data)) => decode_or_skip(d, data)?.map(|b| CMap::parse(&b)),
+            _ => None,
+        };
+        let composite = file.get(font, "Subtype").and_then(Obj::as_name) == Some("Type0");
+        if composite {
+            let (fallback, encoding_cmap) = match file.get(font, "Encoding") {
+                Some(Obj::Name(n)) if n.contains("UCS2") || n.contains("UTF16") => (Fallback::Utf16, None),
+                Some(Obj::Stream(d, data)) => (Fallback::Unmapped, decode_or_skip(d, data)?.map(|b| CMap::parse(&b))),
+                _ => (Fallback::Unmapped, None),
+            };
+            let descendant = file.get(font, "DescendantFonts").and_then(Obj::as_array).and_then(|a| a.first()).map(|o| file.resolve(o)).and_then(Obj::as_dict);
+            let mut widths = HashMap::new();
+            let mut default_width = 1000.0;
+            if let Some(cid) = descendant {
+                default_width = file.get(cid, "DW").and_then(Obj::as_num).unwrap_or(1000.0);
+                if let Some(w) = file.get(cid, "W").and_then(Obj::as_array) {
+                    cid_widths(file, w, &mut widths);
+                }
+            }
+            return Ok(Self { composite, to_unicode, encoding_cmap, fallback, widths, default_width });
+        }
+
+        let mut table = Box::new(base_encoding("StandardEncoding"));
+        match file.get(font, "Encoding") {
+            Some(Obj::Name(n)) => *table = base_encoding(n),
+            Some(Obj::Dict(enc)) => {
+                if let Some(base) = file.get(enc, "BaseEncoding").and_then(Obj::as_name) {
+                    *table = base_encoding(base);
+                }
+                if let Some(diffs) = file.get(enc, "Differences").and_then(Obj::as_array) {
+                    let mut code = 0usize;
+                    for d in diffs {
+                        match d {
+                            Obj::Num(n) => code = *n as usize,
+                            Obj::Name(name) => {
+                                if code < 256 {
+                                    table[code] = glyph_name_char(name);
+                                }
+                                code += 1;
+                            }
+                            _ => {}
+                        }
+                    }
+                }
+            }
+            _ => {}
+        }
+        let first = file.get(font, "FirstChar").and_then(Obj::as_num).unwrap_or(0.0) as u32;
+        let mut widths = HashMap::new();
+        if let Some(w) = file.get(font, "Widths").and_then(Obj::as_array) {
+            for (i, v) in w.iter().enumerate() {
+                if let Some(n) = file.resolve(v).as_num() {
+                    widths.insert(first + i as u32, n);
+                }
+            }
+        }
+        let default_width = file
+            .dict(font, "FontDescriptor")
+            .and_then(|fd| file.get(fd, "MissingWidth"))
+            .and_then(Obj::as_num)
+            .filter(|w| *w > 0.0)
+            .unwrap_or(500.0);
+        Ok(Self { composite, to_unicode, encoding_cmap: None, fallback: Fallback::Simple(table), widths, default_width })
+    }
+
+    pub(crate) fn decode(&self, bytes: &[u8]) -> Vec<Glyph> {
+        let mut out = Vec::new();
+        let mut i = 0;
+        while i < bytes.len() {
+            let len = if self.composite {
+                self.encoding_cmap
+                    .as_ref()
+                    .and_then(|c| c.code_len(&bytes[i..]))
+                    .or_else(|| self.to_unicode.as_ref().and_then(|c| c.code_len(&bytes[i..])))
+                    .unwrap_or(2)
+            } else {
+                1
+            }
+            .min(bytes.len() - i);
+            let code = be(&bytes[i..i + len]);
+            i += len;
+            let text = self
+                .to_unicode
+                .as_ref()
+                .and_then(|c| c.map.get(&(len, code)).cloned())
+                .or_else(|| match &self.fallback {
+                    Fallback::Simple(table) => table.get(code as usize).copied().flatten().map(String::from),
+                    Fallback::Utf16 => utf16be(&code.to_be_bytes()[4 - len..]),
+                    Fallback::Unmapped => None,
+                });
+            let width = self.widths.get(&code).copied().unwrap_or(self.default_width);
+            out.push(Glyph { text, width, is_space: !self.composite && code == 32 });
+        }
+        out
+    }
+}
+
+fn cid_widths(file: &PdfFile, w: &[Obj], out: &mut HashMap<u32, f64>) {
+    let mut i = 0;
+    while i < w.len() {
+        let Some(first) = file.resolve(&w[i]).as_num() else { break };
+        match w.get(i + 1).map(|o| file.resolve(o)) {
+            Some(Obj::Array(list)) => {
+                for (k, v) in list.iter().enumerate() {
+                    if let Some(n) = v.as_num() {
+                        out.insert(first as u32 + k as u32, n);
+                    }
+                }
+                i += 2;
+            }
+            Some(Obj::Num(last)) => {
+                let width = w.get(i + 2).and_then(|o| file.resolve(o).as_num()).unwrap_or(1000.0);
+                for c in first as u32..=(*last as u32).min(first as u32 + 0xFFFF) {
+                    out.insert(c, width);
+                }
+                i += 3;
+            }
+            _ => break,
+        }
+    }
+}
+
+const WIN_ANSI_HIGH: [(u8, char); 27] = [
+    (0x80, '€'), (0x82, '‚'), (0x83, 'ƒ'), (0x84, '„'), (0x85, '…'), (0x86, '†'), (0x87, '‡'), (0x88, 'ˆ'), (0x89, '‰'),
+    (0x8A, 'Š'), (0x8B, '‹'), (0x8C, 'Œ'), (0x8E, 'Ž'), (0x91, '\u{2018}'), (0x92, '\u{2019}'), (0x93, '\u{201C}'), (0x94, '\u{201D}'),
+    (0x95, '•'), (0x96, '–'), (0x97, '—'), (0x98, '˜'), (0x99, '™'), (0x9A, 'š'), (0x9B, '›'), (0x9C, 'œ'), (0x9E, 'ž'), (0x9F, 'Ÿ'),
+];
+
+fn base_encoding(name: &str) -> [Option<char>; 256] {
+    let mut t = [None; 256];
+    for (c, slot) in t.iter_mut().enumerate().take(0x7F).skip(0x20) {
+        *slot = char::from_u32(c as u32);
+    }
+    for c in [b'\t', b'\n', b'\r'] {
+        t[c as usize] = Some(c as char);
+    }
+    if name == "WinAnsiEncoding" {
+        for (code, ch) in WIN_ANSI_HIGH {
+            t[code as usize] = Some(ch);
+        }
+        for (c, slot) in t.iter_mut().enumerate().skip(0xA0) {
+            *slot = char::from_u32(c as u32);
+        }
+    }
+    t
+}
+
+/// Adobe glyph names commonly found in `/Differences` arrays.
+fn glyph_name_char(name: &str) -> Option<char> {
+    if let Some(hex) = name.strip_prefix("uni").filter(|h| h.len() == 4) {
+        return u32::from_str_radix(hex, 16).ok().and_then(char::from_u32);
+    }
+    if let Some(hex) = name.strip_prefix('u').filter(|h| (4..=6).contains(&h.len()) && h.chars().all(|c| c.is_ascii_hexdigit())) {
+        return u32::from_str_radix(hex, 16).ok().and_then(char::from_u32);
+    }
+    let mut chars = name.chars();
+    if let (Some(c), None) = (chars.next(), chars.next()) {
+        return Some(c);
+    }
+    const DIGITS: [&str; 10] = ["zero", "one", "two", "three", "four", "five", "six", "seven", "eight", "nine"];
+    if let Some(d) = DIGITS.iter().position(|d| *d == name) {
+        return char::from_digit(d as u32, 10);
+    }
+    Some(match name {
+        "space" | "nbspace" => ' ',
+        "exclam" => '!',
+        "quotedbl" => '"',
+        "numbersign" => '#',
+        "dollar" => '$',
+        "percent" => '%',
+        "ampersand" => '&',
+        "quotesingle" => '\'',
+        "parenleft" => '(',
+        "parenright" => ')',
+        "asterisk" => '*',
+        "plus" => '+',
+        "comma" => ',',
+        "hyphen" | "minus" => '-',
+        "period" => '.',
+        "slash" => '/',
+        "colon" => ':',
+        "semicolon" => ';',
+        "less" => '<',
+        "equal" => '=',
+        "greater" => '>',
+        "question" => '?',
+        "at" => '@',
+        "bracketleft" => '[',
+        "backslash" => '\\',
+        "bracketright" => ']',
+        "underscore" => '_',
+        "braceleft" => '{',
+        "bar" => '|',
+        "braceright" => '}',
+        "asciitilde" => '~',
+        "endash" => '–',
+        "emdash" => '—',
+        "bullet" => '•',
+        "ellipsis" => '…',
+        "quoteleft" => '\u{2018}',
+        "quoteright" => '\u{2019}',
+        "quotedblleft" => '\u{201C}',
+        "quotedblright" => '\u{201D}',
+        "yen" => '¥',
+        "Euro" => '€',
+        _ => return None,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn to_unicode_bfchar_and_bfrange_map_two_byte_codes() {
+        let cmap = CMap::parse(
+            b"/CIDInit /ProcSet findresource begin 1 begincodespacerange <0000> <FFFF> endcodespacerange\n\
+              2 beginbfchar <0001> <6C7A> <0002> <7B97> endbfchar\n\
+              1 beginbfrange <0010> <0012> <3042> endbfrange\n\
+              1 beginbfrange <0020> <0021> [<5E74> <5EA6>] endbfrange end",
+        );
+        let font = FontDecoder { composite: true, to_unicode: Some(cmap), encoding_cmap: None, fallback: Fallback::Unmapped, widths: HashMap::new(), default_width: 1000.0 };
+        let text: String = font.decode(&[0, 1, 0, 2, 0, 0x11, 0, 0x21, 0, 0x99]).iter().map(|g| g.text.clone().unwrap_or_else(|| "?".into())).collect();
+        assert_eq!(text, "決算ぃ度?");
+    }
+
+    #[test]
+    fn ucs2_cmap_and_differences_fallbacks() {
+        let font = FontDecoder { composite: true, fallback: Fallback::Utf16, ..FontDecoder::default() };
+        assert_eq!(font.decode(&[0x65, 0xE5, 0x67, 0x2C]).iter().filter_map(|g| g.text.clone()).collect::<String>(), "日本");
+        assert_eq!(glyph_name_char("uni30A2"), Some('ア'));
+        assert_eq!(glyph_name_char("seven"), Some('7'));
+        assert_eq!(glyph_name_char("g123"), None);
+    }
+}
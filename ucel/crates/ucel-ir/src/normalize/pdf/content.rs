@@ -0,0 +1,339 @@
+//! Content stream interpretation: positioned text runs and image usage per page.
+
+use super::cmap::FontDecoder;
+use super::{DocumentBudget, PdfTextLine, PdfTextSpan};
+use super::super::errors::IrNormalizationError;
+use super::object::{decode_or_skip, Dict, Lexer, Obj, PdfFile};
+use std::collections::{HashMap, HashSet};
+use std::rc::Rc;
+
+type Matrix = [f64; 6];
+const IDENTITY: Matrix = [1.0, 0.0, 0.0, 1.0, 0.0, 0.0];
+const MAX_FORM_DEPTH: usize = 8;
+
+fn mul(a: &Matrix, b: &Matrix) -> Matrix {
+    [
+        a[0] * b[0] + a[1] * b[2],
+        a[0] * b[1] + a[1] * b[3],
+        a[2] * b[0] + a[3] * b[2],
+        a[2] * b[1] + a[3] * b[3],
+        a[4] * b[0] + a[5] * b[2] + b[4],
+        a[4] * b[1] + a[5] * b[3] + b[5],
+    ]
+}
+
+fn matrix_of(ops: &[Obj]) -> Option<Matrix> {
+    let v: Vec<f64> = ops.iter().filter_map(Obj::as_num).collect();
+    (v.len() == 6).then(|| [v[0], v[1], v[2], v[3], v[4], v[5]])
+}
+
+/// Text shown by one string operand, in device space.
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct TextRun {
+    pub(crate) x: f64,
+    pub(crate) y: f64,
+    pub(crate) end_x: f64,
+    pub(crate) size: f64,
+    pub(crate) text: String,
+}
+
+#[derive(Debug, Default)]
+pub(crate) struct PageScan {
+    pub(crate) runs: Vec<TextRun>,
+    pub(crate) images: usize,
+    pub(crate) unmapped_glyphs: usize,
+    /// First stream that hit the decode size cap or the document budget; the page (and
+    /// document) fails with it.
+    pub(crate) error: Option<IrNormalizationError>,
+}
+
+#[derive(Clone)]
+struct TextState {
+    font: Rc<FontDecoder>,
+    size: f64,
+    char_spacing: f64,
+    word_spacing: f64,
+    h_scale: f64,
+    leading: f64,
+    rise: f64,
+}
+
+struct Interp<'f, 'b> {
+    file: &'f PdfFile,
+    fonts: HashMap<(usize, String), Rc<FontDecoder>>,
+    /// Form XObjects currently being run; a form that draws itself (directly or not) is skipped.
+    active_forms: HashSet<*const Dict>,
+    budget: &'b mut DocumentBudget,
+    scan: PageScan,
+}
+
+pub(crate) fn scan_page(file: &PdfFile, contents: &[u8], resources: Option<&Dict>, budget: &mut DocumentBudget) -> PageScan {
+    let mut it = Interp { file, fonts: HashMap::new(), active_forms: HashSet::new(), budget, scan: PageScan::default() };
+    it.run(contents, resources, IDENTITY, 0);
+    it.scan
+}
+
+impl<'f> Interp<'f, '_> {
+    fn font(&mut self, resources: Option<&Dict>, name: &str) -> Rc<FontDecoder> {
+        let key = (resources.map(|r| r as *const Dict as usize).unwrap_or(0), name.to_string());
+        if let Some(f) = self.fonts.get(&key) {
+            return f.clone();
+        }
+        let file = self.file;
+        let decoder = resources
+            .and_then(|r| file.dict(r, "Font"))
+            .and_then(|fonts| file.dict(fonts, name))
+            .map(|font| FontDecoder::from_font(file, font))
+            .transpose()
+            .unwrap_or_else(|e| {
+                self.scan.error.get_or_insert(e);
+                None
+            })
+            .unwrap_or_default();
+        let decoder = Rc::new(decoder);
+        self.fonts.insert(key, decoder.clone());
+        decoder
+    }
+
+    fn run(&mut self, data: &[u8], resources: Option<&Dict>, base_ctm: Matrix, depth: usize) {
+        let mut lx = Lexer::new(data, 0);
+        let mut operands: Vec<Obj> = Vec::new();
+        let mut ctm = base_ctm;
+        let mut stack: Vec<(Matrix, TextState)> = Vec::new();
+        let mut ts = TextState { font: Rc::new(FontDecoder::default()), size: 0.0, char_spacing: 0.0, word_spacing: 0.0, h_scale: 1.0, leading: 0.0, rise: 0.0 };
+        let mut tm = IDENTITY;
+        let mut tlm = IDENTITY;
+        while let Some(tok) = lx.parse() {
+            let Obj::Op(op) = tok else {
+                operands.push(tok);
+                continue;
+            };
+            if let Err(e) = self.budget.charge_operation() {
+                self.scan.error.get_or_insert(e);
+                return;
+            }
+            let num = |i: usize| operands.get(i).and_then(Obj::as_num).unwrap_or(0.0);
+            match op.as_str() {
+                "q" => stack.push((ctm, ts.clone())),
+                "Q" => {
+                    if let Some((m, s)) = stack.pop() {
+                        ctm = m;
+                        ts = s;
+                    }
+                }
+                "cm" => {
+                    if let Some(m) = matrix_of(&operands) {
+                        ctm = mul(&m, &ctm);
+                    }
+                }
+                "BT" => {
+                    tm = IDENTITY;
+                    tlm = IDENTITY;
+                }
+                "Tf" => {
+                    if let Some(Obj::Name(name)) = operands.first() {
+                        ts.font = self.font(resources, name);
+                    }
+                    ts.size = num(1);
+                }
+                "Tc" => ts.char_spacing = num(0),
+                "Tw" => ts.word_spacing = num(0),
+                "Tz" => ts.h_scale = num(0) / 100.0,
+                "TL" => ts.leading = num(0),
+                "Ts" => ts.rise = num(0),
+                "Td" | "TD" => {
+                    if op == "TD" {
+                        ts.leading = -num(1);
+                    }
+                    tlm = mul(&[1.0, 0.0, 0.0, 1.0, num(0), num(1)], &tlm);
+                    tm = tlm;
+                }
+                "Tm" => {
+                    if let Some(m) = matrix_of(&operands) {
+                        tlm = m;
+                        tm = m;
+                    }
+                }
+                "T*" => {
+                    tlm = mul(&[1.0, 0.0, 0.0, 1.0, 0.0, -ts.leading], &tlm);
+                    tm = tlm;
+                }
+                "Tj" | "'" | "\"" => {
+                    if op != "Tj" {
+                        if op == "\"" {
+                            ts.word_spacing = num(0);
+                            ts.char_spacing = num(1);
+                        }
+                        tlm = mul(&[1.0, 0.0, 0.0, 1.0, 0.0, -ts.leading], &tlm);
+                        tm = tlm;
+                    }
+                    if let Some(Obj::Str(s)) = operands.last() {
+                        self.show(s, &ts, &mut tm, &ctm);
+                    }
+                }
+                "TJ" => {
+                    if let Some(Obj::Array(items)) = operands.first() {
+                        for item in items {
+                            match item {
+                                Obj::Str(s) => self.show(s, &ts, &mut tm, &ctm),
+                                Obj::Num(adj) => {
+                                    let tx = -adj / 1000.0 * ts.size * ts.h_scale;
+                                    tm = mul(&[1.0, 0.0, 0.0, 1.0, tx, 0.0], &tm);
+                                }
+                                _ => {}
+                            }
+                        }
+                    }
+                }
+                "Do" => {
+                    if let Some(Obj::Name(name)) = operands.first() {
+                        self.x_object(resources, name, &ctm, depth);
+                    }
+                }
+                "BI" => {
+                    // inline image: skip to `EI`
+                    let rest = &data[lx.pos..];
+                    let end = rest.windows(4).position(|w| super::object::is_white(w[0]) && &w[1..3] == b"EI" && (super::object::is_white(w[3]) || w[3] == b'Q'));
+                    lx.pos += end.map(|e| e + 3).unwrap_or(rest.len());
+                    self.scan.images += 1;
+                }
+                _ => {}
+            }
+            operands.clear();
+        }
+    }
+
+    fn x_object(&mut self, resources: Option<&Dict>, name: &str, ctm: &Matrix, depth: usize) {
+        let file = self.file;
+        let Some(xobj) = resources.and_then(|r| file.dict(r, "XObject")).and_then(|x| file.get(x, name)) else { return };
+        let Obj::Stream(d, data) = xobj else { return };
+        match file.get(d, "Subtype").and_then(Obj::as_name) {
+            Some("Image") => self.scan.images += 1,
+            Some("Form") if depth < MAX_FORM_DEPTH && self.scan.error.is_none() && !self.active_forms.contains(&(d as *const Dict)) => {
+                let content = match decode_or_skip(d, data).and_then(|c| {
+                    if let Some(c) = &c {
+                        self.budget.charge_bytes(c.len())?;
+                    }
+                    Ok(c)
+                }) {
+                    Ok(Some(content)) => content,
+                    Ok(None) => return,
+                    Err(e) => {
+                        self.scan.error.get_or_insert(e);
+                        return;
+                    }
+                };
+                let matrix = file.get(d, "Matrix").and_then(Obj::as_array).and_then(matrix_of).unwrap_or(IDENTITY);
+                let form_resources = file.dict(d, "Resources").or(resources);
+                self.active_forms.insert(d as *const Dict);
+                self.run(&content, form_resources, mul(&matrix, ctm), depth + 1);
+                self.active_forms.remove(&(d as *const Dict));
+            }
+            _ => {}
+        }
+    }
+
+    fn show(&mut self, bytes: &[u8], ts: &TextState, tm: &mut Matrix, ctm: &Matrix) {
+        let start = mul(&mul(&[1.0, 0.0, 0.0, 1.0, 0.0, ts.rise], tm), ctm);
+        let mut text = String::new();
+        for g in ts.font.decode(bytes) {
+            match &g.text {
+                Some(t) => text.push_str(t),
+                None => self.scan.unmapped_glyphs += 1,
+            }
+            let spacing = ts.char_spacing + if g.is_space { ts.word_spacing } else { 0.0 };
+            let tx = (g.width / 1000.0 * ts.size + spacing) * ts.h_scale;
+            *tm = mul(&[1.0, 0.0, 0.0, 1.0, tx, 0.0], tm);
+        }
+        let end = mul(&mul(&[1.0, 0.0, 0.0, 1.0, 0.0, ts.rise], tm), ctm);
+        let scale = (start[2] * start[2] + start[3] * start[3]).sqrt();
+        if !text.is_empty() {
+            self.scan.runs.push(TextRun { x: start[4], y: start[5], end_x: end[4], size: (ts.size * scale).abs().max(1.0), text });
+        }
+    }
+}
+
+fn is_cjk(c: char) -> bool {
+    matches!(c as u32, 0x3000..=0x30FF | 0x3400..=0x4DBF | 0x4E00..=0x9FFF | 0xF900..=0xFAFF | 0xFF00..=0xFFEF)
+}
+
+/// Groups runs into lines (top to bottom) with runs sorted left to right.
+pub(crate) fn layout_lines(runs: &[TextRun]) -> Vec<PdfTextLine> {
+    let mut runs: Vec<&TextRun> = runs.iter().filter(|r| !r.text.trim().is_empty()).collect();
+    runs.sort_by(|a, b| b.y.total_cmp(&a.y).then(a.x.total_cmp(&b.x)));
+    let mut lines: Vec<PdfTextLine> = Vec::new();
+    for r in runs {
+        let span = PdfTextSpan { x: r.x, end_x: r.end_x, text: r.text.clone() };
+        match lines.last_mut() {
+            Some(line) if (line.y - r.y).abs() <= line.size.max(r.size) * 0.5 => {
+                line.size = line.size.max(r.size);
+                line.spans.push(span);
+            }
+            _ => lines.push(PdfTextLine { y: r.y, size: r.size, spans: vec![span] }),
+        }
+    }
+    for line in &mut lines {
+        line.spans.sort_by(|a, b| a.x.total_cmp(&b.x));
+    }
+    lines
+}
+
+/// Joins spans with a space only where the horizontal gap looks like a word break.
+pub(crate) fn line_text(line: &PdfTextLine) -> String {
+    let mut out = String::new();
+    let mut last_end: Option<f64> = None;
+    for s in &line.spans {
+        if let Some(end) = last_end {
+            let cjk = out.chars().last().is_some_and(is_cjk) && s.text.chars().next().is_some_and(is_cjk);
+            let threshold = if cjk { line.size * 0.8 } else { line.size * 0.15 };
+            if s.x - end > threshold && !out.ends_with(' ') && !s.text.starts_with(' ') {
+                out.push(' ');
+            }
+        }
+        out.push_str(&s.text);
+        last_end = Some(s.end_x.max(last_end.unwrap_or(f64::MIN)));
+    }
+    out.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+/// Rebuilds reading order; a blank line marks a vertical gap wider than ~1.8 lines.
+pub(crate) fn render(lines: &[PdfTextLine]) -> String {
+    let mut out = String::new();
+    let mut prev: Option<(f64, f64)> = None;
+    for line in lines {
+        let text = line_text(line);
+        if text.is_empty() {
+            continue;
+        }
+        if let Some((py, psize)) = prev {
+            out.push('\n');
+            if py - line.y > psize.max(line.size) * 1.8 {
+                out.push('\n');
+            }
+        }
+        out.push_str(&text);
+        prev = Some((line.y, line.size));
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn run(x: f64, y: f64, end_x: f64, text: &str) -> TextRun {
+        TextRun { x, y, end_x, size: 10.0, text: text.into() }
+    }
+
+    #[test]
+    fn reading_order_sorts_lines_and_inserts_word_gaps() {
+        let runs = vec![run(150.0, 700.0, 180.0, "world"), run(72.0, 700.0, 120.0, "Hello"), run(72.0, 600.0, 90.0, "next"), run(72.0, 688.0, 110.0, "below")];
+        assert_eq!(render(&layout_lines(&runs)), "Hello world\nbelow\n\nnext");
+    }
+
+    #[test]
+    fn reading_order_keeps_cjk_runs_tight() {
+        let runs = vec![run(72.0, 700.0, 92.0, "決算"), run(93.0, 700.0, 113.0, "短信")];
+        assert_eq!(render(&layout_lines(&runs)), "決算短信");
+    }
+}
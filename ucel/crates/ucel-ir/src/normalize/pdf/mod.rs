@@ -0,0 +1,200 @@
+//! PDF text layer extraction.
+//!
+//! Content streams are decoded (FlateDecode/ASCIIHex/ASCII85), interpreted for text
+//! positioning and mapped to Unicode via ToUnicode CMaps or the font encoding. Text is
+//! rebuilt per page in reading order; pages that only paint images are reported, not OCR'd.
+
+mod cmap;
+mod content;
+mod object;
+
+pub(crate) use content::line_text;
+
+use super::errors::{IrNormalizationError, IrNormalizationReasonCode};
+use object::{decode_or_skip, Dict, Obj, PdfFile};
+use std::collections::HashSet;
+
+const MAX_PAGE_TREE_DEPTH: usize = 32;
+/// Decoded content bytes per document, counting a stream again each time it is drawn.
+const MAX_DOCUMENT_DECODED_BYTES: u64 = 128 * 1024 * 1024;
+/// Content stream operators interpreted per document, form XObjects included.
+const MAX_DOCUMENT_OPERATIONS: u64 = 5_000_000;
+
+/// Work done on one document so far; shared streams and nested forms are charged on every
+/// use, so fan-out that reuses one object cannot multiply the cost unnoticed.
+#[derive(Debug, Default)]
+pub(crate) struct DocumentBudget {
+    decoded_bytes: u64,
+    operations: u64,
+}
+
+impl DocumentBudget {
+    pub(crate) fn charge_bytes(&mut self, n: usize) -> Result<(), IrNormalizationError> {
+        self.decoded_bytes = self.decoded_bytes.saturating_add(n as u64);
+        if self.decoded_bytes > MAX_DOCUMENT_DECODED_BYTES {
+            return Err(budget_exceeded("decoded content exceeds the document limit", "max_decoded_bytes", MAX_DOCUMENT_DECODED_BYTES));
+        }
+        Ok(())
+    }
+
+    pub(crate) fn charge_operation(&mut self) -> Result<(), IrNormalizationError> {
+        self.operations += 1;
+        if self.operations > MAX_DOCUMENT_OPERATIONS {
+            return Err(budget_exceeded("content operators exceed the document limit", "max_operations", MAX_DOCUMENT_OPERATIONS));
+        }
+        Ok(())
+    }
+}
+
+fn budget_exceeded(message: &str, key: &str, limit: u64) -> IrNormalizationError {
+    let mut err = IrNormalizationError::new(IrNormalizationReasonCode::MalformedPdf, message);
+    err.metadata.insert(key.into(), limit.to_string());
+    err
+}
+
+/// Text drawn by one string operand, in device space.
+#[derive(Debug, Clone, PartialEq)]
+pub struct PdfTextSpan {
+    pub x: f64,
+    pub end_x: f64,
+    pub text: String,
+}
+
+/// Spans sharing a baseline (within half the font size), left to right.
+#[derive(Debug, Clone, PartialEq)]
+pub struct PdfTextLine {
+    pub y: f64,
+    pub size: f64,
+    pub spans: Vec<PdfTextSpan>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct PdfPageText {
+    /// 1-based page number in page tree order.
+    pub page: usize,
+    pub text: String,
+    /// The page paints at least one image and has no extractable text.
+    pub image_only: bool,
+    /// Glyphs whose codes had no Unicode mapping (dropped from `text`).
+    pub unmapped_glyphs: usize,
+    /// Layout the text was rebuilt from, top to bottom.
+    pub lines: Vec<PdfTextLine>,
+}
+
+pub fn pdf_pages(raw: &[u8]) -> Result<Vec<PdfPageText>, IrNormalizationError> {
+    if !raw.starts_with(b"%PDF-") {
+        return Err(IrNormalizationError::new(IrNormalizationReasonCode::MalformedPdf, "missing PDF header"));
+    }
+    let file = PdfFile::parse(raw)?;
+    if is_encrypted(raw, &file) {
+        return Err(IrNormalizationError::new(IrNormalizationReasonCode::MalformedPdf, "encrypted PDF is not supported"));
+    }
+    let pages = page_list(&file)?;
+    if pages.is_empty() {
+        return Err(IrNormalizationError::new(IrNormalizationReasonCode::MalformedPdf, "no pages found"));
+    }
+    let mut budget = DocumentBudget::default();
+    pages
+        .into_iter()
+        .enumerate()
+        .map(|(i, (page, resources))| {
+            let contents = page_contents(&file, page, &mut budget)?;
+            let mut scan = content::scan_page(&file, &contents, resources, &mut budget);
+            if let Some(e) = scan.error.take() {
+                return Err(e);
+            }
+            let lines = content::layout_lines(&scan.runs);
+            let text = content::render(&lines);
+            Ok(PdfPageText { page: i + 1, image_only: text.is_empty() && scan.images > 0, unmapped_glyphs: scan.unmapped_glyphs, text, lines })
+        })
+        .collect()
+}
+
+pub fn pdf_text_layer(raw: &[u8]) -> Result<String, IrNormalizationError> {
+    pages_text(&pdf_pages(raw)?)
+}
+
+/// Joins page texts with a blank line; fails with `ImageOnlyPage` when no page has text but
+/// every page paints an image.
+pub fn pages_text(pages: &[PdfPageText]) -> Result<String, IrNormalizationError> {
+    let text = pages.iter().map(|p| p.text.as_str()).filter(|t| !t.is_empty()).collect::<Vec<_>>().join("\n\n");
+    if text.trim().is_empty() {
+        let mut err = if pages.iter().all(|p| p.image_only) {
+            IrNormalizationError::new(IrNormalizationReasonCode::ImageOnlyPage, "every page is image-only")
+        } else {
+            IrNormalizationError::new(IrNormalizationReasonCode::MalformedPdf, "no text layer found")
+        };
+        err.metadata.insert("pages".into(), pages.len().to_string());
+        return Err(err);
+    }
+    Ok(text)
+}
+
+/// `/Encrypt` in a classic trailer or in a cross-reference stream dictionary.
+fn is_encrypted(raw: &[u8], file: &PdfFile) -> bool {
+    let xref_stream = file.objects.values().filter_map(Obj::as_dict).any(|d| d.get("Type").and_then(Obj::as_name) == Some("XRef") && d.contains_key("Encrypt"));
+    let trailer = raw.windows(7).enumerate().filter(|(_, w)| *w == b"trailer").any(|(at, _)| {
+        matches!(object::Lexer::new(raw, at + 7).parse(), Some(Obj::Dict(d)) if d.contains_key("Encrypt"))
+    });
+    xref_stream || trailer
+}
+
+/// Leaf pages with their (possibly inherited) resources; falls back to every `/Type /Page`
+/// object when there is no usable catalog. A page tree node reached twice (a cycle or a
+/// shared kid) is `MalformedPdf`.
+fn page_list(file: &PdfFile) -> Result<Vec<(&Dict, Option<&Dict>)>, IrNormalizationError> {
+    let mut out = Vec::new();
+    let root = file.objects.values().filter_map(Obj::as_dict).find(|d| file.get(d, "Type").and_then(Obj::as_name) == Some("Catalog"));
+    if let Some(pages) = root.and_then(|r| file.dict(r, "Pages")) {
+        walk_pages(file, pages, None, 0, &mut HashSet::new(), &mut out)?;
+    }
+    if out.is_empty() {
+        for d in file.objects.values().filter_map(Obj::as_dict) {
+            if file.get(d, "Type").and_then(Obj::as_name) == Some("Page") {
+                out.push((d, file.dict(d, "Resources")));
+            }
+        }
+    }
+    Ok(out)
+}
+
+fn walk_pages<'f>(file: &'f PdfFile, node: &'f Dict, inherited: Option<&'f Dict>, depth: usize, visited: &mut HashSet<*const Dict>, out: &mut Vec<(&'f Dict, Option<&'f Dict>)>) -> Result<(), IrNormalizationError> {
+    if depth > MAX_PAGE_TREE_DEPTH {
+        return Ok(());
+    }
+    if !visited.insert(node as *const Dict) {
+        return Err(IrNormalizationError::new(IrNormalizationReasonCode::MalformedPdf, "page tree node is reachable twice"));
+    }
+    let resources = file.dict(node, "Resources").or(inherited);
+    match file.get(node, "Kids").and_then(Obj::as_array) {
+        Some(kids) => {
+            for kid in kids {
+                if let Some(d) = file.resolve(kid).as_dict() {
+                    walk_pages(file, d, resources, depth + 1, visited, out)?;
+                }
+            }
+        }
+        None if file.get(node, "Type").and_then(Obj::as_name) != Some("Pages") => out.push((node, resources)),
+        None => {}
+    }
+    Ok(())
+}
+
+fn page_contents(file: &PdfFile, page: &Dict, budget: &mut DocumentBudget) -> Result<Vec<u8>, IrNormalizationError> {
+    let streams: Vec<&Obj> = match page.get("Contents").map(|c| file.resolve(c)) {
+        Some(Obj::Array(items)) => items.iter().map(|o| file.resolve(o)).collect(),
+        Some(o) => vec![o],
+        None => vec![],
+    };
+    let mut out = Vec::new();
+    for s in streams {
+        if let Obj::Stream(d, data) = s {
+            if let Some(bytes) = decode_or_skip(d, data)? {
+                budget.charge_bytes(bytes.len())?;
+                out.extend_from_slice(&bytes);
+                out.push(b'\n');
+            }
+        }
+    }
+    Ok(out)
+}
@@ -0,0 +1,545 @@
+//! PDF object model and file-level object discovery.
+//!
+//! Objects are found by scanning for `N G obj` rather than trusting the xref table, so
+//! truncated or hand-built files still parse. Object streams (`/Type /ObjStm`) are expanded.
+
+use super::super::errors::{IrNormalizationError, IrNormalizationReasonCode};
+use flate2::read::ZlibDecoder;
+use std::collections::BTreeMap;
+use std::io::Read;
+
+pub(crate) type Dict = BTreeMap<String, Obj>;
+
+/// Arrays and dictionaries nested deeper than this end the parse (hostile input, not real PDFs).
+const MAX_NESTING_DEPTH: usize = 64;
+/// Upper bound for one decoded stream; a deflate bomb fails instead of exhausting memory.
+pub(crate) const MAX_DECODED_STREAM_BYTES: u64 = 64 * 1024 * 1024;
+
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) enum Obj {
+    Null,
+    Bool(bool),
+    Num(f64),
+    Str(Vec<u8>),
+    Name(String),
+    Array(Vec<Obj>),
+    Dict(Dict),
+    Ref(u32),
+    Stream(Dict, Vec<u8>),
+    /// Bare keyword; only meaningful inside content streams (operators).
+    Op(String),
+}
+
+impl Obj {
+    pub(crate) fn as_dict(&self) -> Option<&Dict> {
+        match self {
+            Obj::Dict(d) | Obj::Stream(d, _) => Some(d),
+            _ => None,
+        }
+    }
+    pub(crate) fn as_name(&self) -> Option<&str> {
+        match self {
+            Obj::Name(n) => Some(n),
+            _ => None,
+        }
+    }
+    pub(crate) fn as_num(&self) -> Option<f64> {
+        match self {
+            Obj::Num(n) => Some(*n),
+            _ => None,
+        }
+    }
+    pub(crate) fn as_array(&self) -> Option<&[Obj]> {
+        match self {
+            Obj::Array(a) => Some(a),
+            _ => None,
+        }
+    }
+}
+
+pub(crate) fn is_white(b: u8) -> bool {
+    matches!(b, b' ' | b'\t' | b'\r' | b'\n' | b'\x0c' | b'\0')
+}
+
+pub(crate) fn is_delim(b: u8) -> bool {
+    matches!(b, b'(' | b')' | b'<' | b'>' | b'[' | b']' | b'{' | b'}' | b'/' | b'%')
+}
+
+/// Tokenizer/parser over a byte slice; shared by file objects and content streams.
+pub(crate) struct Lexer<'a> {
+    pub(crate) buf: &'a [u8],
+    pub(crate) pos: usize,
+    depth: usize,
+}
+
+impl<'a> Lexer<'a> {
+    pub(crate) fn new(buf: &'a [u8], pos: usize) -> Self {
+        Self { buf, pos, depth: 0 }
+    }
+
+    pub(crate) fn skip_ws(&mut self) {
+        while self.pos < self.buf.len() {
+            let b = self.buf[self.pos];
+            if is_white(b) {
+                self.pos += 1;
+            } else if b == b'%' {
+                while self.pos < self.buf.len() && !matches!(self.buf[self.pos], b'\r' | b'\n') {
+                    self.pos += 1;
+                }
+            } else {
+                break;
+            }
+        }
+    }
+
+    fn peek(&self) -> Option<u8> {
+        self.buf.get(self.pos).copied()
+    }
+
+    fn keyword(&mut self) -> String {
+        let start = self.pos;
+        while self.pos < self.buf.len() && !is_white(self.buf[self.pos]) && !is_delim(self.buf[self.pos]) {
+            self.pos += 1;
+        }
+        String::from_utf8_lossy(&self.buf[start..self.pos]).into_owned()
+    }
+
+    /// Parses one object. `N G R` references are folded into `Obj::Ref`.
+    ///
+    /// Past `MAX_NESTING_DEPTH` the rest of the buffer is skipped so the caller's loop ends.
+    pub(crate) fn parse(&mut self) -> Option<Obj> {
+        if self.depth >= MAX_NESTING_DEPTH {
+            self.pos = self.buf.len();
+            return None;
+        }
+        self.depth += 1;
+        let obj = self.parse_one();
+        self.depth -= 1;
+        obj
+    }
+
+    fn parse_one(&mut self) -> Option<Obj> {
+        self.skip_ws();
+        let b = self.peek()?;
+        match b {
+            b'/' => {
+                self.pos += 1;
+                Some(Obj::Name(self.name()))
+            }
+            b'(' => {
+                self.pos += 1;
+                Some(Obj::Str(self.literal()))
+            }
+            b'<' if self.buf.get(self.pos + 1) == Some(&b'<') => {
+                self.pos += 2;
+                Some(Obj::Dict(self.dict()))
+            }
+            b'<' => {
+                self.pos += 1;
+                Some(Obj::Str(self.hex()))
+            }
+            b'[' => {
+                self.pos += 1;
+                let mut items = Vec::new();
+                loop {
+                    self.skip_ws();
+                    match self.peek() {
+                        Some(b']') => {
+                            self.pos += 1;
+                            break;
+                        }
+                        None => break,
+                        _ => match self.parse() {
+                            Some(o) => items.push(o),
+                            None => break,
+                        },
+                    }
+                }
+                Some(Obj::Array(items))
+            }
+            b']' | b'>' | b')' | b'{' | b'}' => {
+                self.pos += 1;
+                Some(Obj::Op((b as char).to_string()))
+            }
+            b'+' | b'-' | b'.' | b'0'..=b'9' => {
+                let n = self.number()?;
+                if n.fract() == 0.0 && n >= 0.0 {
+                    let save = self.pos;
+                    if self.lookahead_int().is_some() {
+                        self.skip_ws();
+                        if self.peek() == Some(b'R') && self.buf.get(self.pos + 1).is_none_or(|c| is_white(*c) || is_delim(*c)) {
+                            self.pos += 1;
+                            return Some(Obj::Ref(n as u32));
+                        }
+                    }
+                    self.pos = save;
+                }
+                Some(Obj::Num(n))
+            }
+            _ => {
+                let kw = self.keyword();
+                if kw.is_empty() {
+                    self.pos += 1;
+                    return Some(Obj::Op(String::new()));
+                }
+                Some(match kw.as_str() {
+                    "true" => Obj::Bool(true),
+                    "false" => Obj::Bool(false),
+                    "null" => Obj::Null,
+                    _ => Obj::Op(kw),
+                })
+            }
+        }
+    }
+
+    fn lookahead_int(&mut self) -> Option<u32> {
+        self.skip_ws();
+        let start = self.pos;
+        while self.pos < self.buf.len() && self.buf[self.pos].is_ascii_digit() {
+            self.pos += 1;
+        }
+        if self.pos == start || self.buf.get(self.pos).is_some_and(|c| !is_white(*c) && !is_delim(*c)) {
+            return None;
+        }
+        std::str::from_utf8(&self.buf[start..self.pos]).ok()?.parse().ok()
+    }
+
+    fn number(&mut self) -> Option<f64> {
+        let start = self.pos;
+        self.pos += 1;
+        while self.pos < self.buf.len() && matches!(self.buf[self.pos], b'0'..=b'9' | b'.' | b'-') {
+            self.pos += 1;
+        }
+        let s = std::str::from_utf8(&self.buf[start..self.pos]).ok()?;
+        Some(s.parse::<f64>().unwrap_or(0.0))
+    }
+
+    fn name(&mut self) -> String {
+        let start = self.pos;
+        while self.pos < self.buf.len() && !is_white(self.buf[self.pos]) && !is_delim(self.buf[self.pos]) {
+            self.pos += 1;
+        }
+        let raw = &self.buf[start..self.pos];
+        let mut out = Vec::with_capacity(raw.len());
+        let mut i = 0;
+        while i < raw.len() {
+            if raw[i] == b'#' {
+                if let Some(v) = raw.get(i + 1..i + 3).and_then(|h| u8::from_str_radix(std::str::from_utf8(h).ok()?, 16).ok()) {
+                    out.push(v);
+                    i += 3;
+                    continue;
+                }
+            }
+            out.push(raw[i]);
+            i += 1;
+        }
+        String::from_utf8_lossy(&out).into_owned()
+    }
+
+    fn literal(&mut self) -> Vec<u8> {
+        let mut out = Vec::new();
+        let mut depth = 1usize;
+        while let Some(b) = self.peek() {
+            self.pos += 1;
+            match b {
+                b'\\' => {
+                    let Some(e) = self.peek() else { break };
+                    self.pos += 1;
+                    match e {
+                        b'n' => out.push(b'\n'),
+                        b'r' => out.push(b'\r'),
+                        b't' => out.push(b'\t'),
+                        b'b' => out.push(0x08),
+                        b'f' => out.push(0x0c),
+                        b'\r' => {
+                            if self.peek() == Some(b'\n') {
+                                self.pos += 1;
+                            }
+                        }
+                        b'\n' => {}
+                        b'0'..=b'7' => {
+                            let mut v = (e - b'0') as u32;
+                            for _ in 0..2 {
+                                match self.peek() {
+                                    Some(d @ b'0'..=b'7') => {
+                                        v = v * 8 + (d - b'0') as u32;
+                                        self.pos += 1;
+                                    }
+                                    _ => break,
+                                }
+                            }
+                            out.push(v as u8);
+                        }
+                        other => out.push(other),
+                    }
+                }
+                b'(' => {
+                    depth += 1;
+                    out.push(b);
+                }
+                b')' => {
+                    depth -= 1;
+                    if depth == 0 {
+                        break;
+                    }
+                    out.push(b);
+                }
+                _ => out.push(b),
+            }
+        }
+        out
+    }
+
+    fn hex(&mut self) -> Vec<u8> {
+        let mut nibbles = Vec::new();
+        while let Some(b) = self.peek() {
+            self.pos += 1;
+            if b == b'>' {
+                break;
+            }
+            if let Some(v) = (b as char).to_digit(16) {
+                nibbles.push(v as u8);
+            }
+        }
+        if nibbles.len() % 2 == 1 {
+            nibbles.push(0);
+        }
+        nibbles.chunks(2).map(|c| c[0] << 4 | c[1]).collect()
+    }
+
+    fn dict(&mut self) -> Dict {
+        let mut d = Dict::new();
+        loop {
+            self.skip_ws();
+            match self.peek() {
+                Some(b'>') => {
+                    self.pos += if self.buf.get(self.pos + 1) == Some(&b'>') { 2 } else { 1 };
+                    break;
+                }
+                None => break,
+                _ => {}
+            }
+            let Some(Obj::Name(key)) = self.parse() else { continue };
+            if let Some(v) = self.parse() {
+                d.insert(key, v);
+            }
+        }
+        d
+    }
+}
+
+/// Every indirect object in the file, keyed by object number.
+pub(crate) struct PdfFile {
+    pub(crate) objects: BTreeMap<u32, Obj>,
+}
+
+impl PdfFile {
+    pub(crate) fn parse(raw: &[u8]) -> Result<Self, IrNormalizationError> {
+        let mut objects = BTreeMap::new();
+        let mut i = 0;
+        while let Some(off) = find(&raw[i..], b"obj") {
+            let at = i + off;
+            i = at + 3;
+            if raw.get(at + 3).is_some_and(|c| !is_white(*c) && !is_delim(*c)) {
+                continue;
+            }
+            let Some(num) = object_header(raw, at) else { continue };
+            let mut lx = Lexer::new(raw, at + 3);
+            let Some(obj) = lx.parse() else { continue };
+            let obj = match obj {
+                Obj::Dict(d) => {
+                    let mark = lx.pos;
+                    lx.skip_ws();
+                    if raw[lx.pos..].starts_with(b"stream") {
+                        let (data, end) = stream_data(raw, lx.pos + 6, &d);
+                        lx.pos = end;
+                        Obj::Stream(d, data)
+                    } else {
+                        lx.pos = mark;
+                        Obj::Dict(d)
+                    }
+                }
+                other => other,
+            };
+            i = lx.pos.max(i);
+            objects.insert(num, obj);
+        }
+        let mut file = Self { objects };
+        file.expand_object_streams()?;
+        Ok(file)
+    }
+
+    fn expand_object_streams(&mut self) -> Result<(), IrNormalizationError> {
+        let streams: Vec<(Dict, Vec<u8>)> = self
+            .objects
+            .values()
+            .filter_map(|o| match o {
+                Obj::Stream(d, data) if d.get("Type").and_then(Obj::as_name) == Some("ObjStm") => Some((d.clone(), data.clone())),
+                _ => None,
+            })
+            .collect();
+        for (d, data) in streams {
+            let Some(data) = decode_or_skip(&d, &data)? else { continue };
+            let n = d.get("N").and_then(Obj::as_num).unwrap_or(0.0) as usize;
+            let first = d.get("First").and_then(Obj::as_num).unwrap_or(0.0) as usize;
+            let mut header = Lexer::new(&data, 0);
+            for _ in 0..n {
+                let (Some(Obj::Num(num)), Some(Obj::Num(off))) = (header.parse(), header.parse()) else { break };
+                let mut lx = Lexer::new(&data, first + off as usize);
+                if let Some(obj) = lx.parse() {
+                    self.objects.entry(num as u32).or_insert(obj);
+                }
+            }
+        }
+        Ok(())
+    }
+
+    pub(crate) fn resolve<'s>(&'s self, o: &'s Obj) -> &'s Obj {
+        let mut cur = o;
+        for _ in 0..16 {
+            match cur {
+                Obj::Ref(n) => match self.objects.get(n) {
+                    Some(next) => cur = next,
+                    None => return &Obj::Null,
+                },
+                _ => return cur,
+            }
+        }
+        &Obj::Null
+    }
+
+    pub(crate) fn get<'s>(&'s self, d: &'s Dict, key: &str) -> Option<&'s Obj> {
+        d.get(key).map(|o| self.resolve(o)).filter(|o| !matches!(o, Obj::Null))
+    }
+
+    pub(crate) fn dict<'s>(&'s self, d: &'s Dict, key: &str) -> Option<&'s Dict> {
+        self.get(d, key).and_then(Obj::as_dict)
+    }
+}
+
+fn find(hay: &[u8], needle: &[u8]) -> Option<usize> {
+    hay.windows(needle.len()).position(|w| w == needle)
+}
+
+/// Parses `N G` immediately before the `obj` keyword at `at`.
+fn object_header(raw: &[u8], at: usize) -> Option<u32> {
+    let mut p = at;
+    let mut ints = Vec::with_capacity(2);
+    for _ in 0..2 {
+        while p > 0 && is_white(raw[p - 1]) {
+            p -= 1;
+        }
+        let end = p;
+        while p > 0 && raw[p - 1].is_ascii_digit() {
+            p -= 1;
+        }
+        if p == end {
+            return None;
+        }
+        ints.push(std::str::from_utf8(&raw[p..end]).ok()?.parse::<u32>().ok()?);
+    }
+    if p > 0 && !is_white(raw[p - 1]) && !is_delim(raw[p - 1]) {
+        return None;
+    }
+    Some(ints[1])
+}
+
+/// Returns the raw stream bytes and the position after `endstream`.
+fn stream_data(raw: &[u8], mut start: usize, d: &Dict) -> (Vec<u8>, usize) {
+    if raw.get(start) == Some(&b'\r') {
+        start += 1;
+    }
+    if raw.get(start) == Some(&b'\n') {
+        start += 1;
+    }
+    let by_length = d.get("Length").and_then(Obj::as_num).and_then(|l| start.checked_add(l as usize)).filter(|end| {
+        if *end > raw.len() {
+            return false;
+        }
+        let mut lx = Lexer::new(raw, *end);
+        lx.skip_ws();
+        raw[lx.pos..].starts_with(b"endstream")
+    });
+    let end = by_length.or_else(|| find(&raw[start..], b"endstream").map(|o| start + o)).unwrap_or(raw.len());
+    let mut data_end = end;
+    if by_length.is_none() {
+        while data_end > start && matches!(raw[data_end - 1], b'\r' | b'\n') {
+            data_end -= 1;
+        }
+    }
+    let after = find(&raw[end..], b"endstream").map(|o| end + o + 9).unwrap_or(raw.len());
+    (raw[start..data_end].to_vec(), after)
+}
+
+/// Applies the stream's filter chain. Unsupported filters (images etc.) are `MalformedPdf`;
+/// output beyond `MAX_DECODED_STREAM_BYTES` is `OversizedArtifact`.
+fn decode_stream(d: &Dict, data: &[u8]) -> Result<Vec<u8>, IrNormalizationError> {
+    let malformed = |m: String| IrNormalizationError::new(IrNormalizationReasonCode::MalformedPdf, m);
+    let filters: Vec<String> = match d.get("Filter") {
+        Some(Obj::Name(n)) => vec![n.clone()],
+        Some(Obj::Array(a)) => a.iter().filter_map(|o| o.as_name().map(str::to_string)).collect(),
+        _ => vec![],
+    };
+    let mut out = data.to_vec();
+    for f in filters {
+        out = match f.as_str() {
+            "FlateDecode" | "Fl" => {
+                let mut buf = Vec::new();
+                let mut z = ZlibDecoder::new(out.as_slice()).take(MAX_DECODED_STREAM_BYTES + 1);
+                if let Err(e) = z.read_to_end(&mut buf) {
+                    if buf.is_empty() {
+                        return Err(malformed(format!("FlateDecode: {e}")));
+                    }
+                }
+                if buf.len() as u64 > MAX_DECODED_STREAM_BYTES {
+                    let mut err = IrNormalizationError::new(IrNormalizationReasonCode::OversizedArtifact, "FlateDecode output exceeds the stream limit");
+                    err.metadata.insert("max_bytes".into(), MAX_DECODED_STREAM_BYTES.to_string());
+                    return Err(err);
+                }
+                buf
+            }
+            "ASCIIHexDecode" | "AHx" => Lexer::new(&[b"<".as_slice(), &out].concat(), 1).hex(),
+            "ASCII85Decode" | "A85" => ascii85(&out).map_err(malformed)?,
+            other => return Err(malformed(format!("unsupported filter {other}"))),
+        };
+    }
+    Ok(out)
+}
+
+/// Decoded stream bytes, or `None` for a stream that cannot be decoded and is skipped.
+/// Only the size cap is an error: it fails the whole document.
+pub(crate) fn decode_or_skip(d: &Dict, data: &[u8]) -> Result<Option<Vec<u8>>, IrNormalizationError> {
+    match decode_stream(d, data) {
+        Ok(bytes) => Ok(Some(bytes)),
+        Err(e) if e.reason == IrNormalizationReasonCode::OversizedArtifact => Err(e),
+        Err(_) => Ok(None),
+    }
+}
+
+fn ascii85(data: &[u8]) -> Result<Vec<u8>, String> {
+    let mut out = Vec::new();
+    let mut group = Vec::with_capacity(5);
+    for &b in data {
+        match b {
+            b'~' => break,
+            b'z' if group.is_empty() => out.extend_from_slice(&[0; 4]),
+            b'!'..=b'u' => {
+                group.push(b - b'!');
+                if group.len() == 5 {
+                    let v = group.iter().fold(0u64, |acc, d| acc * 85 + *d as u64);
+                    out.extend_from_slice(&(v as u32).to_be_bytes());
+                    group.clear();
+                }
+            }
+            _ if is_white(b) => {}
+            _ => return Err("ASCII85Decode: invalid byte".into()),
+        }
+    }
+    if !group.is_empty() {
+        let n = group.len();
+        group.resize(5, 84);
+        let v = group.iter().fold(0u64, |acc, d| acc * 85 + *d as u64);
+        out.extend_from_slice(&(v as u32).to_be_bytes()[..n - 1]);
+    }
+    Ok(out)
+}
@@ -0,0 +1,390 @@
+//! HTML `<table>` extraction with colspan/rowspan expansion and multi-row headers.
+
+use super::{detect_unit, flatten_headers, is_footnote_line, parse_number};
+use std::collections::BTreeMap;
+use ucel_core::{IrNormalizationProvenance, IrNormalizedTable};
+
+const MAX_SPAN: usize = 64;
+/// How much text before a table is searched for a unit note / caption.
+const PRECEDING_TEXT_CHARS: usize = 200;
+
+#[derive(Debug, Default)]
+struct Cell {
+    text: String,
+    header: bool,
+    colspan: usize,
+    rowspan: usize,
+    /// Inside `<sup>`: footnote reference, kept out of the numeric value.
+    markers: String,
+}
+
+#[derive(Debug, Default)]
+struct Row {
+    cells: Vec<Cell>,
+    in_thead: bool,
+    in_tfoot: bool,
+}
+
+#[derive(Debug, Default)]
+struct RawTable {
+    caption: Option<String>,
+    preceding: String,
+    rows: Vec<Row>,
+    footnotes: Vec<String>,
+}
+
+#[derive(Debug, Default)]
+struct Builder {
+    table: RawTable,
+    caption: Option<String>,
+    cell: Option<Cell>,
+    in_thead: bool,
+    in_tfoot: bool,
+    in_sup: bool,
+}
+
+fn decode_entities(s: &str) -> String {
+    let mut out = String::with_capacity(s.len());
+    let mut rest = s;
+    while let Some(amp) = rest.find('&') {
+        out.push_str(&rest[..amp]);
+        rest = &rest[amp..];
+        let Some(semi) = rest[..rest.len().min(10)].find(';') else {
+            out.push('&');
+            rest = &rest[1..];
+            continue;
+        };
+        let ent = &rest[1..semi];
+        let decoded = match ent {
+            "amp" => Some('&'),
+            "lt" => Some('<'),
+            "gt" => Some('>'),
+            "quot" => Some('"'),
+            "apos" => Some('\''),
+            "nbsp" | "ensp" | "emsp" | "thinsp" => Some(' '),
+            "minus" => Some('-'),
+            "mdash" => Some('—'),
+            "ndash" => Some('–'),
+            "yen" => Some('¥'),
+            _ => ent
+                .strip_prefix("#x")
+                .or_else(|| ent.strip_prefix("#X"))
+                .and_then(|h| u32::from_str_radix(h, 16).ok())
+                .or_else(|| ent.strip_prefix('#').and_then(|d| d.parse().ok()))
+                .and_then(char::from_u32),
+        };
+        match decoded {
+            Some(c) => {
+                out.push(c);
+                rest = &rest[semi + 1..];
+            }
+            None => {
+                out.push('&');
+                rest = &rest[1..];
+            }
+        }
+    }
+    out.push_str(rest);
+    out
+}
+
+fn collapse(s: &str) -> String {
+    s.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+fn span_attr(attrs: &str, name: &str) -> usize {
+    let lower = attrs.to_ascii_lowercase();
+    let Some(at) = lower.find(name) else { return 1 };
+    let v: String = lower[at + name.len()..].trim_start().trim_start_matches('=').trim_start().trim_start_matches(['"', '\'']).chars().take_while(|c| c.is_ascii_digit()).collect();
+    v.parse::<usize>().unwrap_or(1).clamp(1, MAX_SPAN)
+}
+
+/// Extracts every `<table>` in document order (nested tables become separate tables).
+pub fn html_tables(raw: &str) -> Vec<IrNormalizedTable> {
+    let mut done: Vec<RawTable> = Vec::new();
+    let mut stack: Vec<Builder> = Vec::new();
+    let mut text_since_table = String::new();
+    // index into `done` of the table whose trailing notes are still being collected
+    let mut notes_for: Option<usize> = None;
+    let mut block = String::new();
+    let mut skip_until: Option<&'static str> = None;
+
+    let flush_block = |block: &mut String, notes_for: &mut Option<usize>, done: &mut Vec<RawTable>| {
+        let line = collapse(&decode_entities(block));
+        block.clear();
+        if line.is_empty() {
+            return;
+        }
+        match notes_for {
+            Some(i) if is_footnote_line(&line) => done[*i].footnotes.push(line),
+            _ => *notes_for = None,
+        }
+    };
+
+    let mut rest = raw;
+    while !rest.is_empty() {
+        let Some(lt) = rest.find('<') else {
+            if stack.is_empty() {
+                block.push_str(rest);
+                text_since_table.push_str(rest);
+            }
+            break;
+        };
+        let text = &rest[..lt];
+        rest = &rest[lt..];
+        let Some(gt) = rest.find('>') else { break };
+        let tag = &rest[1..gt];
+        rest = &rest[gt + 1..];
+
+        if let Some(end) = skip_until {
+            if tag.trim().eq_ignore_ascii_case(end) {
+                skip_until = None;
+            }
+            continue;
+        }
+        match stack.last_mut() {
+            Some(b) => {
+                if let Some(cell) = b.cell.as_mut() {
+                    if b.in_sup {
+                        cell.markers.push_str(text);
+                    } else {
+                        cell.text.push_str(text);
+                    }
+                } else if let Some(c) = b.caption.as_mut() {
+                    c.push_str(text);
+                }
+            }
+            None => {
+                block.push_str(text);
+                text_since_table.push_str(text);
+            }
+        }
+        if tag.starts_with('!') || tag.starts_with('?') {
+            continue;
+        }
+        let closing = tag.starts_with('/');
+        let body = tag.trim_start_matches('/');
+        let name_end = body.find(|c: char| c.is_whitespace() || c == '/').unwrap_or(body.len());
+        let name = body[..name_end].to_ascii_lowercase();
+        let attrs = &body[name_end..];
+
+        match (name.as_str(), closing) {
+            ("script", false) => skip_until = Some("/script"),
+            ("style", false) => skip_until = Some("/style"),
+            ("table", false) => {
+                flush_block(&mut block, &mut notes_for, &mut done);
+                notes_for = None;
+                let preceding: String = text_since_table.chars().rev().take(PRECEDING_TEXT_CHARS).collect::<Vec<_>>().into_iter().rev().collect();
+                let preceding = decode_entities(&preceding).lines().map(collapse).filter(|l| !l.is_empty()).collect::<Vec<_>>().join("\n");
+                stack.push(Builder { table: RawTable { preceding, ..Default::default() }, ..Default::default() });
+            }
+            ("table", true) => {
+                if let Some(mut b) = stack.pop() {
+                    close_cell(&mut b);
+                    done.push(b.table);
+                    if stack.is_empty() {
+                        notes_for = Some(done.len() - 1);
+                        text_since_table.clear();
+                    }
+                }
+            }
+            _ => {
+                let Some(b) = stack.last_mut() else {
+                    if matches!(name.as_str(), "p" | "div" | "br" | "li" | "h1" | "h2" | "h3" | "h4" | "h5" | "h6" | "tr") {
+                        flush_block(&mut block, &mut notes_for, &mut done);
+                        text_since_table.push('\n');
+                    }
+                    continue;
+                };
+                match (name.as_str(), closing) {
+                    ("caption", false) => b.caption = Some(String::new()),
+                    ("caption", true) => b.table.caption = b.caption.take().map(|c| collapse(&decode_entities(&c))).filter(|c| !c.is_empty()),
+                    ("thead", closing) => b.in_thead = !closing,
+                    ("tfoot", closing) => b.in_tfoot = !closing,
+                    ("tr", false) => {
+                        close_cell(b);
+                        b.table.rows.push(Row { cells: vec![], in_thead: b.in_thead, in_tfoot: b.in_tfoot });
+                    }
+                    ("tr", true) => close_cell(b),
+                    ("td" | "th", false) => {
+                        close_cell(b);
+                        if b.table.rows.is_empty() {
+                            b.table.rows.push(Row { cells: vec![], in_thead: b.in_thead, in_tfoot: b.in_tfoot });
+                        }
+                        b.cell = Some(Cell { header: name == "th", colspan: span_attr(attrs, "colspan"), rowspan: span_attr(attrs, "rowspan"), ..Default::default() });
+                    }
+                    ("td" | "th", true) => close_cell(b),
+                    ("sup", closing) => b.in_sup = !closing,
+                    ("br" | "p" | "div", _) => {
+                        if let Some(c) = b.cell.as_mut() {
+                            c.text.push(' ');
+                        }
+                    }
+                    _ => {}
+                }
+            }
+        }
+    }
+    flush_block(&mut block, &mut notes_for, &mut done);
+    while let Some(mut b) = stack.pop() {
+        close_cell(&mut b);
+        done.push(b.table);
+    }
+    done.into_iter().enumerate().filter_map(|(i, t)| to_table(i, t)).collect()
+}
+
+fn close_cell(b: &mut Builder) {
+    if let Some(mut cell) = b.cell.take() {
+        cell.text = collapse(&decode_entities(&cell.text));
+        cell.markers = collapse(&decode_entities(&cell.markers));
+        if let Some(row) = b.table.rows.last_mut() {
+            row.cells.push(cell);
+        }
+    }
+    b.in_sup = false;
+}
+
+struct GridCell {
+    text: String,
+    markers: String,
+    header: bool,
+}
+
+/// Lays cells out on a grid: rowspans repeat the value downwards, colspans repeat it in
+/// header rows and leave the covered body columns empty.
+fn grid(rows: &[Row]) -> Vec<Vec<Option<GridCell>>> {
+    let mut out: Vec<Vec<Option<GridCell>>> = Vec::new();
+    let mut carried: BTreeMap<(usize, usize), (String, String, bool)> = BTreeMap::new();
+    for (r, row) in rows.iter().enumerate() {
+        let mut line: Vec<Option<GridCell>> = Vec::new();
+        let mut col = 0;
+        let mut cells = row.cells.iter();
+        loop {
+            if let Some((text, markers, header)) = carried.remove(&(r, col)) {
+                set(&mut line, col, GridCell { text, markers, header });
+                col += 1;
+                continue;
+            }
+            let Some(cell) = cells.next() else {
+                if carried.keys().any(|(cr, cc)| *cr == r && *cc > col) {
+                    col += 1;
+                    continue;
+                }
+                break;
+            };
+            let header_like = cell.header || row.in_thead;
+            for dc in 0..cell.colspan {
+                let text = if dc == 0 || header_like { cell.text.clone() } else { String::new() };
+                let markers = if dc == 0 { cell.markers.clone() } else { String::new() };
+                for dr in 1..cell.rowspan {
+                    carried.insert((r + dr, col + dc), (text.clone(), markers.clone(), cell.header));
+                }
+                set(&mut line, col + dc, GridCell { text, markers, header: cell.header });
+            }
+            col += cell.colspan;
+        }
+        out.push(line);
+    }
+    out
+}
+
+fn set(line: &mut Vec<Option<GridCell>>, col: usize, cell: GridCell) {
+    if line.len() <= col {
+        line.resize_with(col + 1, || None);
+    }
+    line[col] = Some(cell);
+}
+
+fn to_table(index: usize, t: RawTable) -> Option<IrNormalizedTable> {
+    let grid = grid(&t.rows);
+    let width = grid.iter().map(Vec::len).max().unwrap_or(0);
+    if width == 0 {
+        return None;
+    }
+    let text_at = |r: usize, c: usize| grid[r].get(c).and_then(|g| g.as_ref()).map(|g| g.text.clone()).unwrap_or_default();
+
+    let mut extra = BTreeMap::new();
+    let mut unit = None;
+    let mut footnotes = t.footnotes;
+    let mut body_start = 0;
+    // leading single-text rows such as "(単位：百万円)" are unit notes, not data
+    while body_start < grid.len() {
+        let texts: Vec<String> = (0..width).map(|c| text_at(body_start, c)).filter(|s| !s.is_empty()).collect();
+        let distinct = texts.iter().all(|s| *s == texts[0]);
+        match texts.first().filter(|_| distinct).and_then(|s| detect_unit(s)) {
+            Some(u) => {
+                unit = Some(u);
+                extra.insert("unit_source".to_string(), "unit_row".to_string());
+                body_start += 1;
+            }
+            None => break,
+        }
+    }
+    if unit.is_none() {
+        if let Some(u) = t.caption.as_deref().and_then(detect_unit) {
+            unit = Some(u);
+            extra.insert("unit_source".into(), "caption".into());
+        } else if let Some(u) = t.preceding.lines().rev().take(3).find_map(detect_unit) {
+            unit = Some(u);
+            extra.insert("unit_source".into(), "preceding_text".into());
+        }
+    }
+
+    let mut header_end = body_start;
+    while header_end < grid.len() && (t.rows[header_end].in_thead || (grid[header_end].iter().flatten().all(|g| g.header) && grid[header_end].iter().any(Option::is_some))) {
+        header_end += 1;
+    }
+    if header_end == body_start && grid.len() > body_start + 1 {
+        // no <th>/<thead>: treat the first row as header when it has no numbers
+        let first_numeric = (1..width).any(|c| parse_number(&text_at(body_start, c)).is_some());
+        if !first_numeric {
+            header_end = body_start + 1;
+        }
+    }
+    let header_rows: Vec<Vec<String>> = (body_start..header_end).map(|r| (0..width).map(|c| text_at(r, c)).collect()).collect();
+    let headers = flatten_headers(&header_rows, width);
+
+    let mut rows = Vec::new();
+    let mut numeric = Vec::new();
+    for (r, line) in grid.iter().enumerate().skip(header_end) {
+        if t.rows[r].in_tfoot {
+            let line = collapse(&(0..width).map(|c| text_at(r, c)).collect::<Vec<_>>().join(" "));
+            if !line.is_empty() {
+                footnotes.push(line);
+            }
+            continue;
+        }
+        let cells: Vec<String> = (0..width).map(|c| text_at(r, c)).collect();
+        if cells.iter().all(String::is_empty) {
+            continue;
+        }
+        numeric.push(cells.iter().map(|c| parse_number(c)).collect());
+        let with_markers = (0..width)
+            .map(|c| match line.get(c).and_then(|g| g.as_ref()) {
+                Some(g) if !g.markers.is_empty() => format!("{}{}", g.text, g.markers),
+                Some(g) => g.text.clone(),
+                None => String::new(),
+            })
+            .collect();
+        rows.push(with_markers);
+    }
+    if rows.is_empty() && headers.iter().all(String::is_empty) {
+        return None;
+    }
+    extra.insert("header_rows".into(), header_rows.len().to_string());
+    extra.insert("columns".into(), width.to_string());
+    let (unit, unit_scale) = match unit {
+        Some((u, s)) => (u, Some(s)),
+        None => (None, None),
+    };
+    Some(IrNormalizedTable {
+        caption: t.caption,
+        headers,
+        rows,
+        numeric_rows: numeric,
+        unit,
+        unit_scale,
+        footnotes,
+        provenance: IrNormalizationProvenance { source_type: Some("html_table".into()), source_ref: Some(format!("table:{}", index + 1)), context_ref: None, extra },
+    })
+}
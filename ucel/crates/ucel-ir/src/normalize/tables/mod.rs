@@ -0,0 +1,180 @@
+//! Table extraction: CSV, HTML `<table>` grids and column-aligned PDF text.
+
+pub mod html;
+pub mod pdf;
+
+use super::xbrl::canonical_decimal;
+use ucel_core::{IrNormalizationProvenance, IrNormalizedTable};
+
+pub fn csv_to_table(text: &str) -> IrNormalizedTable {
+    let mut lines = text.lines();
+    let headers = lines.next().unwrap_or_default().split(',').map(|s| s.trim().to_string()).collect();
+    let rows: Vec<Vec<String>> = lines.map(|l| l.split(',').map(|s| s.trim().to_string()).collect()).collect();
+    IrNormalizedTable {
+        caption: Some("csv".into()),
+        headers,
+        numeric_rows: numeric_rows(&rows),
+        rows,
+        unit: None,
+        unit_scale: None,
+        footnotes: vec![],
+        provenance: IrNormalizationProvenance { source_type: Some("csv".into()), source_ref: None, context_ref: None, extra: Default::default() },
+    }
+}
+
+pub(crate) fn numeric_rows(rows: &[Vec<String>]) -> Vec<Vec<Option<String>>> {
+    rows.iter().map(|r| r.iter().map(|c| parse_number(c)).collect()).collect()
+}
+
+fn to_ascii(c: char) -> char {
+    match c {
+        '０'..='９' | 'Ａ'..='Ｚ' | 'ａ'..='ｚ' => char::from_u32(c as u32 - 0xFEE0).unwrap_or(c),
+        '，' => ',',
+        '．' => '.',
+        '（' => '(',
+        '）' => ')',
+        '％' => '%',
+        '＊' => '*',
+        '＋' => '+',
+        '－' | '−' | '‐' | '‒' => '-',
+        '\u{00A0}' | '\u{3000}' => ' ',
+        _ => c,
+    }
+}
+
+/// Removes footnote markers such as `※1`, `*2`, `(注3)` and `注1` from a cell.
+pub(crate) fn strip_footnote_markers(cell: &str) -> String {
+    let chars: Vec<char> = cell.chars().collect();
+    let mut out = String::with_capacity(cell.len());
+    let mut i = 0;
+    let skip_digits = |mut j: usize| {
+        while j < chars.len() && chars[j].is_ascii_digit() {
+            j += 1;
+        }
+        j
+    };
+    while i < chars.len() {
+        match chars[i] {
+            '※' | '*' | '†' => i = skip_digits(i + 1),
+            '(' if chars.get(i + 1) == Some(&'注') => {
+                let j = skip_digits(i + 2);
+                i = if chars.get(j) == Some(&')') { j + 1 } else { j };
+            }
+            '注' => i = skip_digits(i + 1),
+            c => {
+                out.push(c);
+                i += 1;
+            }
+        }
+    }
+    out
+}
+
+/// Canonical decimal for a financial table cell.
+///
+/// Accepts grouping commas, full-width digits, `△`/`▲`/`-` and `(1,234)` negatives,
+/// currency symbols and a trailing `%`. Dashes alone ("no amount") and text yield `None`.
+pub fn parse_number(cell: &str) -> Option<String> {
+    let ascii: String = cell.chars().map(to_ascii).collect();
+    let s = strip_footnote_markers(&ascii);
+    let mut s: String = s.chars().filter(|c| !c.is_whitespace()).collect();
+    let mut neg = false;
+    if let Some(rest) = s.strip_prefix(['△', '▲', '-']) {
+        neg = true;
+        s = rest.to_string();
+    }
+    if let Some(inner) = s.strip_prefix('(').and_then(|r| r.strip_suffix(')')) {
+        neg = !neg;
+        s = inner.to_string();
+    }
+    let s = s.trim_start_matches(['¥', '￥', '$', '€']).trim_end_matches(['%', '円']);
+    if !s.chars().next().is_some_and(|c| c.is_ascii_digit() || c == '.') || !s.chars().all(|c| c.is_ascii_digit() || c == ',' || c == '.') {
+        return None;
+    }
+    canonical_decimal(&s.replace(',', ""), 0, neg)
+}
+
+/// `(unit, scale)` from a caption or unit note, e.g. `（単位：百万円）` or `(in millions of USD)`.
+pub fn detect_unit(text: &str) -> Option<(Option<String>, i32)> {
+    let ascii: String = text.chars().map(to_ascii).collect();
+    if ascii.contains("単位") {
+        const JP: [(&str, &str, i32); 8] = [
+            ("十億円", "JPY", 9),
+            ("億円", "JPY", 8),
+            ("百万円", "JPY", 6),
+            ("千円", "JPY", 3),
+            ("円", "JPY", 0),
+            ("百万株", "shares", 6),
+            ("千株", "shares", 3),
+            ("株", "shares", 0),
+        ];
+        let unit_part = &ascii[ascii.find("単位").unwrap_or(0)..];
+        return JP.iter().find(|(k, _, _)| unit_part.contains(k)).map(|(_, u, s)| (Some(u.to_string()), *s));
+    }
+    let lower = ascii.to_ascii_lowercase();
+    let scale = [("in billions", 9), ("in millions", 6), ("in thousands", 3)].iter().find(|(k, _)| lower.contains(k)).map(|(_, s)| *s)?;
+    let unit = if lower.contains("usd") || lower.contains('$') || lower.contains("dollar") {
+        Some("USD")
+    } else if lower.contains("jpy") || lower.contains('¥') || lower.contains("yen") {
+        Some("JPY")
+    } else if lower.contains("eur") || lower.contains('€') {
+        Some("EUR")
+    } else if lower.contains("shares") {
+        Some("shares")
+    } else {
+        None
+    };
+    Some((unit.map(str::to_string), scale))
+}
+
+/// Notes printed under a table: `※1 ...`, `注1 ...`, `(注) ...`, `*1 ...`, `(1) ...`.
+pub(crate) fn is_footnote_line(line: &str) -> bool {
+    let t: String = line.trim_start().chars().take(4).map(to_ascii).collect();
+    t.starts_with('※') || t.starts_with('注') || t.starts_with("(注") || t.starts_with('*') || t.starts_with('†') || {
+        let mut c = t.chars();
+        c.next() == Some('(') && c.next().is_some_and(|d| d.is_ascii_digit()) && c.next() == Some(')')
+    }
+}
+
+/// Flattens multi-row headers per column: distinct non-empty labels joined with ` / `.
+pub(crate) fn flatten_headers(header_rows: &[Vec<String>], width: usize) -> Vec<String> {
+    (0..width)
+        .map(|c| {
+            let mut parts: Vec<&str> = Vec::new();
+            for row in header_rows {
+                let t = row.get(c).map(|s| s.trim()).unwrap_or_default();
+                if !t.is_empty() && parts.last() != Some(&t) {
+                    parts.push(t);
+                }
+            }
+            parts.join(" / ")
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_number_handles_jp_and_us_negative_forms() {
+        assert_eq!(parse_number("△1,234").as_deref(), Some("-1234"));
+        assert_eq!(parse_number("▲ ５６").as_deref(), Some("-56"));
+        assert_eq!(parse_number("(1,250.50)").as_deref(), Some("-1250.5"));
+        assert_eq!(parse_number("$ 391,035").as_deref(), Some("391035"));
+        assert_eq!(parse_number("12,345※2").as_deref(), Some("12345"));
+        assert_eq!(parse_number("8.5%").as_deref(), Some("8.5"));
+        assert_eq!(parse_number("－"), None);
+        assert_eq!(parse_number("売上高"), None);
+        assert_eq!(parse_number("2025年3月期"), None);
+    }
+
+    #[test]
+    fn detect_unit_reads_jp_and_us_captions() {
+        assert_eq!(detect_unit("連結損益計算書（単位：百万円）"), Some((Some("JPY".into()), 6)));
+        assert_eq!(detect_unit("(単位：千株)"), Some((Some("shares".into()), 3)));
+        assert_eq!(detect_unit("(In millions, except per share amounts)"), Some((None, 6)));
+        assert_eq!(detect_unit("(in thousands of U.S. dollars)"), Some((Some("USD".into()), 3)));
+        assert_eq!(detect_unit("配当金 30円"), None);
+    }
+}
@@ -0,0 +1,149 @@
+//! Heuristic tables from PDF text layout: runs of lines whose spans form aligned columns
+//! with numeric cells. There are no ruling lines to rely on, so results are best effort.
+
+use super::{detect_unit, flatten_headers, is_footnote_line, parse_number};
+use crate::normalize::pdf::{line_text, PdfPageText, PdfTextLine, PdfTextSpan};
+use std::collections::BTreeMap;
+use ucel_core::{IrNormalizationProvenance, IrNormalizedTable};
+
+/// Horizontal gap (in font sizes) that separates two columns rather than two words.
+const COLUMN_GAP: f64 = 1.0;
+/// Vertical gap (in font sizes) that ends a table block.
+const MAX_ROW_GAP: f64 = 3.0;
+const MAX_HEADER_LINES: usize = 2;
+const MAX_CAPTION_LINES: usize = 3;
+
+#[derive(Debug, Clone)]
+struct Chunk {
+    x: f64,
+    end_x: f64,
+    text: String,
+}
+
+fn chunks(line: &PdfTextLine) -> Vec<Chunk> {
+    let mut groups: Vec<Vec<PdfTextSpan>> = Vec::new();
+    for span in &line.spans {
+        match groups.last_mut() {
+            Some(g) if span.x - g.iter().map(|s| s.end_x).fold(f64::MIN, f64::max) <= line.size * COLUMN_GAP => g.push(span.clone()),
+            _ => groups.push(vec![span.clone()]),
+        }
+    }
+    groups
+        .into_iter()
+        .map(|spans| {
+            let x = spans[0].x;
+            let end_x = spans.iter().map(|s| s.end_x).fold(f64::MIN, f64::max);
+            Chunk { x, end_x, text: line_text(&PdfTextLine { y: line.y, size: line.size, spans }) }
+        })
+        .filter(|c| !c.text.is_empty())
+        .collect()
+}
+
+fn is_data_row(chunks: &[Chunk]) -> bool {
+    chunks.len() >= 2 && chunks[1..].iter().any(|c| parse_number(&c.text).is_some())
+}
+
+fn close(a: &PdfTextLine, b: &PdfTextLine) -> bool {
+    (a.y - b.y).abs() <= a.size.max(b.size) * MAX_ROW_GAP
+}
+
+/// Column index for a chunk: the anchor it overlaps most, else the nearest edge
+/// (right edge for numbers, which are right-aligned).
+fn column_of(anchors: &[Chunk], c: &Chunk) -> usize {
+    let overlap = |a: &Chunk| (c.end_x.min(a.end_x) - c.x.max(a.x)).max(0.0);
+    if let Some((i, _)) = anchors.iter().enumerate().filter(|(_, a)| overlap(a) > 0.0).max_by(|(_, a), (_, b)| overlap(a).total_cmp(&overlap(b))) {
+        return i;
+    }
+    let numeric = parse_number(&c.text).is_some();
+    let dist = |a: &Chunk| if numeric { (a.end_x - c.end_x).abs() } else { (a.x - c.x).abs() };
+    anchors.iter().enumerate().min_by(|(_, a), (_, b)| dist(a).total_cmp(&dist(b))).map(|(i, _)| i).unwrap_or(0)
+}
+
+fn place(anchors: &[Chunk], chunks: &[Chunk]) -> Vec<String> {
+    let mut row = vec![String::new(); anchors.len()];
+    for c in chunks {
+        let cell = &mut row[column_of(anchors, c)];
+        if !cell.is_empty() {
+            cell.push(' ');
+        }
+        cell.push_str(&c.text);
+    }
+    row
+}
+
+pub fn pdf_tables(pages: &[PdfPageText]) -> Vec<IrNormalizedTable> {
+    let mut out = Vec::new();
+    for page in pages {
+        let lines: Vec<(&PdfTextLine, Vec<Chunk>)> = page.lines.iter().map(|l| (l, chunks(l))).collect();
+        let mut i = 0;
+        let mut on_page = 0;
+        while i < lines.len() {
+            if !is_data_row(&lines[i].1) {
+                i += 1;
+                continue;
+            }
+            let mut end = i + 1;
+            while end < lines.len() && is_data_row(&lines[end].1) && close(lines[end - 1].0, lines[end].0) {
+                end += 1;
+            }
+            if end - i < 2 {
+                i = end;
+                continue;
+            }
+            let mut start = i;
+            while start > 0 && i - start < MAX_HEADER_LINES && lines[start - 1].1.len() >= 2 && !is_data_row(&lines[start - 1].1) && close(lines[start - 1].0, lines[start].0) {
+                start -= 1;
+            }
+            on_page += 1;
+            out.push(build(page.page, on_page, &lines, start, i, end));
+            i = end;
+        }
+    }
+    out
+}
+
+fn build(page: usize, on_page: usize, lines: &[(&PdfTextLine, Vec<Chunk>)], start: usize, body: usize, end: usize) -> IrNormalizedTable {
+    let anchors = lines[body..end].iter().map(|(_, c)| c).max_by_key(|c| c.len()).cloned().unwrap_or_default();
+    let header_rows: Vec<Vec<String>> = lines[start..body].iter().map(|(_, c)| place(&anchors, c)).collect();
+    let rows: Vec<Vec<String>> = lines[body..end].iter().map(|(_, c)| place(&anchors, c)).collect();
+
+    // caption / unit note: the nearest short lines above the table
+    let mut above: Vec<String> = Vec::new();
+    let mut k = start;
+    while k > 0 && above.len() < MAX_CAPTION_LINES && close(lines[k - 1].0, lines[k].0) {
+        k -= 1;
+        above.push(lines[k].1.iter().map(|c| c.text.as_str()).collect::<Vec<_>>().join(" "));
+    }
+    let unit = above.iter().find_map(|l| detect_unit(l)).or_else(|| header_rows.iter().flatten().find_map(|h| detect_unit(h)));
+    let caption = above.iter().find(|l| detect_unit(l).is_none()).or(above.first()).cloned();
+
+    let mut footnotes = Vec::new();
+    let mut k = end;
+    while k < lines.len() && close(lines[k - 1].0, lines[k].0) {
+        let text = line_text(lines[k].0);
+        if !is_footnote_line(&text) {
+            break;
+        }
+        footnotes.push(text);
+        k += 1;
+    }
+
+    let mut extra = BTreeMap::new();
+    extra.insert("table_on_page".to_string(), on_page.to_string());
+    extra.insert("columns".to_string(), anchors.len().to_string());
+    extra.insert("heuristic".to_string(), "column_alignment".to_string());
+    let (unit, unit_scale) = match unit {
+        Some((u, s)) => (u, Some(s)),
+        None => (None, None),
+    };
+    IrNormalizedTable {
+        caption,
+        headers: flatten_headers(&header_rows, anchors.len()),
+        numeric_rows: super::numeric_rows(&rows),
+        rows,
+        unit,
+        unit_scale,
+        footnotes,
+        provenance: IrNormalizationProvenance { source_type: Some("pdf_table".into()), source_ref: Some(format!("page:{page}")), context_ref: None, extra },
+    }
+}
@@ -1 +1,431 @@
+use super::errors::{IrNormalizationError, IrNormalizationReasonCode};
+use roxmltree::{Document, Node, ParsingOptions};
+use std::collections::{BTreeMap, HashMap};
+use ucel_core::{IrFinancialConcept, IrFinancialStatementItem, IrNormalizationProvenance, IrXbrlFact, IrXbrlPeriod};
+
+const NS_XBRLI: &str = "http://www.xbrl.org/2003/instance";
+const NS_XBRLDI: &str = "http://xbrl.org/2006/xbrldi";
+const NS_XSI: &str = "http://www.w3.org/2001/XMLSchema-instance";
+const NS_IX: [&str; 2] = ["http://www.xbrl.org/2013/inlineXBRL", "http://www.xbrl.org/2008/inlineXBRL"];
+/// Largest `ix:nonFraction/@scale` magnitude accepted; real filings use -10..=12.
+const MAX_SCALE: i32 = 30;
+
 pub fn xbrl_to_text(raw: &str) -> String { super::xml::xml_to_text(raw) }
+
+/// Facts of an XBRL instance document (elements carrying `contextRef`).
+pub fn parse_xbrl_instance(raw: &str) -> Result<Vec<IrXbrlFact>, IrNormalizationError> {
+    let doc = parse(raw)?;
+    let ctx = Contexts::collect(&doc);
+    let mut out = Vec::new();
+    for node in doc.descendants().filter(|n| n.is_element() && n.has_attribute("contextRef")) {
+        let ns = node.tag_name().namespace();
+        let local = node.tag_name().name();
+        let concept = match ns.and_then(|u| node.lookup_prefix(u)) {
+            Some(p) if !p.is_empty() => format!("{p}:{local}"),
+            _ => local.to_string(),
+        };
+        let value = text_of(node, false);
+        let numeric_value = if node.has_attribute("unitRef") { canonical_decimal(value.trim(), 0, false) } else { None };
+        out.push(ctx.fact(FactParts {
+            concept,
+            namespace: ns.map(str::to_string),
+            local_name: local.to_string(),
+            node,
+            value: value.trim().to_string(),
+            numeric_value,
+            scale: None,
+            error: None,
+            source_type: "xbrl_fact",
+        }));
+    }
+    Ok(out)
+}
+
+/// Facts of an inline XBRL document (`ix:nonFraction` / `ix:nonNumeric`).
+pub fn parse_ixbrl(raw: &str) -> Result<Vec<IrXbrlFact>, IrNormalizationError> {
+    let xhtml = raw.replace("&nbsp;", "&#160;");
+    let doc = parse(&xhtml)?;
+    let ctx = Contexts::collect(&doc);
+    let continuations: HashMap<&str, Node> = doc
+        .descendants()
+        .filter(|n| is_ix(n, "continuation"))
+        .filter_map(|n| n.attribute("id").map(|id| (id, n)))
+        .collect();
+    let mut out = Vec::new();
+    for node in doc.descendants().filter(|n| is_ix(n, "nonFraction") || is_ix(n, "nonNumeric")) {
+        let Some(name) = node.attribute("name") else { continue };
+        let (prefix, local) = name.split_once(':').map(|(p, l)| (Some(p), l)).unwrap_or((None, name));
+        let namespace = node.lookup_namespace_uri(prefix).map(str::to_string);
+        let mut value = text_of(node, true);
+        let mut next = node.attribute("continuedAt");
+        while let Some(c) = next.and_then(|id| continuations.get(id)) {
+            value.push(' ');
+            value.push_str(&text_of(*c, true));
+            next = c.attribute("continuedAt");
+        }
+        let value = value.split_whitespace().collect::<Vec<_>>().join(" ");
+        let (scale, scale_error) = match node.attribute("scale").map(parse_scale) {
+            Some(Ok(s)) => (Some(s), None),
+            Some(Err(e)) => (None, Some(e)),
+            None => (None, None),
+        };
+        // the sign comes only from @sign; a displayed △ / - is presentation and is not applied
+        let numeric_value = if is_ix(&node, "nonFraction") && scale_error.is_none() {
+            ixt_number(&value, node.attribute("format")).and_then(|v| canonical_decimal(&v, scale.unwrap_or(0), node.attribute("sign") == Some("-")))
+        } else {
+            None
+        };
+        out.push(ctx.fact(FactParts {
+            concept: name.to_string(),
+            namespace,
+            local_name: local.to_string(),
+            node,
+            value,
+            numeric_value,
+            scale,
+            error: scale_error,
+            source_type: "ixbrl_fact",
+        }));
+    }
+    Ok(out)
+}
+
+/// Maps non-dimensional jppfs/jpcrp/us-gaap/dei facts onto the canonical statement model.
+///
+/// When several source concepts map to the same line and period, the one listed first in
+/// the mapping table wins (e.g. profit attributable to owners of parent over total profit).
+pub fn financial_items(facts: &[IrXbrlFact]) -> Vec<IrFinancialStatementItem> {
+    let mut best: BTreeMap<(IrFinancialConcept, String), (usize, &IrXbrlFact)> = BTreeMap::new();
+    for fact in facts.iter().filter(|f| !f.is_nil && f.dimensions.is_empty() && f.numeric_value.is_some()) {
+        let family = taxonomy_family(fact);
+        let Some((rank, (_, _, concept))) = FINANCIAL_CONCEPTS.iter().enumerate().find(|(_, (fam, local, _))| *fam == family && *local == fact.local_name) else { continue };
+        let key = (*concept, period_key(fact.period.as_ref()));
+        match best.get(&key) {
+            Some((r, _)) if *r <= rank => {}
+            _ => { best.insert(key, (rank, fact)); }
+        }
+    }
+    best.into_iter()
+        .map(|((concept, _), (_, f))| IrFinancialStatementItem {
+            concept,
+            value: f.numeric_value.clone().unwrap_or_default(),
+            unit: f.unit.clone(),
+            period: f.period.clone(),
+            source_concept: f.concept.clone(),
+            provenance: IrNormalizationProvenance { source_type: Some("xbrl_fact".into()), source_ref: Some(f.concept.clone()), context_ref: Some(f.context_ref.clone()), extra: Default::default() },
+        })
+        .collect()
+}
+
+const FINANCIAL_CONCEPTS: &[(&str, &str, IrFinancialConcept)] = &[
+    // jppfs (financial statements)
+    ("jppfs", "NetSales", IrFinancialConcept::Revenue),
+    ("jppfs", "OperatingRevenue1", IrFinancialConcept::Revenue),
+    ("jppfs", "Revenue", IrFinancialConcept::Revenue),
+    ("jppfs", "OperatingIncome", IrFinancialConcept::OperatingIncome),
+    ("jppfs", "OrdinaryIncome", IrFinancialConcept::OrdinaryIncome),
+    ("jppfs", "IncomeBeforeIncomeTaxes", IrFinancialConcept::IncomeBeforeTax),
+    ("jppfs", "ProfitLossAttributableToOwnersOfParent", IrFinancialConcept::NetIncome),
+    ("jppfs", "ProfitLoss", IrFinancialConcept::NetIncome),
+    ("jppfs", "NetIncome", IrFinancialConcept::NetIncome),
+    ("jppfs", "Assets", IrFinancialConcept::TotalAssets),
+    ("jppfs", "Liabilities", IrFinancialConcept::TotalLiabilities),
+    ("jppfs", "NetAssets", IrFinancialConcept::NetAssets),
+    ("jppfs", "CashAndCashEquivalents", IrFinancialConcept::CashAndEquivalents),
+    ("jppfs", "NetCashProvidedByUsedInOperatingActivities", IrFinancialConcept::OperatingCashFlow),
+    ("jppfs", "NetCashProvidedByUsedInInvestmentActivities", IrFinancialConcept::InvestingCashFlow),
+    ("jppfs", "NetCashProvidedByUsedInFinancingActivities", IrFinancialConcept::FinancingCashFlow),
+    // jpcrp (summary of business results)
+    ("jpcrp", "NetSalesSummaryOfBusinessResults", IrFinancialConcept::Revenue),
+    ("jpcrp", "RevenueIFRSSummaryOfBusinessResults", IrFinancialConcept::Revenue),
+    ("jpcrp", "OperatingRevenue1SummaryOfBusinessResults", IrFinancialConcept::Revenue),
+    ("jpcrp", "OrdinaryIncomeLossSummaryOfBusinessResults", IrFinancialConcept::OrdinaryIncome),
+    ("jpcrp", "ProfitLossAttributableToOwnersOfParentSummaryOfBusinessResults", IrFinancialConcept::NetIncome),
+    ("jpcrp", "NetIncomeLossSummaryOfBusinessResults", IrFinancialConcept::NetIncome),
+    ("jpcrp", "TotalAssetsSummaryOfBusinessResults", IrFinancialConcept::TotalAssets),
+    ("jpcrp", "NetAssetsSummaryOfBusinessResults", IrFinancialConcept::NetAssets),
+    ("jpcrp", "CashAndCashEquivalentsSummaryOfBusinessResults", IrFinancialConcept::CashAndEquivalents),
+    ("jpcrp", "NetCashProvidedByUsedInOperatingActivitiesSummaryOfBusinessResults", IrFinancialConcept::OperatingCashFlow),
+    ("jpcrp", "NetCashProvidedByUsedInInvestingActivitiesSummaryOfBusinessResults", IrFinancialConcept::InvestingCashFlow),
+    ("jpcrp", "NetCashProvidedByUsedInFinancingActivitiesSummaryOfBusinessResults", IrFinancialConcept::FinancingCashFlow),
+    ("jpcrp", "BasicEarningsLossPerShareSummaryOfBusinessResults", IrFinancialConcept::EpsBasic),
+    ("jpcrp", "DilutedEarningsPerShareSummaryOfBusinessResults", IrFinancialConcept::EpsDiluted),
+    ("jpcrp", "TotalNumberOfIssuedSharesSummaryOfBusinessResults", IrFinancialConcept::SharesOutstanding),
+    // us-gaap / dei
+    ("us-gaap", "Revenues", IrFinancialConcept::Revenue),
+    ("us-gaap", "RevenueFromContractWithCustomerExcludingAssessedTax", IrFinancialConcept::Revenue),
+    ("us-gaap", "SalesRevenueNet", IrFinancialConcept::Revenue),
+    ("us-gaap", "OperatingIncomeLoss", IrFinancialConcept::OperatingIncome),
+    ("us-gaap", "IncomeLossFromContinuingOperationsBeforeIncomeTaxesExtraordinaryItemsNoncontrollingInterest", IrFinancialConcept::IncomeBeforeTax),
+    ("us-gaap", "IncomeLossFromContinuingOperationsBeforeIncomeTaxesMinorityInterestAndIncomeLossFromEquityMethodInvestments", IrFinancialConcept::IncomeBeforeTax),
+    ("us-gaap", "NetIncomeLoss", IrFinancialConcept::NetIncome),
+    ("us-gaap", "Assets", IrFinancialConcept::TotalAssets),
+    ("us-gaap", "Liabilities", IrFinancialConcept::TotalLiabilities),
+    ("us-gaap", "StockholdersEquity", IrFinancialConcept::NetAssets),
+    ("us-gaap", "StockholdersEquityIncludingPortionAttributableToNoncontrollingInterest", IrFinancialConcept::NetAssets),
+    ("us-gaap", "CashAndCashEquivalentsAtCarryingValue", IrFinancialConcept::CashAndEquivalents),
+    ("us-gaap", "NetCashProvidedByUsedInOperatingActivities", IrFinancialConcept::OperatingCashFlow),
+    ("us-gaap", "NetCashProvidedByUsedInInvestingActivities", IrFinancialConcept::InvestingCashFlow),
+    ("us-gaap", "NetCashProvidedByUsedInFinancingActivities", IrFinancialConcept::FinancingCashFlow),
+    ("us-gaap", "EarningsPerShareBasic", IrFinancialConcept::EpsBasic),
+    ("us-gaap", "EarningsPerShareDiluted", IrFinancialConcept::EpsDiluted),
+    ("dei", "EntityCommonStockSharesOutstanding", IrFinancialConcept::SharesOutstanding),
+];
+
+fn taxonomy_family(fact: &IrXbrlFact) -> &'static str {
+    let ns = fact.namespace.as_deref().unwrap_or_default();
+    let prefix = fact.concept.split_once(':').map(|(p, _)| p).unwrap_or_default();
+    if ns.contains("/jppfs/") || prefix.starts_with("jppfs") { return "jppfs"; }
+    if ns.contains("/jpcrp/") || prefix.starts_with("jpcrp") { return "jpcrp"; }
+    if ns.contains("fasb.org/us-gaap") || prefix == "us-gaap" { return "us-gaap"; }
+    if ns.contains("xbrl.sec.gov/dei") || prefix == "dei" { return "dei"; }
+    ""
+}
+
+fn period_key(p: Option<&IrXbrlPeriod>) -> String {
+    match p {
+        Some(IrXbrlPeriod::Instant { date }) => date.clone(),
+        Some(IrXbrlPeriod::Duration { start, end }) => format!("{start}/{end}"),
+        Some(IrXbrlPeriod::Forever) => "forever".into(),
+        None => String::new(),
+    }
+}
+
+fn parse(raw: &str) -> Result<Document<'_>, IrNormalizationError> {
+    let opts = ParsingOptions { allow_dtd: true, ..ParsingOptions::default() };
+    Document::parse_with_options(raw, opts).map_err(|e| IrNormalizationError::new(IrNormalizationReasonCode::MalformedXbrl, e.to_string()))
+}
+
+fn is_ix(n: &Node, local: &str) -> bool {
+    n.is_element() && n.tag_name().name() == local && n.tag_name().namespace().is_some_and(|ns| NS_IX.contains(&ns))
+}
+
+fn is_xbrli(n: &Node, local: &str) -> bool {
+    n.is_element() && n.tag_name().name() == local && n.tag_name().namespace() == Some(NS_XBRLI)
+}
+
+fn child<'a, 'i>(n: Node<'a, 'i>, ns: &str, local: &str) -> Option<Node<'a, 'i>> {
+    n.children().find(|c| c.is_element() && c.tag_name().name() == local && c.tag_name().namespace() == Some(ns))
+}
+
+/// Text content of `n`; with `skip_exclude`, `ix:exclude` subtrees are left out.
+fn text_of(n: Node, skip_exclude: bool) -> String {
+    let mut out = String::new();
+    for c in n.children() {
+        if c.is_text() {
+            out.push_str(c.text().unwrap_or_default());
+        } else if c.is_element() && !(skip_exclude && is_ix(&c, "exclude")) {
+            out.push_str(&text_of(c, skip_exclude));
+        }
+    }
+    out
+}
+
+#[derive(Debug, Clone, Default)]
+struct Context {
+    entity_scheme: Option<String>,
+    entity_id: Option<String>,
+    period: Option<IrXbrlPeriod>,
+    dimensions: BTreeMap<String, String>,
+}
+
+struct FactParts<'a, 'i> {
+    concept: String,
+    namespace: Option<String>,
+    local_name: String,
+    node: Node<'a, 'i>,
+    value: String,
+    numeric_value: Option<String>,
+    scale: Option<i32>,
+    /// Why `numeric_value` could not be produced; recorded in the fact's provenance.
+    error: Option<IrNormalizationError>,
+    source_type: &'static str,
+}
+
+struct Contexts {
+    contexts: HashMap<String, Context>,
+    units: HashMap<String, String>,
+}
+
+impl Contexts {
+    fn collect(doc: &Document) -> Self {
+        let mut contexts = HashMap::new();
+        let mut units = HashMap::new();
+        for n in doc.descendants() {
+            if is_xbrli(&n, "context") {
+                if let Some(id) = n.attribute("id") { contexts.insert(id.to_string(), parse_context(n)); }
+            } else if is_xbrli(&n, "unit") {
+                if let Some(id) = n.attribute("id") { units.insert(id.to_string(), parse_unit(n)); }
+            }
+        }
+        Self { contexts, units }
+    }
+
+    fn fact(&self, p: FactParts) -> IrXbrlFact {
+        let context_ref = p.node.attribute("contextRef").unwrap_or_default().to_string();
+        let ctx = self.contexts.get(&context_ref).cloned().unwrap_or_default();
+        let unit_ref = p.node.attribute("unitRef").map(str::to_string);
+        let unit = unit_ref.as_ref().and_then(|u| self.units.get(u).cloned());
+        let is_nil = p.node.attribute((NS_XSI, "nil")) == Some("true");
+        let mut extra = BTreeMap::new();
+        if let Some(id) = p.node.attribute("id") { extra.insert("fact_id".to_string(), id.to_string()); }
+        if !self.contexts.contains_key(&context_ref) { extra.insert("unresolved_context".to_string(), "true".to_string()); }
+        if let Some(e) = p.error {
+            extra.insert("reason_code".to_string(), e.reason.as_str().to_string());
+            extra.insert("error".to_string(), e.message);
+            extra.extend(e.metadata);
+        }
+        IrXbrlFact {
+            concept: p.concept.clone(),
+            namespace: p.namespace,
+            local_name: p.local_name,
+            context_ref: context_ref.clone(),
+            entity_scheme: ctx.entity_scheme,
+            entity_id: ctx.entity_id,
+            period: ctx.period,
+            dimensions: ctx.dimensions,
+            unit_ref,
+            unit,
+            decimals: p.node.attribute("decimals").map(str::to_string),
+            scale: p.scale,
+            numeric_value: if is_nil { None } else { p.numeric_value },
+            value: p.value,
+            is_nil,
+            provenance: IrNormalizationProvenance { source_type: Some(p.source_type.into()), source_ref: Some(p.concept), context_ref: Some(context_ref), extra },
+        }
+    }
+}
+
+fn parse_context(n: Node) -> Context {
+    let mut ctx = Context::default();
+    if let Some(ident) = child(n, NS_XBRLI, "entity").and_then(|e| child(e, NS_XBRLI, "identifier")) {
+        ctx.entity_scheme = ident.attribute("scheme").map(str::to_string);
+        ctx.entity_id = ident.text().map(|t| t.trim().to_string());
+    }
+    if let Some(p) = child(n, NS_XBRLI, "period") {
+        let text = |local: &str| child(p, NS_XBRLI, local).map(|c| text_of(c, false).trim().to_string());
+        ctx.period = if let Some(date) = text("instant") {
+            Some(IrXbrlPeriod::Instant { date })
+        } else if let (Some(start), Some(end)) = (text("startDate"), text("endDate")) {
+            Some(IrXbrlPeriod::Duration { start, end })
+        } else if child(p, NS_XBRLI, "forever").is_some() {
+            Some(IrXbrlPeriod::Forever)
+        } else {
+            None
+        };
+    }
+    for m in n.descendants().filter(|d| d.is_element() && d.tag_name().namespace() == Some(NS_XBRLDI)) {
+        let Some(dim) = m.attribute("dimension") else { continue };
+        let member = match m.tag_name().name() {
+            "explicitMember" => text_of(m, false),
+            "typedMember" => m.children().find(|c| c.is_element()).map(|c| text_of(c, false)).unwrap_or_default(),
+            _ => continue,
+        };
+        ctx.dimensions.insert(dim.to_string(), member.trim().to_string());
+    }
+    ctx
+}
+
+fn parse_unit(n: Node) -> String {
+    let measures = |parent: Node| parent.children().filter(|c| is_xbrli(c, "measure")).map(|c| text_of(c, false).trim().to_string()).collect::<Vec<_>>().join("*");
+    match child(n, NS_XBRLI, "divide") {
+        Some(d) => {
+            let num = child(d, NS_XBRLI, "unitNumerator").map(measures).unwrap_or_default();
+            let den = child(d, NS_XBRLI, "unitDenominator").map(measures).unwrap_or_default();
+            format!("{num}/{den}")
+        }
+        None => measures(n),
+    }
+}
+
+/// `ix:nonFraction/@scale`, rejected when unparsable or beyond ±[`MAX_SCALE`].
+fn parse_scale(raw: &str) -> Result<i32, IrNormalizationError> {
+    match raw.trim().parse::<i32>() {
+        Ok(s) if s.unsigned_abs() <= MAX_SCALE.unsigned_abs() => Ok(s),
+        _ => {
+            let mut e = IrNormalizationError::new(IrNormalizationReasonCode::MalformedXbrl, format!("ix:nonFraction scale {raw:?} is outside -{MAX_SCALE}..={MAX_SCALE}"));
+            e.metadata.insert("scale".into(), raw.to_string());
+            Err(e)
+        }
+    }
+}
+
+/// Applies the ixt transformation named by `format` and returns an unsigned decimal string.
+/// A leading △ / ▲ / - is display only: the fact's sign is given by `@sign`.
+fn ixt_number(display: &str, format: Option<&str>) -> Option<String> {
+    let fmt = format.map(|f| f.rsplit(':').next().unwrap_or(f).to_ascii_lowercase().replace('-', "")).unwrap_or_default();
+    let s: String = display
+        .chars()
+        .map(|c| match c {
+            '０'..='９' => char::from_u32(c as u32 - '０' as u32 + '0' as u32).unwrap_or(c),
+            '，' => ',',
+            '．' => '.',
+            _ => c,
+        })
+        .collect();
+    let s = s.trim();
+    if fmt.contains("zerodash") || fmt.contains("fixedzero") || s == "-" || s == "―" || s == "—" {
+        return Some("0".into());
+    }
+    let s = s.strip_prefix(['△', '▲', '-']).map(str::trim).unwrap_or(s);
+    let digits: String = if fmt.contains("commadecimal") {
+        s.chars().filter(|c| c.is_ascii_digit() || *c == ',').map(|c| if c == ',' { '.' } else { c }).collect()
+    } else {
+        s.chars().filter(|c| c.is_ascii_digit() || *c == '.').collect()
+    };
+    if digits.is_empty() { return None; }
+    Some(digits)
+}
+
+/// Normalizes a decimal literal, shifting the point by `scale` and optionally negating it.
+/// `None` when `scale` is beyond ±[`MAX_SCALE`] (the result would be thousands of zeros).
+pub(crate) fn canonical_decimal(raw: &str, scale: i32, negate: bool) -> Option<String> {
+    if scale.unsigned_abs() > MAX_SCALE.unsigned_abs() { return None; }
+    let (mut neg, body) = match raw.strip_prefix('-') {
+        Some(rest) => (true, rest),
+        None => (false, raw.strip_prefix('+').unwrap_or(raw)),
+    };
+    let (int, frac) = body.split_once('.').unwrap_or((body, ""));
+    if (int.is_empty() && frac.is_empty()) || !int.chars().chain(frac.chars()).all(|c| c.is_ascii_digit()) {
+        return None;
+    }
+    if negate { neg = !neg; }
+    let digits = format!("{int}{frac}");
+    let point = int.len() as i64 + scale as i64;
+    let (int, frac) = if point <= 0 {
+        (String::new(), format!("{}{digits}", "0".repeat((-point) as usize)))
+    } else if point as usize >= digits.len() {
+        (format!("{digits}{}", "0".repeat(point as usize - digits.len())), String::new())
+    } else {
+        (digits[..point as usize].to_string(), digits[point as usize..].to_string())
+    };
+    let int = int.trim_start_matches('0');
+    let frac = frac.trim_end_matches('0');
+    let int = if int.is_empty() { "0" } else { int };
+    let body = if frac.is_empty() { int.to_string() } else { format!("{int}.{frac}") };
+    Some(if neg && body != "0" { format!("-{body}") } else { body })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn canonical_decimal_applies_scale_and_sign() {
+        assert_eq!(canonical_decimal("1234", 6, false).as_deref(), Some("1234000000"));
+        assert_eq!(canonical_decimal("12.50", 0, true).as_deref(), Some("-12.5"));
+        assert_eq!(canonical_decimal("-5", -2, false).as_deref(), Some("-0.05"));
+        assert_eq!(canonical_decimal("0", 3, true).as_deref(), Some("0"));
+        assert_eq!(canonical_decimal("1e3", 0, false), None);
+        assert_eq!(canonical_decimal("1", 31, false), None);
+        assert_eq!(canonical_decimal("1", i32::MIN, false), None);
+    }
+
+    #[test]
+    fn ixt_number_handles_jp_and_comma_decimal_formats() {
+        assert_eq!(ixt_number("１，２３４", Some("ixt:numdotdecimal")).as_deref(), Some("1234"));
+        assert_eq!(ixt_number("△ 1,234", None).as_deref(), Some("1234"));
+        assert_eq!(ixt_number("1.234,5", Some("ixt:num-comma-decimal")).as_deref(), Some("1234.5"));
+        assert_eq!(ixt_number("－", Some("ixt:fixed-zero")).as_deref(), Some("0"));
+    }
+}
@@ -0,0 +1,497 @@
+//! Long-running incremental sync over `IrSourceAdapter`s.
+//!
+//! Every source keeps a cursor (newest timestamp seen per issuer plus recently emitted
+//! document ids) in a `CheckpointStore` under `ir_sync.<source_id>`, so a restarted runner
+//! resumes without re-emitting documents. When a request budget caps the issuer queries of
+//! one poll, successive polls rotate through the issuer list. Sources are scheduled independently: a successful poll
+//! waits `poll_interval`, a failed one backs off exponentially from the source's
+//! politeness policy.
+
+use crate::access::IrAccessGuard;
+use crate::checkpoint::CheckpointStore;
+use crate::document::IrDocumentListRequest;
+use crate::errors::{UcelIrError, UcelIrErrorKind};
+use crate::fetch::IrSourceAdapter;
+use crate::issuer_sites::IssuerSitePolitenessPolicy;
+use crate::jp_official::JpPolitenessPolicy;
+use crate::us_official::UsPolitenessPolicy;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashSet, VecDeque};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+use ucel_core::{IrAccessDecision, IrDocumentDescriptor, IrDocumentKey};
+
+pub const CURSOR_KEY_PREFIX: &str = "ir_sync.";
+/// Document ids remembered per source to dedupe re-listed documents.
+const MAX_RECENT_IDS: usize = 4096;
+/// Backoff never grows beyond `base_backoff_ms * MAX_BACKOFF_FACTOR`.
+const MAX_BACKOFF_FACTOR: u64 = 240;
+const DEFAULT_POLL_INTERVAL: Duration = Duration::from_secs(300);
+/// Granularity at which `run` notices a stop request while idle.
+const IDLE_TICK: Duration = Duration::from_millis(200);
+
+/// Persisted per-source position.
+///
+/// Timestamps are compared as strings, which is correct for the ISO-8601 values the
+/// adapters emit. Issuer queries are filtered by that issuer's own watermark, so an issuer
+/// whose filings lag behind the others is not cut off by their newer timestamps; an issuer
+/// without an entry yet is deduplicated by `recent_ids` only.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct IrSyncCursor {
+    /// Newest timestamp over all queries; filters source-wide (issuer-less) queries.
+    pub watermark: Option<String>,
+    pub recent_ids: VecDeque<String>,
+    #[serde(default)]
+    pub issuer_watermarks: BTreeMap<String, String>,
+    /// Index into the issuer list where the next budget-limited poll starts.
+    #[serde(default)]
+    pub next_issuer: usize,
+}
+
+impl IrSyncCursor {
+    pub fn key(source_id: &str) -> String {
+        format!("{CURSOR_KEY_PREFIX}{source_id}")
+    }
+
+    pub fn load(store: &dyn CheckpointStore, source_id: &str) -> Result<Self, UcelIrError> {
+        match store.get(&Self::key(source_id))? {
+            Some(raw) => serde_json::from_str(&raw).map_err(|e| {
+                UcelIrError::new(
+                    UcelIrErrorKind::Checkpoint,
+                    format!("invalid cursor for {source_id}: {e}"),
+                )
+            }),
+            None => Ok(Self::default()),
+        }
+    }
+
+    pub fn save(&self, store: &dyn CheckpointStore, source_id: &str) -> Result<(), UcelIrError> {
+        let raw = serde_json::to_string(self)
+            .map_err(|e| UcelIrError::new(UcelIrErrorKind::Checkpoint, e.to_string()))?;
+        store.set(&Self::key(source_id), &raw)
+    }
+
+    fn timestamp(doc: &IrDocumentDescriptor) -> Option<&str> {
+        doc.published_at.as_deref().or(doc.filed_at.as_deref())
+    }
+
+    fn watermark_for(&self, issuer_key: Option<&str>) -> Option<&str> {
+        match issuer_key {
+            Some(k) => self.issuer_watermarks.get(k).map(String::as_str),
+            None => self.watermark.as_deref(),
+        }
+    }
+
+    /// Whether `doc`, listed by the query for `issuer_key`, has not been emitted yet.
+    pub fn is_new(&self, issuer_key: Option<&str>, doc: &IrDocumentDescriptor) -> bool {
+        if self.recent_ids.contains(&doc.key.source_document_id) {
+            return false;
+        }
+        match (self.watermark_for(issuer_key), Self::timestamp(doc)) {
+            (Some(w), Some(ts)) => ts >= w,
+            _ => true,
+        }
+    }
+
+    pub fn advance(&mut self, issuer_key: Option<&str>, doc: &IrDocumentDescriptor) {
+        self.recent_ids
+            .push_back(doc.key.source_document_id.clone());
+        while self.recent_ids.len() > MAX_RECENT_IDS {
+            self.recent_ids.pop_front();
+        }
+        let Some(ts) = Self::timestamp(doc) else {
+            return;
+        };
+        if self.watermark.as_deref().is_none_or(|w| ts > w) {
+            self.watermark = Some(ts.to_string());
+        }
+        if let Some(k) = issuer_key {
+            let w = self.issuer_watermarks.entry(k.to_string()).or_default();
+            if ts > w.as_str() {
+                *w = ts.to_string();
+            }
+        }
+    }
+}
+
+/// Request pacing and retry limits, derived from the family politeness policies.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct IrSyncPoliteness {
+    /// Failed polls in a row before the source is reported as failing.
+    pub retry_budget: u8,
+    /// Delay between consecutive requests of one poll, and the first backoff step.
+    pub base_backoff_ms: u64,
+    pub max_backoff_ms: u64,
+    /// Maximum list requests per poll (issuer-site page budget).
+    pub request_budget: Option<usize>,
+}
+
+impl IrSyncPoliteness {
+    pub fn backoff(&self, consecutive_failures: u32) -> Duration {
+        let exp = consecutive_failures.saturating_sub(1).min(32);
+        let ms = self
+            .base_backoff_ms
+            .saturating_mul(1u64 << exp)
+            .min(self.max_backoff_ms);
+        Duration::from_millis(ms)
+    }
+}
+
+impl From<JpPolitenessPolicy> for IrSyncPoliteness {
+    fn from(p: JpPolitenessPolicy) -> Self {
+        Self {
+            retry_budget: p.retry_budget,
+            base_backoff_ms: p.base_backoff_ms,
+            max_backoff_ms: p.base_backoff_ms * MAX_BACKOFF_FACTOR,
+            request_budget: None,
+        }
+    }
+}
+
+impl From<UsPolitenessPolicy> for IrSyncPoliteness {
+    fn from(p: UsPolitenessPolicy) -> Self {
+        Self {
+            retry_budget: p.retry_budget,
+            base_backoff_ms: p.base_backoff_ms,
+            max_backoff_ms: p.base_backoff_ms * MAX_BACKOFF_FACTOR,
+            request_budget: None,
+        }
+    }
+}
+
+impl From<IssuerSitePolitenessPolicy> for IrSyncPoliteness {
+    fn from(p: IssuerSitePolitenessPolicy) -> Self {
+        Self {
+            retry_budget: p.retry_budget,
+            base_backoff_ms: p.base_backoff_ms,
+            max_backoff_ms: p.base_backoff_ms * MAX_BACKOFF_FACTOR,
+            request_budget: Some(p.page_budget),
+        }
+    }
+}
+
+pub struct IrSyncSourceConfig {
+    pub adapter: Arc<dyn IrSourceAdapter + Send + Sync>,
+    /// One list request per issuer; empty means a single source-wide request.
+    pub issuer_keys: Vec<String>,
+    pub poll_interval: Duration,
+    pub politeness: IrSyncPoliteness,
+    /// `FreePublicNoAuthReviewRequired` sources only run once review is approved.
+    pub review_approved: bool,
+}
+
+impl IrSyncSourceConfig {
+    pub fn new(
+        adapter: Arc<dyn IrSourceAdapter + Send + Sync>,
+        politeness: impl Into<IrSyncPoliteness>,
+    ) -> Self {
+        Self {
+            adapter,
+            issuer_keys: vec![],
+            poll_interval: DEFAULT_POLL_INTERVAL,
+            politeness: politeness.into(),
+            review_approved: false,
+        }
+    }
+
+    pub fn with_issuer_keys(mut self, keys: Vec<String>) -> Self {
+        self.issuer_keys = keys;
+        self
+    }
+
+    pub fn with_poll_interval(mut self, interval: Duration) -> Self {
+        self.poll_interval = interval;
+        self
+    }
+
+    pub fn review_approved(mut self) -> Self {
+        self.review_approved = true;
+        self
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum IrSyncSourceStatus {
+    #[default]
+    Pending,
+    Ok,
+    /// Last poll failed; retrying after a backoff within the retry budget.
+    Backoff,
+    /// Failed more than `retry_budget` polls in a row; still retried at max backoff.
+    Failing,
+    /// Access policy excludes the source (paid, login, blocked).
+    Blocked,
+    /// Access policy requires review and the source was not approved.
+    ReviewRequired,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct IrSyncSourceMetrics {
+    pub status: IrSyncSourceStatus,
+    pub polls: u64,
+    pub requests: u64,
+    pub documents_seen: u64,
+    pub documents_new: u64,
+    pub deduplicated: u64,
+    pub errors: u64,
+    pub consecutive_failures: u32,
+    pub last_error: Option<String>,
+    pub watermark: Option<String>,
+}
+
+/// Receives each newly discovered document once.
+pub trait IrDocumentSink {
+    fn put_document(&self, doc: &IrDocumentDescriptor) -> Result<(), UcelIrError>;
+}
+
+#[derive(Default)]
+pub struct MemoryDocumentSink {
+    documents: Mutex<Vec<IrDocumentDescriptor>>,
+}
+
+impl MemoryDocumentSink {
+    pub fn documents(&self) -> Vec<IrDocumentDescriptor> {
+        self.documents.lock().map(|g| g.clone()).unwrap_or_default()
+    }
+}
+
+impl IrDocumentSink for MemoryDocumentSink {
+    fn put_document(&self, doc: &IrDocumentDescriptor) -> Result<(), UcelIrError> {
+        self.documents
+            .lock()
+            .map_err(|_| UcelIrError::new(UcelIrErrorKind::Internal, "document sink lock poisoned"))?
+            .push(doc.clone());
+        Ok(())
+    }
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct IrSyncPassReport {
+    pub polled: Vec<String>,
+    pub new_documents: usize,
+}
+
+struct SourceState {
+    source_id: String,
+    cfg: IrSyncSourceConfig,
+    next_due: Option<Instant>,
+    metrics: IrSyncSourceMetrics,
+}
+
+pub struct IrSyncRunner {
+    sources: Vec<SourceState>,
+    checkpoints: Arc<dyn CheckpointStore + Send + Sync>,
+    sink: Arc<dyn IrDocumentSink + Send + Sync>,
+    pace: fn(Duration),
+}
+
+impl IrSyncRunner {
+    pub fn new(
+        checkpoints: Arc<dyn CheckpointStore + Send + Sync>,
+        sink: Arc<dyn IrDocumentSink + Send + Sync>,
+    ) -> Self {
+        Self {
+            sources: vec![],
+            checkpoints,
+            sink,
+            pace: std::thread::sleep,
+        }
+    }
+
+    /// Replaces the delay used between requests of one poll (tests pass a no-op).
+    pub fn with_pacing(mut self, pace: fn(Duration)) -> Self {
+        self.pace = pace;
+        self
+    }
+
+    /// Registers a source; its access policy decides whether it is ever polled.
+    pub fn add_source(&mut self, cfg: IrSyncSourceConfig) {
+        let descriptor = cfg.adapter.source_descriptor();
+        let status = match IrAccessGuard::ensure_allowed(descriptor.access_policy_class) {
+            Err(_) => IrSyncSourceStatus::Blocked,
+            Ok(IrAccessDecision::ReviewRequired) if !cfg.review_approved => {
+                IrSyncSourceStatus::ReviewRequired
+            }
+            Ok(_) => IrSyncSourceStatus::Pending,
+        };
+        let watermark = IrSyncCursor::load(self.checkpoints.as_ref(), &descriptor.source_id)
+            .ok()
+            .and_then(|c| c.watermark);
+        self.sources.push(SourceState {
+            source_id: descriptor.source_id,
+            next_due: (status == IrSyncSourceStatus::Pending).then(Instant::now),
+            metrics: IrSyncSourceMetrics {
+                status,
+                watermark,
+                ..Default::default()
+            },
+            cfg,
+        });
+    }
+
+    pub fn metrics(&self) -> BTreeMap<String, IrSyncSourceMetrics> {
+        self.sources
+            .iter()
+            .map(|s| (s.source_id.clone(), s.metrics.clone()))
+            .collect()
+    }
+
+    /// Reasons the runner is unhealthy; empty when every runnable source is fine.
+    pub fn health_reasons(&self) -> Vec<String> {
+        let mut reasons: Vec<String> = self
+            .sources
+            .iter()
+            .filter(|s| s.metrics.status == IrSyncSourceStatus::Failing)
+            .map(|s| format!("source_failing:{}", s.source_id))
+            .collect();
+        if self.sources.iter().all(|s| s.next_due.is_none()) {
+            reasons.push("no_runnable_sources".into());
+        }
+        reasons
+    }
+
+    pub fn next_due(&self) -> Option<Instant> {
+        self.sources.iter().filter_map(|s| s.next_due).min()
+    }
+
+    /// Polls every source whose schedule is due at `now`.
+    pub fn poll_due(&mut self, now: Instant) -> IrSyncPassReport {
+        let mut report = IrSyncPassReport::default();
+        for i in 0..self.sources.len() {
+            if self.sources[i].next_due.is_some_and(|due| due <= now) {
+                report.new_documents += self.poll_source(i, now);
+                report.polled.push(self.sources[i].source_id.clone());
+            }
+        }
+        report
+    }
+
+    /// Polls until `stop` is set, calling `on_pass` after every pass that polled a source.
+    pub fn run(&mut self, stop: &AtomicBool, mut on_pass: impl FnMut(&Self, &IrSyncPassReport)) {
+        while !stop.load(Ordering::Relaxed) {
+            let now = Instant::now();
+            let report = self.poll_due(now);
+            if !report.polled.is_empty() {
+                on_pass(self, &report);
+            }
+            let wait = self
+                .next_due()
+                .map(|d| d.saturating_duration_since(Instant::now()))
+                .unwrap_or(IDLE_TICK)
+                .min(IDLE_TICK);
+            std::thread::sleep(wait);
+        }
+    }
+
+    fn poll_source(&mut self, index: usize, now: Instant) -> usize {
+        let checkpoints = self.checkpoints.clone();
+        let sink = self.sink.clone();
+        let pace = self.pace;
+        let state = &mut self.sources[index];
+        let source_id = state.source_id.clone();
+        let market = state.cfg.adapter.source_descriptor().market;
+        state.metrics.polls += 1;
+
+        let mut failure: Option<UcelIrError> = None;
+        let mut new_documents = 0;
+        let loaded = match IrSyncCursor::load(checkpoints.as_ref(), &source_id) {
+            Ok(c) => c,
+            Err(e) => {
+                failure = Some(e);
+                IrSyncCursor::default()
+            }
+        };
+        let mut cursor = loaded.clone();
+        let issuers = &state.cfg.issuer_keys;
+        let (start, mut queries): (usize, Vec<Option<String>>) = if issuers.is_empty() {
+            (0, vec![None])
+        } else {
+            let start = cursor.next_issuer % issuers.len();
+            let rotated = issuers[start..].iter().chain(&issuers[..start]);
+            (start, rotated.cloned().map(Some).collect())
+        };
+        if let Some(budget) = state.cfg.politeness.request_budget {
+            queries.truncate(budget.max(1));
+        }
+        let mut batch: HashSet<IrDocumentKey> = HashSet::new();
+        let mut completed = 0;
+
+        for (n, issuer_key) in queries.into_iter().enumerate() {
+            if failure.is_some() {
+                break;
+            }
+            if n > 0 {
+                pace(Duration::from_millis(state.cfg.politeness.base_backoff_ms));
+            }
+            state.metrics.requests += 1;
+            let request = IrDocumentListRequest {
+                source_id: source_id.clone(),
+                market,
+                issuer_key: issuer_key.clone(),
+            };
+            let documents = match state.cfg.adapter.list_documents(&request) {
+                Ok(resp) => resp.documents,
+                Err(e) => {
+                    failure = Some(e);
+                    break;
+                }
+            };
+            for doc in documents {
+                state.metrics.documents_seen += 1;
+                if !batch.insert(doc.key.clone()) || !cursor.is_new(issuer_key.as_deref(), &doc) {
+                    state.metrics.deduplicated += 1;
+                    continue;
+                }
+                if let Err(e) = sink.put_document(&doc) {
+                    failure = Some(e);
+                    break;
+                }
+                cursor.advance(issuer_key.as_deref(), &doc);
+                new_documents += 1;
+            }
+            if failure.is_none() {
+                completed += 1;
+            }
+        }
+        // the next poll resumes at the first issuer this one did not finish
+        if !issuers.is_empty() {
+            cursor.next_issuer = (start + completed) % issuers.len();
+        }
+        if cursor != loaded {
+            if let Err(e) = cursor.save(checkpoints.as_ref(), &source_id) {
+                failure.get_or_insert(e);
+            }
+        }
+        state.metrics.documents_new += new_documents as u64;
+        state.metrics.watermark = cursor.watermark.clone();
+
+        match failure {
+            None => {
+                state.metrics.status = IrSyncSourceStatus::Ok;
+                state.metrics.consecutive_failures = 0;
+                state.next_due = Some(now + state.cfg.poll_interval);
+            }
+            Some(e) if e.kind == UcelIrErrorKind::Policy => {
+                state.metrics.errors += 1;
+                state.metrics.last_error = Some(e.to_string());
+                state.metrics.status = IrSyncSourceStatus::Blocked;
+                state.next_due = None;
+            }
+            Some(e) => {
+                state.metrics.errors += 1;
+                state.metrics.last_error = Some(e.to_string());
+                state.metrics.consecutive_failures += 1;
+                let failures = state.metrics.consecutive_failures;
+                state.metrics.status = if failures > u32::from(state.cfg.politeness.retry_budget) {
+                    IrSyncSourceStatus::Failing
+                } else {
+                    IrSyncSourceStatus::Backoff
+                };
+                state.next_due = Some(now + state.cfg.politeness.backoff(failures));
+            }
+        }
+        new_documents
+    }
+}
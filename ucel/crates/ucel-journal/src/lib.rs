@@ -72,6 +72,13 @@ impl WalWriter {
         Ok(())
     }
 
+    /// Fsync the current file regardless of `fsync_mode` (used on drain / stop).
+    pub fn sync(&mut self) -> Result<(), String> {
+        self.current_file.sync_all().map_err(|e| e.to_string())?;
+        self.writes_since_sync = 0;
+        Ok(())
+    }
+
     fn maybe_sync(&mut self) -> Result<(), String> {
         match self.fsync_mode {
             FsyncMode::SafeEveryRecord => {
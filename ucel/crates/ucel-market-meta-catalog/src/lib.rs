@@ -41,6 +41,7 @@ fn map_exchange(s: &str) -> Option<Exchange> {
     match s {
         "bitbank" => Some(Exchange::Bitbank),
         "bitflyer" => Some(Exchange::Bitflyer),
+        "bithumb" => Some(Exchange::Bithumb),
         "coincheck" => Some(Exchange::Coincheck),
         "sbivc" => Some(Exchange::Sbivc),
         "upbit" => Some(Exchange::Upbit),
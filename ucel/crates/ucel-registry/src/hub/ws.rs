@@ -109,24 +109,22 @@ impl WsHub {
                         while let Some(msg) = read.next().await {
                             match msg {
                                 Ok(Message::Text(text)) => {
-                                    if tx
+                                    let sent = tx
                                         .send(Ok(WsMessage {
                                             raw: Bytes::from(text.into_bytes()),
                                         }))
-                                        .await
-                                        .is_err()
-                                    {
+                                        .await;
+                                    if sent.is_err() {
                                         return;
                                     }
                                 }
                                 Ok(Message::Binary(bin)) => {
-                                    if tx
+                                    let sent = tx
                                         .send(Ok(WsMessage {
                                             raw: Bytes::from(bin),
                                         }))
-                                        .await
-                                        .is_err()
-                                    {
+                                        .await;
+                                    if sent.is_err() {
                                         return;
                                     }
                                 }
@@ -1,30 +1,80 @@
 use async_trait::async_trait;
+use std::collections::BTreeMap;
+use std::sync::Arc;
+use tokio::sync::{oneshot, Mutex};
+use ucel_journal::WalWriter;
+use ucel_subscription_store::SubscriptionStore;
+use ucel_transport::obs::TransportMetrics;
+use ucel_transport::ws::adapter::WsVenueAdapter;
+use ucel_transport::ws::connection::{run_ws_connection, ShutdownToken, WsRunConfig};
+use ucel_ws_rules::ExchangeWsRules;
 
 #[derive(Debug, Clone)]
 pub struct IngestPlanRef {
     pub exchange_id: String,
     pub seed_len: usize,
+    /// Connections to run; subscriptions must already be seeded with these as `assigned_conn`.
+    pub conn_ids: Vec<String>,
 }
 
-#[derive(Debug, Clone)]
+/// Called once per connection that ended with an error: `(conn_id, error)`.
+pub type ConnectionEndHook = Arc<dyn Fn(&str, &str) + Send + Sync>;
+
+/// Called from the connection's own thread once its store is open and the socket loop starts.
+pub type ConnectionStartHook = Arc<dyn Fn(&str) + Send + Sync>;
+
+/// Called from the connection's own thread as soon as it returns, whatever the outcome.
+pub type ConnectionExitHook = Arc<dyn Fn(&str, &Result<(), String>) + Send + Sync>;
+
+/// Returns the transport metrics a connection should record into, by `conn_id`.
+pub type ConnectionMetricsHook = Arc<dyn Fn(&str) -> Arc<TransportMetrics> + Send + Sync>;
+
+#[derive(Clone)]
 pub struct IngestRuntimeRef {
     pub store_path: String,
     pub journal_dir: String,
+    pub wal: Arc<Mutex<WalWriter>>,
+    pub shutdown: ShutdownToken,
+    pub on_connection_end: Option<ConnectionEndHook>,
+    pub on_connection_start: Option<ConnectionStartHook>,
+    pub on_connection_exit: Option<ConnectionExitHook>,
+    pub connection_metrics: Option<ConnectionMetricsHook>,
+}
+
+impl std::fmt::Debug for IngestRuntimeRef {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("IngestRuntimeRef")
+            .field("store_path", &self.store_path)
+            .field("journal_dir", &self.journal_dir)
+            .field("shutdown", &self.shutdown)
+            .field("on_connection_end", &self.on_connection_end.is_some())
+            .field("on_connection_start", &self.on_connection_start.is_some())
+            .field("on_connection_exit", &self.on_connection_exit.is_some())
+            .field("connection_metrics", &self.connection_metrics.is_some())
+            .finish_non_exhaustive()
+    }
 }
 
 #[derive(Debug, Clone)]
 pub struct IngestRulesRef {
     pub support_level: String,
+    pub rules: ExchangeWsRules,
 }
 
 #[derive(Debug, Clone)]
 pub struct IngestConfigRef {
     pub enable_private_ws: bool,
+    /// Template for every connection; `exchange_id`/`conn_id` are filled in per connection.
+    pub run: WsRunConfig,
 }
 
 #[async_trait]
 pub trait ExchangeIngestDriver: Send + Sync {
     fn exchange_id(&self) -> &'static str;
+    /// `false` when `run_ws_ingest` can never start a connection for this venue.
+    fn supports_ws_ingest(&self) -> bool {
+        true
+    }
     async fn fetch_symbols(&self) -> Result<Vec<String>, String>;
     async fn run_ws_ingest(
         &self,
@@ -44,6 +94,7 @@ pub fn registered_ingest_driver_ids() -> Vec<&'static str> {
         "bitbank",
         "bitflyer",
         "bitget",
+        "bithumb",
         "bitmex",
         "bittrade",
         "bybit",
@@ -58,3 +109,250 @@ pub fn registered_ingest_driver_ids() -> Vec<&'static str> {
         "upbit",
     ]
 }
+
+pub type IngestDriverFactory =
+    Box<dyn Fn() -> Result<Box<dyn ExchangeIngestDriver>, String> + Send + Sync>;
+
+/// Maps ingest ids (e.g. `kraken`, `bybit-linear`) to driver factories.
+///
+/// Venue crates register their own ids; this crate cannot depend on them, so the
+/// binary assembling the venues owns the registry instance.
+#[derive(Default)]
+pub struct IngestDriverRegistry {
+    factories: BTreeMap<&'static str, IngestDriverFactory>,
+}
+
+impl IngestDriverRegistry {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn register<F>(&mut self, id: &'static str, factory: F)
+    where
+        F: Fn() -> Result<Box<dyn ExchangeIngestDriver>, String> + Send + Sync + 'static,
+    {
+        self.factories.insert(id, Box::new(factory));
+    }
+
+    pub fn contains(&self, id: &str) -> bool {
+        self.factories.contains_key(id)
+    }
+
+    pub fn ids(&self) -> Vec<&'static str> {
+        self.factories.keys().copied().collect()
+    }
+
+    pub fn resolve(&self, id: &str) -> Result<Box<dyn ExchangeIngestDriver>, String> {
+        let factory = self
+            .factories
+            .get(id)
+            .ok_or_else(|| format!("no ingest driver registered for exchange_id={id}"))?;
+        factory()
+    }
+}
+
+/// Driver backed by a `WsVenueAdapter`: one `run_ws_connection` per planned connection.
+pub struct WsAdapterIngestDriver {
+    id: &'static str,
+    adapter: Arc<dyn WsVenueAdapter>,
+    private: bool,
+}
+
+impl WsAdapterIngestDriver {
+    pub fn new(id: &'static str, adapter: Arc<dyn WsVenueAdapter>) -> Self {
+        Self {
+            id,
+            adapter,
+            private: false,
+        }
+    }
+
+    /// Marks the driver as private WS; `run_ws_ingest` then requires `enable_private_ws`.
+    pub fn private(mut self) -> Self {
+        self.private = true;
+        self
+    }
+
+    pub fn boxed(self) -> Box<dyn ExchangeIngestDriver> {
+        Box::new(self)
+    }
+}
+
+#[async_trait]
+impl ExchangeIngestDriver for WsAdapterIngestDriver {
+    fn exchange_id(&self) -> &'static str {
+        self.id
+    }
+
+    async fn fetch_symbols(&self) -> Result<Vec<String>, String> {
+        self.adapter.fetch_symbols().await
+    }
+
+    async fn run_ws_ingest(
+        &self,
+        plan: IngestPlanRef,
+        runtime: IngestRuntimeRef,
+        rules: IngestRulesRef,
+        cfg: IngestConfigRef,
+    ) -> Result<(), String> {
+        if self.private && !cfg.enable_private_ws {
+            return Err(format!("private ws disabled for exchange_id={}", self.id));
+        }
+        if plan.exchange_id != self.id {
+            return Err(format!(
+                "plan exchange_id={} does not match driver {}",
+                plan.exchange_id, self.id
+            ));
+        }
+
+        // run_ws_connection holds the store across awaits (!Send), so each connection
+        // gets its own thread + current-thread runtime.
+        let mut done = Vec::with_capacity(plan.conn_ids.len());
+        for conn_id in &plan.conn_ids {
+            let (tx, rx) = oneshot::channel::<Result<(), String>>();
+            let adapter = self.adapter.clone();
+            let ws_rules = rules.rules.clone();
+            let wal = runtime.wal.clone();
+            let token = runtime.shutdown.clone();
+            let store_path = runtime.store_path.clone();
+            let on_start = runtime.on_connection_start.clone();
+            let on_exit = runtime.on_connection_exit.clone();
+            let id = conn_id.clone();
+            let run_cfg = WsRunConfig {
+                exchange_id: self.id.to_string(),
+                conn_id: conn_id.clone(),
+                metrics: match &runtime.connection_metrics {
+                    Some(hook) => Some(hook(conn_id)),
+                    None => cfg.run.metrics.clone(),
+                },
+                ..cfg.run.clone()
+            };
+            std::thread::Builder::new()
+                .name(format!("ucel-ingest-{conn_id}"))
+                .spawn(move || {
+                    let result = tokio::runtime::Builder::new_current_thread()
+                        .enable_all()
+                        .build()
+                        .map_err(|e| format!("ingest runtime: {e}"))
+                        .and_then(|rt| {
+                            let mut store = SubscriptionStore::open(&store_path)?;
+                            if let Some(hook) = &on_start {
+                                hook(&id);
+                            }
+                            rt.block_on(run_ws_connection(
+                                adapter, ws_rules, &mut store, wal, run_cfg, token,
+                            ))
+                        });
+                    if let Some(hook) = &on_exit {
+                        hook(&id, &result);
+                    }
+                    let _ = tx.send(result);
+                })
+                .map_err(|e| format!("spawn ingest thread for {conn_id}: {e}"))?;
+            done.push((conn_id.clone(), rx));
+        }
+
+        let mut failed = Vec::new();
+        for (conn_id, rx) in done {
+            let err = match rx.await {
+                Ok(Ok(())) => continue,
+                Ok(Err(e)) => e,
+                Err(_) => "ingest thread panicked".to_string(),
+            };
+            if let Some(hook) = &runtime.on_connection_end {
+                hook(&conn_id, &err);
+            }
+            failed.push(format!("{conn_id}: {err}"));
+        }
+        if failed.is_empty() {
+            Ok(())
+        } else {
+            Err(format!(
+                "{} connection(s) ended with error: {}",
+                failed.len(),
+                failed.join("; ")
+            ))
+        }
+    }
+}
+
+/// Driver for venues that have symbol discovery but no `WsVenueAdapter` yet.
+///
+/// bitbank, bitflyer, bitmex, coinbase, coincheck, deribit, sbivc and upbit register this
+/// driver: `fetch_symbols` works, `run_ws_ingest` always fails. Callers that need a live
+/// feed for those venues still go through their venue crate's own WS client.
+pub struct UnsupportedWsIngestDriver {
+    id: &'static str,
+    symbols: fn() -> Result<Vec<String>, String>,
+}
+
+impl UnsupportedWsIngestDriver {
+    pub fn new(id: &'static str, symbols: fn() -> Result<Vec<String>, String>) -> Self {
+        Self { id, symbols }
+    }
+
+    pub fn boxed(self) -> Box<dyn ExchangeIngestDriver> {
+        Box::new(self)
+    }
+}
+
+#[async_trait]
+impl ExchangeIngestDriver for UnsupportedWsIngestDriver {
+    fn exchange_id(&self) -> &'static str {
+        self.id
+    }
+
+    fn supports_ws_ingest(&self) -> bool {
+        false
+    }
+
+    async fn fetch_symbols(&self) -> Result<Vec<String>, String> {
+        (self.symbols)()
+    }
+
+    async fn run_ws_ingest(
+        &self,
+        _plan: IngestPlanRef,
+        _runtime: IngestRuntimeRef,
+        _rules: IngestRulesRef,
+        _cfg: IngestConfigRef,
+    ) -> Result<(), String> {
+        Err(format!(
+            "ws ingest not supported for exchange_id={} (no WsVenueAdapter)",
+            self.id
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn catalog_symbols() -> Result<Vec<String>, String> {
+        Ok(vec!["BTC_JPY".into()])
+    }
+
+    #[tokio::test]
+    async fn registry_resolves_registered_ids_only() {
+        let mut reg = IngestDriverRegistry::new();
+        reg.register("bitbank", || {
+            Ok(UnsupportedWsIngestDriver::new("bitbank", catalog_symbols).boxed())
+        });
+        assert_eq!(reg.ids(), vec!["bitbank"]);
+
+        let driver = reg.resolve("bitbank").unwrap();
+        assert_eq!(driver.exchange_id(), "bitbank");
+        assert!(!driver.supports_ws_ingest());
+        assert_eq!(driver.fetch_symbols().await.unwrap(), vec!["BTC_JPY"]);
+        assert!(reg.resolve("kraken").is_err());
+    }
+
+    #[test]
+    fn registered_ids_are_sorted_and_unique() {
+        let ids = registered_ingest_driver_ids();
+        let mut sorted = ids.clone();
+        sorted.sort();
+        sorted.dedup();
+        assert_eq!(ids, sorted);
+    }
+}
@@ -1,8 +1,33 @@
+use crate::secrets::SecretString;
 use ucel_chain_ethereum::{
-    build_transaction, estimate_eip1559, get_native_balance, sign_transaction, wait_for_receipt,
-    DeterministicTestSigner, EvmProviderSet, FeePolicy, FinalityPolicy,
+    build_transaction, estimate_eip1559, get_native_balance, send_raw_transaction,
+    sign_transaction, wait_for_receipt, DeterministicTestSigner, EvmProviderSet, EvmSigner,
+    FeePolicy, FinalityPolicy, LocalKeySigner,
 };
-use ucel_core::{EvmAddress, EvmBlockRef, EvmChainId, UcelError};
+use ucel_core::{
+    ErrorCode, EvmAddress, EvmBlockRef, EvmChainId, EvmTransactionRequest, KeyRef,
+    SecretRefResolver, UcelError,
+};
+
+/// hex 秘密鍵を保持した `SecretString` から EVM signer を作る
+pub fn evm_signer_from_secret(secret: &SecretString) -> Result<LocalKeySigner, UcelError> {
+    LocalKeySigner::from_secret_hex(secret.expose())
+}
+
+/// `KeyRef` を解決し、`api_secret` に入った hex 秘密鍵から EVM signer を作る
+pub fn evm_signer_from_key_ref(
+    resolver: &dyn SecretRefResolver,
+    key_ref: &KeyRef,
+) -> Result<LocalKeySigner, UcelError> {
+    let resolved = resolver.resolve(key_ref)?;
+    let secret = resolved.api_secret.map(SecretString::new).ok_or_else(|| {
+        UcelError::new(
+            ErrorCode::MissingAuth,
+            format!("key_ref {} has no private key", key_ref.key_id),
+        )
+    })?;
+    evm_signer_from_secret(&secret)
+}
 
 pub struct ChainFacade {
     pub providers: EvmProviderSet,
@@ -46,6 +71,16 @@ impl ChainFacade {
         Ok(signed.tx_hash)
     }
 
+    /// 署名して eth_sendRawTransaction で送信し、tx hash を返す
+    pub fn sign_and_send(
+        &self,
+        signer: &dyn EvmSigner,
+        tx: &EvmTransactionRequest,
+    ) -> Result<String, UcelError> {
+        let signed = sign_transaction(signer, tx)?;
+        send_raw_transaction(&self.providers, self.chain_id, &signed)
+    }
+
     pub fn wait_receipt_preview(&self, tx_hash: &str) -> Result<bool, UcelError> {
         let receipt = wait_for_receipt(
             &self.providers,
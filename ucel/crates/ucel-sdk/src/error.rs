@@ -1,3 +1,4 @@
+use crate::market_data::normalize::NormalizeError;
 use thiserror::Error;
 
 pub type SdkResult<T> = Result<T, SdkError>;
@@ -18,4 +19,15 @@ pub enum SdkError {
 
     #[error("unknown exchange: {0}")]
     UnknownExchange(String),
+
+    #[error("ingest: {0}")]
+    Ingest(String),
+
+    #[error("market data parse ({exchange}/{channel}): {reason}")]
+    MarketDataParse {
+        exchange: String,
+        channel: String,
+        reason: NormalizeError,
+        raw: String,
+    },
 }
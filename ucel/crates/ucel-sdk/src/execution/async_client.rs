@@ -1,8 +1,14 @@
 use crate::execution::{
-    AuditEvent, AuditReplayFilter, AuditSink, BasicOrderGate, ExecutionMode, ExecutionOutcome,
-    OrderCancel, OrderGate, OrderOpenQuery, OrderReceipt, OrderRequest, OrderStatus,
-    ReconcileReport, SdkExecutionError, SdkExecutionErrorCode, SdkExecutionResult, VenueId,
+    optional_capability, AuditEvent, AuditReplayFilter, AuditSink, BasicOrderGate,
+    ExecutionConnector, ExecutionFill, ExecutionMode, ExecutionOutcome, FillQuery,
+    MarketMetaOrderGate, OrderCancel, OrderGate, OrderManager, OrderOpenQuery, OrderReceipt,
+    OrderRequest, OrderStatus, PaperExecutionVenue, ReconcileReport, Reconciler, RemediationAction,
+    SdkExecutionError, SdkExecutionErrorCode, SdkExecutionResult, ShadowValidator, VenueId,
+    VenueSnapshot,
 };
+use std::sync::Arc;
+use ucel_core::CanonicalBalance;
+use ucel_symbol_store::MarketMetaStore;
 
 pub fn unix_ms_now() -> u64 {
     use std::time::{SystemTime, UNIX_EPOCH};
@@ -25,6 +31,20 @@ pub trait ExecutionConnectorAsync: Send + Sync {
             "reconcile not supported",
         ))
     }
+    /// 約定履歴（reconcile の数量照合に使う）。未対応なら NotSupported。
+    async fn list_fills(&self, _q: &FillQuery) -> SdkExecutionResult<Vec<ExecutionFill>> {
+        Err(SdkExecutionError::new(
+            SdkExecutionErrorCode::NotSupported,
+            "list_fills not supported",
+        ))
+    }
+    /// 残高（reconcile の残高照合に使う）。未対応なら NotSupported。
+    async fn list_balances(&self, _venue: &VenueId) -> SdkExecutionResult<Vec<CanonicalBalance>> {
+        Err(SdkExecutionError::new(
+            SdkExecutionErrorCode::NotSupported,
+            "list_balances not supported",
+        ))
+    }
 }
 
 /// ucel-sdk の "唯一の async 発注出口"。
@@ -33,7 +53,12 @@ pub trait ExecutionConnectorAsync: Send + Sync {
 pub struct ExecutionClientAsync<C: ExecutionConnectorAsync> {
     connector: C,
     gate: Box<dyn OrderGate>,
+    gate_explicit: bool,
     audit: Option<Box<dyn AuditSink>>,
+    paper: Option<Arc<PaperExecutionVenue>>,
+    shadow: ShadowValidator,
+    orders: Option<Arc<OrderManager>>,
+    reconciler: Reconciler,
 }
 
 impl<C: ExecutionConnectorAsync> ExecutionClientAsync<C> {
@@ -41,12 +66,27 @@ impl<C: ExecutionConnectorAsync> ExecutionClientAsync<C> {
         Self {
             connector,
             gate: Box::new(BasicOrderGate),
+            gate_explicit: false,
             audit: None,
+            paper: None,
+            shadow: ShadowValidator::default(),
+            orders: None,
+            reconciler: Reconciler::default(),
         }
     }
 
     pub fn with_gate(mut self, gate: Box<dyn OrderGate>) -> Self {
         self.gate = gate;
+        self.gate_explicit = true;
+        self
+    }
+
+    /// MarketMeta の参照元を差し込む。with_gate で明示していなければ
+    /// 入口の gate は MarketMetaOrderGate（tick/step/min_notional 検証）になる。
+    pub fn with_market_meta(mut self, store: Arc<MarketMetaStore>) -> Self {
+        if !self.gate_explicit {
+            self.gate = Box::new(MarketMetaOrderGate::new(store));
+        }
         self
     }
 
@@ -55,6 +95,31 @@ impl<C: ExecutionConnectorAsync> ExecutionClientAsync<C> {
         self
     }
 
+    /// Paper mode の発注先となるシミュレーション venue を差し込む。
+    pub fn with_paper_venue(mut self, paper: Arc<PaperExecutionVenue>) -> Self {
+        self.paper = Some(paper);
+        self
+    }
+
+    /// Shadow mode の照合に使う参照データを差し込む。
+    pub fn with_shadow_validator(mut self, shadow: ShadowValidator) -> Self {
+        self.shadow = shadow;
+        self
+    }
+
+    /// 自注文台帳（OrderManager）を差し込む。
+    /// Live / Paper（paper venue 設定時）の発注・取消・open orders 取得が自動で反映される。
+    pub fn with_order_manager(mut self, orders: Arc<OrderManager>) -> Self {
+        self.orders = Some(orders);
+        self
+    }
+
+    /// reconcile の照合エンジン（許容値・期待残高）を差し替える。
+    pub fn with_reconciler(mut self, reconciler: Reconciler) -> Self {
+        self.reconciler = reconciler;
+        self
+    }
+
     pub async fn place(&self, mut req: OrderRequest) -> SdkExecutionResult<ExecutionOutcome> {
         // Gate（入口の共通検証）
         self.gate.validate(&req)?;
@@ -83,19 +148,47 @@ impl<C: ExecutionConnectorAsync> ExecutionClientAsync<C> {
             None
         };
 
-        let receipt = match req.mode {
-            ExecutionMode::Paper | ExecutionMode::Shadow => OrderReceipt {
-                venue: req.intent.venue.clone(),
-                symbol: req.intent.symbol.clone(),
-                status: OrderStatus::Accepted,
-                venue_order_id: None,
-                client_order_id: req.intent.tags.get("client_order_id").cloned(),
-                intent_id: req.intent.intent_id.clone(),
-                idempotency: req.idempotency.clone(),
-            },
-            ExecutionMode::Live => self.connector.place_order(&req).await?,
+        // 自注文台帳：実際に venue（paper venue を含む）へ出る注文だけを追跡する
+        let tracked = self.orders.as_deref().filter(|_| match req.mode {
+            ExecutionMode::Live => true,
+            ExecutionMode::Paper => self.paper.is_some(),
+            ExecutionMode::Shadow => false,
+        });
+        if let Some(om) = tracked {
+            if req.intent.tags.contains_key("client_order_id") {
+                om.on_submitted(&req)?;
+            }
+        }
+
+        // fills は paper venue が発注時に即時約定させた分
+        let (receipt, fills) = match (req.mode, self.paper.as_ref()) {
+            (ExecutionMode::Paper, Some(p)) => p.place_order_with_fills(&req)?,
+            (ExecutionMode::Shadow, _) => {
+                (self.shadow.run(&req, self.audit.as_deref())?, Vec::new())
+            }
+            (ExecutionMode::Paper, None) => (
+                OrderReceipt {
+                    venue: req.intent.venue.clone(),
+                    symbol: req.intent.symbol.clone(),
+                    status: OrderStatus::Accepted,
+                    venue_order_id: None,
+                    client_order_id: req.intent.tags.get("client_order_id").cloned(),
+                    intent_id: req.intent.intent_id.clone(),
+                    idempotency: req.idempotency.clone(),
+                },
+                Vec::new(),
+            ),
+            (ExecutionMode::Live, _) => (self.connector.place_order(&req).await?, Vec::new()),
         };
 
+        // venue が受理した後の台帳反映失敗で Err を返すと、呼び出し側は未発注と誤認して再発注しうる。
+        // receipt はそのまま返し、失敗は log と監査に残す（台帳は次の reconcile / open orders で追いつく）
+        let tracking_err = tracked.and_then(|om| {
+            om.on_receipt(&req, &receipt)
+                .and_then(|_| fills.iter().try_for_each(|f| om.apply_fill(f).map(drop)))
+                .err()
+        });
+
         // 監査：結果
         let audit_id_res = if let Some(a) = self.audit.as_ref() {
             a.append(AuditEvent::OrderResult {
@@ -109,6 +202,26 @@ impl<C: ExecutionConnectorAsync> ExecutionClientAsync<C> {
             None
         };
 
+        if let Some(e) = tracking_err {
+            tracing::warn!(
+                venue = %req.intent.venue.0,
+                intent_id = %req.intent.intent_id.0,
+                venue_order_id = ?receipt.venue_order_id,
+                error = %e,
+                "order accepted but order manager tracking failed"
+            );
+            if let Some(a) = self.audit.as_ref() {
+                a.append(AuditEvent::OrderTrackingFailed {
+                    run_id: req.run_id.clone(),
+                    idempotency: req.idempotency.clone(),
+                    intent_id: req.intent.intent_id.clone(),
+                    venue_order_id: receipt.venue_order_id.clone(),
+                    error: e.to_string(),
+                    unix_ms: unix_ms_now(),
+                })?;
+            }
+        }
+
         Ok(ExecutionOutcome {
             receipt,
             audit_event_id: audit_id_res.or(audit_id_req),
@@ -127,7 +240,15 @@ impl<C: ExecutionConnectorAsync> ExecutionClientAsync<C> {
                 unix_ms: now,
             })?;
         }
-        let ok = self.connector.cancel_order(&cancel).await?;
+        let ok = match self.paper.as_ref() {
+            Some(p) if p.has_open_order(&cancel.venue_order_id) => p.cancel_order(&cancel)?,
+            _ => self.connector.cancel_order(&cancel).await?,
+        };
+        if ok {
+            if let Some(om) = self.orders.as_ref() {
+                om.apply_cancel_ack(&cancel.venue, &cancel.venue_order_id)?;
+            }
+        }
         if let Some(a) = self.audit.as_ref() {
             a.append(AuditEvent::CancelResult {
                 run_id: cancel.run_id.clone(),
@@ -141,11 +262,77 @@ impl<C: ExecutionConnectorAsync> ExecutionClientAsync<C> {
     }
 
     pub async fn open_orders(&self, q: OrderOpenQuery) -> SdkExecutionResult<Vec<OrderReceipt>> {
-        self.connector.list_open_orders(&q).await
+        let open = self.connector.list_open_orders(&q).await?;
+        if let Some(om) = self.orders.as_ref() {
+            om.apply_open_orders(&q.venue, q.symbol.as_ref(), &open)?;
+        }
+        Ok(open)
     }
 
+    /// reconcile（照合）
+    /// - audit 設定時: 監査ログと venue の open orders / fills / balances を照合エンジンで突き合わせ、
+    ///   connector 固有の reconcile（対応していれば）の結果も取り込む
+    /// - audit 未設定時: connector の reconcile に委譲する
     pub async fn reconcile(&self, venue: &VenueId) -> SdkExecutionResult<ReconcileReport> {
-        let mut r = self.connector.reconcile(venue).await?;
+        self.reconcile_run(venue, None).await
+    }
+
+    /// run_id を指定した reconcile（その run の監査履歴だけを照合対象にする）。
+    /// 指定時は追跡外の venue 注文を orphan 扱いしない（他の run・手動注文を取り消さないため）
+    pub async fn reconcile_run(
+        &self,
+        venue: &VenueId,
+        run_id: Option<&str>,
+    ) -> SdkExecutionResult<ReconcileReport> {
+        let mut r = match self.audit.as_ref() {
+            Some(a) => {
+                let history = a
+                    .replay(AuditReplayFilter {
+                        run_id: run_id.map(str::to_string),
+                        venue: None,
+                        intent_id: None,
+                        idempotency: None,
+                        since_unix_ms: None,
+                        until_unix_ms: None,
+                    })
+                    .map_err(|e| {
+                        SdkExecutionError::new(
+                            SdkExecutionErrorCode::ReconcileFailure,
+                            "reconcile: audit replay failed",
+                        )
+                        .with_source(e)
+                    })?;
+                let snapshot = VenueSnapshot {
+                    open_orders: self
+                        .connector
+                        .list_open_orders(&OrderOpenQuery {
+                            venue: venue.clone(),
+                            symbol: None,
+                        })
+                        .await?,
+                    fills: optional_capability(
+                        self.connector
+                            .list_fills(&FillQuery {
+                                venue: venue.clone(),
+                                symbol: None,
+                                since_unix_ms: self.reconciler.fills_since(venue, &history),
+                            })
+                            .await,
+                    )?,
+                    balances: optional_capability(self.connector.list_balances(venue).await)?,
+                };
+                let mut report = match run_id {
+                    Some(_) => self.reconciler.reconcile_run(venue, &history, &snapshot),
+                    None => self.reconciler.reconcile(venue, &history, &snapshot),
+                };
+                if let Some(extra) = optional_capability(self.connector.reconcile(venue).await)? {
+                    report.merge(extra);
+                }
+                report
+            }
+            None => self.connector.reconcile(venue).await?,
+        };
+        // source は venue が返したものを尊重しつつ、最低限埋める
         if r.generated_at_unix_ms == 0 {
             r.generated_at_unix_ms = unix_ms_now();
         }
@@ -158,6 +345,60 @@ impl<C: ExecutionConnectorAsync> ExecutionClientAsync<C> {
         Ok(r)
     }
 
+    /// reconcile の RemediationAction::ApplyVenueFills を実行する。
+    /// venue の約定一覧から該当 fill を取り直し、AuditEvent::Fill として監査へ追記し
+    /// （Live 注文の約定はここでしか監査に入らない）、OrderManager 設定時はそちらにも反映する。
+    /// 取り込んだ fill を返す。以後の reconcile ではその注文の QtyDrift は出なくなる。
+    pub async fn apply_venue_fills(
+        &self,
+        report: &ReconcileReport,
+    ) -> SdkExecutionResult<Vec<ExecutionFill>> {
+        if !report
+            .actions
+            .iter()
+            .any(|a| matches!(a, RemediationAction::ApplyVenueFills { .. }))
+        {
+            return Ok(Vec::new());
+        }
+        let a = self.audit.as_ref().ok_or_else(|| {
+            SdkExecutionError::new(
+                SdkExecutionErrorCode::ReconcileFailure,
+                "apply_venue_fills: audit sink not configured",
+            )
+        })?;
+        let venue = &report.venue;
+        let history = a.replay(AuditReplayFilter {
+            run_id: None,
+            venue: None,
+            intent_id: None,
+            idempotency: None,
+            since_unix_ms: None,
+            until_unix_ms: None,
+        })?;
+        let venue_fills = self
+            .connector
+            .list_fills(&FillQuery {
+                venue: venue.clone(),
+                symbol: None,
+                since_unix_ms: self.reconciler.fills_since(venue, &history),
+            })
+            .await?;
+        let mut applied = Vec::new();
+        for ev in self
+            .reconciler
+            .venue_fill_events(venue, &history, &venue_fills, &report.actions)
+        {
+            a.append(ev.clone())?;
+            if let AuditEvent::Fill { fill, .. } = ev {
+                if let Some(om) = self.orders.as_ref() {
+                    om.apply_fill(&fill)?;
+                }
+                applied.push(fill);
+            }
+        }
+        Ok(applied)
+    }
+
     pub fn replay(&self, filter: AuditReplayFilter) -> SdkExecutionResult<Vec<AuditEvent>> {
         let a = self.audit.as_ref().ok_or_else(|| {
             SdkExecutionError::new(
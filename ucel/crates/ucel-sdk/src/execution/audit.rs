@@ -17,6 +17,15 @@ pub enum AuditEvent {
         receipt: crate::execution::OrderReceipt,
         unix_ms: u64,
     },
+    /// venue は受理したが自注文台帳への反映に失敗した（receipt は OrderResult に残る）
+    OrderTrackingFailed {
+        run_id: Option<String>,
+        idempotency: crate::execution::IdempotencyKey,
+        intent_id: crate::execution::OrderIntentId,
+        venue_order_id: Option<String>,
+        error: String,
+        unix_ms: u64,
+    },
     CancelRequested {
         run_id: Option<String>,
         idempotency: crate::execution::IdempotencyKey,
@@ -36,6 +45,25 @@ pub enum AuditEvent {
         venue: crate::execution::VenueId,
         report: crate::execution::ReconcileReport,
     },
+    Fill {
+        run_id: Option<String>,
+        fill: crate::execution::ExecutionFill,
+    },
+    OrderStatusChanged {
+        run_id: Option<String>,
+        venue: crate::execution::VenueId,
+        symbol: crate::execution::Symbol,
+        venue_order_id: String,
+        client_order_id: Option<String>,
+        status: crate::execution::OrderStatus,
+        filled_qty: crate::execution::Quantity,
+        unix_ms: u64,
+    },
+    ShadowVerdict {
+        run_id: Option<String>,
+        idempotency: crate::execution::IdempotencyKey,
+        verdict: crate::execution::ShadowVerdict,
+    },
 }
 
 /// AuditSink は「監査の唯一の差し込み口」
@@ -55,6 +83,18 @@ pub struct AuditReplayFilter {
     pub until_unix_ms: Option<u64>,
 }
 
+/// Arc で共有した sink をそのまま差し込めるようにする
+/// （ExecutionClient と Paper venue が同じ監査ストリームへ書く用途）。
+impl<T: AuditSink + ?Sized> AuditSink for std::sync::Arc<T> {
+    fn append(&self, event: AuditEvent) -> SdkExecutionResult<Option<String>> {
+        (**self).append(event)
+    }
+
+    fn replay(&self, filter: AuditReplayFilter) -> SdkExecutionResult<Vec<AuditEvent>> {
+        (**self).replay(filter)
+    }
+}
+
 /// テスト用の in-memory 実装（本番は次タスクで WAL/永続化へ）
 pub struct InMemoryAuditSink {
     events: std::sync::Mutex<Vec<(Option<String>, AuditEvent)>>,
@@ -95,8 +135,16 @@ impl AuditSink for InMemoryAuditSink {
                 let ok = match ev {
                     AuditEvent::OrderRequested { run_id: r, .. } => r.as_deref() == Some(run_id),
                     AuditEvent::OrderResult { run_id: r, .. } => r.as_deref() == Some(run_id),
+                    AuditEvent::OrderTrackingFailed { run_id: r, .. } => {
+                        r.as_deref() == Some(run_id)
+                    }
                     AuditEvent::CancelRequested { run_id: r, .. } => r.as_deref() == Some(run_id),
                     AuditEvent::CancelResult { run_id: r, .. } => r.as_deref() == Some(run_id),
+                    AuditEvent::Fill { run_id: r, .. } => r.as_deref() == Some(run_id),
+                    AuditEvent::OrderStatusChanged { run_id: r, .. } => {
+                        r.as_deref() == Some(run_id)
+                    }
+                    AuditEvent::ShadowVerdict { run_id: r, .. } => r.as_deref() == Some(run_id),
                     AuditEvent::ReconcileResult { .. } => false,
                 };
                 if !ok {
@@ -0,0 +1,722 @@
+use crate::execution::{
+    unix_ms_now, AuditEvent, AuditReplayFilter, AuditSink, FileAuditSink, SdkExecutionError,
+    SdkExecutionErrorCode, SdkExecutionResult,
+};
+use hmac::{Hmac, Mac};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::fs::{self, File, OpenOptions};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+
+/// 最初のレコードの prev_hash_hex（genesis）
+pub const AUDIT_CHAIN_GENESIS_HASH: &str =
+    "0000000000000000000000000000000000000000000000000000000000000000";
+
+const SEGMENT_PREFIX: &str = "audit-";
+const SEGMENT_SUFFIX: &str = ".ndjson";
+const MANIFEST_SUFFIX: &str = ".manifest.json";
+const HEAD_FILE: &str = "audit-head.json";
+
+/// ChainedFileAuditSink の設定
+#[derive(Clone)]
+pub struct ChainedAuditConfig {
+    /// セグメントと manifest を置くディレクトリ
+    pub dir: PathBuf,
+    /// 1 セグメントあたりの最大レコード数（超えたら封印して次のセグメントへ）
+    pub max_segment_records: u64,
+    /// manifest 署名鍵（HMAC-SHA256）。None なら manifest は無署名。
+    pub signing_key: Option<Vec<u8>>,
+    /// true にすると append ごとに fsync を行う
+    pub fsync_each_append: bool,
+    /// open 時、書き込み途中でクラッシュした末尾行（改行なし）を切り詰める
+    pub truncate_torn_tail: bool,
+}
+
+impl ChainedAuditConfig {
+    pub fn new(dir: impl Into<PathBuf>) -> Self {
+        Self {
+            dir: dir.into(),
+            max_segment_records: 100_000,
+            signing_key: None,
+            fsync_each_append: false,
+            truncate_torn_tail: true,
+        }
+    }
+}
+
+/// 1 行分のレコード。this_hash_hex は他フィールドから再計算できる。
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct ChainedAuditRecord {
+    pub seq: u64,
+    pub unix_ms: u64,
+    pub prev_hash_hex: String,
+    pub this_hash_hex: String,
+    pub event: serde_json::Value,
+}
+
+/// 封印済みセグメントの manifest
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct AuditSegmentManifest {
+    pub segment: String,
+    pub first_seq: u64,
+    pub last_seq: u64,
+    pub records: u64,
+    /// セグメント先頭レコードの prev_hash_hex（直前セグメントの末尾ハッシュ）
+    pub prev_hash_hex: String,
+    pub last_hash_hex: String,
+    /// セグメントファイル全体の SHA-256
+    pub file_sha256_hex: String,
+    pub sealed_at_unix_ms: u64,
+    /// HMAC-SHA256（signature_hex 以外のフィールドの canonical JSON に対して）
+    pub signature_hex: Option<String>,
+}
+
+/// チェーン末尾の checkpoint（append ごとに tmp → rename で置き換える）。
+/// 封印前の書き込み中セグメントは manifest を持たないため、末尾の切り詰めや
+/// 末尾セグメントの削除はこの署名付き head と突き合わせて検出する。
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct AuditChainHead {
+    pub last_seq: u64,
+    pub last_hash_hex: String,
+    pub updated_at_unix_ms: u64,
+    /// HMAC-SHA256（signature_hex 以外のフィールドの canonical JSON に対して）
+    pub signature_hex: Option<String>,
+}
+
+/// チェーンが壊れている理由
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ChainBreakKind {
+    /// JSON として読めない行
+    Unparseable,
+    /// 末尾が改行で終わっていない（書き込み途中のクラッシュ）
+    TornTail,
+    /// seq の欠番・重複（行の削除や挿入）
+    SeqGap { expected: u64, found: u64 },
+    /// prev_hash_hex が直前レコードのハッシュと一致しない
+    PrevHashMismatch { expected: String, found: String },
+    /// レコード内容から再計算したハッシュが一致しない（改ざん）
+    HashMismatch { expected: String, found: String },
+    /// 封印済みのはずのセグメントに manifest が無い
+    ManifestMissing,
+    /// manifest の署名が無い・一致しない
+    ManifestSignatureInvalid,
+    /// manifest とセグメント内容が一致しない
+    ManifestMismatch { field: String },
+    /// セグメントがあるのに head checkpoint が無い
+    HeadMissing,
+    /// head checkpoint の署名が無い・一致しない
+    HeadSignatureInvalid,
+    /// head が指すレコードまでチェーンが残っていない（末尾の切り詰め・末尾セグメントの削除）
+    TailTruncated {
+        expected_last_seq: u64,
+        found_last_seq: u64,
+    },
+    /// head とチェーン末尾が一致しない
+    HeadMismatch { field: String },
+}
+
+/// 最初に見つかった破断点
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ChainBreak {
+    pub segment: String,
+    /// 1 始まりの行番号（manifest 起因なら 0）
+    pub line: usize,
+    pub seq: Option<u64>,
+    pub kind: ChainBreakKind,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ChainVerifyReport {
+    pub ok: bool,
+    pub segments: usize,
+    pub records: u64,
+    pub last_seq: u64,
+    pub last_hash_hex: String,
+    pub first_broken: Option<ChainBreak>,
+}
+
+fn io_err(code: SdkExecutionErrorCode, what: &str, e: std::io::Error) -> SdkExecutionError {
+    SdkExecutionError::new(code, format!("{what}: {e}")).with_source(e)
+}
+
+fn segment_name(index: u64) -> String {
+    format!("{SEGMENT_PREFIX}{index:08}{SEGMENT_SUFFIX}")
+}
+
+fn manifest_path(dir: &Path, segment: &str) -> PathBuf {
+    dir.join(format!(
+        "{}{MANIFEST_SUFFIX}",
+        segment.trim_end_matches(SEGMENT_SUFFIX)
+    ))
+}
+
+/// dir 内のセグメントを index 昇順で列挙する
+fn list_segments(dir: &Path) -> SdkExecutionResult<Vec<(u64, String)>> {
+    if !dir.exists() {
+        return Ok(vec![]);
+    }
+    let mut out = vec![];
+    let rd = fs::read_dir(dir)
+        .map_err(|e| io_err(SdkExecutionErrorCode::ReplayFailure, "read audit dir", e))?;
+    for entry in rd.flatten() {
+        let name = entry.file_name().to_string_lossy().to_string();
+        let Some(idx) = name
+            .strip_prefix(SEGMENT_PREFIX)
+            .and_then(|s| s.strip_suffix(SEGMENT_SUFFIX))
+            .and_then(|s| s.parse::<u64>().ok())
+        else {
+            continue;
+        };
+        out.push((idx, name));
+    }
+    out.sort();
+    Ok(out)
+}
+
+/// レコードのハッシュ。event は canonical JSON（キー昇順）で連結する。
+pub fn audit_record_hash(
+    prev_hash_hex: &str,
+    seq: u64,
+    unix_ms: u64,
+    event: &serde_json::Value,
+) -> String {
+    let canon = serde_json::to_vec(event).unwrap_or_default();
+    let mut h = Sha256::new();
+    h.update(prev_hash_hex.as_bytes());
+    h.update(b"|");
+    h.update(seq.to_string().as_bytes());
+    h.update(b"|");
+    h.update(unix_ms.to_string().as_bytes());
+    h.update(b"|");
+    h.update(&canon);
+    hex::encode(h.finalize())
+}
+
+/// signature_hex を None にした値の canonical JSON に対する HMAC
+fn unsigned_mac<T: Serialize>(key: &[u8], unsigned: &T) -> Hmac<Sha256> {
+    let canon =
+        serde_json::to_vec(&serde_json::to_value(unsigned).unwrap_or_default()).unwrap_or_default();
+    // HMAC は任意長の鍵を受け付ける
+    let mut mac = <Hmac<Sha256> as Mac>::new_from_slice(key).expect("hmac accepts any key length");
+    mac.update(&canon);
+    mac
+}
+
+fn verify_mac<T: Serialize>(key: &[u8], unsigned: &T, signature_hex: Option<&str>) -> bool {
+    let Some(sig) = signature_hex.and_then(|s| hex::decode(s).ok()) else {
+        return false;
+    };
+    unsigned_mac(key, unsigned).verify_slice(&sig).is_ok()
+}
+
+fn manifest_mac(key: &[u8], m: &AuditSegmentManifest) -> Hmac<Sha256> {
+    let mut unsigned = m.clone();
+    unsigned.signature_hex = None;
+    unsigned_mac(key, &unsigned)
+}
+
+fn sign_manifest(key: &[u8], m: &AuditSegmentManifest) -> String {
+    hex::encode(manifest_mac(key, m).finalize().into_bytes())
+}
+
+fn verify_manifest_signature(key: &[u8], m: &AuditSegmentManifest) -> bool {
+    let mut unsigned = m.clone();
+    unsigned.signature_hex = None;
+    verify_mac(key, &unsigned, m.signature_hex.as_deref())
+}
+
+fn sign_head(key: &[u8], h: &AuditChainHead) -> String {
+    let mut unsigned = h.clone();
+    unsigned.signature_hex = None;
+    hex::encode(unsigned_mac(key, &unsigned).finalize().into_bytes())
+}
+
+fn verify_head_signature(key: &[u8], h: &AuditChainHead) -> bool {
+    let mut unsigned = h.clone();
+    unsigned.signature_hex = None;
+    verify_mac(key, &unsigned, h.signature_hex.as_deref())
+}
+
+fn read_head(dir: &Path) -> Option<AuditChainHead> {
+    fs::read(dir.join(HEAD_FILE))
+        .ok()
+        .and_then(|b| serde_json::from_slice::<AuditChainHead>(&b).ok())
+}
+
+/// head checkpoint を tmp → rename で原子的に置き換える
+fn write_head(
+    cfg: &ChainedAuditConfig,
+    last_seq: u64,
+    last_hash_hex: &str,
+) -> SdkExecutionResult<()> {
+    let mut h = AuditChainHead {
+        last_seq,
+        last_hash_hex: last_hash_hex.to_string(),
+        updated_at_unix_ms: unix_ms_now(),
+        signature_hex: None,
+    };
+    if let Some(key) = cfg.signing_key.as_deref() {
+        h.signature_hex = Some(sign_head(key, &h));
+    }
+    let json = serde_json::to_vec(&h).map_err(|e| {
+        SdkExecutionError::new(
+            SdkExecutionErrorCode::AuditFailure,
+            format!("head encode failed: {e}"),
+        )
+    })?;
+    let path = cfg.dir.join(HEAD_FILE);
+    let tmp = path.with_extension("json.tmp");
+    let mut f = File::create(&tmp)
+        .map_err(|e| io_err(SdkExecutionErrorCode::AuditFailure, "write head", e))?;
+    f.write_all(&json)
+        .map_err(|e| io_err(SdkExecutionErrorCode::AuditFailure, "write head", e))?;
+    if cfg.fsync_each_append {
+        f.sync_data()
+            .map_err(|e| io_err(SdkExecutionErrorCode::AuditFailure, "fsync head", e))?;
+    }
+    fs::rename(&tmp, &path)
+        .map_err(|e| io_err(SdkExecutionErrorCode::AuditFailure, "write head", e))
+}
+
+/// チェーン末尾の状態（open / verify の副産物）
+#[derive(Clone, Debug)]
+struct ChainTail {
+    /// 書き込み中セグメントの index
+    active_index: u64,
+    active_records: u64,
+    last_seq: u64,
+    last_hash_hex: String,
+}
+
+struct Scan {
+    report: ChainVerifyReport,
+    tail: ChainTail,
+    /// 末尾行が切れていた場合の (セグメントパス, 正常部分の長さ)
+    torn: Option<(PathBuf, u64)>,
+}
+
+/// dir のチェーン全体を先頭から検証する
+fn scan_chain(dir: &Path, signing_key: Option<&[u8]>) -> SdkExecutionResult<Scan> {
+    let segments = list_segments(dir)?;
+    let head = read_head(dir);
+    // head が指す seq のレコードハッシュ（seq 0 は genesis）
+    let mut hash_at_head = head
+        .as_ref()
+        .filter(|h| h.last_seq == 0)
+        .map(|_| AUDIT_CHAIN_GENESIS_HASH.to_string());
+    let mut expected_seq = 1u64;
+    let mut prev_hash = AUDIT_CHAIN_GENESIS_HASH.to_string();
+    let mut records = 0u64;
+    let mut first_broken: Option<ChainBreak> = None;
+    let mut torn = None;
+    let mut active_records = 0u64;
+
+    let brk = |segment: &str, line: usize, seq: Option<u64>, kind: ChainBreakKind| ChainBreak {
+        segment: segment.to_string(),
+        line,
+        seq,
+        kind,
+    };
+
+    'segments: for (pos, (_idx, name)) in segments.iter().enumerate() {
+        let path = dir.join(name);
+        let bytes = fs::read(&path).map_err(|e| {
+            io_err(
+                SdkExecutionErrorCode::ReplayFailure,
+                "read audit segment",
+                e,
+            )
+        })?;
+        let is_last = pos + 1 == segments.len();
+        let seg_prev_hash = prev_hash.clone();
+        let seg_first_seq = expected_seq;
+        let mut seg_records = 0u64;
+
+        let complete_len = match bytes.iter().rposition(|b| *b == b'\n') {
+            Some(i) => i + 1,
+            None => 0,
+        };
+        let text = String::from_utf8_lossy(&bytes[..complete_len]);
+        for (i, line) in text.lines().enumerate() {
+            if line.trim().is_empty() {
+                continue;
+            }
+            let Ok(rec) = serde_json::from_str::<ChainedAuditRecord>(line) else {
+                first_broken = Some(brk(name, i + 1, None, ChainBreakKind::Unparseable));
+                break 'segments;
+            };
+            if rec.seq != expected_seq {
+                first_broken = Some(brk(
+                    name,
+                    i + 1,
+                    Some(rec.seq),
+                    ChainBreakKind::SeqGap {
+                        expected: expected_seq,
+                        found: rec.seq,
+                    },
+                ));
+                break 'segments;
+            }
+            if rec.prev_hash_hex != prev_hash {
+                first_broken = Some(brk(
+                    name,
+                    i + 1,
+                    Some(rec.seq),
+                    ChainBreakKind::PrevHashMismatch {
+                        expected: prev_hash.clone(),
+                        found: rec.prev_hash_hex,
+                    },
+                ));
+                break 'segments;
+            }
+            let h = audit_record_hash(&rec.prev_hash_hex, rec.seq, rec.unix_ms, &rec.event);
+            if h != rec.this_hash_hex {
+                first_broken = Some(brk(
+                    name,
+                    i + 1,
+                    Some(rec.seq),
+                    ChainBreakKind::HashMismatch {
+                        expected: h,
+                        found: rec.this_hash_hex,
+                    },
+                ));
+                break 'segments;
+            }
+            if head.as_ref().is_some_and(|h| h.last_seq == rec.seq) {
+                hash_at_head = Some(rec.this_hash_hex.clone());
+            }
+            prev_hash = rec.this_hash_hex;
+            expected_seq += 1;
+            records += 1;
+            seg_records += 1;
+        }
+
+        if complete_len < bytes.len() {
+            if is_last {
+                torn = Some((path.clone(), complete_len as u64));
+            }
+            first_broken = Some(brk(
+                name,
+                text.lines().count() + 1,
+                None,
+                ChainBreakKind::TornTail,
+            ));
+            break 'segments;
+        }
+
+        // 封印済みセグメント（最後以外）は manifest と照合する
+        if !is_last {
+            let mpath = manifest_path(dir, name);
+            let manifest = fs::read(&mpath)
+                .ok()
+                .and_then(|b| serde_json::from_slice::<AuditSegmentManifest>(&b).ok());
+            let Some(m) = manifest else {
+                first_broken = Some(brk(name, 0, None, ChainBreakKind::ManifestMissing));
+                break 'segments;
+            };
+            if let Some(key) = signing_key {
+                if !verify_manifest_signature(key, &m) {
+                    first_broken =
+                        Some(brk(name, 0, None, ChainBreakKind::ManifestSignatureInvalid));
+                    break 'segments;
+                }
+            }
+            let file_hash = hex::encode(Sha256::digest(&bytes));
+            let mismatch = [
+                ("segment", m.segment == *name),
+                ("first_seq", m.first_seq == seg_first_seq),
+                ("last_seq", m.last_seq + 1 == expected_seq),
+                ("records", m.records == seg_records),
+                ("prev_hash_hex", m.prev_hash_hex == seg_prev_hash),
+                ("last_hash_hex", m.last_hash_hex == prev_hash),
+                ("file_sha256_hex", m.file_sha256_hex == file_hash),
+            ]
+            .into_iter()
+            .find(|(_, ok)| !ok);
+            if let Some((field, _)) = mismatch {
+                first_broken = Some(brk(
+                    name,
+                    0,
+                    None,
+                    ChainBreakKind::ManifestMismatch {
+                        field: field.to_string(),
+                    },
+                ));
+                break 'segments;
+            }
+        } else {
+            active_records = seg_records;
+        }
+    }
+
+    // 書き込み中セグメントは manifest を持たないので、末尾は署名付き head と照合する。
+    // head は行の追記の後に更新するため、クラッシュ時に head より 1 件だけ先へ進んでいるのは許容する。
+    if first_broken.is_none() {
+        if let Some((_, name)) = segments.last() {
+            let last_seq = expected_seq - 1;
+            let kind = match head.as_ref() {
+                None => Some(ChainBreakKind::HeadMissing),
+                Some(h) if signing_key.is_some_and(|k| !verify_head_signature(k, h)) => {
+                    Some(ChainBreakKind::HeadSignatureInvalid)
+                }
+                Some(h) if last_seq < h.last_seq => Some(ChainBreakKind::TailTruncated {
+                    expected_last_seq: h.last_seq,
+                    found_last_seq: last_seq,
+                }),
+                Some(h) if last_seq > h.last_seq + 1 => Some(ChainBreakKind::HeadMismatch {
+                    field: "last_seq".to_string(),
+                }),
+                Some(h) if hash_at_head.as_deref() != Some(h.last_hash_hex.as_str()) => {
+                    Some(ChainBreakKind::HeadMismatch {
+                        field: "last_hash_hex".to_string(),
+                    })
+                }
+                Some(_) => None,
+            };
+            first_broken = kind.map(|kind| brk(name, 0, None, kind));
+        }
+    }
+
+    let active_index = segments.last().map(|(i, _)| *i).unwrap_or(1);
+    Ok(Scan {
+        report: ChainVerifyReport {
+            ok: first_broken.is_none(),
+            segments: segments.len(),
+            records,
+            last_seq: expected_seq - 1,
+            last_hash_hex: prev_hash.clone(),
+            first_broken,
+        },
+        tail: ChainTail {
+            active_index,
+            active_records,
+            last_seq: expected_seq - 1,
+            last_hash_hex: prev_hash,
+        },
+        torn,
+    })
+}
+
+/// dir のチェーンを検証する（sink を開かずに使える）
+pub fn verify_audit_chain(
+    dir: &Path,
+    signing_key: Option<&[u8]>,
+) -> SdkExecutionResult<ChainVerifyReport> {
+    Ok(scan_chain(dir, signing_key)?.report)
+}
+
+/// 改ざん検知付きの永続監査（hash chain）。
+/// - 各レコードは直前レコードの SHA-256（prev_hash_hex）を持つ
+/// - max_segment_records ごとにセグメントを封印し、署名付き manifest を書く
+/// - append ごとに署名付き head checkpoint（最終 seq とハッシュ）を更新し、末尾の切り詰めを検出する
+/// - open 時にチェーン全体を検証し、壊れていれば開かない
+/// - verify() で最初の破断点を報告する
+pub struct ChainedFileAuditSink {
+    cfg: ChainedAuditConfig,
+    tail: Mutex<ChainTail>,
+}
+
+impl ChainedFileAuditSink {
+    /// ディレクトリを開き、チェーンを検証する。
+    /// 書き込み途中の末尾行は truncate_torn_tail が true なら切り詰める。それ以外の破断はエラー。
+    pub fn open(cfg: ChainedAuditConfig) -> SdkExecutionResult<Self> {
+        fs::create_dir_all(&cfg.dir)
+            .map_err(|e| io_err(SdkExecutionErrorCode::AuditFailure, "create audit dir", e))?;
+        let mut scan = scan_chain(&cfg.dir, cfg.signing_key.as_deref())?;
+
+        if let (Some((path, len)), true) = (scan.torn.take(), cfg.truncate_torn_tail) {
+            let f = OpenOptions::new()
+                .write(true)
+                .open(&path)
+                .map_err(|e| io_err(SdkExecutionErrorCode::AuditFailure, "open torn segment", e))?;
+            f.set_len(len).map_err(|e| {
+                io_err(
+                    SdkExecutionErrorCode::AuditFailure,
+                    "truncate torn segment",
+                    e,
+                )
+            })?;
+            tracing::warn!(segment = %path.display(), "audit chain: truncated torn tail");
+            scan = scan_chain(&cfg.dir, cfg.signing_key.as_deref())?;
+        }
+
+        if let Some(b) = scan.report.first_broken.as_ref() {
+            return Err(SdkExecutionError::new(
+                SdkExecutionErrorCode::AuditFailure,
+                format!(
+                    "audit chain broken at {} line {}: {:?}",
+                    b.segment, b.line, b.kind
+                ),
+            ));
+        }
+        // クラッシュで head が 1 件遅れていた場合に追いつかせる
+        write_head(&cfg, scan.tail.last_seq, &scan.tail.last_hash_hex)?;
+        Ok(Self {
+            cfg,
+            tail: Mutex::new(scan.tail),
+        })
+    }
+
+    pub fn dir(&self) -> &Path {
+        &self.cfg.dir
+    }
+
+    /// チェーン全体を検証する
+    pub fn verify(&self) -> SdkExecutionResult<ChainVerifyReport> {
+        let _g = self.lock()?;
+        verify_audit_chain(&self.cfg.dir, self.cfg.signing_key.as_deref())
+    }
+
+    fn lock(&self) -> SdkExecutionResult<std::sync::MutexGuard<'_, ChainTail>> {
+        self.tail.lock().map_err(|_| {
+            SdkExecutionError::new(SdkExecutionErrorCode::AuditFailure, "audit lock poisoned")
+        })
+    }
+
+    /// 書き込み中セグメントを封印し、manifest を書いて次のセグメントへ進む
+    fn seal_active(&self, tail: &mut ChainTail) -> SdkExecutionResult<()> {
+        let name = segment_name(tail.active_index);
+        let path = self.cfg.dir.join(&name);
+        let bytes = fs::read(&path)
+            .map_err(|e| io_err(SdkExecutionErrorCode::AuditFailure, "read segment", e))?;
+        let first = serde_json::from_slice::<ChainedAuditRecord>(
+            bytes.split(|b| *b == b'\n').next().unwrap_or_default(),
+        )
+        .map_err(|e| {
+            SdkExecutionError::new(
+                SdkExecutionErrorCode::AuditFailure,
+                format!("seal: first record unreadable: {e}"),
+            )
+        })?;
+
+        let mut m = AuditSegmentManifest {
+            segment: name.clone(),
+            first_seq: first.seq,
+            last_seq: tail.last_seq,
+            records: tail.active_records,
+            prev_hash_hex: first.prev_hash_hex,
+            last_hash_hex: tail.last_hash_hex.clone(),
+            file_sha256_hex: hex::encode(Sha256::digest(&bytes)),
+            sealed_at_unix_ms: unix_ms_now(),
+            signature_hex: None,
+        };
+        if let Some(key) = self.cfg.signing_key.as_deref() {
+            m.signature_hex = Some(sign_manifest(key, &m));
+        }
+        let json = serde_json::to_vec_pretty(&m).map_err(|e| {
+            SdkExecutionError::new(
+                SdkExecutionErrorCode::AuditFailure,
+                format!("manifest encode failed: {e}"),
+            )
+        })?;
+        // manifest は tmp → rename で原子的に置く
+        let mpath = manifest_path(&self.cfg.dir, &name);
+        let tmp = mpath.with_extension("json.tmp");
+        fs::write(&tmp, json)
+            .and_then(|_| fs::rename(&tmp, &mpath))
+            .map_err(|e| io_err(SdkExecutionErrorCode::AuditFailure, "write manifest", e))?;
+
+        tail.active_index += 1;
+        tail.active_records = 0;
+        Ok(())
+    }
+}
+
+impl AuditSink for ChainedFileAuditSink {
+    fn append(&self, event: AuditEvent) -> SdkExecutionResult<Option<String>> {
+        let mut tail = self.lock()?;
+        if tail.active_records >= self.cfg.max_segment_records.max(1) {
+            self.seal_active(&mut tail)?;
+        }
+
+        let event = serde_json::to_value(&event).map_err(|e| {
+            SdkExecutionError::new(
+                SdkExecutionErrorCode::AuditFailure,
+                format!("audit encode failed: {e}"),
+            )
+        })?;
+        let seq = tail.last_seq + 1;
+        let unix_ms = unix_ms_now();
+        let this_hash_hex = audit_record_hash(&tail.last_hash_hex, seq, unix_ms, &event);
+        let rec = ChainedAuditRecord {
+            seq,
+            unix_ms,
+            prev_hash_hex: tail.last_hash_hex.clone(),
+            this_hash_hex: this_hash_hex.clone(),
+            event,
+        };
+        let mut line = serde_json::to_vec(&rec).map_err(|e| {
+            SdkExecutionError::new(
+                SdkExecutionErrorCode::AuditFailure,
+                format!("audit encode failed: {e}"),
+            )
+        })?;
+        line.push(b'\n');
+
+        let path = self.cfg.dir.join(segment_name(tail.active_index));
+        let mut f = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&path)
+            .map_err(|e| io_err(SdkExecutionErrorCode::AuditFailure, "open audit segment", e))?;
+        // 1 回の write で行全体を書く（途中で落ちても torn tail として検出できる）
+        f.write_all(&line)
+            .map_err(|e| io_err(SdkExecutionErrorCode::AuditFailure, "write failed", e))?;
+        if self.cfg.fsync_each_append {
+            f.sync_data()
+                .map_err(|e| io_err(SdkExecutionErrorCode::AuditFailure, "fsync failed", e))?;
+        }
+
+        tail.last_seq = seq;
+        tail.last_hash_hex = this_hash_hex;
+        tail.active_records += 1;
+        write_head(&self.cfg, tail.last_seq, &tail.last_hash_hex)?;
+        Ok(Some(seq.to_string()))
+    }
+
+    fn replay(&self, filter: AuditReplayFilter) -> SdkExecutionResult<Vec<AuditEvent>> {
+        let _g = self.lock()?;
+        let mut out = vec![];
+        for (_, name) in list_segments(&self.cfg.dir)? {
+            let f = File::open(self.cfg.dir.join(&name)).map_err(|e| {
+                io_err(
+                    SdkExecutionErrorCode::ReplayFailure,
+                    "open audit segment",
+                    e,
+                )
+            })?;
+            let recs = serde_json::Deserializer::from_reader(std::io::BufReader::new(f))
+                .into_iter::<ChainedAuditRecord>();
+            for rec in recs {
+                let rec = rec.map_err(|e| {
+                    SdkExecutionError::new(
+                        SdkExecutionErrorCode::ReplayFailure,
+                        format!("audit record unreadable in {name}: {e}"),
+                    )
+                })?;
+                if let Some(since) = filter.since_unix_ms {
+                    if rec.unix_ms < since {
+                        continue;
+                    }
+                }
+                if let Some(until) = filter.until_unix_ms {
+                    if rec.unix_ms > until {
+                        continue;
+                    }
+                }
+                let ev = serde_json::from_value::<AuditEvent>(rec.event).map_err(|e| {
+                    SdkExecutionError::new(
+                        SdkExecutionErrorCode::ReplayFailure,
+                        format!("audit event undecodable at seq {}: {e}", rec.seq),
+                    )
+                })?;
+                if FileAuditSink::match_filter(&ev, &filter) {
+                    out.push(ev);
+                }
+            }
+        }
+        Ok(out)
+    }
+}
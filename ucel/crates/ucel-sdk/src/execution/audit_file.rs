@@ -70,17 +70,27 @@ impl FileAuditSink {
         serde_json::from_str::<AuditEvent>(line).ok()
     }
 
-    fn match_filter(ev: &AuditEvent, f: &AuditReplayFilter) -> bool {
+    pub(crate) fn match_filter(ev: &AuditEvent, f: &AuditReplayFilter) -> bool {
         if let Some(ref run_id) = f.run_id {
             let ok = match ev {
                 AuditEvent::OrderRequested { run_id: r, .. } => {
                     r.as_deref() == Some(run_id.as_str())
                 }
                 AuditEvent::OrderResult { run_id: r, .. } => r.as_deref() == Some(run_id.as_str()),
+                AuditEvent::OrderTrackingFailed { run_id: r, .. } => {
+                    r.as_deref() == Some(run_id.as_str())
+                }
                 AuditEvent::CancelRequested { run_id: r, .. } => {
                     r.as_deref() == Some(run_id.as_str())
                 }
                 AuditEvent::CancelResult { run_id: r, .. } => r.as_deref() == Some(run_id.as_str()),
+                AuditEvent::Fill { run_id: r, .. } => r.as_deref() == Some(run_id.as_str()),
+                AuditEvent::OrderStatusChanged { run_id: r, .. } => {
+                    r.as_deref() == Some(run_id.as_str())
+                }
+                AuditEvent::ShadowVerdict { run_id: r, .. } => {
+                    r.as_deref() == Some(run_id.as_str())
+                }
                 AuditEvent::ReconcileResult { .. } => false,
             };
             if !ok {
@@ -1,8 +1,13 @@
 use crate::execution::{
-    AuditEvent, AuditReplayFilter, AuditSink, BasicOrderGate, ExecutionMode, ExecutionOutcome,
-    OrderCancel, OrderGate, OrderOpenQuery, OrderReceipt, OrderRequest, OrderStatus,
-    ReconcileReport, SdkExecutionError, SdkExecutionErrorCode, SdkExecutionResult,
+    optional_capability, AuditEvent, AuditReplayFilter, AuditSink, BasicOrderGate, ExecutionFill,
+    ExecutionMode, ExecutionOutcome, FillQuery, MarketMetaOrderGate, OrderCancel, OrderGate,
+    OrderManager, OrderOpenQuery, OrderReceipt, OrderRequest, OrderStatus, PaperExecutionVenue,
+    ReconcileReport, Reconciler, RemediationAction, SdkExecutionError, SdkExecutionErrorCode,
+    SdkExecutionResult, ShadowValidator, VenueId, VenueSnapshot,
 };
+use std::sync::Arc;
+use ucel_core::CanonicalBalance;
+use ucel_symbol_store::MarketMetaStore;
 
 /// ExecutionConnector は venue 実装が満たすべき契約。
 /// - このタスクでは "全venue実装" までやらない（次タスク）
@@ -12,12 +17,26 @@ pub trait ExecutionConnector: Send + Sync {
     fn cancel_order(&self, cancel: &OrderCancel) -> SdkExecutionResult<bool>;
     fn list_open_orders(&self, q: &OrderOpenQuery) -> SdkExecutionResult<Vec<OrderReceipt>>;
     /// reconcile は best-effort。未対応なら NotSupported を返す。
-    fn reconcile(&self, _venue: &crate::execution::VenueId) -> SdkExecutionResult<ReconcileReport> {
+    fn reconcile(&self, _venue: &VenueId) -> SdkExecutionResult<ReconcileReport> {
         Err(SdkExecutionError::new(
             SdkExecutionErrorCode::NotSupported,
             "reconcile not supported",
         ))
     }
+    /// 約定履歴（reconcile の数量照合に使う）。未対応なら NotSupported。
+    fn list_fills(&self, _q: &FillQuery) -> SdkExecutionResult<Vec<ExecutionFill>> {
+        Err(SdkExecutionError::new(
+            SdkExecutionErrorCode::NotSupported,
+            "list_fills not supported",
+        ))
+    }
+    /// 残高（reconcile の残高照合に使う）。未対応なら NotSupported。
+    fn list_balances(&self, _venue: &VenueId) -> SdkExecutionResult<Vec<CanonicalBalance>> {
+        Err(SdkExecutionError::new(
+            SdkExecutionErrorCode::NotSupported,
+            "list_balances not supported",
+        ))
+    }
 }
 
 /// ucel-sdk の "唯一の発注出口"
@@ -25,7 +44,12 @@ pub trait ExecutionConnector: Send + Sync {
 pub struct ExecutionClient<C: ExecutionConnector> {
     connector: C,
     gate: Box<dyn OrderGate>,
+    gate_explicit: bool,
     audit: Option<Box<dyn AuditSink>>,
+    paper: Option<Arc<PaperExecutionVenue>>,
+    shadow: ShadowValidator,
+    orders: Option<Arc<OrderManager>>,
+    reconciler: Reconciler,
 }
 
 impl<C: ExecutionConnector> ExecutionClient<C> {
@@ -33,12 +57,27 @@ impl<C: ExecutionConnector> ExecutionClient<C> {
         Self {
             connector,
             gate: Box::new(BasicOrderGate),
+            gate_explicit: false,
             audit: None,
+            paper: None,
+            shadow: ShadowValidator::default(),
+            orders: None,
+            reconciler: Reconciler::default(),
         }
     }
 
     pub fn with_gate(mut self, gate: Box<dyn OrderGate>) -> Self {
         self.gate = gate;
+        self.gate_explicit = true;
+        self
+    }
+
+    /// MarketMeta の参照元を差し込む。with_gate で明示していなければ
+    /// 入口の gate は MarketMetaOrderGate（tick/step/min_notional 検証）になる。
+    pub fn with_market_meta(mut self, store: Arc<MarketMetaStore>) -> Self {
+        if !self.gate_explicit {
+            self.gate = Box::new(MarketMetaOrderGate::new(store));
+        }
         self
     }
 
@@ -47,6 +86,33 @@ impl<C: ExecutionConnector> ExecutionClient<C> {
         self
     }
 
+    /// Paper mode の発注先となるシミュレーション venue を差し込む。
+    /// 未設定の場合、Paper は従来通り Accepted のみを返す。
+    pub fn with_paper_venue(mut self, paper: Arc<PaperExecutionVenue>) -> Self {
+        self.paper = Some(paper);
+        self
+    }
+
+    /// Shadow mode の照合に使う参照データ（MarketMeta / ticker / 残高）を差し込む。
+    /// 未設定の場合、各チェックは Skipped として記録される。
+    pub fn with_shadow_validator(mut self, shadow: ShadowValidator) -> Self {
+        self.shadow = shadow;
+        self
+    }
+
+    /// 自注文台帳（OrderManager）を差し込む。
+    /// Live / Paper（paper venue 設定時）の発注・取消・open orders 取得が自動で反映される。
+    pub fn with_order_manager(mut self, orders: Arc<OrderManager>) -> Self {
+        self.orders = Some(orders);
+        self
+    }
+
+    /// reconcile の照合エンジン（許容値・期待残高）を差し替える。
+    pub fn with_reconciler(mut self, reconciler: Reconciler) -> Self {
+        self.reconciler = reconciler;
+        self
+    }
+
     pub fn place(&self, mut req: OrderRequest) -> SdkExecutionResult<ExecutionOutcome> {
         // 入口の共通検証（事故防止）
         self.gate.validate(&req)?;
@@ -75,39 +141,56 @@ impl<C: ExecutionConnector> ExecutionClient<C> {
             None
         };
 
-        // mode に応じて挙動を固定
-        let receipt = match req.mode {
-            ExecutionMode::Paper => {
-                // 純シミュレーション：Accepted を返す（venue_order_id なし）
-                OrderReceipt {
-                    venue: req.intent.venue.clone(),
-                    symbol: req.intent.symbol.clone(),
-                    status: OrderStatus::Accepted,
-                    venue_order_id: None,
-                    client_order_id: req.intent.tags.get("client_order_id").cloned(),
-                    intent_id: req.intent.intent_id.clone(),
-                    idempotency: req.idempotency.clone(),
-                }
+        // 自注文台帳：実際に venue（paper venue を含む）へ出る注文だけを追跡する
+        let tracked = self.orders.as_deref().filter(|_| match req.mode {
+            ExecutionMode::Live => true,
+            ExecutionMode::Paper => self.paper.is_some(),
+            ExecutionMode::Shadow => false,
+        });
+        if let Some(om) = tracked {
+            if req.intent.tags.contains_key("client_order_id") {
+                om.on_submitted(&req)?;
             }
+        }
+
+        // mode に応じて挙動を固定
+        // fills は paper venue が発注時に即時約定させた分（Live の約定は private WS / reconcile で入る）
+        let (receipt, fills) = match req.mode {
+            ExecutionMode::Paper => match self.paper.as_ref() {
+                // 純シミュレーション：paper venue の板で照合する
+                Some(p) => p.place_order_with_fills(&req)?,
+                // paper venue 未設定：Accepted を返す（venue_order_id なし）
+                None => (
+                    OrderReceipt {
+                        venue: req.intent.venue.clone(),
+                        symbol: req.intent.symbol.clone(),
+                        status: OrderStatus::Accepted,
+                        venue_order_id: None,
+                        client_order_id: req.intent.tags.get("client_order_id").cloned(),
+                        intent_id: req.intent.intent_id.clone(),
+                        idempotency: req.idempotency.clone(),
+                    },
+                    Vec::new(),
+                ),
+            },
             ExecutionMode::Shadow => {
-                // 現状は "place_order を呼ばず" 監査だけ残す。
-                // 次タスクで quote/validate に接続して照合を強化する。
-                OrderReceipt {
-                    venue: req.intent.venue.clone(),
-                    symbol: req.intent.symbol.clone(),
-                    status: OrderStatus::Accepted,
-                    venue_order_id: None,
-                    client_order_id: req.intent.tags.get("client_order_id").cloned(),
-                    intent_id: req.intent.intent_id.clone(),
-                    idempotency: req.idempotency.clone(),
-                }
+                // 発注はせず、venue 制約に照らした判定だけを監査に残す
+                (self.shadow.run(&req, self.audit.as_deref())?, Vec::new())
             }
             ExecutionMode::Live => {
                 // 実発注：connector に委譲
-                self.connector.place_order(&req)?
+                (self.connector.place_order(&req)?, Vec::new())
             }
         };
 
+        // venue が受理した後の台帳反映失敗で Err を返すと、呼び出し側は未発注と誤認して再発注しうる。
+        // receipt はそのまま返し、失敗は log と監査に残す（台帳は次の reconcile / open orders で追いつく）
+        let tracking_err = tracked.and_then(|om| {
+            om.on_receipt(&req, &receipt)
+                .and_then(|_| fills.iter().try_for_each(|f| om.apply_fill(f).map(drop)))
+                .err()
+        });
+
         // 監査：結果
         let audit_id_res = if let Some(a) = self.audit.as_ref() {
             a.append(AuditEvent::OrderResult {
@@ -121,6 +204,26 @@ impl<C: ExecutionConnector> ExecutionClient<C> {
             None
         };
 
+        if let Some(e) = tracking_err {
+            tracing::warn!(
+                venue = %req.intent.venue.0,
+                intent_id = %req.intent.intent_id.0,
+                venue_order_id = ?receipt.venue_order_id,
+                error = %e,
+                "order accepted but order manager tracking failed"
+            );
+            if let Some(a) = self.audit.as_ref() {
+                a.append(AuditEvent::OrderTrackingFailed {
+                    run_id: req.run_id.clone(),
+                    idempotency: req.idempotency.clone(),
+                    intent_id: req.intent.intent_id.clone(),
+                    venue_order_id: receipt.venue_order_id.clone(),
+                    error: e.to_string(),
+                    unix_ms: unix_ms_now(),
+                })?;
+            }
+        }
+
         Ok(ExecutionOutcome {
             receipt,
             audit_event_id: audit_id_res.or(audit_id_req),
@@ -139,8 +242,16 @@ impl<C: ExecutionConnector> ExecutionClient<C> {
                 unix_ms: now,
             })?;
         }
-        // 実行（このタスクでは mode は cancel に入れず "live cancel" を想定）
-        let ok = self.connector.cancel_order(&cancel)?;
+        // paper venue が採番した注文は paper 側で取り消す。それ以外は "live cancel"
+        let ok = match self.paper.as_ref() {
+            Some(p) if p.has_open_order(&cancel.venue_order_id) => p.cancel_order(&cancel)?,
+            _ => self.connector.cancel_order(&cancel)?,
+        };
+        if ok {
+            if let Some(om) = self.orders.as_ref() {
+                om.apply_cancel_ack(&cancel.venue, &cancel.venue_order_id)?;
+            }
+        }
         if let Some(a) = self.audit.as_ref() {
             a.append(AuditEvent::CancelResult {
                 run_id: cancel.run_id.clone(),
@@ -154,15 +265,69 @@ impl<C: ExecutionConnector> ExecutionClient<C> {
     }
 
     pub fn open_orders(&self, q: OrderOpenQuery) -> SdkExecutionResult<Vec<OrderReceipt>> {
-        self.connector.list_open_orders(&q)
+        let open = self.connector.list_open_orders(&q)?;
+        if let Some(om) = self.orders.as_ref() {
+            om.apply_open_orders(&q.venue, q.symbol.as_ref(), &open)?;
+        }
+        Ok(open)
     }
 
     /// reconcile（照合）
-    pub fn reconcile(
+    /// - audit 設定時: 監査ログと venue の open orders / fills / balances を照合エンジンで突き合わせ、
+    ///   connector 固有の reconcile（対応していれば）の結果も取り込む
+    /// - audit 未設定時: connector の reconcile に委譲する
+    pub fn reconcile(&self, venue: &VenueId) -> SdkExecutionResult<ReconcileReport> {
+        self.reconcile_run(venue, None)
+    }
+
+    /// run_id を指定した reconcile（その run の監査履歴だけを照合対象にする）。
+    /// 指定時は追跡外の venue 注文を orphan 扱いしない（他の run・手動注文を取り消さないため）
+    pub fn reconcile_run(
         &self,
-        venue: &crate::execution::VenueId,
+        venue: &VenueId,
+        run_id: Option<&str>,
     ) -> SdkExecutionResult<ReconcileReport> {
-        let mut r = self.connector.reconcile(venue)?;
+        let mut r = match self.audit.as_ref() {
+            Some(a) => {
+                let history = a
+                    .replay(AuditReplayFilter {
+                        run_id: run_id.map(str::to_string),
+                        venue: None,
+                        intent_id: None,
+                        idempotency: None,
+                        since_unix_ms: None,
+                        until_unix_ms: None,
+                    })
+                    .map_err(|e| {
+                        SdkExecutionError::new(
+                            SdkExecutionErrorCode::ReconcileFailure,
+                            "reconcile: audit replay failed",
+                        )
+                        .with_source(e)
+                    })?;
+                let snapshot = VenueSnapshot {
+                    open_orders: self.connector.list_open_orders(&OrderOpenQuery {
+                        venue: venue.clone(),
+                        symbol: None,
+                    })?,
+                    fills: optional_capability(self.connector.list_fills(&FillQuery {
+                        venue: venue.clone(),
+                        symbol: None,
+                        since_unix_ms: self.reconciler.fills_since(venue, &history),
+                    }))?,
+                    balances: optional_capability(self.connector.list_balances(venue))?,
+                };
+                let mut report = match run_id {
+                    Some(_) => self.reconciler.reconcile_run(venue, &history, &snapshot),
+                    None => self.reconciler.reconcile(venue, &history, &snapshot),
+                };
+                if let Some(extra) = optional_capability(self.connector.reconcile(venue))? {
+                    report.merge(extra);
+                }
+                report
+            }
+            None => self.connector.reconcile(venue)?,
+        };
         // source は venue が返したものを尊重しつつ、最低限埋める
         if r.generated_at_unix_ms == 0 {
             r.generated_at_unix_ms = unix_ms_now();
@@ -177,6 +342,57 @@ impl<C: ExecutionConnector> ExecutionClient<C> {
     }
 
     /// replay（監査ログの再生）
+    /// reconcile の RemediationAction::ApplyVenueFills を実行する。
+    /// venue の約定一覧から該当 fill を取り直し、AuditEvent::Fill として監査へ追記し
+    /// （Live 注文の約定はここでしか監査に入らない）、OrderManager 設定時はそちらにも反映する。
+    /// 取り込んだ fill を返す。以後の reconcile ではその注文の QtyDrift は出なくなる。
+    pub fn apply_venue_fills(
+        &self,
+        report: &ReconcileReport,
+    ) -> SdkExecutionResult<Vec<ExecutionFill>> {
+        if !report
+            .actions
+            .iter()
+            .any(|a| matches!(a, RemediationAction::ApplyVenueFills { .. }))
+        {
+            return Ok(Vec::new());
+        }
+        let a = self.audit.as_ref().ok_or_else(|| {
+            SdkExecutionError::new(
+                SdkExecutionErrorCode::ReconcileFailure,
+                "apply_venue_fills: audit sink not configured",
+            )
+        })?;
+        let venue = &report.venue;
+        let history = a.replay(AuditReplayFilter {
+            run_id: None,
+            venue: None,
+            intent_id: None,
+            idempotency: None,
+            since_unix_ms: None,
+            until_unix_ms: None,
+        })?;
+        let venue_fills = self.connector.list_fills(&FillQuery {
+            venue: venue.clone(),
+            symbol: None,
+            since_unix_ms: self.reconciler.fills_since(venue, &history),
+        })?;
+        let mut applied = Vec::new();
+        for ev in self
+            .reconciler
+            .venue_fill_events(venue, &history, &venue_fills, &report.actions)
+        {
+            a.append(ev.clone())?;
+            if let AuditEvent::Fill { fill, .. } = ev {
+                if let Some(om) = self.orders.as_ref() {
+                    om.apply_fill(&fill)?;
+                }
+                applied.push(fill);
+            }
+        }
+        Ok(applied)
+    }
+
     pub fn replay(&self, filter: AuditReplayFilter) -> SdkExecutionResult<Vec<AuditEvent>> {
         let a = self.audit.as_ref().ok_or_else(|| {
             SdkExecutionError::new(
@@ -1,8 +1,14 @@
-use crate::execution::{SdkExecutionError, SdkExecutionErrorCode, SdkExecutionResult};
+use crate::execution::{OrderType, SdkExecutionError, SdkExecutionErrorCode, SdkExecutionResult};
+use std::collections::BTreeMap;
+use std::sync::Arc;
+use ucel_core::order_gate::OrderGate as CoreOrderGate;
+use ucel_core::{StepSize, TickSize};
+use ucel_symbol_core::{Exchange, MarketMetaId, MarketType};
+use ucel_symbol_store::MarketMetaStore;
 
 /// OrderGate は "入口" で必ず適用されるガード。
-/// 実際の tick/step/min_notional 等の強い検証は UCEL の MarketMeta と連携して次タスクで強化できるが、
-/// このタスクでは public surface を固定し、最低限の事故防止（無効値拒否）を保証する。
+/// - BasicOrderGate: 最低限の事故防止（無効値拒否）
+/// - MarketMetaOrderGate: MarketMeta と連携した tick/step/min_notional の強い検証
 pub trait OrderGate: Send + Sync {
     fn validate(&self, req: &crate::execution::OrderRequest) -> SdkExecutionResult<()>;
 }
@@ -21,3 +27,133 @@ impl OrderGate for BasicOrderGate {
         Ok(())
     }
 }
+
+/// MarketMeta 連携の Gate。
+/// - BasicOrderGate の検証に加え、指値（Limit/PostOnly）を symbol store の MarketMeta で照合する
+/// - tick/step は ucel_core::order_gate::OrderGate（strict policy）で検証し、min_qty/min_notional は
+///   MarketMeta::validate_order で検証する
+/// - meta が見つからない指値は venue へ送らず拒否する（丸めは呼び出し側で order_normalize を使う）
+/// - market type は注文ごとに解決する（`MARKET_TYPE_TAG` > venue 別設定 > venue id から自明なもの > 既定値）
+pub struct MarketMetaOrderGate {
+    store: Arc<MarketMetaStore>,
+    core: CoreOrderGate,
+    market_type: MarketType,
+    venue_market_types: BTreeMap<String, MarketType>,
+}
+
+/// 注文の market type を明示する intent tag（値は `spot` / `linear_perpetual` など snake_case）
+pub const MARKET_TYPE_TAG: &str = "market_type";
+
+impl MarketMetaOrderGate {
+    pub fn new(store: Arc<MarketMetaStore>) -> Self {
+        Self {
+            store,
+            core: CoreOrderGate::default(),
+            market_type: MarketType::Spot,
+            venue_market_types: BTreeMap::new(),
+        }
+    }
+
+    /// tag・venue から決まらない場合に使う market type（既定は Spot）
+    pub fn with_market_type(mut self, market_type: MarketType) -> Self {
+        self.market_type = market_type;
+        self
+    }
+
+    /// venue ごとの market type を固定する（例: "bybit" を LinearPerpetual として扱う）
+    pub fn with_venue_market_type(
+        mut self,
+        venue: impl Into<String>,
+        market_type: MarketType,
+    ) -> Self {
+        self.venue_market_types
+            .insert(venue.into().trim().to_ascii_lowercase(), market_type);
+        self
+    }
+
+    pub fn with_core_gate(mut self, core: CoreOrderGate) -> Self {
+        self.core = core;
+        self
+    }
+
+    fn meta_id(&self, req: &crate::execution::OrderRequest) -> SdkExecutionResult<MarketMetaId> {
+        let venue = req.intent.venue.0.trim().to_ascii_lowercase();
+        let exchange =
+            serde_json::from_value::<Exchange>(serde_json::Value::String(venue.replace('-', "_")))
+                .unwrap_or(Exchange::Other(venue.clone()));
+        let market_type = self.market_type_for(req, &venue, &exchange)?;
+        Ok(MarketMetaId::new(
+            exchange,
+            market_type,
+            req.intent.symbol.0.clone(),
+        ))
+    }
+
+    fn market_type_for(
+        &self,
+        req: &crate::execution::OrderRequest,
+        venue: &str,
+        exchange: &Exchange,
+    ) -> SdkExecutionResult<MarketType> {
+        if let Some(tag) = req.intent.tags.get(MARKET_TYPE_TAG) {
+            return serde_json::from_value::<MarketType>(serde_json::Value::String(
+                tag.trim().to_ascii_lowercase(),
+            ))
+            .map_err(|_| {
+                SdkExecutionError::new(
+                    SdkExecutionErrorCode::OrderGateRejected,
+                    format!("order gate rejected: unknown {MARKET_TYPE_TAG} tag: {tag}"),
+                )
+            });
+        }
+        if let Some(mt) = self.venue_market_types.get(venue) {
+            return Ok(mt.clone());
+        }
+        // venue id が単一の market type しか持たないもの
+        Ok(match exchange {
+            Exchange::BinanceUsdm => MarketType::LinearPerpetual,
+            Exchange::BinanceCoinm => MarketType::InversePerpetual,
+            Exchange::BinanceOptions => MarketType::Option,
+            _ => self.market_type.clone(),
+        })
+    }
+}
+
+impl OrderGate for MarketMetaOrderGate {
+    fn validate(&self, req: &crate::execution::OrderRequest) -> SdkExecutionResult<()> {
+        BasicOrderGate.validate(req)?;
+
+        let intent = &req.intent;
+        let (OrderType::Limit | OrderType::PostOnly, Some(price)) =
+            (intent.order_type, intent.price)
+        else {
+            return Ok(());
+        };
+
+        let id = self.meta_id(req)?;
+        let meta = self.store.get(&id).ok_or_else(|| {
+            SdkExecutionError::new(
+                SdkExecutionErrorCode::OrderGateRejected,
+                format!("order gate rejected: market meta not found: {id:?}"),
+            )
+        })?;
+        let reject = |e: String| {
+            SdkExecutionError::new(
+                SdkExecutionErrorCode::OrderGateRejected,
+                format!("order gate rejected: {e}"),
+            )
+        };
+
+        self.core
+            .validate_limit(
+                price.as_decimal(),
+                intent.qty.as_decimal(),
+                TickSize(meta.tick_size),
+                StepSize(meta.step_size),
+            )
+            .map_err(|e| reject(e.to_string()))?;
+        meta.validate_order(price.as_decimal(), intent.qty.as_decimal())
+            .map_err(|e| reject(e.to_string()))?;
+        Ok(())
+    }
+}
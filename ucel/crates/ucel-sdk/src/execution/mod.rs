@@ -1,17 +1,27 @@
 mod async_client;
 mod audit;
+mod audit_chain;
 mod audit_file;
 mod client;
 mod errors;
 mod gate;
 mod idempotency;
+mod order_manager;
+mod paper;
+mod reconcile;
+mod shadow;
 mod types;
 
 pub use async_client::*;
 pub use audit::*;
+pub use audit_chain::*;
 pub use audit_file::*;
 pub use client::*;
 pub use errors::*;
 pub use gate::*;
 pub use idempotency::*;
+pub use order_manager::*;
+pub use paper::*;
+pub use reconcile::*;
+pub use shadow::*;
 pub use types::*;
@@ -0,0 +1,887 @@
+use crate::execution::{
+    unix_ms_now, ExecutionConnector, ExecutionFill, OrderIntentId, OrderOpenQuery, OrderReceipt,
+    OrderRequest, OrderSide, OrderStatus, Price, Quantity, SdkExecutionError,
+    SdkExecutionErrorCode, SdkExecutionResult, Symbol, VenueId,
+};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeSet, HashMap, HashSet, VecDeque};
+use std::str::FromStr;
+use std::sync::Mutex;
+use tokio::sync::broadcast;
+use ucel_core::{CanonicalFillEvent, CanonicalOrderEvent, CanonicalPrivateWsEvent, Decimal};
+
+/// 自注文 1 件の "権威ある" 状態（client_order_id 単位）。
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ManagedOrder {
+    pub client_order_id: String,
+    pub venue: VenueId,
+    pub symbol: Symbol,
+    pub venue_order_id: Option<String>,
+    pub intent_id: Option<OrderIntentId>,
+    pub side: Option<OrderSide>,
+    pub price: Option<Price>,
+    /// 発注数量。receipt のみで追跡し始めた注文は venue イベントで埋まるまで None。
+    pub qty: Option<Quantity>,
+    pub status: OrderStatus,
+    /// 累積約定数量
+    pub filled_qty: Quantity,
+    /// 約定の数量加重平均価格
+    pub avg_fill_price: Option<Price>,
+    /// 累積手数料（負値は maker リベート）
+    pub fee: Decimal,
+    pub fill_count: usize,
+    /// 最後に反映した venue 注文イベントの時刻（ms）
+    pub last_event_ms: Option<u64>,
+    pub updated_at_unix_ms: u64,
+}
+
+impl ManagedOrder {
+    pub fn remaining_qty(&self) -> Option<Quantity> {
+        self.qty
+            .map(|q| Quantity((q.0 - self.filled_qty.0).max(Decimal::ZERO)))
+    }
+}
+
+/// 状態変化のきっかけ
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum OrderUpdateCause {
+    /// 送信前の登録
+    Submitted,
+    /// ExecutionConnector の receipt
+    Receipt,
+    /// cancel の受理
+    CancelAck,
+    /// private WS の注文イベント
+    VenueOrder,
+    /// private WS / paper venue の約定
+    VenueFill { fill_id: String },
+    /// list_open_orders による REST ポーリング
+    OpenOrdersPoll,
+}
+
+/// 反映時に検出した異常。状態は壊さず、呼び出し側へ知らせる。
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum OrderAnomaly {
+    /// 非合法な状態遷移（適用しない）
+    IllegalTransition { from: OrderStatus, to: OrderStatus },
+    /// 同じ fill_id の再送（適用しない）
+    DuplicateFill { fill_id: String },
+    /// 既に反映した約定より古い時刻の約定（数量は適用する）
+    OutOfOrderFill {
+        fill_id: String,
+        ts_event_ms: u64,
+        last_fill_ms: u64,
+    },
+    /// 既に反映した注文イベントより古い注文イベント（適用しない）
+    StaleOrderEvent {
+        ts_event_ms: u64,
+        last_event_ms: u64,
+    },
+    /// 累積約定が発注数量を超えた
+    Overfill { filled_qty: Quantity, qty: Quantity },
+}
+
+/// 購読者へ配信される更新 1 件
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct OrderUpdate {
+    pub client_order_id: String,
+    pub previous_status: Option<OrderStatus>,
+    pub order: ManagedOrder,
+    pub cause: OrderUpdateCause,
+    pub anomalies: Vec<OrderAnomaly>,
+}
+
+/// list_open_orders と突き合わせた結果
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct OpenOrdersSync {
+    pub updates: Vec<OrderUpdate>,
+    /// venue には存在するがローカルで追跡していない注文
+    pub unknown_venue_orders: Vec<OrderReceipt>,
+    /// ローカルでは open だが venue の一覧に無い注文（client_order_id）。
+    /// 約定か取消かは判別できないため状態は変えない。
+    pub missing_on_venue: Vec<String>,
+}
+
+#[derive(Clone, Debug)]
+pub struct OrderManagerConfig {
+    /// venue_order_id が未紐付けの WS イベントを保持する上限（超過分は古い注文から捨てる）
+    pub max_pending_events: usize,
+    /// subscribe の broadcast チャネル容量
+    pub channel_capacity: usize,
+}
+
+impl Default for OrderManagerConfig {
+    fn default() -> Self {
+        Self {
+            max_pending_events: 4096,
+            channel_capacity: 1024,
+        }
+    }
+}
+
+/// venue 文字列の注文状態を OrderStatus へ正規化する（不明は Unknown）。
+pub fn parse_venue_order_status(raw: &str) -> OrderStatus {
+    let s: String = raw
+        .trim()
+        .chars()
+        .filter(|c| !matches!(c, '_' | '-' | ' '))
+        .collect::<String>()
+        .to_ascii_lowercase();
+    match s.as_str() {
+        "accepted" | "pending" | "pendingnew" | "submitted" | "created" => OrderStatus::Accepted,
+        "new" | "open" | "active" | "live" | "resting" | "untriggered" => OrderStatus::Open,
+        "partiallyfilled" | "partialfilled" | "partial" | "partiallyfill" => {
+            OrderStatus::PartiallyFilled
+        }
+        "filled" | "fullyfilled" | "closed" | "done" | "executed" => OrderStatus::Filled,
+        "canceled" | "cancelled" | "partiallycanceled" | "partiallycancelled" => {
+            OrderStatus::Canceled
+        }
+        "expired" => OrderStatus::Expired,
+        "rejected" | "failed" => OrderStatus::Rejected,
+        _ => OrderStatus::Unknown,
+    }
+}
+
+fn parse_side(raw: &str) -> Option<OrderSide> {
+    match raw.trim().to_ascii_lowercase().as_str() {
+        "buy" | "bid" | "b" => Some(OrderSide::Buy),
+        "sell" | "ask" | "s" => Some(OrderSide::Sell),
+        _ => None,
+    }
+}
+
+fn parse_decimal(field: &str, raw: &str) -> SdkExecutionResult<Decimal> {
+    let raw = raw.trim();
+    Decimal::from_str(raw)
+        .or_else(|_| Decimal::from_scientific(raw))
+        .map_err(|e| {
+            SdkExecutionError::new(
+                SdkExecutionErrorCode::InvalidInput,
+                format!("invalid {field}: {raw}"),
+            )
+            .with_source(e)
+        })
+}
+
+fn parse_opt_decimal(field: &str, raw: Option<&str>) -> SdkExecutionResult<Option<Decimal>> {
+    raw.filter(|s| !s.trim().is_empty())
+        .map(|s| parse_decimal(field, s))
+        .transpose()
+}
+
+/// venue / WS / paper のどこから来ても同じ形で扱う約定
+#[derive(Clone, Debug)]
+struct FillUpdate {
+    fill_id: String,
+    price: Decimal,
+    qty: Decimal,
+    fee: Decimal,
+    ts_event_ms: Option<u64>,
+}
+
+impl FillUpdate {
+    fn from_ws(ev: &CanonicalFillEvent) -> SdkExecutionResult<Self> {
+        let qty = parse_opt_decimal("fill qty", ev.qty.as_deref())?.ok_or_else(|| {
+            SdkExecutionError::new(
+                SdkExecutionErrorCode::InvalidInput,
+                format!("fill {} has no qty", ev.fill_id),
+            )
+        })?;
+        let price = parse_opt_decimal("fill price", ev.price.as_deref())?.ok_or_else(|| {
+            SdkExecutionError::new(
+                SdkExecutionErrorCode::InvalidInput,
+                format!("fill {} has no price", ev.fill_id),
+            )
+        })?;
+        Ok(Self {
+            fill_id: ev.fill_id.clone(),
+            price,
+            qty,
+            fee: parse_opt_decimal("fill fee", ev.fee.as_deref())?.unwrap_or_default(),
+            ts_event_ms: ev.ts_event_ms,
+        })
+    }
+
+    fn from_execution_fill(f: &ExecutionFill) -> Self {
+        Self {
+            fill_id: f.fill_id.clone(),
+            price: f.price.0,
+            qty: f.qty.0,
+            fee: f.fee,
+            ts_event_ms: Some(f.unix_ms),
+        }
+    }
+}
+
+/// venue_order_id が未紐付けのまま届いたイベント（receipt より WS が先に来るケース）
+#[derive(Clone, Debug)]
+enum PendingEvent {
+    Order(CanonicalOrderEvent),
+    Fill(FillUpdate),
+}
+
+#[derive(Clone, Debug)]
+struct OrderEntry {
+    order: ManagedOrder,
+    fill_ids: HashSet<String>,
+    /// Σ price * qty（平均価格の計算用）
+    fill_notional: Decimal,
+    last_fill_ms: Option<u64>,
+}
+
+impl OrderEntry {
+    fn new(order: ManagedOrder) -> Self {
+        Self {
+            order,
+            fill_ids: HashSet::new(),
+            fill_notional: Decimal::ZERO,
+            last_fill_ms: None,
+        }
+    }
+}
+
+/// (venue, venue_order_id)
+type VenueOrderKey = (String, String);
+
+#[derive(Default)]
+struct ManagerState {
+    /// client_order_id -> entry
+    orders: HashMap<String, OrderEntry>,
+    by_venue_order: HashMap<VenueOrderKey, String>,
+    pending: HashMap<VenueOrderKey, Vec<PendingEvent>>,
+    /// 捨てる順序（古い venue_order_id から）
+    pending_keys: VecDeque<VenueOrderKey>,
+    pending_len: usize,
+    dropped_pending: u64,
+}
+
+/// 状態遷移 1 回分の作業領域
+struct Transition<'a> {
+    entry: &'a mut OrderEntry,
+    previous: OrderStatus,
+    before: ManagedOrder,
+    anomalies: Vec<OrderAnomaly>,
+}
+
+impl<'a> Transition<'a> {
+    fn begin(entry: &'a mut OrderEntry) -> Self {
+        let before = entry.order.clone();
+        Self {
+            previous: before.status.clone(),
+            before,
+            entry,
+            anomalies: Vec::new(),
+        }
+    }
+
+    fn set_status(&mut self, next: OrderStatus) {
+        if next == OrderStatus::Unknown || self.entry.order.status == next {
+            return;
+        }
+        if self.entry.order.status.can_transition_to(&next) {
+            self.entry.order.status = next;
+        } else {
+            self.anomalies.push(OrderAnomaly::IllegalTransition {
+                from: self.entry.order.status.clone(),
+                to: next,
+            });
+        }
+    }
+
+    fn apply_fill(&mut self, fill: &FillUpdate) {
+        if !self.entry.fill_ids.insert(fill.fill_id.clone()) {
+            self.anomalies.push(OrderAnomaly::DuplicateFill {
+                fill_id: fill.fill_id.clone(),
+            });
+            return;
+        }
+        if let (Some(ts), Some(last)) = (fill.ts_event_ms, self.entry.last_fill_ms) {
+            if ts < last {
+                self.anomalies.push(OrderAnomaly::OutOfOrderFill {
+                    fill_id: fill.fill_id.clone(),
+                    ts_event_ms: ts,
+                    last_fill_ms: last,
+                });
+            }
+        }
+        if let Some(ts) = fill.ts_event_ms {
+            self.entry.last_fill_ms = Some(self.entry.last_fill_ms.map_or(ts, |l| l.max(ts)));
+        }
+
+        let order = &mut self.entry.order;
+        order.filled_qty = Quantity(order.filled_qty.0 + fill.qty);
+        order.fee += fill.fee;
+        order.fill_count += 1;
+        self.entry.fill_notional += fill.price * fill.qty;
+        if !order.filled_qty.0.is_zero() {
+            order.avg_fill_price = Some(Price(self.entry.fill_notional / order.filled_qty.0));
+        }
+
+        let filled = order.filled_qty;
+        match order.qty {
+            Some(qty) if filled.0 >= qty.0 => {
+                if filled.0 > qty.0 {
+                    self.anomalies.push(OrderAnomaly::Overfill {
+                        filled_qty: filled,
+                        qty,
+                    });
+                }
+                if !self.entry.order.status.is_terminal() {
+                    self.set_status(OrderStatus::Filled);
+                }
+            }
+            _ => {
+                if matches!(
+                    self.entry.order.status,
+                    OrderStatus::Unknown | OrderStatus::Accepted | OrderStatus::Open
+                ) {
+                    self.set_status(OrderStatus::PartiallyFilled);
+                }
+            }
+        }
+    }
+
+    fn apply_order_event(&mut self, ev: &CanonicalOrderEvent) -> SdkExecutionResult<()> {
+        if let (Some(ts), Some(last)) = (ev.ts_event_ms, self.entry.order.last_event_ms) {
+            if ts < last {
+                self.anomalies.push(OrderAnomaly::StaleOrderEvent {
+                    ts_event_ms: ts,
+                    last_event_ms: last,
+                });
+                return Ok(());
+            }
+        }
+        let order = &mut self.entry.order;
+        if order.qty.is_none() {
+            order.qty = parse_opt_decimal("order qty", ev.qty.as_deref())?.map(Quantity);
+        }
+        if order.price.is_none() {
+            order.price = parse_opt_decimal("order price", ev.price.as_deref())?.map(Price);
+        }
+        if order.side.is_none() {
+            order.side = ev.side.as_deref().and_then(parse_side);
+        }
+        if ev.ts_event_ms.is_some() {
+            order.last_event_ms = ev.ts_event_ms;
+        }
+        self.set_status(parse_venue_order_status(&ev.status));
+        Ok(())
+    }
+
+    /// 変化または異常があれば OrderUpdate を返す
+    fn finish(self, cause: OrderUpdateCause) -> Option<OrderUpdate> {
+        let changed = self.entry.order != self.before;
+        if !changed && self.anomalies.is_empty() {
+            return None;
+        }
+        if changed {
+            self.entry.order.updated_at_unix_ms = unix_ms_now();
+        }
+        Some(OrderUpdate {
+            client_order_id: self.entry.order.client_order_id.clone(),
+            previous_status: Some(self.previous),
+            order: self.entry.order.clone(),
+            cause,
+            anomalies: self.anomalies,
+        })
+    }
+}
+
+impl ManagerState {
+    fn client_id_for_venue_order(&self, venue: &VenueId, venue_order_id: &str) -> Option<String> {
+        self.by_venue_order
+            .get(&(venue.0.clone(), venue_order_id.to_string()))
+            .cloned()
+    }
+
+    fn link_venue_order(&mut self, venue: &VenueId, venue_order_id: &str, client_order_id: &str) {
+        self.by_venue_order.insert(
+            (venue.0.clone(), venue_order_id.to_string()),
+            client_order_id.to_string(),
+        );
+    }
+
+    fn push_pending(&mut self, cfg: &OrderManagerConfig, key: VenueOrderKey, ev: PendingEvent) {
+        let slot = self.pending.entry(key.clone()).or_default();
+        if slot.is_empty() {
+            self.pending_keys.push_back(key);
+        }
+        slot.push(ev);
+        self.pending_len += 1;
+
+        while self.pending_len > cfg.max_pending_events {
+            let Some(oldest) = self.pending_keys.pop_front() else {
+                break;
+            };
+            if let Some(dropped) = self.pending.remove(&oldest) {
+                self.pending_len -= dropped.len();
+                self.dropped_pending += dropped.len() as u64;
+            }
+        }
+    }
+
+    fn take_pending(&mut self, venue: &VenueId, venue_order_id: &str) -> Vec<PendingEvent> {
+        let key = (venue.0.clone(), venue_order_id.to_string());
+        let Some(events) = self.pending.remove(&key) else {
+            return Vec::new();
+        };
+        self.pending_len -= events.len();
+        self.pending_keys.retain(|k| k != &key);
+        events
+    }
+
+    fn apply_fill(&mut self, client_order_id: &str, fill: &FillUpdate, out: &mut Vec<OrderUpdate>) {
+        let Some(entry) = self.orders.get_mut(client_order_id) else {
+            return;
+        };
+        let mut t = Transition::begin(entry);
+        t.apply_fill(fill);
+        out.extend(t.finish(OrderUpdateCause::VenueFill {
+            fill_id: fill.fill_id.clone(),
+        }));
+    }
+
+    fn apply_order_event(
+        &mut self,
+        client_order_id: &str,
+        ev: &CanonicalOrderEvent,
+        out: &mut Vec<OrderUpdate>,
+    ) -> SdkExecutionResult<()> {
+        let Some(entry) = self.orders.get_mut(client_order_id) else {
+            return Ok(());
+        };
+        let mut t = Transition::begin(entry);
+        t.apply_order_event(ev)?;
+        out.extend(t.finish(OrderUpdateCause::VenueOrder));
+        Ok(())
+    }
+
+    /// receipt 等で venue_order_id が判明したら、保留していたイベントを到着順に流し込む
+    fn drain_pending(
+        &mut self,
+        venue: &VenueId,
+        venue_order_id: &str,
+        client_order_id: &str,
+        out: &mut Vec<OrderUpdate>,
+    ) -> SdkExecutionResult<()> {
+        for ev in self.take_pending(venue, venue_order_id) {
+            match ev {
+                PendingEvent::Order(o) => self.apply_order_event(client_order_id, &o, out)?,
+                PendingEvent::Fill(f) => self.apply_fill(client_order_id, &f, out),
+            }
+        }
+        Ok(())
+    }
+}
+
+/// 自注文のライフサイクル管理（ローカルの "自分の注文台帳"）。
+/// - 入力: ExecutionConnector の receipt / private WS（CanonicalPrivateWsEvent）/ list_open_orders
+/// - client_order_id ごとに 1 つの状態を持ち、OrderStatus::can_transition_to で遷移を強制する
+/// - 累積約定数量・平均約定価格を計算し、重複・順序逆転した約定を検出する
+/// - subscribe() で状態変化を購読できる
+pub struct OrderManager {
+    cfg: OrderManagerConfig,
+    state: Mutex<ManagerState>,
+    tx: broadcast::Sender<OrderUpdate>,
+}
+
+impl Default for OrderManager {
+    fn default() -> Self {
+        Self::new(OrderManagerConfig::default())
+    }
+}
+
+impl OrderManager {
+    pub fn new(cfg: OrderManagerConfig) -> Self {
+        let (tx, _) = broadcast::channel(cfg.channel_capacity.max(1));
+        Self {
+            cfg,
+            state: Mutex::new(ManagerState::default()),
+            tx,
+        }
+    }
+
+    /// 以後の状態変化を受け取る。遅い購読者は broadcast の Lagged を受ける。
+    pub fn subscribe(&self) -> broadcast::Receiver<OrderUpdate> {
+        self.tx.subscribe()
+    }
+
+    pub fn get(&self, client_order_id: &str) -> Option<ManagedOrder> {
+        self.lock()
+            .ok()?
+            .orders
+            .get(client_order_id)
+            .map(|e| e.order.clone())
+    }
+
+    pub fn find_by_venue_order_id(
+        &self,
+        venue: &VenueId,
+        venue_order_id: &str,
+    ) -> Option<ManagedOrder> {
+        let st = self.lock().ok()?;
+        let cid = st.client_id_for_venue_order(venue, venue_order_id)?;
+        st.orders.get(&cid).map(|e| e.order.clone())
+    }
+
+    /// 追跡中の全注文（client_order_id 順）
+    pub fn orders(&self) -> SdkExecutionResult<Vec<ManagedOrder>> {
+        let st = self.lock()?;
+        let mut out = st
+            .orders
+            .values()
+            .map(|e| e.order.clone())
+            .collect::<Vec<_>>();
+        out.sort_by(|a, b| a.client_order_id.cmp(&b.client_order_id));
+        Ok(out)
+    }
+
+    /// 終端に達していない注文
+    pub fn open_orders(&self, venue: &VenueId) -> SdkExecutionResult<Vec<ManagedOrder>> {
+        Ok(self
+            .orders()?
+            .into_iter()
+            .filter(|o| &o.venue == venue && !o.status.is_terminal())
+            .collect())
+    }
+
+    /// venue_order_id 未紐付けで保留中のイベント数
+    pub fn pending_event_count(&self) -> usize {
+        self.lock().map(|st| st.pending_len).unwrap_or_default()
+    }
+
+    /// 保留上限を超えて捨てたイベント数
+    pub fn dropped_pending_events(&self) -> u64 {
+        self.lock().map(|st| st.dropped_pending).unwrap_or_default()
+    }
+
+    /// 送信前に注文を登録する（状態は receipt まで Unknown）。
+    /// client_order_id（tags）が必須。追跡中の client_order_id の再利用は IdempotencyViolation。
+    pub fn on_submitted(&self, req: &OrderRequest) -> SdkExecutionResult<Option<OrderUpdate>> {
+        self.register(req, true)
+    }
+
+    fn register(
+        &self,
+        req: &OrderRequest,
+        reject_duplicate: bool,
+    ) -> SdkExecutionResult<Option<OrderUpdate>> {
+        let cid = req
+            .intent
+            .tags
+            .get("client_order_id")
+            .cloned()
+            .ok_or_else(|| {
+                SdkExecutionError::new(
+                    SdkExecutionErrorCode::InvalidInput,
+                    "order manager requires client_order_id tag",
+                )
+            })?;
+        let mut st = self.lock()?;
+        if st.orders.contains_key(&cid) {
+            if reject_duplicate {
+                return Err(SdkExecutionError::new(
+                    SdkExecutionErrorCode::IdempotencyViolation,
+                    format!("client_order_id {cid} is already tracked"),
+                ));
+            }
+            return Ok(None);
+        }
+        let order = ManagedOrder {
+            client_order_id: cid.clone(),
+            venue: req.intent.venue.clone(),
+            symbol: req.intent.symbol.clone(),
+            venue_order_id: None,
+            intent_id: Some(req.intent.intent_id.clone()),
+            side: Some(req.intent.side),
+            price: req.intent.price,
+            qty: Some(req.intent.qty),
+            status: OrderStatus::Unknown,
+            filled_qty: Quantity(Decimal::ZERO),
+            avg_fill_price: None,
+            fee: Decimal::ZERO,
+            fill_count: 0,
+            last_event_ms: None,
+            updated_at_unix_ms: unix_ms_now(),
+        };
+        st.orders
+            .insert(cid.clone(), OrderEntry::new(order.clone()));
+        drop(st);
+
+        let update = OrderUpdate {
+            client_order_id: cid,
+            previous_status: None,
+            order,
+            cause: OrderUpdateCause::Submitted,
+            anomalies: Vec::new(),
+        };
+        self.publish(std::slice::from_ref(&update));
+        Ok(Some(update))
+    }
+
+    /// 発注要求と receipt をまとめて反映する（未登録なら登録してから）。
+    pub fn on_receipt(
+        &self,
+        req: &OrderRequest,
+        receipt: &OrderReceipt,
+    ) -> SdkExecutionResult<Vec<OrderUpdate>> {
+        let mut out = Vec::new();
+        if receipt.client_order_id.is_some() || req.intent.tags.contains_key("client_order_id") {
+            let mut req = req.clone();
+            if let Some(cid) = receipt.client_order_id.as_ref() {
+                req.intent
+                    .tags
+                    .insert("client_order_id".to_string(), cid.clone());
+            }
+            out.extend(self.register(&req, false)?);
+        }
+        out.extend(self.apply_receipt(receipt)?);
+        Ok(out)
+    }
+
+    /// receipt を反映する。client_order_id が無い receipt は venue_order_id を鍵にする。
+    pub fn apply_receipt(&self, receipt: &OrderReceipt) -> SdkExecutionResult<Vec<OrderUpdate>> {
+        let mut out = Vec::new();
+        {
+            let mut st = self.lock()?;
+            let cid = receipt
+                .venue_order_id
+                .as_deref()
+                .and_then(|v| st.client_id_for_venue_order(&receipt.venue, v))
+                .or_else(|| receipt.client_order_id.clone())
+                .or_else(|| receipt.venue_order_id.clone())
+                .ok_or_else(|| {
+                    SdkExecutionError::new(
+                        SdkExecutionErrorCode::InvalidInput,
+                        "receipt has neither client_order_id nor venue_order_id",
+                    )
+                })?;
+
+            let entry = st.orders.entry(cid.clone()).or_insert_with(|| {
+                OrderEntry::new(ManagedOrder {
+                    client_order_id: cid.clone(),
+                    venue: receipt.venue.clone(),
+                    symbol: receipt.symbol.clone(),
+                    venue_order_id: None,
+                    intent_id: Some(receipt.intent_id.clone()),
+                    side: None,
+                    price: None,
+                    qty: None,
+                    status: OrderStatus::Unknown,
+                    filled_qty: Quantity(Decimal::ZERO),
+                    avg_fill_price: None,
+                    fee: Decimal::ZERO,
+                    fill_count: 0,
+                    last_event_ms: None,
+                    updated_at_unix_ms: unix_ms_now(),
+                })
+            });
+            let mut t = Transition::begin(entry);
+            if t.entry.order.venue_order_id.is_none() {
+                t.entry.order.venue_order_id = receipt.venue_order_id.clone();
+            }
+            t.set_status(receipt.status.clone());
+            out.extend(t.finish(OrderUpdateCause::Receipt));
+
+            if let Some(vid) = receipt.venue_order_id.as_deref() {
+                st.link_venue_order(&receipt.venue, vid, &cid);
+                st.drain_pending(&receipt.venue, vid, &cid, &mut out)?;
+            }
+        }
+        self.publish(&out);
+        Ok(out)
+    }
+
+    /// paper venue 等が生成した ExecutionFill を反映する
+    pub fn apply_fill(&self, fill: &ExecutionFill) -> SdkExecutionResult<Vec<OrderUpdate>> {
+        let mut out = Vec::new();
+        {
+            let mut st = self.lock()?;
+            let update = FillUpdate::from_execution_fill(fill);
+            let cid = st
+                .client_id_for_venue_order(&fill.venue, &fill.venue_order_id)
+                .or_else(|| {
+                    fill.client_order_id
+                        .clone()
+                        .filter(|c| st.orders.contains_key(c))
+                });
+            match cid {
+                Some(cid) => st.apply_fill(&cid, &update, &mut out),
+                None => st.push_pending(
+                    &self.cfg,
+                    (fill.venue.0.clone(), fill.venue_order_id.clone()),
+                    PendingEvent::Fill(update),
+                ),
+            }
+        }
+        self.publish(&out);
+        Ok(out)
+    }
+
+    /// private WS イベントを反映する。注文・約定以外は無視する。
+    /// 未知の venue_order_id は receipt が届くまで保留する。
+    pub fn apply_private_ws_event(
+        &self,
+        venue: &VenueId,
+        ev: &CanonicalPrivateWsEvent,
+    ) -> SdkExecutionResult<Vec<OrderUpdate>> {
+        let mut out = Vec::new();
+        {
+            let mut st = self.lock()?;
+            match ev {
+                CanonicalPrivateWsEvent::Order(o) => {
+                    match st.client_id_for_venue_order(venue, &o.order_id) {
+                        Some(cid) => st.apply_order_event(&cid, o, &mut out)?,
+                        None => st.push_pending(
+                            &self.cfg,
+                            (venue.0.clone(), o.order_id.clone()),
+                            PendingEvent::Order(o.clone()),
+                        ),
+                    }
+                }
+                CanonicalPrivateWsEvent::Fill(f) => {
+                    let Some(order_id) = f.order_id.as_deref() else {
+                        return Err(SdkExecutionError::new(
+                            SdkExecutionErrorCode::InvalidInput,
+                            format!("fill {} has no order_id", f.fill_id),
+                        ));
+                    };
+                    let update = FillUpdate::from_ws(f)?;
+                    match st.client_id_for_venue_order(venue, order_id) {
+                        Some(cid) => st.apply_fill(&cid, &update, &mut out),
+                        None => st.push_pending(
+                            &self.cfg,
+                            (venue.0.clone(), order_id.to_string()),
+                            PendingEvent::Fill(update),
+                        ),
+                    }
+                }
+                _ => {}
+            }
+        }
+        self.publish(&out);
+        Ok(out)
+    }
+
+    /// cancel が受理された注文を Canceled にする（終端済みなら異常として返す）。
+    pub fn apply_cancel_ack(
+        &self,
+        venue: &VenueId,
+        venue_order_id: &str,
+    ) -> SdkExecutionResult<Vec<OrderUpdate>> {
+        let mut out = Vec::new();
+        {
+            let mut st = self.lock()?;
+            let Some(cid) = st.client_id_for_venue_order(venue, venue_order_id) else {
+                return Ok(out);
+            };
+            if let Some(entry) = st.orders.get_mut(&cid) {
+                let mut t = Transition::begin(entry);
+                t.set_status(OrderStatus::Canceled);
+                out.extend(t.finish(OrderUpdateCause::CancelAck));
+            }
+        }
+        self.publish(&out);
+        Ok(out)
+    }
+
+    /// list_open_orders の結果を突き合わせる。
+    /// - 一覧にある注文は receipt の状態を反映（PartiallyFilled を Open へ戻すことはしない）
+    /// - ローカルで open なのに一覧に無い注文は missing_on_venue として返す
+    pub fn apply_open_orders(
+        &self,
+        venue: &VenueId,
+        symbol: Option<&Symbol>,
+        open: &[OrderReceipt],
+    ) -> SdkExecutionResult<OpenOrdersSync> {
+        let mut sync = OpenOrdersSync::default();
+        {
+            let mut st = self.lock()?;
+            let mut seen = BTreeSet::new();
+            for r in open {
+                let cid = r
+                    .venue_order_id
+                    .as_deref()
+                    .and_then(|v| st.client_id_for_venue_order(venue, v))
+                    .or_else(|| {
+                        r.client_order_id
+                            .clone()
+                            .filter(|c| st.orders.contains_key(c))
+                    });
+                let Some(cid) = cid else {
+                    sync.unknown_venue_orders.push(r.clone());
+                    continue;
+                };
+                seen.insert(cid.clone());
+                if let Some(vid) = r.venue_order_id.as_deref() {
+                    st.link_venue_order(venue, vid, &cid);
+                }
+                let Some(entry) = st.orders.get_mut(&cid) else {
+                    continue;
+                };
+                let mut t = Transition::begin(entry);
+                if t.entry.order.venue_order_id.is_none() {
+                    t.entry.order.venue_order_id = r.venue_order_id.clone();
+                }
+                let stale_open = t.entry.order.status == OrderStatus::PartiallyFilled
+                    && r.status == OrderStatus::Open;
+                if !stale_open {
+                    t.set_status(r.status.clone());
+                }
+                sync.updates
+                    .extend(t.finish(OrderUpdateCause::OpenOrdersPoll));
+                if let Some(vid) = r.venue_order_id.as_deref() {
+                    st.drain_pending(venue, vid, &cid, &mut sync.updates)?;
+                }
+            }
+
+            let mut missing = st
+                .orders
+                .values()
+                .map(|e| &e.order)
+                .filter(|o| &o.venue == venue)
+                .filter(|o| symbol.is_none_or(|s| &o.symbol == s))
+                .filter(|o| o.venue_order_id.is_some())
+                .filter(|o| {
+                    matches!(
+                        o.status,
+                        OrderStatus::Accepted | OrderStatus::Open | OrderStatus::PartiallyFilled
+                    )
+                })
+                .filter(|o| !seen.contains(&o.client_order_id))
+                .map(|o| o.client_order_id.clone())
+                .collect::<Vec<_>>();
+            missing.sort();
+            sync.missing_on_venue = missing;
+        }
+        self.publish(&sync.updates);
+        Ok(sync)
+    }
+
+    /// connector に open orders を問い合わせて突き合わせる（REST ポーリング）
+    pub fn poll_open_orders<C: ExecutionConnector + ?Sized>(
+        &self,
+        connector: &C,
+        q: &OrderOpenQuery,
+    ) -> SdkExecutionResult<OpenOrdersSync> {
+        let open = connector.list_open_orders(q)?;
+        self.apply_open_orders(&q.venue, q.symbol.as_ref(), &open)
+    }
+
+    fn publish(&self, updates: &[OrderUpdate]) {
+        for u in updates {
+            // 購読者がいない場合の送信失敗は無視する
+            let _ = self.tx.send(u.clone());
+        }
+    }
+
+    fn lock(&self) -> SdkExecutionResult<std::sync::MutexGuard<'_, ManagerState>> {
+        self.state.lock().map_err(|_| {
+            SdkExecutionError::new(
+                SdkExecutionErrorCode::Internal,
+                "order manager lock poisoned",
+            )
+        })
+    }
+}
@@ -0,0 +1,561 @@
+use crate::execution::{
+    unix_ms_now, AuditEvent, AuditSink, ExecutionConnector, ExecutionFill, FillLiquidity,
+    IdempotencyKey, OrderCancel, OrderIntentId, OrderOpenQuery, OrderReceipt, OrderRequest,
+    OrderSide, OrderStatus, OrderTimeInForce, OrderType, Price, Quantity, SdkExecutionError,
+    SdkExecutionErrorCode, SdkExecutionResult, Symbol, VenueId,
+};
+use std::collections::{BTreeMap, HashMap};
+use std::sync::Mutex;
+use ucel_core::{
+    CanonicalOrderBookLevel, CanonicalOrderBookSnapshot, CanonicalTrade, Decimal, Side,
+};
+
+/// Paper venue の設定。
+#[derive(Clone, Debug)]
+pub struct PaperVenueConfig {
+    /// receipt / fill に載せる venue 名
+    pub venue: VenueId,
+    /// maker 手数料（bps）。負値はリベート。
+    pub maker_fee_bps: Decimal,
+    /// taker 手数料（bps）
+    pub taker_fee_bps: Decimal,
+}
+
+impl Default for PaperVenueConfig {
+    fn default() -> Self {
+        Self {
+            venue: VenueId::new("paper"),
+            maker_fee_bps: Decimal::ZERO,
+            taker_fee_bps: Decimal::ZERO,
+        }
+    }
+}
+
+/// 板に残っている（resting）自分の注文。
+#[derive(Clone, Debug)]
+struct PaperOrder {
+    seq: u64,
+    venue_order_id: String,
+    client_order_id: Option<String>,
+    intent_id: OrderIntentId,
+    idempotency: IdempotencyKey,
+    run_id: Option<String>,
+    symbol: Symbol,
+    side: OrderSide,
+    price: Decimal,
+    qty: Decimal,
+    filled: Decimal,
+    /// 同価格に自分より先に並んでいる数量（queue position）
+    queue_ahead: Decimal,
+    status: OrderStatus,
+}
+
+impl PaperOrder {
+    fn remaining(&self) -> Decimal {
+        self.qty - self.filled
+    }
+
+    fn crosses(&self, opposite_price: Decimal) -> bool {
+        match self.side {
+            OrderSide::Buy => opposite_price <= self.price,
+            OrderSide::Sell => opposite_price >= self.price,
+        }
+    }
+}
+
+#[derive(Default)]
+struct PaperState {
+    books: HashMap<String, CanonicalOrderBookSnapshot>,
+    /// venue_order_id -> resting order
+    resting: BTreeMap<String, PaperOrder>,
+    fills: Vec<ExecutionFill>,
+    next_order_seq: u64,
+    next_fill_seq: u64,
+}
+
+/// 監査へ流す前の一時バッファ（lock 外で append するため）。
+#[derive(Default)]
+struct PaperEmit {
+    events: Vec<AuditEvent>,
+    fills: Vec<ExecutionFill>,
+}
+
+/// Paper 用のシミュレーション venue。
+/// - `CanonicalOrderBookSnapshot` / `CanonicalTrade` を流し込むと、板と約定から自分の注文を照合する
+/// - 成行 / 指値 / post-only、IOC / FOK、部分約定、queue position、maker/taker 手数料、キャンセルに対応
+/// - 約定と注文状態の変化は AuditSink へ `AuditEvent::Fill` / `OrderStatusChanged` として残す
+///
+/// queue model:
+/// - 指値が板に並んだ時点の同価格数量を queue_ahead とする
+/// - 同価格の約定は queue_ahead を先に消化し、残りで自分が約定する
+/// - 価格を跨いだ約定（trade-through）は queue を無視して約定する
+/// - snapshot で同価格数量が減った場合は前方キャンセルとみなし queue_ahead を縮める（楽観的）
+pub struct PaperExecutionVenue {
+    cfg: PaperVenueConfig,
+    state: Mutex<PaperState>,
+    audit: Option<Box<dyn AuditSink>>,
+}
+
+impl PaperExecutionVenue {
+    pub fn new(cfg: PaperVenueConfig) -> Self {
+        Self {
+            cfg,
+            state: Mutex::new(PaperState::default()),
+            audit: None,
+        }
+    }
+
+    pub fn with_audit(mut self, audit: Box<dyn AuditSink>) -> Self {
+        self.audit = Some(audit);
+        self
+    }
+
+    pub fn config(&self) -> &PaperVenueConfig {
+        &self.cfg
+    }
+
+    /// 板 snapshot を反映し、板を跨いだ resting 注文を maker として約定させる。
+    pub fn on_orderbook_snapshot(
+        &self,
+        snapshot: &CanonicalOrderBookSnapshot,
+    ) -> SdkExecutionResult<Vec<ExecutionFill>> {
+        let emit = {
+            let mut st = self.lock()?;
+            let mut book = snapshot.clone();
+            sort_book(&mut book);
+            let mut emit = PaperEmit::default();
+
+            let ids: Vec<String> = [OrderSide::Buy, OrderSide::Sell]
+                .into_iter()
+                .flat_map(|side| resting_ids_by_priority(&st, &snapshot.symbol, side))
+                .collect();
+            for id in ids {
+                let Some(mut order) = st.resting.remove(&id) else {
+                    continue;
+                };
+                let opposite = match order.side {
+                    OrderSide::Buy => &mut book.asks,
+                    OrderSide::Sell => &mut book.bids,
+                };
+                let mut takes = Vec::new();
+                let mut remaining = order.remaining();
+                for level in opposite.iter_mut() {
+                    if remaining <= Decimal::ZERO || !order.crosses(level.price) {
+                        break;
+                    }
+                    let q = remaining.min(level.qty);
+                    level.qty -= q;
+                    remaining -= q;
+                    takes.push(q);
+                }
+                opposite.retain(|l| l.qty > Decimal::ZERO);
+                for q in takes {
+                    self.fill_resting(&mut st, &mut order, q, &mut emit);
+                }
+
+                let same_side = match order.side {
+                    OrderSide::Buy => &book.bids,
+                    OrderSide::Sell => &book.asks,
+                };
+                let at_price = level_qty(same_side, order.price);
+                order.queue_ahead = order.queue_ahead.min(at_price);
+
+                if order.remaining() > Decimal::ZERO {
+                    st.resting.insert(id, order);
+                }
+            }
+
+            st.books.insert(snapshot.symbol.clone(), book);
+            emit
+        };
+        self.flush(emit)
+    }
+
+    /// 公開約定を反映し、queue を消化したうえで resting 注文を maker として約定させる。
+    /// `trade.side` は aggressor 側（Buy なら ask が食われた）とみなす。
+    pub fn on_trade(&self, trade: &CanonicalTrade) -> SdkExecutionResult<Vec<ExecutionFill>> {
+        let passive_side = match trade.side {
+            Side::Buy => OrderSide::Sell,
+            Side::Sell => OrderSide::Buy,
+            Side::Unknown => return Ok(vec![]),
+        };
+        let emit = {
+            let mut st = self.lock()?;
+            let mut emit = PaperEmit::default();
+            let mut budget = trade.qty;
+
+            for id in resting_ids_by_priority(&st, &trade.symbol, passive_side) {
+                if budget <= Decimal::ZERO {
+                    break;
+                }
+                let Some(mut order) = st.resting.remove(&id) else {
+                    continue;
+                };
+                if !order.crosses(trade.price) {
+                    st.resting.insert(id, order);
+                    continue;
+                }
+                if trade.price == order.price {
+                    let consumed = budget.min(order.queue_ahead);
+                    order.queue_ahead -= consumed;
+                    budget -= consumed;
+                } else {
+                    // 価格を跨いだ約定: 自分の価格帯は食い尽くされている
+                    order.queue_ahead = Decimal::ZERO;
+                }
+                let q = budget.min(order.remaining());
+                if q > Decimal::ZERO {
+                    budget -= q;
+                    self.fill_resting(&mut st, &mut order, q, &mut emit);
+                }
+                if order.remaining() > Decimal::ZERO {
+                    st.resting.insert(id, order);
+                }
+            }
+            emit
+        };
+        self.flush(emit)
+    }
+
+    /// これまでの約定（時系列順）
+    pub fn fills(&self) -> SdkExecutionResult<Vec<ExecutionFill>> {
+        Ok(self.lock()?.fills.clone())
+    }
+
+    /// 発注し、即時に taker として約定した分の fill も返す（OrderManager への反映用）。
+    pub fn place_order_with_fills(
+        &self,
+        req: &OrderRequest,
+    ) -> SdkExecutionResult<(OrderReceipt, Vec<ExecutionFill>)> {
+        let (receipt, emit) = {
+            let mut st = self.lock()?;
+            let mut emit = PaperEmit::default();
+            let receipt = self.match_new_order(&mut st, req, &mut emit)?;
+            (receipt, emit)
+        };
+        let fills = self.flush(emit)?;
+        Ok((receipt, fills))
+    }
+
+    /// この venue が採番した resting 注文かどうか
+    pub fn has_open_order(&self, venue_order_id: &str) -> bool {
+        self.lock()
+            .map(|st| st.resting.contains_key(venue_order_id))
+            .unwrap_or(false)
+    }
+
+    fn lock(&self) -> SdkExecutionResult<std::sync::MutexGuard<'_, PaperState>> {
+        self.state.lock().map_err(|_| {
+            SdkExecutionError::new(SdkExecutionErrorCode::Internal, "paper venue lock poisoned")
+        })
+    }
+
+    fn flush(&self, emit: PaperEmit) -> SdkExecutionResult<Vec<ExecutionFill>> {
+        if let Some(a) = self.audit.as_ref() {
+            for ev in emit.events {
+                a.append(ev)?;
+            }
+        }
+        Ok(emit.fills)
+    }
+
+    fn fee(&self, price: Decimal, qty: Decimal, liquidity: FillLiquidity) -> Decimal {
+        let bps = match liquidity {
+            FillLiquidity::Maker => self.cfg.maker_fee_bps,
+            FillLiquidity::Taker => self.cfg.taker_fee_bps,
+        };
+        price * qty * bps / Decimal::from(10_000)
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    fn record_fill(
+        &self,
+        st: &mut PaperState,
+        venue_order_id: &str,
+        client_order_id: Option<String>,
+        run_id: Option<String>,
+        symbol: &Symbol,
+        side: OrderSide,
+        price: Decimal,
+        qty: Decimal,
+        liquidity: FillLiquidity,
+        emit: &mut PaperEmit,
+    ) {
+        st.next_fill_seq += 1;
+        let fill = ExecutionFill {
+            venue: self.cfg.venue.clone(),
+            symbol: symbol.clone(),
+            venue_order_id: venue_order_id.to_string(),
+            client_order_id,
+            fill_id: format!("paper-fill-{}", st.next_fill_seq),
+            side,
+            price: Price(price),
+            qty: Quantity(qty),
+            fee: self.fee(price, qty, liquidity),
+            liquidity,
+            unix_ms: unix_ms_now(),
+        };
+        st.fills.push(fill.clone());
+        emit.events.push(AuditEvent::Fill {
+            run_id,
+            fill: fill.clone(),
+        });
+        emit.fills.push(fill);
+    }
+
+    /// resting 注文の約定。maker なので約定価格は常に自分の指値。
+    fn fill_resting(
+        &self,
+        st: &mut PaperState,
+        order: &mut PaperOrder,
+        qty: Decimal,
+        emit: &mut PaperEmit,
+    ) {
+        order.filled += qty;
+        self.record_fill(
+            st,
+            &order.venue_order_id,
+            order.client_order_id.clone(),
+            order.run_id.clone(),
+            &order.symbol,
+            order.side,
+            order.price,
+            qty,
+            FillLiquidity::Maker,
+            emit,
+        );
+        order.status = if order.remaining() <= Decimal::ZERO {
+            OrderStatus::Filled
+        } else {
+            OrderStatus::PartiallyFilled
+        };
+        emit.events.push(self.status_event(order));
+    }
+
+    fn status_event(&self, order: &PaperOrder) -> AuditEvent {
+        AuditEvent::OrderStatusChanged {
+            run_id: order.run_id.clone(),
+            venue: self.cfg.venue.clone(),
+            symbol: order.symbol.clone(),
+            venue_order_id: order.venue_order_id.clone(),
+            client_order_id: order.client_order_id.clone(),
+            status: order.status.clone(),
+            filled_qty: Quantity(order.filled),
+            unix_ms: unix_ms_now(),
+        }
+    }
+
+    fn receipt(&self, order: &PaperOrder) -> OrderReceipt {
+        OrderReceipt {
+            venue: self.cfg.venue.clone(),
+            symbol: order.symbol.clone(),
+            status: order.status.clone(),
+            venue_order_id: Some(order.venue_order_id.clone()),
+            client_order_id: order.client_order_id.clone(),
+            intent_id: order.intent_id.clone(),
+            idempotency: order.idempotency.clone(),
+        }
+    }
+
+    fn match_new_order(
+        &self,
+        st: &mut PaperState,
+        req: &OrderRequest,
+        emit: &mut PaperEmit,
+    ) -> SdkExecutionResult<OrderReceipt> {
+        let intent = &req.intent;
+        let qty = intent.qty.as_decimal();
+        let limit = intent.price.map(|p| p.as_decimal());
+        let tif = match intent.order_type {
+            // 成行は板に残さない
+            OrderType::Market => OrderTimeInForce::Ioc,
+            _ => intent.tif.unwrap_or(OrderTimeInForce::Gtc),
+        };
+
+        st.next_order_seq += 1;
+        let mut order = PaperOrder {
+            seq: st.next_order_seq,
+            venue_order_id: format!("paper-{}", st.next_order_seq),
+            client_order_id: intent.tags.get("client_order_id").cloned(),
+            intent_id: intent.intent_id.clone(),
+            idempotency: req.idempotency.clone(),
+            run_id: req.run_id.clone(),
+            symbol: intent.symbol.clone(),
+            side: intent.side,
+            price: limit.unwrap_or(Decimal::ZERO),
+            qty,
+            filled: Decimal::ZERO,
+            queue_ahead: Decimal::ZERO,
+            status: OrderStatus::Accepted,
+        };
+
+        let Some(book) = st.books.get_mut(&intent.symbol.0) else {
+            order.status = OrderStatus::Rejected;
+            emit.events.push(self.status_event(&order));
+            return Ok(self.receipt(&order));
+        };
+
+        let crossable = |level_price: Decimal| match (intent.side, limit) {
+            (_, None) => true,
+            (OrderSide::Buy, Some(p)) => level_price <= p,
+            (OrderSide::Sell, Some(p)) => level_price >= p,
+        };
+        let opposite = match intent.side {
+            OrderSide::Buy => &mut book.asks,
+            OrderSide::Sell => &mut book.bids,
+        };
+
+        if intent.order_type == OrderType::PostOnly
+            && opposite.first().is_some_and(|l| crossable(l.price))
+        {
+            order.status = OrderStatus::Rejected;
+            emit.events.push(self.status_event(&order));
+            return Ok(self.receipt(&order));
+        }
+
+        let mut takes: Vec<(Decimal, Decimal)> = Vec::new();
+        if intent.order_type != OrderType::PostOnly {
+            if tif == OrderTimeInForce::Fok {
+                let available: Decimal = opposite
+                    .iter()
+                    .take_while(|l| crossable(l.price))
+                    .map(|l| l.qty)
+                    .sum();
+                if available < qty {
+                    order.status = OrderStatus::Expired;
+                    emit.events.push(self.status_event(&order));
+                    return Ok(self.receipt(&order));
+                }
+            }
+            let mut remaining = qty;
+            for level in opposite.iter_mut() {
+                if remaining <= Decimal::ZERO || !crossable(level.price) {
+                    break;
+                }
+                let q = remaining.min(level.qty);
+                level.qty -= q;
+                remaining -= q;
+                takes.push((level.price, q));
+            }
+            opposite.retain(|l| l.qty > Decimal::ZERO);
+        }
+
+        let same_side_qty = {
+            let same_side = match intent.side {
+                OrderSide::Buy => &book.bids,
+                OrderSide::Sell => &book.asks,
+            };
+            level_qty(same_side, order.price)
+        };
+
+        for (price, q) in takes {
+            order.filled += q;
+            self.record_fill(
+                st,
+                &order.venue_order_id,
+                order.client_order_id.clone(),
+                order.run_id.clone(),
+                &order.symbol,
+                order.side,
+                price,
+                q,
+                FillLiquidity::Taker,
+                emit,
+            );
+        }
+
+        order.status = if order.remaining() <= Decimal::ZERO {
+            OrderStatus::Filled
+        } else if tif != OrderTimeInForce::Gtc {
+            OrderStatus::Expired
+        } else {
+            order.queue_ahead = same_side_qty;
+            if order.filled > Decimal::ZERO {
+                OrderStatus::PartiallyFilled
+            } else {
+                OrderStatus::Open
+            }
+        };
+        emit.events.push(self.status_event(&order));
+
+        let receipt = self.receipt(&order);
+        if matches!(
+            order.status,
+            OrderStatus::Open | OrderStatus::PartiallyFilled
+        ) {
+            st.resting.insert(order.venue_order_id.clone(), order);
+        }
+        Ok(receipt)
+    }
+}
+
+impl ExecutionConnector for PaperExecutionVenue {
+    fn place_order(&self, req: &OrderRequest) -> SdkExecutionResult<OrderReceipt> {
+        Ok(self.place_order_with_fills(req)?.0)
+    }
+
+    fn cancel_order(&self, cancel: &OrderCancel) -> SdkExecutionResult<bool> {
+        let emit = {
+            let mut st = self.lock()?;
+            let Some(mut order) = st.resting.remove(&cancel.venue_order_id) else {
+                return Ok(false);
+            };
+            order.status = OrderStatus::Canceled;
+            PaperEmit {
+                events: vec![self.status_event(&order)],
+                fills: vec![],
+            }
+        };
+        self.flush(emit)?;
+        Ok(true)
+    }
+
+    fn list_open_orders(&self, q: &OrderOpenQuery) -> SdkExecutionResult<Vec<OrderReceipt>> {
+        if q.venue != self.cfg.venue {
+            return Ok(vec![]);
+        }
+        let st = self.lock()?;
+        let mut orders = st
+            .resting
+            .values()
+            .filter(|o| q.symbol.as_ref().is_none_or(|s| *s == o.symbol))
+            .collect::<Vec<_>>();
+        orders.sort_by_key(|o| o.seq);
+        Ok(orders.into_iter().map(|o| self.receipt(o)).collect())
+    }
+}
+
+/// `side` の resting 注文の id を価格優先・時間優先で並べる。
+fn resting_ids_by_priority(st: &PaperState, symbol: &str, side: OrderSide) -> Vec<String> {
+    let mut orders = st
+        .resting
+        .values()
+        .filter(|o| o.symbol.0 == symbol && o.side == side)
+        .collect::<Vec<_>>();
+    orders.sort_by(|a, b| {
+        let by_price = match side {
+            OrderSide::Buy => b.price.cmp(&a.price),
+            OrderSide::Sell => a.price.cmp(&b.price),
+        };
+        by_price.then(a.seq.cmp(&b.seq))
+    });
+    orders
+        .into_iter()
+        .map(|o| o.venue_order_id.clone())
+        .collect()
+}
+
+fn sort_book(book: &mut CanonicalOrderBookSnapshot) {
+    book.bids.retain(|l| l.qty > Decimal::ZERO);
+    book.asks.retain(|l| l.qty > Decimal::ZERO);
+    book.bids.sort_by_key(|l| std::cmp::Reverse(l.price));
+    book.asks.sort_by_key(|l| l.price);
+}
+
+fn level_qty(levels: &[CanonicalOrderBookLevel], price: Decimal) -> Decimal {
+    levels
+        .iter()
+        .find(|l| l.price == price)
+        .map(|l| l.qty)
+        .unwrap_or(Decimal::ZERO)
+}
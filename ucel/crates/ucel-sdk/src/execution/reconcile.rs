@@ -0,0 +1,603 @@
+use crate::execution::{
+    unix_ms_now, AuditEvent, ExecutionFill, ExecutionMode, OrderReceipt, OrderStatus,
+    ReconcileReport, ReconcileSource, SdkExecutionErrorCode, SdkExecutionResult, Symbol, VenueId,
+};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet};
+use std::str::FromStr;
+use ucel_core::{CanonicalBalance, Decimal};
+
+/// venue から約定履歴を取得する条件
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct FillQuery {
+    pub venue: VenueId,
+    pub symbol: Option<Symbol>,
+    pub since_unix_ms: Option<u64>,
+}
+
+/// 照合で検出した差異の種別
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+pub enum ReconcileMismatchKind {
+    /// venue に open 注文があるが、ローカル監査に発注記録が無い
+    OrphanVenueOrder,
+    /// ローカルでは生きている注文が venue の open 一覧にも約定にも見当たらない
+    MissingLocalOrder,
+    /// 累積約定数量がローカルと venue で一致しない
+    QtyDrift,
+    /// 注文状態がローカルと venue で矛盾する
+    StatusDrift,
+    /// 期待残高と venue 残高の差が許容値を超えた
+    BalanceDrift,
+}
+
+/// 差異 1 件。local / venue には比較した値を文字列で残す。
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ReconcileMismatch {
+    pub kind: ReconcileMismatchKind,
+    pub symbol: Option<Symbol>,
+    pub venue_order_id: Option<String>,
+    pub client_order_id: Option<String>,
+    pub asset: Option<String>,
+    pub local: Option<String>,
+    pub venue: Option<String>,
+    pub detail: String,
+}
+
+/// 差異に対する推奨対処。実行は呼び出し側の判断に委ねる。
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum RemediationAction {
+    /// 追跡外の venue 注文を取り消す
+    CancelOrphan {
+        symbol: Symbol,
+        venue_order_id: String,
+    },
+    /// ローカルで取り消し済みの注文が venue に残っているので再度取り消す
+    RetryCancel {
+        symbol: Symbol,
+        venue_order_id: String,
+    },
+    /// venue で所在不明の注文をローカルで lost として閉じる
+    MarkLost {
+        client_order_id: Option<String>,
+        venue_order_id: Option<String>,
+    },
+    /// ローカル状態を venue の状態へ合わせる
+    ResyncOrderStatus {
+        venue_order_id: String,
+        status: OrderStatus,
+    },
+    /// ローカルに無い venue 約定を取り込む
+    ApplyVenueFills {
+        venue_order_id: String,
+        fill_ids: Vec<String>,
+    },
+    /// 残高を venue から取り直す
+    ResyncBalance { asset: String },
+}
+
+#[derive(Clone, Debug)]
+pub struct ReconcileConfig {
+    /// 残高差の許容値（asset 単位、free + locked で比較）
+    pub balance_tolerance: Decimal,
+}
+
+impl Default for ReconcileConfig {
+    fn default() -> Self {
+        Self {
+            balance_tolerance: Decimal::new(1, 8),
+        }
+    }
+}
+
+/// 照合時点で venue から取得したデータ。
+/// fills / balances は connector が未対応なら None（その照合は行わない）。
+#[derive(Clone, Debug, Default)]
+pub struct VenueSnapshot {
+    pub open_orders: Vec<OrderReceipt>,
+    pub fills: Option<Vec<ExecutionFill>>,
+    pub balances: Option<Vec<CanonicalBalance>>,
+}
+
+/// 監査ログから復元したローカルの注文
+#[derive(Clone, Debug)]
+struct LocalOrder {
+    run_id: Option<String>,
+    symbol: Symbol,
+    client_order_id: Option<String>,
+    venue_order_id: Option<String>,
+    qty: Decimal,
+    status: OrderStatus,
+    filled_qty: Decimal,
+    fill_ids: HashSet<String>,
+}
+
+impl LocalOrder {
+    fn is_live(&self) -> bool {
+        matches!(
+            self.status,
+            OrderStatus::Accepted | OrderStatus::Open | OrderStatus::PartiallyFilled
+        )
+    }
+}
+
+/// AuditSink の履歴（Live のみ）から venue ごとの注文台帳を組み立てる
+fn local_orders(venue: &VenueId, history: &[AuditEvent]) -> Vec<LocalOrder> {
+    let mut orders: Vec<LocalOrder> = Vec::new();
+    let mut by_idem: HashMap<String, usize> = HashMap::new();
+    let mut by_vid: HashMap<String, usize> = HashMap::new();
+
+    for ev in history {
+        match ev {
+            AuditEvent::OrderRequested {
+                run_id,
+                idempotency,
+                intent,
+                mode: ExecutionMode::Live,
+                ..
+            } if &intent.venue == venue => {
+                by_idem.insert(idempotency.0.clone(), orders.len());
+                orders.push(LocalOrder {
+                    run_id: run_id.clone(),
+                    symbol: intent.symbol.clone(),
+                    client_order_id: intent.tags.get("client_order_id").cloned(),
+                    venue_order_id: None,
+                    qty: intent.qty.0,
+                    status: OrderStatus::Unknown,
+                    filled_qty: Decimal::ZERO,
+                    fill_ids: HashSet::new(),
+                });
+            }
+            AuditEvent::OrderResult {
+                idempotency,
+                receipt,
+                ..
+            } => {
+                let Some(&i) = by_idem.get(&idempotency.0) else {
+                    continue;
+                };
+                let o = &mut orders[i];
+                o.status = receipt.status.clone();
+                if receipt.client_order_id.is_some() {
+                    o.client_order_id = receipt.client_order_id.clone();
+                }
+                if let Some(vid) = receipt.venue_order_id.as_ref() {
+                    o.venue_order_id = Some(vid.clone());
+                    by_vid.insert(vid.clone(), i);
+                }
+            }
+            AuditEvent::CancelResult {
+                venue_order_id,
+                ok: true,
+                ..
+            } => {
+                if let Some(&i) = by_vid.get(venue_order_id) {
+                    if !orders[i].status.is_terminal() {
+                        orders[i].status = OrderStatus::Canceled;
+                    }
+                }
+            }
+            AuditEvent::OrderStatusChanged {
+                venue: v,
+                venue_order_id,
+                status,
+                filled_qty,
+                ..
+            } if v == venue => {
+                if let Some(&i) = by_vid.get(venue_order_id) {
+                    let o = &mut orders[i];
+                    if o.status.can_transition_to(status) {
+                        o.status = status.clone();
+                    }
+                    o.filled_qty = o.filled_qty.max(filled_qty.0);
+                }
+            }
+            AuditEvent::Fill { fill, .. } if &fill.venue == venue => {
+                if let Some(&i) = by_vid.get(&fill.venue_order_id) {
+                    let o = &mut orders[i];
+                    if o.fill_ids.insert(fill.fill_id.clone()) {
+                        o.filled_qty += fill.qty.0;
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+    orders
+}
+
+fn balance_total(b: &CanonicalBalance) -> Option<Decimal> {
+    let free = Decimal::from_str(b.free.trim()).ok()?;
+    let locked = if b.locked.trim().is_empty() {
+        Decimal::ZERO
+    } else {
+        Decimal::from_str(b.locked.trim()).ok()?
+    };
+    Some(free + locked)
+}
+
+/// NotSupported を "照合しない" として扱う（他のエラーはそのまま返す）
+pub(crate) fn optional_capability<T>(r: SdkExecutionResult<T>) -> SdkExecutionResult<Option<T>> {
+    match r {
+        Ok(v) => Ok(Some(v)),
+        Err(e) if e.code == SdkExecutionErrorCode::NotSupported => Ok(None),
+        Err(e) => Err(e),
+    }
+}
+
+/// ローカル監査ログと venue の実データを突き合わせる照合エンジン。
+/// - 監査（Live の発注/結果/取消/約定/状態変化）から注文台帳を復元する
+/// - venue の open orders / fills / balances と比較し、差異を種別付きで返す
+/// - 差異ごとに推奨対処（RemediationAction）を添える
+#[derive(Clone, Debug, Default)]
+pub struct Reconciler {
+    cfg: ReconcileConfig,
+    expected_balances: BTreeMap<String, Vec<CanonicalBalance>>,
+}
+
+impl Reconciler {
+    pub fn new(cfg: ReconcileConfig) -> Self {
+        Self {
+            cfg,
+            expected_balances: BTreeMap::new(),
+        }
+    }
+
+    /// 残高照合の基準（ローカルで期待している残高）を venue ごとに与える。
+    /// 未設定の venue では残高照合を行わない。
+    pub fn with_expected_balances(
+        mut self,
+        venue: VenueId,
+        balances: Vec<CanonicalBalance>,
+    ) -> Self {
+        self.expected_balances.insert(venue.0, balances);
+        self
+    }
+
+    /// venue から取得すべき約定の開始時刻（履歴中で最も古い Live 発注）
+    pub fn fills_since(&self, venue: &VenueId, history: &[AuditEvent]) -> Option<u64> {
+        history
+            .iter()
+            .filter_map(|ev| match ev {
+                AuditEvent::OrderRequested {
+                    intent,
+                    unix_ms,
+                    mode: ExecutionMode::Live,
+                    ..
+                } if &intent.venue == venue => Some(*unix_ms),
+                _ => None,
+            })
+            .min()
+    }
+
+    /// RemediationAction::ApplyVenueFills を監査へ取り込む AuditEvent::Fill を組み立てる。
+    /// run_id は発注時の監査に合わせる（run 単位の reconcile でも取り込んだ約定が見えるように）。
+    /// 既に監査にある fill_id と、venue の約定一覧に無い fill_id は含めない。
+    pub fn venue_fill_events(
+        &self,
+        venue: &VenueId,
+        history: &[AuditEvent],
+        venue_fills: &[ExecutionFill],
+        actions: &[RemediationAction],
+    ) -> Vec<AuditEvent> {
+        let locals = local_orders(venue, history);
+        let mut seen = HashSet::new();
+        let mut out = Vec::new();
+        for action in actions {
+            let RemediationAction::ApplyVenueFills {
+                venue_order_id,
+                fill_ids,
+            } = action
+            else {
+                continue;
+            };
+            let Some(o) = locals
+                .iter()
+                .find(|o| o.venue_order_id.as_deref() == Some(venue_order_id.as_str()))
+            else {
+                continue;
+            };
+            for f in venue_fills.iter().filter(|f| {
+                &f.venue == venue
+                    && &f.venue_order_id == venue_order_id
+                    && fill_ids.contains(&f.fill_id)
+                    && !o.fill_ids.contains(&f.fill_id)
+            }) {
+                if seen.insert(f.fill_id.as_str()) {
+                    out.push(AuditEvent::Fill {
+                        run_id: o.run_id.clone(),
+                        fill: f.clone(),
+                    });
+                }
+            }
+        }
+        out
+    }
+
+    pub fn reconcile(
+        &self,
+        venue: &VenueId,
+        history: &[AuditEvent],
+        snapshot: &VenueSnapshot,
+    ) -> ReconcileReport {
+        self.reconcile_inner(venue, history, snapshot, true)
+    }
+
+    /// 1 run 分の監査履歴だけで照合する。
+    /// venue の open 注文には他の run や手動の注文も含まれるため、ローカルに対応が無い注文を
+    /// OrphanVenueOrder / CancelOrphan として扱わない（それ以外の照合は reconcile と同じ）。
+    pub fn reconcile_run(
+        &self,
+        venue: &VenueId,
+        history: &[AuditEvent],
+        snapshot: &VenueSnapshot,
+    ) -> ReconcileReport {
+        self.reconcile_inner(venue, history, snapshot, false)
+    }
+
+    fn reconcile_inner(
+        &self,
+        venue: &VenueId,
+        history: &[AuditEvent],
+        snapshot: &VenueSnapshot,
+        report_orphans: bool,
+    ) -> ReconcileReport {
+        let mut out = Findings::default();
+        let locals = local_orders(venue, history);
+
+        let mut venue_fills: HashMap<&str, Vec<&ExecutionFill>> = HashMap::new();
+        if let Some(fills) = snapshot.fills.as_ref() {
+            let mut seen = HashSet::new();
+            for f in fills.iter().filter(|f| &f.venue == venue) {
+                if seen.insert(f.fill_id.as_str()) {
+                    venue_fills
+                        .entry(f.venue_order_id.as_str())
+                        .or_default()
+                        .push(f);
+                }
+            }
+        }
+
+        // venue の open 注文をローカルへ対応付ける（venue_order_id → client_order_id の順）
+        let mut matched = BTreeSet::new();
+        for o in &locals {
+            let found = snapshot.open_orders.iter().position(|r| {
+                (o.venue_order_id.is_some() && r.venue_order_id == o.venue_order_id)
+                    || (o.client_order_id.is_some() && r.client_order_id == o.client_order_id)
+            });
+            let venue_filled = o
+                .venue_order_id
+                .as_deref()
+                .and_then(|vid| venue_fills.get(vid))
+                .map(|fs| fs.iter().map(|f| f.qty.0).sum::<Decimal>());
+
+            match found {
+                Some(j) => {
+                    matched.insert(j);
+                    let open = &snapshot.open_orders[j];
+                    let vid = open
+                        .venue_order_id
+                        .clone()
+                        .or_else(|| o.venue_order_id.clone());
+                    if o.status.is_terminal() {
+                        out.status_drift(o, &open.status, "venue still lists the order as open");
+                        if let Some(vid) = vid.clone() {
+                            out.actions.push(RemediationAction::RetryCancel {
+                                symbol: o.symbol.clone(),
+                                venue_order_id: vid,
+                            });
+                        }
+                    } else if o.status == OrderStatus::Unknown {
+                        // 発注結果が監査に残っていないが venue には存在する
+                        out.status_drift(o, &open.status, "order outcome unknown locally");
+                        if let Some(vid) = vid.clone() {
+                            out.actions.push(RemediationAction::ResyncOrderStatus {
+                                venue_order_id: vid,
+                                status: open.status.clone(),
+                            });
+                        }
+                    }
+                }
+                None if o.is_live() || o.status == OrderStatus::Unknown => match venue_filled {
+                    Some(filled) if filled >= o.qty => {
+                        out.status_drift(o, &OrderStatus::Filled, "venue fills complete the order");
+                        if let Some(vid) = o.venue_order_id.clone() {
+                            out.actions.push(RemediationAction::ResyncOrderStatus {
+                                venue_order_id: vid,
+                                status: OrderStatus::Filled,
+                            });
+                        }
+                    }
+                    _ if o.status == OrderStatus::Unknown && o.venue_order_id.is_none() => {
+                        out.missing(o, "order outcome unknown and not found on venue");
+                    }
+                    _ => out.missing(o, "live order not found on venue"),
+                },
+                None => {}
+            }
+
+            // 累積約定数量の照合（venue 約定が取れた場合のみ）
+            if let (Some(vid), Some(filled)) = (o.venue_order_id.as_deref(), venue_filled) {
+                if filled != o.filled_qty {
+                    let missing_fill_ids = venue_fills
+                        .get(vid)
+                        .map(|fs| {
+                            fs.iter()
+                                .filter(|f| !o.fill_ids.contains(&f.fill_id))
+                                .map(|f| f.fill_id.clone())
+                                .collect::<Vec<_>>()
+                        })
+                        .unwrap_or_default();
+                    out.push(ReconcileMismatch {
+                        kind: ReconcileMismatchKind::QtyDrift,
+                        symbol: Some(o.symbol.clone()),
+                        venue_order_id: Some(vid.to_string()),
+                        client_order_id: o.client_order_id.clone(),
+                        asset: None,
+                        local: Some(o.filled_qty.to_string()),
+                        venue: Some(filled.to_string()),
+                        detail: format!(
+                            "filled qty drift on {vid}: local={} venue={filled}",
+                            o.filled_qty
+                        ),
+                    });
+                    if !missing_fill_ids.is_empty() {
+                        out.actions.push(RemediationAction::ApplyVenueFills {
+                            venue_order_id: vid.to_string(),
+                            fill_ids: missing_fill_ids,
+                        });
+                    }
+                }
+            }
+        }
+
+        for (j, r) in snapshot.open_orders.iter().enumerate() {
+            if !report_orphans || matched.contains(&j) {
+                continue;
+            }
+            let vid = r.venue_order_id.clone().unwrap_or_default();
+            out.push(ReconcileMismatch {
+                kind: ReconcileMismatchKind::OrphanVenueOrder,
+                symbol: Some(r.symbol.clone()),
+                venue_order_id: r.venue_order_id.clone(),
+                client_order_id: r.client_order_id.clone(),
+                asset: None,
+                local: None,
+                venue: Some(format!("{:?}", r.status)),
+                detail: format!("venue order {vid} on {} is not tracked locally", r.symbol.0),
+            });
+            if let Some(vid) = r.venue_order_id.clone() {
+                out.actions.push(RemediationAction::CancelOrphan {
+                    symbol: r.symbol.clone(),
+                    venue_order_id: vid,
+                });
+            }
+        }
+
+        if let (Some(expected), Some(actual)) = (
+            self.expected_balances.get(&venue.0),
+            snapshot.balances.as_ref(),
+        ) {
+            self.reconcile_balances(expected, actual, &mut out);
+        }
+
+        ReconcileReport {
+            venue: venue.clone(),
+            source: ReconcileSource::Venue,
+            ok: out.details.is_empty(),
+            mismatches: out.details.iter().map(|m| m.detail.clone()).collect(),
+            generated_at_unix_ms: unix_ms_now(),
+            details: out.details,
+            actions: out.actions,
+        }
+    }
+
+    fn reconcile_balances(
+        &self,
+        expected: &[CanonicalBalance],
+        actual: &[CanonicalBalance],
+        out: &mut Findings,
+    ) {
+        let totals = |bs: &[CanonicalBalance]| {
+            bs.iter()
+                .filter_map(|b| Some((b.asset.to_ascii_uppercase(), balance_total(b)?)))
+                .fold(BTreeMap::<String, Decimal>::new(), |mut m, (a, v)| {
+                    *m.entry(a).or_default() += v;
+                    m
+                })
+        };
+        let expected = totals(expected);
+        let actual = totals(actual);
+        let assets = expected
+            .keys()
+            .chain(actual.keys())
+            .collect::<BTreeSet<_>>();
+        for asset in assets {
+            let l = expected.get(asset).copied().unwrap_or_default();
+            let v = actual.get(asset).copied().unwrap_or_default();
+            if (l - v).abs() > self.cfg.balance_tolerance {
+                out.push(ReconcileMismatch {
+                    kind: ReconcileMismatchKind::BalanceDrift,
+                    symbol: None,
+                    venue_order_id: None,
+                    client_order_id: None,
+                    asset: Some(asset.clone()),
+                    local: Some(l.to_string()),
+                    venue: Some(v.to_string()),
+                    detail: format!("balance drift on {asset}: local={l} venue={v}"),
+                });
+                out.actions.push(RemediationAction::ResyncBalance {
+                    asset: asset.clone(),
+                });
+            }
+        }
+    }
+}
+
+#[derive(Default)]
+struct Findings {
+    details: Vec<ReconcileMismatch>,
+    actions: Vec<RemediationAction>,
+}
+
+impl Findings {
+    fn push(&mut self, m: ReconcileMismatch) {
+        self.details.push(m);
+    }
+
+    fn status_drift(&mut self, o: &LocalOrder, venue_status: &OrderStatus, why: &str) {
+        let id = o
+            .venue_order_id
+            .as_deref()
+            .or(o.client_order_id.as_deref())
+            .unwrap_or("?");
+        self.push(ReconcileMismatch {
+            kind: ReconcileMismatchKind::StatusDrift,
+            symbol: Some(o.symbol.clone()),
+            venue_order_id: o.venue_order_id.clone(),
+            client_order_id: o.client_order_id.clone(),
+            asset: None,
+            local: Some(format!("{:?}", o.status)),
+            venue: Some(format!("{venue_status:?}")),
+            detail: format!(
+                "status drift on {id}: local={:?} venue={venue_status:?} ({why})",
+                o.status
+            ),
+        });
+    }
+
+    fn missing(&mut self, o: &LocalOrder, why: &str) {
+        let id = o
+            .venue_order_id
+            .as_deref()
+            .or(o.client_order_id.as_deref())
+            .unwrap_or("?");
+        self.push(ReconcileMismatch {
+            kind: ReconcileMismatchKind::MissingLocalOrder,
+            symbol: Some(o.symbol.clone()),
+            venue_order_id: o.venue_order_id.clone(),
+            client_order_id: o.client_order_id.clone(),
+            asset: None,
+            local: Some(format!("{:?}", o.status)),
+            venue: None,
+            detail: format!("missing order {id}: {why}"),
+        });
+        self.actions.push(RemediationAction::MarkLost {
+            client_order_id: o.client_order_id.clone(),
+            venue_order_id: o.venue_order_id.clone(),
+        });
+    }
+}
+
+impl ReconcileReport {
+    /// connector 固有の照合結果を取り込む
+    pub fn merge(&mut self, other: ReconcileReport) {
+        self.ok &= other.ok;
+        self.mismatches.extend(other.mismatches);
+        self.details.extend(other.details);
+        self.actions.extend(other.actions);
+    }
+
+    pub fn count(&self, kind: ReconcileMismatchKind) -> usize {
+        self.details.iter().filter(|m| m.kind == kind).count()
+    }
+}
@@ -0,0 +1,362 @@
+use crate::execution::{
+    unix_ms_now, AuditEvent, AuditSink, OrderIntentId, OrderReceipt, OrderRequest, OrderSide,
+    OrderStatus, OrderType, Price, SdkExecutionResult, Symbol, VenueId,
+};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::str::FromStr;
+use std::sync::{Arc, RwLock};
+use ucel_core::{CanonicalBalance, CanonicalTicker, Decimal};
+use ucel_symbol_core::MarketMeta;
+
+/// Shadow 照合に使う参照データの取得口。
+/// venue 実データ（symbol store / ticker / 残高）を差し込めるよう trait で固定する。
+pub trait ShadowMarketContext: Send + Sync {
+    fn market_meta(&self, venue: &VenueId, symbol: &Symbol) -> Option<MarketMeta>;
+    fn latest_ticker(&self, venue: &VenueId, symbol: &Symbol) -> Option<CanonicalTicker>;
+    fn balances(&self, venue: &VenueId) -> Option<Vec<CanonicalBalance>>;
+}
+
+/// 参照データを外から流し込む in-memory 実装。
+#[derive(Default)]
+pub struct InMemoryShadowContext {
+    metas: RwLock<HashMap<(VenueId, Symbol), MarketMeta>>,
+    tickers: RwLock<HashMap<(VenueId, Symbol), CanonicalTicker>>,
+    balances: RwLock<HashMap<VenueId, Vec<CanonicalBalance>>>,
+}
+
+impl InMemoryShadowContext {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn set_market_meta(&self, venue: VenueId, symbol: Symbol, meta: MarketMeta) {
+        if let Ok(mut g) = self.metas.write() {
+            g.insert((venue, symbol), meta);
+        }
+    }
+
+    pub fn set_ticker(&self, venue: VenueId, symbol: Symbol, ticker: CanonicalTicker) {
+        if let Ok(mut g) = self.tickers.write() {
+            g.insert((venue, symbol), ticker);
+        }
+    }
+
+    pub fn set_balances(&self, venue: VenueId, balances: Vec<CanonicalBalance>) {
+        if let Ok(mut g) = self.balances.write() {
+            g.insert(venue, balances);
+        }
+    }
+}
+
+impl ShadowMarketContext for InMemoryShadowContext {
+    fn market_meta(&self, venue: &VenueId, symbol: &Symbol) -> Option<MarketMeta> {
+        self.metas
+            .read()
+            .ok()?
+            .get(&(venue.clone(), symbol.clone()))
+            .cloned()
+    }
+
+    fn latest_ticker(&self, venue: &VenueId, symbol: &Symbol) -> Option<CanonicalTicker> {
+        self.tickers
+            .read()
+            .ok()?
+            .get(&(venue.clone(), symbol.clone()))
+            .cloned()
+    }
+
+    fn balances(&self, venue: &VenueId) -> Option<Vec<CanonicalBalance>> {
+        self.balances.read().ok()?.get(venue).cloned()
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ShadowCheckKind {
+    /// tick/step/min_qty/min_notional（MarketMeta::validate_order）
+    Constraints,
+    /// 最新 ticker からの乖離
+    PriceBand,
+    /// 残高の十分性
+    Balance,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ShadowCheckOutcome {
+    Pass,
+    Fail,
+    /// 参照データが無く判定できなかった
+    Skipped,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct ShadowCheck {
+    pub kind: ShadowCheckKind,
+    pub outcome: ShadowCheckOutcome,
+    pub detail: String,
+}
+
+impl ShadowCheck {
+    fn new(kind: ShadowCheckKind, outcome: ShadowCheckOutcome, detail: impl Into<String>) -> Self {
+        Self {
+            kind,
+            outcome,
+            detail: detail.into(),
+        }
+    }
+}
+
+/// Shadow の "would-have-been" 判定。Live と突き合わせられるよう監査に残す。
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct ShadowVerdict {
+    pub venue: VenueId,
+    pub symbol: Symbol,
+    pub intent_id: OrderIntentId,
+    /// Live で送っていたら受理されていたはずか（Fail が 1 つも無い）
+    pub would_accept: bool,
+    pub checks: Vec<ShadowCheck>,
+    /// 判定に使った参照価格（指値 or ticker 由来）
+    pub reference_price: Option<Price>,
+    /// 判定に使った約定想定 notional（quote 建て）
+    pub notional: Option<Decimal>,
+    pub unix_ms: u64,
+}
+
+impl ShadowVerdict {
+    pub fn failed_checks(&self) -> impl Iterator<Item = &ShadowCheck> {
+        self.checks
+            .iter()
+            .filter(|c| c.outcome == ShadowCheckOutcome::Fail)
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct ShadowConfig {
+    /// 指値が最新 last_price からこれ以上（bps）乖離していたら Fail
+    pub max_price_deviation_bps: Decimal,
+    /// true なら Skipped も不合格として扱う（参照データ必須の厳格モード）
+    pub require_all_checks: bool,
+}
+
+impl Default for ShadowConfig {
+    fn default() -> Self {
+        Self {
+            max_price_deviation_bps: Decimal::from(500),
+            require_all_checks: false,
+        }
+    }
+}
+
+/// Shadow mode の事前照合。発注は一切せず、venue 制約に照らした判定だけを返す。
+#[derive(Clone)]
+pub struct ShadowValidator {
+    cfg: ShadowConfig,
+    ctx: Arc<dyn ShadowMarketContext>,
+}
+
+impl Default for ShadowValidator {
+    fn default() -> Self {
+        Self::new(
+            ShadowConfig::default(),
+            Arc::new(InMemoryShadowContext::new()),
+        )
+    }
+}
+
+impl ShadowValidator {
+    pub fn new(cfg: ShadowConfig, ctx: Arc<dyn ShadowMarketContext>) -> Self {
+        Self { cfg, ctx }
+    }
+
+    pub fn evaluate(&self, req: &OrderRequest) -> SdkExecutionResult<ShadowVerdict> {
+        let intent = &req.intent;
+        let qty = intent.qty.as_decimal();
+        let limit = intent.price.map(|p| p.as_decimal());
+        let ticker = self.ctx.latest_ticker(&intent.venue, &intent.symbol);
+
+        // 成行は ticker の反対側で約定する想定
+        let reference_price = match (intent.order_type, limit) {
+            (OrderType::Limit | OrderType::PostOnly, Some(p)) => Some(p),
+            _ => ticker.as_ref().and_then(|t| match intent.side {
+                OrderSide::Buy => t.best_ask,
+                OrderSide::Sell => t.best_bid,
+            }),
+        };
+
+        let meta = self.ctx.market_meta(&intent.venue, &intent.symbol);
+        let checks = vec![
+            self.check_constraints(meta.as_ref(), reference_price, qty),
+            self.check_price_band(intent.order_type, limit, ticker.as_ref()),
+            self.check_balance(
+                &intent.venue,
+                meta.as_ref(),
+                intent.side,
+                reference_price,
+                qty,
+            ),
+        ];
+
+        let would_accept = checks.iter().all(|c| match c.outcome {
+            ShadowCheckOutcome::Pass => true,
+            ShadowCheckOutcome::Skipped => !self.cfg.require_all_checks,
+            ShadowCheckOutcome::Fail => false,
+        });
+        let notional = reference_price.map(|p| {
+            let cs = meta
+                .as_ref()
+                .and_then(|m| m.contract_size)
+                .unwrap_or(Decimal::ONE);
+            p * qty * cs
+        });
+
+        Ok(ShadowVerdict {
+            venue: intent.venue.clone(),
+            symbol: intent.symbol.clone(),
+            intent_id: intent.intent_id.clone(),
+            would_accept,
+            checks,
+            reference_price: reference_price.map(Price),
+            notional,
+            unix_ms: unix_ms_now(),
+        })
+    }
+
+    /// 判定を監査へ残し、"would-have-been" の receipt を返す（ExecutionClient 共通処理）。
+    pub(crate) fn run(
+        &self,
+        req: &OrderRequest,
+        audit: Option<&dyn AuditSink>,
+    ) -> SdkExecutionResult<OrderReceipt> {
+        let verdict = self.evaluate(req)?;
+        let status = if verdict.would_accept {
+            OrderStatus::Accepted
+        } else {
+            OrderStatus::Rejected
+        };
+        if let Some(a) = audit {
+            a.append(AuditEvent::ShadowVerdict {
+                run_id: req.run_id.clone(),
+                idempotency: req.idempotency.clone(),
+                verdict,
+            })?;
+        }
+        Ok(OrderReceipt {
+            venue: req.intent.venue.clone(),
+            symbol: req.intent.symbol.clone(),
+            status,
+            venue_order_id: None,
+            client_order_id: req.intent.tags.get("client_order_id").cloned(),
+            intent_id: req.intent.intent_id.clone(),
+            idempotency: req.idempotency.clone(),
+        })
+    }
+
+    fn check_constraints(
+        &self,
+        meta: Option<&MarketMeta>,
+        price: Option<Decimal>,
+        qty: Decimal,
+    ) -> ShadowCheck {
+        let kind = ShadowCheckKind::Constraints;
+        let Some(meta) = meta else {
+            return ShadowCheck::new(kind, ShadowCheckOutcome::Skipped, "market meta not found");
+        };
+        let Some(price) = price else {
+            return ShadowCheck::new(
+                kind,
+                ShadowCheckOutcome::Skipped,
+                "no reference price for market order",
+            );
+        };
+        match meta.validate_order(price, qty) {
+            Ok(()) => ShadowCheck::new(kind, ShadowCheckOutcome::Pass, "ok"),
+            Err(e) => ShadowCheck::new(kind, ShadowCheckOutcome::Fail, e.to_string()),
+        }
+    }
+
+    fn check_price_band(
+        &self,
+        order_type: OrderType,
+        limit: Option<Decimal>,
+        ticker: Option<&CanonicalTicker>,
+    ) -> ShadowCheck {
+        let kind = ShadowCheckKind::PriceBand;
+        let (Some(limit), OrderType::Limit | OrderType::PostOnly) = (limit, order_type) else {
+            return ShadowCheck::new(kind, ShadowCheckOutcome::Pass, "market order");
+        };
+        let Some(ticker) = ticker else {
+            return ShadowCheck::new(kind, ShadowCheckOutcome::Skipped, "ticker not available");
+        };
+        if ticker.last_price <= Decimal::ZERO {
+            return ShadowCheck::new(
+                kind,
+                ShadowCheckOutcome::Skipped,
+                "ticker last_price invalid",
+            );
+        }
+        let deviation_bps =
+            (limit - ticker.last_price).abs() / ticker.last_price * Decimal::from(10_000);
+        if deviation_bps > self.cfg.max_price_deviation_bps {
+            ShadowCheck::new(
+                kind,
+                ShadowCheckOutcome::Fail,
+                format!(
+                    "price deviates {}bps from last {} (max {}bps)",
+                    deviation_bps.round_dp(2),
+                    ticker.last_price,
+                    self.cfg.max_price_deviation_bps
+                ),
+            )
+        } else {
+            ShadowCheck::new(kind, ShadowCheckOutcome::Pass, "ok")
+        }
+    }
+
+    fn check_balance(
+        &self,
+        venue: &VenueId,
+        meta: Option<&MarketMeta>,
+        side: OrderSide,
+        price: Option<Decimal>,
+        qty: Decimal,
+    ) -> ShadowCheck {
+        let kind = ShadowCheckKind::Balance;
+        let Some(balances) = self.ctx.balances(venue) else {
+            return ShadowCheck::new(kind, ShadowCheckOutcome::Skipped, "balances not available");
+        };
+        let Some(meta) = meta else {
+            return ShadowCheck::new(kind, ShadowCheckOutcome::Skipped, "market meta not found");
+        };
+        let (asset, required) = match side {
+            OrderSide::Buy => {
+                let Some(price) = price else {
+                    return ShadowCheck::new(
+                        kind,
+                        ShadowCheckOutcome::Skipped,
+                        "no reference price for market order",
+                    );
+                };
+                let cs = meta.contract_size.unwrap_or(Decimal::ONE);
+                (meta.quote.as_deref(), price * qty * cs)
+            }
+            OrderSide::Sell => (meta.base.as_deref(), qty),
+        };
+        let Some(asset) = asset else {
+            return ShadowCheck::new(kind, ShadowCheckOutcome::Skipped, "asset unknown in meta");
+        };
+        let free = balances
+            .iter()
+            .find(|b| b.asset.eq_ignore_ascii_case(asset))
+            .and_then(|b| Decimal::from_str(&b.free).ok())
+            .unwrap_or(Decimal::ZERO);
+        if free < required {
+            ShadowCheck::new(
+                kind,
+                ShadowCheckOutcome::Fail,
+                format!("insufficient {asset}: free {free} < required {required}"),
+            )
+        } else {
+            ShadowCheck::new(kind, ShadowCheckOutcome::Pass, "ok")
+        }
+    }
+}
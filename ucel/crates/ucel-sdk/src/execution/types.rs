@@ -1,5 +1,7 @@
 use serde::{Deserialize, Serialize};
 use std::fmt;
+use std::str::FromStr;
+use ucel_core::Decimal;
 
 #[derive(Clone, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
 pub struct VenueId(pub String);
@@ -28,13 +30,48 @@ impl OrderIntentId {
     }
 }
 
-/// 価格・数量は "Decimal SSOT" が既にある前提だが、ここでは public surface 固定が目的なので
-/// 既存の Decimal 型がある場合はそちらへ差し替える（この型名を re-export し続ける想定）。
-#[derive(Clone, Copy, Debug, PartialEq, PartialOrd, Serialize, Deserialize)]
-pub struct Price(pub f64);
+/// 発注価格。Decimal SSOT（ucel_core::Decimal）で保持し、float を経由させない。
+/// tick 整合の保証は入口の OrderGate（MarketMetaOrderGate）が行う。
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+pub struct Price(pub Decimal);
 
-#[derive(Clone, Copy, Debug, PartialEq, PartialOrd, Serialize, Deserialize)]
-pub struct Quantity(pub f64);
+impl Price {
+    pub fn new(v: Decimal) -> Self {
+        Self(v)
+    }
+
+    pub fn as_decimal(&self) -> Decimal {
+        self.0
+    }
+}
+
+impl FromStr for Price {
+    type Err = rust_decimal::Error;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Decimal::from_str_exact(s.trim()).map(Self)
+    }
+}
+
+/// 発注数量。Price と同じく Decimal で保持する（step 整合は OrderGate が保証）。
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+pub struct Quantity(pub Decimal);
+
+impl Quantity {
+    pub fn new(v: Decimal) -> Self {
+        Self(v)
+    }
+
+    pub fn as_decimal(&self) -> Decimal {
+        self.0
+    }
+}
+
+impl FromStr for Quantity {
+    type Err = rust_decimal::Error;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Decimal::from_str_exact(s.trim()).map(Self)
+    }
+}
 
 /// Execution-surface 固有の発注方向（execution::OrderSide）。
 /// ucel_symbol_core::OrderSide とは別型。今後の統合タスクで alias/From を追加できる。
@@ -82,6 +119,39 @@ pub enum OrderStatus {
     Unknown,
 }
 
+impl OrderStatus {
+    /// これ以上状態が進まない（fill の遅延到着のみ許容される）
+    pub fn is_terminal(&self) -> bool {
+        matches!(
+            self,
+            OrderStatus::Rejected
+                | OrderStatus::Filled
+                | OrderStatus::Canceled
+                | OrderStatus::Expired
+        )
+    }
+
+    /// 注文ライフサイクルとして合法な遷移か。
+    /// - Unknown（未確定）からはどこへでも進める
+    /// - 終端状態からは動かない。既知の状態から Unknown へも戻さない
+    pub fn can_transition_to(&self, next: &OrderStatus) -> bool {
+        use OrderStatus::*;
+        if self == next {
+            return true;
+        }
+        matches!(
+            (self, next),
+            (Unknown, _)
+                | (
+                    Accepted,
+                    Open | PartiallyFilled | Filled | Canceled | Expired | Rejected
+                )
+                | (Open, PartiallyFilled | Filled | Canceled | Expired)
+                | (PartiallyFilled, Filled | Canceled | Expired)
+        ) && *next != Unknown
+    }
+}
+
 #[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct OrderIntent {
     pub intent_id: OrderIntentId,
@@ -105,12 +175,12 @@ impl OrderIntent {
         if self.symbol.0.trim().is_empty() {
             return Err("symbol empty");
         }
-        if !self.qty.0.is_finite() || self.qty.0 <= 0.0 {
+        if self.qty.0 <= Decimal::ZERO {
             return Err("qty invalid");
         }
         if matches!(self.order_type, OrderType::Limit | OrderType::PostOnly) {
             let p = self.price.ok_or("price required for limit/postonly")?;
-            if !p.0.is_finite() || p.0 <= 0.0 {
+            if p.0 <= Decimal::ZERO {
                 return Err("price invalid");
             }
         }
@@ -141,6 +211,30 @@ pub struct OrderReceipt {
     pub idempotency: crate::execution::IdempotencyKey,
 }
 
+/// 約定が maker / taker のどちらで成立したか。
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum FillLiquidity {
+    Maker,
+    Taker,
+}
+
+/// 約定（fill）1件。Paper venue の約定や venue からの約定通知を同じ形で監査に残す。
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct ExecutionFill {
+    pub venue: VenueId,
+    pub symbol: Symbol,
+    pub venue_order_id: String,
+    pub client_order_id: Option<String>,
+    pub fill_id: String,
+    pub side: OrderSide,
+    pub price: Price,
+    pub qty: Quantity,
+    /// 手数料（quote 建て）。負値は maker リベート。
+    pub fee: Decimal,
+    pub liquidity: FillLiquidity,
+    pub unix_ms: u64,
+}
+
 #[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct OrderCancel {
     pub venue: VenueId,
@@ -175,8 +269,15 @@ pub struct ReconcileReport {
     pub venue: VenueId,
     pub source: ReconcileSource,
     pub ok: bool,
+    /// 人が読む要約（details と同順）
     pub mismatches: Vec<String>,
     pub generated_at_unix_ms: u64,
+    /// 種別付きの差異
+    #[serde(default)]
+    pub details: Vec<crate::execution::ReconcileMismatch>,
+    /// 推奨対処
+    #[serde(default)]
+    pub actions: Vec<crate::execution::RemediationAction>,
 }
 
 impl fmt::Display for VenueId {
@@ -4,11 +4,12 @@ use ucel_core::{
     IrIssuerKey, IrMarket, IrNormalizedContent,
 };
 use ucel_ir::{
-    jp_issuer_feed_adapter, jp_issuer_html_adapter, statutory_adapter, timely_adapter,
-    us_issuer_feed_adapter, us_issuer_html_adapter, normalize_artifact, IrArtifactFetchRequest, IrArtifactFetchResponse,
-    IrArtifactListRequest, IrArtifactListResponse, IrDiscoverIssuersRequest,
-    IrDocumentDetailRequest, IrDocumentDetailResponse, IrDocumentListRequest,
-    IrDocumentListResponse, IrIssuerResolutionInput, IrIssuerResolutionResult, IrSourceAdapter,
+    jp_issuer_feed_adapter, jp_issuer_html_adapter, normalize_artifact, statutory_adapter,
+    timely_adapter, us_issuer_feed_adapter, us_issuer_html_adapter, IrArtifactFetchRequest,
+    IrArtifactFetchResponse, IrArtifactListRequest, IrArtifactListResponse,
+    IrDiscoverIssuersRequest, IrDocumentDetailRequest, IrDocumentDetailResponse,
+    IrDocumentListRequest, IrDocumentListResponse, IrIssuerResolutionInput,
+    IrIssuerResolutionResult, IrSourceAdapter,
 };
 use ucel_registry::hub::registry;
 
@@ -51,9 +52,9 @@ impl IrFacade {
         request: &IrDocumentListRequest,
     ) -> SdkResult<IrDocumentListResponse> {
         let adapter = Self::adapter_for(&request.source_id)?;
-        Ok(adapter
+        adapter
             .list_documents(request)
-            .map_err(|e| SdkError::Config(e.to_string()))?)
+            .map_err(|e| SdkError::Config(e.to_string()))
     }
 
     pub fn fetch_ir_document_detail(
@@ -61,9 +62,9 @@ impl IrFacade {
         request: &IrDocumentDetailRequest,
     ) -> SdkResult<IrDocumentDetailResponse> {
         let adapter = Self::adapter_for(&request.source_id)?;
-        Ok(adapter
+        adapter
             .fetch_document_detail(request)
-            .map_err(|e| SdkError::Config(e.to_string()))?)
+            .map_err(|e| SdkError::Config(e.to_string()))
     }
 
     pub fn list_ir_artifacts(
@@ -71,9 +72,9 @@ impl IrFacade {
         request: &IrArtifactListRequest,
     ) -> SdkResult<IrArtifactListResponse> {
         let adapter = Self::adapter_for(&request.source_id)?;
-        Ok(adapter
+        adapter
             .list_artifacts(request)
-            .map_err(|e| SdkError::Config(e.to_string()))?)
+            .map_err(|e| SdkError::Config(e.to_string()))
     }
 
     pub fn fetch_ir_artifact(
@@ -81,13 +82,15 @@ impl IrFacade {
         request: &IrArtifactFetchRequest,
     ) -> SdkResult<IrArtifactFetchResponse> {
         let adapter = Self::adapter_for(&request.source_id)?;
-        Ok(adapter
+        adapter
             .fetch_artifact(request)
-            .map_err(|e| SdkError::Config(e.to_string()))?)
+            .map_err(|e| SdkError::Config(e.to_string()))
     }
 
-
-    pub fn normalize_ir_artifact(&self, request: &IrArtifactFetchRequest) -> SdkResult<IrNormalizedContent> {
+    pub fn normalize_ir_artifact(
+        &self,
+        request: &IrArtifactFetchRequest,
+    ) -> SdkResult<IrNormalizedContent> {
         let fetched = self.fetch_ir_artifact(request)?;
         normalize_artifact(&fetched).map_err(|e| SdkError::Config(e.message))
     }
@@ -154,7 +157,6 @@ impl IrFacade {
         Ok((docs.documents.len(), docs.documents, artifacts))
     }
 
-
     pub fn preview_issuer_site_document_summary(
         &self,
         source_id: &str,
@@ -58,7 +58,10 @@ pub mod prelude {
     pub use crate::sdk::{Sdk, SdkBuilder};
     pub use crate::secrets::SecretString;
 
-    pub use crate::market_data::MarketDataFacade;
+    pub use crate::market_data::{
+        IngestDrainReport, IngestHandle, IngestSpec, MarketData, MarketDataFacade,
+        MarketDataStream, OrderBookEvent,
+    };
     pub use crate::market_meta::{
         MarketMetaService, MarketMetaServiceConfig, MarketMetaServiceError,
     };
@@ -74,6 +77,7 @@ pub mod prelude {
 
     pub use crate::hub::{ExchangeId, Hub, HubConfig, HubError, RestHub, WsHub, WsMessage};
     pub use crate::ingest::{
-        ExchangeIngestDriver, IngestConfigRef, IngestPlanRef, IngestRulesRef, IngestRuntimeRef,
+        ExchangeIngestDriver, IngestConfigRef, IngestDriverRegistry, IngestPlanRef, IngestRulesRef,
+        IngestRuntimeRef,
     };
 }
@@ -0,0 +1,497 @@
+//! WS ingest ランタイム。
+//!
+//! coverage_v2 から `generate_plan_v2` で接続計画を作り、SubscriptionStore に seed してから
+//! ucel-registry の `ExchangeIngestDriver::run_ws_ingest` に接続の起動を任せる。接続ごとの
+//! スレッドと current_thread runtime は driver 側が持つので、ここでは開始・終了の hook で
+//! lifecycle を追うだけにしている。
+
+use crate::error::{SdkError, SdkResult};
+use serde::Serialize;
+use serde_json::Value;
+use std::collections::HashMap;
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+use tokio::sync::{oneshot, Mutex};
+use ucel_core::IngestLifecycleState;
+use ucel_journal::{FsyncMode, WalWriter};
+use ucel_registry::ingest::{
+    ConnectionExitHook, ConnectionStartHook, ExchangeIngestDriver, IngestConfigRef, IngestPlanRef,
+    IngestRulesRef, IngestRuntimeRef,
+};
+use ucel_subscription_planner::{canon_params, generate_plan_v2, stable_key, CoverageV2, Plan};
+use ucel_subscription_store::{SubscriptionRow, SubscriptionStateCounts, SubscriptionStore};
+use ucel_transport::ws::connection::{ShutdownToken, WsRunConfig};
+use ucel_ws_rules::ExchangeWsRules;
+
+/// ingest の起動に必要な設定一式
+#[derive(Debug, Clone)]
+pub struct IngestSpec {
+    pub coverage: CoverageV2,
+    pub rules: ExchangeWsRules,
+    /// 空なら driver.fetch_symbols() の結果を使う
+    pub symbols: Vec<String>,
+    /// 接続スレッドと handle が同じ DB を開くので ":memory:" は使えない
+    pub store_path: PathBuf,
+    pub journal_dir: PathBuf,
+    pub wal_max_bytes: u64,
+    pub fsync_mode: FsyncMode,
+    /// exchange_id / conn_id は計画から上書きする
+    pub run: WsRunConfig,
+    pub max_connections: usize,
+    /// private WS の driver を動かすか
+    pub enable_private_ws: bool,
+    /// drain で接続の終了を待つ上限
+    pub drain_timeout: Duration,
+}
+
+impl IngestSpec {
+    pub fn new(
+        coverage: CoverageV2,
+        rules: ExchangeWsRules,
+        store_path: impl Into<PathBuf>,
+        journal_dir: impl Into<PathBuf>,
+    ) -> Self {
+        Self {
+            coverage,
+            rules,
+            symbols: Vec::new(),
+            store_path: store_path.into(),
+            journal_dir: journal_dir.into(),
+            wal_max_bytes: 64 * 1024 * 1024,
+            fsync_mode: FsyncMode::Balanced,
+            run: WsRunConfig::default(),
+            max_connections: 64,
+            enable_private_ws: false,
+            drain_timeout: Duration::from_secs(30),
+        }
+    }
+
+    pub fn with_symbols(mut self, symbols: Vec<String>) -> Self {
+        self.symbols = symbols;
+        self
+    }
+
+    /// strict な coverage が展開できなければ PlanError の内容を Config エラーで返す
+    pub fn plan(&self, exchange_id: &str) -> SdkResult<Plan> {
+        generate_plan_v2(exchange_id, &self.coverage, &self.symbols, &self.rules)
+            .map_err(|e| SdkError::Config(e.to_string()))
+    }
+
+    fn store_path_str(&self) -> SdkResult<String> {
+        let path = self.store_path.to_str().ok_or_else(|| {
+            SdkError::Config(format!(
+                "store_path is not utf-8: {}",
+                self.store_path.display()
+            ))
+        })?;
+        if path == ":memory:" {
+            return Err(SdkError::Config(
+                "ingest needs a file-backed store_path; :memory: is per-connection".into(),
+            ));
+        }
+        Ok(path.to_string())
+    }
+}
+
+/// 計画を JSON にしたもの。接続は全て Planned
+pub fn plan_preview(exchange_id: &str, spec: &IngestSpec, plan: &Plan) -> Value {
+    let connections: Vec<Value> = plan
+        .conn_plans
+        .iter()
+        .map(|cp| {
+            serde_json::json!({
+                "conn_id": cp.conn_id,
+                "limit": cp.limit,
+                "keys": cp.keys,
+                "state": IngestLifecycleState::Planned,
+            })
+        })
+        .collect();
+    serde_json::json!({
+        "exchange": exchange_id,
+        "symbols": spec.symbols.len(),
+        "families": spec.coverage.families.len(),
+        "subscriptions": plan.seed.len(),
+        "max_connections": spec.max_connections,
+        "connections": connections,
+        "report": plan.report,
+    })
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct IngestConnectionStatus {
+    pub conn_id: String,
+    pub state: IngestLifecycleState,
+    pub subscriptions: SubscriptionStateCounts,
+    pub last_error: Option<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct IngestDrainReport {
+    pub exchange_id: String,
+    pub connections: Vec<IngestConnectionStatus>,
+    /// drain_timeout までに終わらなかった接続
+    pub timed_out: Vec<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum ConnPhase {
+    Spawned,
+    Running,
+    Exited(Result<(), String>),
+}
+
+struct ConnTask {
+    conn_id: String,
+    phase: Arc<std::sync::Mutex<ConnPhase>>,
+    done: Option<oneshot::Receiver<()>>,
+}
+
+/// 起動中の ingest。drop すると shutdown を通知する（終了は待たない）
+pub struct IngestHandle {
+    exchange_id: String,
+    shutdown: ShutdownToken,
+    wal: Arc<Mutex<WalWriter>>,
+    store: std::sync::Mutex<SubscriptionStore>,
+    conns: Vec<ConnTask>,
+    completed: AtomicBool,
+    drain_timeout: Duration,
+}
+
+fn now_unix_i64() -> i64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs() as i64)
+        .unwrap_or(0)
+}
+
+/// 計画を seed し、driver に接続を起動させる
+pub(crate) async fn start(
+    exchange_id: &str,
+    driver: Box<dyn ExchangeIngestDriver>,
+    mut spec: IngestSpec,
+) -> SdkResult<IngestHandle> {
+    if driver.exchange_id() != exchange_id {
+        return Err(SdkError::Config(format!(
+            "driver is for {} but facade is for {exchange_id}",
+            driver.exchange_id()
+        )));
+    }
+    if !driver.supports_ws_ingest() {
+        return Err(SdkError::Config(format!(
+            "ws ingest not supported for exchange_id={exchange_id}"
+        )));
+    }
+    let store_path = spec.store_path_str()?;
+    if spec.symbols.is_empty() {
+        spec.symbols = driver.fetch_symbols().await.map_err(SdkError::Ingest)?;
+    }
+    let plan = spec.plan(exchange_id)?;
+    if plan.conn_plans.len() > spec.max_connections {
+        return Err(SdkError::Config(format!(
+            "too many connections planned: exchange={exchange_id} conns={} max={}",
+            plan.conn_plans.len(),
+            spec.max_connections
+        )));
+    }
+
+    let conn_by_key: HashMap<String, String> = plan
+        .conn_plans
+        .iter()
+        .flat_map(|cp| cp.keys.iter().map(|k| (k.clone(), cp.conn_id.clone())))
+        .collect();
+    let rows: Vec<SubscriptionRow> = plan
+        .seed
+        .iter()
+        .map(|k| {
+            let sk = stable_key(k);
+            SubscriptionRow {
+                assigned_conn: conn_by_key.get(&sk).cloned(),
+                key: sk,
+                exchange_id: k.exchange_id.clone(),
+                op_id: k.op_id.clone(),
+                symbol: k.symbol.clone(),
+                params_json: canon_params(&k.params),
+            }
+        })
+        .collect();
+    let mut store = SubscriptionStore::open(&store_path).map_err(SdkError::Ingest)?;
+    store
+        .seed(&rows, now_unix_i64())
+        .map_err(SdkError::Ingest)?;
+
+    let wal = WalWriter::open(&spec.journal_dir, spec.wal_max_bytes, spec.fsync_mode)
+        .map_err(SdkError::Ingest)?;
+    let wal = Arc::new(Mutex::new(wal));
+    let shutdown = ShutdownToken {
+        flag: Arc::new(AtomicBool::new(false)),
+    };
+
+    let mut conns = Vec::with_capacity(plan.conn_plans.len());
+    let mut phases = HashMap::new();
+    let mut done_txs = HashMap::new();
+    for cp in &plan.conn_plans {
+        let phase = Arc::new(std::sync::Mutex::new(ConnPhase::Spawned));
+        let (done_tx, done_rx) = oneshot::channel();
+        phases.insert(cp.conn_id.clone(), phase.clone());
+        done_txs.insert(cp.conn_id.clone(), done_tx);
+        conns.push(ConnTask {
+            conn_id: cp.conn_id.clone(),
+            phase,
+            done: Some(done_rx),
+        });
+    }
+    let tracker = Arc::new(ConnTracker {
+        exchange_id: exchange_id.to_string(),
+        phases,
+        done: std::sync::Mutex::new(done_txs),
+    });
+    let on_start: ConnectionStartHook = {
+        let tracker = tracker.clone();
+        Arc::new(move |conn_id| tracker.set_phase(conn_id, ConnPhase::Running))
+    };
+    let on_exit: ConnectionExitHook = {
+        let tracker = tracker.clone();
+        Arc::new(move |conn_id, result| tracker.exit(conn_id, result.clone()))
+    };
+
+    let plan_ref = IngestPlanRef {
+        exchange_id: exchange_id.to_string(),
+        seed_len: plan.seed.len(),
+        conn_ids: plan
+            .conn_plans
+            .iter()
+            .map(|cp| cp.conn_id.clone())
+            .collect(),
+    };
+    let runtime_ref = IngestRuntimeRef {
+        store_path,
+        journal_dir: spec.journal_dir.display().to_string(),
+        wal: wal.clone(),
+        shutdown: shutdown.clone(),
+        on_connection_end: None,
+        on_connection_start: Some(on_start),
+        on_connection_exit: Some(on_exit),
+        connection_metrics: None,
+    };
+    let rules_ref = IngestRulesRef {
+        support_level: format!("{:?}", spec.rules.support_level),
+        rules: spec.rules.clone(),
+    };
+    let cfg_ref = IngestConfigRef {
+        enable_private_ws: spec.enable_private_ws,
+        run: spec.run.clone(),
+    };
+    tokio::spawn(async move {
+        if let Err(e) = driver
+            .run_ws_ingest(plan_ref, runtime_ref, rules_ref, cfg_ref)
+            .await
+        {
+            // 起動前に driver が断った接続（private 無効、スレッド起動失敗など）は exit hook が
+            // 呼ばれないので、ここで終了扱いにする
+            tracker.fail_remaining(&e);
+        }
+    });
+
+    Ok(IngestHandle {
+        exchange_id: exchange_id.to_string(),
+        shutdown,
+        wal,
+        store: std::sync::Mutex::new(store),
+        conns,
+        completed: AtomicBool::new(false),
+        drain_timeout: spec.drain_timeout,
+    })
+}
+
+/// driver の hook から接続ごとの phase と終了通知を更新する
+struct ConnTracker {
+    exchange_id: String,
+    phases: HashMap<String, Arc<std::sync::Mutex<ConnPhase>>>,
+    done: std::sync::Mutex<HashMap<String, oneshot::Sender<()>>>,
+}
+
+impl ConnTracker {
+    fn set_phase(&self, conn_id: &str, phase: ConnPhase) {
+        if let Some(p) = self.phases.get(conn_id) {
+            *p.lock().unwrap_or_else(|e| e.into_inner()) = phase;
+        }
+    }
+
+    fn exit(&self, conn_id: &str, result: Result<(), String>) {
+        if let Err(e) = &result {
+            tracing::warn!(exchange_id = %self.exchange_id, conn = %conn_id, err = %e, "ingest connection ended");
+        }
+        self.set_phase(conn_id, ConnPhase::Exited(result));
+        let tx = self
+            .done
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .remove(conn_id);
+        if let Some(tx) = tx {
+            let _ = tx.send(());
+        }
+    }
+
+    fn fail_remaining(&self, err: &str) {
+        let remaining: Vec<String> = self
+            .done
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .keys()
+            .cloned()
+            .collect();
+        for conn_id in remaining {
+            self.exit(&conn_id, Err(err.to_string()));
+        }
+    }
+}
+
+impl IngestHandle {
+    pub fn exchange_id(&self) -> &str {
+        &self.exchange_id
+    }
+
+    pub fn conn_ids(&self) -> Vec<String> {
+        self.conns.iter().map(|c| c.conn_id.clone()).collect()
+    }
+
+    pub fn is_stopping(&self) -> bool {
+        self.shutdown.is_triggered()
+    }
+
+    /// 接続ごとの現在の lifecycle。接続中は SubscriptionStore の状態から判断する
+    pub fn status(&self) -> Vec<IngestConnectionStatus> {
+        let completed = self.completed.load(Ordering::SeqCst);
+        let store = self.store.lock().unwrap_or_else(|e| e.into_inner());
+        self.conns
+            .iter()
+            .map(|c| {
+                let phase = c.phase.lock().unwrap_or_else(|e| e.into_inner()).clone();
+                let subscriptions = store
+                    .state_counts(&self.exchange_id, &c.conn_id)
+                    .unwrap_or_default();
+                let (state, last_error) = lifecycle_of(&phase, subscriptions, completed);
+                IngestConnectionStatus {
+                    conn_id: c.conn_id.clone(),
+                    state,
+                    subscriptions,
+                    last_error,
+                }
+            })
+            .collect()
+    }
+
+    /// 全接続に shutdown を通知する。終了や WAL の flush は待たない
+    pub fn stop(&self) {
+        self.shutdown.trigger();
+    }
+
+    /// shutdown を通知し、全接続の graceful shutdown（outbound flush → WAL queue drain →
+    /// requeue → join）を待ってから WAL を fsync する
+    pub async fn drain(mut self) -> SdkResult<IngestDrainReport> {
+        self.shutdown.trigger();
+        let deadline = tokio::time::Instant::now() + self.drain_timeout;
+        let mut timed_out = Vec::new();
+        for c in &mut self.conns {
+            let Some(done) = c.done.take() else {
+                continue;
+            };
+            if tokio::time::timeout_at(deadline, done).await.is_err() {
+                timed_out.push(c.conn_id.clone());
+            }
+        }
+        self.wal
+            .lock()
+            .await
+            .sync()
+            .map_err(|e| SdkError::Ingest(format!("wal sync: {e}")))?;
+        if timed_out.is_empty() {
+            self.completed.store(true, Ordering::SeqCst);
+        }
+        Ok(IngestDrainReport {
+            exchange_id: self.exchange_id.clone(),
+            connections: self.status(),
+            timed_out,
+        })
+    }
+}
+
+impl Drop for IngestHandle {
+    fn drop(&mut self) {
+        self.shutdown.trigger();
+    }
+}
+
+fn lifecycle_of(
+    phase: &ConnPhase,
+    subs: SubscriptionStateCounts,
+    completed: bool,
+) -> (IngestLifecycleState, Option<String>) {
+    use IngestLifecycleState::*;
+    let state = match phase {
+        ConnPhase::Exited(Err(e)) => return (Deadlettered, Some(e.clone())),
+        ConnPhase::Exited(Ok(())) if completed => Completed,
+        ConnPhase::Exited(Ok(())) => Drained,
+        ConnPhase::Spawned => PendingConnect,
+        ConnPhase::Running if subs.total() > 0 && subs.deadletter == subs.total() => Deadlettered,
+        ConnPhase::Running if subs.active > 0 => Active,
+        ConnPhase::Running if subs.inflight > 0 => AwaitingAck,
+        ConnPhase::Running => Connecting,
+    };
+    (state, None)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn counts(
+        pending: usize,
+        inflight: usize,
+        active: usize,
+        deadletter: usize,
+    ) -> SubscriptionStateCounts {
+        SubscriptionStateCounts {
+            pending,
+            inflight,
+            active,
+            deadletter,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn lifecycle_follows_phase_then_store_counts() {
+        use IngestLifecycleState::*;
+        let running = ConnPhase::Running;
+        assert_eq!(
+            lifecycle_of(&ConnPhase::Spawned, counts(3, 0, 0, 0), false).0,
+            PendingConnect
+        );
+        assert_eq!(
+            lifecycle_of(&running, counts(3, 0, 0, 0), false).0,
+            Connecting
+        );
+        assert_eq!(
+            lifecycle_of(&running, counts(2, 1, 0, 0), false).0,
+            AwaitingAck
+        );
+        assert_eq!(lifecycle_of(&running, counts(1, 1, 1, 0), false).0, Active);
+        assert_eq!(
+            lifecycle_of(&running, counts(0, 0, 0, 2), false).0,
+            Deadlettered
+        );
+
+        let ok = ConnPhase::Exited(Ok(()));
+        assert_eq!(lifecycle_of(&ok, counts(3, 0, 0, 0), false).0, Drained);
+        assert_eq!(lifecycle_of(&ok, counts(3, 0, 0, 0), true).0, Completed);
+        let failed = ConnPhase::Exited(Err("reconnect storm detected: 11".into()));
+        assert_eq!(
+            lifecycle_of(&failed, counts(3, 0, 0, 0), true),
+            (Deadlettered, Some("reconnect storm detected: 11".into()))
+        );
+    }
+}
@@ -0,0 +1,559 @@
+//! 公開マーケットデータの facade。
+//!
+//! REST / WS の生ペイロードは [`normalize::MarketDataNormalizer`] で canonical 型へ変換し、
+//! [`MarketData`] として meta / quality / 生の値と一緒に返す。
+
+pub mod ingest;
+pub mod normalize;
+
+use crate::error::{SdkError, SdkResult};
+use crate::hub::{ExchangeId, Hub, HubError, WsMessage};
+use crate::ingest::{IngestDriverRegistry, WsAdapterIngestDriver};
+use futures_util::{Stream, StreamExt};
+use normalize::{MarketDataNormalizer, NormalizeError, NormalizeResult, Normalized, PayloadSource};
+use serde_json::Value;
+use std::collections::VecDeque;
+use std::pin::Pin;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+use ucel_core::{
+    guard_orderbook, validate_candle, validate_ticker, validate_trade, CanonicalCandle,
+    CanonicalOrderBookSnapshot, CanonicalTicker, CanonicalTrade, Envelope, Meta, Quality,
+    SchemaVersion,
+};
+use ucel_transport::ws::adapter::WsVenueAdapter;
+
+pub use ingest::{IngestConnectionStatus, IngestDrainReport, IngestHandle, IngestSpec};
+pub use normalize::OrderBookEvent;
+
+/// Quality::parse_failures_recent に数える期間
+const PARSE_FAILURE_WINDOW: Duration = Duration::from_secs(60);
+
+pub type MarketDataStream<T> = Pin<Box<dyn Stream<Item = SdkResult<MarketData<T>>> + Send>>;
+
+/// canonical 型に変換済みのデータ。raw は venue から受け取ったままの値（調査用）
+#[derive(Debug, Clone, PartialEq)]
+pub struct MarketData<T> {
+    pub meta: Meta,
+    pub data: T,
+    pub quality: Quality,
+    pub raw: Value,
+}
+
+impl<T> MarketData<T> {
+    pub fn into_envelope(self, schema_version: SchemaVersion) -> Envelope<T> {
+        Envelope {
+            schema_version,
+            meta: self.meta,
+            data: self.data,
+            quality: self.quality,
+        }
+    }
+}
+
+/// 直近 PARSE_FAILURE_WINDOW 内の parse 失敗時刻。facade の clone 間で共有する
+#[derive(Debug, Default)]
+struct ParseFailureWindow {
+    at: Mutex<VecDeque<Instant>>,
+}
+
+impl ParseFailureWindow {
+    fn record(&self) -> u32 {
+        let mut at = self.at.lock().unwrap_or_else(|e| e.into_inner());
+        at.push_back(Instant::now());
+        Self::prune(&mut at)
+    }
+
+    fn recent(&self) -> u32 {
+        let mut at = self.at.lock().unwrap_or_else(|e| e.into_inner());
+        Self::prune(&mut at)
+    }
+
+    fn prune(at: &mut VecDeque<Instant>) -> u32 {
+        let now = Instant::now();
+        while at
+            .front()
+            .is_some_and(|t| now.duration_since(*t) > PARSE_FAILURE_WINDOW)
+        {
+            at.pop_front();
+        }
+        at.len() as u32
+    }
+}
+
+/// 変換に使う文脈。stream の中へ move できるよう facade から切り出している
+#[derive(Clone)]
+struct Decoder {
+    exchange: ExchangeId,
+    normalizer: MarketDataNormalizer,
+    failures: Arc<ParseFailureWindow>,
+}
+
+impl Decoder {
+    /// Ok(None) は control frame
+    fn decode<T>(
+        &self,
+        channel: &'static str,
+        symbol: &str,
+        raw: Value,
+        out: NormalizeResult<T>,
+        anomalies: impl FnOnce(&T) -> Vec<String>,
+    ) -> SdkResult<Option<MarketData<T>>> {
+        let normalized = match out {
+            Ok(Some(n)) => n,
+            Ok(None) => return Ok(None),
+            Err(reason) => {
+                self.failures.record();
+                return Err(SdkError::MarketDataParse {
+                    exchange: self.exchange.as_str().to_string(),
+                    channel: channel.to_string(),
+                    reason,
+                    raw: raw.to_string(),
+                });
+            }
+        };
+        let Normalized {
+            data,
+            ts_event,
+            missing_fields,
+        } = normalized;
+        let ts_recv = now_ms();
+        let quality = Quality {
+            is_stale: false,
+            delay_ms: ts_event.map(|t| ts_recv.saturating_sub(t)).unwrap_or(0),
+            missing_fields,
+            anomaly_flags: anomalies(&data),
+            parse_failures_recent: self.failures.recent(),
+        };
+        let request_id = uuid::Uuid::new_v4().to_string();
+        Ok(Some(MarketData {
+            meta: Meta {
+                venue: self.exchange.as_str().to_string(),
+                symbol: symbol.to_string(),
+                venue_symbol: symbol.to_string(),
+                ts_recv,
+                ts_event,
+                trace_id: request_id.clone(),
+                request_id,
+                run_id: String::new(),
+            },
+            data,
+            quality,
+            raw,
+        }))
+    }
+}
+
+fn now_ms() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_millis() as u64)
+        .unwrap_or(0)
+}
+
+fn ticker_anomalies(t: &CanonicalTicker) -> Vec<String> {
+    validate_ticker(t)
+        .err()
+        .map(|e| e.message)
+        .into_iter()
+        .collect()
+}
+
+fn trade_anomalies(trades: &[CanonicalTrade]) -> Vec<String> {
+    trades
+        .iter()
+        .filter_map(|t| {
+            validate_trade(t)
+                .err()
+                .map(|e| format!("{}: {}", t.trade_id, e.message))
+        })
+        .collect()
+}
+
+fn book_anomalies(book: &CanonicalOrderBookSnapshot) -> Vec<String> {
+    guard_orderbook(book)
+        .err()
+        .map(|e| e.message)
+        .into_iter()
+        .collect()
+}
+
+fn book_event_anomalies(event: &OrderBookEvent) -> Vec<String> {
+    match event {
+        OrderBookEvent::Snapshot(s) => book_anomalies(s),
+        OrderBookEvent::Delta(_) => Vec::new(),
+    }
+}
+
+fn candle_anomalies(candles: &[CanonicalCandle]) -> Vec<String> {
+    candles
+        .iter()
+        .filter_map(|c| {
+            validate_candle(c)
+                .err()
+                .map(|e| format!("{}: {}", c.ts_open, e.message))
+        })
+        .collect()
+}
+
+#[derive(Clone)]
+pub struct MarketDataFacade {
+    hub: Hub,
+    exchange: ExchangeId,
+    decoder: Decoder,
+}
+
+impl MarketDataFacade {
+    pub fn new(hub: Hub, exchange: ExchangeId) -> Self {
+        Self {
+            hub,
+            exchange,
+            decoder: Decoder {
+                exchange,
+                normalizer: MarketDataNormalizer::for_exchange(exchange),
+                failures: Arc::new(ParseFailureWindow::default()),
+            },
+        }
+    }
+
+    /// 直近 60 秒の parse 失敗数
+    pub fn parse_failures_recent(&self) -> u32 {
+        self.decoder.failures.recent()
+    }
+
+    pub async fn get_ticker(&self, symbol: &str) -> SdkResult<MarketData<CanonicalTicker>> {
+        let raw = self.call_public_rest("public_ticker", symbol).await?;
+        let out = self
+            .decoder
+            .normalizer
+            .ticker(symbol, PayloadSource::Rest, &raw);
+        self.decode_rest("public_ticker", symbol, raw, out, ticker_anomalies)
+    }
+
+    pub async fn get_trades(&self, symbol: &str) -> SdkResult<MarketData<Vec<CanonicalTrade>>> {
+        let raw = self.call_public_rest("public_trades", symbol).await?;
+        let out = self
+            .decoder
+            .normalizer
+            .trades(symbol, PayloadSource::Rest, &raw);
+        self.decode_rest("public_trades", symbol, raw, out, |t: &Vec<_>| {
+            trade_anomalies(t)
+        })
+    }
+
+    pub async fn get_orderbook_snapshot(
+        &self,
+        symbol: &str,
+    ) -> SdkResult<MarketData<CanonicalOrderBookSnapshot>> {
+        let raw = self.call_public_rest("public_orderbook", symbol).await?;
+        // REST の板は常に Snapshot になる
+        let out = self
+            .decoder
+            .normalizer
+            .orderbook(symbol, PayloadSource::Rest, &raw)
+            .map(|n| {
+                n.and_then(|n| match n.data {
+                    OrderBookEvent::Snapshot(data) => Some(Normalized {
+                        data,
+                        ts_event: n.ts_event,
+                        missing_fields: n.missing_fields,
+                    }),
+                    OrderBookEvent::Delta(_) => None,
+                })
+            });
+        self.decode_rest("public_orderbook", symbol, raw, out, book_anomalies)
+    }
+
+    pub async fn get_candles(&self, symbol: &str) -> SdkResult<MarketData<Vec<CanonicalCandle>>> {
+        let raw = self.call_public_rest("public_candles", symbol).await?;
+        let out = self
+            .decoder
+            .normalizer
+            .candles(symbol, PayloadSource::Rest, &raw);
+        self.decode_rest("public_candles", symbol, raw, out, |c: &Vec<_>| {
+            candle_anomalies(c)
+        })
+    }
+
+    pub async fn list_symbols(&self) -> Result<Value, HubError> {
+        let resp = self
+            .hub
+            .rest(self.exchange)
+            .call("public_symbols", None, None)
+            .await?;
+        resp.json_value()
+    }
+
+    pub async fn get_market_meta(&self, symbol: &str) -> Result<Value, HubError> {
+        self.call_public_rest("public_market_meta", symbol).await
+    }
+
+    pub async fn subscribe_ticker(
+        &self,
+        symbol: &str,
+    ) -> SdkResult<MarketDataStream<CanonicalTicker>> {
+        self.subscribe_typed(
+            "public_ticker",
+            symbol,
+            |n, s, raw| n.ticker(s, PayloadSource::Ws, raw),
+            ticker_anomalies,
+        )
+        .await
+    }
+
+    pub async fn subscribe_trades(
+        &self,
+        symbol: &str,
+    ) -> SdkResult<MarketDataStream<Vec<CanonicalTrade>>> {
+        self.subscribe_typed(
+            "public_trades",
+            symbol,
+            |n, s, raw| n.trades(s, PayloadSource::Ws, raw),
+            |t: &Vec<_>| trade_anomalies(t),
+        )
+        .await
+    }
+
+    /// WS の板は venue により Snapshot と Delta が混在する
+    pub async fn subscribe_orderbook(
+        &self,
+        symbol: &str,
+    ) -> SdkResult<MarketDataStream<OrderBookEvent>> {
+        self.subscribe_typed(
+            "public_orderbook",
+            symbol,
+            |n, s, raw| n.orderbook(s, PayloadSource::Ws, raw),
+            book_event_anomalies,
+        )
+        .await
+    }
+
+    pub async fn subscribe_candles(
+        &self,
+        symbol: &str,
+    ) -> SdkResult<MarketDataStream<Vec<CanonicalCandle>>> {
+        self.subscribe_typed(
+            "public_candles",
+            symbol,
+            |n, s, raw| n.candles(s, PayloadSource::Ws, raw),
+            |c: &Vec<_>| candle_anomalies(c),
+        )
+        .await
+    }
+
+    /// 変換前の WS メッセージが必要な場合（新しい venue の調査など）
+    pub async fn subscribe_raw(
+        &self,
+        channel: &'static str,
+        symbol: &str,
+    ) -> Result<Pin<Box<dyn Stream<Item = Result<WsMessage, HubError>> + Send>>, HubError> {
+        self.subscribe(channel, symbol).await
+    }
+
+    pub fn preview_market_data_plan(&self, symbol: &str) -> Value {
+        serde_json::json!({
+            "exchange": self.exchange.as_str(),
+            "symbol": symbol,
+            "rest": ["public_ticker", "public_trades", "public_orderbook", "public_candles", "public_symbols", "public_market_meta"],
+            "ws": ["public_ticker", "public_trades", "public_orderbook", "public_candles"]
+        })
+    }
+
+    /// generate_plan_v2 で作る接続計画。spec.symbols が空なら symbol 無しの family だけになる
+    pub fn preview_ingest_plan(&self, spec: &IngestSpec) -> SdkResult<Value> {
+        let plan = spec.plan(self.exchange.as_str())?;
+        Ok(ingest::plan_preview(self.exchange.as_str(), spec, &plan))
+    }
+
+    pub async fn start_ingest(
+        &self,
+        adapter: Arc<dyn WsVenueAdapter>,
+        spec: IngestSpec,
+    ) -> SdkResult<IngestHandle> {
+        if adapter.exchange_id() != self.exchange.as_str() {
+            return Err(SdkError::Config(format!(
+                "adapter is for {} but facade is for {}",
+                adapter.exchange_id(),
+                self.exchange.as_str()
+            )));
+        }
+        let driver = WsAdapterIngestDriver::new(self.exchange.as_str(), adapter).boxed();
+        ingest::start(self.exchange.as_str(), driver, spec).await
+    }
+
+    /// venue crate が登録した driver で ingest を起動する。WsVenueAdapter を持たない venue は
+    /// UnsupportedWsIngestDriver なので Config エラーになる
+    pub async fn start_ingest_with(
+        &self,
+        registry: &IngestDriverRegistry,
+        spec: IngestSpec,
+    ) -> SdkResult<IngestHandle> {
+        let driver = registry
+            .resolve(self.exchange.as_str())
+            .map_err(SdkError::Config)?;
+        ingest::start(self.exchange.as_str(), driver, spec).await
+    }
+
+    pub fn stop_ingest(&self, handle: &IngestHandle) {
+        handle.stop();
+    }
+
+    pub async fn drain_ingest(&self, handle: IngestHandle) -> SdkResult<IngestDrainReport> {
+        handle.drain().await
+    }
+
+    async fn call_public_rest(&self, op: &'static str, symbol: &str) -> Result<Value, HubError> {
+        let resp = self
+            .hub
+            .rest(self.exchange)
+            .call(op, Some(&[("symbol", symbol)]), None)
+            .await?;
+        resp.json_value()
+    }
+
+    fn decode_rest<T>(
+        &self,
+        channel: &'static str,
+        symbol: &str,
+        raw: Value,
+        out: NormalizeResult<T>,
+        anomalies: impl FnOnce(&T) -> Vec<String>,
+    ) -> SdkResult<MarketData<T>> {
+        let exchange = self.exchange.as_str().to_string();
+        self.decoder
+            .decode(channel, symbol, raw.clone(), out, anomalies)?
+            .ok_or_else(|| SdkError::MarketDataParse {
+                exchange,
+                channel: channel.to_string(),
+                reason: NormalizeError::Decode("no market data in response".into()),
+                raw: raw.to_string(),
+            })
+    }
+
+    async fn subscribe_typed<T, N, A>(
+        &self,
+        channel: &'static str,
+        symbol: &str,
+        normalize: N,
+        anomalies: A,
+    ) -> SdkResult<MarketDataStream<T>>
+    where
+        T: Send + 'static,
+        N: Fn(&MarketDataNormalizer, &str, &Value) -> NormalizeResult<T> + Send + 'static,
+        A: Fn(&T) -> Vec<String> + Send + 'static,
+    {
+        let stream = self.subscribe(channel, symbol).await?;
+        let decoder = self.decoder.clone();
+        let symbol = symbol.to_string();
+        let typed = stream.filter_map(move |msg| {
+            let item = match msg {
+                Err(e) => Some(Err(SdkError::Hub(e))),
+                Ok(msg) => match msg.json_value() {
+                    Err(e) => {
+                        decoder.failures.record();
+                        Some(Err(SdkError::Hub(e)))
+                    }
+                    Ok(raw) => {
+                        let out = normalize(&decoder.normalizer, &symbol, &raw);
+                        decoder
+                            .decode(channel, &symbol, raw, out, &anomalies)
+                            .transpose()
+                    }
+                },
+            };
+            futures_util::future::ready(item)
+        });
+        Ok(Box::pin(typed))
+    }
+
+    async fn subscribe(
+        &self,
+        channel: &'static str,
+        symbol: &str,
+    ) -> Result<Pin<Box<dyn Stream<Item = Result<WsMessage, HubError>> + Send>>, HubError> {
+        self.hub
+            .ws(self.exchange)
+            .subscribe(channel, Some(serde_json::json!({"symbol": symbol})))
+            .await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn decoder(exchange: ExchangeId) -> Decoder {
+        Decoder {
+            exchange,
+            normalizer: MarketDataNormalizer::for_exchange(exchange),
+            failures: Arc::new(ParseFailureWindow::default()),
+        }
+    }
+
+    #[test]
+    fn parse_failures_are_counted_in_later_quality() {
+        let d = decoder(ExchangeId::Bithumb);
+        let bad = json!({"type": "ticker", "content": {"symbol": "BTC_KRW"}});
+        let out = d.normalizer.ticker("BTC/KRW", PayloadSource::Ws, &bad);
+        match d.decode("public_ticker", "BTC/KRW", bad, out, ticker_anomalies) {
+            Err(SdkError::MarketDataParse {
+                exchange,
+                reason: NormalizeError::Decode(_),
+                raw,
+                ..
+            }) => {
+                assert_eq!(exchange, "bithumb");
+                assert!(raw.contains("BTC_KRW"));
+            }
+            other => panic!("unexpected {other:?}"),
+        }
+
+        let good = json!({"type": "ticker", "content": {"bidPrice": "100", "askPrice": "99", "closePrice": "100"}});
+        let out = d.normalizer.ticker("BTC/KRW", PayloadSource::Ws, &good);
+        let md = d
+            .decode(
+                "public_ticker",
+                "BTC/KRW",
+                good.clone(),
+                out,
+                ticker_anomalies,
+            )
+            .unwrap()
+            .unwrap();
+        assert_eq!(md.quality.parse_failures_recent, 1);
+        assert_eq!(md.meta.venue, "bithumb");
+        assert_eq!(md.raw, good);
+        // bid > ask は validate_ticker の anomaly として残す
+        assert_eq!(md.quality.anomaly_flags, vec!["crossed book in ticker"]);
+    }
+
+    #[test]
+    fn missing_decoders_surface_as_typed_parse_failures() {
+        let d = decoder(ExchangeId::Binance);
+        let raw = json!({"b": "100", "a": "101", "c": "100"});
+        let out = d.normalizer.ticker("BTC/USDT", PayloadSource::Ws, &raw);
+        match d.decode("public_ticker", "BTC/USDT", raw, out, ticker_anomalies) {
+            Err(SdkError::MarketDataParse {
+                reason: NormalizeError::NoDecoder { .. },
+                ..
+            }) => {}
+            other => panic!("unexpected {other:?}"),
+        }
+        assert_eq!(d.failures.recent(), 1);
+    }
+
+    #[test]
+    fn control_frames_are_skipped_without_counting() {
+        let d = decoder(ExchangeId::Okx);
+        let ack = json!({"event": "subscribe", "arg": {"channel": "trades"}});
+        let out = d.normalizer.trades("BTC/USDT", PayloadSource::Ws, &ack);
+        let got = d
+            .decode("public_trades", "BTC/USDT", ack, out, |t: &Vec<_>| {
+                trade_anomalies(t)
+            })
+            .unwrap();
+        assert!(got.is_none());
+        assert_eq!(d.failures.recent(), 0);
+    }
+}
@@ -0,0 +1,933 @@
+//! venue の生ペイロード（REST 応答 / WS メッセージ）を UCEL canonical 型へ変換する。
+//!
+//! 変換は venue crate の型付きデコーダだけで行い、キー名の別名やタイムスタンプの単位は推測しない。
+//! (venue, チャネル, REST / WS) に対応するデコーダが無ければ [`NormalizeError::NoDecoder`] を返す。
+//! trade_id はデコーダが返した値だけを使い、無ければ約定ごとエラーにする（合成しない）。
+//! bybit / kraken / okx / upbit の WS フレームは各 crate でエラー・応答を振り分けてから読む。
+//! bittrade は ucel-sdk への依存を持つため crate 側へは回せない。
+
+use crate::hub::ExchangeId;
+use serde_json::Value;
+use std::fmt;
+use std::str::FromStr;
+use ucel_core::{
+    CanonicalCandle, CanonicalOrderBookDelta, CanonicalOrderBookLevel, CanonicalOrderBookSnapshot,
+    CanonicalTicker, CanonicalTrade, Decimal, OrderBookLevel, Side, TradeEvent, UcelError,
+};
+
+/// ペイロードの出どころ。REST の板は常にスナップショットとして扱う
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PayloadSource {
+    Rest,
+    Ws,
+}
+
+/// 正規化するチャネル
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MarketDataChannel {
+    Ticker,
+    Trades,
+    OrderBook,
+    Candles,
+}
+
+impl MarketDataChannel {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Ticker => "ticker",
+            Self::Trades => "trades",
+            Self::OrderBook => "orderbook",
+            Self::Candles => "candles",
+        }
+    }
+}
+
+/// 正規化の失敗
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum NormalizeError {
+    /// venue crate にこのチャネル・経路の型付きデコーダが無い
+    NoDecoder {
+        exchange: ExchangeId,
+        channel: MarketDataChannel,
+        source: PayloadSource,
+    },
+    /// デコーダがペイロードを拒否した、または canonical 型に必要な値が無かった
+    Decode(String),
+}
+
+impl fmt::Display for NormalizeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::NoDecoder {
+                exchange,
+                channel,
+                source,
+            } => write!(
+                f,
+                "no typed {} decoder for {} {source:?} payloads",
+                channel.as_str(),
+                exchange.as_str()
+            ),
+            Self::Decode(reason) => f.write_str(reason),
+        }
+    }
+}
+
+impl std::error::Error for NormalizeError {}
+
+impl From<UcelError> for NormalizeError {
+    fn from(e: UcelError) -> Self {
+        Self::Decode(e.message)
+    }
+}
+
+impl From<&str> for NormalizeError {
+    fn from(reason: &str) -> Self {
+        Self::Decode(reason.to_string())
+    }
+}
+
+impl From<String> for NormalizeError {
+    fn from(reason: String) -> Self {
+        Self::Decode(reason)
+    }
+}
+
+/// 板チャネルの 1 メッセージ
+#[derive(Debug, Clone, PartialEq)]
+pub enum OrderBookEvent {
+    Snapshot(CanonicalOrderBookSnapshot),
+    Delta(CanonicalOrderBookDelta),
+}
+
+/// 正規化結果。デコーダが返さなかったフィールドは missing_fields に残す
+#[derive(Debug, Clone, PartialEq)]
+pub struct Normalized<T> {
+    pub data: T,
+    pub ts_event: Option<u64>,
+    pub missing_fields: Vec<String>,
+}
+
+/// Ok(None) はデータではないメッセージ（ack / heartbeat など）、Err は解釈できなかったデータ
+pub type NormalizeResult<T> = Result<Option<Normalized<T>>, NormalizeError>;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct MarketDataNormalizer {
+    exchange: ExchangeId,
+}
+
+impl MarketDataNormalizer {
+    pub fn for_exchange(exchange: ExchangeId) -> Self {
+        Self { exchange }
+    }
+
+    pub fn exchange(&self) -> ExchangeId {
+        self.exchange
+    }
+
+    fn no_decoder<T>(
+        &self,
+        channel: MarketDataChannel,
+        source: PayloadSource,
+    ) -> NormalizeResult<T> {
+        Err(NormalizeError::NoDecoder {
+            exchange: self.exchange,
+            channel,
+            source,
+        })
+    }
+
+    pub fn ticker(
+        &self,
+        symbol: &str,
+        source: PayloadSource,
+        raw: &Value,
+    ) -> NormalizeResult<CanonicalTicker> {
+        if let Some(out) = self.venue_frame(source, raw) {
+            return out;
+        }
+        let bytes = raw.to_string();
+        match (self.exchange, source) {
+            (ExchangeId::Bithumb, PayloadSource::Ws) => match ucel_cex_bithumb::normalize_ws_event(
+                "openapi.public.ws.ticker.snapshot",
+                &bytes,
+            )? {
+                ucel_cex_bithumb::BithumbWsEvent::Ticker(t) => {
+                    ticker(symbol, Some(t.bid), Some(t.ask), t.last, None)
+                }
+                _ => Ok(None),
+            },
+            (ExchangeId::Bithumb, PayloadSource::Rest) => {
+                match ucel_cex_bithumb::normalize_rest_response(
+                    "openapi.public.rest.ticker.list",
+                    bytes.as_bytes(),
+                )? {
+                    ucel_cex_bithumb::BithumbRestResponse::TickerList(items) => {
+                        let t = only(items, "ticker")?;
+                        ticker(symbol, None, None, t.trade_price, None)
+                    }
+                    _ => Err("unexpected bithumb ticker response".into()),
+                }
+            }
+            (ExchangeId::Upbit, PayloadSource::Ws) => {
+                match ucel_cex_upbit::normalize_ws_message(&bytes)? {
+                    // upbit の ticker は約定価格だけで気配を持たない
+                    ucel_cex_upbit::MarketEvent::Ticker {
+                        trade_price,
+                        timestamp,
+                        ..
+                    } => ticker(symbol, None, None, trade_price, timestamp),
+                    _ => Ok(None),
+                }
+            }
+            (ExchangeId::Upbit, PayloadSource::Rest) => match ucel_cex_upbit::parse_upbit_response(
+                "quotation.public.rest.ticker.pairs",
+                bytes.as_bytes(),
+            )? {
+                ucel_cex_upbit::UpbitRestResponse::Tickers(items) => {
+                    let t = only(items, "ticker")?;
+                    ticker(symbol, None, None, t.trade_price, t.timestamp)
+                }
+                _ => Err("unexpected upbit ticker response".into()),
+            },
+            (ExchangeId::Sbivc, PayloadSource::Ws) => {
+                match ucel_cex_sbivc::parse_market_event(
+                    "crypto.public.ws.market.ticker",
+                    bytes.as_bytes(),
+                )? {
+                    ucel_cex_sbivc::MarketEvent::Ticker { last, .. } => {
+                        ticker(symbol, None, None, last, None)
+                    }
+                    _ => Ok(None),
+                }
+            }
+            (ExchangeId::Coinbase, PayloadSource::Ws) => {
+                match ucel_cex_coinbase::decode_market_event(bytes.as_bytes())? {
+                    ucel_cex_coinbase::MarketEvent::Ticker { price, .. } => {
+                        ticker(symbol, None, None, price, None)
+                    }
+                    _ => Ok(None),
+                }
+            }
+            _ => self.no_decoder(MarketDataChannel::Ticker, source),
+        }
+    }
+
+    pub fn trades(
+        &self,
+        symbol: &str,
+        source: PayloadSource,
+        raw: &Value,
+    ) -> NormalizeResult<Vec<CanonicalTrade>> {
+        if let Some(out) = self.venue_frame(source, raw) {
+            return out;
+        }
+        let bytes = raw.to_string();
+        match (self.exchange, source) {
+            (ExchangeId::Bithumb, PayloadSource::Ws) => match ucel_cex_bithumb::normalize_ws_event(
+                "openapi.public.ws.trade.snapshot",
+                &bytes,
+            )? {
+                ucel_cex_bithumb::BithumbWsEvent::Trade(t) => {
+                    trades(vec![core_trade(symbol, t, None)?])
+                }
+                _ => Ok(None),
+            },
+            (ExchangeId::Sbivc, PayloadSource::Ws) => {
+                match ucel_cex_sbivc::parse_market_event(
+                    "crypto.public.ws.market.trades",
+                    bytes.as_bytes(),
+                )? {
+                    ucel_cex_sbivc::MarketEvent::Trade(t) => {
+                        trades(vec![core_trade(symbol, t, None)?])
+                    }
+                    _ => Ok(None),
+                }
+            }
+            (ExchangeId::Coinbase, PayloadSource::Ws) => {
+                match ucel_cex_coinbase::decode_market_event(bytes.as_bytes())? {
+                    ucel_cex_coinbase::MarketEvent::Trades { trades: ts, .. } => trades(
+                        ts.into_iter()
+                            .map(|t| core_trade(symbol, t, None))
+                            .collect::<Result<_, _>>()?,
+                    ),
+                    _ => Ok(None),
+                }
+            }
+            (ExchangeId::Coincheck, PayloadSource::Ws) => {
+                match ucel_cex_coincheck::decode_market_event(
+                    "coincheck.ws.public.trades",
+                    bytes.as_bytes(),
+                )? {
+                    ucel_cex_coincheck::MarketEvent::Trade {
+                        trade_id,
+                        side,
+                        rate,
+                        amount,
+                        timestamp,
+                        ..
+                    } => {
+                        // coincheck の timestamp は epoch 秒の文字列
+                        let secs: u64 = timestamp
+                            .parse()
+                            .map_err(|_| format!("invalid coincheck timestamp {timestamp}"))?;
+                        let side = match side.as_str() {
+                            "buy" => Side::Buy,
+                            "sell" => Side::Sell,
+                            other => return Err(format!("invalid coincheck side {other}").into()),
+                        };
+                        let trade = TradeEvent {
+                            trade_id,
+                            price: parse_decimal_str(&rate).ok_or("invalid rate")?,
+                            qty: parse_decimal_str(&amount).ok_or("invalid amount")?,
+                            side,
+                        };
+                        trades(vec![core_trade(symbol, trade, Some(secs * 1000))?])
+                    }
+                    _ => Ok(None),
+                }
+            }
+            (ExchangeId::Upbit, PayloadSource::Ws) => {
+                match ucel_cex_upbit::normalize_ws_message(&bytes)? {
+                    ucel_cex_upbit::MarketEvent::Trade {
+                        trade_price,
+                        trade_volume,
+                        ask_bid,
+                        trade_timestamp,
+                        sequential_id,
+                        ..
+                    } => trades(vec![upbit_trade(
+                        symbol,
+                        trade_price,
+                        trade_volume,
+                        ask_bid.as_deref(),
+                        trade_timestamp,
+                        sequential_id,
+                    )?]),
+                    _ => Ok(None),
+                }
+            }
+            (ExchangeId::Upbit, PayloadSource::Rest) => match ucel_cex_upbit::parse_upbit_response(
+                "quotation.public.rest.trades.recent",
+                bytes.as_bytes(),
+            )? {
+                ucel_cex_upbit::UpbitRestResponse::Trades(items) => trades(
+                    items
+                        .into_iter()
+                        .map(|t| {
+                            upbit_trade(
+                                symbol,
+                                t.trade_price,
+                                t.trade_volume,
+                                t.ask_bid.as_deref(),
+                                t.timestamp,
+                                t.sequential_id,
+                            )
+                        })
+                        .collect::<Result<_, _>>()?,
+                ),
+                _ => Err("unexpected upbit trades response".into()),
+            },
+            _ => self.no_decoder(MarketDataChannel::Trades, source),
+        }
+    }
+
+    pub fn orderbook(
+        &self,
+        symbol: &str,
+        source: PayloadSource,
+        raw: &Value,
+    ) -> NormalizeResult<OrderBookEvent> {
+        if let Some(out) = self.venue_frame(source, raw) {
+            return out;
+        }
+        let bytes = raw.to_string();
+        let snapshot = |bids, asks, sequence| {
+            OrderBookEvent::Snapshot(CanonicalOrderBookSnapshot {
+                symbol: symbol.to_string(),
+                bids,
+                asks,
+                sequence,
+            })
+        };
+        let delta = |bids, asks, sequence_start, sequence_end| {
+            OrderBookEvent::Delta(CanonicalOrderBookDelta {
+                symbol: symbol.to_string(),
+                bids,
+                asks,
+                sequence_start,
+                sequence_end,
+            })
+        };
+        match (self.exchange, source) {
+            (ExchangeId::Bithumb, PayloadSource::Ws) => match ucel_cex_bithumb::normalize_ws_event(
+                "openapi.public.ws.orderbook.snapshot",
+                &bytes,
+            )? {
+                ucel_cex_bithumb::BithumbWsEvent::OrderBookDelta(d) => {
+                    let side = |ls: Vec<(Decimal, Decimal)>| {
+                        ls.into_iter()
+                            .map(|(price, qty)| CanonicalOrderBookLevel { price, qty })
+                            .collect()
+                    };
+                    book(delta(side(d.bids), side(d.asks), None, None), Some(d.ts_ms))
+                }
+                _ => Ok(None),
+            },
+            (ExchangeId::Bithumb, PayloadSource::Rest) => {
+                match ucel_cex_bithumb::normalize_rest_response(
+                    "openapi.public.rest.orderbook.snapshot",
+                    bytes.as_bytes(),
+                )? {
+                    ucel_cex_bithumb::BithumbRestResponse::OrderBookSnapshot(s) => book(
+                        snapshot(levels(s.bids), levels(s.asks), Some(s.sequence)),
+                        None,
+                    ),
+                    _ => Err("unexpected bithumb orderbook response".into()),
+                }
+            }
+            (ExchangeId::Sbivc, PayloadSource::Ws) => {
+                // 差分は sequence_start / sequence_end を必須で持つ。読めなければ全量として読む
+                let event = ucel_cex_sbivc::parse_market_event(
+                    "crypto.public.ws.market.orderbook.delta",
+                    bytes.as_bytes(),
+                )
+                .or_else(|_| {
+                    ucel_cex_sbivc::parse_market_event(
+                        "crypto.public.ws.market.orderbook.snapshot",
+                        bytes.as_bytes(),
+                    )
+                })?;
+                match event {
+                    ucel_cex_sbivc::MarketEvent::OrderBookSnapshot(s) => book(
+                        snapshot(levels(s.bids), levels(s.asks), Some(s.sequence)),
+                        None,
+                    ),
+                    ucel_cex_sbivc::MarketEvent::OrderBookDelta(d) => book(
+                        delta(
+                            levels(d.bids),
+                            levels(d.asks),
+                            Some(d.sequence_start),
+                            Some(d.sequence_end),
+                        ),
+                        None,
+                    ),
+                    _ => Ok(None),
+                }
+            }
+            (ExchangeId::Coinbase, PayloadSource::Ws) => {
+                match ucel_cex_coinbase::decode_market_event(bytes.as_bytes())? {
+                    ucel_cex_coinbase::MarketEvent::OrderbookSnapshot { snapshot: s, .. } => book(
+                        snapshot(levels(s.bids), levels(s.asks), Some(s.sequence)),
+                        None,
+                    ),
+                    _ => Ok(None),
+                }
+            }
+            (ExchangeId::Coincheck, PayloadSource::Ws) => {
+                match ucel_cex_coincheck::decode_market_event(
+                    "coincheck.ws.public.orderbook",
+                    bytes.as_bytes(),
+                )? {
+                    // coincheck の板メッセージは差分
+                    ucel_cex_coincheck::MarketEvent::Orderbook { bids, asks, .. } => book(
+                        delta(text_levels(bids)?, text_levels(asks)?, None, None),
+                        None,
+                    ),
+                    _ => Ok(None),
+                }
+            }
+            (ExchangeId::Okx, PayloadSource::Ws) => {
+                match ucel_cex_okx::parse_okx_orderbook_payload(&bytes)? {
+                    // 全量は prevSeqId = -1（crate では None）
+                    Some(b) => {
+                        let (bids, asks) = (text_levels(b.bids)?, text_levels(b.asks)?);
+                        let event = match b.prev_seq_id {
+                            None => snapshot(bids, asks, b.seq_id),
+                            Some(prev) => delta(bids, asks, Some(prev), b.seq_id),
+                        };
+                        book(event, None)
+                    }
+                    None => Ok(None),
+                }
+            }
+            (ExchangeId::Kraken, PayloadSource::Ws) => {
+                match ucel_cex_kraken::parse_kraken_book_v1(&bytes)? {
+                    Some(b) => {
+                        let (bids, asks) = (text_levels(b.bids)?, text_levels(b.asks)?);
+                        let event = if b.snapshot {
+                            snapshot(bids, asks, None)
+                        } else {
+                            delta(bids, asks, None, None)
+                        };
+                        book(event, None)
+                    }
+                    None => Ok(None),
+                }
+            }
+            (ExchangeId::Upbit, PayloadSource::Rest) => match ucel_cex_upbit::parse_upbit_response(
+                "quotation.public.rest.orderbook.snapshot",
+                bytes.as_bytes(),
+            )? {
+                ucel_cex_upbit::UpbitRestResponse::Orderbook(items) => {
+                    let b = only(items, "orderbook")?;
+                    let (bids, asks) = b
+                        .orderbook_units
+                        .iter()
+                        .map(|u| {
+                            (
+                                CanonicalOrderBookLevel {
+                                    price: u.bid_price,
+                                    qty: u.bid_size,
+                                },
+                                CanonicalOrderBookLevel {
+                                    price: u.ask_price,
+                                    qty: u.ask_size,
+                                },
+                            )
+                        })
+                        .unzip();
+                    book(snapshot(bids, asks, None), None)
+                }
+                _ => Err("unexpected upbit orderbook response".into()),
+            },
+            _ => self.no_decoder(MarketDataChannel::OrderBook, source),
+        }
+    }
+
+    /// OHLCV を揃えて返す型付きデコーダを持つ venue crate はまだ無い
+    pub fn candles(
+        &self,
+        _symbol: &str,
+        source: PayloadSource,
+        raw: &Value,
+    ) -> NormalizeResult<Vec<CanonicalCandle>> {
+        if let Some(out) = self.venue_frame(source, raw) {
+            return out;
+        }
+        self.no_decoder(MarketDataChannel::Candles, source)
+    }
+
+    /// bybit / kraken / okx / upbit の WS フレームを venue crate で分類する。
+    /// エラーフレームは Err、データを持たない応答は Ok(None)、データなら None（続けて読む）
+    fn venue_frame<T>(&self, source: PayloadSource, raw: &Value) -> Option<NormalizeResult<T>> {
+        if source != PayloadSource::Ws {
+            return None;
+        }
+        let bytes = raw.to_string();
+        if self.exchange == ExchangeId::Upbit {
+            return match ucel_cex_upbit::normalize_ws_message(&bytes) {
+                Err(e) => Some(Err(e.into())),
+                Ok(
+                    ucel_cex_upbit::MarketEvent::SubscriptionList { .. }
+                    | ucel_cex_upbit::MarketEvent::MyOrder { .. }
+                    | ucel_cex_upbit::MarketEvent::MyAsset { .. },
+                ) => Some(Ok(None)),
+                Ok(_) => None,
+            };
+        }
+        let kind = match self.exchange {
+            ExchangeId::Bybit => {
+                ucel_cex_bybit::normalize_ws_event("bybit.public.ws", &bytes).map(|e| e.kind)
+            }
+            ExchangeId::Kraken => {
+                ucel_cex_kraken::normalize_ws_event("kraken.public.ws", &bytes).map(|e| e.kind)
+            }
+            ExchangeId::Okx => {
+                ucel_cex_okx::normalize_ws_event("okx.public.ws", &bytes).map(|e| e.event_type)
+            }
+            _ => return None,
+        };
+        match kind {
+            Err(e) => Some(Err(e.into())),
+            Ok(kind) if kind == "error" => Some(Err(format!(
+                "{} error frame: {bytes}",
+                self.exchange.as_str()
+            )
+            .into())),
+            // okx は event 付きのフレーム（subscribe / login など）とデータ無しのフレームを返す
+            Ok(kind) if self.exchange == ExchangeId::Okx && kind != "update" => Some(Ok(None)),
+            // bybit の購読応答は op と success だけを持つ
+            Ok(_) if self.exchange == ExchangeId::Bybit && raw.get("op").is_some() => {
+                Some(Ok(None))
+            }
+            Ok(_) => None,
+        }
+    }
+}
+
+fn ticker(
+    symbol: &str,
+    best_bid: Option<Decimal>,
+    best_ask: Option<Decimal>,
+    last_price: Decimal,
+    ts_event: Option<u64>,
+) -> NormalizeResult<CanonicalTicker> {
+    // 気配が無い ticker は None のまま返す（last_price で埋めると偽の気配になる）
+    let missing = [
+        (best_bid.is_none(), "best_bid"),
+        (best_ask.is_none(), "best_ask"),
+        (ts_event.is_none(), "ts_event"),
+    ]
+    .into_iter()
+    .filter(|(m, _)| *m)
+    .map(|(_, name)| name.to_string())
+    .collect();
+    Ok(Some(Normalized {
+        data: CanonicalTicker {
+            symbol: symbol.to_string(),
+            best_bid,
+            best_ask,
+            last_price,
+            ts_event,
+        },
+        ts_event,
+        missing_fields: missing,
+    }))
+}
+
+fn trades(trades: Vec<CanonicalTrade>) -> NormalizeResult<Vec<CanonicalTrade>> {
+    let mut missing = Vec::new();
+    if trades.iter().any(|t| t.ts_event.is_none()) {
+        missing.push("ts_event".to_string());
+    }
+    if trades.iter().any(|t| t.side == Side::Unknown) {
+        missing.push("side".to_string());
+    }
+    let ts_event = trades.iter().filter_map(|t| t.ts_event).max();
+    Ok(Some(Normalized {
+        data: trades,
+        ts_event,
+        missing_fields: missing,
+    }))
+}
+
+fn book(event: OrderBookEvent, ts_event: Option<u64>) -> NormalizeResult<OrderBookEvent> {
+    Ok(Some(Normalized {
+        data: event,
+        ts_event,
+        missing_fields: Vec::new(),
+    }))
+}
+
+/// venue が約定番号を返さない約定は canonical にしない
+fn core_trade(
+    symbol: &str,
+    t: TradeEvent,
+    ts_event: Option<u64>,
+) -> Result<CanonicalTrade, NormalizeError> {
+    if t.trade_id.trim().is_empty() {
+        return Err("trade without trade_id".into());
+    }
+    Ok(CanonicalTrade {
+        symbol: symbol.to_string(),
+        trade_id: t.trade_id,
+        price: t.price,
+        qty: t.qty,
+        side: t.side,
+        ts_event,
+    })
+}
+
+fn upbit_trade(
+    symbol: &str,
+    price: Decimal,
+    qty: Decimal,
+    ask_bid: Option<&str>,
+    ts_ms: Option<u64>,
+    sequential_id: Option<u64>,
+) -> Result<CanonicalTrade, NormalizeError> {
+    let trade_id = sequential_id.ok_or("upbit trade without sequential_id")?;
+    let side = match ask_bid {
+        Some("BID") => Side::Buy,
+        Some("ASK") => Side::Sell,
+        Some(other) => return Err(format!("invalid upbit ask_bid {other}").into()),
+        None => Side::Unknown,
+    };
+    core_trade(
+        symbol,
+        TradeEvent {
+            trade_id: trade_id.to_string(),
+            price,
+            qty,
+            side,
+        },
+        ts_ms,
+    )
+}
+
+/// 1 銘柄を指定した REST 応答は 1 件だけを返す
+fn only<T>(items: Vec<T>, what: &str) -> Result<T, NormalizeError> {
+    let n = items.len();
+    let mut items = items.into_iter();
+    match (items.next(), n) {
+        (Some(item), 1) => Ok(item),
+        _ => Err(format!("expected one {what} entry, got {n}").into()),
+    }
+}
+
+fn levels(ls: Vec<OrderBookLevel>) -> Vec<CanonicalOrderBookLevel> {
+    ls.into_iter()
+        .map(|l| CanonicalOrderBookLevel {
+            price: l.price,
+            qty: l.qty,
+        })
+        .collect()
+}
+
+fn text_levels(ls: Vec<(String, String)>) -> Result<Vec<CanonicalOrderBookLevel>, NormalizeError> {
+    ls.iter()
+        .map(|(p, q)| {
+            Ok(CanonicalOrderBookLevel {
+                price: parse_decimal_str(p).ok_or_else(|| format!("invalid level price {p}"))?,
+                qty: parse_decimal_str(q).ok_or_else(|| format!("invalid level qty {q}"))?,
+            })
+        })
+        .collect()
+}
+
+fn parse_decimal_str(s: &str) -> Option<Decimal> {
+    let s = s.trim();
+    Decimal::from_str(s)
+        .or_else(|_| Decimal::from_scientific(s))
+        .ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn dec(s: &str) -> Decimal {
+        Decimal::from_str(s).unwrap()
+    }
+
+    #[test]
+    fn venues_without_a_typed_decoder_fail_with_no_decoder() {
+        let binance = MarketDataNormalizer::for_exchange(ExchangeId::Binance);
+        let raw = json!({"e": "aggTrade", "a": 26129, "p": "0.01633102", "q": "4.70443515", "T": 1499405254326u64, "m": true});
+        assert_eq!(
+            binance.trades("BNB/BTC", PayloadSource::Ws, &raw),
+            Err(NormalizeError::NoDecoder {
+                exchange: ExchangeId::Binance,
+                channel: MarketDataChannel::Trades,
+                source: PayloadSource::Ws,
+            })
+        );
+        let okx = MarketDataNormalizer::for_exchange(ExchangeId::Okx);
+        let raw = json!({"code": "0", "data": [{"instId": "BTC-USDT", "last": "100.5", "bidPx": "100.4", "askPx": "100.6", "ts": "1700000000000"}]});
+        let err = okx
+            .ticker("BTC/USDT", PayloadSource::Rest, &raw)
+            .unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "no typed ticker decoder for okx Rest payloads"
+        );
+        let upbit = MarketDataNormalizer::for_exchange(ExchangeId::Upbit);
+        assert!(matches!(
+            upbit.candles("BTC/KRW", PayloadSource::Rest, &json!([])),
+            Err(NormalizeError::NoDecoder { .. })
+        ));
+    }
+
+    #[test]
+    fn ticker_without_quotes_leaves_them_unset_and_reports_missing() {
+        let upbit = MarketDataNormalizer::for_exchange(ExchangeId::Upbit);
+        let raw = json!([{"market": "KRW-BTC", "trade_price": 50000000.0, "timestamp": 1700000000123u64}]);
+        let t = upbit
+            .ticker("BTC/KRW", PayloadSource::Rest, &raw)
+            .unwrap()
+            .unwrap();
+        assert_eq!((t.data.best_bid, t.data.best_ask), (None, None));
+        assert_eq!(t.data.last_price, dec("50000000"));
+        assert_eq!(t.ts_event, Some(1_700_000_000_123));
+        assert_eq!(t.missing_fields, vec!["best_bid", "best_ask"]);
+        assert!(upbit
+            .ticker(
+                "BTC/KRW",
+                PayloadSource::Rest,
+                &json!([{"market": "KRW-BTC"}])
+            )
+            .is_err());
+    }
+
+    #[test]
+    fn trades_never_invent_a_trade_id() {
+        let upbit = MarketDataNormalizer::for_exchange(ExchangeId::Upbit);
+        let raw = json!({"type": "trade", "code": "KRW-BTC", "trade_price": 50000000.0, "trade_volume": 0.01, "ask_bid": "ASK", "trade_timestamp": 1700000000123u64, "sequential_id": 17000000001230000u64});
+        let t = upbit
+            .trades("BTC/KRW", PayloadSource::Ws, &raw)
+            .unwrap()
+            .unwrap();
+        assert_eq!(t.data[0].trade_id, "17000000001230000");
+        assert_eq!(t.data[0].side, Side::Sell);
+        assert_eq!(t.ts_event, Some(1_700_000_000_123));
+        assert!(t.missing_fields.is_empty());
+
+        let raw = json!({"type": "trade", "code": "KRW-BTC", "trade_price": 50000000.0, "trade_volume": 0.01});
+        assert_eq!(
+            upbit.trades("BTC/KRW", PayloadSource::Ws, &raw),
+            Err(NormalizeError::Decode(
+                "upbit trade without sequential_id".into()
+            ))
+        );
+
+        // bithumb の decoder は contNo が無いと空文字を返す
+        let bithumb = MarketDataNormalizer::for_exchange(ExchangeId::Bithumb);
+        let raw = json!({"type": "trade", "content": {"contPrice": "100", "contQty": "1", "buySellGb": "1"}});
+        assert_eq!(
+            bithumb.trades("BTC/KRW", PayloadSource::Ws, &raw),
+            Err(NormalizeError::Decode("trade without trade_id".into()))
+        );
+    }
+
+    #[test]
+    fn coincheck_trade_timestamp_is_read_as_seconds() {
+        let coincheck = MarketDataNormalizer::for_exchange(ExchangeId::Coincheck);
+        let raw = json!([[
+            "1663318663",
+            "btc_jpy",
+            "2357007.0",
+            "0.0001",
+            "sell",
+            "1663318663"
+        ]]);
+        match coincheck.trades("BTC/JPY", PayloadSource::Ws, &raw) {
+            Ok(Some(t)) => {
+                assert_eq!(t.data[0].side, Side::Sell);
+                assert_eq!(t.ts_event, Some(1_663_318_663_000));
+            }
+            other => panic!("unexpected {other:?}"),
+        }
+    }
+
+    #[test]
+    fn orderbook_snapshots_and_deltas_come_from_venue_decoders() {
+        let okx = MarketDataNormalizer::for_exchange(ExchangeId::Okx);
+        let snap = json!({"arg": {"channel": "books", "instId": "BTC-USDT"}, "action": "snapshot", "data": [{"bids": [["100.0", "1", "0", "1"]], "asks": [["101.0", "2", "0", "1"]], "seqId": 10, "prevSeqId": -1}]});
+        match okx
+            .orderbook("BTC/USDT", PayloadSource::Ws, &snap)
+            .unwrap()
+            .unwrap()
+            .data
+        {
+            OrderBookEvent::Snapshot(s) => {
+                assert_eq!(s.sequence, Some(10));
+                assert_eq!(s.asks[0].qty, dec("2"));
+            }
+            other => panic!("unexpected {other:?}"),
+        }
+        let update = json!({"arg": {"channel": "books", "instId": "BTC-USDT"}, "action": "update", "data": [{"bids": [["100.0", "0", "0", "0"]], "asks": [], "seqId": 11, "prevSeqId": 10}]});
+        match okx
+            .orderbook("BTC/USDT", PayloadSource::Ws, &update)
+            .unwrap()
+            .unwrap()
+            .data
+        {
+            OrderBookEvent::Delta(d) => {
+                assert_eq!((d.sequence_start, d.sequence_end), (Some(10), Some(11)));
+                assert_eq!(d.bids[0].qty, dec("0"));
+            }
+            other => panic!("unexpected {other:?}"),
+        }
+
+        let upbit = MarketDataNormalizer::for_exchange(ExchangeId::Upbit);
+        let raw = json!([{"market": "KRW-BTC", "orderbook_units": [{"ask_price": 101, "bid_price": 100, "ask_size": 0.5, "bid_size": 1.5}]}]);
+        match upbit
+            .orderbook("BTC/KRW", PayloadSource::Rest, &raw)
+            .unwrap()
+            .unwrap()
+            .data
+        {
+            OrderBookEvent::Snapshot(s) => {
+                assert_eq!(s.asks[0].qty, dec("0.5"));
+                assert_eq!(s.bids[0].price, dec("100"));
+            }
+            other => panic!("unexpected {other:?}"),
+        }
+    }
+
+    #[test]
+    fn venue_crate_decoders_take_precedence() {
+        let bithumb = MarketDataNormalizer::for_exchange(ExchangeId::Bithumb);
+        let raw = json!({"type": "ticker", "content": {"bidPrice": "100", "askPrice": "101", "closePrice": "100.5"}});
+        let t = bithumb
+            .ticker("BTC/KRW", PayloadSource::Ws, &raw)
+            .unwrap()
+            .unwrap();
+        assert_eq!(t.data.last_price, dec("100.5"));
+        assert_eq!(t.missing_fields, vec!["ts_event"]);
+
+        let coinbase = MarketDataNormalizer::for_exchange(ExchangeId::Coinbase);
+        let raw = json!({"type": "trades", "channel_id": "c", "trades": [{"trade_id": "t1", "price": "10", "qty": "2", "side": "buy"}]});
+        let t = coinbase
+            .trades("BTC/USD", PayloadSource::Ws, &raw)
+            .unwrap()
+            .unwrap();
+        assert_eq!(t.data[0].trade_id, "t1");
+        assert_eq!(t.data[0].qty, dec("2"));
+    }
+
+    #[test]
+    fn ws_frames_are_routed_through_venue_crates() {
+        let okx = MarketDataNormalizer::for_exchange(ExchangeId::Okx);
+        let err = json!({"event": "error", "code": "60012", "msg": "Invalid request"});
+        assert!(okx.ticker("BTC/USDT", PayloadSource::Ws, &err).is_err());
+        let ack = json!({"event": "subscribe", "arg": {"channel": "tickers"}});
+        assert_eq!(okx.ticker("BTC/USDT", PayloadSource::Ws, &ack), Ok(None));
+
+        let bybit = MarketDataNormalizer::for_exchange(ExchangeId::Bybit);
+        assert!(bybit
+            .trades(
+                "BTC/USDT",
+                PayloadSource::Ws,
+                &json!({"success": false, "ret_msg": "bad topic"})
+            )
+            .is_err());
+        assert_eq!(
+            bybit.trades(
+                "BTC/USDT",
+                PayloadSource::Ws,
+                &json!({"success": true, "op": "subscribe", "conn_id": "c"})
+            ),
+            Ok(None)
+        );
+
+        let kraken = MarketDataNormalizer::for_exchange(ExchangeId::Kraken);
+        assert!(kraken
+            .orderbook(
+                "BTC/USD",
+                PayloadSource::Ws,
+                &json!({"error": "Currency pair not supported"})
+            )
+            .is_err());
+        let snap = json!([0, {"as": [["101.0", "1.0", "1700000000.1"]], "bs": [["100.0", "2.0", "1700000000.1"]]}, "book-10", "XBT/USD"]);
+        match kraken
+            .orderbook("BTC/USD", PayloadSource::Ws, &snap)
+            .unwrap()
+            .unwrap()
+            .data
+        {
+            OrderBookEvent::Snapshot(s) => assert_eq!(s.bids[0].qty, dec("2.0")),
+            other => panic!("unexpected {other:?}"),
+        }
+
+        // upbit の WS ticker は気配を持たないので None のまま
+        let upbit = MarketDataNormalizer::for_exchange(ExchangeId::Upbit);
+        let raw = json!({"type": "ticker", "code": "KRW-BTC", "trade_price": 50000000.0, "timestamp": 1700000000123u64});
+        let t = upbit
+            .ticker("BTC/KRW", PayloadSource::Ws, &raw)
+            .unwrap()
+            .unwrap();
+        assert_eq!((t.data.best_bid, t.data.best_ask), (None, None));
+        assert_eq!(t.data.last_price, dec("50000000"));
+        assert_eq!(t.ts_event, Some(1_700_000_000_123));
+        assert!(upbit
+            .ticker(
+                "BTC/KRW",
+                PayloadSource::Ws,
+                &json!({"type": "ticker", "code": "KRW-BTC"})
+            )
+            .is_err());
+    }
+}
@@ -0,0 +1,252 @@
+/// hash chain 監査: 追記・セグメント封印・再オープン・改ざん検知（最初の破断点）を検証する。
+use std::fs;
+use std::path::Path;
+use ucel_sdk::execution::*;
+
+const KEY: &[u8] = b"audit-manifest-key";
+
+fn cfg(dir: &Path) -> ChainedAuditConfig {
+    let mut c = ChainedAuditConfig::new(dir);
+    c.max_segment_records = 3;
+    c.signing_key = Some(KEY.to_vec());
+    c
+}
+
+fn cancel_result(run_id: &str, n: usize) -> AuditEvent {
+    AuditEvent::CancelResult {
+        run_id: Some(run_id.to_string()),
+        idempotency: IdempotencyKey::random_uuid(),
+        venue_order_id: format!("v-{n}"),
+        ok: true,
+        unix_ms: n as u64,
+    }
+}
+
+fn all() -> AuditReplayFilter {
+    AuditReplayFilter {
+        run_id: None,
+        venue: None,
+        intent_id: None,
+        idempotency: None,
+        since_unix_ms: None,
+        until_unix_ms: None,
+    }
+}
+
+fn segment(dir: &Path, idx: u64) -> std::path::PathBuf {
+    dir.join(format!("audit-{idx:08}.ndjson"))
+}
+
+#[test]
+fn chain_rotates_segments_and_survives_reopen() {
+    let tmp = tempfile::tempdir().unwrap();
+    {
+        let sink = ChainedFileAuditSink::open(cfg(tmp.path())).unwrap();
+        for n in 0..7 {
+            let id = sink.append(cancel_result("run-a", n)).unwrap();
+            assert_eq!(id, Some((n + 1).to_string()));
+        }
+    }
+    // 3 + 3 件で封印、7 件目は書き込み中セグメント
+    assert!(tmp.path().join("audit-00000001.manifest.json").exists());
+    assert!(tmp.path().join("audit-00000002.manifest.json").exists());
+    assert!(!tmp.path().join("audit-00000003.manifest.json").exists());
+
+    let sink = ChainedFileAuditSink::open(cfg(tmp.path())).unwrap();
+    sink.append(cancel_result("run-b", 7)).unwrap();
+    let report = sink.verify().unwrap();
+    assert!(report.ok, "{:?}", report.first_broken);
+    assert_eq!(report.segments, 3);
+    assert_eq!(report.records, 8);
+    assert_eq!(report.last_seq, 8);
+
+    let mut f = all();
+    f.run_id = Some("run-a".to_string());
+    assert_eq!(sink.replay(f).unwrap().len(), 7);
+    assert_eq!(sink.replay(all()).unwrap().len(), 8);
+}
+
+#[test]
+fn edited_record_is_reported_as_first_broken_link() {
+    let tmp = tempfile::tempdir().unwrap();
+    let sink = ChainedFileAuditSink::open(cfg(tmp.path())).unwrap();
+    for n in 0..5 {
+        sink.append(cancel_result("run-a", n)).unwrap();
+    }
+
+    // 書き込み中セグメント（2 つ目）の 1 行目の内容を書き換える
+    let p = segment(tmp.path(), 2);
+    let body = fs::read_to_string(&p).unwrap().replacen("v-3", "v-9", 1);
+    fs::write(&p, body).unwrap();
+
+    let report = sink.verify().unwrap();
+    assert!(!report.ok);
+    let b = report.first_broken.unwrap();
+    assert_eq!(b.segment, "audit-00000002.ndjson");
+    assert_eq!(b.line, 1);
+    assert_eq!(b.seq, Some(4));
+    assert!(matches!(b.kind, ChainBreakKind::HashMismatch { .. }));
+
+    // 壊れたチェーンは開かない
+    let err = ChainedFileAuditSink::open(cfg(tmp.path())).err().unwrap();
+    assert_eq!(err.code, SdkExecutionErrorCode::AuditFailure);
+}
+
+#[test]
+fn deleted_line_and_forged_manifest_are_detected() {
+    let tmp = tempfile::tempdir().unwrap();
+    let sink = ChainedFileAuditSink::open(cfg(tmp.path())).unwrap();
+    for n in 0..5 {
+        sink.append(cancel_result("run-a", n)).unwrap();
+    }
+
+    // 行削除 → seq の欠番
+    let p = segment(tmp.path(), 2);
+    let original = fs::read_to_string(&p).unwrap();
+    let tail: String = original.lines().skip(1).map(|l| format!("{l}\n")).collect();
+    fs::write(&p, tail).unwrap();
+    let b = verify_audit_chain(tmp.path(), Some(KEY))
+        .unwrap()
+        .first_broken
+        .unwrap();
+    assert_eq!(
+        b.kind,
+        ChainBreakKind::SeqGap {
+            expected: 4,
+            found: 5
+        }
+    );
+    fs::write(&p, original).unwrap();
+    assert!(verify_audit_chain(tmp.path(), Some(KEY)).unwrap().ok);
+
+    // 封印済み manifest の改ざん → 署名不一致 / 鍵が違っても不一致
+    let mp = tmp.path().join("audit-00000001.manifest.json");
+    let mut m: AuditSegmentManifest = serde_json::from_slice(&fs::read(&mp).unwrap()).unwrap();
+    assert!(
+        !verify_audit_chain(tmp.path(), Some(b"other-key"))
+            .unwrap()
+            .ok
+    );
+    m.records = 2;
+    fs::write(&mp, serde_json::to_vec(&m).unwrap()).unwrap();
+    let b = verify_audit_chain(tmp.path(), Some(KEY))
+        .unwrap()
+        .first_broken
+        .unwrap();
+    assert_eq!(b.segment, "audit-00000001.ndjson");
+    assert_eq!(b.kind, ChainBreakKind::ManifestSignatureInvalid);
+
+    // 署名なしで検証すると内容との不一致として出る
+    let b = verify_audit_chain(tmp.path(), None)
+        .unwrap()
+        .first_broken
+        .unwrap();
+    assert_eq!(
+        b.kind,
+        ChainBreakKind::ManifestMismatch {
+            field: "records".to_string()
+        }
+    );
+}
+
+#[test]
+fn torn_tail_is_truncated_on_open() {
+    let tmp = tempfile::tempdir().unwrap();
+    {
+        let sink = ChainedFileAuditSink::open(cfg(tmp.path())).unwrap();
+        for n in 0..2 {
+            sink.append(cancel_result("run-a", n)).unwrap();
+        }
+    }
+    let p = segment(tmp.path(), 1);
+    let mut body = fs::read_to_string(&p).unwrap();
+    body.push_str("{\"seq\":3,\"unix_ms\"");
+    fs::write(&p, body).unwrap();
+
+    let b = verify_audit_chain(tmp.path(), Some(KEY))
+        .unwrap()
+        .first_broken
+        .unwrap();
+    assert_eq!(b.kind, ChainBreakKind::TornTail);
+
+    let sink = ChainedFileAuditSink::open(cfg(tmp.path())).unwrap();
+    sink.append(cancel_result("run-a", 2)).unwrap();
+    let report = sink.verify().unwrap();
+    assert!(report.ok);
+    assert_eq!(report.last_seq, 3);
+}
+
+#[test]
+fn tail_truncation_and_deleted_trailing_segments_are_detected_by_head() {
+    let tmp = tempfile::tempdir().unwrap();
+    let sink = ChainedFileAuditSink::open(cfg(tmp.path())).unwrap();
+    for n in 0..5 {
+        sink.append(cancel_result("run-a", n)).unwrap();
+    }
+    let head_path = tmp.path().join("audit-head.json");
+    let head: AuditChainHead = serde_json::from_slice(&fs::read(&head_path).unwrap()).unwrap();
+    assert_eq!(head.last_seq, 5);
+
+    // 書き込み中セグメントの末尾行を落とす
+    let p = segment(tmp.path(), 2);
+    let original = fs::read_to_string(&p).unwrap();
+    let first: String = original.lines().take(1).map(|l| format!("{l}\n")).collect();
+    fs::write(&p, first).unwrap();
+    let b = sink.verify().unwrap().first_broken.unwrap();
+    assert_eq!(
+        b.kind,
+        ChainBreakKind::TailTruncated {
+            expected_last_seq: 5,
+            found_last_seq: 4
+        }
+    );
+
+    // 書き込み中セグメントごと消す
+    fs::remove_file(&p).unwrap();
+    let b = sink.verify().unwrap().first_broken.unwrap();
+    assert_eq!(b.segment, "audit-00000001.ndjson");
+    assert_eq!(
+        b.kind,
+        ChainBreakKind::TailTruncated {
+            expected_last_seq: 5,
+            found_last_seq: 3
+        }
+    );
+    assert!(ChainedFileAuditSink::open(cfg(tmp.path())).is_err());
+    fs::write(&p, original).unwrap();
+    assert!(sink.verify().unwrap().ok);
+
+    // head を書き換えると署名不一致、消すと HeadMissing
+    let mut forged = head.clone();
+    forged.last_seq = 3;
+    fs::write(&head_path, serde_json::to_vec(&forged).unwrap()).unwrap();
+    assert_eq!(
+        sink.verify().unwrap().first_broken.unwrap().kind,
+        ChainBreakKind::HeadSignatureInvalid
+    );
+    fs::remove_file(&head_path).unwrap();
+    assert_eq!(
+        sink.verify().unwrap().first_broken.unwrap().kind,
+        ChainBreakKind::HeadMissing
+    );
+}
+
+#[test]
+fn head_one_record_behind_after_crash_is_accepted_and_caught_up_on_open() {
+    let tmp = tempfile::tempdir().unwrap();
+    let head_path = tmp.path().join("audit-head.json");
+    {
+        let sink = ChainedFileAuditSink::open(cfg(tmp.path())).unwrap();
+        sink.append(cancel_result("run-a", 0)).unwrap();
+        let stale = fs::read(&head_path).unwrap();
+        sink.append(cancel_result("run-a", 1)).unwrap();
+        // 行の追記後、head 更新前に落ちた状態
+        fs::write(&head_path, stale).unwrap();
+    }
+    assert!(verify_audit_chain(tmp.path(), Some(KEY)).unwrap().ok);
+
+    let sink = ChainedFileAuditSink::open(cfg(tmp.path())).unwrap();
+    let head: AuditChainHead = serde_json::from_slice(&fs::read(&head_path).unwrap()).unwrap();
+    assert_eq!(head.last_seq, 2);
+    assert_eq!(head.last_hash_hex, sink.verify().unwrap().last_hash_hex);
+}
@@ -67,6 +67,8 @@ impl ExecutionConnectorAsync for MockBittradeConnector {
             ok: true,
             mismatches: vec![],
             generated_at_unix_ms: unix_ms_now(),
+            details: vec![],
+            actions: vec![],
         })
     }
 }
@@ -79,8 +81,8 @@ fn mk_intent() -> OrderIntent {
         side: OrderSide::Buy,
         order_type: OrderType::Limit,
         tif: Some(OrderTimeInForce::Gtc),
-        price: Some(Price(100.0)),
-        qty: Quantity(0.01),
+        price: Some("100".parse().unwrap()),
+        qty: "0.01".parse().unwrap(),
         tags: std::collections::BTreeMap::new(),
     }
 }
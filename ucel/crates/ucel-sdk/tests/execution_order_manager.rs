@@ -0,0 +1,367 @@
+use std::str::FromStr;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+use ucel_core::{CanonicalFillEvent, CanonicalOrderEvent, CanonicalPrivateWsEvent, Decimal};
+use ucel_sdk::execution::*;
+
+struct MockVenue {
+    seq: AtomicUsize,
+    open: Mutex<Vec<OrderReceipt>>,
+}
+
+impl MockVenue {
+    fn new() -> Self {
+        Self {
+            seq: AtomicUsize::new(0),
+            open: Mutex::new(vec![]),
+        }
+    }
+}
+
+impl ExecutionConnector for MockVenue {
+    fn place_order(&self, req: &OrderRequest) -> SdkExecutionResult<OrderReceipt> {
+        let n = self.seq.fetch_add(1, Ordering::SeqCst) + 1;
+        Ok(OrderReceipt {
+            venue: req.intent.venue.clone(),
+            symbol: req.intent.symbol.clone(),
+            status: OrderStatus::Accepted,
+            venue_order_id: Some(format!("v-{n}")),
+            client_order_id: req.intent.tags.get("client_order_id").cloned(),
+            intent_id: req.intent.intent_id.clone(),
+            idempotency: req.idempotency.clone(),
+        })
+    }
+
+    fn cancel_order(&self, _cancel: &OrderCancel) -> SdkExecutionResult<bool> {
+        Ok(true)
+    }
+
+    fn list_open_orders(&self, _q: &OrderOpenQuery) -> SdkExecutionResult<Vec<OrderReceipt>> {
+        Ok(self.open.lock().unwrap().clone())
+    }
+}
+
+fn d(s: &str) -> Decimal {
+    Decimal::from_str(s).unwrap()
+}
+
+fn venue() -> VenueId {
+    VenueId::new("bybit")
+}
+
+fn req(client_order_id: &str, qty: &str) -> OrderRequest {
+    let mut tags = std::collections::BTreeMap::new();
+    tags.insert("client_order_id".to_string(), client_order_id.to_string());
+    OrderRequest {
+        mode: ExecutionMode::Live,
+        intent: OrderIntent {
+            intent_id: OrderIntentId::new("intent-om"),
+            venue: venue(),
+            symbol: Symbol::new("BTCUSDT"),
+            side: OrderSide::Buy,
+            order_type: OrderType::Limit,
+            tif: None,
+            price: Some(Price::new(d("100"))),
+            qty: Quantity::from_str(qty).unwrap(),
+            tags,
+        },
+        idempotency: IdempotencyKey::random_uuid(),
+        run_id: Some("run-om".into()),
+    }
+}
+
+fn ws_order(order_id: &str, status: &str, ts: u64) -> CanonicalPrivateWsEvent {
+    CanonicalPrivateWsEvent::Order(CanonicalOrderEvent {
+        order_id: order_id.into(),
+        symbol: "BTCUSDT".into(),
+        side: Some("Buy".into()),
+        status: status.into(),
+        price: Some("100".into()),
+        qty: Some("1".into()),
+        ts_event_ms: Some(ts),
+    })
+}
+
+fn ws_fill(
+    fill_id: &str,
+    order_id: &str,
+    price: &str,
+    qty: &str,
+    ts: u64,
+) -> CanonicalPrivateWsEvent {
+    CanonicalPrivateWsEvent::Fill(CanonicalFillEvent {
+        fill_id: fill_id.into(),
+        order_id: Some(order_id.into()),
+        symbol: Some("BTCUSDT".into()),
+        side: Some("buy".into()),
+        price: Some(price.into()),
+        qty: Some(qty.into()),
+        fee: Some("0.01".into()),
+        ts_event_ms: Some(ts),
+    })
+}
+
+#[test]
+fn order_moves_from_intent_to_filled_and_notifies_subscribers() {
+    let om = Arc::new(OrderManager::default());
+    let mut rx = om.subscribe();
+    let client = ExecutionClient::new(MockVenue::new()).with_order_manager(om.clone());
+
+    let out = client.place(req("cli-1", "1")).unwrap();
+    let vid = out.receipt.venue_order_id.unwrap();
+    assert_eq!(om.get("cli-1").unwrap().status, OrderStatus::Accepted);
+
+    om.apply_private_ws_event(&venue(), &ws_order(&vid, "New", 10))
+        .unwrap();
+    om.apply_private_ws_event(&venue(), &ws_fill("f-1", &vid, "100", "0.4", 11))
+        .unwrap();
+    let o = om.get("cli-1").unwrap();
+    assert_eq!(o.status, OrderStatus::PartiallyFilled);
+    assert_eq!(o.remaining_qty(), Some(Quantity::new(d("0.6"))));
+
+    om.apply_private_ws_event(&venue(), &ws_fill("f-2", &vid, "101", "0.6", 12))
+        .unwrap();
+    let o = om.get("cli-1").unwrap();
+    assert_eq!(o.status, OrderStatus::Filled);
+    assert_eq!(o.filled_qty, Quantity::new(d("1")));
+    assert_eq!(o.avg_fill_price, Some(Price::new(d("100.6"))));
+    assert_eq!(o.fee, d("0.02"));
+
+    let mut seen = vec![];
+    while let Ok(u) = rx.try_recv() {
+        seen.push((u.cause, u.order.status));
+    }
+    assert_eq!(
+        seen.first(),
+        Some(&(OrderUpdateCause::Submitted, OrderStatus::Unknown))
+    );
+    assert_eq!(
+        seen.last(),
+        Some(&(
+            OrderUpdateCause::VenueFill {
+                fill_id: "f-2".into()
+            },
+            OrderStatus::Filled
+        ))
+    );
+}
+
+#[test]
+fn duplicate_stale_and_out_of_order_events_are_reported() {
+    let om = OrderManager::default();
+    let r = MockVenue::new().place_order(&req("cli-2", "1")).unwrap();
+    om.on_receipt(&req("cli-2", "1"), &r).unwrap();
+    let vid = r.venue_order_id.unwrap();
+
+    om.apply_private_ws_event(&venue(), &ws_fill("f-1", &vid, "100", "0.5", 20))
+        .unwrap();
+    let dup = om
+        .apply_private_ws_event(&venue(), &ws_fill("f-1", &vid, "100", "0.5", 20))
+        .unwrap();
+    assert_eq!(
+        dup[0].anomalies,
+        vec![OrderAnomaly::DuplicateFill {
+            fill_id: "f-1".into()
+        }]
+    );
+    assert_eq!(om.get("cli-2").unwrap().filled_qty, Quantity::new(d("0.5")));
+
+    // 先行した約定より古い約定も数量には反映する
+    let late = om
+        .apply_private_ws_event(&venue(), &ws_fill("f-0", &vid, "99", "0.5", 15))
+        .unwrap();
+    assert!(matches!(
+        late[0].anomalies[0],
+        OrderAnomaly::OutOfOrderFill {
+            ts_event_ms: 15,
+            last_fill_ms: 20,
+            ..
+        }
+    ));
+    assert_eq!(om.get("cli-2").unwrap().status, OrderStatus::Filled);
+
+    // Filled の後に届いた古い注文イベントでは戻らない
+    om.apply_private_ws_event(&venue(), &ws_order(&vid, "PartiallyFilled", 30))
+        .unwrap();
+    let stale = om
+        .apply_private_ws_event(&venue(), &ws_order(&vid, "New", 25))
+        .unwrap();
+    assert!(matches!(
+        stale[0].anomalies[0],
+        OrderAnomaly::StaleOrderEvent { .. }
+    ));
+    assert_eq!(om.get("cli-2").unwrap().status, OrderStatus::Filled);
+}
+
+#[test]
+fn ws_events_before_receipt_are_buffered_until_linked() {
+    let om = OrderManager::default();
+    om.apply_private_ws_event(&venue(), &ws_order("v-1", "New", 1))
+        .unwrap();
+    om.apply_private_ws_event(&venue(), &ws_fill("f-1", "v-1", "100", "1", 2))
+        .unwrap();
+    assert_eq!(om.pending_event_count(), 2);
+
+    let r = MockVenue::new().place_order(&req("cli-3", "1")).unwrap();
+    let updates = om.on_receipt(&req("cli-3", "1"), &r).unwrap();
+    assert_eq!(om.pending_event_count(), 0);
+    assert_eq!(updates.last().unwrap().order.status, OrderStatus::Filled);
+}
+
+#[test]
+fn illegal_transitions_are_rejected() {
+    assert!(OrderStatus::Open.can_transition_to(&OrderStatus::PartiallyFilled));
+    assert!(!OrderStatus::Canceled.can_transition_to(&OrderStatus::Open));
+    assert!(!OrderStatus::Filled.can_transition_to(&OrderStatus::Unknown));
+
+    let om = Arc::new(OrderManager::default());
+    let client = ExecutionClient::new(MockVenue::new()).with_order_manager(om.clone());
+    let vid = client
+        .place(req("cli-4", "1"))
+        .unwrap()
+        .receipt
+        .venue_order_id
+        .unwrap();
+    client
+        .cancel(OrderCancel {
+            venue: venue(),
+            symbol: Symbol::new("BTCUSDT"),
+            venue_order_id: vid.clone(),
+            idempotency: IdempotencyKey::random_uuid(),
+            run_id: None,
+        })
+        .unwrap();
+    let u = om
+        .apply_private_ws_event(&venue(), &ws_order(&vid, "New", 5))
+        .unwrap();
+    assert_eq!(
+        u[0].anomalies,
+        vec![OrderAnomaly::IllegalTransition {
+            from: OrderStatus::Canceled,
+            to: OrderStatus::Open
+        }]
+    );
+    assert_eq!(om.get("cli-4").unwrap().status, OrderStatus::Canceled);
+    assert_eq!(parse_venue_order_status("CANCELLED"), OrderStatus::Canceled);
+    assert_eq!(
+        parse_venue_order_status("partially-filled"),
+        OrderStatus::PartiallyFilled
+    );
+}
+
+#[test]
+fn open_orders_poll_reports_missing_and_unknown_orders() {
+    let venue_mock = MockVenue::new();
+    let om = OrderManager::default();
+    for cid in ["cli-a", "cli-b"] {
+        let r = venue_mock.place_order(&req(cid, "1")).unwrap();
+        om.on_receipt(&req(cid, "1"), &r).unwrap();
+    }
+    om.apply_private_ws_event(&venue(), &ws_fill("f-1", "v-1", "100", "0.5", 1))
+        .unwrap();
+
+    let open_a = OrderReceipt {
+        venue: venue(),
+        symbol: Symbol::new("BTCUSDT"),
+        status: OrderStatus::Open,
+        venue_order_id: Some("v-1".into()),
+        client_order_id: None,
+        intent_id: OrderIntentId::new("x"),
+        idempotency: IdempotencyKey::random_uuid(),
+    };
+    let mut foreign = open_a.clone();
+    foreign.venue_order_id = Some("v-99".into());
+    *venue_mock.open.lock().unwrap() = vec![open_a, foreign];
+
+    let sync = om
+        .poll_open_orders(
+            &venue_mock,
+            &OrderOpenQuery {
+                venue: venue(),
+                symbol: None,
+            },
+        )
+        .unwrap();
+    assert_eq!(sync.missing_on_venue, vec!["cli-b".to_string()]);
+    assert_eq!(sync.unknown_venue_orders.len(), 1);
+    // REST の "open" で PartiallyFilled を巻き戻さない
+    assert_eq!(
+        om.get("cli-a").unwrap().status,
+        OrderStatus::PartiallyFilled
+    );
+}
+
+/// venue_order_id も client_order_id も返さない venue（台帳に紐付けられない receipt）
+struct AnonymousReceiptVenue;
+
+impl ExecutionConnector for AnonymousReceiptVenue {
+    fn place_order(&self, req: &OrderRequest) -> SdkExecutionResult<OrderReceipt> {
+        Ok(OrderReceipt {
+            venue: req.intent.venue.clone(),
+            symbol: req.intent.symbol.clone(),
+            status: OrderStatus::Accepted,
+            venue_order_id: None,
+            client_order_id: None,
+            intent_id: req.intent.intent_id.clone(),
+            idempotency: req.idempotency.clone(),
+        })
+    }
+
+    fn cancel_order(&self, _cancel: &OrderCancel) -> SdkExecutionResult<bool> {
+        Ok(true)
+    }
+
+    fn list_open_orders(&self, _q: &OrderOpenQuery) -> SdkExecutionResult<Vec<OrderReceipt>> {
+        Ok(vec![])
+    }
+}
+
+#[test]
+fn reused_client_order_id_is_rejected_before_reaching_the_venue() {
+    let om = Arc::new(OrderManager::default());
+    let client = ExecutionClient::new(MockVenue::new()).with_order_manager(om.clone());
+
+    let first = client.place(req("cli-dup", "1")).unwrap();
+    assert_eq!(first.receipt.venue_order_id.as_deref(), Some("v-1"));
+
+    let err = client.place(req("cli-dup", "2")).unwrap_err();
+    assert_eq!(err.code, SdkExecutionErrorCode::IdempotencyViolation);
+    let err = om.on_submitted(&req("cli-dup", "3")).unwrap_err();
+    assert_eq!(err.code, SdkExecutionErrorCode::IdempotencyViolation);
+
+    // 最初の注文の追跡は上書きされない
+    let order = om.get("cli-dup").unwrap();
+    assert_eq!(order.venue_order_id.as_deref(), Some("v-1"));
+    assert_eq!(order.qty, Some(Quantity::new(d("1"))));
+    assert_eq!(om.orders().unwrap().len(), 1);
+}
+
+#[test]
+fn tracking_failure_after_acceptance_still_returns_receipt_and_audits() {
+    let audit = Arc::new(InMemoryAuditSink::new());
+    let client = ExecutionClient::new(AnonymousReceiptVenue)
+        .with_audit(Box::new(audit.clone()))
+        .with_order_manager(Arc::new(OrderManager::default()));
+
+    let out = client.place(req("cli-5", "1")).unwrap();
+    assert_eq!(out.receipt.status, OrderStatus::Accepted);
+
+    let events = audit
+        .replay(AuditReplayFilter {
+            run_id: Some("run-om".into()),
+            venue: None,
+            intent_id: None,
+            idempotency: None,
+            since_unix_ms: None,
+            until_unix_ms: None,
+        })
+        .unwrap();
+    assert!(events
+        .iter()
+        .any(|e| matches!(e, AuditEvent::OrderResult { .. })));
+    assert!(events.iter().any(|e| matches!(
+        e,
+        AuditEvent::OrderTrackingFailed { error, .. }
+            if error.contains("neither client_order_id nor venue_order_id")
+    )));
+}
@@ -0,0 +1,356 @@
+use std::str::FromStr;
+use std::sync::Arc;
+use ucel_core::{
+    CanonicalOrderBookLevel, CanonicalOrderBookSnapshot, CanonicalTrade, Decimal, Side,
+};
+use ucel_sdk::execution::*;
+
+fn d(s: &str) -> Decimal {
+    Decimal::from_str(s).unwrap()
+}
+
+fn lv(price: &str, qty: &str) -> CanonicalOrderBookLevel {
+    CanonicalOrderBookLevel {
+        price: d(price),
+        qty: d(qty),
+    }
+}
+
+fn book() -> CanonicalOrderBookSnapshot {
+    CanonicalOrderBookSnapshot {
+        symbol: "BTCUSDT".into(),
+        bids: vec![lv("99", "1"), lv("98", "2")],
+        asks: vec![lv("101", "1"), lv("102", "2")],
+        sequence: Some(1),
+    }
+}
+
+fn venue() -> PaperExecutionVenue {
+    let v = PaperExecutionVenue::new(PaperVenueConfig {
+        venue: VenueId::new("paper"),
+        maker_fee_bps: d("-1"),
+        taker_fee_bps: d("10"),
+    });
+    v.on_orderbook_snapshot(&book()).unwrap();
+    v
+}
+
+fn req(
+    side: OrderSide,
+    order_type: OrderType,
+    tif: Option<OrderTimeInForce>,
+    price: Option<&str>,
+    qty: &str,
+) -> OrderRequest {
+    OrderRequest {
+        mode: ExecutionMode::Paper,
+        intent: OrderIntent {
+            intent_id: OrderIntentId::new("intent-paper"),
+            venue: VenueId::new("paper"),
+            symbol: Symbol::new("BTCUSDT"),
+            side,
+            order_type,
+            tif,
+            price: price.map(|p| Price::from_str(p).unwrap()),
+            qty: Quantity::from_str(qty).unwrap(),
+            tags: std::collections::BTreeMap::new(),
+        },
+        idempotency: IdempotencyKey::random_uuid(),
+        run_id: Some("run-paper".into()),
+    }
+}
+
+fn trade(side: Side, price: &str, qty: &str) -> CanonicalTrade {
+    CanonicalTrade {
+        symbol: "BTCUSDT".into(),
+        trade_id: "t".into(),
+        price: d(price),
+        qty: d(qty),
+        side,
+        ts_event: None,
+    }
+}
+
+#[test]
+fn market_order_walks_book_and_charges_taker_fee() {
+    let v = venue();
+    let r = v
+        .place_order(&req(OrderSide::Buy, OrderType::Market, None, None, "2"))
+        .unwrap();
+    assert_eq!(r.status, OrderStatus::Filled);
+    let fills = v.fills().unwrap();
+    assert_eq!(fills.len(), 2);
+    assert_eq!(fills[0].price, Price::new(d("101")));
+    assert_eq!(fills[1].price, Price::new(d("102")));
+    assert!(fills.iter().all(|f| f.liquidity == FillLiquidity::Taker));
+    // 101 * 1 * 10bps
+    assert_eq!(fills[0].fee, d("0.101"));
+}
+
+#[test]
+fn market_order_without_enough_depth_expires_remainder() {
+    let v = venue();
+    let r = v
+        .place_order(&req(OrderSide::Sell, OrderType::Market, None, None, "5"))
+        .unwrap();
+    assert_eq!(r.status, OrderStatus::Expired);
+    assert_eq!(v.fills().unwrap().len(), 2);
+}
+
+#[test]
+fn limit_crossing_partially_fills_then_rests() {
+    let v = venue();
+    let r = v
+        .place_order(&req(
+            OrderSide::Buy,
+            OrderType::Limit,
+            None,
+            Some("101"),
+            "1.5",
+        ))
+        .unwrap();
+    assert_eq!(r.status, OrderStatus::PartiallyFilled);
+    let open = v
+        .list_open_orders(&OrderOpenQuery {
+            venue: VenueId::new("paper"),
+            symbol: None,
+        })
+        .unwrap();
+    assert_eq!(open.len(), 1);
+}
+
+#[test]
+fn ioc_and_fok_semantics() {
+    let v = venue();
+    let ioc = v
+        .place_order(&req(
+            OrderSide::Buy,
+            OrderType::Limit,
+            Some(OrderTimeInForce::Ioc),
+            Some("101"),
+            "1.5",
+        ))
+        .unwrap();
+    assert_eq!(ioc.status, OrderStatus::Expired);
+    assert_eq!(v.fills().unwrap().len(), 1);
+
+    let fok = v
+        .place_order(&req(
+            OrderSide::Buy,
+            OrderType::Limit,
+            Some(OrderTimeInForce::Fok),
+            Some("102"),
+            "3",
+        ))
+        .unwrap();
+    assert_eq!(fok.status, OrderStatus::Expired);
+    // FOK は一切約定しない
+    assert_eq!(v.fills().unwrap().len(), 1);
+}
+
+#[test]
+fn post_only_rejected_when_crossing() {
+    let v = venue();
+    let r = v
+        .place_order(&req(
+            OrderSide::Sell,
+            OrderType::PostOnly,
+            None,
+            Some("99"),
+            "1",
+        ))
+        .unwrap();
+    assert_eq!(r.status, OrderStatus::Rejected);
+
+    let r = v
+        .place_order(&req(
+            OrderSide::Sell,
+            OrderType::PostOnly,
+            None,
+            Some("100.5"),
+            "1",
+        ))
+        .unwrap();
+    assert_eq!(r.status, OrderStatus::Open);
+}
+
+#[test]
+fn resting_order_respects_queue_position() {
+    let v = venue();
+    // 99 には既に 1 並んでいる
+    let r = v
+        .place_order(&req(
+            OrderSide::Buy,
+            OrderType::Limit,
+            None,
+            Some("99"),
+            "1",
+        ))
+        .unwrap();
+    assert_eq!(r.status, OrderStatus::Open);
+
+    // 先頭の 1 を消化するだけ
+    assert!(v
+        .on_trade(&trade(Side::Sell, "99", "1"))
+        .unwrap()
+        .is_empty());
+    // queue が空いたので約定する
+    let fills = v.on_trade(&trade(Side::Sell, "99", "0.4")).unwrap();
+    assert_eq!(fills.len(), 1);
+    assert_eq!(fills[0].qty, Quantity::new(d("0.4")));
+    assert_eq!(fills[0].liquidity, FillLiquidity::Maker);
+    assert!(fills[0].fee < Decimal::ZERO);
+
+    // 価格を跨いだ約定は queue を無視
+    let fills = v.on_trade(&trade(Side::Sell, "98", "5")).unwrap();
+    assert_eq!(fills[0].qty, Quantity::new(d("0.6")));
+    assert!(!v.has_open_order(r.venue_order_id.as_deref().unwrap()));
+}
+
+#[test]
+fn better_priced_bid_fills_first_with_asks_resting_between() {
+    let v = venue();
+    for (side, price) in [
+        (OrderSide::Buy, "98.5"),
+        (OrderSide::Sell, "100.5"),
+        (OrderSide::Buy, "99.5"),
+    ] {
+        v.place_order(&req(side, OrderType::Limit, None, Some(price), "1"))
+            .unwrap();
+    }
+    let fills = v.on_trade(&trade(Side::Sell, "98", "1")).unwrap();
+    assert_eq!(fills.len(), 1);
+    assert_eq!(fills[0].price, Price::new(d("99.5")));
+}
+
+#[test]
+fn snapshot_crossing_resting_order_fills_as_maker() {
+    let v = venue();
+    v.place_order(&req(
+        OrderSide::Sell,
+        OrderType::Limit,
+        None,
+        Some("100"),
+        "1",
+    ))
+    .unwrap();
+    let mut moved = book();
+    moved.bids = vec![lv("100.5", "0.25"), lv("99", "1")];
+    let fills = v.on_orderbook_snapshot(&moved).unwrap();
+    assert_eq!(fills.len(), 1);
+    assert_eq!(fills[0].price, Price::new(d("100")));
+    assert_eq!(fills[0].qty, Quantity::new(d("0.25")));
+}
+
+#[test]
+fn execution_client_routes_paper_mode_and_audits_fills() {
+    let audit = Arc::new(InMemoryAuditSink::new());
+    let paper = Arc::new(venue().with_audit(Box::new(audit.clone())));
+    let client = ExecutionClient::new(PaperExecutionVenue::new(PaperVenueConfig::default()))
+        .with_audit(Box::new(audit.clone()))
+        .with_paper_venue(paper.clone());
+
+    let out = client
+        .place(req(OrderSide::Buy, OrderType::Limit, None, Some("98"), "1"))
+        .unwrap();
+    assert_eq!(out.receipt.status, OrderStatus::Open);
+    let venue_order_id = out.receipt.venue_order_id.clone().unwrap();
+
+    let ok = client
+        .cancel(OrderCancel {
+            venue: VenueId::new("paper"),
+            symbol: Symbol::new("BTCUSDT"),
+            venue_order_id,
+            idempotency: IdempotencyKey::random_uuid(),
+            run_id: Some("run-paper".into()),
+        })
+        .unwrap();
+    assert!(ok);
+
+    client
+        .place(req(OrderSide::Buy, OrderType::Market, None, None, "1"))
+        .unwrap();
+
+    let events = client
+        .replay(AuditReplayFilter {
+            run_id: Some("run-paper".into()),
+            venue: None,
+            intent_id: None,
+            idempotency: None,
+            since_unix_ms: None,
+            until_unix_ms: None,
+        })
+        .unwrap();
+    assert!(events.iter().any(|e| matches!(e, AuditEvent::Fill { .. })));
+    assert!(events.iter().any(|e| matches!(
+        e,
+        AuditEvent::OrderStatusChanged {
+            status: OrderStatus::Canceled,
+            ..
+        }
+    )));
+}
+
+fn tracked_req(client_order_id: &str) -> OrderRequest {
+    let mut r = req(OrderSide::Buy, OrderType::Market, None, None, "1.5");
+    r.intent
+        .tags
+        .insert("client_order_id".into(), client_order_id.into());
+    r
+}
+
+fn assert_walked_fills_applied(om: &OrderManager, client_order_id: &str) {
+    let order = om.get(client_order_id).unwrap();
+    assert_eq!(order.status, OrderStatus::Filled);
+    assert_eq!(order.filled_qty, Quantity::new(d("1.5")));
+    assert_eq!(order.fill_count, 2);
+    // (101 * 1 + 102 * 0.5) / 1.5
+    assert_eq!(
+        order.avg_fill_price.map(|p| p.0.round_dp(6)),
+        Some(d("101.333333"))
+    );
+    assert_eq!(order.remaining_qty(), Some(Quantity::new(d("0"))));
+}
+
+#[test]
+fn paper_fills_reach_the_order_manager_before_place_returns() {
+    let om = Arc::new(OrderManager::default());
+    let client = ExecutionClient::new(PaperExecutionVenue::new(PaperVenueConfig::default()))
+        .with_paper_venue(Arc::new(venue()))
+        .with_order_manager(om.clone());
+
+    let out = client.place(tracked_req("cli-paper-1")).unwrap();
+    assert_eq!(out.receipt.status, OrderStatus::Filled);
+    assert_walked_fills_applied(&om, "cli-paper-1");
+}
+
+struct NoLiveVenue;
+
+impl ExecutionConnectorAsync for NoLiveVenue {
+    async fn place_order(&self, _req: &OrderRequest) -> SdkExecutionResult<OrderReceipt> {
+        Err(SdkExecutionError::new(
+            SdkExecutionErrorCode::NotSupported,
+            "paper only",
+        ))
+    }
+
+    async fn cancel_order(&self, _cancel: &OrderCancel) -> SdkExecutionResult<bool> {
+        Ok(false)
+    }
+
+    async fn list_open_orders(&self, _q: &OrderOpenQuery) -> SdkExecutionResult<Vec<OrderReceipt>> {
+        Ok(vec![])
+    }
+}
+
+#[tokio::test]
+async fn async_client_applies_paper_fills_before_place_returns() {
+    let om = Arc::new(OrderManager::default());
+    let client = ExecutionClientAsync::new(NoLiveVenue)
+        .with_paper_venue(Arc::new(venue()))
+        .with_order_manager(om.clone());
+
+    let out = client.place(tracked_req("cli-paper-2")).await.unwrap();
+    assert_eq!(out.receipt.status, OrderStatus::Filled);
+    assert_walked_fills_applied(&om, "cli-paper-2");
+}
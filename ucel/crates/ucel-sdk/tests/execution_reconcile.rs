@@ -0,0 +1,358 @@
+/// reconcile: 監査ログ（InMemoryAuditSink）と mock venue の実データを突き合わせ、
+/// 差異が種別と推奨対処付きで返ることを検証する。
+use std::collections::BTreeMap;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+use ucel_core::CanonicalBalance;
+use ucel_sdk::execution::*;
+
+#[derive(Default)]
+struct VenueBook {
+    open: Vec<OrderReceipt>,
+    fills: Option<Vec<ExecutionFill>>,
+    balances: Option<Vec<CanonicalBalance>>,
+}
+
+struct MockVenueConnector {
+    seq: AtomicUsize,
+    book: Mutex<VenueBook>,
+}
+
+impl MockVenueConnector {
+    fn new() -> Self {
+        Self {
+            seq: AtomicUsize::new(0),
+            book: Mutex::new(VenueBook::default()),
+        }
+    }
+}
+
+fn not_supported<T>() -> SdkExecutionResult<T> {
+    Err(SdkExecutionError::new(
+        SdkExecutionErrorCode::NotSupported,
+        "mock",
+    ))
+}
+
+impl ExecutionConnectorAsync for MockVenueConnector {
+    async fn place_order(&self, req: &OrderRequest) -> SdkExecutionResult<OrderReceipt> {
+        let n = self.seq.fetch_add(1, Ordering::SeqCst);
+        Ok(OrderReceipt {
+            venue: req.intent.venue.clone(),
+            symbol: req.intent.symbol.clone(),
+            status: OrderStatus::Accepted,
+            venue_order_id: Some(format!("v-{}", (b'A' + n as u8) as char)),
+            client_order_id: req.intent.tags.get("client_order_id").cloned(),
+            intent_id: req.intent.intent_id.clone(),
+            idempotency: req.idempotency.clone(),
+        })
+    }
+
+    async fn cancel_order(&self, _cancel: &OrderCancel) -> SdkExecutionResult<bool> {
+        Ok(true)
+    }
+
+    async fn list_open_orders(&self, _q: &OrderOpenQuery) -> SdkExecutionResult<Vec<OrderReceipt>> {
+        Ok(self.book.lock().unwrap().open.clone())
+    }
+
+    async fn list_fills(&self, _q: &FillQuery) -> SdkExecutionResult<Vec<ExecutionFill>> {
+        match self.book.lock().unwrap().fills.clone() {
+            Some(f) => Ok(f),
+            None => not_supported(),
+        }
+    }
+
+    async fn list_balances(&self, _venue: &VenueId) -> SdkExecutionResult<Vec<CanonicalBalance>> {
+        match self.book.lock().unwrap().balances.clone() {
+            Some(b) => Ok(b),
+            None => not_supported(),
+        }
+    }
+}
+
+fn venue() -> VenueId {
+    VenueId::new("bittrade")
+}
+
+fn live(cid: &str) -> OrderRequest {
+    let mut tags = BTreeMap::new();
+    tags.insert("client_order_id".to_string(), cid.to_string());
+    OrderRequest {
+        mode: ExecutionMode::Live,
+        intent: OrderIntent {
+            intent_id: OrderIntentId::new(format!("intent-{cid}")),
+            venue: venue(),
+            symbol: Symbol::new("BTCJPY"),
+            side: OrderSide::Buy,
+            order_type: OrderType::Limit,
+            tif: Some(OrderTimeInForce::Gtc),
+            price: Some("100".parse().unwrap()),
+            qty: "0.01".parse().unwrap(),
+            tags,
+        },
+        idempotency: IdempotencyKey::random_uuid(),
+        run_id: Some("run-rec".to_string()),
+    }
+}
+
+fn open(vid: &str) -> OrderReceipt {
+    OrderReceipt {
+        venue: venue(),
+        symbol: Symbol::new("BTCJPY"),
+        status: OrderStatus::Open,
+        venue_order_id: Some(vid.to_string()),
+        client_order_id: None,
+        intent_id: OrderIntentId::new("unknown"),
+        idempotency: IdempotencyKey::random_uuid(),
+    }
+}
+
+fn fill(fill_id: &str, vid: &str, qty: &str) -> ExecutionFill {
+    ExecutionFill {
+        venue: venue(),
+        symbol: Symbol::new("BTCJPY"),
+        venue_order_id: vid.to_string(),
+        client_order_id: None,
+        fill_id: fill_id.to_string(),
+        side: OrderSide::Buy,
+        price: "100".parse().unwrap(),
+        qty: qty.parse().unwrap(),
+        fee: Default::default(),
+        liquidity: FillLiquidity::Maker,
+        unix_ms: 1,
+    }
+}
+
+fn balance(asset: &str, free: &str) -> CanonicalBalance {
+    CanonicalBalance {
+        asset: asset.to_string(),
+        free: free.to_string(),
+        locked: "0".to_string(),
+    }
+}
+
+fn client_with(
+    book: VenueBook,
+    audit: &Arc<InMemoryAuditSink>,
+) -> ExecutionClientAsync<MockVenueConnector> {
+    let connector = MockVenueConnector::new();
+    *connector.book.lock().unwrap() = book;
+    ExecutionClientAsync::new(connector)
+        .with_audit(Box::new(audit.clone()))
+        .with_reconciler(
+            Reconciler::default()
+                .with_expected_balances(venue(), vec![balance("JPY", "1000"), balance("BTC", "0")]),
+        )
+}
+
+#[tokio::test]
+async fn reconcile_classifies_each_mismatch_kind() {
+    let audit = Arc::new(InMemoryAuditSink::new());
+
+    // 発注と取消（監査に残る）
+    let trader = client_with(VenueBook::default(), &audit);
+    for cid in ["cli-a", "cli-b", "cli-c"] {
+        trader.place(live(cid)).await.unwrap();
+    }
+    trader
+        .cancel(OrderCancel {
+            venue: venue(),
+            symbol: Symbol::new("BTCJPY"),
+            venue_order_id: "v-C".to_string(),
+            idempotency: IdempotencyKey::random_uuid(),
+            run_id: Some("run-rec".to_string()),
+        })
+        .await
+        .unwrap();
+
+    // venue の実データ:
+    // v-A は open のまま / v-B は部分約定後に消えた / v-C は取消済みのはずが残っている / v-X は追跡外
+    let client = client_with(
+        VenueBook {
+            open: vec![open("v-A"), open("v-C"), open("v-X")],
+            fills: Some(vec![fill("f-1", "v-B", "0.005")]),
+            balances: Some(vec![balance("JPY", "990"), balance("BTC", "0.005")]),
+        },
+        &audit,
+    );
+
+    let report = client
+        .reconcile_run(&venue(), Some("run-rec"))
+        .await
+        .unwrap();
+    assert!(!report.ok);
+    assert_eq!(report.mismatches.len(), report.details.len());
+    // run 指定時、v-X は他の run / 手動の注文かもしれないので orphan にしない
+    assert_eq!(report.count(ReconcileMismatchKind::OrphanVenueOrder), 0);
+    assert_eq!(report.count(ReconcileMismatchKind::MissingLocalOrder), 1);
+    assert_eq!(report.count(ReconcileMismatchKind::QtyDrift), 1);
+    assert_eq!(report.count(ReconcileMismatchKind::StatusDrift), 1);
+    assert_eq!(report.count(ReconcileMismatchKind::BalanceDrift), 2);
+    assert!(!report
+        .actions
+        .iter()
+        .any(|a| matches!(a, RemediationAction::CancelOrphan { .. })));
+
+    // 全履歴での照合では追跡外として取消を推奨する
+    let all_runs = client.reconcile(&venue()).await.unwrap();
+    assert_eq!(all_runs.count(ReconcileMismatchKind::OrphanVenueOrder), 1);
+    assert!(all_runs.actions.contains(&RemediationAction::CancelOrphan {
+        symbol: Symbol::new("BTCJPY"),
+        venue_order_id: "v-X".to_string(),
+    }));
+    assert!(report.actions.contains(&RemediationAction::RetryCancel {
+        symbol: Symbol::new("BTCJPY"),
+        venue_order_id: "v-C".to_string(),
+    }));
+    assert!(report.actions.contains(&RemediationAction::MarkLost {
+        client_order_id: Some("cli-b".to_string()),
+        venue_order_id: Some("v-B".to_string()),
+    }));
+    assert!(report
+        .actions
+        .contains(&RemediationAction::ApplyVenueFills {
+            venue_order_id: "v-B".to_string(),
+            fill_ids: vec!["f-1".to_string()],
+        }));
+    assert!(report.actions.contains(&RemediationAction::ResyncBalance {
+        asset: "JPY".to_string()
+    }));
+
+    // 照合結果自体も監査に残る
+    let all = audit
+        .replay(AuditReplayFilter {
+            run_id: None,
+            venue: None,
+            intent_id: None,
+            idempotency: None,
+            since_unix_ms: None,
+            until_unix_ms: None,
+        })
+        .unwrap();
+    assert!(matches!(
+        all.last(),
+        Some(AuditEvent::ReconcileResult { .. })
+    ));
+}
+
+#[tokio::test]
+async fn reconcile_skips_unsupported_venue_data_and_completes_fills() {
+    let audit = Arc::new(InMemoryAuditSink::new());
+    let connector = MockVenueConnector::new();
+    let client = ExecutionClientAsync::new(connector).with_audit(Box::new(audit.clone()));
+    client.place(live("cli-a")).await.unwrap();
+
+    // fills / balances 未対応 + open 一覧に存在 → 差異なし
+    let connector = MockVenueConnector::new();
+    connector.book.lock().unwrap().open = vec![open("v-A")];
+    let client = ExecutionClientAsync::new(connector).with_audit(Box::new(audit.clone()));
+    let report = client.reconcile(&venue()).await.unwrap();
+    assert!(report.ok, "{:?}", report.mismatches);
+
+    // venue で全量約定済み → StatusDrift（Filled へ同期）
+    let connector = MockVenueConnector::new();
+    connector.book.lock().unwrap().fills = Some(vec![
+        fill("f-1", "v-A", "0.004"),
+        fill("f-2", "v-A", "0.006"),
+    ]);
+    let client = ExecutionClientAsync::new(connector).with_audit(Box::new(audit));
+    let report = client.reconcile(&venue()).await.unwrap();
+    assert_eq!(report.count(ReconcileMismatchKind::StatusDrift), 1);
+    assert_eq!(report.count(ReconcileMismatchKind::MissingLocalOrder), 0);
+    assert!(report
+        .actions
+        .contains(&RemediationAction::ResyncOrderStatus {
+            venue_order_id: "v-A".to_string(),
+            status: OrderStatus::Filled,
+        }));
+}
+
+#[tokio::test]
+async fn run_scoped_reconcile_ignores_other_runs_orders() {
+    let audit = Arc::new(InMemoryAuditSink::new());
+    let trader = client_with(VenueBook::default(), &audit);
+    trader.place(live("cli-a")).await.unwrap();
+    let mut other = live("cli-b");
+    other.run_id = Some("run-other".to_string());
+    trader.place(other).await.unwrap();
+
+    let connector = MockVenueConnector::new();
+    connector.book.lock().unwrap().open = vec![open("v-A"), open("v-B"), open("v-manual")];
+    let client = ExecutionClientAsync::new(connector).with_audit(Box::new(audit));
+
+    let report = client
+        .reconcile_run(&venue(), Some("run-rec"))
+        .await
+        .unwrap();
+    assert!(report.ok, "{:?}", report.mismatches);
+    assert!(report.actions.is_empty());
+
+    // run-other の注文は全履歴照合でも追跡済み。手動注文だけが orphan
+    let report = client.reconcile(&venue()).await.unwrap();
+    assert_eq!(report.count(ReconcileMismatchKind::OrphanVenueOrder), 1);
+    assert_eq!(
+        report.actions,
+        vec![RemediationAction::CancelOrphan {
+            symbol: Symbol::new("BTCJPY"),
+            venue_order_id: "v-manual".to_string(),
+        }]
+    );
+}
+
+#[tokio::test]
+async fn applying_venue_fills_makes_the_next_reconcile_clean() {
+    let audit = Arc::new(InMemoryAuditSink::new());
+    let orders = Arc::new(OrderManager::default());
+    let mut partial = open("v-A");
+    partial.status = OrderStatus::PartiallyFilled;
+    let client = client_with(
+        VenueBook {
+            open: vec![partial],
+            fills: Some(vec![fill("f-1", "v-A", "0.004")]),
+            balances: None,
+        },
+        &audit,
+    )
+    .with_order_manager(orders.clone());
+    client.place(live("cli-a")).await.unwrap();
+
+    let report = client
+        .reconcile_run(&venue(), Some("run-rec"))
+        .await
+        .unwrap();
+    assert_eq!(report.count(ReconcileMismatchKind::QtyDrift), 1);
+
+    let applied = client.apply_venue_fills(&report).await.unwrap();
+    assert_eq!(
+        applied.iter().map(|f| f.fill_id.as_str()).collect::<Vec<_>>(),
+        ["f-1"]
+    );
+    let order = orders.get("cli-a").unwrap();
+    assert_eq!(order.filled_qty, "0.004".parse().unwrap());
+    assert_eq!(order.status, OrderStatus::PartiallyFilled);
+    // 取り込んだ約定は発注と同じ run の監査に残る
+    let fills = audit
+        .replay(AuditReplayFilter {
+            run_id: Some("run-rec".to_string()),
+            venue: None,
+            intent_id: None,
+            idempotency: None,
+            since_unix_ms: None,
+            until_unix_ms: None,
+        })
+        .unwrap()
+        .into_iter()
+        .filter(|e| matches!(e, AuditEvent::Fill { .. }))
+        .count();
+    assert_eq!(fills, 1);
+
+    let again = client
+        .reconcile_run(&venue(), Some("run-rec"))
+        .await
+        .unwrap();
+    assert!(again.ok, "{:?}", again.mismatches);
+    assert!(again.actions.is_empty());
+    // 同じ report を再適用しても二重には取り込まない
+    assert!(client.apply_venue_fills(&report).await.unwrap().is_empty());
+}
@@ -0,0 +1,199 @@
+use std::str::FromStr;
+use std::sync::Arc;
+use ucel_core::{CanonicalBalance, CanonicalTicker, Decimal};
+use ucel_sdk::execution::*;
+use ucel_symbol_core::{Exchange, MarketMeta, MarketMetaId, MarketType};
+
+/// Shadow では place_order が呼ばれたら失敗する
+struct NeverPlace;
+
+impl ExecutionConnector for NeverPlace {
+    fn place_order(&self, _req: &OrderRequest) -> SdkExecutionResult<OrderReceipt> {
+        Err(SdkExecutionError::new(
+            SdkExecutionErrorCode::Internal,
+            "shadow must not place",
+        ))
+    }
+
+    fn cancel_order(&self, _cancel: &OrderCancel) -> SdkExecutionResult<bool> {
+        Ok(false)
+    }
+
+    fn list_open_orders(&self, _q: &OrderOpenQuery) -> SdkExecutionResult<Vec<OrderReceipt>> {
+        Ok(vec![])
+    }
+}
+
+fn d(s: &str) -> Decimal {
+    Decimal::from_str(s).unwrap()
+}
+
+fn context() -> Arc<InMemoryShadowContext> {
+    let ctx = InMemoryShadowContext::new();
+    let venue = VenueId::new("binance");
+    let symbol = Symbol::new("BTCUSDT");
+
+    let mut meta = MarketMeta::new(
+        MarketMetaId::new(Exchange::Binance, MarketType::Spot, "BTCUSDT"),
+        d("0.1"),
+        d("0.001"),
+    );
+    meta.base = Some("BTC".into());
+    meta.quote = Some("USDT".into());
+    meta.min_notional = Some(d("10"));
+    ctx.set_market_meta(venue.clone(), symbol.clone(), meta);
+
+    ctx.set_ticker(
+        venue.clone(),
+        symbol,
+        CanonicalTicker {
+            symbol: "BTCUSDT".into(),
+            best_bid: Some(d("99.9")),
+            best_ask: Some(d("100.1")),
+            last_price: d("100"),
+            ts_event: None,
+        },
+    );
+    ctx.set_balances(
+        venue,
+        vec![
+            CanonicalBalance {
+                asset: "USDT".into(),
+                free: "50".into(),
+                locked: "0".into(),
+            },
+            CanonicalBalance {
+                asset: "BTC".into(),
+                free: "0.2".into(),
+                locked: "0".into(),
+            },
+        ],
+    );
+    Arc::new(ctx)
+}
+
+fn req(side: OrderSide, price: Option<&str>, qty: &str) -> OrderRequest {
+    OrderRequest {
+        mode: ExecutionMode::Shadow,
+        intent: OrderIntent {
+            intent_id: OrderIntentId::new("intent-shadow"),
+            venue: VenueId::new("binance"),
+            symbol: Symbol::new("BTCUSDT"),
+            side,
+            order_type: if price.is_some() {
+                OrderType::Limit
+            } else {
+                OrderType::Market
+            },
+            tif: None,
+            price: price.map(|p| Price::from_str(p).unwrap()),
+            qty: Quantity::from_str(qty).unwrap(),
+            tags: std::collections::BTreeMap::new(),
+        },
+        idempotency: IdempotencyKey::random_uuid(),
+        run_id: Some("run-shadow".into()),
+    }
+}
+
+fn outcome(v: &ShadowVerdict, kind: ShadowCheckKind) -> ShadowCheckOutcome {
+    v.checks.iter().find(|c| c.kind == kind).unwrap().outcome
+}
+
+#[test]
+fn shadow_accepts_valid_order_without_placing() {
+    let audit = Arc::new(InMemoryAuditSink::new());
+    let client = ExecutionClient::new(NeverPlace)
+        .with_audit(Box::new(audit.clone()))
+        .with_shadow_validator(ShadowValidator::new(ShadowConfig::default(), context()));
+
+    let out = client
+        .place(req(OrderSide::Buy, Some("100"), "0.3"))
+        .unwrap();
+    assert_eq!(out.receipt.status, OrderStatus::Accepted);
+
+    let verdicts = audit
+        .replay(AuditReplayFilter {
+            run_id: Some("run-shadow".into()),
+            venue: None,
+            intent_id: None,
+            idempotency: None,
+            since_unix_ms: None,
+            until_unix_ms: None,
+        })
+        .unwrap()
+        .into_iter()
+        .filter_map(|e| match e {
+            AuditEvent::ShadowVerdict { verdict, .. } => Some(verdict),
+            _ => None,
+        })
+        .collect::<Vec<_>>();
+    assert_eq!(verdicts.len(), 1);
+    assert!(verdicts[0].would_accept);
+    assert_eq!(verdicts[0].notional, Some(d("30")));
+}
+
+#[test]
+fn shadow_reports_each_failed_check() {
+    let v = ShadowValidator::new(ShadowConfig::default(), context());
+
+    // tick 違反
+    let verdict = v
+        .evaluate(&req(OrderSide::Buy, Some("100.05"), "0.3"))
+        .unwrap();
+    assert!(!verdict.would_accept);
+    assert_eq!(
+        outcome(&verdict, ShadowCheckKind::Constraints),
+        ShadowCheckOutcome::Fail
+    );
+
+    // 価格帯逸脱（last 100 から 10%）
+    let verdict = v
+        .evaluate(&req(OrderSide::Sell, Some("110"), "0.1"))
+        .unwrap();
+    assert_eq!(
+        outcome(&verdict, ShadowCheckKind::PriceBand),
+        ShadowCheckOutcome::Fail
+    );
+
+    // quote 残高不足（100 * 1 > 50 USDT）
+    let verdict = v.evaluate(&req(OrderSide::Buy, Some("100"), "1")).unwrap();
+    assert_eq!(
+        outcome(&verdict, ShadowCheckKind::Balance),
+        ShadowCheckOutcome::Fail
+    );
+    assert_eq!(verdict.failed_checks().count(), 1);
+
+    // 成行売りは best_bid を参照、base 残高で判定
+    let verdict = v.evaluate(&req(OrderSide::Sell, None, "0.3")).unwrap();
+    assert_eq!(verdict.reference_price, Some(Price::new(d("99.9"))));
+    assert_eq!(
+        outcome(&verdict, ShadowCheckKind::Balance),
+        ShadowCheckOutcome::Fail
+    );
+}
+
+#[test]
+fn shadow_without_context_skips_or_rejects_in_strict_mode() {
+    let relaxed = ShadowValidator::default();
+    let verdict = relaxed
+        .evaluate(&req(OrderSide::Buy, Some("100"), "0.3"))
+        .unwrap();
+    assert!(verdict.would_accept);
+    assert!(verdict
+        .checks
+        .iter()
+        .all(|c| c.outcome == ShadowCheckOutcome::Skipped));
+
+    let strict = ShadowValidator::new(
+        ShadowConfig {
+            require_all_checks: true,
+            ..ShadowConfig::default()
+        },
+        Arc::new(InMemoryShadowContext::new()),
+    );
+    let client = ExecutionClient::new(NeverPlace).with_shadow_validator(strict);
+    let out = client
+        .place(req(OrderSide::Buy, Some("100"), "0.3"))
+        .unwrap();
+    assert_eq!(out.receipt.status, OrderStatus::Rejected);
+}
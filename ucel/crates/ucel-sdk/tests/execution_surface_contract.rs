@@ -1,5 +1,15 @@
+use std::str::FromStr;
 use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+use ucel_core::Decimal;
 use ucel_sdk::execution::*;
+use ucel_symbol_core::{Exchange, MarketMeta, MarketMetaId, MarketMetaSnapshot, MarketType};
+use ucel_symbol_store::MarketMetaStore;
+
+fn d(s: &str) -> Decimal {
+    Decimal::from_str(s).unwrap()
+}
 
 struct SpyConnector {
     place_calls: AtomicUsize,
@@ -50,8 +60,8 @@ fn base_intent() -> OrderIntent {
         side: OrderSide::Buy,
         order_type: OrderType::Limit,
         tif: Some(OrderTimeInForce::Gtc),
-        price: Some(Price(100.0)),
-        qty: Quantity(0.01),
+        price: Some("100".parse().unwrap()),
+        qty: "0.01".parse().unwrap(),
         tags: {
             let mut m = std::collections::BTreeMap::new();
             m.insert("client_order_id".to_string(), "cli-1".to_string());
@@ -83,6 +93,112 @@ fn gate_rejects_missing_price_for_limit() {
     assert_eq!(e.code, SdkExecutionErrorCode::OrderGateRejected);
 }
 
+#[test]
+fn market_meta_gate_rejects_off_tick_and_below_min_notional() {
+    let meta_id = MarketMetaId::new(Exchange::Bybit, MarketType::Spot, "BTCUSDT");
+    let mut meta = MarketMeta::new(meta_id, d("0.5"), d("0.01"));
+    meta.min_notional = Some(d("5"));
+    let store = Arc::new(MarketMetaStore::new(Duration::from_secs(60)));
+    store.apply_snapshot_full(MarketMetaSnapshot::new_rest(vec![meta]));
+
+    // meta の参照元があれば gate は既定で MarketMetaOrderGate になる
+    let client = ExecutionClient::new(SpyConnector::new()).with_market_meta(store);
+    let live = |intent: OrderIntent| OrderRequest {
+        mode: ExecutionMode::Live,
+        idempotency: IdempotencyKey::random_uuid(),
+        intent,
+        run_id: Some("run-meta".to_string()),
+    };
+
+    // 100 * 0.01 = 1 < min_notional
+    let e = client.place(live(base_intent())).unwrap_err();
+    assert_eq!(e.code, SdkExecutionErrorCode::OrderGateRejected);
+
+    let mut intent = base_intent();
+    intent.price = Some("100.3".parse().unwrap());
+    intent.qty = "0.1".parse().unwrap();
+    let e = client.place(live(intent)).unwrap_err();
+    assert_eq!(e.code, SdkExecutionErrorCode::OrderGateRejected);
+
+    let mut intent = base_intent();
+    intent.qty = "0.1".parse().unwrap();
+    let out = client.place(live(intent)).unwrap();
+    assert_eq!(out.receipt.status, OrderStatus::Accepted);
+
+    // meta の無い銘柄の指値は送らない
+    let mut intent = base_intent();
+    intent.symbol = Symbol::new("ETHUSDT");
+    intent.qty = "0.1".parse().unwrap();
+    let e = client.place(live(intent)).unwrap_err();
+    assert_eq!(e.code, SdkExecutionErrorCode::OrderGateRejected);
+}
+
+#[test]
+fn market_meta_gate_resolves_market_type_per_order() {
+    let perp = MarketMetaId::new(Exchange::Bybit, MarketType::LinearPerpetual, "BTCUSDT");
+    let usdm = MarketMetaId::new(
+        Exchange::BinanceUsdm,
+        MarketType::LinearPerpetual,
+        "BTCUSDT",
+    );
+    let store = Arc::new(MarketMetaStore::new(Duration::from_secs(60)));
+    store.apply_snapshot_full(MarketMetaSnapshot::new_rest(vec![
+        MarketMeta::new(perp, d("0.5"), d("0.01")),
+        MarketMeta::new(usdm, d("0.5"), d("0.01")),
+    ]));
+    let gate = MarketMetaOrderGate::new(store.clone());
+    let live = |intent: OrderIntent| OrderRequest {
+        mode: ExecutionMode::Live,
+        idempotency: IdempotencyKey::random_uuid(),
+        intent,
+        run_id: Some("run-meta".to_string()),
+    };
+
+    // 既定の Spot では見つからない
+    assert!(gate.validate(&live(base_intent())).is_err());
+
+    // intent tag で指定
+    let mut intent = base_intent();
+    intent
+        .tags
+        .insert(MARKET_TYPE_TAG.to_string(), "linear_perpetual".to_string());
+    gate.validate(&live(intent)).unwrap();
+
+    let mut intent = base_intent();
+    intent
+        .tags
+        .insert(MARKET_TYPE_TAG.to_string(), "perp".to_string());
+    assert!(gate.validate(&live(intent)).is_err());
+
+    // venue 別設定
+    let gate = MarketMetaOrderGate::new(store.clone())
+        .with_venue_market_type("bybit", MarketType::LinearPerpetual);
+    gate.validate(&live(base_intent())).unwrap();
+
+    // venue id から自明なもの
+    let gate = MarketMetaOrderGate::new(store);
+    let mut intent = base_intent();
+    intent.venue = VenueId::new("binance-usdm");
+    gate.validate(&live(intent)).unwrap();
+}
+
+#[test]
+fn explicit_gate_is_kept_when_market_meta_is_added() {
+    let store = Arc::new(MarketMetaStore::new(Duration::from_secs(60)));
+    let client = ExecutionClient::new(SpyConnector::new())
+        .with_gate(Box::new(BasicOrderGate))
+        .with_market_meta(store);
+    let out = client
+        .place(OrderRequest {
+            mode: ExecutionMode::Live,
+            idempotency: IdempotencyKey::random_uuid(),
+            intent: base_intent(),
+            run_id: Some("run-meta".to_string()),
+        })
+        .unwrap();
+    assert_eq!(out.receipt.status, OrderStatus::Accepted);
+}
+
 #[test]
 fn paper_and_shadow_do_not_call_connector_place() {
     let spy = SpyConnector::new();
@@ -171,8 +287,8 @@ fn execution_surface_types_compile() {
     let _vid: VenueId = VenueId::new("test");
     let _sym: Symbol = Symbol::new("BTCUSDT");
     let _iid: OrderIntentId = OrderIntentId::new("id-1");
-    let _p: Price = Price(1.0);
-    let _q: Quantity = Quantity(1.0);
+    let _p: Price = Price::new(Decimal::ONE);
+    let _q: Quantity = Quantity::new(Decimal::ONE);
     let _s: OrderSide = OrderSide::Buy;
     let _ot: OrderType = OrderType::Limit;
     let _tif: OrderTimeInForce = OrderTimeInForce::Gtc;
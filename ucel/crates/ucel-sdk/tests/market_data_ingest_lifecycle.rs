@@ -0,0 +1,234 @@
+use std::collections::BTreeMap;
+use std::path::PathBuf;
+use std::sync::Arc;
+use std::time::Duration;
+
+use futures_util::{SinkExt, StreamExt};
+use serde_json::{json, Value};
+use tokio::net::TcpListener;
+use tokio_tungstenite::tungstenite::Message;
+
+use ucel_core::IngestLifecycleState;
+use ucel_sdk::hub::{ExchangeId, Hub};
+use ucel_sdk::ingest::{IngestDriverRegistry, UnsupportedWsIngestDriver, WsAdapterIngestDriver};
+use ucel_sdk::market_data::{IngestSpec, MarketDataFacade};
+use ucel_subscription_planner::{canon_params, CoverageV2, FamilyV2};
+use ucel_transport::ws::adapter::{InboundClass, OutboundMsg, WsVenueAdapter};
+use ucel_transport::ws::connection::WsRunConfig;
+use ucel_ws_rules::load_for_exchange;
+
+struct EchoAdapter {
+    url: String,
+}
+
+#[async_trait::async_trait]
+impl WsVenueAdapter for EchoAdapter {
+    fn exchange_id(&self) -> &str {
+        "gmocoin"
+    }
+
+    fn ws_url(&self) -> String {
+        self.url.clone()
+    }
+
+    async fn fetch_symbols(&self) -> Result<Vec<String>, String> {
+        Ok(vec!["BTC/JPY".into()])
+    }
+
+    fn build_subscribe(
+        &self,
+        op_id: &str,
+        symbol: &str,
+        params: &Value,
+    ) -> Result<Vec<OutboundMsg>, String> {
+        Ok(vec![OutboundMsg {
+            text: json!({"op_id": op_id, "symbol": symbol, "params": canon_params(params)})
+                .to_string(),
+        }])
+    }
+
+    fn build_unsubscribe(
+        &self,
+        _op_id: &str,
+        _symbol: &str,
+        _params: &Value,
+    ) -> Result<Vec<OutboundMsg>, String> {
+        Err("echo: unsubscribe not supported".into())
+    }
+
+    fn classify_inbound(&self, raw: &[u8]) -> InboundClass {
+        let Ok(v) = serde_json::from_slice::<Value>(raw) else {
+            return InboundClass::Unknown;
+        };
+        let text = |k: &str| v.get(k).and_then(Value::as_str).map(str::to_string);
+        InboundClass::Data {
+            op_id: text("op_id"),
+            symbol: text("symbol"),
+            params_canon_hint: text("params"),
+        }
+    }
+}
+
+/// subscribe をそのまま data として返し続ける WS サーバ
+async fn spawn_echo_server() -> String {
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+    tokio::spawn(async move {
+        while let Ok((stream, _)) = listener.accept().await {
+            tokio::spawn(async move {
+                let Ok(ws) = tokio_tungstenite::accept_async(stream).await else {
+                    return;
+                };
+                let (mut w, mut r) = ws.split();
+                let Some(Ok(Message::Text(sub))) = r.next().await else {
+                    return;
+                };
+                for _ in 0..50 {
+                    if w.send(Message::Text(sub.clone())).await.is_err() {
+                        return;
+                    }
+                    tokio::time::sleep(Duration::from_millis(20)).await;
+                }
+                while let Some(Ok(msg)) = r.next().await {
+                    if msg.is_close() {
+                        break;
+                    }
+                }
+            });
+        }
+    });
+    format!("ws://{addr}")
+}
+
+fn spec(tmp: &std::path::Path) -> IngestSpec {
+    let coverage = CoverageV2 {
+        venue: "gmocoin".into(),
+        strict: true,
+        families: vec![FamilyV2 {
+            id: "crypto.public.ws.ticker".into(),
+            requires_symbol: true,
+            topic_template: "ticker:{symbol}".into(),
+            params: BTreeMap::new(),
+            weight: 0,
+        }],
+    };
+    let rules = load_for_exchange(&tmp.join("rules"), "gmocoin");
+    let mut spec = IngestSpec::new(coverage, rules, tmp.join("subs.sqlite"), tmp.join("wal"));
+    spec.run = WsRunConfig {
+        connect_timeout: Duration::from_secs(2),
+        ..WsRunConfig::default()
+    };
+    spec.drain_timeout = Duration::from_secs(15);
+    spec
+}
+
+fn wal_lines(dir: PathBuf) -> usize {
+    std::fs::read_dir(dir)
+        .unwrap()
+        .map(|e| std::fs::read_to_string(e.unwrap().path()).unwrap())
+        .map(|s| s.lines().count())
+        .sum()
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn start_reaches_active_and_drain_completes_with_wal_flushed() {
+    let url = spawn_echo_server().await;
+    let tmp = tempfile::tempdir().unwrap();
+    let facade = MarketDataFacade::new(Hub::default(), ExchangeId::Gmocoin);
+
+    let spec = spec(tmp.path()).with_symbols(vec!["BTC/JPY".into()]);
+    let preview = facade.preview_ingest_plan(&spec).unwrap();
+    assert_eq!(preview["subscriptions"], 1);
+    assert_eq!(preview["report"]["shards"][0]["streams"], 1);
+    assert_eq!(preview["connections"][0]["conn_id"], "gmocoin-conn-1");
+    assert_eq!(preview["connections"][0]["state"], "Planned");
+
+    let handle = facade
+        .start_ingest(Arc::new(EchoAdapter { url }), spec)
+        .await
+        .unwrap();
+    assert_eq!(handle.conn_ids(), vec!["gmocoin-conn-1".to_string()]);
+
+    let mut active = false;
+    for _ in 0..100 {
+        let status = handle.status();
+        if status[0].state == IngestLifecycleState::Active {
+            assert_eq!(status[0].subscriptions.active, 1);
+            active = true;
+            break;
+        }
+        tokio::time::sleep(Duration::from_millis(50)).await;
+    }
+    assert!(
+        active,
+        "connection never became active: {:?}",
+        handle.status()
+    );
+
+    let report = facade.drain_ingest(handle).await.unwrap();
+    assert!(report.timed_out.is_empty());
+    assert_eq!(report.connections[0].state, IngestLifecycleState::Completed);
+    // graceful shutdown で active は pending に戻される
+    assert_eq!(report.connections[0].subscriptions.pending, 1);
+    assert!(wal_lines(tmp.path().join("wal")) > 0);
+}
+
+#[tokio::test]
+async fn start_rejects_memory_store_and_mismatched_adapter() {
+    let tmp = tempfile::tempdir().unwrap();
+    let adapter = Arc::new(EchoAdapter {
+        url: "ws://127.0.0.1:1".into(),
+    });
+
+    let mut in_memory = spec(tmp.path());
+    in_memory.store_path = ":memory:".into();
+    let facade = MarketDataFacade::new(Hub::default(), ExchangeId::Gmocoin);
+    assert!(facade
+        .start_ingest(adapter.clone(), in_memory)
+        .await
+        .is_err());
+
+    let other = MarketDataFacade::new(Hub::default(), ExchangeId::Bitbank);
+    assert!(other.start_ingest(adapter, spec(tmp.path())).await.is_err());
+}
+
+#[tokio::test]
+async fn start_with_registry_dispatches_through_driver() {
+    let tmp = tempfile::tempdir().unwrap();
+    let mut registry = IngestDriverRegistry::new();
+    registry.register("bitbank", || {
+        Ok(UnsupportedWsIngestDriver::new("bitbank", || Ok(vec!["BTC/JPY".into()])).boxed())
+    });
+    registry.register("gmocoin", || {
+        let adapter = Arc::new(EchoAdapter {
+            url: "ws://127.0.0.1:1".into(),
+        });
+        Ok(WsAdapterIngestDriver::new("gmocoin", adapter)
+            .private()
+            .boxed())
+    });
+
+    // WsVenueAdapter を持たない venue は起動前に断る
+    let bitbank = MarketDataFacade::new(Hub::default(), ExchangeId::Bitbank);
+    assert!(bitbank
+        .start_ingest_with(&registry, spec(tmp.path()))
+        .await
+        .is_err());
+
+    // driver が接続を起動せずに失敗した場合も接続ごとの状態に残る
+    let gmocoin = MarketDataFacade::new(Hub::default(), ExchangeId::Gmocoin);
+    let handle = gmocoin
+        .start_ingest_with(&registry, spec(tmp.path()))
+        .await
+        .unwrap();
+    let report = gmocoin.drain_ingest(handle).await.unwrap();
+    assert_eq!(
+        report.connections[0].state,
+        IngestLifecycleState::Deadlettered
+    );
+    assert!(report.connections[0]
+        .last_error
+        .as_deref()
+        .unwrap()
+        .contains("private ws disabled"));
+}
@@ -0,0 +1,279 @@
+//! Cost-aware sharding: estimate per-stream inbound load and bin-pack streams across connections.
+//!
+//! Heaviest streams are placed first, each on the least-loaded connection that still fits the
+//! caps (LPT), so depth-like families spread out instead of piling onto one socket.
+//! The same model sizes streams for live replanning (`diff_plan`).
+
+use crate::{key_weight, stable_key, ConnPlan, ShardCaps, SubscriptionKey};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, BTreeSet};
+
+/// Weight of the previous estimate when folding in a new observation.
+const OBSERVED_DECAY: f64 = 0.5;
+
+/// Per-stream inbound message rate estimates (msgs/sec), keyed by op_id.
+///
+/// Without observations a stream costs `weight / 10` msgs/sec (ticker 1, trades 2, depth 4).
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct LoadModel {
+    observed: BTreeMap<String, f64>,
+}
+
+impl LoadModel {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets the per-stream rate of `op_id` directly.
+    pub fn with_observed(mut self, op_id: &str, msgs_per_sec: f64) -> Self {
+        self.observed
+            .insert(op_id.to_string(), msgs_per_sec.max(0.0));
+        self
+    }
+
+    /// Folds in one connection's measured inbound rate, e.g. the delta of
+    /// `TransportMetrics::inbound_frames` over a sample window, split across its streams by
+    /// their current estimates.
+    pub fn observe_connection(&mut self, keys: &[SubscriptionKey], frames_per_sec: f64) {
+        if keys.is_empty() || !frames_per_sec.is_finite() || frames_per_sec < 0.0 {
+            return;
+        }
+        let total: f64 = keys.iter().map(|k| self.estimate(k)).sum();
+        let mut per_op: BTreeMap<&str, (f64, usize)> = BTreeMap::new();
+        for k in keys {
+            let share = if total > 0.0 {
+                self.estimate(k) / total
+            } else {
+                1.0 / keys.len() as f64
+            };
+            let e = per_op.entry(k.op_id.as_str()).or_default();
+            e.0 += frames_per_sec * share;
+            e.1 += 1;
+        }
+        for (op, (rate, n)) in per_op {
+            let sample = rate / n as f64;
+            let next = match self.observed.get(op) {
+                Some(prev) => prev * OBSERVED_DECAY + sample * (1.0 - OBSERVED_DECAY),
+                None => sample,
+            };
+            self.observed.insert(op.to_string(), next);
+        }
+    }
+
+    /// Estimated inbound msgs/sec of one stream.
+    pub fn estimate(&self, key: &SubscriptionKey) -> f64 {
+        self.observed
+            .get(&key.op_id)
+            .copied()
+            .unwrap_or_else(|| key_weight(key) as f64 / 10.0)
+    }
+}
+
+#[derive(Default)]
+struct Bin {
+    keys: Vec<String>,
+    symbols: BTreeSet<String>,
+    load: f64,
+}
+
+impl Bin {
+    fn fits(&self, symbol: Option<&str>, load: f64, caps: ShardCaps) -> bool {
+        if self.keys.len() >= caps.max_streams || !caps.fits_load(self.keys.len(), self.load, load)
+        {
+            return false;
+        }
+        match symbol {
+            Some(s) if !self.symbols.contains(s) => self.symbols.len() < caps.max_symbols,
+            _ => true,
+        }
+    }
+}
+
+/// Packs `keys` into connections under `caps`, balancing estimated load.
+///
+/// Starts from the fewest connections the caps allow and only opens more when nothing fits.
+/// Deterministic for the same inputs; connection ids are `{exchange_id}-conn-{n}`.
+pub fn pack_by_load(
+    exchange_id: &str,
+    keys: &[SubscriptionKey],
+    caps: ShardCaps,
+    model: &LoadModel,
+) -> Vec<ConnPlan> {
+    let caps = caps.normalized();
+    if keys.is_empty() {
+        return Vec::new();
+    }
+
+    let mut items: Vec<(f64, String, Option<&str>)> = keys
+        .iter()
+        .map(|k| (model.estimate(k), stable_key(k), k.symbol.as_deref()))
+        .collect();
+    items.sort_by(|a, b| b.0.total_cmp(&a.0).then_with(|| a.1.cmp(&b.1)));
+
+    let distinct_symbols = items
+        .iter()
+        .filter_map(|(_, _, s)| *s)
+        .collect::<BTreeSet<_>>()
+        .len();
+    let by_load = match caps.max_msgs_per_sec {
+        Some(cap) if cap > 0 => {
+            let total: f64 = items.iter().map(|(load, _, _)| *load).sum();
+            (total / cap as f64).ceil() as usize
+        }
+        _ => 0,
+    };
+    let min_conns = items
+        .len()
+        .div_ceil(caps.max_streams)
+        .max(distinct_symbols.div_ceil(caps.max_symbols))
+        .max(by_load)
+        .max(1);
+    let mut bins: Vec<Bin> = (0..min_conns).map(|_| Bin::default()).collect();
+
+    for (load, key, symbol) in items {
+        let target = bins
+            .iter()
+            .enumerate()
+            .filter(|(_, b)| b.fits(symbol, load, caps))
+            .min_by(|(ia, a), (ib, b)| a.load.total_cmp(&b.load).then(ia.cmp(ib)))
+            .map(|(i, _)| i);
+        let i = match target {
+            Some(i) => i,
+            None => {
+                bins.push(Bin::default());
+                bins.len() - 1
+            }
+        };
+        let bin = &mut bins[i];
+        bin.keys.push(key);
+        if let Some(s) = symbol {
+            bin.symbols.insert(s.to_string());
+        }
+        bin.load += load;
+    }
+
+    bins.into_iter()
+        .filter(|b| !b.keys.is_empty())
+        .enumerate()
+        .map(|(i, b)| ConnPlan {
+            conn_id: format!("{exchange_id}-conn-{}", i + 1),
+            keys: b.keys,
+            limit: caps.max_streams,
+            est_msgs_per_sec: b.load,
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn key(op: &str, sym: &str, w: u64) -> SubscriptionKey {
+        SubscriptionKey {
+            exchange_id: "x".into(),
+            op_id: op.into(),
+            symbol: Some(sym.into()),
+            params: json!({"_topic": format!("{op}:{sym}"), "_w": w}),
+        }
+    }
+
+    fn ops_per_conn(plans: &[ConnPlan]) -> Vec<Vec<String>> {
+        plans
+            .iter()
+            .map(|cp| {
+                cp.keys
+                    .iter()
+                    .map(|k| k.split('|').nth(1).unwrap().to_string())
+                    .collect()
+            })
+            .collect()
+    }
+
+    #[test]
+    fn depth_streams_spread_across_connections() {
+        let mut keys = Vec::new();
+        for s in ["A", "B"] {
+            keys.push(key("ticker", s, 10));
+            keys.push(key("depth", s, 40));
+        }
+        let caps = ShardCaps {
+            max_streams: 2,
+            max_symbols: 2,
+            max_msgs_per_sec: None,
+        };
+        let plans = pack_by_load("x", &keys, caps, &LoadModel::new());
+        assert_eq!(
+            ops_per_conn(&plans),
+            vec![vec!["depth", "ticker"], vec!["depth", "ticker"]]
+        );
+        assert_eq!(plans[0].est_msgs_per_sec, 5.0);
+        assert_eq!(plans, pack_by_load("x", &keys, caps, &LoadModel::new()));
+    }
+
+    #[test]
+    fn observed_rates_override_weights() {
+        let keys = vec![
+            key("ticker", "A", 10),
+            key("ticker", "B", 10),
+            key("trades", "A", 20),
+            key("trades", "B", 20),
+        ];
+        let caps = ShardCaps {
+            max_streams: 4,
+            max_symbols: 4,
+            max_msgs_per_sec: None,
+        };
+        // a hot ticker feed outweighs trades; 2 streams per conn forces a split
+        let model = LoadModel::new().with_observed("ticker", 50.0);
+        let caps2 = ShardCaps {
+            max_streams: 2,
+            ..caps
+        };
+        let plans = pack_by_load("x", &keys, caps2, &model);
+        assert_eq!(
+            ops_per_conn(&plans),
+            vec![vec!["ticker", "trades"], vec!["ticker", "trades"]]
+        );
+        assert_eq!(plans[0].est_msgs_per_sec, 52.0);
+
+        // one connection when the caps allow it
+        assert_eq!(pack_by_load("x", &keys, caps, &model).len(), 1);
+    }
+
+    #[test]
+    fn load_cap_opens_connections_before_stream_caps_do() {
+        let keys: Vec<_> = ["A", "B", "C"]
+            .into_iter()
+            .map(|s| key("depth", s, 40))
+            .collect();
+        let caps = ShardCaps {
+            max_streams: 10,
+            max_symbols: 10,
+            max_msgs_per_sec: Some(8),
+        };
+        let plans = pack_by_load("x", &keys, caps, &LoadModel::new());
+        assert_eq!(
+            ops_per_conn(&plans),
+            vec![vec!["depth", "depth"], vec!["depth"]]
+        );
+
+        // a single stream over the cap still gets a connection of its own
+        let model = LoadModel::new().with_observed("depth", 100.0);
+        let plans = pack_by_load("x", &keys, caps, &model);
+        assert_eq!(plans.len(), 3);
+        assert!(plans.iter().all(|cp| cp.est_msgs_per_sec == 100.0));
+    }
+
+    #[test]
+    fn observe_connection_apportions_by_estimate() {
+        let keys = vec![key("ticker", "A", 10), key("depth", "A", 40)];
+        let mut model = LoadModel::new();
+        model.observe_connection(&keys, 50.0);
+        assert_eq!(model.estimate(&keys[0]), 10.0);
+        assert_eq!(model.estimate(&keys[1]), 40.0);
+
+        model.observe_connection(&keys, 0.0);
+        assert_eq!(model.estimate(&keys[1]), 20.0);
+    }
+}
@@ -0,0 +1,485 @@
+//! Incremental replanning: diff a desired key set against the live store assignments.
+//!
+//! Retained keys stay on their connection. Added keys go to the least-loaded existing
+//! connection that fits (by `LoadModel` estimate); a connection is only opened when every
+//! shard is full, and only reported closed once it has nothing left assigned.
+
+use crate::{stable_key, LoadModel, SubscriptionKey};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+use ucel_ws_rules::ExchangeWsRules;
+
+/// A connection should finish its initial subscribe drip within this many seconds.
+pub const SUBSCRIBE_WINDOW_SECS: usize = 60;
+
+/// Per-connection caps used for sharding (streams, distinct symbols and estimated load).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ShardCaps {
+    pub max_streams: usize,
+    pub max_symbols: usize,
+    /// Estimated inbound msgs/sec per connection. A stream heavier than this on its own
+    /// still gets a connection to itself.
+    #[serde(default)]
+    pub max_msgs_per_sec: Option<u32>,
+}
+
+impl ShardCaps {
+    /// Same caps `generate_plan_v2` shards by. The stream cap is further bounded by the
+    /// subscribe rate budget (`messages_per_second * SUBSCRIBE_WINDOW_SECS`); the load cap
+    /// is `safety_profile.max_msgs_per_sec_per_conn`.
+    pub fn from_rules(rules: &ExchangeWsRules) -> Self {
+        let mut max_streams = rules.effective_max_streams_per_conn().max(1);
+        if let Some(mps) = rules.rate.as_ref().and_then(|r| r.messages_per_second) {
+            max_streams = max_streams.min((mps as usize).max(1) * SUBSCRIBE_WINDOW_SECS);
+        }
+        let max_symbols = rules
+            .safety_profile
+            .as_ref()
+            .and_then(|p| p.max_symbols_per_conn)
+            .or(rules.max_symbols_per_conn)
+            .unwrap_or(max_streams);
+        let max_msgs_per_sec = rules
+            .safety_profile
+            .as_ref()
+            .and_then(|p| p.max_msgs_per_sec_per_conn);
+        Self {
+            max_streams,
+            max_symbols,
+            max_msgs_per_sec,
+        }
+    }
+
+    /// Clamps the count caps to at least one.
+    pub(crate) fn normalized(self) -> Self {
+        Self {
+            max_streams: self.max_streams.max(1),
+            max_symbols: self.max_symbols.max(1),
+            ..self
+        }
+    }
+
+    /// Whether a connection already carrying `streams` at `load` msgs/sec can take `extra` more.
+    pub(crate) fn fits_load(&self, streams: usize, load: f64, extra: f64) -> bool {
+        match self.max_msgs_per_sec {
+            Some(cap) if streams > 0 => load + extra <= cap as f64,
+            _ => true,
+        }
+    }
+}
+
+/// A subscription as currently assigned in the store.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct LiveAssignment {
+    pub key: String,
+    pub conn_id: String,
+    pub symbol: Option<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct AddedKey {
+    pub key: SubscriptionKey,
+    pub conn_id: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct MovedKey {
+    pub key: String,
+    pub from_conn: String,
+    pub to_conn: String,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct PlanDiff {
+    pub added: Vec<AddedKey>,
+    pub removed: Vec<LiveAssignment>,
+    /// Only produced when a live shard exceeds the caps (e.g. after a rules change or once
+    /// observed load outgrows `max_msgs_per_sec`).
+    pub moved: Vec<MovedKey>,
+    /// New connections that must be started for their added/moved keys.
+    pub opened_conns: Vec<String>,
+    /// Live connections left with no assignment.
+    pub closed_conns: Vec<String>,
+}
+
+impl PlanDiff {
+    pub fn is_empty(&self) -> bool {
+        self.added.is_empty()
+            && self.removed.is_empty()
+            && self.moved.is_empty()
+            && self.opened_conns.is_empty()
+            && self.closed_conns.is_empty()
+    }
+}
+
+#[derive(Default)]
+struct Shard {
+    streams: usize,
+    symbols: BTreeMap<String, usize>,
+    load: f64,
+}
+
+impl Shard {
+    fn fits(&self, symbol: Option<&str>, load: f64, caps: ShardCaps) -> bool {
+        if self.streams >= caps.max_streams || !caps.fits_load(self.streams, self.load, load) {
+            return false;
+        }
+        match symbol {
+            Some(s) if !self.symbols.contains_key(s) => self.symbols.len() < caps.max_symbols,
+            _ => true,
+        }
+    }
+
+    fn insert(&mut self, symbol: Option<&str>, load: f64) {
+        self.streams += 1;
+        self.load += load;
+        if let Some(s) = symbol {
+            *self.symbols.entry(s.to_string()).or_default() += 1;
+        }
+    }
+}
+
+/// `{exchange}-conn-{n}` sorts by `n`; anything else sorts after, by name.
+fn conn_order(conn_id: &str) -> (u64, String) {
+    let idx = conn_id
+        .rsplit('-')
+        .next()
+        .and_then(|s| s.parse::<u64>().ok())
+        .unwrap_or(u64::MAX);
+    (idx, conn_id.to_string())
+}
+
+/// Diffs `desired` (e.g. `generate_plan_v2(..).seed`) against `live`, sizing streams with
+/// `model`.
+///
+/// Live shards keep their lightest keys first; what no longer fits the caps is moved.
+/// Placed keys go heaviest first, each to the least-loaded shard that fits.
+/// `reserved_conns` are connection ids still running or shutting down; new connections
+/// never reuse them. Output is deterministic for the same inputs.
+pub fn diff_plan(
+    exchange_id: &str,
+    desired: &[SubscriptionKey],
+    live: &[LiveAssignment],
+    caps: ShardCaps,
+    model: &LoadModel,
+    reserved_conns: &[String],
+) -> PlanDiff {
+    let caps = caps.normalized();
+    let desired_by_key: BTreeMap<String, &SubscriptionKey> =
+        desired.iter().map(|k| (stable_key(k), k)).collect();
+    let load_of = |key: &str| {
+        desired_by_key
+            .get(key)
+            .map(|k| model.estimate(k))
+            .unwrap_or(0.0)
+    };
+
+    let mut diff = PlanDiff::default();
+
+    // live, grouped per connection in connection order
+    let mut by_conn: BTreeMap<(u64, String), Vec<&LiveAssignment>> = BTreeMap::new();
+    let mut live_keys: BTreeSet<&str> = BTreeSet::new();
+    for a in live {
+        live_keys.insert(a.key.as_str());
+        by_conn.entry(conn_order(&a.conn_id)).or_default().push(a);
+    }
+
+    let mut order: Vec<(u64, String)> = Vec::new();
+    let mut shards: HashMap<String, Shard> = HashMap::new();
+    let mut to_place: Vec<(f64, String, Option<String>, Option<String>)> = Vec::new();
+
+    for (conn_key, mut assigned) in by_conn {
+        let conn_id = conn_key.1.clone();
+        assigned.sort_by(|a, b| {
+            load_of(&a.key)
+                .total_cmp(&load_of(&b.key))
+                .then_with(|| a.key.cmp(&b.key))
+        });
+        let mut shard = Shard::default();
+        for a in assigned {
+            if !desired_by_key.contains_key(&a.key) {
+                diff.removed.push(a.clone());
+                continue;
+            }
+            let load = load_of(&a.key);
+            if shard.fits(a.symbol.as_deref(), load, caps) {
+                shard.insert(a.symbol.as_deref(), load);
+            } else {
+                to_place.push((load, a.key.clone(), a.symbol.clone(), Some(conn_id.clone())));
+            }
+        }
+        shards.insert(conn_id, shard);
+        order.push(conn_key);
+    }
+
+    for (key, k) in &desired_by_key {
+        if !live_keys.contains(key.as_str()) {
+            to_place.push((model.estimate(k), key.clone(), k.symbol.clone(), None));
+        }
+    }
+    to_place.sort_by(|a, b| {
+        b.0.total_cmp(&a.0)
+            .then_with(|| (&a.1, &a.2, &a.3).cmp(&(&b.1, &b.2, &b.3)))
+    });
+
+    let mut next_idx = order
+        .iter()
+        .map(|(idx, _)| *idx)
+        .chain(reserved_conns.iter().map(|c| conn_order(c).0))
+        .filter(|idx| *idx != u64::MAX)
+        .max()
+        .unwrap_or(0)
+        + 1;
+
+    for (load, key, symbol, from) in to_place {
+        let target = order
+            .iter()
+            .map(|(_, c)| (c, &shards[c.as_str()]))
+            .filter(|(_, s)| s.fits(symbol.as_deref(), load, caps))
+            .min_by(|(_, a), (_, b)| a.load.total_cmp(&b.load))
+            .map(|(c, _)| c.clone());
+        let conn_id = match target {
+            Some(c) => c,
+            None => {
+                let c = format!("{exchange_id}-conn-{next_idx}");
+                next_idx += 1;
+                shards.insert(c.clone(), Shard::default());
+                order.push(conn_order(&c));
+                diff.opened_conns.push(c.clone());
+                c
+            }
+        };
+        if let Some(shard) = shards.get_mut(&conn_id) {
+            shard.insert(symbol.as_deref(), load);
+        }
+        match from {
+            Some(from_conn) => diff.moved.push(MovedKey {
+                key,
+                from_conn,
+                to_conn: conn_id,
+            }),
+            None => diff.added.push(AddedKey {
+                key: desired_by_key[&key].clone(),
+                conn_id,
+            }),
+        }
+    }
+
+    diff.closed_conns = order
+        .iter()
+        .map(|(_, c)| c)
+        .filter(|c| shards[c.as_str()].streams == 0)
+        .cloned()
+        .collect();
+    diff.removed.sort_by(|a, b| a.key.cmp(&b.key));
+    diff
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn key(op: &str, sym: &str, w: u64) -> SubscriptionKey {
+        SubscriptionKey {
+            exchange_id: "x".into(),
+            op_id: op.into(),
+            symbol: Some(sym.into()),
+            params: json!({"_topic": format!("{op}:{sym}"), "_w": w}),
+        }
+    }
+
+    fn live(k: &SubscriptionKey, conn: &str) -> LiveAssignment {
+        LiveAssignment {
+            key: stable_key(k),
+            conn_id: conn.into(),
+            symbol: k.symbol.clone(),
+        }
+    }
+
+    const CAPS: ShardCaps = ShardCaps {
+        max_streams: 2,
+        max_symbols: 2,
+        max_msgs_per_sec: None,
+    };
+
+    #[test]
+    fn listing_fills_existing_shard_before_opening_one() {
+        let a = key("trades", "A", 10);
+        let b = key("trades", "B", 10);
+        let c = key("trades", "C", 10);
+        let live_now = vec![live(&a, "x-conn-1")];
+
+        let d = diff_plan(
+            "x",
+            &[a.clone(), b.clone()],
+            &live_now,
+            CAPS,
+            &LoadModel::new(),
+            &[],
+        );
+        assert_eq!(d.added.len(), 1);
+        assert_eq!(d.added[0].conn_id, "x-conn-1");
+        assert!(d.opened_conns.is_empty() && d.closed_conns.is_empty());
+
+        let live_now = vec![live(&a, "x-conn-1"), live(&b, "x-conn-1")];
+        let d = diff_plan(
+            "x",
+            &[a, b, c.clone()],
+            &live_now,
+            CAPS,
+            &LoadModel::new(),
+            &[],
+        );
+        assert_eq!(d.added[0].key, c);
+        assert_eq!(d.added[0].conn_id, "x-conn-2");
+        assert_eq!(d.opened_conns, vec!["x-conn-2".to_string()]);
+    }
+
+    #[test]
+    fn delisting_removes_and_closes_emptied_shard() {
+        let a = key("trades", "A", 10);
+        let b = key("trades", "B", 10);
+        let live_now = vec![live(&a, "x-conn-1"), live(&b, "x-conn-2")];
+
+        let d = diff_plan(
+            "x",
+            std::slice::from_ref(&a),
+            &live_now,
+            CAPS,
+            &LoadModel::new(),
+            &[],
+        );
+        assert_eq!(d.removed, vec![live(&b, "x-conn-2")]);
+        assert!(d.added.is_empty() && d.moved.is_empty());
+        assert_eq!(d.closed_conns, vec!["x-conn-2".to_string()]);
+
+        // no change at all
+        let d = diff_plan(
+            "x",
+            std::slice::from_ref(&a),
+            &live_now[..1],
+            CAPS,
+            &LoadModel::new(),
+            &[],
+        );
+        assert!(d.is_empty());
+    }
+
+    #[test]
+    fn overflowing_shard_moves_heaviest_keys_and_skips_reserved_ids() {
+        let a = key("ticker", "A", 10);
+        let b = key("trades", "A", 20);
+        let c = key("depth", "A", 40);
+        let live_now = vec![
+            live(&a, "x-conn-1"),
+            live(&b, "x-conn-1"),
+            live(&c, "x-conn-1"),
+        ];
+
+        let d = diff_plan(
+            "x",
+            &[a, b, c.clone()],
+            &live_now,
+            CAPS,
+            &LoadModel::new(),
+            &["x-conn-2".to_string()],
+        );
+        assert_eq!(
+            d.moved,
+            vec![MovedKey {
+                key: stable_key(&c),
+                from_conn: "x-conn-1".into(),
+                to_conn: "x-conn-3".into(),
+            }]
+        );
+        assert_eq!(d.opened_conns, vec!["x-conn-3".to_string()]);
+    }
+
+    #[test]
+    fn symbol_cap_is_respected() {
+        let caps = ShardCaps {
+            max_streams: 10,
+            max_symbols: 1,
+            max_msgs_per_sec: None,
+        };
+        let a = key("trades", "A", 10);
+        let a2 = key("depth", "A", 40);
+        let b = key("trades", "B", 10);
+        let live_now = vec![live(&a, "x-conn-1")];
+
+        let d = diff_plan(
+            "x",
+            &[a, a2.clone(), b.clone()],
+            &live_now,
+            caps,
+            &LoadModel::new(),
+            &[],
+        );
+        let placed: Vec<_> = d
+            .added
+            .iter()
+            .map(|x| (x.key.clone(), x.conn_id.as_str()))
+            .collect();
+        assert_eq!(placed, vec![(a2, "x-conn-1"), (b, "x-conn-2")]);
+    }
+
+    #[test]
+    fn listing_goes_to_least_loaded_shard() {
+        let caps = ShardCaps {
+            max_streams: 10,
+            max_symbols: 10,
+            max_msgs_per_sec: None,
+        };
+        let a = key("depth", "A", 40);
+        let b = key("ticker", "B", 10);
+        let c = key("trades", "C", 20);
+        let live_now = vec![live(&a, "x-conn-1"), live(&b, "x-conn-2")];
+
+        let d = diff_plan(
+            "x",
+            &[a, b, c.clone()],
+            &live_now,
+            caps,
+            &LoadModel::new(),
+            &[],
+        );
+        assert_eq!(d.added[0].key, c);
+        assert_eq!(d.added[0].conn_id, "x-conn-2");
+    }
+
+    #[test]
+    fn observed_load_over_cap_moves_streams_off_hot_shard() {
+        let caps = ShardCaps {
+            max_streams: 10,
+            max_symbols: 10,
+            max_msgs_per_sec: Some(100),
+        };
+        let a = key("depth", "A", 40);
+        let b = key("depth", "B", 40);
+        let t = key("ticker", "A", 10);
+        let live_now = vec![
+            live(&a, "x-conn-1"),
+            live(&b, "x-conn-1"),
+            live(&t, "x-conn-2"),
+        ];
+        let desired = [a, b.clone(), t];
+
+        // within the estimate-based budget nothing moves
+        let d = diff_plan("x", &desired, &live_now, caps, &LoadModel::new(), &[]);
+        assert!(d.is_empty());
+
+        // once depth is observed at 80 msgs/sec, conn-1 sheds one depth stream to conn-2
+        let mut model = LoadModel::new();
+        model.observe_connection(&[desired[0].clone(), desired[1].clone()], 160.0);
+        let d = diff_plan("x", &desired, &live_now, caps, &model, &[]);
+        assert_eq!(
+            d.moved,
+            vec![MovedKey {
+                key: stable_key(&b),
+                from_conn: "x-conn-1".into(),
+                to_conn: "x-conn-2".into(),
+            }]
+        );
+        assert!(d.opened_conns.is_empty());
+    }
+}
@@ -1,5 +1,8 @@
+pub mod cost;
+pub mod diff;
 pub mod plan;
 pub mod replan;
+pub mod report;
 use serde::{Deserialize, Serialize};
 use serde_json::json;
 use std::collections::{BTreeMap, BTreeSet, HashMap};
@@ -106,17 +109,31 @@ pub fn stable_key(k: &SubscriptionKey) -> String {
     )
 }
 
-#[derive(Debug, Clone, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq)]
 pub struct ConnPlan {
     pub conn_id: String,
     pub keys: Vec<String>, // stable key
     pub limit: usize,
+    /// Estimated inbound msgs/sec across `keys` (see `cost::LoadModel`).
+    pub est_msgs_per_sec: f64,
 }
 
 #[derive(Debug, Clone, PartialEq)]
 pub struct Plan {
     pub conn_plans: Vec<ConnPlan>,
     pub seed: Vec<SubscriptionKey>,
+    pub report: PlanReport,
+}
+
+fn shard_summaries(conn_plans: &[ConnPlan]) -> Vec<ShardSummary> {
+    conn_plans
+        .iter()
+        .map(|cp| ShardSummary {
+            conn_id: cp.conn_id.clone(),
+            streams: cp.keys.len(),
+            est_msgs_per_sec: cp.est_msgs_per_sec,
+        })
+        .collect()
 }
 
 pub fn generate_plan(
@@ -163,15 +180,29 @@ pub fn generate_plan(
     }
 
     let limit = rules.effective_max_streams_per_conn().max(1);
+    let model = LoadModel::default();
     let mut conn_plans = Vec::new();
     for (i, chunk) in seed.chunks(limit).enumerate() {
         conn_plans.push(ConnPlan {
             conn_id: format!("{exchange_id}-conn-{}", i + 1),
             keys: chunk.iter().map(stable_key).collect(),
             limit,
+            est_msgs_per_sec: chunk.iter().map(|k| model.estimate(k)).sum(),
         });
     }
-    Plan { conn_plans, seed }
+    let report = PlanReport {
+        exchange_id: exchange_id.to_string(),
+        families_total: ws_ops.len(),
+        symbols_total: symbols.len(),
+        streams: seed.len(),
+        shards: shard_summaries(&conn_plans),
+        ..Default::default()
+    };
+    Plan {
+        conn_plans,
+        seed,
+        report,
+    }
 }
 
 // --------------------
@@ -347,6 +378,28 @@ fn render_template(mut tpl: String, vars: &HashMap<String, String>) -> String {
     tpl
 }
 
+/// Why a family could not be expanded.
+enum ExpandError {
+    /// `requires_symbol=false` but the template references `{symbol}`.
+    SymbolNotAllowed,
+    /// Template vars with no (or an empty) value pool.
+    MissingVars(Vec<String>),
+}
+
+impl ExpandError {
+    fn reason(&self) -> String {
+        match self {
+            ExpandError::SymbolNotAllowed => {
+                "requires_symbol=false but topic_template references {symbol}".to_string()
+            }
+            ExpandError::MissingVars(vars) => {
+                let vars: Vec<String> = vars.iter().map(|v| format!("{{{v}}}")).collect();
+                format!("no values for template var {}", vars.join(", "))
+            }
+        }
+    }
+}
+
 /// Expand a family into concrete (symbol_opt, params) where:
 /// params includes:
 /// - expanded family params
@@ -357,15 +410,12 @@ fn expand_family_topics(
     family: &FamilyV2,
     symbols: &[String],
     var_pool: &HashMap<String, Vec<String>>,
-) -> Result<Vec<(Option<String>, serde_json::Value)>, String> {
+) -> Result<Vec<(Option<String>, serde_json::Value)>, ExpandError> {
     let tpl_vars = extract_template_vars(&family.topic_template);
 
     // SSOT safety: requires_symbol=false must NOT reference {symbol}
     if !family.requires_symbol && tpl_vars.contains("symbol") {
-        return Err(format!(
-            "family {}: requires_symbol=false but topic_template references {{symbol}}",
-            family.id
-        ));
+        return Err(ExpandError::SymbolNotAllowed);
     }
 
     let variants = expand_params(&family.params);
@@ -375,25 +425,20 @@ fn expand_family_topics(
         family.weight
     };
 
-    // Which template vars (excluding symbol) must be bound from pool?
+    // Which template vars (excluding symbol and family params) must be bound from pool?
     let mut need_vars: Vec<String> = tpl_vars.iter().cloned().collect();
-    need_vars.retain(|v| v != "symbol");
+    need_vars.retain(|v| v != "symbol" && !family.params.contains_key(v));
 
     let mut pools: Vec<(String, Vec<String>)> = Vec::new();
+    let mut missing: Vec<String> = Vec::new();
     for v in &need_vars {
-        let Some(list) = var_pool.get(v) else {
-            return Err(format!(
-                "family {}: missing var pool for {{{}}}",
-                family.id, v
-            ));
-        };
-        if list.is_empty() {
-            return Err(format!(
-                "family {}: empty var pool for {{{}}}",
-                family.id, v
-            ));
+        match var_pool.get(v) {
+            Some(list) if !list.is_empty() => pools.push((v.clone(), list.clone())),
+            _ => missing.push(v.clone()),
         }
-        pools.push((v.clone(), list.clone()));
+    }
+    if !missing.is_empty() {
+        return Err(ExpandError::MissingVars(missing));
     }
 
     // Cartesian product for pool vars
@@ -492,21 +537,74 @@ fn expand_family_topics(
     Ok(out)
 }
 
+/// Family weight stamped into `params["_w"]` by expansion (50 when absent).
+pub(crate) fn key_weight(k: &SubscriptionKey) -> u32 {
+    k.params.get("_w").and_then(|v| v.as_u64()).unwrap_or(50) as u32
+}
+
 /// v2 planner: symbols × families × params × template-vars, then shard by rules.
 /// This version fixes topic rendering into params["_topic"] for adapter usage.
+///
+/// Non-strict coverage skips families that fail to expand and records them in the report;
+/// strict coverage returns every failure as a `PlanError` instead.
 pub fn generate_plan_v2(
     exchange_id: &str,
     cov: &CoverageV2,
     symbols: &[String],
     rules: &ExchangeWsRules,
-) -> Plan {
-    let max_streams = rules.effective_max_streams_per_conn().max(1);
-    let max_symbols = rules
-        .safety_profile
-        .as_ref()
-        .and_then(|p| p.max_symbols_per_conn)
-        .or(rules.max_symbols_per_conn)
-        .unwrap_or(max_streams);
+) -> Result<Plan, PlanError> {
+    generate_plan_v2_with_load(exchange_id, cov, symbols, rules, &LoadModel::default())
+}
+
+/// `generate_plan_v2` with observed stream rates; connections are bin-packed by estimated load.
+pub fn generate_plan_v2_with_load(
+    exchange_id: &str,
+    cov: &CoverageV2,
+    symbols: &[String],
+    rules: &ExchangeWsRules,
+    model: &LoadModel,
+) -> Result<Plan, PlanError> {
+    let mut report = PlanReport {
+        exchange_id: exchange_id.to_string(),
+        strict: cov.strict,
+        families_total: cov.families.len(),
+        symbols_total: symbols.len(),
+        ..Default::default()
+    };
+
+    // empty and repeated symbols would only produce bogus or duplicate keys
+    let mut seen = BTreeSet::new();
+    let mut kept: Vec<String> = Vec::new();
+    for s in symbols {
+        let reason = if s.trim().is_empty() {
+            "empty"
+        } else if !seen.insert(s.as_str()) {
+            "duplicate"
+        } else {
+            kept.push(s.clone());
+            continue;
+        };
+        report.symbols_filtered.push(FilteredSymbol {
+            symbol: s.clone(),
+            reason: reason.to_string(),
+        });
+    }
+    let symbols = kept.as_slice();
+
+    let derives_pairs = cov.families.iter().any(|f| {
+        let vars = extract_template_vars(&f.topic_template);
+        vars.contains("pair") || vars.contains("assetSymbol")
+    });
+    if derives_pairs {
+        for s in symbols {
+            if split_canonical_symbol(s).is_none() {
+                report.symbols_filtered.push(FilteredSymbol {
+                    symbol: s.clone(),
+                    reason: "not BASE/QUOTE; excluded from {pair} and {assetSymbol}".to_string(),
+                });
+            }
+        }
+    }
 
     let var_pool = build_var_pool(exchange_id, symbols);
 
@@ -516,11 +614,19 @@ pub fn generate_plan_v2(
         let expanded = match expand_family_topics(exchange_id, fam, symbols, &var_pool) {
             Ok(v) => v,
             Err(e) => {
-                if cov.strict {
-                    panic!("coverage_v2 expansion error: {e}");
-                } else {
-                    continue;
+                if let ExpandError::MissingVars(vars) = &e {
+                    for var in vars {
+                        report.missing_template_vars.push(MissingTemplateVar {
+                            family_id: fam.id.clone(),
+                            var: var.clone(),
+                        });
+                    }
                 }
+                report.families_skipped.push(SkippedFamily {
+                    family_id: fam.id.clone(),
+                    reason: e.reason(),
+                });
+                continue;
             }
         };
 
@@ -534,62 +640,33 @@ pub fn generate_plan_v2(
         }
     }
 
-    // sort by weight then stable key for determinism
-    seed.sort_by_key(|k| {
-        let w = k.params.get("_w").and_then(|v| v.as_u64()).unwrap_or(50) as u32;
-        (w, stable_key(k))
-    });
-
-    // shard (streams + unique symbols)
-    let mut conn_plans: Vec<ConnPlan> = Vec::new();
-    let mut cur_keys: Vec<String> = Vec::new();
-    let mut cur_streams: usize = 0;
-    let mut cur_symbols: HashMap<String, ()> = HashMap::new();
-    let mut idx = 1usize;
-
-    for k in &seed {
-        let sk = stable_key(k);
-
-        let mut next_symbols = cur_symbols.len();
-        if let Some(sym) = &k.symbol {
-            if !cur_symbols.contains_key(sym) {
-                next_symbols += 1;
-            }
-        }
-        let next_streams = cur_streams + 1;
-
-        if next_streams > max_streams || next_symbols > max_symbols {
-            conn_plans.push(ConnPlan {
-                conn_id: format!("{exchange_id}-conn-{idx}"),
-                keys: cur_keys,
-                limit: max_streams,
-            });
-            idx += 1;
-            cur_keys = Vec::new();
-            cur_streams = 0;
-            cur_symbols = HashMap::new();
-        }
-
-        cur_streams += 1;
-        if let Some(sym) = &k.symbol {
-            cur_symbols.insert(sym.clone(), ());
-        }
-        cur_keys.push(sk);
-    }
-
-    if !cur_keys.is_empty() {
-        conn_plans.push(ConnPlan {
-            conn_id: format!("{exchange_id}-conn-{idx}"),
-            keys: cur_keys,
-            limit: max_streams,
+    if cov.strict && !report.families_skipped.is_empty() {
+        return Err(PlanError {
+            report: Box::new(report),
         });
     }
 
-    Plan { conn_plans, seed }
+    // sort by weight then stable key for determinism
+    seed.sort_by_key(|k| (key_weight(k), stable_key(k)));
+
+    let conn_plans = pack_by_load(exchange_id, &seed, ShardCaps::from_rules(rules), model);
+    report.streams = seed.len();
+    report.shards = shard_summaries(&conn_plans);
+
+    Ok(Plan {
+        conn_plans,
+        seed,
+        report,
+    })
 }
 
+pub use cost::{pack_by_load, LoadModel};
+pub use diff::{diff_plan, AddedKey, LiveAssignment, MovedKey, PlanDiff, ShardCaps};
 pub use plan::{build_desired_plan, DesiredIngestStream};
 pub use replan::replan_for_resume;
+pub use report::{
+    FilteredSymbol, MissingTemplateVar, PlanError, PlanReport, ShardSummary, SkippedFamily,
+};
 
 #[cfg(test)]
 mod coverage_v1_tests {
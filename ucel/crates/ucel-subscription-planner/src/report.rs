@@ -0,0 +1,161 @@
+//! Structured diagnostics for coverage_v2 plan generation.
+//!
+//! Served on the subscriber's `/healthz` and support bundle, and printed by the offline
+//! coverage check.
+
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+/// A family that produced no subscriptions and why.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SkippedFamily {
+    pub family_id: String,
+    pub reason: String,
+}
+
+/// A `{var}` in a family's `topic_template` with no values for the current symbol list.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct MissingTemplateVar {
+    pub family_id: String,
+    pub var: String,
+}
+
+/// An input symbol dropped (or only partially used) by expansion.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct FilteredSymbol {
+    pub symbol: String,
+    pub reason: String,
+}
+
+/// One planned connection.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ShardSummary {
+    pub conn_id: String,
+    pub streams: usize,
+    pub est_msgs_per_sec: f64,
+}
+
+/// What plan generation did with a coverage file and symbol list.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct PlanReport {
+    pub exchange_id: String,
+    pub strict: bool,
+    pub families_total: usize,
+    pub families_skipped: Vec<SkippedFamily>,
+    pub missing_template_vars: Vec<MissingTemplateVar>,
+    pub symbols_total: usize,
+    pub symbols_filtered: Vec<FilteredSymbol>,
+    pub streams: usize,
+    pub shards: Vec<ShardSummary>,
+}
+
+impl PlanReport {
+    /// Every family expanded.
+    pub fn is_clean(&self) -> bool {
+        self.families_skipped.is_empty() && self.missing_template_vars.is_empty()
+    }
+}
+
+/// Strict coverage that failed to expand. `report` lists every failing family; no shards.
+#[derive(Debug, Clone, PartialEq)]
+pub struct PlanError {
+    pub report: Box<PlanReport>,
+}
+
+impl fmt::Display for PlanError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "coverage_v2 expansion error: exchange={}",
+            self.report.exchange_id
+        )?;
+        for s in &self.report.families_skipped {
+            write!(f, "; family {}: {}", s.family_id, s.reason)?;
+        }
+        Ok(())
+    }
+}
+
+impl std::error::Error for PlanError {}
+
+impl From<PlanError> for String {
+    fn from(e: PlanError) -> Self {
+        e.to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{generate_plan_v2, CoverageV2};
+    use ucel_ws_rules::load_for_exchange;
+
+    fn coverage(strict: bool) -> CoverageV2 {
+        serde_json::from_value(serde_json::json!({
+            "venue": "x",
+            "strict": strict,
+            "families": [
+                {"id": "x.trades", "requires_symbol": true, "topic_template": "{symbol}@trade"},
+                {"id": "x.candles", "requires_symbol": true, "topic_template": "{symbol}@kline_{interval}",
+                 "params": {"interval": ["1m", "5m"]}},
+                {"id": "x.kline", "requires_symbol": false, "topic_template": "{pair}_{contractType}@kline"},
+                {"id": "x.bad", "requires_symbol": false, "topic_template": "{symbol}@bad"}
+            ]
+        }))
+        .unwrap()
+    }
+
+    fn symbols() -> Vec<String> {
+        ["BTC/USDT", "ETHUSDT", "BTC/USDT", ""]
+            .iter()
+            .map(|s| s.to_string())
+            .collect()
+    }
+
+    #[test]
+    fn non_strict_skips_and_reports() {
+        let rules = load_for_exchange(std::path::Path::new("/nonexistent"), "x");
+        let plan = generate_plan_v2("x", &coverage(false), &symbols(), &rules).unwrap();
+        let r = &plan.report;
+
+        assert!(!r.is_clean());
+        assert_eq!((r.families_total, r.symbols_total, r.streams), (4, 4, 6));
+        let skipped: Vec<&str> = r
+            .families_skipped
+            .iter()
+            .map(|s| s.family_id.as_str())
+            .collect();
+        assert_eq!(skipped, vec!["x.kline", "x.bad"]);
+        assert_eq!(r.missing_template_vars.len(), 1);
+        assert_eq!(r.missing_template_vars[0].var, "contractType");
+        let filtered: Vec<(&str, &str)> = r
+            .symbols_filtered
+            .iter()
+            .map(|f| (f.symbol.as_str(), f.reason.as_str()))
+            .collect();
+        assert_eq!(
+            filtered,
+            vec![
+                ("BTC/USDT", "duplicate"),
+                ("", "empty"),
+                (
+                    "ETHUSDT",
+                    "not BASE/QUOTE; excluded from {pair} and {assetSymbol}"
+                ),
+            ]
+        );
+        assert_eq!(r.shards.len(), plan.conn_plans.len());
+        assert_eq!(r.shards[0].streams, 6);
+    }
+
+    #[test]
+    fn strict_returns_every_failure_instead_of_panicking() {
+        let rules = load_for_exchange(std::path::Path::new("/nonexistent"), "x");
+        let err = generate_plan_v2("x", &coverage(true), &symbols(), &rules).unwrap_err();
+        assert!(err.report.strict);
+        assert_eq!(err.report.families_skipped.len(), 2);
+        assert!(err.report.shards.is_empty());
+        let msg = err.to_string();
+        assert!(msg.contains("family x.kline: no values for template var {contractType}"));
+        assert!(msg.contains("family x.bad: requires_symbol=false"));
+    }
+}
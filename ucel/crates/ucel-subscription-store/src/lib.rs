@@ -10,6 +10,29 @@ pub enum SubscriptionState {
     Inflight,
     Active,
     Deadletter,
+    /// Unsubscribe queued or sent on the assigned connection, awaiting ack. A row with
+    /// `move_to_conn` set goes back to pending on that connection once the unsubscribe is done.
+    Unsubscribing,
+    /// Tombstone: no longer subscribed anywhere; purged by `purge_removed_older_than`.
+    Removed,
+}
+
+/// Per-state subscription counts for one assigned connection.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SubscriptionStateCounts {
+    pub pending: usize,
+    pub inflight: usize,
+    pub active: usize,
+    pub deadletter: usize,
+    pub unsubscribing: usize,
+    pub removed: usize,
+}
+
+impl SubscriptionStateCounts {
+    /// Rows still held by the connection; `removed` tombstones are not counted.
+    pub fn total(&self) -> usize {
+        self.pending + self.inflight + self.active + self.deadletter + self.unsubscribing
+    }
 }
 
 #[derive(Debug, Clone)]
@@ -26,6 +49,14 @@ pub struct SubscriptionStore {
     conn: Connection,
 }
 
+/// Ends an unsubscribe: the row becomes a tombstone, or pending on the connection it was
+/// moved to while the unsubscribe was outstanding.
+const FINISH_UNSUBSCRIBE: &str =
+    "state=CASE WHEN move_to_conn IS NULL THEN 'removed' ELSE 'pending' END,
+                     assigned_conn=COALESCE(move_to_conn, assigned_conn),
+                     move_to_conn=NULL,
+                     unsubscribe_sent_at=NULL";
+
 impl SubscriptionStore {
     pub fn open(path: &str) -> Result<Self, String> {
         let conn = if path == ":memory:" {
@@ -55,7 +86,9 @@ impl SubscriptionStore {
               updated_at INTEGER NOT NULL,
               first_active_at INTEGER,
               last_message_at INTEGER,
-              rate_limit_until INTEGER
+              rate_limit_until INTEGER,
+              unsubscribe_sent_at INTEGER,
+              move_to_conn TEXT
             );
             CREATE INDEX IF NOT EXISTS idx_subs_exchange_conn_state
               ON subscriptions(exchange_id, assigned_conn, state);
@@ -70,6 +103,13 @@ impl SubscriptionStore {
             "ALTER TABLE subscriptions ADD COLUMN rate_limit_until INTEGER",
             [],
         );
+        let _ = self.conn.execute(
+            "ALTER TABLE subscriptions ADD COLUMN unsubscribe_sent_at INTEGER",
+            [],
+        );
+        let _ = self
+            .conn
+            .execute("ALTER TABLE subscriptions ADD COLUMN move_to_conn TEXT", []);
 
         Ok(())
     }
@@ -86,6 +126,9 @@ impl SubscriptionStore {
                     symbol=excluded.symbol,
                     params_json=excluded.params_json,
                     assigned_conn=excluded.assigned_conn,
+                    state=CASE WHEN state IN ('unsubscribing','removed') THEN 'pending' ELSE state END,
+                    unsubscribe_sent_at=NULL,
+                    move_to_conn=NULL,
                     updated_at=excluded.updated_at",
                 params![r.key, r.exchange_id, r.op_id, r.symbol, r.params_json, r.assigned_conn, now],
             )
@@ -158,6 +201,7 @@ impl SubscriptionStore {
             .map_err(|e| e.to_string())
     }
 
+    /// No-op for rows being unsubscribed or removed: late data must not revive them.
     pub fn mark_active(&self, key: &str, now: i64) -> Result<(), String> {
         self.conn
             .execute(
@@ -165,7 +209,7 @@ impl SubscriptionStore {
                  SET state='active',
                      first_active_at=COALESCE(first_active_at, ?2),
                      updated_at=?2
-                 WHERE key=?1",
+                 WHERE key=?1 AND state NOT IN ('unsubscribing','removed')",
                 params![key, now],
             )
             .map_err(|e| e.to_string())?;
@@ -182,6 +226,136 @@ impl SubscriptionStore {
         Ok(())
     }
 
+    /// Claims up to `max_n` unsubscribing rows whose unsubscribe has not been sent yet,
+    /// stamping `unsubscribe_sent_at` so the ack timeout can be tracked.
+    pub fn next_unsubscribe_batch(
+        &mut self,
+        exchange_id: &str,
+        conn_id: &str,
+        max_n: usize,
+        now: i64,
+    ) -> Result<Vec<String>, String> {
+        let tx = self.conn.transaction().map_err(|e| e.to_string())?;
+        let mut stmt = tx
+            .prepare(
+                "SELECT key FROM subscriptions
+         WHERE exchange_id=?1
+           AND assigned_conn=?2
+           AND state='unsubscribing'
+           AND unsubscribe_sent_at IS NULL
+           AND (rate_limit_until IS NULL OR rate_limit_until <= ?4)
+         ORDER BY updated_at ASC
+         LIMIT ?3",
+            )
+            .map_err(|e| e.to_string())?;
+
+        let mut rows = stmt
+            .query(rusqlite::params![exchange_id, conn_id, max_n as i64, now])
+            .map_err(|e| e.to_string())?;
+        let mut keys = Vec::new();
+        while let Some(row) = rows.next().map_err(|e| e.to_string())? {
+            keys.push(row.get::<_, String>(0).map_err(|e| e.to_string())?);
+        }
+        drop(rows);
+        drop(stmt);
+
+        for k in &keys {
+            tx.execute(
+                "UPDATE subscriptions SET unsubscribe_sent_at=?2, updated_at=?2
+                 WHERE key=?1 AND state='unsubscribing'",
+                params![k, now],
+            )
+            .map_err(|e| e.to_string())?;
+        }
+        tx.commit().map_err(|e| e.to_string())?;
+        Ok(keys)
+    }
+
+    /// Finishes an unsubscribe (ack, nack or no unsubscribe frame for the venue).
+    pub fn mark_removed(&self, key: &str, reason: Option<&str>, now: i64) -> Result<(), String> {
+        self.conn
+            .execute(
+                &format!(
+                    "UPDATE subscriptions
+                 SET {FINISH_UNSUBSCRIBE}, last_error=COALESCE(?2, last_error), updated_at=?3
+                 WHERE key=?1 AND state='unsubscribing'"
+                ),
+                params![key, reason, now],
+            )
+            .map_err(|e| e.to_string())?;
+        Ok(())
+    }
+
+    /// Puts a sent unsubscribe back in the queue (e.g. rate-limited), after `cooldown_secs`.
+    pub fn requeue_unsubscribe(
+        &self,
+        key: &str,
+        now: i64,
+        cooldown_secs: i64,
+    ) -> Result<(), String> {
+        let until = now.saturating_add(cooldown_secs.max(0));
+        self.conn
+            .execute(
+                "UPDATE subscriptions
+                 SET unsubscribe_sent_at=NULL, rate_limit_until=?2, updated_at=?3
+                 WHERE key=?1 AND state='unsubscribing'",
+                params![key, until, now],
+            )
+            .map_err(|e| e.to_string())?;
+        Ok(())
+    }
+
+    /// Unsubscribes sent more than `timeout_secs` ago without an ack are treated as done.
+    /// Many venues never ack an unsubscribe.
+    pub fn expire_unsubscribing(
+        &self,
+        exchange_id: &str,
+        conn_id: &str,
+        timeout_secs: i64,
+        now: i64,
+    ) -> Result<usize, String> {
+        let cutoff = now.saturating_sub(timeout_secs.max(0));
+        self.conn
+            .execute(
+                &format!(
+                    "UPDATE subscriptions
+                 SET {FINISH_UNSUBSCRIBE}, last_error='unsubscribe ack timeout', updated_at=?4
+                 WHERE exchange_id=?1 AND assigned_conn=?2 AND state='unsubscribing'
+                   AND unsubscribe_sent_at IS NOT NULL AND unsubscribe_sent_at <= ?3"
+                ),
+                params![exchange_id, conn_id, cutoff, now],
+            )
+            .map_err(|e| e.to_string())
+    }
+
+    /// A fresh socket carries no streams, so every pending unsubscribe on it is already done.
+    pub fn settle_unsubscribing(
+        &self,
+        exchange_id: &str,
+        conn_id: &str,
+        now: i64,
+    ) -> Result<usize, String> {
+        self.conn
+            .execute(
+                &format!(
+                    "UPDATE subscriptions
+                 SET {FINISH_UNSUBSCRIBE}, updated_at=?3
+                 WHERE exchange_id=?1 AND assigned_conn=?2 AND state='unsubscribing'"
+                ),
+                params![exchange_id, conn_id, now],
+            )
+            .map_err(|e| e.to_string())
+    }
+
+    pub fn purge_removed_older_than(&mut self, older_than_unix: i64) -> Result<usize, String> {
+        self.conn
+            .execute(
+                "DELETE FROM subscriptions WHERE state='removed' AND updated_at < ?1",
+                rusqlite::params![older_than_unix],
+            )
+            .map_err(|e| e.to_string())
+    }
+
     pub fn requeue_key_to_pending(&self, key: &str, now: i64) -> Result<(), String> {
         self.conn
             .execute(
@@ -380,6 +554,146 @@ WHERE state='deadletter'
             .map_err(|e| e.to_string())
     }
 
+    pub fn state_counts(
+        &self,
+        exchange_id: &str,
+        conn_id: &str,
+    ) -> Result<SubscriptionStateCounts, String> {
+        let mut stmt = self
+            .conn
+            .prepare(
+                "SELECT state, COUNT(*) FROM subscriptions
+                 WHERE exchange_id=?1 AND assigned_conn=?2
+                 GROUP BY state",
+            )
+            .map_err(|e| e.to_string())?;
+        let mut rows = stmt
+            .query(params![exchange_id, conn_id])
+            .map_err(|e| e.to_string())?;
+        let mut out = SubscriptionStateCounts::default();
+        while let Some(row) = rows.next().map_err(|e| e.to_string())? {
+            let state: String = row.get(0).map_err(|e| e.to_string())?;
+            let n = row.get::<_, i64>(1).map_err(|e| e.to_string())? as usize;
+            match state.as_str() {
+                "pending" => out.pending = n,
+                "inflight" => out.inflight = n,
+                "active" => out.active = n,
+                "deadletter" => out.deadletter = n,
+                "unsubscribing" => out.unsubscribing = n,
+                "removed" => out.removed = n,
+                _ => {}
+            }
+        }
+        Ok(out)
+    }
+
+    /// Every row of `exchange_id` with an assigned connection, ordered by key.
+    /// Rows being unsubscribed or removed are not assignments any more and are skipped.
+    pub fn assigned_rows(&self, exchange_id: &str) -> Result<Vec<SubscriptionRow>, String> {
+        let mut stmt = self
+            .conn
+            .prepare(
+                "SELECT key, exchange_id, op_id, symbol, params_json, assigned_conn
+                 FROM subscriptions
+                 WHERE exchange_id=?1 AND assigned_conn IS NOT NULL
+                   AND state NOT IN ('unsubscribing','removed')
+                 ORDER BY key ASC",
+            )
+            .map_err(|e| e.to_string())?;
+        let rows = stmt
+            .query_map(params![exchange_id], |r| {
+                Ok(SubscriptionRow {
+                    key: r.get(0)?,
+                    exchange_id: r.get(1)?,
+                    op_id: r.get(2)?,
+                    symbol: r.get(3)?,
+                    params_json: r.get(4)?,
+                    assigned_conn: r.get(5)?,
+                })
+            })
+            .map_err(|e| e.to_string())?;
+        rows.collect::<Result<Vec<_>, _>>()
+            .map_err(|e| e.to_string())
+    }
+
+    /// Applies a replan in one transaction so a connection never observes a half-applied shard.
+    ///
+    /// `added` rows start pending; `removed` keys that may be live on the socket (active or
+    /// inflight) become unsubscribing, the rest are removed outright; `moved` is
+    /// `(key, new_conn)` and goes back to pending on the new connection (deadletter rows keep
+    /// their state).
+    ///
+    /// A key that is live on another connection, or still being unsubscribed there, is never
+    /// taken over directly: it is (or stays) unsubscribing on the old connection with
+    /// `move_to_conn` set, and only turns pending on the new one when that unsubscribe ends.
+    /// Removing such a key drops the pending move but keeps the unsubscribe.
+    pub fn apply_reassignment(
+        &mut self,
+        added: &[SubscriptionRow],
+        removed: &[String],
+        moved: &[(String, String)],
+        now: i64,
+    ) -> Result<(), String> {
+        let tx = self.conn.transaction().map_err(|e| e.to_string())?;
+        for r in added {
+            tx.execute(
+                "INSERT INTO subscriptions(key,exchange_id,op_id,symbol,params_json,state,assigned_conn,updated_at)
+                 VALUES(?1,?2,?3,?4,?5,'pending',?6,?7)
+                 ON CONFLICT(key) DO UPDATE SET
+                    move_to_conn=CASE WHEN state='unsubscribing'
+                                        OR (state IN ('active','inflight') AND assigned_conn IS NOT excluded.assigned_conn)
+                                      THEN excluded.assigned_conn ELSE NULL END,
+                    assigned_conn=CASE WHEN state='unsubscribing'
+                                         OR (state IN ('active','inflight') AND assigned_conn IS NOT excluded.assigned_conn)
+                                       THEN assigned_conn ELSE excluded.assigned_conn END,
+                    unsubscribe_sent_at=CASE WHEN state='unsubscribing' THEN unsubscribe_sent_at ELSE NULL END,
+                    state=CASE WHEN state='unsubscribing' THEN state
+                               WHEN state IN ('active','inflight') AND assigned_conn IS NOT excluded.assigned_conn THEN 'unsubscribing'
+                               ELSE 'pending' END,
+                    updated_at=excluded.updated_at",
+                params![r.key, r.exchange_id, r.op_id, r.symbol, r.params_json, r.assigned_conn, now],
+            )
+            .map_err(|e| e.to_string())?;
+        }
+        for k in removed {
+            tx.execute(
+                "UPDATE subscriptions
+                 SET state=CASE WHEN state IN ('active','inflight') THEN 'unsubscribing' ELSE 'removed' END,
+                     unsubscribe_sent_at=NULL,
+                     updated_at=?2
+                 WHERE key=?1 AND state NOT IN ('unsubscribing','removed')",
+                params![k, now],
+            )
+            .map_err(|e| e.to_string())?;
+            tx.execute(
+                "UPDATE subscriptions SET move_to_conn=NULL, updated_at=?2
+                 WHERE key=?1 AND state='unsubscribing' AND move_to_conn IS NOT NULL",
+                params![k, now],
+            )
+            .map_err(|e| e.to_string())?;
+        }
+        for (k, conn_id) in moved {
+            tx.execute(
+                "UPDATE subscriptions
+                 SET move_to_conn=CASE WHEN state='unsubscribing'
+                                         OR (state IN ('active','inflight') AND assigned_conn IS NOT ?2)
+                                       THEN ?2 ELSE NULL END,
+                     assigned_conn=CASE WHEN state='unsubscribing'
+                                          OR (state IN ('active','inflight') AND assigned_conn IS NOT ?2)
+                                        THEN assigned_conn ELSE ?2 END,
+                     unsubscribe_sent_at=CASE WHEN state='unsubscribing' THEN unsubscribe_sent_at ELSE NULL END,
+                     state=CASE WHEN state IN ('deadletter','unsubscribing') THEN state
+                                WHEN state IN ('active','inflight') AND assigned_conn IS NOT ?2 THEN 'unsubscribing'
+                                ELSE 'pending' END,
+                     updated_at=?3
+                 WHERE key=?1",
+                params![k, conn_id, now],
+            )
+            .map_err(|e| e.to_string())?;
+        }
+        tx.commit().map_err(|e| e.to_string())
+    }
+
     pub fn rate_limit_until_of(&self, key: &str) -> Result<Option<i64>, String> {
         self.conn
             .query_row(
@@ -424,5 +738,136 @@ mod tests {
             .unwrap()
             .unwrap();
         assert_eq!(k, "x|op|BTC/USDT|{}");
+
+        let counts = store.state_counts("x", "c1").unwrap();
+        assert_eq!(counts.inflight, 1);
+        store.mark_active(&k, 3).unwrap();
+        let counts = store.state_counts("x", "c1").unwrap();
+        assert_eq!((counts.inflight, counts.active, counts.total()), (0, 1, 1));
+    }
+
+    #[test]
+    fn reassignment_adds_removes_and_moves_atomically() {
+        let row = |key: &str, conn: &str| SubscriptionRow {
+            key: key.into(),
+            exchange_id: "x".into(),
+            op_id: "op".into(),
+            symbol: Some(key.into()),
+            params_json: "{}".into(),
+            assigned_conn: Some(conn.into()),
+        };
+        let mut store = SubscriptionStore::open(":memory:").unwrap();
+        store.seed(&[row("a", "c1"), row("b", "c1")], 1).unwrap();
+        store.next_pending_batch("x", "c1", 10, 2).unwrap();
+        store.mark_active("a", 3).unwrap();
+
+        store
+            .apply_reassignment(
+                &[row("c", "c2")],
+                &["b".into()],
+                &[("a".into(), "c2".into())],
+                4,
+            )
+            .unwrap();
+
+        let assigned = |store: &SubscriptionStore| -> Vec<(String, String)> {
+            store
+                .assigned_rows("x")
+                .unwrap()
+                .into_iter()
+                .map(|r| (r.key, r.assigned_conn.unwrap()))
+                .collect()
+        };
+        let pair = |k: &str, c: &str| (k.to_string(), c.to_string());
+        // a is live on c1, so it is unsubscribed there before c2 may subscribe it
+        assert_eq!(assigned(&store), vec![pair("c", "c2")]);
+        assert_eq!(
+            store.state_of("a").unwrap().as_deref(),
+            Some("unsubscribing")
+        );
+        // b was inflight, so it may be live on the socket and needs an unsubscribe
+        assert_eq!(
+            store.state_of("b").unwrap().as_deref(),
+            Some("unsubscribing")
+        );
+        assert_eq!(store.state_counts("x", "c1").unwrap().unsubscribing, 2);
+        assert_eq!(store.state_counts("x", "c2").unwrap().pending, 1);
+
+        // re-adding b elsewhere keeps its pending unsubscribe on c1
+        store
+            .apply_reassignment(&[row("b", "c3")], &[], &[], 5)
+            .unwrap();
+        assert_eq!(store.state_counts("x", "c1").unwrap().unsubscribing, 2);
+
+        let mut sent = store.next_unsubscribe_batch("x", "c1", 10, 6).unwrap();
+        sent.sort();
+        assert_eq!(sent, vec!["a", "b"]);
+        store.mark_removed("a", None, 7).unwrap();
+        assert_eq!(assigned(&store), vec![pair("a", "c2"), pair("c", "c2")]);
+        assert_eq!(store.state_counts("x", "c2").unwrap().pending, 2);
+
+        // a fresh socket on c1 settles b, which then moves on to c3
+        assert_eq!(store.settle_unsubscribing("x", "c1", 8).unwrap(), 1);
+        assert_eq!(store.state_counts("x", "c3").unwrap().pending, 1);
+        assert_eq!(store.state_counts("x", "c1").unwrap().total(), 0);
+    }
+
+    #[test]
+    fn unsubscribe_lifecycle_ack_timeout_and_settle() {
+        let row = |key: &str| SubscriptionRow {
+            key: key.into(),
+            exchange_id: "x".into(),
+            op_id: "op".into(),
+            symbol: Some(key.into()),
+            params_json: "{}".into(),
+            assigned_conn: Some("c1".into()),
+        };
+        let mut store = SubscriptionStore::open(":memory:").unwrap();
+        store
+            .seed(&[row("a"), row("b"), row("c"), row("d")], 1)
+            .unwrap();
+        store.next_pending_batch("x", "c1", 3, 2).unwrap();
+        for k in ["a", "b", "c"] {
+            store.mark_active(k, 3).unwrap();
+        }
+        let keys: Vec<String> = ["a", "b", "c", "d"].iter().map(|k| k.to_string()).collect();
+        store.apply_reassignment(&[], &keys, &[], 4).unwrap();
+
+        // never sent -> straight to removed; live ones wait for an unsubscribe
+        assert_eq!(store.state_of("d").unwrap().as_deref(), Some("removed"));
+        let c = store.state_counts("x", "c1").unwrap();
+        assert_eq!((c.unsubscribing, c.removed, c.total()), (3, 1, 3));
+        assert!(store.assigned_rows("x").unwrap().is_empty());
+
+        assert_eq!(
+            store.next_unsubscribe_batch("x", "c1", 2, 5).unwrap(),
+            vec!["a", "b"]
+        );
+        // late data does not revive a row being unsubscribed
+        store.mark_active("a", 6).unwrap();
+        assert_eq!(
+            store.state_of("a").unwrap().as_deref(),
+            Some("unsubscribing")
+        );
+
+        store.mark_removed("a", None, 6).unwrap();
+        store.requeue_unsubscribe("b", 6, 10).unwrap();
+        assert_eq!(
+            store.next_unsubscribe_batch("x", "c1", 10, 7).unwrap(),
+            vec!["c"]
+        );
+        assert_eq!(store.expire_unsubscribing("x", "c1", 10, 16).unwrap(), 0);
+        assert_eq!(store.expire_unsubscribing("x", "c1", 10, 17).unwrap(), 1);
+        assert_eq!(store.state_of("c").unwrap().as_deref(), Some("removed"));
+
+        // reconnect: b (cooling down, unsent) has nothing to unsubscribe on a fresh socket
+        assert_eq!(store.settle_unsubscribing("x", "c1", 18).unwrap(), 1);
+        let c = store.state_counts("x", "c1").unwrap();
+        assert_eq!((c.total(), c.removed), (0, 4));
+
+        // relisting revives the tombstone
+        store.seed(&[row("a")], 19).unwrap();
+        assert_eq!(store.state_of("a").unwrap().as_deref(), Some("pending"));
+        assert_eq!(store.purge_removed_older_than(100).unwrap(), 3);
     }
 }
@@ -21,6 +21,7 @@ pub enum Exchange {
     Bitbank,
     Bitflyer,
     Bitget,
+    Bithumb,
     Bitmex,
     Bittrade,
     Bybit,
@@ -70,7 +70,7 @@ pub fn collect_workspace_domestic_venues(root: &Path) -> Result<BTreeSet<String>
 }
 
 pub fn collect_route_reachability() -> Result<BTreeSet<String>, Box<dyn std::error::Error>> {
-    Ok(collect_repo_public_evidence()?)
+    collect_repo_public_evidence()
 }
 
 pub fn collect_docs_matrix_counts(root: &Path) -> Result<BTreeMap<String, usize>, Box<dyn std::error::Error>> {
@@ -14,6 +14,23 @@ pub struct ZipSpec {
     pub entries: Vec<ZipSpecEntry>,
 }
 
+/// One indirect object; object numbers are assigned in order starting at 1.
+#[derive(Debug, Deserialize)]
+pub struct PdfSpecObject {
+    /// Dictionary body without the surrounding `<< >>`.
+    pub dict: String,
+    #[serde(default)]
+    pub stream: Option<String>,
+    /// Compress the stream and add `/Filter /FlateDecode`.
+    #[serde(default)]
+    pub flate: bool,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct PdfSpec {
+    pub objects: Vec<PdfSpecObject>,
+}
+
 pub fn load_text_fixture(path: &str) -> String {
     fs::read_to_string(path).expect("fixture must be readable")
 }
@@ -22,6 +39,40 @@ pub fn build_pdf_bytes_from_text_fixture(path: &str) -> Vec<u8> {
     load_text_fixture(path).into_bytes()
 }
 
+/// Builds a PDF (object 1 is the catalog) with a valid xref table from a JSON spec.
+pub fn build_pdf_bytes_from_spec(path: &str) -> Vec<u8> {
+    let spec: PdfSpec = serde_json::from_str(&load_text_fixture(path)).expect("valid pdf spec json");
+    let mut out = b"%PDF-1.7\n%\xE2\xE3\xCF\xD3\n".to_vec();
+    let mut offsets = Vec::with_capacity(spec.objects.len());
+    for (i, obj) in spec.objects.iter().enumerate() {
+        offsets.push(out.len());
+        writeln!(out, "{} 0 obj", i + 1).unwrap();
+        match &obj.stream {
+            Some(stream) => {
+                let data = if obj.flate {
+                    let mut z = flate2::write::ZlibEncoder::new(Vec::new(), flate2::Compression::default());
+                    z.write_all(stream.as_bytes()).unwrap();
+                    z.finish().unwrap()
+                } else {
+                    stream.as_bytes().to_vec()
+                };
+                let filter = if obj.flate { " /Filter /FlateDecode" } else { "" };
+                write!(out, "<< {} /Length {}{} >>\nstream\n", obj.dict, data.len(), filter).unwrap();
+                out.extend_from_slice(&data);
+                out.extend_from_slice(b"\nendstream\nendobj\n");
+            }
+            None => write!(out, "<< {} >>\nendobj\n", obj.dict).unwrap(),
+        }
+    }
+    let xref = out.len();
+    write!(out, "xref\n0 {}\n0000000000 65535 f \n", offsets.len() + 1).unwrap();
+    for off in offsets {
+        writeln!(out, "{off:010} 00000 n ").unwrap();
+    }
+    write!(out, "trailer\n<< /Root 1 0 R /Size {} >>\nstartxref\n{xref}\n%%EOF\n", spec.objects.len() + 1).unwrap();
+    out
+}
+
 pub fn build_zip_bytes_from_spec(path: &str) -> Vec<u8> {
     let spec: ZipSpec = serde_json::from_str(&load_text_fixture(path)).expect("valid zip spec json");
     let mut out = Vec::<u8>::new();
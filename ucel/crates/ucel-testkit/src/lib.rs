@@ -21,6 +21,7 @@ pub mod ir_canonical;
 pub mod ir_inventory;
 pub mod ir_jp_official;
 pub mod ir_normalize;
+pub mod ir_us_official;
 pub mod market_data;
 pub mod normalize;
 pub mod okx;
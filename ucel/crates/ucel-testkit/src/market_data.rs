@@ -10,7 +10,7 @@ pub fn public_adapter_support_matrix() -> BTreeMap<&'static str, PublicAdapterSu
         ("binance-usdm", PublicAdapterSupport::Supported),
         ("binance-coinm", PublicAdapterSupport::Supported),
         ("binance-options", PublicAdapterSupport::Partial),
-        ("bithumb", PublicAdapterSupport::Partial),
+        ("bithumb", PublicAdapterSupport::Supported),
         ("bitmex", PublicAdapterSupport::Supported),
         ("deribit", PublicAdapterSupport::Supported),
         ("coinbase", PublicAdapterSupport::Supported),
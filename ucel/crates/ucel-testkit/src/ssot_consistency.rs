@@ -57,7 +57,6 @@ const FAMILY_SPLIT_BRIDGES: &[&str] = &["binance", "bybit", "okx", "bitget", "gm
 const EXPLICIT_EXCEPTIONS: &[(&str, &str)] = &[
     ("MissingCoverageV2Entry", "bitbank"),
     ("MissingCoverageV2Entry", "bitflyer"),
-    ("MissingCoverageV2Entry", "bithumb"),
     ("MissingCoverageV2Entry", "bitmex"),
     ("MissingCoverageV2Entry", "coinbase"),
     ("MissingCoverageV2Entry", "coincheck"),
@@ -0,0 +1,478 @@
+use futures_util::{SinkExt, StreamExt};
+use std::time::Duration;
+use tokio::net::TcpListener;
+use tokio_tungstenite::tungstenite::Message;
+use ucel_chain_ethereum::balance::get_chain_id;
+use ucel_chain_ethereum::provider::{EvmHttpProvider, EvmWsProvider};
+use ucel_chain_ethereum::{
+    parse_log_event, rpc_error_reason, subscribe_logs, subscribe_new_heads, EvmProviderInfo,
+    EvmProviderPolicy, EvmProviderSet, EvmReasonCode, EvmWsEvent, JsonRpcError, JsonRpcHttpConfig,
+    JsonRpcHttpProvider, JsonRpcWsConfig, JsonRpcWsProvider,
+};
+use ucel_core::{EvmAddress, EvmChainId};
+use wiremock::matchers::{body_partial_json, method};
+use wiremock::{Mock, MockServer, ResponseTemplate};
+
+fn info(id: &str) -> EvmProviderInfo {
+    EvmProviderInfo {
+        id: id.into(),
+        chain_id: EvmChainId(1),
+        priority: 0,
+    }
+}
+
+fn http_config(endpoint: String) -> JsonRpcHttpConfig {
+    let mut cfg = JsonRpcHttpConfig::new(endpoint);
+    cfg.reliable.retry.base_delay_ms = 1;
+    cfg.reliable.retry.jitter_ms = 0;
+    cfg.reliable.max_attempts = 3;
+    cfg.call_timeout = Duration::from_secs(5);
+    cfg
+}
+
+fn rpc_error(code: i64, message: &str) -> JsonRpcError {
+    JsonRpcError {
+        code,
+        message: message.into(),
+        data: None,
+    }
+}
+
+#[test]
+fn json_rpc_errors_map_to_reason_codes() {
+    let cases = [
+        (-32000, "nonce too low", EvmReasonCode::NonceTooLow),
+        (-32000, "nonce too high", EvmReasonCode::NonceTooHigh),
+        (
+            -32000,
+            "replacement transaction underpriced",
+            EvmReasonCode::ReplacementUnderpriced,
+        ),
+        (
+            -32000,
+            "insufficient funds for gas * price + value",
+            EvmReasonCode::InsufficientFunds,
+        ),
+        (
+            3,
+            "execution reverted: ERC20: transfer amount exceeds balance",
+            EvmReasonCode::ExecutionReverted,
+        ),
+        (
+            -32601,
+            "the method eth_foo does not exist",
+            EvmReasonCode::UnsupportedRpcMethod,
+        ),
+        (-32005, "limit exceeded", EvmReasonCode::ProviderTimeout),
+        (-32602, "invalid argument 0", EvmReasonCode::InvalidRequest),
+        (-32700, "parse error", EvmReasonCode::InvalidRequest),
+        (-32600, "invalid request", EvmReasonCode::InvalidRequest),
+        // revert data の無い -32000 は文言が execution reverted でもノード側の失敗扱い
+        (-32000, "execution reverted", EvmReasonCode::ProviderTimeout),
+        (-32603, "internal error", EvmReasonCode::ProviderTimeout),
+        (42, "something else", EvmReasonCode::ProviderTimeout),
+    ];
+    for (code, message, expected) in cases {
+        assert_eq!(
+            rpc_error_reason(&rpc_error(code, message)),
+            expected,
+            "{message}"
+        );
+    }
+
+    let mut reverted = rpc_error(-32000, "execution reverted");
+    reverted.data = Some(serde_json::json!("0x08c379a0"));
+    assert_eq!(
+        rpc_error_reason(&reverted),
+        EvmReasonCode::ExecutionReverted
+    );
+    reverted.data = Some(serde_json::json!({"data": "0x08c379a0"}));
+    assert_eq!(
+        rpc_error_reason(&reverted),
+        EvmReasonCode::ExecutionReverted
+    );
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn http_provider_calls_and_fails_over_through_provider_set() {
+    let server = MockServer::start().await;
+    Mock::given(method("POST"))
+        .and(body_partial_json(
+            serde_json::json!({"method": "eth_chainId"}),
+        ))
+        .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+            "jsonrpc": "2.0", "id": 1, "result": "0x1"
+        })))
+        .mount(&server)
+        .await;
+
+    let uri = server.uri();
+    tokio::task::spawn_blocking(move || {
+        // primary は繋がらない endpoint、fallback がモックサーバ
+        let mut dead = http_config("http://127.0.0.1:9".into());
+        dead.reliable.max_attempts = 1;
+        let set = EvmProviderSet {
+            primary_http: Box::new(JsonRpcHttpProvider::new(info("dead"), dead).unwrap()),
+            fallback_http: vec![Box::new(
+                JsonRpcHttpProvider::new(info("mock"), http_config(uri)).unwrap(),
+            )],
+            primary_ws: None,
+            fallback_ws: vec![],
+            policy: EvmProviderPolicy::default(),
+        };
+        assert_eq!(get_chain_id(&set, EvmChainId(1)).unwrap(), EvmChainId(1));
+    })
+    .await
+    .unwrap();
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn http_provider_runs_concurrent_calls_in_parallel() {
+    let server = MockServer::start().await;
+    Mock::given(method("POST"))
+        .respond_with(
+            ResponseTemplate::new(200)
+                .set_body_json(serde_json::json!({
+                    "jsonrpc": "2.0", "id": 1, "result": "0x1"
+                }))
+                .set_delay(Duration::from_millis(400)),
+        )
+        .mount(&server)
+        .await;
+
+    let uri = server.uri();
+    tokio::task::spawn_blocking(move || {
+        let p =
+            std::sync::Arc::new(JsonRpcHttpProvider::new(info("mock"), http_config(uri)).unwrap());
+        let started = std::time::Instant::now();
+        let handles: Vec<_> = (0..4)
+            .map(|_| {
+                let p = p.clone();
+                std::thread::spawn(move || p.rpc_call("eth_chainId", serde_json::json!([])))
+            })
+            .collect();
+        for h in handles {
+            assert_eq!(h.join().unwrap().unwrap().value, serde_json::json!("0x1"));
+        }
+        // 直列なら 1.6s 以上かかる
+        assert!(started.elapsed() < Duration::from_millis(1200));
+    })
+    .await
+    .unwrap();
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn http_provider_retries_5xx_and_maps_rpc_errors() {
+    let server = MockServer::start().await;
+    Mock::given(method("POST"))
+        .and(body_partial_json(
+            serde_json::json!({"method": "eth_blockNumber"}),
+        ))
+        .respond_with(ResponseTemplate::new(503))
+        .up_to_n_times(1)
+        .with_priority(1)
+        .mount(&server)
+        .await;
+    Mock::given(method("POST"))
+        .and(body_partial_json(
+            serde_json::json!({"method": "eth_blockNumber"}),
+        ))
+        .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+            "jsonrpc": "2.0", "id": 1, "result": "0x10"
+        })))
+        .mount(&server)
+        .await;
+    Mock::given(method("POST"))
+        .and(body_partial_json(
+            serde_json::json!({"method": "eth_sendRawTransaction"}),
+        ))
+        .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+            "jsonrpc": "2.0", "id": 2,
+            "error": {"code": -32000, "message": "nonce too low: next nonce 5, tx nonce 4"}
+        })))
+        .mount(&server)
+        .await;
+
+    let uri = server.uri();
+    tokio::task::spawn_blocking(move || {
+        let p = JsonRpcHttpProvider::new(info("mock"), http_config(uri)).unwrap();
+        let r = p
+            .rpc_call("eth_blockNumber", serde_json::json!([]))
+            .unwrap();
+        assert_eq!(r.value, serde_json::json!("0x10"));
+        assert_eq!(r.provider, "mock");
+
+        let e = p
+            .rpc_call("eth_sendRawTransaction", serde_json::json!(["0x02"]))
+            .unwrap_err();
+        assert_eq!(e.reason, EvmReasonCode::NonceTooLow);
+    })
+    .await
+    .unwrap();
+
+    let requests = server.received_requests().await.unwrap();
+    assert_eq!(requests.len(), 3);
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn http_provider_batches_and_reorders_by_id() {
+    let server = MockServer::start().await;
+    // id 順と逆に返し、途中の 1 件はエラー
+    Mock::given(method("POST"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!([
+            {"jsonrpc": "2.0", "id": 3, "result": "0xde0b6b3a7640000"},
+            {"jsonrpc": "2.0", "id": 2, "error": {"code": -32601, "message": "method not found"}},
+            {"jsonrpc": "2.0", "id": 1, "result": "0x1"}
+        ])))
+        .mount(&server)
+        .await;
+
+    let uri = server.uri();
+    tokio::task::spawn_blocking(move || {
+        let p = JsonRpcHttpProvider::new(info("mock"), http_config(uri)).unwrap();
+        let out = p
+            .batch_call(&[
+                ("eth_chainId", serde_json::json!([])),
+                ("eth_foo", serde_json::json!([])),
+                (
+                    "eth_getBalance",
+                    serde_json::json!(["0x1111111111111111111111111111111111111111", "latest"]),
+                ),
+            ])
+            .unwrap();
+        assert_eq!(out.len(), 3);
+        assert_eq!(out[0].as_ref().unwrap().value, serde_json::json!("0x1"));
+        assert_eq!(
+            out[1].as_ref().unwrap_err().reason,
+            EvmReasonCode::UnsupportedRpcMethod
+        );
+        let balance = out[2].as_ref().unwrap();
+        assert_eq!(balance.method, "eth_getBalance");
+        assert_eq!(balance.value, serde_json::json!("0xde0b6b3a7640000"));
+    })
+    .await
+    .unwrap();
+
+    let requests = server.received_requests().await.unwrap();
+    assert_eq!(requests.len(), 1);
+    let sent: serde_json::Value = serde_json::from_slice(&requests[0].body).unwrap();
+    assert_eq!(sent.as_array().unwrap().len(), 3);
+    assert_eq!(sent[0]["jsonrpc"], "2.0");
+}
+
+/// 1 リクエストへの応答と、eth_subscribe なら続けて流す通知
+fn ws_rpc_reply(
+    req: &serde_json::Value,
+    issued: &mut u32,
+    live: &mut Vec<String>,
+) -> (serde_json::Value, Option<serde_json::Value>) {
+    let id = req["id"].clone();
+    match req["method"].as_str().unwrap() {
+        "eth_subscribe" => {
+            *issued += 1;
+            let sub_id = format!("0xsub{issued}");
+            live.push(sub_id.clone());
+            let result = match req["params"][0].as_str().unwrap() {
+                "newHeads" => serde_json::json!({"number": "0x10", "hash": "0xh16"}),
+                _ => serde_json::json!({
+                    "address": req["params"][1]["address"],
+                    "blockNumber": "0x10",
+                    "blockHash": "0xh16",
+                    "transactionHash": "0xt1",
+                    "logIndex": "0x2",
+                    "topics": ["0xddf2"],
+                    "data": "0x01",
+                    "removed": false
+                }),
+            };
+            let note = serde_json::json!({
+                "jsonrpc": "2.0",
+                "method": "eth_subscription",
+                "params": {"subscription": sub_id, "result": result}
+            });
+            (
+                serde_json::json!({"jsonrpc": "2.0", "id": id, "result": sub_id}),
+                Some(note),
+            )
+        }
+        "eth_unsubscribe" => {
+            // 今の接続で発行した購読 id しか知らない
+            let sub_id = req["params"][0].as_str().unwrap();
+            let known = live.iter().any(|s| s == sub_id);
+            live.retain(|s| s != sub_id);
+            (
+                serde_json::json!({"jsonrpc": "2.0", "id": id, "result": known}),
+                None,
+            )
+        }
+        "eth_chainId" => (
+            serde_json::json!({"jsonrpc": "2.0", "id": id, "result": "0x1"}),
+            None,
+        ),
+        _ => (
+            serde_json::json!({
+                "jsonrpc": "2.0", "id": id,
+                "error": {"code": -32601, "message": "method not found"}
+            }),
+            None,
+        ),
+    }
+}
+
+/// eth_subscribe / eth_unsubscribe / eth_chainId に応答し、購読ごとに通知を 1 件流すモック。
+/// バッチ（配列）は配列で返す。drop_first_after_subscribe なら最初の接続を最初の通知の直後に切る
+async fn spawn_ws_rpc_server_with(drop_first_after_subscribe: bool) -> String {
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+    tokio::spawn(async move {
+        let mut issued = 0;
+        let mut connection = 0;
+        while let Ok((tcp, _)) = listener.accept().await {
+            connection += 1;
+            let mut ws = tokio_tungstenite::accept_async(tcp).await.unwrap();
+            let mut live = Vec::new();
+            while let Some(Ok(msg)) = ws.next().await {
+                let Message::Text(text) = msg else { continue };
+                let req: serde_json::Value = serde_json::from_str(&text).unwrap();
+                if let Some(batch) = req.as_array() {
+                    let replies: Vec<_> = batch
+                        .iter()
+                        .map(|r| ws_rpc_reply(r, &mut issued, &mut live).0)
+                        .collect();
+                    let reply = serde_json::Value::Array(replies);
+                    ws.send(Message::Text(reply.to_string())).await.unwrap();
+                    continue;
+                }
+                let (reply, note) = ws_rpc_reply(&req, &mut issued, &mut live);
+                ws.send(Message::Text(reply.to_string())).await.unwrap();
+                if let Some(note) = note {
+                    ws.send(Message::Text(note.to_string())).await.unwrap();
+                    if drop_first_after_subscribe && connection == 1 {
+                        break;
+                    }
+                }
+            }
+        }
+    });
+    format!("ws://{addr}")
+}
+
+async fn spawn_ws_rpc_server() -> String {
+    spawn_ws_rpc_server_with(false).await
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn ws_provider_subscribes_to_new_heads_and_logs() {
+    let url = spawn_ws_rpc_server().await;
+    tokio::task::spawn_blocking(move || {
+        let ws = JsonRpcWsProvider::connect(info("ws"), JsonRpcWsConfig::new(url)).unwrap();
+        assert_eq!(
+            ws.call("eth_chainId", serde_json::json!([])).unwrap().value,
+            serde_json::json!("0x1")
+        );
+        assert_eq!(
+            ws.call("eth_foo", serde_json::json!([]))
+                .unwrap_err()
+                .reason,
+            EvmReasonCode::UnsupportedRpcMethod
+        );
+
+        let heads = subscribe_new_heads(&ws).unwrap();
+        let address = EvmAddress("0x2222222222222222222222222222222222222222".into());
+        let logs = subscribe_logs(&ws, address.clone()).unwrap();
+        assert_ne!(heads, logs);
+
+        let mut notes = Vec::new();
+        while notes.len() < 2 {
+            match ws.recv_event(Duration::from_secs(5)).expect("notification") {
+                EvmWsEvent::Notification {
+                    subscription,
+                    result,
+                } => notes.push((subscription, result)),
+                other => panic!("unexpected event: {other:?}"),
+            }
+        }
+        let head = notes.iter().find(|(s, _)| *s == heads).unwrap();
+        assert_eq!(head.1["number"], "0x10");
+        let log = notes.iter().find(|(s, _)| *s == logs).unwrap();
+        let event = parse_log_event(&log.1);
+        assert_eq!(event.address, address);
+        assert_eq!(event.block_number, 16);
+        assert_eq!(event.log_index, 2);
+
+        ws.unsubscribe(&heads).unwrap();
+        assert!(ws.unsubscribe("0xnope").is_err());
+    })
+    .await
+    .unwrap();
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn ws_provider_reports_connect_failure() {
+    tokio::task::spawn_blocking(|| {
+        let mut cfg = JsonRpcWsConfig::new("ws://127.0.0.1:9");
+        cfg.connect_attempts = 2;
+        cfg.retry.base_delay_ms = 1;
+        cfg.retry.jitter_ms = 0;
+        let err = JsonRpcWsProvider::connect(info("ws"), cfg).err().unwrap();
+        assert_eq!(err.reason, EvmReasonCode::ProviderTimeout);
+        assert_eq!(err.provider, "ws");
+    })
+    .await
+    .unwrap();
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn ws_provider_reconnects_resubscribes_and_batches() {
+    let url = spawn_ws_rpc_server_with(true).await;
+    tokio::task::spawn_blocking(move || {
+        let mut cfg = JsonRpcWsConfig::new(url);
+        cfg.retry.base_delay_ms = 1;
+        cfg.retry.jitter_ms = 0;
+        let ws = JsonRpcWsProvider::connect(info("ws"), cfg).unwrap();
+        let heads = subscribe_new_heads(&ws).unwrap();
+
+        // 最初の接続は通知 1 件の後に切れる。張り直した接続の通知も元の購読 id で届く
+        let mut events = Vec::new();
+        while events.len() < 3 {
+            events.push(ws.recv_event(Duration::from_secs(5)).expect("event"));
+        }
+        let note = EvmWsEvent::Notification {
+            subscription: heads.clone(),
+            result: serde_json::json!({"number": "0x10", "hash": "0xh16"}),
+        };
+        assert_eq!(events[0], note);
+        match &events[1] {
+            EvmWsEvent::Reconnected {
+                resubscribed,
+                failed,
+                ..
+            } => {
+                assert_eq!(resubscribed, &vec![heads.clone()]);
+                assert!(failed.is_empty());
+            }
+            other => panic!("unexpected event: {other:?}"),
+        }
+        assert_eq!(events[2], note);
+
+        // サーバ側では 0xsub2 になっているが、呼び出し側の id のまま外せる
+        ws.unsubscribe(&heads).unwrap();
+        assert!(ws.unsubscribe(&heads).is_err());
+
+        let results = ws
+            .batch_call(&[
+                ("eth_chainId", serde_json::json!([])),
+                ("eth_foo", serde_json::json!([])),
+            ])
+            .unwrap();
+        assert_eq!(results.len(), 2);
+        assert_eq!(results[0].as_ref().unwrap().value, serde_json::json!("0x1"));
+        assert_eq!(
+            results[1].as_ref().unwrap_err().reason,
+            EvmReasonCode::UnsupportedRpcMethod
+        );
+        assert!(ws.batch_call(&[]).unwrap().is_empty());
+    })
+    .await
+    .unwrap();
+}
@@ -0,0 +1,269 @@
+use ucel_chain_ethereum::{
+    address_from_secret, build_transaction, encode_signed_transaction, encode_unsigned_transaction,
+    keccak256, recover_address, rlp_encode, sign_transaction, transaction_signing_hash, EvmSigner,
+    LocalKeySigner, RecoverableSignature, RlpItem,
+};
+use ucel_core::{
+    ErrorCode, EvmAddress, EvmChainId, EvmFeeEstimate, KeyRef, KeyScope, ResolvedSecret,
+    SecretRefResolver, UcelError,
+};
+use ucel_sdk::chain::{evm_signer_from_key_ref, evm_signer_from_secret};
+use ucel_sdk::prelude::SecretString;
+
+// EIP-155 の example（https://eips.ethereum.org/EIPS/eip-155）
+const EIP155_KEY: &str = "0x4646464646464646464646464646464646464646464646464646464646464646";
+const EIP155_SIGNING_DATA: &str =
+    "ec098504a817c800825208943535353535353535353535353535353535353535880de0b6b3a764000080018080";
+const EIP155_SIGNING_HASH: &str =
+    "daf5a779ae972f972197303d7b574746c7ef83eadac0f2791ad23db92e4c8e53";
+const EIP155_SIGNED: &str = "0xf86c098504a817c800825208943535353535353535353535353535353535353535880de0b6b3a76400008025a028ef61340bd939bc2195fe537567866003e1a15d3c71ff63e1590620aa636276a067cbe9d8997f761aecb703304b3800ccf555c9f3dc64214b297fb1966a3b6d83";
+
+// EIP-155 の鍵で、同じ送金を type-2（tip 2 gwei / max fee 30 gwei）にしたもの。
+// RLP・keccak・RFC 6979 署名をこのクレートと独立に実装したもの（Python cryptography）で作った値
+const EIP1559_SIGNING_HASH: &str =
+    "fae77debb64203fbaea6213fcde74f1b138c6854c3d7b44ba1c2ced52c2d8c4d";
+const EIP1559_SIGNED: &str = "0x02f873010984773594008506fc23ac00825208943535353535353535353535353535353535353535880de0b6b3a764000080c080a02b03b67e070f45175ce9d07c4512720168bd468a24edb6997977a53d48c87a12a0733d775fdd689d306e08ac8ab399f34b5a0253b47ed81b8bf2d2a6ea607fcac7";
+const EIP1559_TX_HASH: &str = "0xbb94970b7e5afad02e4e38a462eacd085a96791deacaab2827d61aeb20e0778e";
+
+// mainnet に載った type-2 tx（alloy-consensus の live tx デコードテストと同じもの）。
+// 秘密鍵は公開されていないので、エンコードと署名復元を突き合わせる
+const MAINNET_1559_RAW: &str = "0x02f86f0102843b9aca0085029e7822d68298f094d9e1459a7a482635700cbc20bbaf52d495ab9c9680841b55ba3ac080a0c199674fcb29f353693dd779c017823b954b3c69dffa3cd6b2a6ff7888798039a028ca912de909e7e6cdef9cdcaf24c54dd8c1032946dfa1d85c206b32a9064fe8";
+const MAINNET_1559_SENDER: &str = "0x001e2b7de757ba469a57bf6b23d982458a07efce";
+
+fn h(s: &str) -> Vec<u8> {
+    hex::decode(s).unwrap()
+}
+
+fn str_item(s: &str) -> RlpItem {
+    RlpItem::bytes(s.as_bytes())
+}
+
+fn eip155_tx(from: EvmAddress) -> ucel_core::EvmTransactionRequest {
+    build_transaction(
+        EvmChainId(1),
+        from,
+        Some(EvmAddress(
+            "0x3535353535353535353535353535353535353535".into(),
+        )),
+        "0x".into(),
+        1_000_000_000_000_000_000,
+        21_000,
+        EvmFeeEstimate {
+            legacy_gas_price: Some(20_000_000_000),
+            max_fee_per_gas: None,
+            max_priority_fee_per_gas: None,
+            gas_limit: 21_000,
+        },
+        9,
+    )
+    .unwrap()
+}
+
+#[test]
+fn rlp_matches_ethereum_tests_vectors() {
+    // ethereum/tests RLPTests/rlptest.json より
+    assert_eq!(rlp_encode(&str_item("")), h("80"));
+    assert_eq!(rlp_encode(&str_item("dog")), h("83646f67"));
+    assert_eq!(
+        rlp_encode(&RlpItem::list(vec![str_item("cat"), str_item("dog")])),
+        h("c88363617483646f67")
+    );
+    assert_eq!(rlp_encode(&RlpItem::list(vec![])), h("c0"));
+    assert_eq!(rlp_encode(&RlpItem::uint(0)), h("80"));
+    assert_eq!(rlp_encode(&RlpItem::uint(15)), h("0f"));
+    assert_eq!(rlp_encode(&RlpItem::uint(1024)), h("820400"));
+    let lorem = "Lorem ipsum dolor sit amet, consectetur adipisicing elit";
+    let mut expected = h("b838");
+    expected.extend(lorem.as_bytes());
+    assert_eq!(rlp_encode(&str_item(lorem)), expected);
+    let e = || RlpItem::list(vec![]);
+    let set = RlpItem::list(vec![
+        e(),
+        RlpItem::list(vec![e()]),
+        RlpItem::list(vec![e(), RlpItem::list(vec![e()])]),
+    ]);
+    assert_eq!(rlp_encode(&set), h("c7c0c1c0c3c0c1c0"));
+}
+
+#[test]
+fn keccak_and_address_derivation_vectors() {
+    assert_eq!(
+        hex::encode(keccak256(b"")),
+        "c5d2460186f7233c927e7db2dcc703c0e500b653ca82273b7bfad8045d85a470"
+    );
+    let mut one = [0u8; 32];
+    one[31] = 1;
+    assert_eq!(
+        address_from_secret(&one).unwrap().0,
+        "0x7e5f4552091a69125d5dfcb7b8c2659029395bdf"
+    );
+    let signer = LocalKeySigner::from_secret_hex(EIP155_KEY).unwrap();
+    assert_eq!(
+        signer.address().0,
+        "0x9d8a62f656a8d1615c1294fd71e9cfb3e4855a4f"
+    );
+    assert!(!format!("{signer:?}").contains("4646"));
+}
+
+#[test]
+fn eip155_legacy_vector_round_trips() {
+    let signer = LocalKeySigner::from_secret_hex(EIP155_KEY).unwrap();
+    let tx = eip155_tx(signer.address().clone());
+    assert_eq!(
+        hex::encode(encode_unsigned_transaction(&tx).unwrap()),
+        EIP155_SIGNING_DATA
+    );
+    assert_eq!(
+        hex::encode(transaction_signing_hash(&tx).unwrap()),
+        EIP155_SIGNING_HASH
+    );
+
+    let signed = sign_transaction(&signer, &tx).unwrap();
+    assert_eq!(signed.raw_tx_hex, EIP155_SIGNED);
+    let raw = h(signed.raw_tx_hex.trim_start_matches("0x"));
+    assert_eq!(
+        signed.tx_hash,
+        format!("0x{}", hex::encode(keccak256(&raw)))
+    );
+}
+
+#[test]
+fn eip1559_type2_signature_recovers_the_signer() {
+    let signer = LocalKeySigner::from_secret_hex(EIP155_KEY).unwrap();
+    let mut tx = eip155_tx(signer.address().clone());
+    tx.fee = EvmFeeEstimate {
+        legacy_gas_price: None,
+        max_fee_per_gas: Some(30_000_000_000),
+        max_priority_fee_per_gas: Some(2_000_000_000),
+        gas_limit: 21_000,
+    };
+    tx.data_hex = "0xa9059cbb".into();
+
+    let unsigned = encode_unsigned_transaction(&tx).unwrap();
+    assert_eq!(unsigned[0], 0x02);
+    let signed = signer.sign_transaction(&tx).unwrap();
+    let raw = h(signed.raw_tx_hex.trim_start_matches("0x"));
+    assert_eq!(raw[0], 0x02);
+    // 署名は決定的（RFC 6979）
+    assert_eq!(signer.sign_transaction(&tx).unwrap(), signed);
+
+    // 末尾 3 要素は yParity (1 byte), r (0xa0 + 32), s (0xa0 + 32)
+    let tail = &raw[raw.len() - 67..];
+    assert!(tail[0] == 0x80 || tail[0] == 0x01);
+    assert_eq!(tail[1], 0xa0);
+    assert_eq!(tail[34], 0xa0);
+    let sig = RecoverableSignature {
+        r: tail[2..34].try_into().unwrap(),
+        s: tail[35..67].try_into().unwrap(),
+        recovery_id: u8::from(tail[0] == 0x01),
+    };
+    let hash = transaction_signing_hash(&tx).unwrap();
+    assert_eq!(recover_address(&hash, &sig).unwrap(), *signer.address());
+}
+
+#[test]
+fn eip1559_type2_vector_matches_independent_signing() {
+    let signer = LocalKeySigner::from_secret_hex(EIP155_KEY).unwrap();
+    let mut tx = eip155_tx(signer.address().clone());
+    tx.fee = EvmFeeEstimate {
+        legacy_gas_price: None,
+        max_fee_per_gas: Some(30_000_000_000),
+        max_priority_fee_per_gas: Some(2_000_000_000),
+        gas_limit: 21_000,
+    };
+    assert_eq!(
+        hex::encode(transaction_signing_hash(&tx).unwrap()),
+        EIP1559_SIGNING_HASH
+    );
+    let signed = sign_transaction(&signer, &tx).unwrap();
+    assert_eq!(signed.raw_tx_hex, EIP1559_SIGNED);
+    assert_eq!(signed.tx_hash, EIP1559_TX_HASH);
+}
+
+#[test]
+fn eip1559_mainnet_transaction_reencodes_byte_for_byte() {
+    let raw = h(MAINNET_1559_RAW.trim_start_matches("0x"));
+    let tx = build_transaction(
+        EvmChainId(1),
+        EvmAddress(MAINNET_1559_SENDER.into()),
+        Some(EvmAddress(
+            "0xd9e1459a7a482635700cbc20bbaf52d495ab9c96".into(),
+        )),
+        "0x1b55ba3a".into(),
+        0,
+        0x98f0,
+        EvmFeeEstimate {
+            legacy_gas_price: None,
+            max_fee_per_gas: Some(0x029e7822d6),
+            max_priority_fee_per_gas: Some(0x3b9aca00),
+            gas_limit: 0x98f0,
+        },
+        2,
+    )
+    .unwrap();
+    let tail = &raw[raw.len() - 67..];
+    let sig = RecoverableSignature {
+        r: tail[2..34].try_into().unwrap(),
+        s: tail[35..67].try_into().unwrap(),
+        recovery_id: u8::from(tail[0] == 0x01),
+    };
+    assert_eq!(encode_signed_transaction(&tx, &sig).unwrap(), raw);
+    let hash = transaction_signing_hash(&tx).unwrap();
+    assert_eq!(recover_address(&hash, &sig).unwrap().0, MAINNET_1559_SENDER);
+}
+
+#[test]
+fn signer_rejects_foreign_from_and_bad_keys() {
+    let signer = LocalKeySigner::from_secret_hex(EIP155_KEY).unwrap();
+    let tx = eip155_tx(EvmAddress(
+        "0x1111111111111111111111111111111111111111".into(),
+    ));
+    assert_eq!(
+        signer.sign_transaction(&tx).unwrap_err().code,
+        ErrorCode::AuthFailed
+    );
+    assert_eq!(
+        LocalKeySigner::from_secret_hex("0x1234").unwrap_err().code,
+        ErrorCode::AuthFailed
+    );
+    assert_eq!(
+        LocalKeySigner::from_secret_hex(&"00".repeat(32))
+            .unwrap_err()
+            .code,
+        ErrorCode::AuthFailed
+    );
+}
+
+struct EnvLikeResolver;
+
+impl SecretRefResolver for EnvLikeResolver {
+    fn resolve(&self, key_ref: &KeyRef) -> Result<ResolvedSecret, UcelError> {
+        if key_ref.secret_ref != "vault://evm/hot" {
+            return Err(UcelError::new(ErrorCode::PermissionDenied, "unknown ref"));
+        }
+        Ok(ResolvedSecret {
+            api_key: "evm-hot".into(),
+            api_secret: Some(EIP155_KEY.into()),
+            passphrase: None,
+        })
+    }
+}
+
+#[test]
+fn sdk_loads_signer_through_secret_paths() {
+    let from_secret = evm_signer_from_secret(&SecretString::new(EIP155_KEY)).unwrap();
+    let key_ref = KeyRef {
+        key_id: "evm-hot".into(),
+        secret_ref: "vault://evm/hot".into(),
+        scope: KeyScope::Trade,
+        account_id: None,
+    };
+    let from_ref = evm_signer_from_key_ref(&EnvLikeResolver, &key_ref).unwrap();
+    assert_eq!(from_secret.address(), from_ref.address());
+
+    let missing = KeyRef {
+        secret_ref: "vault://evm/cold".into(),
+        ..key_ref
+    };
+    assert!(evm_signer_from_key_ref(&EnvLikeResolver, &missing).is_err());
+}
@@ -73,6 +73,7 @@ fn supported_ws_ops_aligns_with_coverage_v2() {
         "coinbase",
         "bybit",
         "bitget",
+        "bithumb",
         "bitmex",
         "bittrade",
         "deribit",
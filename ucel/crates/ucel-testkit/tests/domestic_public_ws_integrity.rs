@@ -1,6 +1,7 @@
-use ucel_core::PublicWsReasonCode;
+use ucel_core::{IngestResumeDirective, PublicWsReasonCode};
 use ucel_transport::ws::integrity::{
-    check_checksum, check_crossed_book, check_negative_qty, check_sequence,
+    book_crc32, check_book_checksum, check_checksum, check_crossed_book, check_negative_qty,
+    check_sequence, interleaved_checksum_payload, BookChecksumGuard,
 };
 
 #[test]
@@ -30,3 +31,28 @@ fn none_mode_equivalent_checks_can_pass() {
     check_crossed_book(Some(100.0), Some(100.0)).expect("flat top book is okay");
     check_negative_qty(false).expect("non-negative qty should pass");
 }
+
+#[test]
+fn book_checksum_primitives() {
+    assert_eq!(book_crc32("123456789"), 0xCBF4_3926);
+    assert_eq!(
+        interleaved_checksum_payload(
+            &[("3366.1", "7"), ("3366", "6"), ("3365", "1")],
+            &[("3366.8", "9"), ("3368", "8")]
+        ),
+        "3366.1:7:3366.8:9:3366:6:3368:8:3365:1"
+    );
+    assert_eq!(
+        check_book_checksum(1, 2).expect_err("checksum mismatch must fail"),
+        PublicWsReasonCode::ChecksumMismatch
+    );
+
+    let mut guard = BookChecksumGuard::default();
+    assert_eq!(
+        guard.verify(1, 2),
+        Err(IngestResumeDirective::ResnapshotThenResubscribe)
+    );
+    guard.verify(2, 2).expect("match resets the streak");
+    assert_eq!(guard.consecutive_mismatches, 0);
+    assert_eq!(guard.mismatch_total, 1);
+}
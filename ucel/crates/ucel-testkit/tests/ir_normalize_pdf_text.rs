@@ -0,0 +1,169 @@
+use ucel_core::{IrArtifactDescriptor, IrArtifactKey, IrArtifactKind, IrArtifactSource, IrDocumentKey, IrNormalizationSupport, IrNormalizedFormat};
+use ucel_ir::artifact::IrArtifactFetchResponse;
+use ucel_ir::normalize::errors::IrNormalizationReasonCode;
+use ucel_ir::normalize::normalize_artifact_with_format;
+use ucel_ir::normalize::pdf::{pdf_pages, pdf_text_layer};
+use ucel_testkit::ir_normalize::{build_pdf_bytes_from_spec, fixture_path};
+
+fn fetch(bytes: Vec<u8>) -> IrArtifactFetchResponse {
+    let metadata = IrArtifactDescriptor { key: IrArtifactKey { document: IrDocumentKey { source_id: "s".into(), source_document_id: "d".into() }, artifact_id: "a".into() }, source_id: "s".into(), kind: IrArtifactKind::Pdf, content_type: Some("application/pdf".into()), source: IrArtifactSource::ByteSource, checksum_sha256: None, size_bytes: None, encoding: None };
+    IrArtifactFetchResponse { metadata, bytes: Some(bytes), text_candidate: None, source_metadata: serde_json::Value::Null }
+}
+
+#[test]
+fn flate_streams_tounicode_cmaps_and_reading_order_per_page() {
+    let pdf = build_pdf_bytes_from_spec(&fixture_path("pdf/synthetic_type0_flate_tounicode.pdf.spec.json"));
+    let pages = pdf_pages(&pdf).unwrap();
+    assert_eq!(pages.len(), 3);
+    // runs are drawn bottom line first; reading order puts the heading first
+    assert_eq!(pages[0].text, "決算短信\n2025年度");
+    assert_eq!(pages[0].unmapped_glyphs, 0);
+    // TJ kerning inside a word vs. a wide gap; the right column is drawn first
+    assert_eq!(pages[1].text, "Net sales 1,234 right");
+    assert!(pages[2].image_only && pages[2].text.is_empty());
+    assert!(!pages[0].image_only && !pages[1].image_only);
+
+    assert_eq!(pdf_text_layer(&pdf).unwrap(), "決算短信\n2025年度\n\nNet sales 1,234 right");
+}
+
+#[test]
+fn normalized_pdf_has_page_sections_and_reports_image_only_pages() {
+    let pdf = build_pdf_bytes_from_spec(&fixture_path("pdf/synthetic_type0_flate_tounicode.pdf.spec.json"));
+    let c = normalize_artifact_with_format(&fetch(pdf), IrNormalizedFormat::Pdf).unwrap();
+    assert_eq!(c.support_level, IrNormalizationSupport::Partial);
+    assert_eq!(c.provenance.extra["image_only_pages"], "3");
+    assert_eq!(c.provenance.extra["reason_code"], IrNormalizationReasonCode::ImageOnlyPage.as_str());
+
+    let titles: Vec<&str> = c.sections.iter().map(|s| s.title.as_str()).collect();
+    assert_eq!(titles, vec!["page 1", "page 2"]);
+    let (start, end) = c.sections[1].text_range;
+    assert_eq!(&c.normalized_text[start..end], "Net sales 1,234 right");
+    assert_eq!(c.sections[1].provenance.source_ref.as_deref(), Some("page:2"));
+}
+
+#[test]
+fn scanned_pdf_fails_with_image_only_reason() {
+    let body = "1 0 obj << /Type /Catalog /Pages 2 0 R >> endobj\n\
+                2 0 obj << /Type /Pages /Kids [3 0 R] /Count 1 >> endobj\n\
+                3 0 obj << /Type /Page /Parent 2 0 R /Resources << /XObject << /Scan 4 0 R >> >> /Contents 5 0 R >> endobj\n\
+                4 0 obj << /Type /XObject /Subtype /Image /Width 1 /Height 1 /Filter /CCITTFaxDecode /Length 4 >> stream\nscan\nendstream endobj\n\
+                5 0 obj << /Length 31 >> stream\nq 595 0 0 842 0 0 cm /Scan Do Q\nendstream endobj\n";
+    let pdf = format!("%PDF-1.4\n{body}%%EOF\n").into_bytes();
+    let err = pdf_text_layer(&pdf).unwrap_err();
+    assert_eq!(err.reason, IrNormalizationReasonCode::ImageOnlyPage);
+    assert_eq!(err.metadata["pages"], "1");
+
+    let encrypted = b"%PDF-1.4\n1 0 obj << /Type /Catalog >> endobj\ntrailer << /Root 1 0 R /Encrypt 2 0 R >>\n%%EOF".to_vec();
+    assert_eq!(pdf_text_layer(&encrypted).unwrap_err().reason, IrNormalizationReasonCode::MalformedPdf);
+}
+
+fn one_page_pdf(content_dict: &str, content: &[u8]) -> Vec<u8> {
+    let mut pdf = b"%PDF-1.4\n1 0 obj << /Type /Catalog /Pages 2 0 R >> endobj\n\
+                    2 0 obj << /Type /Pages /Kids [3 0 R] /Count 1 >> endobj\n\
+                    3 0 obj << /Type /Page /Parent 2 0 R /Resources << /Font << /F1 4 0 R >> >> /Contents 5 0 R >> endobj\n\
+                    4 0 obj << /Type /Font /Subtype /Type1 /BaseFont /Helvetica >> endobj\n"
+        .to_vec();
+    pdf.extend_from_slice(format!("5 0 obj << {content_dict} >> stream\n").as_bytes());
+    pdf.extend_from_slice(content);
+    pdf.extend_from_slice(b"\nendstream endobj\n%%EOF\n");
+    pdf
+}
+
+#[test]
+fn hostile_nesting_and_lengths_do_not_crash_the_parser() {
+    let deep = format!("%PDF-1.4\n1 0 obj {} endobj\n%%EOF\n", "[".repeat(200_000));
+    assert_eq!(pdf_pages(deep.as_bytes()).unwrap_err().reason, IrNormalizationReasonCode::MalformedPdf);
+
+    // /Length past the end of the file falls back to the endstream marker
+    let pdf = one_page_pdf("/Length 18446744073709551615", b"BT /F1 12 Tf 72 700 Td (kept) Tj ET");
+    assert_eq!(pdf_text_layer(&pdf).unwrap(), "kept");
+}
+
+#[test]
+fn flate_bomb_fails_with_oversized_reason() {
+    let mut z = flate2::write::ZlibEncoder::new(Vec::new(), flate2::Compression::best());
+    let zeros = vec![0u8; 1024 * 1024];
+    for _ in 0..65 {
+        std::io::Write::write_all(&mut z, &zeros).unwrap();
+    }
+    let bomb = z.finish().unwrap();
+    let pdf = one_page_pdf(&format!("/Length {} /Filter /FlateDecode", bomb.len()), &bomb);
+    assert_eq!(pdf_pages(&pdf).unwrap_err().reason, IrNormalizationReasonCode::OversizedArtifact);
+}
+
+fn pdf_from_objects(objects: &[Vec<u8>]) -> Vec<u8> {
+    let mut pdf = b"%PDF-1.4\n".to_vec();
+    for (i, body) in objects.iter().enumerate() {
+        pdf.extend_from_slice(format!("{} 0 obj ", i + 1).as_bytes());
+        pdf.extend_from_slice(body);
+        pdf.extend_from_slice(b" endobj\n");
+    }
+    pdf.extend_from_slice(b"%%EOF\n");
+    pdf
+}
+
+fn stream(dict: &str, data: &[u8]) -> Vec<u8> {
+    let mut out = format!("<< {dict} /Length {} >> stream\n", data.len()).into_bytes();
+    out.extend_from_slice(data);
+    out.extend_from_slice(b"\nendstream");
+    out
+}
+
+#[test]
+fn page_tree_that_contains_itself_is_malformed() {
+    let pdf = pdf_from_objects(&[b"<< /Type /Catalog /Pages 2 0 R >>".to_vec(), b"<< /Type /Pages /Kids [2 0 R 2 0 R] /Count 2 >>".to_vec()]);
+    assert_eq!(pdf_pages(&pdf).unwrap_err().reason, IrNormalizationReasonCode::MalformedPdf);
+}
+
+#[test]
+fn form_that_draws_itself_is_run_once() {
+    let form = format!("BT /F1 12 Tf 72 700 Td (form) Tj ET {}", "/Fm Do ".repeat(1000));
+    let pdf = pdf_from_objects(&[
+        b"<< /Type /Catalog /Pages 2 0 R >>".to_vec(),
+        b"<< /Type /Pages /Kids [3 0 R] /Count 1 >>".to_vec(),
+        b"<< /Type /Page /Parent 2 0 R /Resources 4 0 R /Contents 6 0 R >>".to_vec(),
+        b"<< /Font << /F1 5 0 R >> /XObject << /Fm 7 0 R >> >>".to_vec(),
+        b"<< /Type /Font /Subtype /Type1 /BaseFont /Helvetica >>".to_vec(),
+        stream("", b"/Fm Do"),
+        stream("/Type /XObject /Subtype /Form /Resources 4 0 R", form.as_bytes()),
+    ]);
+    assert_eq!(pdf_text_layer(&pdf).unwrap(), "form");
+}
+
+#[test]
+fn form_fan_out_fails_on_the_operation_budget() {
+    // eight distinct forms, each drawing the next twenty times: 20^8 draws of the last one
+    let mut objects = vec![
+        b"<< /Type /Catalog /Pages 2 0 R >>".to_vec(),
+        b"<< /Type /Pages /Kids [3 0 R] /Count 1 >>".to_vec(),
+        b"<< /Type /Page /Parent 2 0 R /Resources << /XObject << /Fm 5 0 R >> >> /Contents 4 0 R >>".to_vec(),
+        stream("", b"/Fm Do"),
+    ];
+    for level in 0..8 {
+        let next = objects.len() + 2;
+        let body = if level == 7 { "0 0 m 1 1 l S ".repeat(20) } else { "/Fm Do ".repeat(20) };
+        objects.push(stream(&format!("/Type /XObject /Subtype /Form /Resources << /XObject << /Fm {next} 0 R >> >>"), body.as_bytes()));
+    }
+    let err = pdf_pages(&pdf_from_objects(&objects)).unwrap_err();
+    assert_eq!(err.reason, IrNormalizationReasonCode::MalformedPdf);
+    assert!(err.metadata.contains_key("max_operations"));
+}
+
+#[test]
+fn contents_repeating_one_large_stream_fail_on_the_decoded_byte_budget() {
+    let mut z = flate2::write::ZlibEncoder::new(Vec::new(), flate2::Compression::best());
+    let spaces = vec![b' '; 1024 * 1024];
+    for _ in 0..48 {
+        std::io::Write::write_all(&mut z, &spaces).unwrap();
+    }
+    let big = z.finish().unwrap();
+    let pdf = pdf_from_objects(&[
+        b"<< /Type /Catalog /Pages 2 0 R >>".to_vec(),
+        b"<< /Type /Pages /Kids [3 0 R] /Count 1 >>".to_vec(),
+        b"<< /Type /Page /Parent 2 0 R /Contents [4 0 R 4 0 R 4 0 R 4 0 R] >>".to_vec(),
+        stream("/Filter /FlateDecode", &big),
+    ]);
+    let err = pdf_pages(&pdf).unwrap_err();
+    assert_eq!(err.reason, IrNormalizationReasonCode::MalformedPdf);
+    assert!(err.metadata.contains_key("max_decoded_bytes"));
+}
@@ -0,0 +1,69 @@
+use ucel_core::{IrArtifactDescriptor, IrArtifactKey, IrArtifactKind, IrArtifactSource, IrDocumentKey, IrNormalizedFormat};
+use ucel_ir::artifact::IrArtifactFetchResponse;
+use ucel_ir::normalize::normalize_artifact_with_format;
+use ucel_ir::normalize::pdf::pdf_pages;
+use ucel_ir::normalize::tables::{html::html_tables, pdf::pdf_tables};
+use ucel_testkit::ir_normalize::{build_pdf_bytes_from_spec, fixture_path, load_text_fixture};
+
+fn fetch(kind: IrArtifactKind, bytes: Vec<u8>) -> IrArtifactFetchResponse {
+    let metadata = IrArtifactDescriptor { key: IrArtifactKey { document: IrDocumentKey { source_id: "s".into(), source_document_id: "d".into() }, artifact_id: "a".into() }, source_id: "s".into(), kind, content_type: None, source: IrArtifactSource::ByteSource, checksum_sha256: None, size_bytes: None, encoding: None };
+    IrArtifactFetchResponse { metadata, bytes: Some(bytes), text_candidate: None, source_metadata: serde_json::Value::Null }
+}
+
+fn some(v: &[Option<&str>]) -> Vec<Option<String>> {
+    v.iter().map(|s| s.map(str::to_string)).collect()
+}
+
+#[test]
+fn html_tables_expand_spans_flatten_headers_and_normalize_numbers() {
+    let tables = html_tables(&load_text_fixture(&fixture_path("html/tdnet_financial_tables.html")));
+    assert_eq!(tables.len(), 2);
+
+    let jp = &tables[0];
+    assert_eq!(jp.headers, vec!["", "連結経営成績 / 2024年3月期", "連結経営成績 / 2025年3月期"]);
+    assert_eq!(jp.rows[0], vec!["売上高", "12,345", "13,000※1"]);
+    assert_eq!(jp.numeric_rows[0], some(&[None, Some("12345"), Some("13000")]));
+    assert_eq!(jp.numeric_rows[1], some(&[None, Some("-1234"), Some("567")]));
+    assert_eq!(jp.numeric_rows[2], some(&[None, Some("-89"), None]));
+    assert_eq!((jp.unit.as_deref(), jp.unit_scale), (Some("JPY"), Some(6)));
+    assert_eq!(jp.provenance.extra["unit_source"], "preceding_text");
+    assert_eq!(jp.footnotes, vec!["※1 決算期変更により9ヶ月決算となっております。"]);
+    assert_eq!(jp.provenance.source_type.as_deref(), Some("html_table"));
+    assert_eq!(jp.provenance.source_ref.as_deref(), Some("table:1"));
+
+    let us = &tables[1];
+    assert_eq!(us.caption.as_deref(), Some("Consolidated Statements of Operations (In millions, except per share amounts)"));
+    assert_eq!(us.headers, vec!["", "2024", "2023"]);
+    assert_eq!(us.numeric_rows[0], some(&[None, Some("391035"), Some("383285")]));
+    // rowspan repeats the label on the covered row
+    assert_eq!(us.rows[2], vec!["Segment", "3", "4"]);
+    assert_eq!((us.unit.as_deref(), us.unit_scale), (None, Some(6)));
+    assert_eq!(us.footnotes, vec!["(1) Includes products and services."]);
+}
+
+#[test]
+fn pdf_tables_follow_column_alignment() {
+    let pdf = build_pdf_bytes_from_spec(&fixture_path("pdf/summary_results_table.pdf.spec.json"));
+    let tables = pdf_tables(&pdf_pages(&pdf).unwrap());
+    assert_eq!(tables.len(), 1);
+    let t = &tables[0];
+    assert_eq!(t.caption.as_deref(), Some("Consolidated results"));
+    assert_eq!((t.unit.as_deref(), t.unit_scale), (Some("JPY"), Some(6)));
+    assert_eq!(t.headers, vec!["", "FY2024", "FY2025"]);
+    assert_eq!(t.rows, vec![vec!["Net sales", "12,345", "13,000"], vec!["Operating income", "(1,234)", "567"], vec!["Net income", "-89", "-"]]);
+    assert_eq!(t.numeric_rows[1], some(&[None, Some("-1234"), Some("567")]));
+    assert_eq!(t.footnotes, vec!["*1 Nine-month period."]);
+    assert_eq!(t.provenance.source_ref.as_deref(), Some("page:1"));
+}
+
+#[test]
+fn normalized_html_and_pdf_carry_tables() {
+    let html = load_text_fixture(&fixture_path("html/tdnet_financial_tables.html"));
+    let c = normalize_artifact_with_format(&fetch(IrArtifactKind::Html, html.into_bytes()), IrNormalizedFormat::Html).unwrap();
+    assert_eq!(c.tables.len(), 2);
+
+    let pdf = build_pdf_bytes_from_spec(&fixture_path("pdf/summary_results_table.pdf.spec.json"));
+    let c = normalize_artifact_with_format(&fetch(IrArtifactKind::Pdf, pdf), IrNormalizedFormat::Pdf).unwrap();
+    assert_eq!(c.tables.len(), 1);
+    assert_eq!(c.tables[0].provenance.source_type.as_deref(), Some("pdf_table"));
+}
@@ -0,0 +1,95 @@
+use ucel_core::{IrArtifactDescriptor, IrArtifactKey, IrArtifactKind, IrArtifactSource, IrDocumentKey, IrFinancialConcept, IrNormalizationSupport, IrNormalizedFormat, IrXbrlPeriod};
+use ucel_ir::artifact::IrArtifactFetchResponse;
+use ucel_ir::normalize::errors::IrNormalizationReasonCode;
+use ucel_ir::normalize::normalize_artifact_with_format;
+use ucel_testkit::ir_normalize::{fixture_path, load_text_fixture};
+
+fn fetch(kind: IrArtifactKind, body: &str) -> IrArtifactFetchResponse {
+    let metadata = IrArtifactDescriptor { key: IrArtifactKey { document: IrDocumentKey { source_id: "s".into(), source_document_id: "d".into() }, artifact_id: "a".into() }, source_id: "s".into(), kind, content_type: None, source: IrArtifactSource::ByteSource, checksum_sha256: None, size_bytes: None, encoding: Some("utf-8".into()) };
+    IrArtifactFetchResponse { metadata, bytes: Some(body.as_bytes().to_vec()), text_candidate: None, source_metadata: serde_json::Value::Null }
+}
+
+#[test]
+fn edinet_instance_resolves_contexts_units_and_maps_statement() {
+    let raw = load_text_fixture(&fixture_path("xbrl/edinet_consolidated_instance.xml"));
+    let c = normalize_artifact_with_format(&fetch(IrArtifactKind::Xbrl, &raw), IrNormalizedFormat::Xbrl).unwrap();
+    assert_eq!(c.facts.len(), 9);
+
+    let non_consolidated = c.facts.iter().find(|f| f.context_ref == "CurrentYearDuration_NonConsolidatedMember").unwrap();
+    assert_eq!(non_consolidated.dimensions["jppfs_cor:ConsolidatedOrNonConsolidatedAxis"], "jppfs_cor:NonConsolidatedMember");
+    assert_eq!(non_consolidated.entity_id.as_deref(), Some("E00001-000"));
+
+    let eps = c.facts.iter().find(|f| f.local_name == "BasicEarningsLossPerShareSummaryOfBusinessResults").unwrap();
+    assert_eq!(eps.unit.as_deref(), Some("iso4217:JPY/xbrli:shares"));
+    assert_eq!(eps.numeric_value.as_deref(), Some("12.3"));
+    assert!(c.facts.iter().any(|f| f.local_name == "DilutedEarningsPerShareSummaryOfBusinessResults" && f.is_nil && f.numeric_value.is_none()));
+    assert!(c.facts.iter().any(|f| f.local_name == "CompanyNameCoverPage" && f.value == "サンプル株式会社" && f.numeric_value.is_none()));
+
+    let item = |k: IrFinancialConcept| c.financial_items.iter().find(|i| i.concept == k).unwrap();
+    let revenue = item(IrFinancialConcept::Revenue);
+    assert_eq!(revenue.value, "1250000000");
+    assert_eq!(revenue.provenance.context_ref.as_deref(), Some("CurrentYearDuration"));
+    assert_eq!(revenue.period, Some(IrXbrlPeriod::Duration { start: "2024-04-01".into(), end: "2025-03-31".into() }));
+    assert_eq!(item(IrFinancialConcept::OperatingIncome).value, "-35000000");
+    assert_eq!(item(IrFinancialConcept::NetIncome).source_concept, "jppfs_cor:ProfitLossAttributableToOwnersOfParent");
+    assert_eq!(item(IrFinancialConcept::TotalAssets).period, Some(IrXbrlPeriod::Instant { date: "2025-03-31".into() }));
+    assert!(c.financial_items.iter().all(|i| i.concept != IrFinancialConcept::EpsDiluted));
+}
+
+#[test]
+fn inline_xbrl_applies_scale_sign_format_and_continuations() {
+    let raw = load_text_fixture(&fixture_path("ixbrl/sec_10k_facts.html"));
+    let c = normalize_artifact_with_format(&fetch(IrArtifactKind::Ixbrl, &raw), IrNormalizedFormat::Ixbrl).unwrap();
+    assert_eq!(c.support_level, IrNormalizationSupport::Supported);
+
+    let revenue = c.facts.iter().find(|f| f.local_name == "RevenueFromContractWithCustomerExcludingAssessedTax").unwrap();
+    assert_eq!(revenue.namespace.as_deref(), Some("http://fasb.org/us-gaap/2024"));
+    assert_eq!(revenue.value, "391,035");
+    assert_eq!(revenue.numeric_value.as_deref(), Some("391035000000"));
+    assert_eq!(revenue.scale, Some(6));
+    assert_eq!(revenue.provenance.extra["fact_id"], "f1");
+
+    let basis = c.facts.iter().find(|f| f.local_name == "BasisOfAccounting").unwrap();
+    assert_eq!(basis.value, "Prepared under US GAAP for fiscal 2024.");
+
+    let item = |k: IrFinancialConcept| c.financial_items.iter().find(|i| i.concept == k).unwrap();
+    assert_eq!(item(IrFinancialConcept::NetIncome).value, "-1250000000");
+    assert_eq!(item(IrFinancialConcept::SharesOutstanding).value, "15115823000");
+    assert_eq!(item(IrFinancialConcept::SharesOutstanding).provenance.context_ref.as_deref(), Some("AsOf2024"));
+}
+
+#[test]
+fn malformed_xbrl_and_loose_inline_html_degrade_to_partial_text() {
+    let c = normalize_artifact_with_format(&fetch(IrArtifactKind::Xbrl, "<xbrli:xbrl><a>売上高 100</a>"), IrNormalizedFormat::Xbrl).unwrap();
+    assert_eq!(c.support_level, IrNormalizationSupport::Partial);
+    assert!(c.facts.is_empty());
+    assert!(c.normalized_text.contains("売上高"), "{}", c.normalized_text);
+    assert_eq!(c.provenance.extra["reason_code"], IrNormalizationReasonCode::MalformedXbrl.as_str());
+
+    let c = normalize_artifact_with_format(&fetch(IrArtifactKind::Ixbrl, "<html><body><p>unclosed</body></html>"), IrNormalizedFormat::Ixbrl).unwrap();
+    assert_eq!(c.support_level, IrNormalizationSupport::Partial);
+    assert!(c.facts.is_empty());
+}
+
+#[test]
+fn inline_xbrl_takes_sign_only_from_attribute_and_rejects_extreme_scale() {
+    let raw = r#"<html xmlns="http://www.w3.org/1999/xhtml" xmlns:ix="http://www.xbrl.org/2013/inlineXBRL" xmlns:jppfs_cor="http://disclosure.edinet-fsa.go.jp/taxonomy/jppfs/2024-11-01/jppfs_cor"><body>
+<p><ix:nonFraction name="jppfs_cor:OperatingIncome" contextRef="CurrentYearDuration" unitRef="JPY" decimals="-6" scale="6">△1,000</ix:nonFraction></p>
+<p><ix:nonFraction name="jppfs_cor:OrdinaryIncome" contextRef="CurrentYearDuration" unitRef="JPY" decimals="-6" scale="6" sign="-">△1,000</ix:nonFraction></p>
+<p><ix:nonFraction name="jppfs_cor:NetSales" contextRef="CurrentYearDuration" unitRef="JPY" decimals="0" scale="400">1</ix:nonFraction></p>
+</body></html>"#;
+    let c = normalize_artifact_with_format(&fetch(IrArtifactKind::Ixbrl, raw), IrNormalizedFormat::Ixbrl).unwrap();
+    let fact = |local: &str| c.facts.iter().find(|f| f.local_name == local).unwrap();
+    assert_eq!(fact("OperatingIncome").numeric_value.as_deref(), Some("1000000000"));
+    assert_eq!(fact("OrdinaryIncome").numeric_value.as_deref(), Some("-1000000000"));
+
+    let sales = fact("NetSales");
+    assert_eq!(sales.value, "1");
+    assert_eq!(sales.numeric_value, None);
+    assert_eq!(sales.scale, None);
+    assert_eq!(sales.provenance.extra["reason_code"], IrNormalizationReasonCode::MalformedXbrl.as_str());
+    assert_eq!(sales.provenance.extra["scale"], "400");
+    assert_eq!(c.support_level, IrNormalizationSupport::Partial);
+    assert_eq!(c.provenance.extra["reason_code"], IrNormalizationReasonCode::MalformedXbrl.as_str());
+    assert!(c.financial_items.iter().all(|i| i.concept != IrFinancialConcept::Revenue));
+}
@@ -0,0 +1,366 @@
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+use ucel_core::IrDocumentDescriptor;
+use ucel_ir::{
+    statutory_adapter, timely_adapter, FsCheckpointStore, IrArtifactFetchRequest,
+    IrArtifactFetchResponse, IrArtifactListRequest, IrArtifactListResponse,
+    IrDiscoverIssuersRequest, IrDiscoverIssuersResponse, IrDocumentDetailRequest,
+    IrDocumentDetailResponse, IrDocumentListRequest, IrDocumentListResponse,
+    IrIssuerResolutionInput, IrIssuerResolutionResult, IrSourceAdapter, IrSyncCursor,
+    IrSyncPoliteness, IrSyncRunner, IrSyncSourceConfig, IrSyncSourceStatus, JpOfficialAdapter,
+    JpPolitenessPolicy, MemoryCheckpointStore, MemoryDocumentSink, UcelIrError, UcelIrErrorKind,
+};
+
+/// EDINET adapter whose listing is scripted: a growing document feed and injected failures.
+struct ScriptedAdapter {
+    inner: JpOfficialAdapter,
+    /// Documents tagged with an issuer are only listed by that issuer's query.
+    documents: Mutex<Vec<(Option<String>, IrDocumentDescriptor)>>,
+    failures: Mutex<Vec<UcelIrErrorKind>>,
+    queried: Mutex<Vec<Option<String>>>,
+}
+
+impl ScriptedAdapter {
+    fn new() -> Arc<Self> {
+        Arc::new(Self {
+            inner: statutory_adapter(),
+            documents: Mutex::new(vec![]),
+            failures: Mutex::new(vec![]),
+            queried: Mutex::new(vec![]),
+        })
+    }
+
+    fn publish(&self, id: &str, published_at: &str) {
+        self.publish_for(None, id, published_at);
+    }
+
+    fn publish_for(&self, issuer: Option<&str>, id: &str, published_at: &str) {
+        let mut doc = self
+            .inner
+            .list_documents(&list_request())
+            .unwrap()
+            .documents[0]
+            .clone();
+        doc.key.source_document_id = id.into();
+        doc.published_at = Some(published_at.into());
+        self.documents
+            .lock()
+            .unwrap()
+            .push((issuer.map(str::to_string), doc));
+    }
+
+    fn fail_next(&self, kind: UcelIrErrorKind, times: usize) {
+        self.failures
+            .lock()
+            .unwrap()
+            .extend(std::iter::repeat_n(kind, times));
+    }
+}
+
+fn list_request() -> IrDocumentListRequest {
+    IrDocumentListRequest {
+        source_id: "edinet_api_documents_v2".into(),
+        market: ucel_core::IrMarket::Jp,
+        issuer_key: None,
+    }
+}
+
+impl IrSourceAdapter for ScriptedAdapter {
+    fn source_descriptor(&self) -> ucel_core::IrSourceDescriptor {
+        self.inner.source_descriptor()
+    }
+    fn discover_issuers(
+        &self,
+        request: &IrDiscoverIssuersRequest,
+    ) -> Result<IrDiscoverIssuersResponse, UcelIrError> {
+        self.inner.discover_issuers(request)
+    }
+    fn resolve_issuer(
+        &self,
+        input: &IrIssuerResolutionInput,
+    ) -> Result<IrIssuerResolutionResult, UcelIrError> {
+        self.inner.resolve_issuer(input)
+    }
+    fn list_documents(
+        &self,
+        request: &IrDocumentListRequest,
+    ) -> Result<IrDocumentListResponse, UcelIrError> {
+        self.queried
+            .lock()
+            .unwrap()
+            .push(request.issuer_key.clone());
+        if let Some(kind) = self.failures.lock().unwrap().pop() {
+            return Err(UcelIrError::new(kind, "scripted failure"));
+        }
+        let documents = self
+            .documents
+            .lock()
+            .unwrap()
+            .iter()
+            .filter(|(issuer, _)| issuer.is_none() || *issuer == request.issuer_key)
+            .map(|(_, doc)| doc.clone())
+            .collect();
+        Ok(IrDocumentListResponse {
+            documents,
+            next_cursor: None,
+        })
+    }
+    fn fetch_document_detail(
+        &self,
+        request: &IrDocumentDetailRequest,
+    ) -> Result<IrDocumentDetailResponse, UcelIrError> {
+        self.inner.fetch_document_detail(request)
+    }
+    fn list_artifacts(
+        &self,
+        request: &IrArtifactListRequest,
+    ) -> Result<IrArtifactListResponse, UcelIrError> {
+        self.inner.list_artifacts(request)
+    }
+    fn fetch_artifact(
+        &self,
+        request: &IrArtifactFetchRequest,
+    ) -> Result<IrArtifactFetchResponse, UcelIrError> {
+        self.inner.fetch_artifact(request)
+    }
+}
+
+fn no_pacing(_: Duration) {}
+
+fn ids(sink: &MemoryDocumentSink) -> Vec<String> {
+    sink.documents()
+        .into_iter()
+        .map(|d| d.key.source_document_id)
+        .collect()
+}
+
+#[test]
+fn ir_sync_runner_emits_only_new_documents_and_resumes_from_checkpoint() {
+    let dir = tempfile::tempdir().unwrap();
+    let adapter = ScriptedAdapter::new();
+    adapter.publish("S100A001", "2026-06-20T09:00:00Z");
+    adapter.publish("S100A002", "2026-06-20T10:00:00Z");
+
+    let sink = Arc::new(MemoryDocumentSink::default());
+    let store = Arc::new(FsCheckpointStore::new(dir.path()).unwrap());
+    let mut runner = IrSyncRunner::new(store.clone(), sink.clone()).with_pacing(no_pacing);
+    runner.add_source(
+        IrSyncSourceConfig::new(adapter.clone(), JpPolitenessPolicy::default())
+            .with_poll_interval(Duration::from_secs(60)),
+    );
+
+    let t0 = Instant::now();
+    let pass = runner.poll_due(t0);
+    assert_eq!(pass.polled, vec!["edinet_api_documents_v2".to_string()]);
+    assert_eq!(pass.new_documents, 2);
+    // not due again before the poll interval elapses
+    assert!(runner
+        .poll_due(t0 + Duration::from_secs(30))
+        .polled
+        .is_empty());
+
+    adapter.publish("S100A003", "2026-06-20T10:00:00Z");
+    let pass = runner.poll_due(t0 + Duration::from_secs(60));
+    assert_eq!(pass.new_documents, 1);
+    assert_eq!(ids(&sink), vec!["S100A001", "S100A002", "S100A003"]);
+
+    let m = &runner.metrics()["edinet_api_documents_v2"];
+    assert_eq!(m.status, IrSyncSourceStatus::Ok);
+    assert_eq!(m.documents_new, 3);
+    assert_eq!(m.deduplicated, 2);
+    assert_eq!(m.watermark.as_deref(), Some("2026-06-20T10:00:00Z"));
+
+    // restart: a fresh runner over the same checkpoint dir re-emits nothing
+    let cursor = IrSyncCursor::load(store.as_ref(), "edinet_api_documents_v2").unwrap();
+    assert_eq!(cursor.recent_ids.len(), 3);
+    let resumed_sink = Arc::new(MemoryDocumentSink::default());
+    let mut resumed = IrSyncRunner::new(
+        Arc::new(FsCheckpointStore::new(dir.path()).unwrap()),
+        resumed_sink.clone(),
+    )
+    .with_pacing(no_pacing);
+    resumed.add_source(IrSyncSourceConfig::new(
+        adapter.clone(),
+        JpPolitenessPolicy::default(),
+    ));
+    adapter.publish("S100A004", "2026-06-21T08:00:00Z");
+    assert_eq!(resumed.poll_due(Instant::now()).new_documents, 1);
+    assert_eq!(ids(&resumed_sink), vec!["S100A004"]);
+}
+
+#[test]
+fn ir_sync_runner_backs_off_and_reports_failing_source_in_health() {
+    let adapter = ScriptedAdapter::new();
+    adapter.publish("S100B001", "2026-06-20T09:00:00Z");
+    let politeness = IrSyncPoliteness {
+        retry_budget: 1,
+        base_backoff_ms: 1_000,
+        max_backoff_ms: 3_000,
+        request_budget: None,
+    };
+    let mut runner = IrSyncRunner::new(
+        Arc::new(MemoryCheckpointStore::default()),
+        Arc::new(MemoryDocumentSink::default()),
+    )
+    .with_pacing(no_pacing);
+    runner.add_source(IrSyncSourceConfig::new(adapter.clone(), politeness));
+    adapter.fail_next(UcelIrErrorKind::Http, 3);
+
+    let t0 = Instant::now();
+    runner.poll_due(t0);
+    let m = &runner.metrics()["edinet_api_documents_v2"];
+    assert_eq!(m.status, IrSyncSourceStatus::Backoff);
+    assert_eq!(runner.next_due(), Some(t0 + Duration::from_millis(1_000)));
+    assert!(runner.health_reasons().is_empty());
+
+    let t1 = t0 + Duration::from_millis(1_000);
+    runner.poll_due(t1);
+    assert_eq!(runner.next_due(), Some(t1 + Duration::from_millis(2_000)));
+    assert_eq!(
+        runner.metrics()["edinet_api_documents_v2"].status,
+        IrSyncSourceStatus::Failing
+    );
+    assert_eq!(
+        runner.health_reasons(),
+        vec!["source_failing:edinet_api_documents_v2".to_string()]
+    );
+
+    let t2 = t1 + Duration::from_millis(2_000);
+    runner.poll_due(t2);
+    assert_eq!(runner.next_due(), Some(t2 + Duration::from_millis(3_000)));
+
+    // recovery resets the failure streak and the schedule
+    runner.poll_due(t2 + Duration::from_millis(3_000));
+    let m = &runner.metrics()["edinet_api_documents_v2"];
+    assert_eq!(m.status, IrSyncSourceStatus::Ok);
+    assert_eq!(m.consecutive_failures, 0);
+    assert_eq!(m.errors, 3);
+    assert_eq!(m.documents_new, 1);
+    assert!(runner.health_reasons().is_empty());
+}
+
+#[test]
+fn ir_sync_runner_respects_review_gate_and_policy_errors() {
+    let sink = Arc::new(MemoryDocumentSink::default());
+    let mut runner = IrSyncRunner::new(Arc::new(MemoryCheckpointStore::default()), sink.clone())
+        .with_pacing(no_pacing);
+    runner.add_source(IrSyncSourceConfig::new(
+        Arc::new(timely_adapter()),
+        JpPolitenessPolicy::default(),
+    ));
+    assert_eq!(
+        runner.metrics()["jp_tdnet_timely_html"].status,
+        IrSyncSourceStatus::ReviewRequired
+    );
+    assert!(runner.poll_due(Instant::now()).polled.is_empty());
+    assert_eq!(
+        runner.health_reasons(),
+        vec!["no_runnable_sources".to_string()]
+    );
+
+    let adapter = ScriptedAdapter::new();
+    adapter.fail_next(UcelIrErrorKind::Policy, 1);
+    runner.add_source(IrSyncSourceConfig::new(
+        adapter,
+        JpPolitenessPolicy::default(),
+    ));
+    runner.poll_due(Instant::now());
+    assert_eq!(
+        runner.metrics()["edinet_api_documents_v2"].status,
+        IrSyncSourceStatus::Blocked
+    );
+    assert_eq!(runner.next_due(), None);
+
+    let mut approved = IrSyncRunner::new(Arc::new(MemoryCheckpointStore::default()), sink.clone())
+        .with_pacing(no_pacing);
+    approved.add_source(
+        IrSyncSourceConfig::new(Arc::new(timely_adapter()), JpPolitenessPolicy::default())
+            .review_approved(),
+    );
+    let pass = approved.poll_due(Instant::now());
+    assert!(pass.new_documents > 0);
+    assert!(sink
+        .documents()
+        .iter()
+        .all(|d| d.key.source_id == "jp_tdnet_timely_html"));
+}
+
+#[test]
+fn ir_sync_runner_run_loop_stops_on_flag() {
+    let adapter = ScriptedAdapter::new();
+    adapter.publish("S100C001", "2026-06-20T09:00:00Z");
+    let mut runner = IrSyncRunner::new(
+        Arc::new(MemoryCheckpointStore::default()),
+        Arc::new(MemoryDocumentSink::default()),
+    )
+    .with_pacing(no_pacing);
+    runner.add_source(IrSyncSourceConfig::new(
+        adapter,
+        JpPolitenessPolicy::default(),
+    ));
+    let stop = AtomicBool::new(false);
+    let mut passes = 0;
+    runner.run(&stop, |r, _| {
+        passes += 1;
+        assert_eq!(r.metrics()["edinet_api_documents_v2"].documents_new, 1);
+        stop.store(true, Ordering::Relaxed);
+    });
+    assert_eq!(passes, 1);
+}
+
+#[test]
+fn ir_sync_runner_tracks_issuers_separately_and_rotates_under_budget() {
+    let adapter = ScriptedAdapter::new();
+    adapter.publish_for(Some("E00001"), "S100D001", "2026-06-20T12:00:00Z");
+    adapter.publish_for(Some("E00002"), "S100D002", "2026-06-20T09:00:00Z");
+    adapter.publish_for(Some("E00003"), "S100D003", "2026-06-20T10:00:00Z");
+    let politeness = IrSyncPoliteness {
+        request_budget: Some(2),
+        ..JpPolitenessPolicy::default().into()
+    };
+    let sink = Arc::new(MemoryDocumentSink::default());
+    let store = Arc::new(MemoryCheckpointStore::default());
+    let mut runner = IrSyncRunner::new(store.clone(), sink.clone()).with_pacing(no_pacing);
+    runner.add_source(
+        IrSyncSourceConfig::new(adapter.clone(), politeness)
+            .with_issuer_keys(vec!["E00001".into(), "E00002".into(), "E00003".into()])
+            .with_poll_interval(Duration::from_secs(60)),
+    );
+
+    // E00002 files older documents than E00001's newest; they are still new for E00002
+    let t0 = Instant::now();
+    assert_eq!(runner.poll_due(t0).new_documents, 2);
+    assert_eq!(ids(&sink), vec!["S100D001", "S100D002"]);
+
+    // the budget covers two issuers per poll; the next poll starts with the one skipped
+    adapter.publish_for(Some("E00002"), "S100D004", "2026-06-20T09:30:00Z");
+    assert_eq!(
+        runner.poll_due(t0 + Duration::from_secs(60)).new_documents,
+        1
+    );
+    assert_eq!(
+        runner.poll_due(t0 + Duration::from_secs(120)).new_documents,
+        1
+    );
+    assert_eq!(
+        ids(&sink),
+        vec!["S100D001", "S100D002", "S100D003", "S100D004"]
+    );
+    let queried: Vec<String> = adapter
+        .queried
+        .lock()
+        .unwrap()
+        .iter()
+        .map(|k| k.clone().unwrap())
+        .collect();
+    assert_eq!(
+        queried,
+        vec!["E00001", "E00002", "E00003", "E00001", "E00002", "E00003"]
+    );
+
+    let cursor = IrSyncCursor::load(store.as_ref(), "edinet_api_documents_v2").unwrap();
+    assert_eq!(cursor.issuer_watermarks["E00002"], "2026-06-20T09:30:00Z");
+    assert_eq!(cursor.watermark.as_deref(), Some("2026-06-20T12:00:00Z"));
+    assert_eq!(cursor.next_issuer, 0);
+}
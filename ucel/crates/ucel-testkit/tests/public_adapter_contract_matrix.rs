@@ -8,7 +8,10 @@ fn public_adapter_support_state_is_defined_for_target_venues() {
         matrix.get("binance"),
         Some(&PublicAdapterSupport::Supported)
     );
-    assert_eq!(matrix.get("bithumb"), Some(&PublicAdapterSupport::Partial));
+    assert_eq!(
+        matrix.get("bithumb"),
+        Some(&PublicAdapterSupport::Supported)
+    );
     assert_eq!(matrix.get("upbit"), Some(&PublicAdapterSupport::Supported));
     assert!(matrix
         .values()
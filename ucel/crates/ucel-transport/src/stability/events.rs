@@ -9,6 +9,7 @@ pub enum ReconnectReason {
     ReadError,
     CloseFrame,
     CircuitOpenWait,
+    UnsubscribeUnsupported,
     Shutdown,
 }
 
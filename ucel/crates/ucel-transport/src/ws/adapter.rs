@@ -49,6 +49,15 @@ pub trait WsVenueAdapter: Send + Sync + 'static {
         symbol: &str,
         params: &Value,
     ) -> Result<Vec<OutboundMsg>, String>;
+    /// Inverse of `build_subscribe` for the same (op_id, symbol, params).
+    /// Venues without an unsubscribe frame return Err; the transport then drops the
+    /// stream from the store and relies on the next reconnect to stop it.
+    fn build_unsubscribe(
+        &self,
+        op_id: &str,
+        symbol: &str,
+        params: &Value,
+    ) -> Result<Vec<OutboundMsg>, String>;
     fn classify_inbound(&self, raw: &[u8]) -> InboundClass;
 
     /// periodic app ping (Bybit/Bitget/Kraken/OKX)
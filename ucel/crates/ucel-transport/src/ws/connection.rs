@@ -1,4 +1,5 @@
 use std::collections::VecDeque;
+use std::sync::atomic::AtomicBool;
 use std::sync::Arc;
 use std::time::{Duration, Instant};
 
@@ -81,6 +82,18 @@ pub struct WsRunConfig {
     pub rl_max_cooldown_secs: i64,
     /// fallback RL penalty when retry_after is absent
     pub rl_default_penalty_ms: u64,
+
+    /// close this connection (without triggering the shared shutdown token) once the store
+    /// has no subscription assigned to it; set by live replanning when a shard empties
+    pub close_when_drained: bool,
+
+    /// sent unsubscribes without an ack after this long are treated as done (many venues
+    /// never ack an unsubscribe)
+    pub unsubscribe_ack_timeout: Duration,
+
+    /// this connection's transport metrics; pass one in to sample them from outside
+    /// (e.g. inbound msgs/sec for load-aware sharding), a fresh set is used when None
+    pub metrics: Option<Arc<TransportMetrics>>,
 }
 
 #[derive(Clone, Debug)]
@@ -133,6 +146,9 @@ impl Default for WsRunConfig {
             rl_base_cooldown_secs: 1,
             rl_max_cooldown_secs: 60,
             rl_default_penalty_ms: 500,
+            close_when_drained: false,
+            unsubscribe_ack_timeout: Duration::from_secs(10),
+            metrics: None,
         }
     }
 }
@@ -370,7 +386,7 @@ pub async fn run_ws_connection(
     let overflow_policy = build_overflow_policy(&cfg)?;
     let mut breaker = CircuitBreaker::new(cfg.breaker.clone());
     let stability = Arc::new(StabilityHub::new());
-    let obs_metrics = TransportMetrics::new();
+    let obs_metrics = cfg.metrics.clone().unwrap_or_else(TransportMetrics::new);
     let obs_events = StabilityEventRing::new(512);
     let obs_required = ObsRequiredKeys::try_new_wildcard_symbol(
         cfg.exchange_id.clone(),
@@ -402,7 +418,9 @@ pub async fn run_ws_connection(
         }
 
         // Always start by requeueing active/inflight to pending before a fresh connect.
+        // A fresh socket carries no streams, so outstanding unsubscribes are done as well.
         let _ = store.requeue_active_to_pending(&cfg.exchange_id, &cfg.conn_id, now_unix_i64());
+        let _ = store.settle_unsubscribing(&cfg.exchange_id, &cfg.conn_id, now_unix_i64());
 
         // Storm guard
         let nowi = Instant::now();
@@ -517,17 +535,23 @@ pub async fn run_ws_connection(
         // WAL queue
         let (wal_tx, mut wal_rx) = mpsc::channel::<RawRecord>(cfg.wal_queue_cap);
 
+        // Per-connection close signal (drained shard); the shared token still stops everything.
+        let conn_closing = ShutdownToken {
+            flag: Arc::new(AtomicBool::new(false)),
+        };
+
         // WAL writer task (drain-friendly)
         let wal_writer = {
             let wal = wal.clone();
             let exchange_id = cfg.exchange_id.clone();
             let conn_id = cfg.conn_id.clone();
             let shutdown2 = shutdown.clone();
+            let closing2 = conn_closing.clone();
             let stability2 = stability.clone();
             let obs_metrics2 = obs_metrics.clone();
             tokio::spawn(async move {
                 loop {
-                    if shutdown2.is_triggered() && wal_rx.is_empty() {
+                    if (shutdown2.is_triggered() || closing2.is_triggered()) && wal_rx.is_empty() {
                         break;
                     }
                     stability2.set_gauge("wal_queue_len", wal_rx.len() as i64);
@@ -558,13 +582,16 @@ pub async fn run_ws_connection(
             let exchange_id = cfg.exchange_id.clone();
             let conn_id = cfg.conn_id.clone();
             let shutdown2 = shutdown.clone();
+            let closing2 = conn_closing.clone();
             let outq2 = outq.clone();
             let ws_limiter2 = ws_limiter.clone();
             let stability2 = stability.clone();
             let obs_metrics2 = obs_metrics.clone();
             tokio::spawn(async move {
                 loop {
-                    if shutdown2.is_triggered() && outq2.is_empty().await {
+                    if (shutdown2.is_triggered() || closing2.is_triggered())
+                        && outq2.is_empty().await
+                    {
                         break;
                     }
                     stability2.set_gauge("outq_len", outq2.len() as i64);
@@ -613,11 +640,19 @@ pub async fn run_ws_connection(
         let mut last_drip = Instant::now();
         let mut last_periodic_ping = Instant::now();
         let mut last_stale_sweep = Instant::now();
+        let mut drained = false;
 
         // === Inner run loop ===
         loop {
             // Shutdown => graceful path (close->flush->requeue->join)
-            if shutdown.is_triggered() {
+            // drained => same path, but with a local token so sibling connections keep running
+            if shutdown.is_triggered() || drained {
+                let token = if drained {
+                    info!(exchange_id=%cfg.exchange_id, conn=%cfg.conn_id, "no subscriptions assigned -> closing drained connection");
+                    conn_closing.clone()
+                } else {
+                    shutdown.clone()
+                };
                 stability.emit(TransportStabilityEvent::ConnectionState {
                     exchange_id: cfg.exchange_id.clone(),
                     conn_id: cfg.conn_id.clone(),
@@ -634,7 +669,7 @@ pub async fn run_ws_connection(
                     &cfg.conn_id,
                     store,
                     &outq,
-                    &shutdown,
+                    &token,
                     writer,
                     wal_writer,
                 )
@@ -777,13 +812,126 @@ pub async fn run_ws_connection(
                         "stale subscriptions requeued -> will resubscribe"
                     );
                 }
+
+                let expired = store
+                    .expire_unsubscribing(
+                        &cfg.exchange_id,
+                        &cfg.conn_id,
+                        cfg.unsubscribe_ack_timeout.as_secs() as i64,
+                        now,
+                    )
+                    .unwrap_or(0);
+                if expired > 0 {
+                    info!(
+                        exchange_id=%cfg.exchange_id,
+                        conn=%cfg.conn_id,
+                        expired,
+                        "unsubscribe ack timeout -> removed"
+                    );
+                }
+
+                if cfg.close_when_drained {
+                    drained = store
+                        .state_counts(&cfg.exchange_id, &cfg.conn_id)
+                        .map(|c| c.total() == 0)
+                        .unwrap_or(false);
+                }
             }
 
-            // Drip pending subscriptions (private priority via op_id classifier)
+            // Drip pending unsubscribes first (frees venue stream slots), then subscriptions
+            // (private priority via op_id classifier)
             if last_drip.elapsed() >= Duration::from_millis(200) {
                 last_drip = Instant::now();
                 let now = now_unix_i64();
 
+                let keys = store.next_unsubscribe_batch(
+                    &cfg.exchange_id,
+                    &cfg.conn_id,
+                    cfg.max_inflight_per_conn,
+                    now,
+                )?;
+
+                let mut unsubscribe_by_reconnect = false;
+                for key in keys {
+                    let Some((_ex, op_id, sym_opt, params_canon)) = parse_stable_key(&key) else {
+                        store.mark_removed(&key, Some("bad_key_format"), now)?;
+                        continue;
+                    };
+                    let params: Value = serde_json::from_str(params_canon)
+                        .unwrap_or_else(|_| serde_json::json!({}));
+
+                    // no unsubscribe frame for this venue/op -> the stream only stops with the
+                    // socket, so the key stays unsubscribing and this connection is recycled;
+                    // the fresh socket settles it (and a moved key is released to its new conn)
+                    let msgs = match adapter.build_unsubscribe(
+                        op_id,
+                        sym_opt.unwrap_or(""),
+                        &params,
+                    ) {
+                        Ok(m) => m,
+                        Err(e) => {
+                            info!(exchange_id=%cfg.exchange_id, conn=%cfg.conn_id, key=%key, reason=%e, "venue cannot unsubscribe -> reconnect to drop the stream");
+                            unsubscribe_by_reconnect = true;
+                            continue;
+                        }
+                    };
+
+                    let prio = match classify_op_id_priority(op_id) {
+                        OutboundPriority::Private => OutboundPriority::Private,
+                        _ => OutboundPriority::Public,
+                    };
+                    let w = {
+                        let mut lim = ws_limiter.lock().await;
+                        lim.acquire_wait(prio, Instant::now())
+                    };
+                    if w > Duration::from_secs(0) {
+                        tokio::time::sleep(w).await;
+                    }
+
+                    for m in msgs {
+                        let out = outq
+                            .push(
+                                &cfg.exchange_id,
+                                &cfg.conn_id,
+                                QueuedOutbound {
+                                    priority: prio,
+                                    op_id: Some(op_id.to_string()),
+                                    symbol: sym_opt.map(|s| s.to_string()),
+                                    frame: WsOutboundFrame::Text(m.text),
+                                    meta: serde_json::json!({"kind":"unsubscribe"}),
+                                },
+                                &overflow_policy,
+                                now_unix_u64(),
+                            )
+                            .await
+                            .unwrap_or(PushOutcome::Dropped);
+                        stability.emit(TransportStabilityEvent::OutqOverflowOutcome {
+                            exchange_id: cfg.exchange_id.clone(),
+                            conn_id: cfg.conn_id.clone(),
+                            outcome: map_outcome(out),
+                        });
+                        match out {
+                            PushOutcome::Enqueued => {}
+                            PushOutcome::Dropped => {
+                                warn!(exchange_id=%cfg.exchange_id, conn=%cfg.conn_id, op_id=%op_id, priority=%prio.as_str(), "outbound overflow -> dropped");
+                            }
+                            PushOutcome::Spilled => {
+                                warn!(exchange_id=%cfg.exchange_id, conn=%cfg.conn_id, op_id=%op_id, priority=%prio.as_str(), "outbound overflow -> spilled-to-disk");
+                            }
+                        }
+                    }
+                }
+
+                if unsubscribe_by_reconnect {
+                    stability.emit(TransportStabilityEvent::ReconnectAttempt {
+                        exchange_id: cfg.exchange_id.clone(),
+                        conn_id: cfg.conn_id.clone(),
+                        reason: ReconnectReason::UnsubscribeUnsupported,
+                        attempt: reconnect_attempt as u64,
+                    });
+                    break;
+                }
+
                 let keys = store.next_pending_batch(
                     &cfg.exchange_id,
                     &cfg.conn_id,
@@ -1208,8 +1356,13 @@ async fn handle_inbound(
                 params_canon,
             )?;
             if let Some(k) = key {
-                store.mark_active(&k, now)?;
-                store.bump_last_message(&k, now)?;
+                // the same ack shape confirms an unsubscribe for rows being removed
+                if store.state_of(&k)?.as_deref() == Some("unsubscribing") {
+                    store.mark_removed(&k, None, now)?;
+                } else {
+                    store.mark_active(&k, now)?;
+                    store.bump_last_message(&k, now)?;
+                }
             }
         }
         InboundClass::Data {
@@ -1253,7 +1406,22 @@ async fn handle_inbound(
                 )?;
 
                 if let Some(k) = key {
-                    if is_rl {
+                    if store.state_of(&k)?.as_deref() == Some("unsubscribing") {
+                        // unsubscribe の nack: RL なら cooldown 後に再送、それ以外は購読されていないとみなし removed
+                        if is_rl {
+                            let cooldown_secs = retry_after_ms
+                                .map(|ms| ((ms as f64) / 1000.0).ceil() as i64)
+                                .unwrap_or(cfg.rl_base_cooldown_secs);
+                            store.requeue_unsubscribe(&k, now, cooldown_secs)?;
+                        } else {
+                            store.mark_removed(
+                                &k,
+                                Some(&format!("unsubscribe nack:{reason}")),
+                                now,
+                            )?;
+                        }
+                        warn!(exchange_id=%cfg.exchange_id, conn=%cfg.conn_id, key=%k, reason=%reason, rate_limited=is_rl, "ws nack for unsubscribe");
+                    } else if is_rl {
                         // attempts 上限で deadletter
                         let attempts = store.attempts_of(&k)?.unwrap_or(0);
                         if attempts >= cfg.rl_max_attempts {
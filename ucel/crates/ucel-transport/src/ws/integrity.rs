@@ -1,4 +1,7 @@
-use ucel_core::PublicWsReasonCode;
+use ucel_core::{
+    escalate_integrity_failure, failure_to_resume_directive, Decimal, IngestFailureClass,
+    IngestIntegrityMode, IngestResumeDirective, IngestRetryBudget, PublicWsReasonCode,
+};
 
 #[derive(Debug, Clone, Default)]
 pub struct DomesticPublicWsIntegrityState {
@@ -45,3 +48,95 @@ pub fn check_negative_qty(found: bool) -> Result<(), PublicWsReasonCode> {
     }
     Ok(())
 }
+
+/// 板 checksum（CRC32 / IEEE）。
+/// OKX / Bitget は i32、Kraken は u32 で配信するため、比較は u32 に揃えて行う。
+/// Bybit は板 checksum を配信しないため対象外（u / seq による連番検査のみ）。
+pub fn book_crc32(payload: &str) -> u32 {
+    crc32fast::hash(payload.as_bytes())
+}
+
+pub fn check_book_checksum(expected: u32, computed: u32) -> Result<(), PublicWsReasonCode> {
+    if expected != computed {
+        return Err(PublicWsReasonCode::ChecksumMismatch);
+    }
+    Ok(())
+}
+
+/// checksum 用に板の (price, qty) を文字列へ戻す。
+/// `Decimal` は受信時の桁（末尾ゼロ含む）を保持するので、venue の表記がそのまま再現される。
+pub fn checksum_levels<I>(levels: I) -> Vec<(String, String)>
+where
+    I: IntoIterator<Item = (Decimal, Decimal)>,
+{
+    levels
+        .into_iter()
+        .map(|(p, q)| (p.to_string(), q.to_string()))
+        .collect()
+}
+
+/// OKX / Bitget 方式: bid と ask を 1 段ずつ交互に "price:qty" で ':' 連結する。
+/// 片側が尽きたら残りの側だけを続ける。
+pub fn interleaved_checksum_payload<S: AsRef<str>>(bids: &[(S, S)], asks: &[(S, S)]) -> String {
+    let mut parts = Vec::with_capacity((bids.len() + asks.len()) * 2);
+    for i in 0..bids.len().max(asks.len()) {
+        if let Some((p, q)) = bids.get(i) {
+            parts.push(p.as_ref());
+            parts.push(q.as_ref());
+        }
+        if let Some((p, q)) = asks.get(i) {
+            parts.push(p.as_ref());
+            parts.push(q.as_ref());
+        }
+    }
+    parts.join(":")
+}
+
+/// checksum 不一致の連続回数を数え、resync / deadletter へ昇格させる。
+/// - 不一致: failure_to_resume_directive（Checksum 系 mode なら ResnapshotThenResubscribe）
+/// - 予算超過: escalate_integrity_failure が true になったら Deadletter
+/// - 一致: 連続回数をリセット
+#[derive(Debug, Clone)]
+pub struct BookChecksumGuard {
+    pub integrity_mode: IngestIntegrityMode,
+    pub budget: IngestRetryBudget,
+    pub consecutive_mismatches: u32,
+    pub mismatch_total: u64,
+}
+
+impl Default for BookChecksumGuard {
+    fn default() -> Self {
+        Self {
+            integrity_mode: IngestIntegrityMode::SequenceAndChecksum,
+            budget: IngestRetryBudget {
+                max_retries: 3,
+                max_checksum_retries: 3,
+                max_heartbeat_retries: 3,
+            },
+            consecutive_mismatches: 0,
+            mismatch_total: 0,
+        }
+    }
+}
+
+impl BookChecksumGuard {
+    pub fn verify(&mut self, expected: u32, computed: u32) -> Result<(), IngestResumeDirective> {
+        if check_book_checksum(expected, computed).is_ok() {
+            self.consecutive_mismatches = 0;
+            return Ok(());
+        }
+        self.consecutive_mismatches += 1;
+        self.mismatch_total += 1;
+        if escalate_integrity_failure(
+            self.consecutive_mismatches,
+            self.budget,
+            IngestFailureClass::ChecksumMismatch,
+        ) {
+            return Err(IngestResumeDirective::Deadletter);
+        }
+        Err(failure_to_resume_directive(
+            IngestFailureClass::ChecksumMismatch,
+            self.integrity_mode,
+        ))
+    }
+}
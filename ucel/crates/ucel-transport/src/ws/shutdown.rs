@@ -105,8 +105,9 @@ pub async fn graceful_shutdown_ws(
 
     outq.close();
 
-    // 3) requeue
+    // 3) requeue (closing the socket also completes outstanding unsubscribes)
     let _ = store.requeue_active_to_pending(exchange_id, conn_id, now_unix_i64());
+    let _ = store.settle_unsubscribing(exchange_id, conn_id, now_unix_i64());
 
     // 4) join
     let join_writer = tokio::time::timeout(cfg.join_timeout, &mut writer).await;
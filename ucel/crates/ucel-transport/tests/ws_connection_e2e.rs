@@ -23,6 +23,8 @@ use ucel_ws_rules::load_for_exchange;
 struct TestAdapter {
     exchange_id: String,
     url: String,
+    /// false: unsubscribe フレームの無い venue（Bithumb 相当）
+    can_unsubscribe: bool,
 }
 
 #[async_trait::async_trait]
@@ -55,11 +57,37 @@ impl WsVenueAdapter for TestAdapter {
         }])
     }
 
+    fn build_unsubscribe(
+        &self,
+        op_id: &str,
+        symbol: &str,
+        _params: &Value,
+    ) -> Result<Vec<OutboundMsg>, String> {
+        if !self.can_unsubscribe {
+            return Err("venue has no unsubscribe".to_string());
+        }
+        Ok(vec![OutboundMsg {
+            text: json!({
+                "command":"unsubscribe",
+                "op_id": op_id,
+                "symbol": symbol
+            })
+            .to_string(),
+        }])
+    }
+
     fn classify_inbound(&self, raw: &[u8]) -> InboundClass {
         let v: Value = match serde_json::from_slice(raw) {
             Ok(x) => x,
             Err(_) => return InboundClass::Unknown,
         };
+        if v.get("ack").is_some() {
+            return InboundClass::Ack {
+                op_id: v["op_id"].as_str().unwrap_or_default().to_string(),
+                symbol: v["symbol"].as_str().map(|s| s.to_string()),
+                params_canon_hint: Some("{}".to_string()),
+            };
+        }
         let op_id = v
             .get("op_id")
             .and_then(|x| x.as_str())
@@ -246,6 +274,7 @@ async fn e2e_reconnect_drip_wal() {
     let adapter: Arc<dyn WsVenueAdapter> = Arc::new(TestAdapter {
         exchange_id: "gmocoin".to_string(),
         url: format!("ws://{addr}"),
+        can_unsubscribe: true,
     });
 
     let shutdown = ShutdownToken {
@@ -283,6 +312,9 @@ async fn e2e_reconnect_drip_wal() {
         rl_base_cooldown_secs: 1,
         rl_max_cooldown_secs: 60,
         rl_default_penalty_ms: 500,
+        close_when_drained: false,
+        unsubscribe_ack_timeout: Duration::from_secs(10),
+        metrics: None,
 
         graceful: default_graceful(),
     };
@@ -336,6 +368,7 @@ async fn e2e_stop_on_oversized_frame() {
     let adapter: Arc<dyn WsVenueAdapter> = Arc::new(TestAdapter {
         exchange_id: "gmocoin".to_string(),
         url: format!("ws://{addr}"),
+        can_unsubscribe: true,
     });
 
     let shutdown = ShutdownToken {
@@ -373,6 +406,9 @@ async fn e2e_stop_on_oversized_frame() {
         rl_base_cooldown_secs: 1,
         rl_max_cooldown_secs: 60,
         rl_default_penalty_ms: 500,
+        close_when_drained: false,
+        unsubscribe_ack_timeout: Duration::from_secs(10),
+        metrics: None,
 
         graceful: default_graceful(),
     };
@@ -427,6 +463,7 @@ async fn e2e_symbol_less_subscription_is_dripped() {
     let adapter: Arc<dyn WsVenueAdapter> = Arc::new(TestAdapter {
         exchange_id: "gmocoin".to_string(),
         url: format!("ws://{addr}"),
+        can_unsubscribe: true,
     });
 
     let shutdown = ShutdownToken {
@@ -464,6 +501,9 @@ async fn e2e_symbol_less_subscription_is_dripped() {
         rl_base_cooldown_secs: 1,
         rl_max_cooldown_secs: 60,
         rl_default_penalty_ms: 500,
+        close_when_drained: false,
+        unsubscribe_ack_timeout: Duration::from_secs(10),
+        metrics: None,
 
         graceful: default_graceful(),
     };
@@ -488,3 +528,308 @@ async fn e2e_symbol_less_subscription_is_dripped() {
         })
         .await;
 }
+
+#[tokio::test(flavor = "current_thread")]
+async fn e2e_drained_connection_closes_without_shared_shutdown() {
+    let (addr, stop_tx, _received) = spawn_fake_ws_server(false, false, 0).await;
+
+    let tmp = tempfile::tempdir().unwrap();
+    let rules_dir = tmp.path().join("rules");
+    write_rules_toml(&rules_dir, "gmocoin", 10);
+    let rules = load_for_exchange(&rules_dir, "gmocoin");
+
+    let wal = ucel_journal::WalWriter::open(
+        tmp.path().join("wal"),
+        64 * 1024 * 1024,
+        ucel_journal::FsyncMode::Balanced,
+    )
+    .unwrap();
+    let wal = Arc::new(Mutex::new(wal));
+
+    // nothing assigned to this connection (its shard was emptied by a replan)
+    let mut store = SubscriptionStore::open(":memory:").unwrap();
+
+    let adapter: Arc<dyn WsVenueAdapter> = Arc::new(TestAdapter {
+        exchange_id: "gmocoin".to_string(),
+        url: format!("ws://{addr}"),
+        can_unsubscribe: true,
+    });
+    let shutdown = ShutdownToken {
+        flag: Arc::new(AtomicBool::new(false)),
+    };
+    let cfg = WsRunConfig {
+        exchange_id: "gmocoin".to_string(),
+        conn_id: "gmocoin-conn-2".to_string(),
+        connect_timeout: Duration::from_secs(2),
+        idle_timeout: Duration::from_secs(10),
+        stale_sweep_interval: Duration::from_millis(200),
+        graceful: default_graceful(),
+        close_when_drained: true,
+        ..WsRunConfig::default()
+    };
+
+    let shared = shutdown.clone();
+    let local = LocalSet::new();
+    local
+        .run_until(async move {
+            let res = tokio::time::timeout(
+                Duration::from_secs(8),
+                run_ws_connection(adapter, rules, &mut store, wal, cfg, shutdown),
+            )
+            .await
+            .expect("drained connection should close on its own");
+            assert!(res.is_ok());
+            assert!(!shared.is_triggered(), "sibling connections keep running");
+        })
+        .await;
+    let _ = stop_tx.send(());
+}
+
+/// 疑似WSサーバ: subscribe には Data、unsubscribe には ack を返し続ける。
+/// 再接続も受け付け、受信フレームは "<接続番号> <frame>" で記録する
+async fn spawn_sub_unsub_server() -> (SocketAddr, Arc<Mutex<Vec<String>>>) {
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+    let received = Arc::new(Mutex::new(Vec::<String>::new()));
+    let received2 = received.clone();
+
+    tokio::spawn(async move {
+        let mut conn_no = 0;
+        while let Ok((stream, _)) = listener.accept().await {
+            conn_no += 1;
+            let received3 = received2.clone();
+            tokio::spawn(async move {
+                let ws = tokio_tungstenite::accept_async(stream).await.unwrap();
+                let (mut w, mut r) = ws.split();
+                while let Some(Ok(msg)) = r.next().await {
+                    let Message::Text(t) = msg else {
+                        continue;
+                    };
+                    received3.lock().await.push(format!("{conn_no} {t}"));
+                    let v: Value = serde_json::from_str(&t).unwrap();
+                    let mut reply = json!({"op_id": v["op_id"], "symbol": v["symbol"]});
+                    if v["command"] == "unsubscribe" {
+                        reply["ack"] = json!("unsubscribe");
+                    }
+                    let _ = w.send(Message::Text(reply.to_string())).await;
+                }
+            });
+        }
+    });
+
+    (addr, received)
+}
+
+async fn wait_for_state(store: &SubscriptionStore, key: &str, state: &str) {
+    for _ in 0..50 {
+        if store.state_of(key).unwrap().as_deref() == Some(state) {
+            return;
+        }
+        tokio::time::sleep(Duration::from_millis(100)).await;
+    }
+    panic!("{key} never reached {state}");
+}
+
+#[tokio::test(flavor = "current_thread")]
+async fn e2e_unsubscribe_is_sent_and_ack_removes_stream() {
+    let (addr, received) = spawn_sub_unsub_server().await;
+
+    let tmp = tempfile::tempdir().unwrap();
+    let rules_dir = tmp.path().join("rules");
+    write_rules_toml(&rules_dir, "gmocoin", 10);
+    let rules = load_for_exchange(&rules_dir, "gmocoin");
+
+    let wal = ucel_journal::WalWriter::open(
+        tmp.path().join("wal"),
+        64 * 1024 * 1024,
+        ucel_journal::FsyncMode::Balanced,
+    )
+    .unwrap();
+    let wal = Arc::new(Mutex::new(wal));
+
+    // file-backed so the test can replan through a second handle while the connection runs
+    let db = tmp.path().join("subs.sqlite");
+    let db = db.to_str().unwrap();
+    let mut store = SubscriptionStore::open(db).unwrap();
+    let k = SubscriptionKey {
+        exchange_id: "gmocoin".to_string(),
+        op_id: "crypto.public.ws.ticker.update".to_string(),
+        symbol: Some("BTC/JPY".to_string()),
+        params: json!({}),
+    };
+    let key = stable_key(&k);
+    store
+        .seed(
+            &[SubscriptionRow {
+                key: key.clone(),
+                exchange_id: "gmocoin".to_string(),
+                op_id: k.op_id.clone(),
+                symbol: k.symbol.clone(),
+                params_json: canon_params(&k.params),
+                assigned_conn: Some("gmocoin-conn-1".to_string()),
+            }],
+            1,
+        )
+        .unwrap();
+    let mut replanner = SubscriptionStore::open(db).unwrap();
+
+    let adapter: Arc<dyn WsVenueAdapter> = Arc::new(TestAdapter {
+        exchange_id: "gmocoin".to_string(),
+        url: format!("ws://{addr}"),
+        can_unsubscribe: true,
+    });
+    let shutdown = ShutdownToken {
+        flag: Arc::new(AtomicBool::new(false)),
+    };
+    let cfg = WsRunConfig {
+        exchange_id: "gmocoin".to_string(),
+        conn_id: "gmocoin-conn-1".to_string(),
+        connect_timeout: Duration::from_secs(2),
+        idle_timeout: Duration::from_secs(10),
+        stale_sweep_interval: Duration::from_millis(200),
+        graceful: default_graceful(),
+        close_when_drained: true,
+        ..WsRunConfig::default()
+    };
+
+    let local = LocalSet::new();
+    local
+        .run_until(async move {
+            let run = tokio::task::spawn_local(async move {
+                run_ws_connection(adapter, rules, &mut store, wal, cfg, shutdown).await
+            });
+
+            wait_for_state(&replanner, &key, "active").await;
+
+            // delisted by a replan while the stream is live
+            replanner
+                .apply_reassignment(&[], std::slice::from_ref(&key), &[], 2)
+                .unwrap();
+            wait_for_state(&replanner, &key, "removed").await;
+
+            // nothing left on the connection -> it closes itself
+            let res = tokio::time::timeout(Duration::from_secs(5), run)
+                .await
+                .expect("drained connection should close")
+                .unwrap();
+            assert!(res.is_ok());
+
+            let r = received.lock().await.clone();
+            assert!(r[0].contains("\"command\":\"subscribe\""), "{r:?}");
+            assert!(
+                r.iter().any(|m| m.contains("\"command\":\"unsubscribe\"")),
+                "{r:?}"
+            );
+        })
+        .await;
+}
+
+#[tokio::test(flavor = "current_thread")]
+async fn e2e_venue_without_unsubscribe_recycles_the_connection() {
+    let (addr, received) = spawn_sub_unsub_server().await;
+
+    let tmp = tempfile::tempdir().unwrap();
+    let rules_dir = tmp.path().join("rules");
+    write_rules_toml(&rules_dir, "bithumb", 10);
+    let rules = load_for_exchange(&rules_dir, "bithumb");
+
+    let wal = ucel_journal::WalWriter::open(
+        tmp.path().join("wal"),
+        64 * 1024 * 1024,
+        ucel_journal::FsyncMode::Balanced,
+    )
+    .unwrap();
+    let wal = Arc::new(Mutex::new(wal));
+
+    let db = tmp.path().join("subs.sqlite");
+    let db = db.to_str().unwrap();
+    let mut store = SubscriptionStore::open(db).unwrap();
+    let sub = |op_id: &str| SubscriptionKey {
+        exchange_id: "bithumb".to_string(),
+        op_id: op_id.to_string(),
+        symbol: Some("BTC/JPY".to_string()),
+        params: json!({}),
+    };
+    let keys = [
+        sub("crypto.public.ws.ticker.update"),
+        sub("crypto.public.ws.trades.update"),
+    ];
+    let rows: Vec<SubscriptionRow> = keys
+        .iter()
+        .map(|k| SubscriptionRow {
+            key: stable_key(k),
+            exchange_id: "bithumb".to_string(),
+            op_id: k.op_id.clone(),
+            symbol: k.symbol.clone(),
+            params_json: canon_params(&k.params),
+            assigned_conn: Some("bithumb-conn-1".to_string()),
+        })
+        .collect();
+    store.seed(&rows, 1).unwrap();
+    let (delisted, kept) = (stable_key(&keys[0]), stable_key(&keys[1]));
+    let mut replanner = SubscriptionStore::open(db).unwrap();
+
+    let adapter: Arc<dyn WsVenueAdapter> = Arc::new(TestAdapter {
+        exchange_id: "bithumb".to_string(),
+        url: format!("ws://{addr}"),
+        can_unsubscribe: false,
+    });
+    let shutdown = ShutdownToken {
+        flag: Arc::new(AtomicBool::new(false)),
+    };
+    let shutdown2 = shutdown.clone();
+    let cfg = WsRunConfig {
+        exchange_id: "bithumb".to_string(),
+        conn_id: "bithumb-conn-1".to_string(),
+        connect_timeout: Duration::from_secs(2),
+        idle_timeout: Duration::from_secs(10),
+        stale_sweep_interval: Duration::from_millis(200),
+        graceful: default_graceful(),
+        ..WsRunConfig::default()
+    };
+
+    let local = LocalSet::new();
+    local
+        .run_until(async move {
+            let run = tokio::task::spawn_local(async move {
+                run_ws_connection(adapter, rules, &mut store, wal, cfg, shutdown).await
+            });
+
+            wait_for_state(&replanner, &delisted, "active").await;
+            wait_for_state(&replanner, &kept, "active").await;
+
+            replanner
+                .apply_reassignment(&[], std::slice::from_ref(&delisted), &[], 2)
+                .unwrap();
+            // 実ストリームは新しいソケットになって初めて止まる
+            wait_for_state(&replanner, &delisted, "removed").await;
+            wait_for_state(&replanner, &kept, "active").await;
+            for _ in 0..50 {
+                if received.lock().await.iter().any(|m| m.starts_with("2 ")) {
+                    break;
+                }
+                tokio::time::sleep(Duration::from_millis(100)).await;
+            }
+
+            shutdown2.trigger();
+            let res = tokio::time::timeout(Duration::from_secs(5), run)
+                .await
+                .expect("connection should stop on shutdown")
+                .unwrap();
+            assert!(res.is_ok());
+
+            let r = received.lock().await.clone();
+            assert!(
+                !r.iter().any(|m| m.contains("\"command\":\"unsubscribe\"")),
+                "{r:?}"
+            );
+            // 2 本目の接続では残った購読だけを張り直す
+            let second: Vec<&String> = r.iter().filter(|m| m.starts_with("2 ")).collect();
+            assert_eq!(second.len(), 1, "{r:?}");
+            assert!(
+                second[0].contains("crypto.public.ws.trades.update"),
+                "{r:?}"
+            );
+        })
+        .await;
+}
@@ -47,6 +47,17 @@ impl WsVenueAdapter for TestAdapter {
         }])
     }
 
+    fn build_unsubscribe(
+        &self,
+        op_id: &str,
+        symbol: &str,
+        _params: &serde_json::Value,
+    ) -> Result<Vec<OutboundMsg>, String> {
+        Ok(vec![OutboundMsg {
+            text: json!({"command":"unsubscribe","op_id":op_id,"symbol":symbol}).to_string(),
+        }])
+    }
+
     fn classify_inbound(&self, raw: &[u8]) -> InboundClass {
         let v: serde_json::Value = serde_json::from_slice(raw).unwrap_or(json!({}));
         let kind = v.get("kind").and_then(|x| x.as_str()).unwrap_or("");
@@ -151,6 +151,7 @@ fn conservative(exchange_id: &str, _path: Option<PathBuf>) -> ExchangeWsRules {
         safety_profile: Some(SafetyProfile {
             max_streams_per_conn: Some(25),
             max_symbols_per_conn: Some(50),
+            max_msgs_per_sec_per_conn: None,
         }),
         stability: None,
         max_streams_per_conn: None,
@@ -27,6 +27,9 @@ pub struct HeartbeatPolicy {
 pub struct SafetyProfile {
     pub max_streams_per_conn: Option<usize>,
     pub max_symbols_per_conn: Option<usize>,
+    /// estimated inbound msgs/sec one connection may carry before shards are split
+    #[serde(default)]
+    pub max_msgs_per_sec_per_conn: Option<u32>,
 }
 
 #[derive(Debug, Clone, Deserialize)]